@@ -1005,4 +1005,991 @@ pub const GL_VERTEX_SHADER_BIT: types::GLenum = 0x00000001;
 pub const GL_VIEWPORT: types::GLenum = 0x0BA2;
 pub const GL_WAIT_FAILED: types::GLenum = 0x911D;
 pub const GL_WRITE_ONLY: types::GLenum = 0x88B9;
-pub const GL_ZERO: types::GLenum = 0;
\ No newline at end of file
+pub const GL_ZERO: types::GLenum = 0;
+
+// Extensions
+
+pub const GL_ETC1_RGB8_OES: types::GLenum = 0x8D64;
+pub const GL_COMPRESSED_RGB_S3TC_DXT1_EXT: types::GLenum = 0x83F0;
+pub const GL_COMPRESSED_RGBA_S3TC_DXT1_EXT: types::GLenum = 0x83F1;
+pub const GL_COMPRESSED_RGBA_S3TC_DXT3_EXT: types::GLenum = 0x83F2;
+pub const GL_COMPRESSED_RGBA_S3TC_DXT5_EXT: types::GLenum = 0x83F3;
+pub const GL_COMPRESSED_RGB_PVRTC_4BPPV1_IMG: types::GLenum = 0x8C00;
+pub const GL_COMPRESSED_RGB_PVRTC_2BPPV1_IMG: types::GLenum = 0x8C01;
+pub const GL_COMPRESSED_RGBA_PVRTC_4BPPV1_IMG: types::GLenum = 0x8C02;
+pub const GL_COMPRESSED_RGBA_PVRTC_2BPPV1_IMG: types::GLenum = 0x8C03;
+pub const GL_HALF_FLOAT_OES: types::GLenum = 0x8D61;
+pub const GL_SGX_BINARY_IMG: types::GLenum = 0x8C0A;
+pub const GL_NVIDIA_PLATFORM_BINARY_NV: types::GLenum = 0x890B;
+pub const GL_MALI_SHADER_BINARY_ARM: types::GLenum = 0x8F60;
+pub const GL_SHADER_BINARY_VIV: types::GLenum = 0x8FC4;
+pub const GL_SHADER_BINARY_DMP: types::GLenum = 0x9250;
+pub const GL_GCCSO_SHADER_BINARY_FJ: types::GLenum = 0x9260;
+
+/// Returns the name of the constant with the given value.
+///
+/// Values below `0x100` (`GL_ZERO`, `GL_POINTS`, `GL_NO_ERROR`, ...) and bit masks are ambiguous
+/// and yield `None`; callers have to name those from context.
+pub fn enum_name(value: types::GLenum) -> Option<&'static str> {
+    match value {
+        GL_NEVER => Some("GL_NEVER"),
+        GL_LESS => Some("GL_LESS"),
+        GL_EQUAL => Some("GL_EQUAL"),
+        GL_LEQUAL => Some("GL_LEQUAL"),
+        GL_GREATER => Some("GL_GREATER"),
+        GL_NOTEQUAL => Some("GL_NOTEQUAL"),
+        GL_GEQUAL => Some("GL_GEQUAL"),
+        GL_ALWAYS => Some("GL_ALWAYS"),
+        GL_SRC_COLOR => Some("GL_SRC_COLOR"),
+        GL_ONE_MINUS_SRC_COLOR => Some("GL_ONE_MINUS_SRC_COLOR"),
+        GL_SRC_ALPHA => Some("GL_SRC_ALPHA"),
+        GL_ONE_MINUS_SRC_ALPHA => Some("GL_ONE_MINUS_SRC_ALPHA"),
+        GL_DST_ALPHA => Some("GL_DST_ALPHA"),
+        GL_ONE_MINUS_DST_ALPHA => Some("GL_ONE_MINUS_DST_ALPHA"),
+        GL_DST_COLOR => Some("GL_DST_COLOR"),
+        GL_ONE_MINUS_DST_COLOR => Some("GL_ONE_MINUS_DST_COLOR"),
+        GL_SRC_ALPHA_SATURATE => Some("GL_SRC_ALPHA_SATURATE"),
+        GL_FRONT => Some("GL_FRONT"),
+        GL_BACK => Some("GL_BACK"),
+        GL_FRONT_AND_BACK => Some("GL_FRONT_AND_BACK"),
+        GL_INVALID_ENUM => Some("GL_INVALID_ENUM"),
+        GL_INVALID_VALUE => Some("GL_INVALID_VALUE"),
+        GL_INVALID_OPERATION => Some("GL_INVALID_OPERATION"),
+        GL_STACK_OVERFLOW => Some("GL_STACK_OVERFLOW"),
+        GL_STACK_UNDERFLOW => Some("GL_STACK_UNDERFLOW"),
+        GL_OUT_OF_MEMORY => Some("GL_OUT_OF_MEMORY"),
+        GL_INVALID_FRAMEBUFFER_OPERATION => Some("GL_INVALID_FRAMEBUFFER_OPERATION"),
+        GL_CONTEXT_LOST => Some("GL_CONTEXT_LOST"),
+        GL_CW => Some("GL_CW"),
+        GL_CCW => Some("GL_CCW"),
+        GL_LINE_WIDTH => Some("GL_LINE_WIDTH"),
+        GL_CULL_FACE => Some("GL_CULL_FACE"),
+        GL_CULL_FACE_MODE => Some("GL_CULL_FACE_MODE"),
+        GL_FRONT_FACE => Some("GL_FRONT_FACE"),
+        GL_DEPTH_RANGE => Some("GL_DEPTH_RANGE"),
+        GL_DEPTH_TEST => Some("GL_DEPTH_TEST"),
+        GL_DEPTH_WRITEMASK => Some("GL_DEPTH_WRITEMASK"),
+        GL_DEPTH_CLEAR_VALUE => Some("GL_DEPTH_CLEAR_VALUE"),
+        GL_DEPTH_FUNC => Some("GL_DEPTH_FUNC"),
+        GL_STENCIL_TEST => Some("GL_STENCIL_TEST"),
+        GL_STENCIL_CLEAR_VALUE => Some("GL_STENCIL_CLEAR_VALUE"),
+        GL_STENCIL_FUNC => Some("GL_STENCIL_FUNC"),
+        GL_STENCIL_VALUE_MASK => Some("GL_STENCIL_VALUE_MASK"),
+        GL_STENCIL_FAIL => Some("GL_STENCIL_FAIL"),
+        GL_STENCIL_PASS_DEPTH_FAIL => Some("GL_STENCIL_PASS_DEPTH_FAIL"),
+        GL_STENCIL_PASS_DEPTH_PASS => Some("GL_STENCIL_PASS_DEPTH_PASS"),
+        GL_STENCIL_REF => Some("GL_STENCIL_REF"),
+        GL_STENCIL_WRITEMASK => Some("GL_STENCIL_WRITEMASK"),
+        GL_VIEWPORT => Some("GL_VIEWPORT"),
+        GL_DITHER => Some("GL_DITHER"),
+        GL_BLEND => Some("GL_BLEND"),
+        GL_READ_BUFFER => Some("GL_READ_BUFFER"),
+        GL_SCISSOR_BOX => Some("GL_SCISSOR_BOX"),
+        GL_SCISSOR_TEST => Some("GL_SCISSOR_TEST"),
+        GL_COLOR_CLEAR_VALUE => Some("GL_COLOR_CLEAR_VALUE"),
+        GL_COLOR_WRITEMASK => Some("GL_COLOR_WRITEMASK"),
+        GL_UNPACK_ROW_LENGTH => Some("GL_UNPACK_ROW_LENGTH"),
+        GL_UNPACK_SKIP_ROWS => Some("GL_UNPACK_SKIP_ROWS"),
+        GL_UNPACK_SKIP_PIXELS => Some("GL_UNPACK_SKIP_PIXELS"),
+        GL_UNPACK_ALIGNMENT => Some("GL_UNPACK_ALIGNMENT"),
+        GL_PACK_ROW_LENGTH => Some("GL_PACK_ROW_LENGTH"),
+        GL_PACK_SKIP_ROWS => Some("GL_PACK_SKIP_ROWS"),
+        GL_PACK_SKIP_PIXELS => Some("GL_PACK_SKIP_PIXELS"),
+        GL_PACK_ALIGNMENT => Some("GL_PACK_ALIGNMENT"),
+        GL_MAX_TEXTURE_SIZE => Some("GL_MAX_TEXTURE_SIZE"),
+        GL_MAX_VIEWPORT_DIMS => Some("GL_MAX_VIEWPORT_DIMS"),
+        GL_SUBPIXEL_BITS => Some("GL_SUBPIXEL_BITS"),
+        GL_RED_BITS => Some("GL_RED_BITS"),
+        GL_GREEN_BITS => Some("GL_GREEN_BITS"),
+        GL_BLUE_BITS => Some("GL_BLUE_BITS"),
+        GL_ALPHA_BITS => Some("GL_ALPHA_BITS"),
+        GL_DEPTH_BITS => Some("GL_DEPTH_BITS"),
+        GL_STENCIL_BITS => Some("GL_STENCIL_BITS"),
+        GL_TEXTURE_2D => Some("GL_TEXTURE_2D"),
+        GL_TEXTURE_WIDTH => Some("GL_TEXTURE_WIDTH"),
+        GL_TEXTURE_HEIGHT => Some("GL_TEXTURE_HEIGHT"),
+        GL_TEXTURE_INTERNAL_FORMAT => Some("GL_TEXTURE_INTERNAL_FORMAT"),
+        GL_TEXTURE_BORDER_COLOR => Some("GL_TEXTURE_BORDER_COLOR"),
+        GL_DONT_CARE => Some("GL_DONT_CARE"),
+        GL_FASTEST => Some("GL_FASTEST"),
+        GL_NICEST => Some("GL_NICEST"),
+        GL_BYTE => Some("GL_BYTE"),
+        GL_UNSIGNED_BYTE => Some("GL_UNSIGNED_BYTE"),
+        GL_SHORT => Some("GL_SHORT"),
+        GL_UNSIGNED_SHORT => Some("GL_UNSIGNED_SHORT"),
+        GL_INT => Some("GL_INT"),
+        GL_UNSIGNED_INT => Some("GL_UNSIGNED_INT"),
+        GL_FLOAT => Some("GL_FLOAT"),
+        GL_HALF_FLOAT => Some("GL_HALF_FLOAT"),
+        GL_FIXED => Some("GL_FIXED"),
+        GL_INVERT => Some("GL_INVERT"),
+        GL_TEXTURE => Some("GL_TEXTURE"),
+        GL_COLOR => Some("GL_COLOR"),
+        GL_DEPTH => Some("GL_DEPTH"),
+        GL_STENCIL => Some("GL_STENCIL"),
+        GL_STENCIL_INDEX => Some("GL_STENCIL_INDEX"),
+        GL_DEPTH_COMPONENT => Some("GL_DEPTH_COMPONENT"),
+        GL_RED => Some("GL_RED"),
+        GL_GREEN => Some("GL_GREEN"),
+        GL_BLUE => Some("GL_BLUE"),
+        GL_ALPHA => Some("GL_ALPHA"),
+        GL_RGB => Some("GL_RGB"),
+        GL_RGBA => Some("GL_RGBA"),
+        GL_LUMINANCE => Some("GL_LUMINANCE"),
+        GL_LUMINANCE_ALPHA => Some("GL_LUMINANCE_ALPHA"),
+        GL_KEEP => Some("GL_KEEP"),
+        GL_REPLACE => Some("GL_REPLACE"),
+        GL_INCR => Some("GL_INCR"),
+        GL_DECR => Some("GL_DECR"),
+        GL_VENDOR => Some("GL_VENDOR"),
+        GL_RENDERER => Some("GL_RENDERER"),
+        GL_VERSION => Some("GL_VERSION"),
+        GL_EXTENSIONS => Some("GL_EXTENSIONS"),
+        GL_NEAREST => Some("GL_NEAREST"),
+        GL_LINEAR => Some("GL_LINEAR"),
+        GL_NEAREST_MIPMAP_NEAREST => Some("GL_NEAREST_MIPMAP_NEAREST"),
+        GL_LINEAR_MIPMAP_NEAREST => Some("GL_LINEAR_MIPMAP_NEAREST"),
+        GL_NEAREST_MIPMAP_LINEAR => Some("GL_NEAREST_MIPMAP_LINEAR"),
+        GL_LINEAR_MIPMAP_LINEAR => Some("GL_LINEAR_MIPMAP_LINEAR"),
+        GL_TEXTURE_MAG_FILTER => Some("GL_TEXTURE_MAG_FILTER"),
+        GL_TEXTURE_MIN_FILTER => Some("GL_TEXTURE_MIN_FILTER"),
+        GL_TEXTURE_WRAP_S => Some("GL_TEXTURE_WRAP_S"),
+        GL_TEXTURE_WRAP_T => Some("GL_TEXTURE_WRAP_T"),
+        GL_REPEAT => Some("GL_REPEAT"),
+        GL_POLYGON_OFFSET_UNITS => Some("GL_POLYGON_OFFSET_UNITS"),
+        GL_CONSTANT_COLOR => Some("GL_CONSTANT_COLOR"),
+        GL_ONE_MINUS_CONSTANT_COLOR => Some("GL_ONE_MINUS_CONSTANT_COLOR"),
+        GL_CONSTANT_ALPHA => Some("GL_CONSTANT_ALPHA"),
+        GL_ONE_MINUS_CONSTANT_ALPHA => Some("GL_ONE_MINUS_CONSTANT_ALPHA"),
+        GL_BLEND_COLOR => Some("GL_BLEND_COLOR"),
+        GL_FUNC_ADD => Some("GL_FUNC_ADD"),
+        GL_MIN => Some("GL_MIN"),
+        GL_MAX => Some("GL_MAX"),
+        GL_BLEND_EQUATION => Some("GL_BLEND_EQUATION"),
+        GL_FUNC_SUBTRACT => Some("GL_FUNC_SUBTRACT"),
+        GL_FUNC_REVERSE_SUBTRACT => Some("GL_FUNC_REVERSE_SUBTRACT"),
+        GL_UNSIGNED_SHORT_4_4_4_4 => Some("GL_UNSIGNED_SHORT_4_4_4_4"),
+        GL_UNSIGNED_SHORT_5_5_5_1 => Some("GL_UNSIGNED_SHORT_5_5_5_1"),
+        GL_POLYGON_OFFSET_FILL => Some("GL_POLYGON_OFFSET_FILL"),
+        GL_POLYGON_OFFSET_FACTOR => Some("GL_POLYGON_OFFSET_FACTOR"),
+        GL_RGB8 => Some("GL_RGB8"),
+        GL_RGBA4 => Some("GL_RGBA4"),
+        GL_RGB5_A1 => Some("GL_RGB5_A1"),
+        GL_RGBA8 => Some("GL_RGBA8"),
+        GL_RGB10_A2 => Some("GL_RGB10_A2"),
+        GL_TEXTURE_RED_SIZE => Some("GL_TEXTURE_RED_SIZE"),
+        GL_TEXTURE_GREEN_SIZE => Some("GL_TEXTURE_GREEN_SIZE"),
+        GL_TEXTURE_BLUE_SIZE => Some("GL_TEXTURE_BLUE_SIZE"),
+        GL_TEXTURE_ALPHA_SIZE => Some("GL_TEXTURE_ALPHA_SIZE"),
+        GL_TEXTURE_BINDING_2D => Some("GL_TEXTURE_BINDING_2D"),
+        GL_TEXTURE_BINDING_3D => Some("GL_TEXTURE_BINDING_3D"),
+        GL_UNPACK_SKIP_IMAGES => Some("GL_UNPACK_SKIP_IMAGES"),
+        GL_UNPACK_IMAGE_HEIGHT => Some("GL_UNPACK_IMAGE_HEIGHT"),
+        GL_TEXTURE_3D => Some("GL_TEXTURE_3D"),
+        GL_TEXTURE_DEPTH => Some("GL_TEXTURE_DEPTH"),
+        GL_TEXTURE_WRAP_R => Some("GL_TEXTURE_WRAP_R"),
+        GL_MAX_3D_TEXTURE_SIZE => Some("GL_MAX_3D_TEXTURE_SIZE"),
+        GL_VERTEX_ARRAY => Some("GL_VERTEX_ARRAY"),
+        GL_SAMPLE_ALPHA_TO_COVERAGE => Some("GL_SAMPLE_ALPHA_TO_COVERAGE"),
+        GL_SAMPLE_COVERAGE => Some("GL_SAMPLE_COVERAGE"),
+        GL_SAMPLE_BUFFERS => Some("GL_SAMPLE_BUFFERS"),
+        GL_SAMPLES => Some("GL_SAMPLES"),
+        GL_SAMPLE_COVERAGE_VALUE => Some("GL_SAMPLE_COVERAGE_VALUE"),
+        GL_SAMPLE_COVERAGE_INVERT => Some("GL_SAMPLE_COVERAGE_INVERT"),
+        GL_BLEND_DST_RGB => Some("GL_BLEND_DST_RGB"),
+        GL_BLEND_SRC_RGB => Some("GL_BLEND_SRC_RGB"),
+        GL_BLEND_DST_ALPHA => Some("GL_BLEND_DST_ALPHA"),
+        GL_BLEND_SRC_ALPHA => Some("GL_BLEND_SRC_ALPHA"),
+        GL_MAX_ELEMENTS_VERTICES => Some("GL_MAX_ELEMENTS_VERTICES"),
+        GL_MAX_ELEMENTS_INDICES => Some("GL_MAX_ELEMENTS_INDICES"),
+        GL_CLAMP_TO_BORDER => Some("GL_CLAMP_TO_BORDER"),
+        GL_CLAMP_TO_EDGE => Some("GL_CLAMP_TO_EDGE"),
+        GL_TEXTURE_MIN_LOD => Some("GL_TEXTURE_MIN_LOD"),
+        GL_TEXTURE_MAX_LOD => Some("GL_TEXTURE_MAX_LOD"),
+        GL_TEXTURE_BASE_LEVEL => Some("GL_TEXTURE_BASE_LEVEL"),
+        GL_TEXTURE_MAX_LEVEL => Some("GL_TEXTURE_MAX_LEVEL"),
+        GL_GENERATE_MIPMAP_HINT => Some("GL_GENERATE_MIPMAP_HINT"),
+        GL_DEPTH_COMPONENT16 => Some("GL_DEPTH_COMPONENT16"),
+        GL_DEPTH_COMPONENT24 => Some("GL_DEPTH_COMPONENT24"),
+        GL_FRAMEBUFFER_ATTACHMENT_COLOR_ENCODING => Some("GL_FRAMEBUFFER_ATTACHMENT_COLOR_ENCODING"),
+        GL_FRAMEBUFFER_ATTACHMENT_COMPONENT_TYPE => Some("GL_FRAMEBUFFER_ATTACHMENT_COMPONENT_TYPE"),
+        GL_FRAMEBUFFER_ATTACHMENT_RED_SIZE => Some("GL_FRAMEBUFFER_ATTACHMENT_RED_SIZE"),
+        GL_FRAMEBUFFER_ATTACHMENT_GREEN_SIZE => Some("GL_FRAMEBUFFER_ATTACHMENT_GREEN_SIZE"),
+        GL_FRAMEBUFFER_ATTACHMENT_BLUE_SIZE => Some("GL_FRAMEBUFFER_ATTACHMENT_BLUE_SIZE"),
+        GL_FRAMEBUFFER_ATTACHMENT_ALPHA_SIZE => Some("GL_FRAMEBUFFER_ATTACHMENT_ALPHA_SIZE"),
+        GL_FRAMEBUFFER_ATTACHMENT_DEPTH_SIZE => Some("GL_FRAMEBUFFER_ATTACHMENT_DEPTH_SIZE"),
+        GL_FRAMEBUFFER_ATTACHMENT_STENCIL_SIZE => Some("GL_FRAMEBUFFER_ATTACHMENT_STENCIL_SIZE"),
+        GL_FRAMEBUFFER_DEFAULT => Some("GL_FRAMEBUFFER_DEFAULT"),
+        GL_FRAMEBUFFER_UNDEFINED => Some("GL_FRAMEBUFFER_UNDEFINED"),
+        GL_DEPTH_STENCIL_ATTACHMENT => Some("GL_DEPTH_STENCIL_ATTACHMENT"),
+        GL_MAJOR_VERSION => Some("GL_MAJOR_VERSION"),
+        GL_MINOR_VERSION => Some("GL_MINOR_VERSION"),
+        GL_NUM_EXTENSIONS => Some("GL_NUM_EXTENSIONS"),
+        GL_CONTEXT_FLAGS => Some("GL_CONTEXT_FLAGS"),
+        GL_PRIMITIVE_RESTART_FOR_PATCHES_SUPPORTED => Some("GL_PRIMITIVE_RESTART_FOR_PATCHES_SUPPORTED"),
+        GL_RG => Some("GL_RG"),
+        GL_RG_INTEGER => Some("GL_RG_INTEGER"),
+        GL_R8 => Some("GL_R8"),
+        GL_RG8 => Some("GL_RG8"),
+        GL_R16F => Some("GL_R16F"),
+        GL_R32F => Some("GL_R32F"),
+        GL_RG16F => Some("GL_RG16F"),
+        GL_RG32F => Some("GL_RG32F"),
+        GL_R8I => Some("GL_R8I"),
+        GL_R8UI => Some("GL_R8UI"),
+        GL_R16I => Some("GL_R16I"),
+        GL_R16UI => Some("GL_R16UI"),
+        GL_R32I => Some("GL_R32I"),
+        GL_R32UI => Some("GL_R32UI"),
+        GL_RG8I => Some("GL_RG8I"),
+        GL_RG8UI => Some("GL_RG8UI"),
+        GL_RG16I => Some("GL_RG16I"),
+        GL_RG16UI => Some("GL_RG16UI"),
+        GL_RG32I => Some("GL_RG32I"),
+        GL_RG32UI => Some("GL_RG32UI"),
+        GL_DEBUG_OUTPUT_SYNCHRONOUS => Some("GL_DEBUG_OUTPUT_SYNCHRONOUS"),
+        GL_DEBUG_NEXT_LOGGED_MESSAGE_LENGTH => Some("GL_DEBUG_NEXT_LOGGED_MESSAGE_LENGTH"),
+        GL_DEBUG_CALLBACK_FUNCTION => Some("GL_DEBUG_CALLBACK_FUNCTION"),
+        GL_DEBUG_CALLBACK_USER_PARAM => Some("GL_DEBUG_CALLBACK_USER_PARAM"),
+        GL_DEBUG_SOURCE_API => Some("GL_DEBUG_SOURCE_API"),
+        GL_DEBUG_SOURCE_WINDOW_SYSTEM => Some("GL_DEBUG_SOURCE_WINDOW_SYSTEM"),
+        GL_DEBUG_SOURCE_SHADER_COMPILER => Some("GL_DEBUG_SOURCE_SHADER_COMPILER"),
+        GL_DEBUG_SOURCE_THIRD_PARTY => Some("GL_DEBUG_SOURCE_THIRD_PARTY"),
+        GL_DEBUG_SOURCE_APPLICATION => Some("GL_DEBUG_SOURCE_APPLICATION"),
+        GL_DEBUG_SOURCE_OTHER => Some("GL_DEBUG_SOURCE_OTHER"),
+        GL_DEBUG_TYPE_ERROR => Some("GL_DEBUG_TYPE_ERROR"),
+        GL_DEBUG_TYPE_DEPRECATED_BEHAVIOR => Some("GL_DEBUG_TYPE_DEPRECATED_BEHAVIOR"),
+        GL_DEBUG_TYPE_UNDEFINED_BEHAVIOR => Some("GL_DEBUG_TYPE_UNDEFINED_BEHAVIOR"),
+        GL_DEBUG_TYPE_PORTABILITY => Some("GL_DEBUG_TYPE_PORTABILITY"),
+        GL_DEBUG_TYPE_PERFORMANCE => Some("GL_DEBUG_TYPE_PERFORMANCE"),
+        GL_DEBUG_TYPE_OTHER => Some("GL_DEBUG_TYPE_OTHER"),
+        GL_LOSE_CONTEXT_ON_RESET => Some("GL_LOSE_CONTEXT_ON_RESET"),
+        GL_GUILTY_CONTEXT_RESET => Some("GL_GUILTY_CONTEXT_RESET"),
+        GL_INNOCENT_CONTEXT_RESET => Some("GL_INNOCENT_CONTEXT_RESET"),
+        GL_UNKNOWN_CONTEXT_RESET => Some("GL_UNKNOWN_CONTEXT_RESET"),
+        GL_RESET_NOTIFICATION_STRATEGY => Some("GL_RESET_NOTIFICATION_STRATEGY"),
+        GL_PROGRAM_BINARY_RETRIEVABLE_HINT => Some("GL_PROGRAM_BINARY_RETRIEVABLE_HINT"),
+        GL_PROGRAM_SEPARABLE => Some("GL_PROGRAM_SEPARABLE"),
+        GL_ACTIVE_PROGRAM => Some("GL_ACTIVE_PROGRAM"),
+        GL_PROGRAM_PIPELINE_BINDING => Some("GL_PROGRAM_PIPELINE_BINDING"),
+        GL_LAYER_PROVOKING_VERTEX => Some("GL_LAYER_PROVOKING_VERTEX"),
+        GL_UNDEFINED_VERTEX => Some("GL_UNDEFINED_VERTEX"),
+        GL_NO_RESET_NOTIFICATION => Some("GL_NO_RESET_NOTIFICATION"),
+        GL_MAX_COMPUTE_SHARED_MEMORY_SIZE => Some("GL_MAX_COMPUTE_SHARED_MEMORY_SIZE"),
+        GL_MAX_COMPUTE_UNIFORM_COMPONENTS => Some("GL_MAX_COMPUTE_UNIFORM_COMPONENTS"),
+        GL_MAX_COMPUTE_ATOMIC_COUNTER_BUFFERS => Some("GL_MAX_COMPUTE_ATOMIC_COUNTER_BUFFERS"),
+        GL_MAX_COMPUTE_ATOMIC_COUNTERS => Some("GL_MAX_COMPUTE_ATOMIC_COUNTERS"),
+        GL_MAX_COMBINED_COMPUTE_UNIFORM_COMPONENTS => Some("GL_MAX_COMBINED_COMPUTE_UNIFORM_COMPONENTS"),
+        GL_COMPUTE_WORK_GROUP_SIZE => Some("GL_COMPUTE_WORK_GROUP_SIZE"),
+        GL_DEBUG_TYPE_MARKER => Some("GL_DEBUG_TYPE_MARKER"),
+        GL_DEBUG_TYPE_PUSH_GROUP => Some("GL_DEBUG_TYPE_PUSH_GROUP"),
+        GL_DEBUG_TYPE_POP_GROUP => Some("GL_DEBUG_TYPE_POP_GROUP"),
+        GL_DEBUG_SEVERITY_NOTIFICATION => Some("GL_DEBUG_SEVERITY_NOTIFICATION"),
+        GL_MAX_DEBUG_GROUP_STACK_DEPTH => Some("GL_MAX_DEBUG_GROUP_STACK_DEPTH"),
+        GL_DEBUG_GROUP_STACK_DEPTH => Some("GL_DEBUG_GROUP_STACK_DEPTH"),
+        GL_MAX_UNIFORM_LOCATIONS => Some("GL_MAX_UNIFORM_LOCATIONS"),
+        GL_VERTEX_ATTRIB_BINDING => Some("GL_VERTEX_ATTRIB_BINDING"),
+        GL_VERTEX_ATTRIB_RELATIVE_OFFSET => Some("GL_VERTEX_ATTRIB_RELATIVE_OFFSET"),
+        GL_VERTEX_BINDING_DIVISOR => Some("GL_VERTEX_BINDING_DIVISOR"),
+        GL_VERTEX_BINDING_OFFSET => Some("GL_VERTEX_BINDING_OFFSET"),
+        GL_VERTEX_BINDING_STRIDE => Some("GL_VERTEX_BINDING_STRIDE"),
+        GL_MAX_VERTEX_ATTRIB_RELATIVE_OFFSET => Some("GL_MAX_VERTEX_ATTRIB_RELATIVE_OFFSET"),
+        GL_MAX_VERTEX_ATTRIB_BINDINGS => Some("GL_MAX_VERTEX_ATTRIB_BINDINGS"),
+        GL_TEXTURE_IMMUTABLE_LEVELS => Some("GL_TEXTURE_IMMUTABLE_LEVELS"),
+        GL_BUFFER => Some("GL_BUFFER"),
+        GL_SHADER => Some("GL_SHADER"),
+        GL_PROGRAM => Some("GL_PROGRAM"),
+        GL_QUERY => Some("GL_QUERY"),
+        GL_PROGRAM_PIPELINE => Some("GL_PROGRAM_PIPELINE"),
+        GL_MAX_VERTEX_ATTRIB_STRIDE => Some("GL_MAX_VERTEX_ATTRIB_STRIDE"),
+        GL_SAMPLER => Some("GL_SAMPLER"),
+        GL_MAX_LABEL_LENGTH => Some("GL_MAX_LABEL_LENGTH"),
+        GL_UNSIGNED_SHORT_5_6_5 => Some("GL_UNSIGNED_SHORT_5_6_5"),
+        GL_UNSIGNED_INT_2_10_10_10_REV => Some("GL_UNSIGNED_INT_2_10_10_10_REV"),
+        GL_MIRRORED_REPEAT => Some("GL_MIRRORED_REPEAT"),
+        GL_ALIASED_POINT_SIZE_RANGE => Some("GL_ALIASED_POINT_SIZE_RANGE"),
+        GL_ALIASED_LINE_WIDTH_RANGE => Some("GL_ALIASED_LINE_WIDTH_RANGE"),
+        GL_TEXTURE0 => Some("GL_TEXTURE0"),
+        GL_TEXTURE1 => Some("GL_TEXTURE1"),
+        GL_TEXTURE2 => Some("GL_TEXTURE2"),
+        GL_TEXTURE3 => Some("GL_TEXTURE3"),
+        GL_TEXTURE4 => Some("GL_TEXTURE4"),
+        GL_TEXTURE5 => Some("GL_TEXTURE5"),
+        GL_TEXTURE6 => Some("GL_TEXTURE6"),
+        GL_TEXTURE7 => Some("GL_TEXTURE7"),
+        GL_TEXTURE8 => Some("GL_TEXTURE8"),
+        GL_TEXTURE9 => Some("GL_TEXTURE9"),
+        GL_TEXTURE10 => Some("GL_TEXTURE10"),
+        GL_TEXTURE11 => Some("GL_TEXTURE11"),
+        GL_TEXTURE12 => Some("GL_TEXTURE12"),
+        GL_TEXTURE13 => Some("GL_TEXTURE13"),
+        GL_TEXTURE14 => Some("GL_TEXTURE14"),
+        GL_TEXTURE15 => Some("GL_TEXTURE15"),
+        GL_TEXTURE16 => Some("GL_TEXTURE16"),
+        GL_TEXTURE17 => Some("GL_TEXTURE17"),
+        GL_TEXTURE18 => Some("GL_TEXTURE18"),
+        GL_TEXTURE19 => Some("GL_TEXTURE19"),
+        GL_TEXTURE20 => Some("GL_TEXTURE20"),
+        GL_TEXTURE21 => Some("GL_TEXTURE21"),
+        GL_TEXTURE22 => Some("GL_TEXTURE22"),
+        GL_TEXTURE23 => Some("GL_TEXTURE23"),
+        GL_TEXTURE24 => Some("GL_TEXTURE24"),
+        GL_TEXTURE25 => Some("GL_TEXTURE25"),
+        GL_TEXTURE26 => Some("GL_TEXTURE26"),
+        GL_TEXTURE27 => Some("GL_TEXTURE27"),
+        GL_TEXTURE28 => Some("GL_TEXTURE28"),
+        GL_TEXTURE29 => Some("GL_TEXTURE29"),
+        GL_TEXTURE30 => Some("GL_TEXTURE30"),
+        GL_TEXTURE31 => Some("GL_TEXTURE31"),
+        GL_ACTIVE_TEXTURE => Some("GL_ACTIVE_TEXTURE"),
+        GL_MAX_RENDERBUFFER_SIZE => Some("GL_MAX_RENDERBUFFER_SIZE"),
+        GL_DEPTH_STENCIL => Some("GL_DEPTH_STENCIL"),
+        GL_UNSIGNED_INT_24_8 => Some("GL_UNSIGNED_INT_24_8"),
+        GL_MAX_TEXTURE_LOD_BIAS => Some("GL_MAX_TEXTURE_LOD_BIAS"),
+        GL_INCR_WRAP => Some("GL_INCR_WRAP"),
+        GL_DECR_WRAP => Some("GL_DECR_WRAP"),
+        GL_TEXTURE_CUBE_MAP => Some("GL_TEXTURE_CUBE_MAP"),
+        GL_TEXTURE_BINDING_CUBE_MAP => Some("GL_TEXTURE_BINDING_CUBE_MAP"),
+        GL_TEXTURE_CUBE_MAP_POSITIVE_X => Some("GL_TEXTURE_CUBE_MAP_POSITIVE_X"),
+        GL_TEXTURE_CUBE_MAP_NEGATIVE_X => Some("GL_TEXTURE_CUBE_MAP_NEGATIVE_X"),
+        GL_TEXTURE_CUBE_MAP_POSITIVE_Y => Some("GL_TEXTURE_CUBE_MAP_POSITIVE_Y"),
+        GL_TEXTURE_CUBE_MAP_NEGATIVE_Y => Some("GL_TEXTURE_CUBE_MAP_NEGATIVE_Y"),
+        GL_TEXTURE_CUBE_MAP_POSITIVE_Z => Some("GL_TEXTURE_CUBE_MAP_POSITIVE_Z"),
+        GL_TEXTURE_CUBE_MAP_NEGATIVE_Z => Some("GL_TEXTURE_CUBE_MAP_NEGATIVE_Z"),
+        GL_MAX_CUBE_MAP_TEXTURE_SIZE => Some("GL_MAX_CUBE_MAP_TEXTURE_SIZE"),
+        GL_VERTEX_ARRAY_BINDING => Some("GL_VERTEX_ARRAY_BINDING"),
+        GL_VERTEX_ATTRIB_ARRAY_ENABLED => Some("GL_VERTEX_ATTRIB_ARRAY_ENABLED"),
+        GL_VERTEX_ATTRIB_ARRAY_SIZE => Some("GL_VERTEX_ATTRIB_ARRAY_SIZE"),
+        GL_VERTEX_ATTRIB_ARRAY_STRIDE => Some("GL_VERTEX_ATTRIB_ARRAY_STRIDE"),
+        GL_VERTEX_ATTRIB_ARRAY_TYPE => Some("GL_VERTEX_ATTRIB_ARRAY_TYPE"),
+        GL_CURRENT_VERTEX_ATTRIB => Some("GL_CURRENT_VERTEX_ATTRIB"),
+        GL_VERTEX_ATTRIB_ARRAY_POINTER => Some("GL_VERTEX_ATTRIB_ARRAY_POINTER"),
+        GL_TEXTURE_COMPRESSED => Some("GL_TEXTURE_COMPRESSED"),
+        GL_NUM_COMPRESSED_TEXTURE_FORMATS => Some("GL_NUM_COMPRESSED_TEXTURE_FORMATS"),
+        GL_COMPRESSED_TEXTURE_FORMATS => Some("GL_COMPRESSED_TEXTURE_FORMATS"),
+        GL_PROGRAM_BINARY_LENGTH => Some("GL_PROGRAM_BINARY_LENGTH"),
+        GL_BUFFER_SIZE => Some("GL_BUFFER_SIZE"),
+        GL_BUFFER_USAGE => Some("GL_BUFFER_USAGE"),
+        GL_NUM_PROGRAM_BINARY_FORMATS => Some("GL_NUM_PROGRAM_BINARY_FORMATS"),
+        GL_PROGRAM_BINARY_FORMATS => Some("GL_PROGRAM_BINARY_FORMATS"),
+        GL_STENCIL_BACK_FUNC => Some("GL_STENCIL_BACK_FUNC"),
+        GL_STENCIL_BACK_FAIL => Some("GL_STENCIL_BACK_FAIL"),
+        GL_STENCIL_BACK_PASS_DEPTH_FAIL => Some("GL_STENCIL_BACK_PASS_DEPTH_FAIL"),
+        GL_STENCIL_BACK_PASS_DEPTH_PASS => Some("GL_STENCIL_BACK_PASS_DEPTH_PASS"),
+        GL_RGBA32F => Some("GL_RGBA32F"),
+        GL_RGB32F => Some("GL_RGB32F"),
+        GL_RGBA16F => Some("GL_RGBA16F"),
+        GL_RGB16F => Some("GL_RGB16F"),
+        GL_MAX_DRAW_BUFFERS => Some("GL_MAX_DRAW_BUFFERS"),
+        GL_DRAW_BUFFER0 => Some("GL_DRAW_BUFFER0"),
+        GL_DRAW_BUFFER1 => Some("GL_DRAW_BUFFER1"),
+        GL_DRAW_BUFFER2 => Some("GL_DRAW_BUFFER2"),
+        GL_DRAW_BUFFER3 => Some("GL_DRAW_BUFFER3"),
+        GL_DRAW_BUFFER4 => Some("GL_DRAW_BUFFER4"),
+        GL_DRAW_BUFFER5 => Some("GL_DRAW_BUFFER5"),
+        GL_DRAW_BUFFER6 => Some("GL_DRAW_BUFFER6"),
+        GL_DRAW_BUFFER7 => Some("GL_DRAW_BUFFER7"),
+        GL_DRAW_BUFFER8 => Some("GL_DRAW_BUFFER8"),
+        GL_DRAW_BUFFER9 => Some("GL_DRAW_BUFFER9"),
+        GL_DRAW_BUFFER10 => Some("GL_DRAW_BUFFER10"),
+        GL_DRAW_BUFFER11 => Some("GL_DRAW_BUFFER11"),
+        GL_DRAW_BUFFER12 => Some("GL_DRAW_BUFFER12"),
+        GL_DRAW_BUFFER13 => Some("GL_DRAW_BUFFER13"),
+        GL_DRAW_BUFFER14 => Some("GL_DRAW_BUFFER14"),
+        GL_DRAW_BUFFER15 => Some("GL_DRAW_BUFFER15"),
+        GL_BLEND_EQUATION_ALPHA => Some("GL_BLEND_EQUATION_ALPHA"),
+        GL_TEXTURE_DEPTH_SIZE => Some("GL_TEXTURE_DEPTH_SIZE"),
+        GL_TEXTURE_COMPARE_MODE => Some("GL_TEXTURE_COMPARE_MODE"),
+        GL_TEXTURE_COMPARE_FUNC => Some("GL_TEXTURE_COMPARE_FUNC"),
+        GL_COMPARE_REF_TO_TEXTURE => Some("GL_COMPARE_REF_TO_TEXTURE"),
+        GL_CURRENT_QUERY => Some("GL_CURRENT_QUERY"),
+        GL_QUERY_RESULT => Some("GL_QUERY_RESULT"),
+        GL_QUERY_RESULT_AVAILABLE => Some("GL_QUERY_RESULT_AVAILABLE"),
+        GL_MAX_VERTEX_ATTRIBS => Some("GL_MAX_VERTEX_ATTRIBS"),
+        GL_VERTEX_ATTRIB_ARRAY_NORMALIZED => Some("GL_VERTEX_ATTRIB_ARRAY_NORMALIZED"),
+        GL_MAX_TESS_CONTROL_INPUT_COMPONENTS => Some("GL_MAX_TESS_CONTROL_INPUT_COMPONENTS"),
+        GL_MAX_TESS_EVALUATION_INPUT_COMPONENTS => Some("GL_MAX_TESS_EVALUATION_INPUT_COMPONENTS"),
+        GL_MAX_TEXTURE_IMAGE_UNITS => Some("GL_MAX_TEXTURE_IMAGE_UNITS"),
+        GL_GEOMETRY_SHADER_INVOCATIONS => Some("GL_GEOMETRY_SHADER_INVOCATIONS"),
+        GL_ARRAY_BUFFER => Some("GL_ARRAY_BUFFER"),
+        GL_ELEMENT_ARRAY_BUFFER => Some("GL_ELEMENT_ARRAY_BUFFER"),
+        GL_ARRAY_BUFFER_BINDING => Some("GL_ARRAY_BUFFER_BINDING"),
+        GL_ELEMENT_ARRAY_BUFFER_BINDING => Some("GL_ELEMENT_ARRAY_BUFFER_BINDING"),
+        GL_VERTEX_ATTRIB_ARRAY_BUFFER_BINDING => Some("GL_VERTEX_ATTRIB_ARRAY_BUFFER_BINDING"),
+        GL_READ_ONLY => Some("GL_READ_ONLY"),
+        GL_WRITE_ONLY => Some("GL_WRITE_ONLY"),
+        GL_READ_WRITE => Some("GL_READ_WRITE"),
+        GL_BUFFER_MAPPED => Some("GL_BUFFER_MAPPED"),
+        GL_BUFFER_MAP_POINTER => Some("GL_BUFFER_MAP_POINTER"),
+        GL_STREAM_DRAW => Some("GL_STREAM_DRAW"),
+        GL_STREAM_READ => Some("GL_STREAM_READ"),
+        GL_STREAM_COPY => Some("GL_STREAM_COPY"),
+        GL_STATIC_DRAW => Some("GL_STATIC_DRAW"),
+        GL_STATIC_READ => Some("GL_STATIC_READ"),
+        GL_STATIC_COPY => Some("GL_STATIC_COPY"),
+        GL_DYNAMIC_DRAW => Some("GL_DYNAMIC_DRAW"),
+        GL_DYNAMIC_READ => Some("GL_DYNAMIC_READ"),
+        GL_DYNAMIC_COPY => Some("GL_DYNAMIC_COPY"),
+        GL_PIXEL_PACK_BUFFER => Some("GL_PIXEL_PACK_BUFFER"),
+        GL_PIXEL_UNPACK_BUFFER => Some("GL_PIXEL_UNPACK_BUFFER"),
+        GL_PIXEL_PACK_BUFFER_BINDING => Some("GL_PIXEL_PACK_BUFFER_BINDING"),
+        GL_PIXEL_UNPACK_BUFFER_BINDING => Some("GL_PIXEL_UNPACK_BUFFER_BINDING"),
+        GL_DEPTH24_STENCIL8 => Some("GL_DEPTH24_STENCIL8"),
+        GL_TEXTURE_STENCIL_SIZE => Some("GL_TEXTURE_STENCIL_SIZE"),
+        GL_VERTEX_ATTRIB_ARRAY_INTEGER => Some("GL_VERTEX_ATTRIB_ARRAY_INTEGER"),
+        GL_VERTEX_ATTRIB_ARRAY_DIVISOR => Some("GL_VERTEX_ATTRIB_ARRAY_DIVISOR"),
+        GL_MAX_ARRAY_TEXTURE_LAYERS => Some("GL_MAX_ARRAY_TEXTURE_LAYERS"),
+        GL_MIN_PROGRAM_TEXEL_OFFSET => Some("GL_MIN_PROGRAM_TEXEL_OFFSET"),
+        GL_MAX_PROGRAM_TEXEL_OFFSET => Some("GL_MAX_PROGRAM_TEXEL_OFFSET"),
+        GL_GEOMETRY_VERTICES_OUT => Some("GL_GEOMETRY_VERTICES_OUT"),
+        GL_GEOMETRY_INPUT_TYPE => Some("GL_GEOMETRY_INPUT_TYPE"),
+        GL_GEOMETRY_OUTPUT_TYPE => Some("GL_GEOMETRY_OUTPUT_TYPE"),
+        GL_SAMPLER_BINDING => Some("GL_SAMPLER_BINDING"),
+        GL_UNIFORM_BUFFER => Some("GL_UNIFORM_BUFFER"),
+        GL_UNIFORM_BUFFER_BINDING => Some("GL_UNIFORM_BUFFER_BINDING"),
+        GL_UNIFORM_BUFFER_START => Some("GL_UNIFORM_BUFFER_START"),
+        GL_UNIFORM_BUFFER_SIZE => Some("GL_UNIFORM_BUFFER_SIZE"),
+        GL_MAX_VERTEX_UNIFORM_BLOCKS => Some("GL_MAX_VERTEX_UNIFORM_BLOCKS"),
+        GL_MAX_GEOMETRY_UNIFORM_BLOCKS => Some("GL_MAX_GEOMETRY_UNIFORM_BLOCKS"),
+        GL_MAX_FRAGMENT_UNIFORM_BLOCKS => Some("GL_MAX_FRAGMENT_UNIFORM_BLOCKS"),
+        GL_MAX_COMBINED_UNIFORM_BLOCKS => Some("GL_MAX_COMBINED_UNIFORM_BLOCKS"),
+        GL_MAX_UNIFORM_BUFFER_BINDINGS => Some("GL_MAX_UNIFORM_BUFFER_BINDINGS"),
+        GL_MAX_UNIFORM_BLOCK_SIZE => Some("GL_MAX_UNIFORM_BLOCK_SIZE"),
+        GL_MAX_COMBINED_VERTEX_UNIFORM_COMPONENTS => Some("GL_MAX_COMBINED_VERTEX_UNIFORM_COMPONENTS"),
+        GL_MAX_COMBINED_GEOMETRY_UNIFORM_COMPONENTS => Some("GL_MAX_COMBINED_GEOMETRY_UNIFORM_COMPONENTS"),
+        GL_MAX_COMBINED_FRAGMENT_UNIFORM_COMPONENTS => Some("GL_MAX_COMBINED_FRAGMENT_UNIFORM_COMPONENTS"),
+        GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT => Some("GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT"),
+        GL_ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH => Some("GL_ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH"),
+        GL_ACTIVE_UNIFORM_BLOCKS => Some("GL_ACTIVE_UNIFORM_BLOCKS"),
+        GL_UNIFORM_TYPE => Some("GL_UNIFORM_TYPE"),
+        GL_UNIFORM_SIZE => Some("GL_UNIFORM_SIZE"),
+        GL_UNIFORM_NAME_LENGTH => Some("GL_UNIFORM_NAME_LENGTH"),
+        GL_UNIFORM_BLOCK_INDEX => Some("GL_UNIFORM_BLOCK_INDEX"),
+        GL_UNIFORM_OFFSET => Some("GL_UNIFORM_OFFSET"),
+        GL_UNIFORM_ARRAY_STRIDE => Some("GL_UNIFORM_ARRAY_STRIDE"),
+        GL_UNIFORM_MATRIX_STRIDE => Some("GL_UNIFORM_MATRIX_STRIDE"),
+        GL_UNIFORM_IS_ROW_MAJOR => Some("GL_UNIFORM_IS_ROW_MAJOR"),
+        GL_UNIFORM_BLOCK_BINDING => Some("GL_UNIFORM_BLOCK_BINDING"),
+        GL_UNIFORM_BLOCK_DATA_SIZE => Some("GL_UNIFORM_BLOCK_DATA_SIZE"),
+        GL_UNIFORM_BLOCK_NAME_LENGTH => Some("GL_UNIFORM_BLOCK_NAME_LENGTH"),
+        GL_UNIFORM_BLOCK_ACTIVE_UNIFORMS => Some("GL_UNIFORM_BLOCK_ACTIVE_UNIFORMS"),
+        GL_UNIFORM_BLOCK_ACTIVE_UNIFORM_INDICES => Some("GL_UNIFORM_BLOCK_ACTIVE_UNIFORM_INDICES"),
+        GL_UNIFORM_BLOCK_REFERENCED_BY_VERTEX_SHADER => Some("GL_UNIFORM_BLOCK_REFERENCED_BY_VERTEX_SHADER"),
+        GL_UNIFORM_BLOCK_REFERENCED_BY_FRAGMENT_SHADER => Some("GL_UNIFORM_BLOCK_REFERENCED_BY_FRAGMENT_SHADER"),
+        GL_FRAGMENT_SHADER => Some("GL_FRAGMENT_SHADER"),
+        GL_VERTEX_SHADER => Some("GL_VERTEX_SHADER"),
+        GL_MAX_FRAGMENT_UNIFORM_COMPONENTS => Some("GL_MAX_FRAGMENT_UNIFORM_COMPONENTS"),
+        GL_MAX_VERTEX_UNIFORM_COMPONENTS => Some("GL_MAX_VERTEX_UNIFORM_COMPONENTS"),
+        GL_MAX_VARYING_COMPONENTS => Some("GL_MAX_VARYING_COMPONENTS"),
+        GL_MAX_VERTEX_TEXTURE_IMAGE_UNITS => Some("GL_MAX_VERTEX_TEXTURE_IMAGE_UNITS"),
+        GL_MAX_COMBINED_TEXTURE_IMAGE_UNITS => Some("GL_MAX_COMBINED_TEXTURE_IMAGE_UNITS"),
+        GL_SHADER_TYPE => Some("GL_SHADER_TYPE"),
+        GL_FLOAT_VEC2 => Some("GL_FLOAT_VEC2"),
+        GL_FLOAT_VEC3 => Some("GL_FLOAT_VEC3"),
+        GL_FLOAT_VEC4 => Some("GL_FLOAT_VEC4"),
+        GL_INT_VEC2 => Some("GL_INT_VEC2"),
+        GL_INT_VEC3 => Some("GL_INT_VEC3"),
+        GL_INT_VEC4 => Some("GL_INT_VEC4"),
+        GL_BOOL => Some("GL_BOOL"),
+        GL_BOOL_VEC2 => Some("GL_BOOL_VEC2"),
+        GL_BOOL_VEC3 => Some("GL_BOOL_VEC3"),
+        GL_BOOL_VEC4 => Some("GL_BOOL_VEC4"),
+        GL_FLOAT_MAT2 => Some("GL_FLOAT_MAT2"),
+        GL_FLOAT_MAT3 => Some("GL_FLOAT_MAT3"),
+        GL_FLOAT_MAT4 => Some("GL_FLOAT_MAT4"),
+        GL_SAMPLER_2D => Some("GL_SAMPLER_2D"),
+        GL_SAMPLER_3D => Some("GL_SAMPLER_3D"),
+        GL_SAMPLER_CUBE => Some("GL_SAMPLER_CUBE"),
+        GL_SAMPLER_2D_SHADOW => Some("GL_SAMPLER_2D_SHADOW"),
+        GL_FLOAT_MAT2x3 => Some("GL_FLOAT_MAT2x3"),
+        GL_FLOAT_MAT2x4 => Some("GL_FLOAT_MAT2x4"),
+        GL_FLOAT_MAT3x2 => Some("GL_FLOAT_MAT3x2"),
+        GL_FLOAT_MAT3x4 => Some("GL_FLOAT_MAT3x4"),
+        GL_FLOAT_MAT4x2 => Some("GL_FLOAT_MAT4x2"),
+        GL_FLOAT_MAT4x3 => Some("GL_FLOAT_MAT4x3"),
+        GL_DELETE_STATUS => Some("GL_DELETE_STATUS"),
+        GL_COMPILE_STATUS => Some("GL_COMPILE_STATUS"),
+        GL_LINK_STATUS => Some("GL_LINK_STATUS"),
+        GL_VALIDATE_STATUS => Some("GL_VALIDATE_STATUS"),
+        GL_INFO_LOG_LENGTH => Some("GL_INFO_LOG_LENGTH"),
+        GL_ATTACHED_SHADERS => Some("GL_ATTACHED_SHADERS"),
+        GL_ACTIVE_UNIFORMS => Some("GL_ACTIVE_UNIFORMS"),
+        GL_ACTIVE_UNIFORM_MAX_LENGTH => Some("GL_ACTIVE_UNIFORM_MAX_LENGTH"),
+        GL_SHADER_SOURCE_LENGTH => Some("GL_SHADER_SOURCE_LENGTH"),
+        GL_ACTIVE_ATTRIBUTES => Some("GL_ACTIVE_ATTRIBUTES"),
+        GL_ACTIVE_ATTRIBUTE_MAX_LENGTH => Some("GL_ACTIVE_ATTRIBUTE_MAX_LENGTH"),
+        GL_FRAGMENT_SHADER_DERIVATIVE_HINT => Some("GL_FRAGMENT_SHADER_DERIVATIVE_HINT"),
+        GL_SHADING_LANGUAGE_VERSION => Some("GL_SHADING_LANGUAGE_VERSION"),
+        GL_CURRENT_PROGRAM => Some("GL_CURRENT_PROGRAM"),
+        GL_IMPLEMENTATION_COLOR_READ_TYPE => Some("GL_IMPLEMENTATION_COLOR_READ_TYPE"),
+        GL_IMPLEMENTATION_COLOR_READ_FORMAT => Some("GL_IMPLEMENTATION_COLOR_READ_FORMAT"),
+        GL_TEXTURE_RED_TYPE => Some("GL_TEXTURE_RED_TYPE"),
+        GL_TEXTURE_GREEN_TYPE => Some("GL_TEXTURE_GREEN_TYPE"),
+        GL_TEXTURE_BLUE_TYPE => Some("GL_TEXTURE_BLUE_TYPE"),
+        GL_TEXTURE_ALPHA_TYPE => Some("GL_TEXTURE_ALPHA_TYPE"),
+        GL_TEXTURE_DEPTH_TYPE => Some("GL_TEXTURE_DEPTH_TYPE"),
+        GL_UNSIGNED_NORMALIZED => Some("GL_UNSIGNED_NORMALIZED"),
+        GL_TEXTURE_2D_ARRAY => Some("GL_TEXTURE_2D_ARRAY"),
+        GL_TEXTURE_BINDING_2D_ARRAY => Some("GL_TEXTURE_BINDING_2D_ARRAY"),
+        GL_MAX_GEOMETRY_TEXTURE_IMAGE_UNITS => Some("GL_MAX_GEOMETRY_TEXTURE_IMAGE_UNITS"),
+        GL_TEXTURE_BUFFER => Some("GL_TEXTURE_BUFFER"),
+        GL_MAX_TEXTURE_BUFFER_SIZE => Some("GL_MAX_TEXTURE_BUFFER_SIZE"),
+        GL_TEXTURE_BINDING_BUFFER => Some("GL_TEXTURE_BINDING_BUFFER"),
+        GL_TEXTURE_BUFFER_DATA_STORE_BINDING => Some("GL_TEXTURE_BUFFER_DATA_STORE_BINDING"),
+        GL_ANY_SAMPLES_PASSED => Some("GL_ANY_SAMPLES_PASSED"),
+        GL_SAMPLE_SHADING => Some("GL_SAMPLE_SHADING"),
+        GL_MIN_SAMPLE_SHADING_VALUE => Some("GL_MIN_SAMPLE_SHADING_VALUE"),
+        GL_R11F_G11F_B10F => Some("GL_R11F_G11F_B10F"),
+        GL_UNSIGNED_INT_10F_11F_11F_REV => Some("GL_UNSIGNED_INT_10F_11F_11F_REV"),
+        GL_RGB9_E5 => Some("GL_RGB9_E5"),
+        GL_UNSIGNED_INT_5_9_9_9_REV => Some("GL_UNSIGNED_INT_5_9_9_9_REV"),
+        GL_TEXTURE_SHARED_SIZE => Some("GL_TEXTURE_SHARED_SIZE"),
+        GL_SRGB => Some("GL_SRGB"),
+        GL_SRGB8 => Some("GL_SRGB8"),
+        GL_SRGB8_ALPHA8 => Some("GL_SRGB8_ALPHA8"),
+        GL_TRANSFORM_FEEDBACK_VARYING_MAX_LENGTH => Some("GL_TRANSFORM_FEEDBACK_VARYING_MAX_LENGTH"),
+        GL_TRANSFORM_FEEDBACK_BUFFER_MODE => Some("GL_TRANSFORM_FEEDBACK_BUFFER_MODE"),
+        GL_MAX_TRANSFORM_FEEDBACK_SEPARATE_COMPONENTS => Some("GL_MAX_TRANSFORM_FEEDBACK_SEPARATE_COMPONENTS"),
+        GL_TRANSFORM_FEEDBACK_VARYINGS => Some("GL_TRANSFORM_FEEDBACK_VARYINGS"),
+        GL_TRANSFORM_FEEDBACK_BUFFER_START => Some("GL_TRANSFORM_FEEDBACK_BUFFER_START"),
+        GL_TRANSFORM_FEEDBACK_BUFFER_SIZE => Some("GL_TRANSFORM_FEEDBACK_BUFFER_SIZE"),
+        GL_PRIMITIVES_GENERATED => Some("GL_PRIMITIVES_GENERATED"),
+        GL_TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN => Some("GL_TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN"),
+        GL_RASTERIZER_DISCARD => Some("GL_RASTERIZER_DISCARD"),
+        GL_MAX_TRANSFORM_FEEDBACK_INTERLEAVED_COMPONENTS => Some("GL_MAX_TRANSFORM_FEEDBACK_INTERLEAVED_COMPONENTS"),
+        GL_MAX_TRANSFORM_FEEDBACK_SEPARATE_ATTRIBS => Some("GL_MAX_TRANSFORM_FEEDBACK_SEPARATE_ATTRIBS"),
+        GL_INTERLEAVED_ATTRIBS => Some("GL_INTERLEAVED_ATTRIBS"),
+        GL_SEPARATE_ATTRIBS => Some("GL_SEPARATE_ATTRIBS"),
+        GL_TRANSFORM_FEEDBACK_BUFFER => Some("GL_TRANSFORM_FEEDBACK_BUFFER"),
+        GL_TRANSFORM_FEEDBACK_BUFFER_BINDING => Some("GL_TRANSFORM_FEEDBACK_BUFFER_BINDING"),
+        GL_STENCIL_BACK_REF => Some("GL_STENCIL_BACK_REF"),
+        GL_STENCIL_BACK_VALUE_MASK => Some("GL_STENCIL_BACK_VALUE_MASK"),
+        GL_STENCIL_BACK_WRITEMASK => Some("GL_STENCIL_BACK_WRITEMASK"),
+        GL_FRAMEBUFFER_BINDING => Some("GL_FRAMEBUFFER_BINDING"),
+        GL_RENDERBUFFER_BINDING => Some("GL_RENDERBUFFER_BINDING"),
+        GL_READ_FRAMEBUFFER => Some("GL_READ_FRAMEBUFFER"),
+        GL_DRAW_FRAMEBUFFER => Some("GL_DRAW_FRAMEBUFFER"),
+        GL_READ_FRAMEBUFFER_BINDING => Some("GL_READ_FRAMEBUFFER_BINDING"),
+        GL_RENDERBUFFER_SAMPLES => Some("GL_RENDERBUFFER_SAMPLES"),
+        GL_DEPTH_COMPONENT32F => Some("GL_DEPTH_COMPONENT32F"),
+        GL_DEPTH32F_STENCIL8 => Some("GL_DEPTH32F_STENCIL8"),
+        GL_FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE => Some("GL_FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE"),
+        GL_FRAMEBUFFER_ATTACHMENT_OBJECT_NAME => Some("GL_FRAMEBUFFER_ATTACHMENT_OBJECT_NAME"),
+        GL_FRAMEBUFFER_ATTACHMENT_TEXTURE_LEVEL => Some("GL_FRAMEBUFFER_ATTACHMENT_TEXTURE_LEVEL"),
+        GL_FRAMEBUFFER_ATTACHMENT_TEXTURE_CUBE_MAP_FACE => Some("GL_FRAMEBUFFER_ATTACHMENT_TEXTURE_CUBE_MAP_FACE"),
+        GL_FRAMEBUFFER_ATTACHMENT_TEXTURE_LAYER => Some("GL_FRAMEBUFFER_ATTACHMENT_TEXTURE_LAYER"),
+        GL_FRAMEBUFFER_COMPLETE => Some("GL_FRAMEBUFFER_COMPLETE"),
+        GL_FRAMEBUFFER_INCOMPLETE_ATTACHMENT => Some("GL_FRAMEBUFFER_INCOMPLETE_ATTACHMENT"),
+        GL_FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => Some("GL_FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT"),
+        GL_FRAMEBUFFER_INCOMPLETE_DIMENSIONS => Some("GL_FRAMEBUFFER_INCOMPLETE_DIMENSIONS"),
+        GL_FRAMEBUFFER_UNSUPPORTED => Some("GL_FRAMEBUFFER_UNSUPPORTED"),
+        GL_MAX_COLOR_ATTACHMENTS => Some("GL_MAX_COLOR_ATTACHMENTS"),
+        GL_COLOR_ATTACHMENT0 => Some("GL_COLOR_ATTACHMENT0"),
+        GL_COLOR_ATTACHMENT1 => Some("GL_COLOR_ATTACHMENT1"),
+        GL_COLOR_ATTACHMENT2 => Some("GL_COLOR_ATTACHMENT2"),
+        GL_COLOR_ATTACHMENT3 => Some("GL_COLOR_ATTACHMENT3"),
+        GL_COLOR_ATTACHMENT4 => Some("GL_COLOR_ATTACHMENT4"),
+        GL_COLOR_ATTACHMENT5 => Some("GL_COLOR_ATTACHMENT5"),
+        GL_COLOR_ATTACHMENT6 => Some("GL_COLOR_ATTACHMENT6"),
+        GL_COLOR_ATTACHMENT7 => Some("GL_COLOR_ATTACHMENT7"),
+        GL_COLOR_ATTACHMENT8 => Some("GL_COLOR_ATTACHMENT8"),
+        GL_COLOR_ATTACHMENT9 => Some("GL_COLOR_ATTACHMENT9"),
+        GL_COLOR_ATTACHMENT10 => Some("GL_COLOR_ATTACHMENT10"),
+        GL_COLOR_ATTACHMENT11 => Some("GL_COLOR_ATTACHMENT11"),
+        GL_COLOR_ATTACHMENT12 => Some("GL_COLOR_ATTACHMENT12"),
+        GL_COLOR_ATTACHMENT13 => Some("GL_COLOR_ATTACHMENT13"),
+        GL_COLOR_ATTACHMENT14 => Some("GL_COLOR_ATTACHMENT14"),
+        GL_COLOR_ATTACHMENT15 => Some("GL_COLOR_ATTACHMENT15"),
+        GL_COLOR_ATTACHMENT16 => Some("GL_COLOR_ATTACHMENT16"),
+        GL_COLOR_ATTACHMENT17 => Some("GL_COLOR_ATTACHMENT17"),
+        GL_COLOR_ATTACHMENT18 => Some("GL_COLOR_ATTACHMENT18"),
+        GL_COLOR_ATTACHMENT19 => Some("GL_COLOR_ATTACHMENT19"),
+        GL_COLOR_ATTACHMENT20 => Some("GL_COLOR_ATTACHMENT20"),
+        GL_COLOR_ATTACHMENT21 => Some("GL_COLOR_ATTACHMENT21"),
+        GL_COLOR_ATTACHMENT22 => Some("GL_COLOR_ATTACHMENT22"),
+        GL_COLOR_ATTACHMENT23 => Some("GL_COLOR_ATTACHMENT23"),
+        GL_COLOR_ATTACHMENT24 => Some("GL_COLOR_ATTACHMENT24"),
+        GL_COLOR_ATTACHMENT25 => Some("GL_COLOR_ATTACHMENT25"),
+        GL_COLOR_ATTACHMENT26 => Some("GL_COLOR_ATTACHMENT26"),
+        GL_COLOR_ATTACHMENT27 => Some("GL_COLOR_ATTACHMENT27"),
+        GL_COLOR_ATTACHMENT28 => Some("GL_COLOR_ATTACHMENT28"),
+        GL_COLOR_ATTACHMENT29 => Some("GL_COLOR_ATTACHMENT29"),
+        GL_COLOR_ATTACHMENT30 => Some("GL_COLOR_ATTACHMENT30"),
+        GL_COLOR_ATTACHMENT31 => Some("GL_COLOR_ATTACHMENT31"),
+        GL_DEPTH_ATTACHMENT => Some("GL_DEPTH_ATTACHMENT"),
+        GL_STENCIL_ATTACHMENT => Some("GL_STENCIL_ATTACHMENT"),
+        GL_FRAMEBUFFER => Some("GL_FRAMEBUFFER"),
+        GL_RENDERBUFFER => Some("GL_RENDERBUFFER"),
+        GL_RENDERBUFFER_WIDTH => Some("GL_RENDERBUFFER_WIDTH"),
+        GL_RENDERBUFFER_HEIGHT => Some("GL_RENDERBUFFER_HEIGHT"),
+        GL_RENDERBUFFER_INTERNAL_FORMAT => Some("GL_RENDERBUFFER_INTERNAL_FORMAT"),
+        GL_STENCIL_INDEX8 => Some("GL_STENCIL_INDEX8"),
+        GL_RENDERBUFFER_RED_SIZE => Some("GL_RENDERBUFFER_RED_SIZE"),
+        GL_RENDERBUFFER_GREEN_SIZE => Some("GL_RENDERBUFFER_GREEN_SIZE"),
+        GL_RENDERBUFFER_BLUE_SIZE => Some("GL_RENDERBUFFER_BLUE_SIZE"),
+        GL_RENDERBUFFER_ALPHA_SIZE => Some("GL_RENDERBUFFER_ALPHA_SIZE"),
+        GL_RENDERBUFFER_DEPTH_SIZE => Some("GL_RENDERBUFFER_DEPTH_SIZE"),
+        GL_RENDERBUFFER_STENCIL_SIZE => Some("GL_RENDERBUFFER_STENCIL_SIZE"),
+        GL_FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => Some("GL_FRAMEBUFFER_INCOMPLETE_MULTISAMPLE"),
+        GL_MAX_SAMPLES => Some("GL_MAX_SAMPLES"),
+        GL_RGB565 => Some("GL_RGB565"),
+        GL_PRIMITIVE_RESTART_FIXED_INDEX => Some("GL_PRIMITIVE_RESTART_FIXED_INDEX"),
+        GL_ANY_SAMPLES_PASSED_CONSERVATIVE => Some("GL_ANY_SAMPLES_PASSED_CONSERVATIVE"),
+        GL_MAX_ELEMENT_INDEX => Some("GL_MAX_ELEMENT_INDEX"),
+        GL_RGBA32UI => Some("GL_RGBA32UI"),
+        GL_RGB32UI => Some("GL_RGB32UI"),
+        GL_RGBA16UI => Some("GL_RGBA16UI"),
+        GL_RGB16UI => Some("GL_RGB16UI"),
+        GL_RGBA8UI => Some("GL_RGBA8UI"),
+        GL_RGB8UI => Some("GL_RGB8UI"),
+        GL_RGBA32I => Some("GL_RGBA32I"),
+        GL_RGB32I => Some("GL_RGB32I"),
+        GL_RGBA16I => Some("GL_RGBA16I"),
+        GL_RGB16I => Some("GL_RGB16I"),
+        GL_RGBA8I => Some("GL_RGBA8I"),
+        GL_RGB8I => Some("GL_RGB8I"),
+        GL_RED_INTEGER => Some("GL_RED_INTEGER"),
+        GL_RGB_INTEGER => Some("GL_RGB_INTEGER"),
+        GL_RGBA_INTEGER => Some("GL_RGBA_INTEGER"),
+        GL_INT_2_10_10_10_REV => Some("GL_INT_2_10_10_10_REV"),
+        GL_FRAMEBUFFER_ATTACHMENT_LAYERED => Some("GL_FRAMEBUFFER_ATTACHMENT_LAYERED"),
+        GL_FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => Some("GL_FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS"),
+        GL_FLOAT_32_UNSIGNED_INT_24_8_REV => Some("GL_FLOAT_32_UNSIGNED_INT_24_8_REV"),
+        GL_SAMPLER_2D_ARRAY => Some("GL_SAMPLER_2D_ARRAY"),
+        GL_SAMPLER_BUFFER => Some("GL_SAMPLER_BUFFER"),
+        GL_SAMPLER_2D_ARRAY_SHADOW => Some("GL_SAMPLER_2D_ARRAY_SHADOW"),
+        GL_SAMPLER_CUBE_SHADOW => Some("GL_SAMPLER_CUBE_SHADOW"),
+        GL_UNSIGNED_INT_VEC2 => Some("GL_UNSIGNED_INT_VEC2"),
+        GL_UNSIGNED_INT_VEC3 => Some("GL_UNSIGNED_INT_VEC3"),
+        GL_UNSIGNED_INT_VEC4 => Some("GL_UNSIGNED_INT_VEC4"),
+        GL_INT_SAMPLER_2D => Some("GL_INT_SAMPLER_2D"),
+        GL_INT_SAMPLER_3D => Some("GL_INT_SAMPLER_3D"),
+        GL_INT_SAMPLER_CUBE => Some("GL_INT_SAMPLER_CUBE"),
+        GL_INT_SAMPLER_2D_ARRAY => Some("GL_INT_SAMPLER_2D_ARRAY"),
+        GL_INT_SAMPLER_BUFFER => Some("GL_INT_SAMPLER_BUFFER"),
+        GL_UNSIGNED_INT_SAMPLER_2D => Some("GL_UNSIGNED_INT_SAMPLER_2D"),
+        GL_UNSIGNED_INT_SAMPLER_3D => Some("GL_UNSIGNED_INT_SAMPLER_3D"),
+        GL_UNSIGNED_INT_SAMPLER_CUBE => Some("GL_UNSIGNED_INT_SAMPLER_CUBE"),
+        GL_UNSIGNED_INT_SAMPLER_2D_ARRAY => Some("GL_UNSIGNED_INT_SAMPLER_2D_ARRAY"),
+        GL_UNSIGNED_INT_SAMPLER_BUFFER => Some("GL_UNSIGNED_INT_SAMPLER_BUFFER"),
+        GL_GEOMETRY_SHADER => Some("GL_GEOMETRY_SHADER"),
+        GL_MAX_GEOMETRY_UNIFORM_COMPONENTS => Some("GL_MAX_GEOMETRY_UNIFORM_COMPONENTS"),
+        GL_MAX_GEOMETRY_OUTPUT_VERTICES => Some("GL_MAX_GEOMETRY_OUTPUT_VERTICES"),
+        GL_MAX_GEOMETRY_TOTAL_OUTPUT_COMPONENTS => Some("GL_MAX_GEOMETRY_TOTAL_OUTPUT_COMPONENTS"),
+        GL_LOW_FLOAT => Some("GL_LOW_FLOAT"),
+        GL_MEDIUM_FLOAT => Some("GL_MEDIUM_FLOAT"),
+        GL_HIGH_FLOAT => Some("GL_HIGH_FLOAT"),
+        GL_LOW_INT => Some("GL_LOW_INT"),
+        GL_MEDIUM_INT => Some("GL_MEDIUM_INT"),
+        GL_HIGH_INT => Some("GL_HIGH_INT"),
+        GL_SHADER_BINARY_FORMATS => Some("GL_SHADER_BINARY_FORMATS"),
+        GL_NUM_SHADER_BINARY_FORMATS => Some("GL_NUM_SHADER_BINARY_FORMATS"),
+        GL_SHADER_COMPILER => Some("GL_SHADER_COMPILER"),
+        GL_MAX_VERTEX_UNIFORM_VECTORS => Some("GL_MAX_VERTEX_UNIFORM_VECTORS"),
+        GL_MAX_VARYING_VECTORS => Some("GL_MAX_VARYING_VECTORS"),
+        GL_MAX_FRAGMENT_UNIFORM_VECTORS => Some("GL_MAX_FRAGMENT_UNIFORM_VECTORS"),
+        GL_MAX_COMBINED_TESS_CONTROL_UNIFORM_COMPONENTS => Some("GL_MAX_COMBINED_TESS_CONTROL_UNIFORM_COMPONENTS"),
+        GL_MAX_COMBINED_TESS_EVALUATION_UNIFORM_COMPONENTS => Some("GL_MAX_COMBINED_TESS_EVALUATION_UNIFORM_COMPONENTS"),
+        GL_TRANSFORM_FEEDBACK => Some("GL_TRANSFORM_FEEDBACK"),
+        GL_TRANSFORM_FEEDBACK_PAUSED => Some("GL_TRANSFORM_FEEDBACK_PAUSED"),
+        GL_TRANSFORM_FEEDBACK_ACTIVE => Some("GL_TRANSFORM_FEEDBACK_ACTIVE"),
+        GL_TRANSFORM_FEEDBACK_BINDING => Some("GL_TRANSFORM_FEEDBACK_BINDING"),
+        GL_TEXTURE_SWIZZLE_R => Some("GL_TEXTURE_SWIZZLE_R"),
+        GL_TEXTURE_SWIZZLE_G => Some("GL_TEXTURE_SWIZZLE_G"),
+        GL_TEXTURE_SWIZZLE_B => Some("GL_TEXTURE_SWIZZLE_B"),
+        GL_TEXTURE_SWIZZLE_A => Some("GL_TEXTURE_SWIZZLE_A"),
+        GL_FIRST_VERTEX_CONVENTION => Some("GL_FIRST_VERTEX_CONVENTION"),
+        GL_LAST_VERTEX_CONVENTION => Some("GL_LAST_VERTEX_CONVENTION"),
+        GL_SAMPLE_POSITION => Some("GL_SAMPLE_POSITION"),
+        GL_SAMPLE_MASK => Some("GL_SAMPLE_MASK"),
+        GL_SAMPLE_MASK_VALUE => Some("GL_SAMPLE_MASK_VALUE"),
+        GL_MAX_SAMPLE_MASK_WORDS => Some("GL_MAX_SAMPLE_MASK_WORDS"),
+        GL_MAX_GEOMETRY_SHADER_INVOCATIONS => Some("GL_MAX_GEOMETRY_SHADER_INVOCATIONS"),
+        GL_MIN_FRAGMENT_INTERPOLATION_OFFSET => Some("GL_MIN_FRAGMENT_INTERPOLATION_OFFSET"),
+        GL_MAX_FRAGMENT_INTERPOLATION_OFFSET => Some("GL_MAX_FRAGMENT_INTERPOLATION_OFFSET"),
+        GL_FRAGMENT_INTERPOLATION_OFFSET_BITS => Some("GL_FRAGMENT_INTERPOLATION_OFFSET_BITS"),
+        GL_MIN_PROGRAM_TEXTURE_GATHER_OFFSET => Some("GL_MIN_PROGRAM_TEXTURE_GATHER_OFFSET"),
+        GL_MAX_PROGRAM_TEXTURE_GATHER_OFFSET => Some("GL_MAX_PROGRAM_TEXTURE_GATHER_OFFSET"),
+        GL_PATCH_VERTICES => Some("GL_PATCH_VERTICES"),
+        GL_TESS_CONTROL_OUTPUT_VERTICES => Some("GL_TESS_CONTROL_OUTPUT_VERTICES"),
+        GL_TESS_GEN_MODE => Some("GL_TESS_GEN_MODE"),
+        GL_TESS_GEN_SPACING => Some("GL_TESS_GEN_SPACING"),
+        GL_TESS_GEN_VERTEX_ORDER => Some("GL_TESS_GEN_VERTEX_ORDER"),
+        GL_TESS_GEN_POINT_MODE => Some("GL_TESS_GEN_POINT_MODE"),
+        GL_ISOLINES => Some("GL_ISOLINES"),
+        GL_FRACTIONAL_ODD => Some("GL_FRACTIONAL_ODD"),
+        GL_FRACTIONAL_EVEN => Some("GL_FRACTIONAL_EVEN"),
+        GL_MAX_PATCH_VERTICES => Some("GL_MAX_PATCH_VERTICES"),
+        GL_MAX_TESS_GEN_LEVEL => Some("GL_MAX_TESS_GEN_LEVEL"),
+        GL_MAX_TESS_CONTROL_UNIFORM_COMPONENTS => Some("GL_MAX_TESS_CONTROL_UNIFORM_COMPONENTS"),
+        GL_MAX_TESS_EVALUATION_UNIFORM_COMPONENTS => Some("GL_MAX_TESS_EVALUATION_UNIFORM_COMPONENTS"),
+        GL_MAX_TESS_CONTROL_TEXTURE_IMAGE_UNITS => Some("GL_MAX_TESS_CONTROL_TEXTURE_IMAGE_UNITS"),
+        GL_MAX_TESS_EVALUATION_TEXTURE_IMAGE_UNITS => Some("GL_MAX_TESS_EVALUATION_TEXTURE_IMAGE_UNITS"),
+        GL_MAX_TESS_CONTROL_OUTPUT_COMPONENTS => Some("GL_MAX_TESS_CONTROL_OUTPUT_COMPONENTS"),
+        GL_MAX_TESS_PATCH_COMPONENTS => Some("GL_MAX_TESS_PATCH_COMPONENTS"),
+        GL_MAX_TESS_CONTROL_TOTAL_OUTPUT_COMPONENTS => Some("GL_MAX_TESS_CONTROL_TOTAL_OUTPUT_COMPONENTS"),
+        GL_MAX_TESS_EVALUATION_OUTPUT_COMPONENTS => Some("GL_MAX_TESS_EVALUATION_OUTPUT_COMPONENTS"),
+        GL_TESS_EVALUATION_SHADER => Some("GL_TESS_EVALUATION_SHADER"),
+        GL_TESS_CONTROL_SHADER => Some("GL_TESS_CONTROL_SHADER"),
+        GL_MAX_TESS_CONTROL_UNIFORM_BLOCKS => Some("GL_MAX_TESS_CONTROL_UNIFORM_BLOCKS"),
+        GL_MAX_TESS_EVALUATION_UNIFORM_BLOCKS => Some("GL_MAX_TESS_EVALUATION_UNIFORM_BLOCKS"),
+        GL_COPY_READ_BUFFER => Some("GL_COPY_READ_BUFFER"),
+        GL_COPY_WRITE_BUFFER => Some("GL_COPY_WRITE_BUFFER"),
+        GL_MAX_IMAGE_UNITS => Some("GL_MAX_IMAGE_UNITS"),
+        GL_MAX_COMBINED_SHADER_OUTPUT_RESOURCES => Some("GL_MAX_COMBINED_SHADER_OUTPUT_RESOURCES"),
+        GL_IMAGE_BINDING_NAME => Some("GL_IMAGE_BINDING_NAME"),
+        GL_IMAGE_BINDING_LEVEL => Some("GL_IMAGE_BINDING_LEVEL"),
+        GL_IMAGE_BINDING_LAYERED => Some("GL_IMAGE_BINDING_LAYERED"),
+        GL_IMAGE_BINDING_LAYER => Some("GL_IMAGE_BINDING_LAYER"),
+        GL_IMAGE_BINDING_ACCESS => Some("GL_IMAGE_BINDING_ACCESS"),
+        GL_DRAW_INDIRECT_BUFFER => Some("GL_DRAW_INDIRECT_BUFFER"),
+        GL_DRAW_INDIRECT_BUFFER_BINDING => Some("GL_DRAW_INDIRECT_BUFFER_BINDING"),
+        GL_VERTEX_BINDING_BUFFER => Some("GL_VERTEX_BINDING_BUFFER"),
+        GL_R8_SNORM => Some("GL_R8_SNORM"),
+        GL_RG8_SNORM => Some("GL_RG8_SNORM"),
+        GL_RGB8_SNORM => Some("GL_RGB8_SNORM"),
+        GL_RGBA8_SNORM => Some("GL_RGBA8_SNORM"),
+        GL_SIGNED_NORMALIZED => Some("GL_SIGNED_NORMALIZED"),
+        GL_TEXTURE_CUBE_MAP_ARRAY => Some("GL_TEXTURE_CUBE_MAP_ARRAY"),
+        GL_TEXTURE_BINDING_CUBE_MAP_ARRAY => Some("GL_TEXTURE_BINDING_CUBE_MAP_ARRAY"),
+        GL_SAMPLER_CUBE_MAP_ARRAY => Some("GL_SAMPLER_CUBE_MAP_ARRAY"),
+        GL_SAMPLER_CUBE_MAP_ARRAY_SHADOW => Some("GL_SAMPLER_CUBE_MAP_ARRAY_SHADOW"),
+        GL_INT_SAMPLER_CUBE_MAP_ARRAY => Some("GL_INT_SAMPLER_CUBE_MAP_ARRAY"),
+        GL_UNSIGNED_INT_SAMPLER_CUBE_MAP_ARRAY => Some("GL_UNSIGNED_INT_SAMPLER_CUBE_MAP_ARRAY"),
+        GL_IMAGE_2D => Some("GL_IMAGE_2D"),
+        GL_IMAGE_3D => Some("GL_IMAGE_3D"),
+        GL_IMAGE_CUBE => Some("GL_IMAGE_CUBE"),
+        GL_IMAGE_BUFFER => Some("GL_IMAGE_BUFFER"),
+        GL_IMAGE_2D_ARRAY => Some("GL_IMAGE_2D_ARRAY"),
+        GL_IMAGE_CUBE_MAP_ARRAY => Some("GL_IMAGE_CUBE_MAP_ARRAY"),
+        GL_INT_IMAGE_2D => Some("GL_INT_IMAGE_2D"),
+        GL_INT_IMAGE_3D => Some("GL_INT_IMAGE_3D"),
+        GL_INT_IMAGE_CUBE => Some("GL_INT_IMAGE_CUBE"),
+        GL_INT_IMAGE_BUFFER => Some("GL_INT_IMAGE_BUFFER"),
+        GL_INT_IMAGE_2D_ARRAY => Some("GL_INT_IMAGE_2D_ARRAY"),
+        GL_INT_IMAGE_CUBE_MAP_ARRAY => Some("GL_INT_IMAGE_CUBE_MAP_ARRAY"),
+        GL_UNSIGNED_INT_IMAGE_2D => Some("GL_UNSIGNED_INT_IMAGE_2D"),
+        GL_UNSIGNED_INT_IMAGE_3D => Some("GL_UNSIGNED_INT_IMAGE_3D"),
+        GL_UNSIGNED_INT_IMAGE_CUBE => Some("GL_UNSIGNED_INT_IMAGE_CUBE"),
+        GL_UNSIGNED_INT_IMAGE_BUFFER => Some("GL_UNSIGNED_INT_IMAGE_BUFFER"),
+        GL_UNSIGNED_INT_IMAGE_2D_ARRAY => Some("GL_UNSIGNED_INT_IMAGE_2D_ARRAY"),
+        GL_UNSIGNED_INT_IMAGE_CUBE_MAP_ARRAY => Some("GL_UNSIGNED_INT_IMAGE_CUBE_MAP_ARRAY"),
+        GL_IMAGE_BINDING_FORMAT => Some("GL_IMAGE_BINDING_FORMAT"),
+        GL_RGB10_A2UI => Some("GL_RGB10_A2UI"),
+        GL_IMAGE_FORMAT_COMPATIBILITY_TYPE => Some("GL_IMAGE_FORMAT_COMPATIBILITY_TYPE"),
+        GL_IMAGE_FORMAT_COMPATIBILITY_BY_SIZE => Some("GL_IMAGE_FORMAT_COMPATIBILITY_BY_SIZE"),
+        GL_IMAGE_FORMAT_COMPATIBILITY_BY_CLASS => Some("GL_IMAGE_FORMAT_COMPATIBILITY_BY_CLASS"),
+        GL_MAX_VERTEX_IMAGE_UNIFORMS => Some("GL_MAX_VERTEX_IMAGE_UNIFORMS"),
+        GL_MAX_TESS_CONTROL_IMAGE_UNIFORMS => Some("GL_MAX_TESS_CONTROL_IMAGE_UNIFORMS"),
+        GL_MAX_TESS_EVALUATION_IMAGE_UNIFORMS => Some("GL_MAX_TESS_EVALUATION_IMAGE_UNIFORMS"),
+        GL_MAX_GEOMETRY_IMAGE_UNIFORMS => Some("GL_MAX_GEOMETRY_IMAGE_UNIFORMS"),
+        GL_MAX_FRAGMENT_IMAGE_UNIFORMS => Some("GL_MAX_FRAGMENT_IMAGE_UNIFORMS"),
+        GL_MAX_COMBINED_IMAGE_UNIFORMS => Some("GL_MAX_COMBINED_IMAGE_UNIFORMS"),
+        GL_SHADER_STORAGE_BUFFER => Some("GL_SHADER_STORAGE_BUFFER"),
+        GL_SHADER_STORAGE_BUFFER_BINDING => Some("GL_SHADER_STORAGE_BUFFER_BINDING"),
+        GL_SHADER_STORAGE_BUFFER_START => Some("GL_SHADER_STORAGE_BUFFER_START"),
+        GL_SHADER_STORAGE_BUFFER_SIZE => Some("GL_SHADER_STORAGE_BUFFER_SIZE"),
+        GL_MAX_VERTEX_SHADER_STORAGE_BLOCKS => Some("GL_MAX_VERTEX_SHADER_STORAGE_BLOCKS"),
+        GL_MAX_GEOMETRY_SHADER_STORAGE_BLOCKS => Some("GL_MAX_GEOMETRY_SHADER_STORAGE_BLOCKS"),
+        GL_MAX_TESS_CONTROL_SHADER_STORAGE_BLOCKS => Some("GL_MAX_TESS_CONTROL_SHADER_STORAGE_BLOCKS"),
+        GL_MAX_TESS_EVALUATION_SHADER_STORAGE_BLOCKS => Some("GL_MAX_TESS_EVALUATION_SHADER_STORAGE_BLOCKS"),
+        GL_MAX_FRAGMENT_SHADER_STORAGE_BLOCKS => Some("GL_MAX_FRAGMENT_SHADER_STORAGE_BLOCKS"),
+        GL_MAX_COMPUTE_SHADER_STORAGE_BLOCKS => Some("GL_MAX_COMPUTE_SHADER_STORAGE_BLOCKS"),
+        GL_MAX_COMBINED_SHADER_STORAGE_BLOCKS => Some("GL_MAX_COMBINED_SHADER_STORAGE_BLOCKS"),
+        GL_MAX_SHADER_STORAGE_BUFFER_BINDINGS => Some("GL_MAX_SHADER_STORAGE_BUFFER_BINDINGS"),
+        GL_MAX_SHADER_STORAGE_BLOCK_SIZE => Some("GL_MAX_SHADER_STORAGE_BLOCK_SIZE"),
+        GL_SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT => Some("GL_SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT"),
+        GL_DEPTH_STENCIL_TEXTURE_MODE => Some("GL_DEPTH_STENCIL_TEXTURE_MODE"),
+        GL_MAX_COMPUTE_WORK_GROUP_INVOCATIONS => Some("GL_MAX_COMPUTE_WORK_GROUP_INVOCATIONS"),
+        GL_DISPATCH_INDIRECT_BUFFER => Some("GL_DISPATCH_INDIRECT_BUFFER"),
+        GL_DISPATCH_INDIRECT_BUFFER_BINDING => Some("GL_DISPATCH_INDIRECT_BUFFER_BINDING"),
+        GL_TEXTURE_2D_MULTISAMPLE => Some("GL_TEXTURE_2D_MULTISAMPLE"),
+        GL_TEXTURE_2D_MULTISAMPLE_ARRAY => Some("GL_TEXTURE_2D_MULTISAMPLE_ARRAY"),
+        GL_TEXTURE_BINDING_2D_MULTISAMPLE => Some("GL_TEXTURE_BINDING_2D_MULTISAMPLE"),
+        GL_TEXTURE_BINDING_2D_MULTISAMPLE_ARRAY => Some("GL_TEXTURE_BINDING_2D_MULTISAMPLE_ARRAY"),
+        GL_TEXTURE_SAMPLES => Some("GL_TEXTURE_SAMPLES"),
+        GL_TEXTURE_FIXED_SAMPLE_LOCATIONS => Some("GL_TEXTURE_FIXED_SAMPLE_LOCATIONS"),
+        GL_SAMPLER_2D_MULTISAMPLE => Some("GL_SAMPLER_2D_MULTISAMPLE"),
+        GL_INT_SAMPLER_2D_MULTISAMPLE => Some("GL_INT_SAMPLER_2D_MULTISAMPLE"),
+        GL_UNSIGNED_INT_SAMPLER_2D_MULTISAMPLE => Some("GL_UNSIGNED_INT_SAMPLER_2D_MULTISAMPLE"),
+        GL_SAMPLER_2D_MULTISAMPLE_ARRAY => Some("GL_SAMPLER_2D_MULTISAMPLE_ARRAY"),
+        GL_INT_SAMPLER_2D_MULTISAMPLE_ARRAY => Some("GL_INT_SAMPLER_2D_MULTISAMPLE_ARRAY"),
+        GL_UNSIGNED_INT_SAMPLER_2D_MULTISAMPLE_ARRAY => Some("GL_UNSIGNED_INT_SAMPLER_2D_MULTISAMPLE_ARRAY"),
+        GL_MAX_COLOR_TEXTURE_SAMPLES => Some("GL_MAX_COLOR_TEXTURE_SAMPLES"),
+        GL_MAX_DEPTH_TEXTURE_SAMPLES => Some("GL_MAX_DEPTH_TEXTURE_SAMPLES"),
+        GL_MAX_INTEGER_SAMPLES => Some("GL_MAX_INTEGER_SAMPLES"),
+        GL_MAX_SERVER_WAIT_TIMEOUT => Some("GL_MAX_SERVER_WAIT_TIMEOUT"),
+        GL_OBJECT_TYPE => Some("GL_OBJECT_TYPE"),
+        GL_SYNC_CONDITION => Some("GL_SYNC_CONDITION"),
+        GL_SYNC_STATUS => Some("GL_SYNC_STATUS"),
+        GL_SYNC_FLAGS => Some("GL_SYNC_FLAGS"),
+        GL_SYNC_FENCE => Some("GL_SYNC_FENCE"),
+        GL_SYNC_GPU_COMMANDS_COMPLETE => Some("GL_SYNC_GPU_COMMANDS_COMPLETE"),
+        GL_UNSIGNALED => Some("GL_UNSIGNALED"),
+        GL_SIGNALED => Some("GL_SIGNALED"),
+        GL_ALREADY_SIGNALED => Some("GL_ALREADY_SIGNALED"),
+        GL_TIMEOUT_EXPIRED => Some("GL_TIMEOUT_EXPIRED"),
+        GL_CONDITION_SATISFIED => Some("GL_CONDITION_SATISFIED"),
+        GL_WAIT_FAILED => Some("GL_WAIT_FAILED"),
+        GL_BUFFER_ACCESS_FLAGS => Some("GL_BUFFER_ACCESS_FLAGS"),
+        GL_BUFFER_MAP_LENGTH => Some("GL_BUFFER_MAP_LENGTH"),
+        GL_BUFFER_MAP_OFFSET => Some("GL_BUFFER_MAP_OFFSET"),
+        GL_MAX_VERTEX_OUTPUT_COMPONENTS => Some("GL_MAX_VERTEX_OUTPUT_COMPONENTS"),
+        GL_MAX_GEOMETRY_INPUT_COMPONENTS => Some("GL_MAX_GEOMETRY_INPUT_COMPONENTS"),
+        GL_MAX_GEOMETRY_OUTPUT_COMPONENTS => Some("GL_MAX_GEOMETRY_OUTPUT_COMPONENTS"),
+        GL_MAX_FRAGMENT_INPUT_COMPONENTS => Some("GL_MAX_FRAGMENT_INPUT_COMPONENTS"),
+        GL_TEXTURE_IMMUTABLE_FORMAT => Some("GL_TEXTURE_IMMUTABLE_FORMAT"),
+        GL_MAX_DEBUG_MESSAGE_LENGTH => Some("GL_MAX_DEBUG_MESSAGE_LENGTH"),
+        GL_MAX_DEBUG_LOGGED_MESSAGES => Some("GL_MAX_DEBUG_LOGGED_MESSAGES"),
+        GL_DEBUG_LOGGED_MESSAGES => Some("GL_DEBUG_LOGGED_MESSAGES"),
+        GL_DEBUG_SEVERITY_HIGH => Some("GL_DEBUG_SEVERITY_HIGH"),
+        GL_DEBUG_SEVERITY_MEDIUM => Some("GL_DEBUG_SEVERITY_MEDIUM"),
+        GL_DEBUG_SEVERITY_LOW => Some("GL_DEBUG_SEVERITY_LOW"),
+        GL_TEXTURE_BUFFER_OFFSET => Some("GL_TEXTURE_BUFFER_OFFSET"),
+        GL_TEXTURE_BUFFER_SIZE => Some("GL_TEXTURE_BUFFER_SIZE"),
+        GL_TEXTURE_BUFFER_OFFSET_ALIGNMENT => Some("GL_TEXTURE_BUFFER_OFFSET_ALIGNMENT"),
+        GL_COMPUTE_SHADER => Some("GL_COMPUTE_SHADER"),
+        GL_MAX_COMPUTE_UNIFORM_BLOCKS => Some("GL_MAX_COMPUTE_UNIFORM_BLOCKS"),
+        GL_MAX_COMPUTE_TEXTURE_IMAGE_UNITS => Some("GL_MAX_COMPUTE_TEXTURE_IMAGE_UNITS"),
+        GL_MAX_COMPUTE_IMAGE_UNIFORMS => Some("GL_MAX_COMPUTE_IMAGE_UNIFORMS"),
+        GL_MAX_COMPUTE_WORK_GROUP_COUNT => Some("GL_MAX_COMPUTE_WORK_GROUP_COUNT"),
+        GL_MAX_COMPUTE_WORK_GROUP_SIZE => Some("GL_MAX_COMPUTE_WORK_GROUP_SIZE"),
+        GL_COMPRESSED_R11_EAC => Some("GL_COMPRESSED_R11_EAC"),
+        GL_COMPRESSED_SIGNED_R11_EAC => Some("GL_COMPRESSED_SIGNED_R11_EAC"),
+        GL_COMPRESSED_RG11_EAC => Some("GL_COMPRESSED_RG11_EAC"),
+        GL_COMPRESSED_SIGNED_RG11_EAC => Some("GL_COMPRESSED_SIGNED_RG11_EAC"),
+        GL_COMPRESSED_RGB8_ETC2 => Some("GL_COMPRESSED_RGB8_ETC2"),
+        GL_COMPRESSED_SRGB8_ETC2 => Some("GL_COMPRESSED_SRGB8_ETC2"),
+        GL_COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2 => Some("GL_COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2"),
+        GL_COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2 => Some("GL_COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2"),
+        GL_COMPRESSED_RGBA8_ETC2_EAC => Some("GL_COMPRESSED_RGBA8_ETC2_EAC"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ETC2_EAC => Some("GL_COMPRESSED_SRGB8_ALPHA8_ETC2_EAC"),
+        GL_MULTIPLY => Some("GL_MULTIPLY"),
+        GL_SCREEN => Some("GL_SCREEN"),
+        GL_OVERLAY => Some("GL_OVERLAY"),
+        GL_DARKEN => Some("GL_DARKEN"),
+        GL_LIGHTEN => Some("GL_LIGHTEN"),
+        GL_COLORDODGE => Some("GL_COLORDODGE"),
+        GL_COLORBURN => Some("GL_COLORBURN"),
+        GL_HARDLIGHT => Some("GL_HARDLIGHT"),
+        GL_SOFTLIGHT => Some("GL_SOFTLIGHT"),
+        GL_DIFFERENCE => Some("GL_DIFFERENCE"),
+        GL_EXCLUSION => Some("GL_EXCLUSION"),
+        GL_HSL_HUE => Some("GL_HSL_HUE"),
+        GL_HSL_SATURATION => Some("GL_HSL_SATURATION"),
+        GL_HSL_COLOR => Some("GL_HSL_COLOR"),
+        GL_HSL_LUMINOSITY => Some("GL_HSL_LUMINOSITY"),
+        GL_PRIMITIVE_BOUNDING_BOX => Some("GL_PRIMITIVE_BOUNDING_BOX"),
+        GL_ATOMIC_COUNTER_BUFFER => Some("GL_ATOMIC_COUNTER_BUFFER"),
+        GL_ATOMIC_COUNTER_BUFFER_BINDING => Some("GL_ATOMIC_COUNTER_BUFFER_BINDING"),
+        GL_ATOMIC_COUNTER_BUFFER_START => Some("GL_ATOMIC_COUNTER_BUFFER_START"),
+        GL_ATOMIC_COUNTER_BUFFER_SIZE => Some("GL_ATOMIC_COUNTER_BUFFER_SIZE"),
+        GL_MAX_VERTEX_ATOMIC_COUNTER_BUFFERS => Some("GL_MAX_VERTEX_ATOMIC_COUNTER_BUFFERS"),
+        GL_MAX_TESS_CONTROL_ATOMIC_COUNTER_BUFFERS => Some("GL_MAX_TESS_CONTROL_ATOMIC_COUNTER_BUFFERS"),
+        GL_MAX_TESS_EVALUATION_ATOMIC_COUNTER_BUFFERS => Some("GL_MAX_TESS_EVALUATION_ATOMIC_COUNTER_BUFFERS"),
+        GL_MAX_GEOMETRY_ATOMIC_COUNTER_BUFFERS => Some("GL_MAX_GEOMETRY_ATOMIC_COUNTER_BUFFERS"),
+        GL_MAX_FRAGMENT_ATOMIC_COUNTER_BUFFERS => Some("GL_MAX_FRAGMENT_ATOMIC_COUNTER_BUFFERS"),
+        GL_MAX_COMBINED_ATOMIC_COUNTER_BUFFERS => Some("GL_MAX_COMBINED_ATOMIC_COUNTER_BUFFERS"),
+        GL_MAX_VERTEX_ATOMIC_COUNTERS => Some("GL_MAX_VERTEX_ATOMIC_COUNTERS"),
+        GL_MAX_TESS_CONTROL_ATOMIC_COUNTERS => Some("GL_MAX_TESS_CONTROL_ATOMIC_COUNTERS"),
+        GL_MAX_TESS_EVALUATION_ATOMIC_COUNTERS => Some("GL_MAX_TESS_EVALUATION_ATOMIC_COUNTERS"),
+        GL_MAX_GEOMETRY_ATOMIC_COUNTERS => Some("GL_MAX_GEOMETRY_ATOMIC_COUNTERS"),
+        GL_MAX_FRAGMENT_ATOMIC_COUNTERS => Some("GL_MAX_FRAGMENT_ATOMIC_COUNTERS"),
+        GL_MAX_COMBINED_ATOMIC_COUNTERS => Some("GL_MAX_COMBINED_ATOMIC_COUNTERS"),
+        GL_MAX_ATOMIC_COUNTER_BUFFER_SIZE => Some("GL_MAX_ATOMIC_COUNTER_BUFFER_SIZE"),
+        GL_ACTIVE_ATOMIC_COUNTER_BUFFERS => Some("GL_ACTIVE_ATOMIC_COUNTER_BUFFERS"),
+        GL_UNSIGNED_INT_ATOMIC_COUNTER => Some("GL_UNSIGNED_INT_ATOMIC_COUNTER"),
+        GL_MAX_ATOMIC_COUNTER_BUFFER_BINDINGS => Some("GL_MAX_ATOMIC_COUNTER_BUFFER_BINDINGS"),
+        GL_DEBUG_OUTPUT => Some("GL_DEBUG_OUTPUT"),
+        GL_UNIFORM => Some("GL_UNIFORM"),
+        GL_UNIFORM_BLOCK => Some("GL_UNIFORM_BLOCK"),
+        GL_PROGRAM_INPUT => Some("GL_PROGRAM_INPUT"),
+        GL_PROGRAM_OUTPUT => Some("GL_PROGRAM_OUTPUT"),
+        GL_BUFFER_VARIABLE => Some("GL_BUFFER_VARIABLE"),
+        GL_SHADER_STORAGE_BLOCK => Some("GL_SHADER_STORAGE_BLOCK"),
+        GL_IS_PER_PATCH => Some("GL_IS_PER_PATCH"),
+        GL_TRANSFORM_FEEDBACK_VARYING => Some("GL_TRANSFORM_FEEDBACK_VARYING"),
+        GL_ACTIVE_RESOURCES => Some("GL_ACTIVE_RESOURCES"),
+        GL_MAX_NAME_LENGTH => Some("GL_MAX_NAME_LENGTH"),
+        GL_MAX_NUM_ACTIVE_VARIABLES => Some("GL_MAX_NUM_ACTIVE_VARIABLES"),
+        GL_NAME_LENGTH => Some("GL_NAME_LENGTH"),
+        GL_TYPE => Some("GL_TYPE"),
+        GL_ARRAY_SIZE => Some("GL_ARRAY_SIZE"),
+        GL_OFFSET => Some("GL_OFFSET"),
+        GL_BLOCK_INDEX => Some("GL_BLOCK_INDEX"),
+        GL_ARRAY_STRIDE => Some("GL_ARRAY_STRIDE"),
+        GL_MATRIX_STRIDE => Some("GL_MATRIX_STRIDE"),
+        GL_IS_ROW_MAJOR => Some("GL_IS_ROW_MAJOR"),
+        GL_ATOMIC_COUNTER_BUFFER_INDEX => Some("GL_ATOMIC_COUNTER_BUFFER_INDEX"),
+        GL_BUFFER_BINDING => Some("GL_BUFFER_BINDING"),
+        GL_BUFFER_DATA_SIZE => Some("GL_BUFFER_DATA_SIZE"),
+        GL_NUM_ACTIVE_VARIABLES => Some("GL_NUM_ACTIVE_VARIABLES"),
+        GL_ACTIVE_VARIABLES => Some("GL_ACTIVE_VARIABLES"),
+        GL_REFERENCED_BY_VERTEX_SHADER => Some("GL_REFERENCED_BY_VERTEX_SHADER"),
+        GL_REFERENCED_BY_TESS_CONTROL_SHADER => Some("GL_REFERENCED_BY_TESS_CONTROL_SHADER"),
+        GL_REFERENCED_BY_TESS_EVALUATION_SHADER => Some("GL_REFERENCED_BY_TESS_EVALUATION_SHADER"),
+        GL_REFERENCED_BY_GEOMETRY_SHADER => Some("GL_REFERENCED_BY_GEOMETRY_SHADER"),
+        GL_REFERENCED_BY_FRAGMENT_SHADER => Some("GL_REFERENCED_BY_FRAGMENT_SHADER"),
+        GL_REFERENCED_BY_COMPUTE_SHADER => Some("GL_REFERENCED_BY_COMPUTE_SHADER"),
+        GL_TOP_LEVEL_ARRAY_SIZE => Some("GL_TOP_LEVEL_ARRAY_SIZE"),
+        GL_TOP_LEVEL_ARRAY_STRIDE => Some("GL_TOP_LEVEL_ARRAY_STRIDE"),
+        GL_LOCATION => Some("GL_LOCATION"),
+        GL_FRAMEBUFFER_DEFAULT_WIDTH => Some("GL_FRAMEBUFFER_DEFAULT_WIDTH"),
+        GL_FRAMEBUFFER_DEFAULT_HEIGHT => Some("GL_FRAMEBUFFER_DEFAULT_HEIGHT"),
+        GL_FRAMEBUFFER_DEFAULT_LAYERS => Some("GL_FRAMEBUFFER_DEFAULT_LAYERS"),
+        GL_FRAMEBUFFER_DEFAULT_SAMPLES => Some("GL_FRAMEBUFFER_DEFAULT_SAMPLES"),
+        GL_FRAMEBUFFER_DEFAULT_FIXED_SAMPLE_LOCATIONS => Some("GL_FRAMEBUFFER_DEFAULT_FIXED_SAMPLE_LOCATIONS"),
+        GL_MAX_FRAMEBUFFER_WIDTH => Some("GL_MAX_FRAMEBUFFER_WIDTH"),
+        GL_MAX_FRAMEBUFFER_HEIGHT => Some("GL_MAX_FRAMEBUFFER_HEIGHT"),
+        GL_MAX_FRAMEBUFFER_LAYERS => Some("GL_MAX_FRAMEBUFFER_LAYERS"),
+        GL_MAX_FRAMEBUFFER_SAMPLES => Some("GL_MAX_FRAMEBUFFER_SAMPLES"),
+        GL_NUM_SAMPLE_COUNTS => Some("GL_NUM_SAMPLE_COUNTS"),
+        GL_MULTISAMPLE_LINE_WIDTH_RANGE => Some("GL_MULTISAMPLE_LINE_WIDTH_RANGE"),
+        GL_MULTISAMPLE_LINE_WIDTH_GRANULARITY => Some("GL_MULTISAMPLE_LINE_WIDTH_GRANULARITY"),
+        GL_COMPRESSED_RGBA_ASTC_4x4 => Some("GL_COMPRESSED_RGBA_ASTC_4x4"),
+        GL_COMPRESSED_RGBA_ASTC_5x4 => Some("GL_COMPRESSED_RGBA_ASTC_5x4"),
+        GL_COMPRESSED_RGBA_ASTC_5x5 => Some("GL_COMPRESSED_RGBA_ASTC_5x5"),
+        GL_COMPRESSED_RGBA_ASTC_6x5 => Some("GL_COMPRESSED_RGBA_ASTC_6x5"),
+        GL_COMPRESSED_RGBA_ASTC_6x6 => Some("GL_COMPRESSED_RGBA_ASTC_6x6"),
+        GL_COMPRESSED_RGBA_ASTC_8x5 => Some("GL_COMPRESSED_RGBA_ASTC_8x5"),
+        GL_COMPRESSED_RGBA_ASTC_8x6 => Some("GL_COMPRESSED_RGBA_ASTC_8x6"),
+        GL_COMPRESSED_RGBA_ASTC_8x8 => Some("GL_COMPRESSED_RGBA_ASTC_8x8"),
+        GL_COMPRESSED_RGBA_ASTC_10x5 => Some("GL_COMPRESSED_RGBA_ASTC_10x5"),
+        GL_COMPRESSED_RGBA_ASTC_10x6 => Some("GL_COMPRESSED_RGBA_ASTC_10x6"),
+        GL_COMPRESSED_RGBA_ASTC_10x8 => Some("GL_COMPRESSED_RGBA_ASTC_10x8"),
+        GL_COMPRESSED_RGBA_ASTC_10x10 => Some("GL_COMPRESSED_RGBA_ASTC_10x10"),
+        GL_COMPRESSED_RGBA_ASTC_12x10 => Some("GL_COMPRESSED_RGBA_ASTC_12x10"),
+        GL_COMPRESSED_RGBA_ASTC_12x12 => Some("GL_COMPRESSED_RGBA_ASTC_12x12"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4x4 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4x4"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5x4 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5x4"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5x5 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5x5"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6x5 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6x5"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6x6 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6x6"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8x5 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8x5"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8x6 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8x6"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8x8 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8x8"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10x5 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10x5"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10x6 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10x6"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10x8 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10x8"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10x10 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10x10"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12x10 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12x10"),
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12x12 => Some("GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12x12"),
+        GL_ETC1_RGB8_OES => Some("GL_ETC1_RGB8_OES"),
+        GL_COMPRESSED_RGB_S3TC_DXT1_EXT => Some("GL_COMPRESSED_RGB_S3TC_DXT1_EXT"),
+        GL_COMPRESSED_RGBA_S3TC_DXT1_EXT => Some("GL_COMPRESSED_RGBA_S3TC_DXT1_EXT"),
+        GL_COMPRESSED_RGBA_S3TC_DXT3_EXT => Some("GL_COMPRESSED_RGBA_S3TC_DXT3_EXT"),
+        GL_COMPRESSED_RGBA_S3TC_DXT5_EXT => Some("GL_COMPRESSED_RGBA_S3TC_DXT5_EXT"),
+        GL_COMPRESSED_RGB_PVRTC_4BPPV1_IMG => Some("GL_COMPRESSED_RGB_PVRTC_4BPPV1_IMG"),
+        GL_COMPRESSED_RGB_PVRTC_2BPPV1_IMG => Some("GL_COMPRESSED_RGB_PVRTC_2BPPV1_IMG"),
+        GL_COMPRESSED_RGBA_PVRTC_4BPPV1_IMG => Some("GL_COMPRESSED_RGBA_PVRTC_4BPPV1_IMG"),
+        GL_COMPRESSED_RGBA_PVRTC_2BPPV1_IMG => Some("GL_COMPRESSED_RGBA_PVRTC_2BPPV1_IMG"),
+        GL_HALF_FLOAT_OES => Some("GL_HALF_FLOAT_OES"),
+        GL_SGX_BINARY_IMG => Some("GL_SGX_BINARY_IMG"),
+        GL_NVIDIA_PLATFORM_BINARY_NV => Some("GL_NVIDIA_PLATFORM_BINARY_NV"),
+        GL_MALI_SHADER_BINARY_ARM => Some("GL_MALI_SHADER_BINARY_ARM"),
+        GL_SHADER_BINARY_VIV => Some("GL_SHADER_BINARY_VIV"),
+        GL_SHADER_BINARY_DMP => Some("GL_SHADER_BINARY_DMP"),
+        GL_GCCSO_SHADER_BINARY_FJ => Some("GL_GCCSO_SHADER_BINARY_FJ"),
+        _ => None,
+    }
+}
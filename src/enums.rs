@@ -43,8 +43,8 @@ pub enum TextureUnit {
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum BufferTarget {
-    ARRAY_BUFFER = GL_ARRAY_BUFFER_BINDING as isize,
-    ELEMENT_ARRAY_BUFFER = GL_ELEMENT_ARRAY_BUFFER_BINDING as isize
+    ARRAY_BUFFER = GL_ARRAY_BUFFER as isize,
+    ELEMENT_ARRAY_BUFFER = GL_ELEMENT_ARRAY_BUFFER as isize
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -59,8 +59,8 @@ pub enum RenderBufferTarget {
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum TextureBindTarget {
-    TEXTURE_2D = GL_TEXTURE_BINDING_2D as isize,
-    TEXTURE_CUBE_MAP = GL_TEXTURE_BINDING_CUBE_MAP as isize
+    TEXTURE_2D = GL_TEXTURE_2D as isize,
+    TEXTURE_CUBE_MAP = GL_TEXTURE_CUBE_MAP as isize
 
 }
 
@@ -116,17 +116,28 @@ pub enum FrameBufferStatus {
     FRAMEBUFFER_INCOMPLETE_ATTACHMENT = GL_FRAMEBUFFER_INCOMPLETE_ATTACHMENT as isize,
     FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT = GL_FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT as isize,
     FRAMEBUFFER_INCOMPLETE_DIMENSIONS = GL_FRAMEBUFFER_INCOMPLETE_DIMENSIONS as isize,
-    FRAMEBUFFER_UNSUPPORTED = GL_FRAMEBUFFER_UNSUPPORTED as isize
-}
-
-impl From<GLuint> for FrameBufferStatus {
-    fn from(id: GLuint) -> Self {
-        match id {
-            GL_FRAMEBUFFER_COMPLETE => FrameBufferStatus::FRAMEBUFFER_COMPLETE,
-            GL_FRAMEBUFFER_INCOMPLETE_ATTACHMENT => FrameBufferStatus::FRAMEBUFFER_INCOMPLETE_ATTACHMENT,
-            GL_FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => FrameBufferStatus::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT,
-            GL_FRAMEBUFFER_INCOMPLETE_DIMENSIONS => FrameBufferStatus::FRAMEBUFFER_INCOMPLETE_DIMENSIONS,
-            _ => FrameBufferStatus::FRAMEBUFFER_UNSUPPORTED
+    FRAMEBUFFER_UNSUPPORTED = GL_FRAMEBUFFER_UNSUPPORTED as isize,
+    /// ES 3.0 and multisample extensions: the attachments have different sample counts.
+    FRAMEBUFFER_INCOMPLETE_MULTISAMPLE = GL_FRAMEBUFFER_INCOMPLETE_MULTISAMPLE as isize,
+    /// ES 3.0: the default framebuffer is bound but does not exist.
+    FRAMEBUFFER_UNDEFINED = GL_FRAMEBUFFER_UNDEFINED as isize
+}
+
+impl FrameBufferStatus {
+    /// The status for a `glCheckFramebufferStatus` result, `None` for 0 (the call failed) and
+    /// codes this crate does not know.
+    pub fn from_raw(status: GLenum) -> Option<FrameBufferStatus> {
+        match status {
+            GL_FRAMEBUFFER_COMPLETE => Some(FrameBufferStatus::FRAMEBUFFER_COMPLETE),
+            GL_FRAMEBUFFER_INCOMPLETE_ATTACHMENT => Some(FrameBufferStatus::FRAMEBUFFER_INCOMPLETE_ATTACHMENT),
+            GL_FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => {
+                Some(FrameBufferStatus::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT)
+            }
+            GL_FRAMEBUFFER_INCOMPLETE_DIMENSIONS => Some(FrameBufferStatus::FRAMEBUFFER_INCOMPLETE_DIMENSIONS),
+            GL_FRAMEBUFFER_UNSUPPORTED => Some(FrameBufferStatus::FRAMEBUFFER_UNSUPPORTED),
+            GL_FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => Some(FrameBufferStatus::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE),
+            GL_FRAMEBUFFER_UNDEFINED => Some(FrameBufferStatus::FRAMEBUFFER_UNDEFINED),
+            _ => None
         }
     }
 }
@@ -198,6 +209,47 @@ pub enum ShaderType {
     VERTEX_SHADER = GL_VERTEX_SHADER as isize
 }
 
+/// The vendor formats `glShaderBinary` loads, each from the extension named after its suffix.
+/// Formats this crate does not know are `Other`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ShaderBinaryFormat {
+    SGX_BINARY_IMG,
+    NVIDIA_PLATFORM_BINARY_NV,
+    MALI_SHADER_BINARY_ARM,
+    SHADER_BINARY_VIV,
+    SHADER_BINARY_DMP,
+    GCCSO_SHADER_BINARY_FJ,
+    Other(GLenum)
+}
+
+impl ShaderBinaryFormat {
+    /// The format for a value of `SHADER_BINARY_FORMATS`.
+    pub fn from_raw(format: GLenum) -> ShaderBinaryFormat {
+        match format {
+            GL_SGX_BINARY_IMG => ShaderBinaryFormat::SGX_BINARY_IMG,
+            GL_NVIDIA_PLATFORM_BINARY_NV => ShaderBinaryFormat::NVIDIA_PLATFORM_BINARY_NV,
+            GL_MALI_SHADER_BINARY_ARM => ShaderBinaryFormat::MALI_SHADER_BINARY_ARM,
+            GL_SHADER_BINARY_VIV => ShaderBinaryFormat::SHADER_BINARY_VIV,
+            GL_SHADER_BINARY_DMP => ShaderBinaryFormat::SHADER_BINARY_DMP,
+            GL_GCCSO_SHADER_BINARY_FJ => ShaderBinaryFormat::GCCSO_SHADER_BINARY_FJ,
+            other => ShaderBinaryFormat::Other(other)
+        }
+    }
+
+    /// The GL value of the format, as passed to `glShaderBinary`.
+    pub fn as_raw(self) -> GLenum {
+        match self {
+            ShaderBinaryFormat::SGX_BINARY_IMG => GL_SGX_BINARY_IMG,
+            ShaderBinaryFormat::NVIDIA_PLATFORM_BINARY_NV => GL_NVIDIA_PLATFORM_BINARY_NV,
+            ShaderBinaryFormat::MALI_SHADER_BINARY_ARM => GL_MALI_SHADER_BINARY_ARM,
+            ShaderBinaryFormat::SHADER_BINARY_VIV => GL_SHADER_BINARY_VIV,
+            ShaderBinaryFormat::SHADER_BINARY_DMP => GL_SHADER_BINARY_DMP,
+            ShaderBinaryFormat::GCCSO_SHADER_BINARY_FJ => GL_GCCSO_SHADER_BINARY_FJ,
+            ShaderBinaryFormat::Other(format) => format
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum FaceMode {
     FRONT = GL_FRONT as isize,
@@ -388,11 +440,15 @@ pub enum StateType {
     MAX_VERTEX_UNIFORM_VECTORS = GL_MAX_VERTEX_UNIFORM_VECTORS as isize,
     MAX_VIEWPORT_DIMS = GL_MAX_VIEWPORT_DIMS as isize,
     NUM_COMPRESSED_TEXTURE_FORMATS = GL_NUM_COMPRESSED_TEXTURE_FORMATS as isize,
+    /// `GL_OES_get_program_binary` or ES 3.0.
+    NUM_PROGRAM_BINARY_FORMATS = GL_NUM_PROGRAM_BINARY_FORMATS as isize,
     NUM_SHADER_BINARY_FORMATS = GL_NUM_SHADER_BINARY_FORMATS as isize,
     PACK_ALIGNMENT = GL_PACK_ALIGNMENT as isize,
     POLYGON_OFFSET_FACTOR = GL_POLYGON_OFFSET_FACTOR as isize,
     POLYGON_OFFSET_FILL = GL_POLYGON_OFFSET_FILL as isize,
     POLYGON_OFFSET_UNITS = GL_POLYGON_OFFSET_UNITS as isize,
+    /// `GL_OES_get_program_binary` or ES 3.0.
+    PROGRAM_BINARY_FORMATS = GL_PROGRAM_BINARY_FORMATS as isize,
     RED_BITS = GL_RED_BITS as isize,
     RENDERBUFFER_BINDING = GL_RENDERBUFFER_BINDING as isize,
     SAMPLE_ALPHA_TO_COVERAGE = GL_SAMPLE_ALPHA_TO_COVERAGE as isize,
@@ -477,7 +533,9 @@ pub enum ProgramParamType {
     ACTIVE_ATTRIBUTES = GL_ACTIVE_ATTRIBUTES as isize,
     ACTIVE_ATTRIBUTE_MAX_LENGTH = GL_ACTIVE_ATTRIBUTE_MAX_LENGTH as isize,
     ACTIVE_UNIFORMS = GL_ACTIVE_UNIFORMS as isize,
-    ACTIVE_UNIFORM_MAX_LENGTH = GL_ACTIVE_UNIFORM_MAX_LENGTH as isize
+    ACTIVE_UNIFORM_MAX_LENGTH = GL_ACTIVE_UNIFORM_MAX_LENGTH as isize,
+    /// `GL_OES_get_program_binary` or ES 3.0.
+    PROGRAM_BINARY_LENGTH = GL_PROGRAM_BINARY_LENGTH as isize
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -529,6 +587,64 @@ pub enum TextureParamType {
     TEXTURE_WRAP_T = GL_TEXTURE_WRAP_T as isize
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TextureWrapMode {
+    REPEAT = GL_REPEAT as isize,
+    CLAMP_TO_EDGE = GL_CLAMP_TO_EDGE as isize,
+    MIRRORED_REPEAT = GL_MIRRORED_REPEAT as isize
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TextureMinFilter {
+    NEAREST = GL_NEAREST as isize,
+    LINEAR = GL_LINEAR as isize,
+    NEAREST_MIPMAP_NEAREST = GL_NEAREST_MIPMAP_NEAREST as isize,
+    LINEAR_MIPMAP_NEAREST = GL_LINEAR_MIPMAP_NEAREST as isize,
+    NEAREST_MIPMAP_LINEAR = GL_NEAREST_MIPMAP_LINEAR as isize,
+    LINEAR_MIPMAP_LINEAR = GL_LINEAR_MIPMAP_LINEAR as isize
+}
+
+impl TextureMinFilter {
+    /// Whether sampling reads levels other than the base level.
+    pub fn uses_mipmaps(self) -> bool {
+        !matches!(self, TextureMinFilter::NEAREST | TextureMinFilter::LINEAR)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TextureMagFilter {
+    NEAREST = GL_NEAREST as isize,
+    LINEAR = GL_LINEAR as isize
+}
+
+/// A `TextureParamType` together with a value of the matching type.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TextureParameter {
+    MagFilter(TextureMagFilter),
+    MinFilter(TextureMinFilter),
+    WrapS(TextureWrapMode),
+    WrapT(TextureWrapMode)
+}
+
+impl TextureParameter {
+    pub fn name(self) -> TextureParamType {
+        match self {
+            TextureParameter::MagFilter(_) => TextureParamType::TEXTURE_MAG_FILTER,
+            TextureParameter::MinFilter(_) => TextureParamType::TEXTURE_MIN_FILTER,
+            TextureParameter::WrapS(_) => TextureParamType::TEXTURE_WRAP_S,
+            TextureParameter::WrapT(_) => TextureParamType::TEXTURE_WRAP_T,
+        }
+    }
+
+    pub fn value(self) -> GLenum {
+        match self {
+            TextureParameter::MagFilter(filter) => filter as GLenum,
+            TextureParameter::MinFilter(filter) => filter as GLenum,
+            TextureParameter::WrapS(mode) | TextureParameter::WrapT(mode) => mode as GLenum,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum VertexAttributeParamType {
     VERTEX_ATTRIB_ARRAY_BUFFER_BINDING = GL_VERTEX_ATTRIB_ARRAY_BUFFER_BINDING as isize,
@@ -568,7 +684,10 @@ pub enum PixelFormat {
     DEPTH_COMPONENT16 = GL_DEPTH_COMPONENT16 as isize,
     STENCIL_INDEX8 = GL_STENCIL_INDEX8 as isize,
     LUMINANCE = GL_LUMINANCE as isize,
-    LUMINANCE_ALPHA = GL_LUMINANCE_ALPHA as isize
+    LUMINANCE_ALPHA = GL_LUMINANCE_ALPHA as isize,
+    // ES 3.0 and GL_EXT_texture_rg.
+    RED = GL_RED as isize,
+    RG = GL_RG as isize
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -576,7 +695,23 @@ pub enum PixelDataType {
     UNSIGNED_BYTE = GL_UNSIGNED_BYTE as isize,
     UNSIGNED_SHORT_5_6_5 = GL_UNSIGNED_SHORT_5_6_5 as isize,
     UNSIGNED_SHORT_4_4_4_4 = GL_UNSIGNED_SHORT_4_4_4_4 as isize,
-    UNSIGNED_SHORT_5_5_5_1 = GL_UNSIGNED_SHORT_5_5_5_1 as isize
+    UNSIGNED_SHORT_5_5_5_1 = GL_UNSIGNED_SHORT_5_5_5_1 as isize,
+    // GL_OES_texture_half_float, which has its own value for the type.
+    HALF_FLOAT_OES = GL_HALF_FLOAT_OES as isize,
+    // ES 3.0.
+    HALF_FLOAT = GL_HALF_FLOAT as isize,
+    FLOAT = GL_FLOAT as isize,
+    UNSIGNED_INT_2_10_10_10_REV = GL_UNSIGNED_INT_2_10_10_10_REV as isize,
+    UNSIGNED_INT_10F_11F_11F_REV = GL_UNSIGNED_INT_10F_11F_11F_REV as isize,
+    UNSIGNED_INT_5_9_9_9_REV = GL_UNSIGNED_INT_5_9_9_9_REV as isize
+}
+
+impl PixelDataType {
+    /// Whether texels of this type are unsigned bytes or packed into 16 bits, the ES 2.0 types.
+    pub fn is_8_bit_or_packed(self) -> bool {
+        matches!(self, PixelDataType::UNSIGNED_BYTE | PixelDataType::UNSIGNED_SHORT_5_6_5 |
+                       PixelDataType::UNSIGNED_SHORT_4_4_4_4 | PixelDataType::UNSIGNED_SHORT_5_5_5_1)
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -0,0 +1,1543 @@
+// The safety contract is the same for every entry point and is documented once on `Backend`.
+#![allow(clippy::missing_safety_doc)]
+
+use types::*;
+
+use super::ffi;
+
+/// The set of GL entry points a `Wrapper` dispatches to.
+///
+/// Every method mirrors the function of the same name in `ffi`, taking the raw GL types and
+/// pointers. `NativeBackend` forwards to the driver; other implementations can render in
+/// software or replay into a different context.
+///
+/// # Safety
+///
+/// Pointer arguments follow the C API: they must be valid for the amount of memory GL reads or
+/// writes for the given arguments and the current state.
+pub trait Backend {
+    unsafe fn glActiveTexture(&mut self, texture: GLenum);
+
+    unsafe fn glAttachShader(&mut self, program: GLuint, shader: GLuint);
+
+    unsafe fn glBindAttribLocation(&mut self, program: GLuint, index: GLuint, name: *const GLchar);
+
+    unsafe fn glBindBuffer(&mut self, target: GLenum, buffer: GLuint);
+
+    unsafe fn glBindFramebuffer(&mut self, target: GLenum, framebuffer: GLuint);
+
+    unsafe fn glBindRenderbuffer(&mut self, target: GLenum, renderbuffer: GLuint);
+
+    unsafe fn glBindTexture(&mut self, target: GLenum, texture: GLuint);
+
+    unsafe fn glBlendColor(
+        &mut self,
+        red: GLclampf,
+        green: GLclampf,
+        blue: GLclampf,
+        alpha: GLclampf,
+    );
+
+    unsafe fn glBlendEquation(&mut self, mode: GLenum);
+
+    unsafe fn glBlendEquationSeparate(&mut self, modeRGB: GLenum, modeAlpha: GLenum);
+
+    unsafe fn glBlendFunc(&mut self, sfactor: GLenum, dfactor: GLenum);
+
+    unsafe fn glBlendFuncSeparate(
+        &mut self,
+        srcRGB: GLenum,
+        dstRGB: GLenum,
+        srcAlpha: GLenum,
+        dstAlpha: GLenum,
+    );
+
+    unsafe fn glBufferData(
+        &mut self,
+        target: GLenum,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+        usage: GLenum,
+    );
+
+    unsafe fn glBufferSubData(
+        &mut self,
+        target: GLenum,
+        offset: GLintptr,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+    );
+
+    unsafe fn glCheckFramebufferStatus(&mut self, target: GLenum) -> GLenum;
+
+    unsafe fn glClear(&mut self, mask: GLbitfield);
+
+    unsafe fn glClearColor(
+        &mut self,
+        red: GLclampf,
+        green: GLclampf,
+        blue: GLclampf,
+        alpha: GLclampf,
+    );
+
+    unsafe fn glClearDepthf(&mut self, depth: GLclampf);
+
+    unsafe fn glClearStencil(&mut self, s: GLint);
+
+    unsafe fn glColorMask(
+        &mut self,
+        red: GLboolean,
+        green: GLboolean,
+        blue: GLboolean,
+        alpha: GLboolean,
+    );
+
+    unsafe fn glCompileShader(&mut self, shader: GLuint);
+
+    unsafe fn glCompressedTexImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        imageSize: GLsizei,
+        data: *const GLvoid,
+    );
+
+    unsafe fn glCompressedTexSubImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        imageSize: GLsizei,
+        data: *const GLvoid,
+    );
+
+    unsafe fn glCopyTexImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        internalformat: GLenum,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+    );
+
+    unsafe fn glCopyTexSubImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    );
+
+    unsafe fn glCreateProgram(&mut self) -> GLuint;
+
+    unsafe fn glCreateShader(&mut self, type_: GLenum) -> GLuint;
+
+    unsafe fn glCullFace(&mut self, mode: GLenum);
+
+    unsafe fn glDeleteBuffers(&mut self, n: GLsizei, buffers: *const GLuint);
+
+    unsafe fn glDeleteFramebuffers(&mut self, n: GLsizei, framebuffers: *const GLuint);
+
+    unsafe fn glDeleteProgram(&mut self, program: GLuint);
+
+    unsafe fn glDeleteRenderbuffers(&mut self, n: GLsizei, renderbuffers: *const GLuint);
+
+    unsafe fn glDeleteShader(&mut self, shader: GLuint);
+
+    unsafe fn glDeleteTextures(&mut self, n: GLsizei, textures: *const GLuint);
+
+    unsafe fn glDepthFunc(&mut self, func: GLenum);
+
+    unsafe fn glDepthMask(&mut self, flag: GLboolean);
+
+    unsafe fn glDepthRangef(&mut self, zNear: GLclampf, zFar: GLclampf);
+
+    unsafe fn glDetachShader(&mut self, program: GLuint, shader: GLuint);
+
+    unsafe fn glDisable(&mut self, cap: GLenum);
+
+    unsafe fn glDisableVertexAttribArray(&mut self, index: GLuint);
+
+    unsafe fn glDrawArrays(&mut self, mode: GLenum, first: GLint, count: GLsizei);
+
+    unsafe fn glDrawElements(
+        &mut self,
+        mode: GLenum,
+        count: GLsizei,
+        type_: GLenum,
+        indices: *const GLvoid,
+    );
+
+    unsafe fn glEnable(&mut self, cap: GLenum);
+
+    unsafe fn glEnableVertexAttribArray(&mut self, index: GLuint);
+
+    unsafe fn glFinish(&mut self);
+
+    unsafe fn glFlush(&mut self);
+
+    unsafe fn glFramebufferRenderbuffer(
+        &mut self,
+        target: GLenum,
+        attachment: GLenum,
+        renderbuffertarget: GLenum,
+        renderbuffer: GLuint,
+    );
+
+    unsafe fn glFramebufferTexture2D(
+        &mut self,
+        target: GLenum,
+        attachment: GLenum,
+        textarget: GLenum,
+        texture: GLuint,
+        level: GLint,
+    );
+
+    unsafe fn glFrontFace(&mut self, mode: GLenum);
+
+    unsafe fn glGenBuffers(&mut self, n: GLsizei, buffers: *mut GLuint);
+
+    unsafe fn glGenerateMipmap(&mut self, target: GLenum);
+
+    unsafe fn glGenFramebuffers(&mut self, n: GLsizei, framebuffers: *mut GLuint);
+
+    unsafe fn glGenRenderbuffers(&mut self, n: GLsizei, renderbuffers: *mut GLuint);
+
+    unsafe fn glGenTextures(&mut self, n: GLsizei, textures: *mut GLuint);
+
+    unsafe fn glGetActiveAttrib(
+        &mut self,
+        program: GLuint,
+        index: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        size: *mut GLint,
+        type_: *mut GLenum,
+        name: *mut GLchar,
+    );
+
+    unsafe fn glGetActiveUniform(
+        &mut self,
+        program: GLuint,
+        index: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        size: *mut GLint,
+        type_: *mut GLenum,
+        name: *mut GLchar,
+    );
+
+    unsafe fn glGetAttachedShaders(
+        &mut self,
+        program: GLuint,
+        maxcount: GLsizei,
+        count: *mut GLsizei,
+        shaders: *mut GLuint,
+    );
+
+    unsafe fn glGetAttribLocation(&mut self, program: GLuint, name: *const GLchar) -> GLint;
+
+    unsafe fn glGetBooleanv(&mut self, pname: GLenum, params: *mut GLboolean);
+
+    unsafe fn glGetBufferParameteriv(&mut self, target: GLenum, pname: GLenum, params: *mut GLint);
+
+    unsafe fn glGetError(&mut self) -> GLenum;
+
+    unsafe fn glGetFloatv(&mut self, pname: GLenum, params: *mut GLfloat);
+
+    unsafe fn glGetFramebufferAttachmentParameteriv(
+        &mut self,
+        target: GLenum,
+        attachment: GLenum,
+        pname: GLenum,
+        params: *mut GLint,
+    );
+
+    unsafe fn glGetIntegerv(&mut self, pname: GLenum, params: *mut GLint);
+
+    unsafe fn glGetProgramiv(&mut self, program: GLuint, pname: GLenum, params: *mut GLint);
+
+    unsafe fn glGetProgramInfoLog(
+        &mut self,
+        program: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        infolog: *mut GLchar,
+    );
+
+    unsafe fn glGetRenderbufferParameteriv(
+        &mut self,
+        target: GLenum,
+        pname: GLenum,
+        params: *mut GLint,
+    );
+
+    unsafe fn glGetShaderiv(&mut self, shader: GLuint, pname: GLenum, params: *mut GLint);
+
+    unsafe fn glGetShaderInfoLog(
+        &mut self,
+        shader: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        infolog: *mut GLchar,
+    );
+
+    unsafe fn glGetShaderPrecisionFormat(
+        &mut self,
+        shadertype: GLenum,
+        precisiontype: GLenum,
+        range: *mut GLint,
+        precision: *mut GLint,
+    );
+
+    unsafe fn glGetShaderSource(
+        &mut self,
+        shader: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        source: *mut GLchar,
+    );
+
+    unsafe fn glGetString(&mut self, name: GLenum) -> *const GLubyte;
+
+    unsafe fn glGetTexParameterfv(&mut self, target: GLenum, pname: GLenum, params: *mut GLfloat);
+
+    unsafe fn glGetTexParameteriv(&mut self, target: GLenum, pname: GLenum, params: *mut GLint);
+
+    unsafe fn glGetUniformfv(&mut self, program: GLuint, location: GLint, params: *mut GLfloat);
+
+    unsafe fn glGetUniformiv(&mut self, program: GLuint, location: GLint, params: *mut GLint);
+
+    unsafe fn glGetUniformLocation(&mut self, program: GLuint, name: *const GLchar) -> GLint;
+
+    unsafe fn glGetVertexAttribfv(&mut self, index: GLuint, pname: GLenum, params: *mut GLfloat);
+
+    unsafe fn glGetVertexAttribiv(&mut self, index: GLuint, pname: GLenum, params: *mut GLint);
+
+    unsafe fn glGetVertexAttribPointerv(
+        &mut self,
+        index: GLuint,
+        pname: GLenum,
+        pointer: *mut *mut GLvoid,
+    );
+
+    unsafe fn glHint(&mut self, target: GLenum, mode: GLenum);
+
+    unsafe fn glIsBuffer(&mut self, buffer: GLuint) -> GLboolean;
+
+    unsafe fn glIsEnabled(&mut self, cap: GLenum) -> GLboolean;
+
+    unsafe fn glIsFramebuffer(&mut self, framebuffer: GLuint) -> GLboolean;
+
+    unsafe fn glIsProgram(&mut self, program: GLuint) -> GLboolean;
+
+    unsafe fn glIsRenderbuffer(&mut self, renderbuffer: GLuint) -> GLboolean;
+
+    unsafe fn glIsShader(&mut self, shader: GLuint) -> GLboolean;
+
+    unsafe fn glIsTexture(&mut self, texture: GLuint) -> GLboolean;
+
+    unsafe fn glLineWidth(&mut self, width: GLfloat);
+
+    unsafe fn glLinkProgram(&mut self, program: GLuint);
+
+    unsafe fn glPixelStorei(&mut self, pname: GLenum, param: GLint);
+
+    unsafe fn glPolygonOffset(&mut self, factor: GLfloat, units: GLfloat);
+
+    unsafe fn glReadPixels(
+        &mut self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *mut GLvoid,
+    );
+
+    unsafe fn glReleaseShaderCompiler(&mut self);
+
+    unsafe fn glRenderbufferStorage(
+        &mut self,
+        target: GLenum,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    );
+
+    unsafe fn glSampleCoverage(&mut self, value: GLclampf, invert: GLboolean);
+
+    unsafe fn glScissor(&mut self, x: GLint, y: GLint, width: GLsizei, height: GLsizei);
+
+    unsafe fn glShaderBinary(
+        &mut self,
+        n: GLsizei,
+        shaders: *const GLuint,
+        binaryformat: GLenum,
+        binary: *const GLvoid,
+        length: GLsizei,
+    );
+
+    unsafe fn glShaderSource(
+        &mut self,
+        shader: GLuint,
+        count: GLsizei,
+        string: *const *const GLchar,
+        length: *const GLint,
+    );
+
+    unsafe fn glStencilFunc(&mut self, func: GLenum, ref_: GLint, mask: GLuint);
+
+    unsafe fn glStencilFuncSeparate(
+        &mut self,
+        face: GLenum,
+        func: GLenum,
+        ref_: GLint,
+        mask: GLuint,
+    );
+
+    unsafe fn glStencilMask(&mut self, mask: GLuint);
+
+    unsafe fn glStencilMaskSeparate(&mut self, face: GLenum, mask: GLuint);
+
+    unsafe fn glStencilOp(&mut self, fail: GLenum, zfail: GLenum, zpass: GLenum);
+
+    unsafe fn glStencilOpSeparate(
+        &mut self,
+        face: GLenum,
+        fail: GLenum,
+        zfail: GLenum,
+        zpass: GLenum,
+    );
+
+    unsafe fn glTexImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        internalformat: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *const GLvoid,
+    );
+
+    unsafe fn glTexParameterf(&mut self, target: GLenum, pname: GLenum, param: GLfloat);
+
+    unsafe fn glTexParameterfv(&mut self, target: GLenum, pname: GLenum, params: *const GLfloat);
+
+    unsafe fn glTexParameteri(&mut self, target: GLenum, pname: GLenum, param: GLint);
+
+    unsafe fn glTexParameteriv(&mut self, target: GLenum, pname: GLenum, params: *const GLint);
+
+    unsafe fn glTexSubImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *const GLvoid,
+    );
+
+    unsafe fn glUniform1f(&mut self, location: GLint, x: GLfloat);
+
+    unsafe fn glUniform1fv(&mut self, location: GLint, count: GLsizei, v: *const GLfloat);
+
+    unsafe fn glUniform1i(&mut self, location: GLint, x: GLint);
+
+    unsafe fn glUniform1iv(&mut self, location: GLint, count: GLsizei, v: *const GLint);
+
+    unsafe fn glUniform2f(&mut self, location: GLint, x: GLfloat, y: GLfloat);
+
+    unsafe fn glUniform2fv(&mut self, location: GLint, count: GLsizei, v: *const GLfloat);
+
+    unsafe fn glUniform2i(&mut self, location: GLint, x: GLint, y: GLint);
+
+    unsafe fn glUniform2iv(&mut self, location: GLint, count: GLsizei, v: *const GLint);
+
+    unsafe fn glUniform3f(&mut self, location: GLint, x: GLfloat, y: GLfloat, z: GLfloat);
+
+    unsafe fn glUniform3fv(&mut self, location: GLint, count: GLsizei, v: *const GLfloat);
+
+    unsafe fn glUniform3i(&mut self, location: GLint, x: GLint, y: GLint, z: GLint);
+
+    unsafe fn glUniform3iv(&mut self, location: GLint, count: GLsizei, v: *const GLint);
+
+    unsafe fn glUniform4f(
+        &mut self,
+        location: GLint,
+        x: GLfloat,
+        y: GLfloat,
+        z: GLfloat,
+        w: GLfloat,
+    );
+
+    unsafe fn glUniform4fv(&mut self, location: GLint, count: GLsizei, v: *const GLfloat);
+
+    unsafe fn glUniform4i(&mut self, location: GLint, x: GLint, y: GLint, z: GLint, w: GLint);
+
+    unsafe fn glUniform4iv(&mut self, location: GLint, count: GLsizei, v: *const GLint);
+
+    unsafe fn glUniformMatrix2fv(
+        &mut self,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    );
+
+    unsafe fn glUniformMatrix3fv(
+        &mut self,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    );
+
+    unsafe fn glUniformMatrix4fv(
+        &mut self,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    );
+
+    unsafe fn glUseProgram(&mut self, program: GLuint);
+
+    unsafe fn glValidateProgram(&mut self, program: GLuint);
+
+    unsafe fn glVertexAttrib1f(&mut self, indx: GLuint, x: GLfloat);
+
+    unsafe fn glVertexAttrib1fv(&mut self, indx: GLuint, values: *const GLfloat);
+
+    unsafe fn glVertexAttrib2f(&mut self, indx: GLuint, x: GLfloat, y: GLfloat);
+
+    unsafe fn glVertexAttrib2fv(&mut self, indx: GLuint, values: *const GLfloat);
+
+    unsafe fn glVertexAttrib3f(&mut self, indx: GLuint, x: GLfloat, y: GLfloat, z: GLfloat);
+
+    unsafe fn glVertexAttrib3fv(&mut self, indx: GLuint, values: *const GLfloat);
+
+    unsafe fn glVertexAttrib4f(
+        &mut self,
+        indx: GLuint,
+        x: GLfloat,
+        y: GLfloat,
+        z: GLfloat,
+        w: GLfloat,
+    );
+
+    unsafe fn glVertexAttrib4fv(&mut self, indx: GLuint, values: *const GLfloat);
+
+    unsafe fn glVertexAttribPointer(
+        &mut self,
+        indx: GLuint,
+        size: GLint,
+        type_: GLenum,
+        normalized: GLboolean,
+        stride: GLsizei,
+        ptr: *const GLvoid,
+    );
+
+    unsafe fn glViewport(&mut self, x: GLint, y: GLint, width: GLsizei, height: GLsizei);
+
+    /// Whether `name`, a function beyond ES 2.0 core, can be called. Calling one that cannot does
+    /// nothing.
+    fn has_entry_point(&self, name: &str) -> bool {
+        let _ = name;
+        true
+    }
+
+    unsafe fn glGetProgramBinary(
+        &mut self,
+        program: GLuint,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        binaryFormat: *mut GLenum,
+        binary: *mut GLvoid,
+    );
+
+    unsafe fn glProgramBinary(
+        &mut self,
+        program: GLuint,
+        binaryFormat: GLenum,
+        binary: *const GLvoid,
+        length: GLsizei,
+    );
+
+    unsafe fn glGetProgramBinaryOES(
+        &mut self,
+        program: GLuint,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        binaryFormat: *mut GLenum,
+        binary: *mut GLvoid,
+    );
+
+    unsafe fn glProgramBinaryOES(
+        &mut self,
+        program: GLuint,
+        binaryFormat: GLenum,
+        binary: *const GLvoid,
+        length: GLsizei,
+    );
+
+    unsafe fn glBindVertexArray(&mut self, array: GLuint);
+
+    unsafe fn glDeleteVertexArrays(&mut self, n: GLsizei, arrays: *const GLuint);
+
+    unsafe fn glGenVertexArrays(&mut self, n: GLsizei, arrays: *mut GLuint);
+
+    unsafe fn glBindVertexArrayOES(&mut self, array: GLuint);
+
+    unsafe fn glDeleteVertexArraysOES(&mut self, n: GLsizei, arrays: *const GLuint);
+
+    unsafe fn glGenVertexArraysOES(&mut self, n: GLsizei, arrays: *mut GLuint);
+}
+
+/// Dispatches straight to the platform GL library.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NativeBackend;
+
+impl Backend for NativeBackend {
+    fn has_entry_point(&self, name: &str) -> bool {
+        ffi::entry_points().has(name)
+    }
+
+    unsafe fn glActiveTexture(&mut self, texture: GLenum) {
+        ffi::glActiveTexture(texture)
+    }
+
+    unsafe fn glAttachShader(&mut self, program: GLuint, shader: GLuint) {
+        ffi::glAttachShader(program, shader)
+    }
+
+    unsafe fn glBindAttribLocation(&mut self, program: GLuint, index: GLuint, name: *const GLchar) {
+        ffi::glBindAttribLocation(program, index, name)
+    }
+
+    unsafe fn glBindBuffer(&mut self, target: GLenum, buffer: GLuint) {
+        ffi::glBindBuffer(target, buffer)
+    }
+
+    unsafe fn glBindFramebuffer(&mut self, target: GLenum, framebuffer: GLuint) {
+        ffi::glBindFramebuffer(target, framebuffer)
+    }
+
+    unsafe fn glBindRenderbuffer(&mut self, target: GLenum, renderbuffer: GLuint) {
+        ffi::glBindRenderbuffer(target, renderbuffer)
+    }
+
+    unsafe fn glBindTexture(&mut self, target: GLenum, texture: GLuint) {
+        ffi::glBindTexture(target, texture)
+    }
+
+    unsafe fn glBlendColor(
+        &mut self,
+        red: GLclampf,
+        green: GLclampf,
+        blue: GLclampf,
+        alpha: GLclampf,
+    ) {
+        ffi::glBlendColor(red, green, blue, alpha)
+    }
+
+    unsafe fn glBlendEquation(&mut self, mode: GLenum) {
+        ffi::glBlendEquation(mode)
+    }
+
+    unsafe fn glBlendEquationSeparate(&mut self, modeRGB: GLenum, modeAlpha: GLenum) {
+        ffi::glBlendEquationSeparate(modeRGB, modeAlpha)
+    }
+
+    unsafe fn glBlendFunc(&mut self, sfactor: GLenum, dfactor: GLenum) {
+        ffi::glBlendFunc(sfactor, dfactor)
+    }
+
+    unsafe fn glBlendFuncSeparate(
+        &mut self,
+        srcRGB: GLenum,
+        dstRGB: GLenum,
+        srcAlpha: GLenum,
+        dstAlpha: GLenum,
+    ) {
+        ffi::glBlendFuncSeparate(srcRGB, dstRGB, srcAlpha, dstAlpha)
+    }
+
+    unsafe fn glBufferData(
+        &mut self,
+        target: GLenum,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+        usage: GLenum,
+    ) {
+        ffi::glBufferData(target, size, data, usage)
+    }
+
+    unsafe fn glBufferSubData(
+        &mut self,
+        target: GLenum,
+        offset: GLintptr,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+    ) {
+        ffi::glBufferSubData(target, offset, size, data)
+    }
+
+    unsafe fn glCheckFramebufferStatus(&mut self, target: GLenum) -> GLenum {
+        ffi::glCheckFramebufferStatus(target)
+    }
+
+    unsafe fn glClear(&mut self, mask: GLbitfield) {
+        ffi::glClear(mask)
+    }
+
+    unsafe fn glClearColor(
+        &mut self,
+        red: GLclampf,
+        green: GLclampf,
+        blue: GLclampf,
+        alpha: GLclampf,
+    ) {
+        ffi::glClearColor(red, green, blue, alpha)
+    }
+
+    unsafe fn glClearDepthf(&mut self, depth: GLclampf) {
+        ffi::glClearDepthf(depth)
+    }
+
+    unsafe fn glClearStencil(&mut self, s: GLint) {
+        ffi::glClearStencil(s)
+    }
+
+    unsafe fn glColorMask(
+        &mut self,
+        red: GLboolean,
+        green: GLboolean,
+        blue: GLboolean,
+        alpha: GLboolean,
+    ) {
+        ffi::glColorMask(red, green, blue, alpha)
+    }
+
+    unsafe fn glCompileShader(&mut self, shader: GLuint) {
+        ffi::glCompileShader(shader)
+    }
+
+    unsafe fn glCompressedTexImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        imageSize: GLsizei,
+        data: *const GLvoid,
+    ) {
+        ffi::glCompressedTexImage2D(target, level, internalformat, width, height, border, imageSize, data)
+    }
+
+    unsafe fn glCompressedTexSubImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        imageSize: GLsizei,
+        data: *const GLvoid,
+    ) {
+        ffi::glCompressedTexSubImage2D(target, level, xoffset, yoffset, width, height, format, imageSize, data)
+    }
+
+    unsafe fn glCopyTexImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        internalformat: GLenum,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+    ) {
+        ffi::glCopyTexImage2D(target, level, internalformat, x, y, width, height, border)
+    }
+
+    unsafe fn glCopyTexSubImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        ffi::glCopyTexSubImage2D(target, level, xoffset, yoffset, x, y, width, height)
+    }
+
+    unsafe fn glCreateProgram(&mut self) -> GLuint {
+        ffi::glCreateProgram()
+    }
+
+    unsafe fn glCreateShader(&mut self, type_: GLenum) -> GLuint {
+        ffi::glCreateShader(type_)
+    }
+
+    unsafe fn glCullFace(&mut self, mode: GLenum) {
+        ffi::glCullFace(mode)
+    }
+
+    unsafe fn glDeleteBuffers(&mut self, n: GLsizei, buffers: *const GLuint) {
+        ffi::glDeleteBuffers(n, buffers)
+    }
+
+    unsafe fn glDeleteFramebuffers(&mut self, n: GLsizei, framebuffers: *const GLuint) {
+        ffi::glDeleteFramebuffers(n, framebuffers)
+    }
+
+    unsafe fn glDeleteProgram(&mut self, program: GLuint) {
+        ffi::glDeleteProgram(program)
+    }
+
+    unsafe fn glDeleteRenderbuffers(&mut self, n: GLsizei, renderbuffers: *const GLuint) {
+        ffi::glDeleteRenderbuffers(n, renderbuffers)
+    }
+
+    unsafe fn glDeleteShader(&mut self, shader: GLuint) {
+        ffi::glDeleteShader(shader)
+    }
+
+    unsafe fn glDeleteTextures(&mut self, n: GLsizei, textures: *const GLuint) {
+        ffi::glDeleteTextures(n, textures)
+    }
+
+    unsafe fn glDepthFunc(&mut self, func: GLenum) {
+        ffi::glDepthFunc(func)
+    }
+
+    unsafe fn glDepthMask(&mut self, flag: GLboolean) {
+        ffi::glDepthMask(flag)
+    }
+
+    unsafe fn glDepthRangef(&mut self, zNear: GLclampf, zFar: GLclampf) {
+        ffi::glDepthRangef(zNear, zFar)
+    }
+
+    unsafe fn glDetachShader(&mut self, program: GLuint, shader: GLuint) {
+        ffi::glDetachShader(program, shader)
+    }
+
+    unsafe fn glDisable(&mut self, cap: GLenum) {
+        ffi::glDisable(cap)
+    }
+
+    unsafe fn glDisableVertexAttribArray(&mut self, index: GLuint) {
+        ffi::glDisableVertexAttribArray(index)
+    }
+
+    unsafe fn glDrawArrays(&mut self, mode: GLenum, first: GLint, count: GLsizei) {
+        ffi::glDrawArrays(mode, first, count)
+    }
+
+    unsafe fn glDrawElements(
+        &mut self,
+        mode: GLenum,
+        count: GLsizei,
+        type_: GLenum,
+        indices: *const GLvoid,
+    ) {
+        ffi::glDrawElements(mode, count, type_, indices)
+    }
+
+    unsafe fn glEnable(&mut self, cap: GLenum) {
+        ffi::glEnable(cap)
+    }
+
+    unsafe fn glEnableVertexAttribArray(&mut self, index: GLuint) {
+        ffi::glEnableVertexAttribArray(index)
+    }
+
+    unsafe fn glFinish(&mut self) {
+        ffi::glFinish()
+    }
+
+    unsafe fn glFlush(&mut self) {
+        ffi::glFlush()
+    }
+
+    unsafe fn glFramebufferRenderbuffer(
+        &mut self,
+        target: GLenum,
+        attachment: GLenum,
+        renderbuffertarget: GLenum,
+        renderbuffer: GLuint,
+    ) {
+        ffi::glFramebufferRenderbuffer(target, attachment, renderbuffertarget, renderbuffer)
+    }
+
+    unsafe fn glFramebufferTexture2D(
+        &mut self,
+        target: GLenum,
+        attachment: GLenum,
+        textarget: GLenum,
+        texture: GLuint,
+        level: GLint,
+    ) {
+        ffi::glFramebufferTexture2D(target, attachment, textarget, texture, level)
+    }
+
+    unsafe fn glFrontFace(&mut self, mode: GLenum) {
+        ffi::glFrontFace(mode)
+    }
+
+    unsafe fn glGenBuffers(&mut self, n: GLsizei, buffers: *mut GLuint) {
+        ffi::glGenBuffers(n, buffers)
+    }
+
+    unsafe fn glGenerateMipmap(&mut self, target: GLenum) {
+        ffi::glGenerateMipmap(target)
+    }
+
+    unsafe fn glGenFramebuffers(&mut self, n: GLsizei, framebuffers: *mut GLuint) {
+        ffi::glGenFramebuffers(n, framebuffers)
+    }
+
+    unsafe fn glGenRenderbuffers(&mut self, n: GLsizei, renderbuffers: *mut GLuint) {
+        ffi::glGenRenderbuffers(n, renderbuffers)
+    }
+
+    unsafe fn glGenTextures(&mut self, n: GLsizei, textures: *mut GLuint) {
+        ffi::glGenTextures(n, textures)
+    }
+
+    unsafe fn glGetActiveAttrib(
+        &mut self,
+        program: GLuint,
+        index: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        size: *mut GLint,
+        type_: *mut GLenum,
+        name: *mut GLchar,
+    ) {
+        ffi::glGetActiveAttrib(program, index, bufsize, length, size, type_, name)
+    }
+
+    unsafe fn glGetActiveUniform(
+        &mut self,
+        program: GLuint,
+        index: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        size: *mut GLint,
+        type_: *mut GLenum,
+        name: *mut GLchar,
+    ) {
+        ffi::glGetActiveUniform(program, index, bufsize, length, size, type_, name)
+    }
+
+    unsafe fn glGetAttachedShaders(
+        &mut self,
+        program: GLuint,
+        maxcount: GLsizei,
+        count: *mut GLsizei,
+        shaders: *mut GLuint,
+    ) {
+        ffi::glGetAttachedShaders(program, maxcount, count, shaders)
+    }
+
+    unsafe fn glGetAttribLocation(&mut self, program: GLuint, name: *const GLchar) -> GLint {
+        ffi::glGetAttribLocation(program, name)
+    }
+
+    unsafe fn glGetBooleanv(&mut self, pname: GLenum, params: *mut GLboolean) {
+        ffi::glGetBooleanv(pname, params)
+    }
+
+    unsafe fn glGetBufferParameteriv(&mut self, target: GLenum, pname: GLenum, params: *mut GLint) {
+        ffi::glGetBufferParameteriv(target, pname, params)
+    }
+
+    unsafe fn glGetError(&mut self) -> GLenum {
+        ffi::glGetError()
+    }
+
+    unsafe fn glGetFloatv(&mut self, pname: GLenum, params: *mut GLfloat) {
+        ffi::glGetFloatv(pname, params)
+    }
+
+    unsafe fn glGetFramebufferAttachmentParameteriv(
+        &mut self,
+        target: GLenum,
+        attachment: GLenum,
+        pname: GLenum,
+        params: *mut GLint,
+    ) {
+        ffi::glGetFramebufferAttachmentParameteriv(target, attachment, pname, params)
+    }
+
+    unsafe fn glGetIntegerv(&mut self, pname: GLenum, params: *mut GLint) {
+        ffi::glGetIntegerv(pname, params)
+    }
+
+    unsafe fn glGetProgramiv(&mut self, program: GLuint, pname: GLenum, params: *mut GLint) {
+        ffi::glGetProgramiv(program, pname, params)
+    }
+
+    unsafe fn glGetProgramInfoLog(
+        &mut self,
+        program: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        infolog: *mut GLchar,
+    ) {
+        ffi::glGetProgramInfoLog(program, bufsize, length, infolog)
+    }
+
+    unsafe fn glGetRenderbufferParameteriv(
+        &mut self,
+        target: GLenum,
+        pname: GLenum,
+        params: *mut GLint,
+    ) {
+        ffi::glGetRenderbufferParameteriv(target, pname, params)
+    }
+
+    unsafe fn glGetShaderiv(&mut self, shader: GLuint, pname: GLenum, params: *mut GLint) {
+        ffi::glGetShaderiv(shader, pname, params)
+    }
+
+    unsafe fn glGetShaderInfoLog(
+        &mut self,
+        shader: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        infolog: *mut GLchar,
+    ) {
+        ffi::glGetShaderInfoLog(shader, bufsize, length, infolog)
+    }
+
+    unsafe fn glGetShaderPrecisionFormat(
+        &mut self,
+        shadertype: GLenum,
+        precisiontype: GLenum,
+        range: *mut GLint,
+        precision: *mut GLint,
+    ) {
+        ffi::glGetShaderPrecisionFormat(shadertype, precisiontype, range, precision)
+    }
+
+    unsafe fn glGetShaderSource(
+        &mut self,
+        shader: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        source: *mut GLchar,
+    ) {
+        ffi::glGetShaderSource(shader, bufsize, length, source)
+    }
+
+    unsafe fn glGetString(&mut self, name: GLenum) -> *const GLubyte {
+        ffi::glGetString(name)
+    }
+
+    unsafe fn glGetTexParameterfv(&mut self, target: GLenum, pname: GLenum, params: *mut GLfloat) {
+        ffi::glGetTexParameterfv(target, pname, params)
+    }
+
+    unsafe fn glGetTexParameteriv(&mut self, target: GLenum, pname: GLenum, params: *mut GLint) {
+        ffi::glGetTexParameteriv(target, pname, params)
+    }
+
+    unsafe fn glGetUniformfv(&mut self, program: GLuint, location: GLint, params: *mut GLfloat) {
+        ffi::glGetUniformfv(program, location, params)
+    }
+
+    unsafe fn glGetUniformiv(&mut self, program: GLuint, location: GLint, params: *mut GLint) {
+        ffi::glGetUniformiv(program, location, params)
+    }
+
+    unsafe fn glGetUniformLocation(&mut self, program: GLuint, name: *const GLchar) -> GLint {
+        ffi::glGetUniformLocation(program, name)
+    }
+
+    unsafe fn glGetVertexAttribfv(&mut self, index: GLuint, pname: GLenum, params: *mut GLfloat) {
+        ffi::glGetVertexAttribfv(index, pname, params)
+    }
+
+    unsafe fn glGetVertexAttribiv(&mut self, index: GLuint, pname: GLenum, params: *mut GLint) {
+        ffi::glGetVertexAttribiv(index, pname, params)
+    }
+
+    unsafe fn glGetVertexAttribPointerv(
+        &mut self,
+        index: GLuint,
+        pname: GLenum,
+        pointer: *mut *mut GLvoid,
+    ) {
+        ffi::glGetVertexAttribPointerv(index, pname, pointer)
+    }
+
+    unsafe fn glHint(&mut self, target: GLenum, mode: GLenum) {
+        ffi::glHint(target, mode)
+    }
+
+    unsafe fn glIsBuffer(&mut self, buffer: GLuint) -> GLboolean {
+        ffi::glIsBuffer(buffer)
+    }
+
+    unsafe fn glIsEnabled(&mut self, cap: GLenum) -> GLboolean {
+        ffi::glIsEnabled(cap)
+    }
+
+    unsafe fn glIsFramebuffer(&mut self, framebuffer: GLuint) -> GLboolean {
+        ffi::glIsFramebuffer(framebuffer)
+    }
+
+    unsafe fn glIsProgram(&mut self, program: GLuint) -> GLboolean {
+        ffi::glIsProgram(program)
+    }
+
+    unsafe fn glIsRenderbuffer(&mut self, renderbuffer: GLuint) -> GLboolean {
+        ffi::glIsRenderbuffer(renderbuffer)
+    }
+
+    unsafe fn glIsShader(&mut self, shader: GLuint) -> GLboolean {
+        ffi::glIsShader(shader)
+    }
+
+    unsafe fn glIsTexture(&mut self, texture: GLuint) -> GLboolean {
+        ffi::glIsTexture(texture)
+    }
+
+    unsafe fn glLineWidth(&mut self, width: GLfloat) {
+        ffi::glLineWidth(width)
+    }
+
+    unsafe fn glLinkProgram(&mut self, program: GLuint) {
+        ffi::glLinkProgram(program)
+    }
+
+    unsafe fn glPixelStorei(&mut self, pname: GLenum, param: GLint) {
+        ffi::glPixelStorei(pname, param)
+    }
+
+    unsafe fn glPolygonOffset(&mut self, factor: GLfloat, units: GLfloat) {
+        ffi::glPolygonOffset(factor, units)
+    }
+
+    unsafe fn glReadPixels(
+        &mut self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *mut GLvoid,
+    ) {
+        ffi::glReadPixels(x, y, width, height, format, type_, pixels)
+    }
+
+    unsafe fn glReleaseShaderCompiler(&mut self) {
+        ffi::glReleaseShaderCompiler()
+    }
+
+    unsafe fn glRenderbufferStorage(
+        &mut self,
+        target: GLenum,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        ffi::glRenderbufferStorage(target, internalformat, width, height)
+    }
+
+    unsafe fn glSampleCoverage(&mut self, value: GLclampf, invert: GLboolean) {
+        ffi::glSampleCoverage(value, invert)
+    }
+
+    unsafe fn glScissor(&mut self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        ffi::glScissor(x, y, width, height)
+    }
+
+    unsafe fn glShaderBinary(
+        &mut self,
+        n: GLsizei,
+        shaders: *const GLuint,
+        binaryformat: GLenum,
+        binary: *const GLvoid,
+        length: GLsizei,
+    ) {
+        ffi::glShaderBinary(n, shaders, binaryformat, binary, length)
+    }
+
+    unsafe fn glShaderSource(
+        &mut self,
+        shader: GLuint,
+        count: GLsizei,
+        string: *const *const GLchar,
+        length: *const GLint,
+    ) {
+        ffi::glShaderSource(shader, count, string, length)
+    }
+
+    unsafe fn glStencilFunc(&mut self, func: GLenum, ref_: GLint, mask: GLuint) {
+        ffi::glStencilFunc(func, ref_, mask)
+    }
+
+    unsafe fn glStencilFuncSeparate(
+        &mut self,
+        face: GLenum,
+        func: GLenum,
+        ref_: GLint,
+        mask: GLuint,
+    ) {
+        ffi::glStencilFuncSeparate(face, func, ref_, mask)
+    }
+
+    unsafe fn glStencilMask(&mut self, mask: GLuint) {
+        ffi::glStencilMask(mask)
+    }
+
+    unsafe fn glStencilMaskSeparate(&mut self, face: GLenum, mask: GLuint) {
+        ffi::glStencilMaskSeparate(face, mask)
+    }
+
+    unsafe fn glStencilOp(&mut self, fail: GLenum, zfail: GLenum, zpass: GLenum) {
+        ffi::glStencilOp(fail, zfail, zpass)
+    }
+
+    unsafe fn glStencilOpSeparate(
+        &mut self,
+        face: GLenum,
+        fail: GLenum,
+        zfail: GLenum,
+        zpass: GLenum,
+    ) {
+        ffi::glStencilOpSeparate(face, fail, zfail, zpass)
+    }
+
+    unsafe fn glTexImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        internalformat: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *const GLvoid,
+    ) {
+        ffi::glTexImage2D(target, level, internalformat, width, height, border, format, type_, pixels)
+    }
+
+    unsafe fn glTexParameterf(&mut self, target: GLenum, pname: GLenum, param: GLfloat) {
+        ffi::glTexParameterf(target, pname, param)
+    }
+
+    unsafe fn glTexParameterfv(&mut self, target: GLenum, pname: GLenum, params: *const GLfloat) {
+        ffi::glTexParameterfv(target, pname, params)
+    }
+
+    unsafe fn glTexParameteri(&mut self, target: GLenum, pname: GLenum, param: GLint) {
+        ffi::glTexParameteri(target, pname, param)
+    }
+
+    unsafe fn glTexParameteriv(&mut self, target: GLenum, pname: GLenum, params: *const GLint) {
+        ffi::glTexParameteriv(target, pname, params)
+    }
+
+    unsafe fn glTexSubImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *const GLvoid,
+    ) {
+        ffi::glTexSubImage2D(target, level, xoffset, yoffset, width, height, format, type_, pixels)
+    }
+
+    unsafe fn glUniform1f(&mut self, location: GLint, x: GLfloat) {
+        ffi::glUniform1f(location, x)
+    }
+
+    unsafe fn glUniform1fv(&mut self, location: GLint, count: GLsizei, v: *const GLfloat) {
+        ffi::glUniform1fv(location, count, v)
+    }
+
+    unsafe fn glUniform1i(&mut self, location: GLint, x: GLint) {
+        ffi::glUniform1i(location, x)
+    }
+
+    unsafe fn glUniform1iv(&mut self, location: GLint, count: GLsizei, v: *const GLint) {
+        ffi::glUniform1iv(location, count, v)
+    }
+
+    unsafe fn glUniform2f(&mut self, location: GLint, x: GLfloat, y: GLfloat) {
+        ffi::glUniform2f(location, x, y)
+    }
+
+    unsafe fn glUniform2fv(&mut self, location: GLint, count: GLsizei, v: *const GLfloat) {
+        ffi::glUniform2fv(location, count, v)
+    }
+
+    unsafe fn glUniform2i(&mut self, location: GLint, x: GLint, y: GLint) {
+        ffi::glUniform2i(location, x, y)
+    }
+
+    unsafe fn glUniform2iv(&mut self, location: GLint, count: GLsizei, v: *const GLint) {
+        ffi::glUniform2iv(location, count, v)
+    }
+
+    unsafe fn glUniform3f(&mut self, location: GLint, x: GLfloat, y: GLfloat, z: GLfloat) {
+        ffi::glUniform3f(location, x, y, z)
+    }
+
+    unsafe fn glUniform3fv(&mut self, location: GLint, count: GLsizei, v: *const GLfloat) {
+        ffi::glUniform3fv(location, count, v)
+    }
+
+    unsafe fn glUniform3i(&mut self, location: GLint, x: GLint, y: GLint, z: GLint) {
+        ffi::glUniform3i(location, x, y, z)
+    }
+
+    unsafe fn glUniform3iv(&mut self, location: GLint, count: GLsizei, v: *const GLint) {
+        ffi::glUniform3iv(location, count, v)
+    }
+
+    unsafe fn glUniform4f(
+        &mut self,
+        location: GLint,
+        x: GLfloat,
+        y: GLfloat,
+        z: GLfloat,
+        w: GLfloat,
+    ) {
+        ffi::glUniform4f(location, x, y, z, w)
+    }
+
+    unsafe fn glUniform4fv(&mut self, location: GLint, count: GLsizei, v: *const GLfloat) {
+        ffi::glUniform4fv(location, count, v)
+    }
+
+    unsafe fn glUniform4i(&mut self, location: GLint, x: GLint, y: GLint, z: GLint, w: GLint) {
+        ffi::glUniform4i(location, x, y, z, w)
+    }
+
+    unsafe fn glUniform4iv(&mut self, location: GLint, count: GLsizei, v: *const GLint) {
+        ffi::glUniform4iv(location, count, v)
+    }
+
+    unsafe fn glUniformMatrix2fv(
+        &mut self,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    ) {
+        ffi::glUniformMatrix2fv(location, count, transpose, value)
+    }
+
+    unsafe fn glUniformMatrix3fv(
+        &mut self,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    ) {
+        ffi::glUniformMatrix3fv(location, count, transpose, value)
+    }
+
+    unsafe fn glUniformMatrix4fv(
+        &mut self,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    ) {
+        ffi::glUniformMatrix4fv(location, count, transpose, value)
+    }
+
+    unsafe fn glUseProgram(&mut self, program: GLuint) {
+        ffi::glUseProgram(program)
+    }
+
+    unsafe fn glValidateProgram(&mut self, program: GLuint) {
+        ffi::glValidateProgram(program)
+    }
+
+    unsafe fn glVertexAttrib1f(&mut self, indx: GLuint, x: GLfloat) {
+        ffi::glVertexAttrib1f(indx, x)
+    }
+
+    unsafe fn glVertexAttrib1fv(&mut self, indx: GLuint, values: *const GLfloat) {
+        ffi::glVertexAttrib1fv(indx, values)
+    }
+
+    unsafe fn glVertexAttrib2f(&mut self, indx: GLuint, x: GLfloat, y: GLfloat) {
+        ffi::glVertexAttrib2f(indx, x, y)
+    }
+
+    unsafe fn glVertexAttrib2fv(&mut self, indx: GLuint, values: *const GLfloat) {
+        ffi::glVertexAttrib2fv(indx, values)
+    }
+
+    unsafe fn glVertexAttrib3f(&mut self, indx: GLuint, x: GLfloat, y: GLfloat, z: GLfloat) {
+        ffi::glVertexAttrib3f(indx, x, y, z)
+    }
+
+    unsafe fn glVertexAttrib3fv(&mut self, indx: GLuint, values: *const GLfloat) {
+        ffi::glVertexAttrib3fv(indx, values)
+    }
+
+    unsafe fn glVertexAttrib4f(
+        &mut self,
+        indx: GLuint,
+        x: GLfloat,
+        y: GLfloat,
+        z: GLfloat,
+        w: GLfloat,
+    ) {
+        ffi::glVertexAttrib4f(indx, x, y, z, w)
+    }
+
+    unsafe fn glVertexAttrib4fv(&mut self, indx: GLuint, values: *const GLfloat) {
+        ffi::glVertexAttrib4fv(indx, values)
+    }
+
+    unsafe fn glVertexAttribPointer(
+        &mut self,
+        indx: GLuint,
+        size: GLint,
+        type_: GLenum,
+        normalized: GLboolean,
+        stride: GLsizei,
+        ptr: *const GLvoid,
+    ) {
+        ffi::glVertexAttribPointer(indx, size, type_, normalized, stride, ptr)
+    }
+
+    unsafe fn glViewport(&mut self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        ffi::glViewport(x, y, width, height)
+    }
+
+    unsafe fn glGetProgramBinary(
+        &mut self,
+        program: GLuint,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        binaryFormat: *mut GLenum,
+        binary: *mut GLvoid,
+    ) {
+        if let Some(function) = ffi::entry_points().glGetProgramBinary {
+            function(program, bufSize, length, binaryFormat, binary)
+        }
+    }
+
+    unsafe fn glProgramBinary(
+        &mut self,
+        program: GLuint,
+        binaryFormat: GLenum,
+        binary: *const GLvoid,
+        length: GLsizei,
+    ) {
+        if let Some(function) = ffi::entry_points().glProgramBinary {
+            function(program, binaryFormat, binary, length)
+        }
+    }
+
+    unsafe fn glGetProgramBinaryOES(
+        &mut self,
+        program: GLuint,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        binaryFormat: *mut GLenum,
+        binary: *mut GLvoid,
+    ) {
+        if let Some(function) = ffi::entry_points().glGetProgramBinaryOES {
+            function(program, bufSize, length, binaryFormat, binary)
+        }
+    }
+
+    unsafe fn glProgramBinaryOES(
+        &mut self,
+        program: GLuint,
+        binaryFormat: GLenum,
+        binary: *const GLvoid,
+        length: GLsizei,
+    ) {
+        if let Some(function) = ffi::entry_points().glProgramBinaryOES {
+            function(program, binaryFormat, binary, length)
+        }
+    }
+
+    unsafe fn glBindVertexArray(&mut self, array: GLuint) {
+        if let Some(function) = ffi::entry_points().glBindVertexArray {
+            function(array)
+        }
+    }
+
+    unsafe fn glDeleteVertexArrays(&mut self, n: GLsizei, arrays: *const GLuint) {
+        if let Some(function) = ffi::entry_points().glDeleteVertexArrays {
+            function(n, arrays)
+        }
+    }
+
+    unsafe fn glGenVertexArrays(&mut self, n: GLsizei, arrays: *mut GLuint) {
+        if let Some(function) = ffi::entry_points().glGenVertexArrays {
+            function(n, arrays)
+        }
+    }
+
+    unsafe fn glBindVertexArrayOES(&mut self, array: GLuint) {
+        if let Some(function) = ffi::entry_points().glBindVertexArrayOES {
+            function(array)
+        }
+    }
+
+    unsafe fn glDeleteVertexArraysOES(&mut self, n: GLsizei, arrays: *const GLuint) {
+        if let Some(function) = ffi::entry_points().glDeleteVertexArraysOES {
+            function(n, arrays)
+        }
+    }
+
+    unsafe fn glGenVertexArraysOES(&mut self, n: GLsizei, arrays: *mut GLuint) {
+        if let Some(function) = ffi::entry_points().glGenVertexArraysOES {
+            function(n, arrays)
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use std;
+use std::mem::size_of_val;
+
+use types::*;
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+/// The kind of GL object an object name refers to.
+///
+/// Names handed out by `glGen*`/`glCreate*` are only meaningful within one context, so anything
+/// that re-issues calls elsewhere needs to know which namespace a name lives in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ObjectKind {
+    Buffer,
+    Framebuffer,
+    Renderbuffer,
+    Texture,
+    Program,
+    Shader,
+    VertexArray,
+}
+
+/// A single argument or return value of an intercepted call.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Void,
+    Bool(bool),
+    Int(i32),
+    Uint(u32),
+    Enum(GLenum),
+    Bitfield(GLbitfield),
+    Float(f32),
+    /// An object name, e.g. the `texture` of `glBindTexture`.
+    Name(ObjectKind, u32),
+    /// A list of object names, e.g. the result of `glGenTextures`.
+    Names(ObjectKind, Vec<u32>),
+    /// A uniform location, which is only meaningful together with the program it came from.
+    Location(i32),
+    Str(String),
+    /// Client memory read by the call: buffer uploads, pixels, index lists, ...
+    Bytes(Vec<u8>),
+    Ints(Vec<i32>),
+    Floats(Vec<f32>),
+    /// A byte offset into the currently bound buffer passed where the C API takes a pointer.
+    Offset(usize),
+}
+
+impl Value {
+    /// Copies the memory behind `buffer` into a `Value::Bytes`.
+    pub fn bytes<T>(buffer: &[T]) -> Value {
+        unsafe {
+            let bytes = std::slice::from_raw_parts(buffer.as_ptr() as *const u8, size_of_val(buffer));
+
+            Value::Bytes(bytes.to_vec())
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// A GL call as seen by an `Interceptor`.
+///
+/// `name` is the C entry point (`"glBindTexture"`) and `args` hold the arguments in the order of
+/// the C prototype. `ret` is `Value::Void` until the call has been executed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Call {
+    pub name: &'static str,
+    pub args: Vec<Value>,
+    pub ret: Value,
+}
+
+impl Call {
+    pub fn new(name: &'static str, args: Vec<Value>) -> Call {
+        Call {
+            name,
+            args,
+            ret: Value::Void,
+        }
+    }
+}
+
+/// The entry points `Wrapper` can issue, in the order of `ffi`.
+///
+/// Binary traces write this table into their header and refer to calls by their position in it, so
+/// entries can be added or reordered without breaking older traces.
+pub const CALL_NAMES: &[&str] = &[
+    "glActiveTexture",
+    "glAttachShader",
+    "glBindAttribLocation",
+    "glBindBuffer",
+    "glBindFramebuffer",
+    "glBindRenderbuffer",
+    "glBindTexture",
+    "glBlendColor",
+    "glBlendEquation",
+    "glBlendEquationSeparate",
+    "glBlendFunc",
+    "glBlendFuncSeparate",
+    "glBufferData",
+    "glBufferSubData",
+    "glCheckFramebufferStatus",
+    "glClear",
+    "glClearColor",
+    "glClearDepthf",
+    "glClearStencil",
+    "glColorMask",
+    "glCompileShader",
+    "glCompressedTexImage2D",
+    "glCompressedTexSubImage2D",
+    "glCopyTexImage2D",
+    "glCopyTexSubImage2D",
+    "glCreateProgram",
+    "glCreateShader",
+    "glCullFace",
+    "glDeleteBuffers",
+    "glDeleteFramebuffers",
+    "glDeleteProgram",
+    "glDeleteRenderbuffers",
+    "glDeleteShader",
+    "glDeleteTextures",
+    "glDepthFunc",
+    "glDepthMask",
+    "glDepthRangef",
+    "glDetachShader",
+    "glDisable",
+    "glDisableVertexAttribArray",
+    "glDrawArrays",
+    "glDrawElements",
+    "glEnable",
+    "glEnableVertexAttribArray",
+    "glFinish",
+    "glFlush",
+    "glFramebufferRenderbuffer",
+    "glFramebufferTexture2D",
+    "glFrontFace",
+    "glGenBuffers",
+    "glGenerateMipmap",
+    "glGenFramebuffers",
+    "glGenRenderbuffers",
+    "glGenTextures",
+    "glGetActiveAttrib",
+    "glGetActiveUniform",
+    "glGetAttachedShaders",
+    "glGetAttribLocation",
+    "glGetBooleanv",
+    "glGetBufferParameteriv",
+    "glGetError",
+    "glGetFloatv",
+    "glGetFramebufferAttachmentParameteriv",
+    "glGetIntegerv",
+    "glGetProgramiv",
+    "glGetProgramInfoLog",
+    "glGetRenderbufferParameteriv",
+    "glGetShaderiv",
+    "glGetShaderInfoLog",
+    "glGetShaderPrecisionFormat",
+    "glGetShaderSource",
+    "glGetString",
+    "glGetTexParameterfv",
+    "glGetTexParameteriv",
+    "glGetUniformfv",
+    "glGetUniformiv",
+    "glGetUniformLocation",
+    "glGetVertexAttribfv",
+    "glGetVertexAttribiv",
+    "glGetVertexAttribPointerv",
+    "glHint",
+    "glIsBuffer",
+    "glIsEnabled",
+    "glIsFramebuffer",
+    "glIsProgram",
+    "glIsRenderbuffer",
+    "glIsShader",
+    "glIsTexture",
+    "glLineWidth",
+    "glLinkProgram",
+    "glPixelStorei",
+    "glPolygonOffset",
+    "glReadPixels",
+    "glReleaseShaderCompiler",
+    "glRenderbufferStorage",
+    "glSampleCoverage",
+    "glScissor",
+    "glShaderBinary",
+    "glShaderSource",
+    "glStencilFunc",
+    "glStencilFuncSeparate",
+    "glStencilMask",
+    "glStencilMaskSeparate",
+    "glStencilOp",
+    "glStencilOpSeparate",
+    "glTexImage2D",
+    "glTexParameterf",
+    "glTexParameterfv",
+    "glTexParameteri",
+    "glTexParameteriv",
+    "glTexSubImage2D",
+    "glUniform1f",
+    "glUniform1fv",
+    "glUniform1i",
+    "glUniform1iv",
+    "glUniform2f",
+    "glUniform2fv",
+    "glUniform2i",
+    "glUniform2iv",
+    "glUniform3f",
+    "glUniform3fv",
+    "glUniform3i",
+    "glUniform3iv",
+    "glUniform4f",
+    "glUniform4fv",
+    "glUniform4i",
+    "glUniform4iv",
+    "glUniformMatrix2fv",
+    "glUniformMatrix3fv",
+    "glUniformMatrix4fv",
+    "glUseProgram",
+    "glValidateProgram",
+    "glVertexAttrib1f",
+    "glVertexAttrib1fv",
+    "glVertexAttrib2f",
+    "glVertexAttrib2fv",
+    "glVertexAttrib3f",
+    "glVertexAttrib3fv",
+    "glVertexAttrib4f",
+    "glVertexAttrib4fv",
+    "glVertexAttribPointer",
+    "glViewport",
+    "glGetProgramBinary",
+    "glProgramBinary",
+    "glGetProgramBinaryOES",
+    "glProgramBinaryOES",
+    "glBindVertexArray",
+    "glDeleteVertexArrays",
+    "glGenVertexArrays",
+    "glBindVertexArrayOES",
+    "glDeleteVertexArraysOES",
+    "glGenVertexArraysOES",
+];
@@ -0,0 +1,268 @@
+use std::marker::PhantomData;
+use std::mem;
+
+use types::GLenum;
+use consts::{GL_UNSIGNED_BYTE, GL_UNSIGNED_INT, GL_UNSIGNED_SHORT};
+use enums::{BeginMode, BufferTarget, DataType};
+use super::wrapper::{Error, ErrorKind, Wrapper};
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// A type indices of `Draw::draw_elements` can have. `u32` needs `GL_OES_element_index_uint`.
+pub trait ElementIndex: Copy + std::fmt::Debug {
+    const TYPE: GLenum;
+
+    fn value(self) -> usize;
+}
+
+impl ElementIndex for u8 {
+    const TYPE: GLenum = GL_UNSIGNED_BYTE;
+
+    fn value(self) -> usize {
+        self as usize
+    }
+}
+
+impl ElementIndex for u16 {
+    const TYPE: GLenum = GL_UNSIGNED_SHORT;
+
+    fn value(self) -> usize {
+        self as usize
+    }
+}
+
+impl ElementIndex for u32 {
+    const TYPE: GLenum = GL_UNSIGNED_INT;
+
+    fn value(self) -> usize {
+        self as usize
+    }
+}
+
+// An attribute reading client memory, and how far it reaches.
+struct ClientArray {
+    index: u32,
+    // Bytes from the pointer on.
+    length: usize,
+    // Bytes from one vertex to the next, and read for one vertex.
+    stride: usize,
+    size: usize,
+}
+
+/// A scope for drawing with attributes in client memory.
+///
+/// GL reads client arrays at the draw, not when they are pointed at, so the slices passed to
+/// `client_attribute` are borrowed for as long as the scope lives and the borrow checker keeps
+/// them alive and unmoved through every draw. Draws check that client arrays hold every vertex
+/// they read. Attributes in buffer objects are set with `buffer_attribute`, at a byte offset.
+///
+/// The scope holds the wrapper for as long as it lives and only offers attribute and draw calls, so
+/// nothing can draw with its attributes or repoint them unchecked; set the program and uniforms
+/// before opening it. The attributes the scope enabled are disabled again when it ends, so no
+/// pointer into the borrowed memory is read after it. The `ARRAY_BUFFER` and `ELEMENT_ARRAY_BUFFER` bindings are
+/// left as the last call needed them.
+pub struct Draw<'gl, 'data> {
+    gl: &'gl mut Wrapper,
+    enabled: Vec<u32>,
+    client: Vec<ClientArray>,
+    data: PhantomData<&'data [u8]>,
+}
+
+impl<'gl, 'data> Draw<'gl, 'data> {
+    pub fn new(gl: &'gl mut Wrapper) -> Draw<'gl, 'data> {
+        Draw {
+            gl,
+            enabled: Vec::new(),
+            client: Vec::new(),
+            data: PhantomData,
+        }
+    }
+
+    /// Makes attribute `index` read `size` components of `type_` per vertex from `data`, `stride`
+    /// bytes apart or tightly packed for 0.
+    pub fn client_attribute<T>(&mut self, index: u32, size: i32, type_: DataType, normalized: bool, stride: i32,
+                               data: &'data [T]) -> Result<(), Error> where T: std::fmt::Debug + Clone {
+        let component = component_size(type_)?;
+        if data.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidArgument,
+                                  format!("client array of attribute {} is empty", index)));
+        }
+
+        // Client pointers only mean client memory while no buffer is bound.
+        self.gl.gl_bind_buffer(BufferTarget::ARRAY_BUFFER, 0)?;
+        // The scope borrows `data` for 'data and disables the attribute before that ends.
+        unsafe { self.gl.gl_vertex_attrib_pointer(index, size, type_, normalized, stride, data)? };
+        self.enable(index)?;
+
+        let size = size.max(0) as usize * component;
+        self.client.retain(|array| array.index != index);
+        self.client.push(ClientArray {
+            index,
+            length: mem::size_of_val(data),
+            stride: if stride > 0 { stride as usize } else { size },
+            size,
+        });
+        Ok(())
+    }
+
+    /// Makes attribute `index` read from `buffer`, starting `offset` bytes in.
+    pub fn buffer_attribute(&mut self, index: u32, buffer: u32, size: i32, type_: DataType, normalized: bool,
+                            stride: i32, offset: u32) -> Result<(), Error> {
+        self.gl.gl_bind_buffer(BufferTarget::ARRAY_BUFFER, buffer)?;
+        self.gl.gl_vertex_attrib_pointer_offset(index, size, type_, normalized, stride, offset)?;
+        self.enable(index)?;
+        self.client.retain(|array| array.index != index);
+        Ok(())
+    }
+
+    pub fn draw_arrays(&mut self, mode: BeginMode, first: i32, count: i32) -> Result<(), Error> {
+        if count > 0 {
+            let last = (first.max(0) as usize).checked_add(count as usize - 1).ok_or_else(|| {
+                Error::new(ErrorKind::InvalidArgument, format!("drawing {} vertices from {} overflows", count, first))
+            })?;
+            self.check_vertex(last)?;
+        }
+        self.gl.gl_draw_arrays(mode, first, count)
+    }
+
+    /// Draws with the indices in `indices`.
+    pub fn draw_elements<T: ElementIndex>(&mut self, mode: BeginMode, indices: &[T]) -> Result<(), Error> {
+        if let Some(last) = indices.iter().map(|index| index.value()).max() {
+            self.check_vertex(last)?;
+        }
+        self.gl.gl_bind_buffer(BufferTarget::ELEMENT_ARRAY_BUFFER, 0)?;
+        self.gl.gl_draw_elements(mode, indices.len() as i32, T::TYPE, indices)
+    }
+
+    /// Draws with `count` indices of `type_` read from `buffer`, starting `offset` bytes in. The
+    /// indices are not known here, so client arrays are not checked against them.
+    pub fn draw_elements_offset(&mut self, mode: BeginMode, count: i32, type_: GLenum, buffer: u32, offset: u32)
+        -> Result<(), Error> {
+        self.gl.gl_bind_buffer(BufferTarget::ELEMENT_ARRAY_BUFFER, buffer)?;
+        self.gl.gl_draw_elements_offset(mode, count, type_, offset)
+    }
+
+    fn enable(&mut self, index: u32) -> Result<(), Error> {
+        if !self.enabled.contains(&index) {
+            self.gl.gl_enable_vertex_attrib_array(index)?;
+            self.enabled.push(index);
+        }
+        Ok(())
+    }
+
+    // Fails if a client array ends before vertex `last`.
+    fn check_vertex(&self, last: usize) -> Result<(), Error> {
+        for array in &self.client {
+            let end = last.checked_mul(array.stride).and_then(|start| start.checked_add(array.size));
+            match end {
+                Some(end) if end <= array.length => {}
+                Some(end) => return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                    "drawing vertex {} reads {} bytes of attribute {}, whose client array has {}",
+                    last, end, array.index, array.length))),
+                None => return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                    "drawing vertex {} reads past the end of the address space in attribute {}",
+                    last, array.index))),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'gl, 'data> Drop for Draw<'gl, 'data> {
+    fn drop(&mut self) {
+        for &index in &self.enabled {
+            let _ = self.gl.gl_disable_vertex_attrib_array(index);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// FUNCTIONS
+// -------------------------------------------------------------------------------------------------
+
+// The bytes of one component of an attribute of `type_`.
+fn component_size(type_: DataType) -> Result<usize, Error> {
+    match type_ {
+        DataType::BYTE | DataType::UNSIGNED_BYTE => Ok(1),
+        DataType::SHORT | DataType::UNSIGNED_SHORT => Ok(2),
+        DataType::FIXED | DataType::FLOAT => Ok(4),
+        _ => Err(Error::new(ErrorKind::InvalidArgument, format!("{:?} is not an attribute component type", type_))),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consts::GL_COLOR_BUFFER_BIT;
+    use enums::{PixelDataType, PixelFormat, VertexAttributeParamType};
+    use es20::software::program::{ClosureProgram, VertexOutput};
+    use es20::software::SoftwareBackend;
+
+    const QUAD: [f32; 8] = [-1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0, 1.0];
+
+    // A wrapper drawing attribute 0 as positions in white.
+    fn white() -> Wrapper {
+        let backend = SoftwareBackend::new(2, 2);
+        let programs = backend.programs();
+        let mut gl = Wrapper::with_backend(Box::new(backend));
+
+        let program = gl.gl_create_program().unwrap();
+        let closures = ClosureProgram::new(|vertex| {
+            let position = vertex.attribute(0);
+            VertexOutput::new([position[0], position[1], 0.0, 1.0], Vec::new())
+        }, |_| Some([1.0, 1.0, 1.0, 1.0]));
+        programs.register(program, closures.attribute("position", DataType::FLOAT_VEC2));
+        gl.gl_link_program(program).unwrap();
+        gl.gl_use_program(program).unwrap();
+        gl.gl_clear(GL_COLOR_BUFFER_BIT).unwrap();
+        gl
+    }
+
+    fn pixels(gl: &mut Wrapper) -> [u8; 16] {
+        let mut pixels = [0u8; 16];
+        gl.gl_read_pixels(0, 0, 2, 2, PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE, &mut pixels).unwrap();
+        pixels
+    }
+
+    #[test]
+    fn draws_client_arrays() {
+        let mut gl = white();
+        {
+            let mut draw = Draw::new(&mut gl);
+            draw.client_attribute(0, 2, DataType::FLOAT, false, 0, &QUAD).unwrap();
+            draw.draw_elements(BeginMode::TRIANGLES, &[0u16, 1, 2, 2, 1, 3]).unwrap();
+        }
+        assert_eq!(pixels(&mut gl), [255; 16]);
+    }
+
+    #[test]
+    fn disables_its_attributes_when_it_ends() {
+        let mut gl = white();
+        {
+            let mut draw = Draw::new(&mut gl);
+            draw.client_attribute(0, 2, DataType::FLOAT, false, 0, &QUAD).unwrap();
+            draw.draw_arrays(BeginMode::TRIANGLE_STRIP, 0, 4).unwrap();
+        }
+        assert_eq!(gl.gl_get_vertex_attribiv(0, VertexAttributeParamType::VERTEX_ATTRIB_ARRAY_ENABLED).unwrap(), 0);
+    }
+
+    #[test]
+    fn rejects_draws_past_a_client_array() {
+        let mut gl = white();
+        {
+            let mut draw = Draw::new(&mut gl);
+            draw.client_attribute(0, 2, DataType::FLOAT, false, 0, &QUAD[..6]).unwrap();
+            assert_eq!(draw.draw_arrays(BeginMode::TRIANGLE_STRIP, 0, 4).unwrap_err().kind, ErrorKind::InvalidArgument);
+            assert_eq!(draw.draw_elements(BeginMode::TRIANGLES, &[0u8, 1, 3]).unwrap_err().kind,
+                       ErrorKind::InvalidArgument);
+            assert!(draw.client_attribute::<f32>(1, 2, DataType::FLOAT, false, 0, &[]).is_err());
+        }
+        assert_eq!(pixels(&mut gl), [0; 16]);
+    }
+}
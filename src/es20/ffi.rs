@@ -1,3 +1,10 @@
+use std::ffi::CString;
+use std::mem;
+use std::ptr;
+use std::sync::OnceLock;
+
+use libc::{c_char, c_void};
+
 use types::*;
 
 extern "C" {
@@ -444,4 +451,106 @@ extern "C" {
     );
 
     pub fn glViewport(x: GLint, y: GLint, width: GLsizei, height: GLsizei);
-}
\ No newline at end of file
+}
+
+// -------------------------------------------------------------------------------------------------
+// RUNTIME ENTRY POINTS
+// -------------------------------------------------------------------------------------------------
+
+/// Functions beyond ES 2.0 core. Not every `libGLESv2` exports them, and linking them statically
+/// would keep the crate from linking there, so they are looked up on first use. `None` where the
+/// driver does not have them.
+pub struct EntryPoints {
+    pub glGetProgramBinary: Option<unsafe extern "C" fn(GLuint, GLsizei, *mut GLsizei, *mut GLenum, *mut GLvoid)>,
+    pub glProgramBinary: Option<unsafe extern "C" fn(GLuint, GLenum, *const GLvoid, GLsizei)>,
+    pub glGetProgramBinaryOES: Option<unsafe extern "C" fn(GLuint, GLsizei, *mut GLsizei, *mut GLenum, *mut GLvoid)>,
+    pub glProgramBinaryOES: Option<unsafe extern "C" fn(GLuint, GLenum, *const GLvoid, GLsizei)>,
+    pub glBindVertexArray: Option<unsafe extern "C" fn(GLuint)>,
+    pub glDeleteVertexArrays: Option<unsafe extern "C" fn(GLsizei, *const GLuint)>,
+    pub glGenVertexArrays: Option<unsafe extern "C" fn(GLsizei, *mut GLuint)>,
+    pub glBindVertexArrayOES: Option<unsafe extern "C" fn(GLuint)>,
+    pub glDeleteVertexArraysOES: Option<unsafe extern "C" fn(GLsizei, *const GLuint)>,
+    pub glGenVertexArraysOES: Option<unsafe extern "C" fn(GLsizei, *mut GLuint)>,
+}
+
+impl EntryPoints {
+    /// Whether the function `name` was found.
+    pub fn has(&self, name: &str) -> bool {
+        match name {
+            "glGetProgramBinary" => self.glGetProgramBinary.is_some(),
+            "glProgramBinary" => self.glProgramBinary.is_some(),
+            "glGetProgramBinaryOES" => self.glGetProgramBinaryOES.is_some(),
+            "glProgramBinaryOES" => self.glProgramBinaryOES.is_some(),
+            "glBindVertexArray" => self.glBindVertexArray.is_some(),
+            "glDeleteVertexArrays" => self.glDeleteVertexArrays.is_some(),
+            "glGenVertexArrays" => self.glGenVertexArrays.is_some(),
+            "glBindVertexArrayOES" => self.glBindVertexArrayOES.is_some(),
+            "glDeleteVertexArraysOES" => self.glDeleteVertexArraysOES.is_some(),
+            "glGenVertexArraysOES" => self.glGenVertexArraysOES.is_some(),
+            _ => false,
+        }
+    }
+}
+
+/// The entry points of the driver, looked up on the first call.
+pub fn entry_points() -> &'static EntryPoints {
+    static ENTRY_POINTS: OnceLock<EntryPoints> = OnceLock::new();
+    ENTRY_POINTS.get_or_init(|| unsafe {
+        EntryPoints {
+            glGetProgramBinary: load(&["glGetProgramBinary"]),
+            glProgramBinary: load(&["glProgramBinary"]),
+            glGetProgramBinaryOES: load(&extension_names("glGetProgramBinaryOES", "glGetProgramBinary")),
+            glProgramBinaryOES: load(&extension_names("glProgramBinaryOES", "glProgramBinary")),
+            glBindVertexArray: load(&["glBindVertexArray"]),
+            glDeleteVertexArrays: load(&["glDeleteVertexArrays"]),
+            glGenVertexArrays: load(&["glGenVertexArrays"]),
+            glBindVertexArrayOES: load(&extension_names("glBindVertexArrayOES", "glBindVertexArray")),
+            glDeleteVertexArraysOES: load(&extension_names("glDeleteVertexArraysOES", "glDeleteVertexArrays")),
+            glGenVertexArraysOES: load(&extension_names("glGenVertexArraysOES", "glGenVertexArrays")),
+        }
+    })
+}
+
+// The names to look extension function `extension` up by. iOS exports only the ES 3.0 functions
+// of its extensions, under the core name.
+fn extension_names(extension: &'static str, core: &'static str) -> Vec<&'static str> {
+    if cfg!(target_os = "ios") {
+        vec![extension, core]
+    } else {
+        vec![extension]
+    }
+}
+
+// The first of `names` the driver has, as a function pointer of type `F`.
+unsafe fn load<F: Copy>(names: &[&str]) -> Option<F> {
+    assert_eq!(mem::size_of::<F>(), mem::size_of::<*mut c_void>());
+    names.iter()
+        .map(|name| lookup(name))
+        .find(|symbol| !symbol.is_null())
+        .map(|symbol| mem::transmute_copy(&symbol))
+}
+
+#[cfg(unix)]
+unsafe fn lookup(name: &str) -> *mut c_void {
+    let name = match CString::new(name) {
+        Ok(name) => name,
+        Err(_) => return ptr::null_mut(),
+    };
+    let symbol = libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr());
+    if !symbol.is_null() {
+        return symbol;
+    }
+
+    // Dispatch libraries such as glvnd hand extension functions out only through EGL.
+    let get_proc_address = libc::dlsym(libc::RTLD_DEFAULT, b"eglGetProcAddress\0".as_ptr() as *const c_char);
+    if get_proc_address.is_null() {
+        return ptr::null_mut();
+    }
+    let get_proc_address: unsafe extern "C" fn(*const c_char) -> *mut c_void = mem::transmute(get_proc_address);
+    get_proc_address(name.as_ptr())
+}
+
+#[cfg(not(unix))]
+unsafe fn lookup(_name: &str) -> *mut c_void {
+    ptr::null_mut()
+}
@@ -0,0 +1,761 @@
+use std::fmt;
+
+use types::*;
+use consts::*;
+use enums::{FrameBufferAttachmentParamType, FrameBufferAttachmentType, FrameBufferStatus, FrameBufferTarget,
+            PixelDataType, PixelFormat, RenderBufferParamType, RenderBufferTarget, TextureTarget};
+use format::FormatInfo;
+use super::texture::builder::{SamplerSettings, Texture, TextureBuilder};
+use super::wrapper::{Error, ErrorKind, Wrapper};
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+/// Where the image of an attachment lives.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Storage {
+    /// A 2D texture with the given client format and type, which can be sampled after rendering.
+    Texture(PixelFormat, PixelDataType),
+    /// A renderbuffer with the given internal format: `RGBA4`, `RGB565` or `RGB5_A1` for color,
+    /// `DEPTH_COMPONENT16` for depth and `STENCIL_INDEX8` for stencil.
+    Renderbuffer(PixelFormat),
+}
+
+/// An attachment created and owned by a `Framebuffer`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttachedImage {
+    Texture(Texture),
+    Renderbuffer(Renderbuffer),
+}
+
+impl AttachedImage {
+    pub fn width(&self) -> u32 {
+        match *self {
+            AttachedImage::Texture(ref texture) => texture.width,
+            AttachedImage::Renderbuffer(ref renderbuffer) => renderbuffer.width,
+        }
+    }
+
+    pub fn height(&self) -> u32 {
+        match *self {
+            AttachedImage::Texture(ref texture) => texture.height,
+            AttachedImage::Renderbuffer(ref renderbuffer) => renderbuffer.height,
+        }
+    }
+
+    fn resize(&mut self, gl: &mut Wrapper, width: u32, height: u32) -> Result<(), Error> {
+        match *self {
+            AttachedImage::Texture(ref mut texture) => texture.resize(gl, width, height),
+            AttachedImage::Renderbuffer(ref mut renderbuffer) => renderbuffer.resize(gl, width, height),
+        }
+    }
+
+    fn delete(self, gl: &mut Wrapper) -> Result<(), Error> {
+        match self {
+            AttachedImage::Texture(texture) => texture.delete(gl),
+            AttachedImage::Renderbuffer(renderbuffer) => renderbuffer.delete(gl),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// Describes one attachment of a `FramebufferBuilder`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Attachment {
+    pub storage: Storage,
+    /// Overrides the framebuffer size. ES 2.0 requires all attachments to have the same size, so
+    /// this is mostly useful on drivers that relax that rule.
+    pub size: Option<(u32, u32)>,
+}
+
+impl Attachment {
+    pub fn texture(format: PixelFormat, type_: PixelDataType) -> Attachment {
+        Attachment { storage: Storage::Texture(format, type_), size: None }
+    }
+
+    pub fn renderbuffer(format: PixelFormat) -> Attachment {
+        Attachment { storage: Storage::Renderbuffer(format), size: None }
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Attachment {
+        self.size = Some((width, height));
+        self
+    }
+}
+
+/// A renderbuffer with its storage allocated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Renderbuffer {
+    pub id: u32,
+    pub format: PixelFormat,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Renderbuffer {
+    /// Generates a renderbuffer, binds it and allocates its storage.
+    pub fn new(gl: &mut Wrapper, format: PixelFormat, width: u32, height: u32) -> Result<Renderbuffer, Error> {
+        let id = gl.gl_gen_renderbuffers(1)?[0];
+        let mut renderbuffer = Renderbuffer { id, format, width, height };
+        renderbuffer.resize(gl, width, height)?;
+        Ok(renderbuffer)
+    }
+
+    pub fn bind(&self, gl: &mut Wrapper) -> Result<(), Error> {
+        gl.gl_bind_renderbuffer(RenderBufferTarget::RENDERBUFFER, self.id)
+    }
+
+    /// Binds the renderbuffer and reallocates its storage, dropping the contents.
+    pub fn resize(&mut self, gl: &mut Wrapper, width: u32, height: u32) -> Result<(), Error> {
+        self.bind(gl)?;
+        gl.gl_renderbuffer_storage(RenderBufferTarget::RENDERBUFFER, self.format, width as i32, height as i32)?;
+        self.width = width;
+        self.height = height;
+        Ok(())
+    }
+
+    pub fn delete(self, gl: &mut Wrapper) -> Result<(), Error> {
+        gl.gl_delete_renderbuffers(&[self.id])
+    }
+}
+
+/// A framebuffer together with the attachments it owns.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Framebuffer {
+    pub id: u32,
+    pub width: u32,
+    pub height: u32,
+    pub color: Option<AttachedImage>,
+    pub depth: Option<AttachedImage>,
+    pub stencil: Option<AttachedImage>,
+}
+
+impl Framebuffer {
+    pub fn bind(&self, gl: &mut Wrapper) -> Result<(), Error> {
+        gl.gl_bind_framebuffer(FrameBufferTarget::FrameBuffer, self.id)
+    }
+
+    /// Binds the window system framebuffer again.
+    pub fn unbind(gl: &mut Wrapper) -> Result<(), Error> {
+        gl.gl_bind_framebuffer(FrameBufferTarget::FrameBuffer, 0)
+    }
+
+    /// The color texture, if color is rendered into a texture.
+    pub fn color_texture(&self) -> Option<&Texture> {
+        match self.color {
+            Some(AttachedImage::Texture(ref texture)) => Some(texture),
+            _ => None,
+        }
+    }
+
+    /// Binds the framebuffer and returns its completeness status.
+    pub fn status(&self, gl: &mut Wrapper) -> Result<FrameBufferStatus, Error> {
+        self.bind(gl)?;
+        gl.gl_check_framebuffer_status(FrameBufferTarget::FrameBuffer)
+    }
+
+    /// Reallocates every attachment at the new size, including those that were given an explicit
+    /// size, and checks completeness again. The contents are lost. The framebuffer stays bound.
+    pub fn resize(&mut self, gl: &mut Wrapper, width: u32, height: u32) -> Result<(), Error> {
+        if width == 0 || height == 0 {
+            return Err(Error::new(ErrorKind::InvalidArgument, "framebuffer dimensions must be non-zero"));
+        }
+
+        for image in self.attachments_mut() {
+            image.resize(gl, width, height)?;
+        }
+
+        self.width = width;
+        self.height = height;
+        self.bind(gl)?;
+        check_complete(gl, &self.textures())
+    }
+
+    /// Binds the framebuffer and explains why it is incomplete, see `diagnose`.
+    pub fn diagnose(&self, gl: &mut Wrapper) -> Result<Diagnosis, Error> {
+        self.bind(gl)?;
+        diagnose(gl, &self.textures())
+    }
+
+    /// Deletes the framebuffer and all of its attachments.
+    pub fn delete(self, gl: &mut Wrapper) -> Result<(), Error> {
+        gl.gl_delete_framebuffers(&[self.id])?;
+        for image in vec![self.color, self.depth, self.stencil].into_iter().flatten() {
+            image.delete(gl)?;
+        }
+        Ok(())
+    }
+
+    fn textures(&self) -> Vec<&Texture> {
+        [&self.color, &self.depth, &self.stencil].iter()
+            .filter_map(|image| match **image {
+                Some(AttachedImage::Texture(ref texture)) => Some(texture),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn attachments_mut(&mut self) -> impl Iterator<Item = &mut AttachedImage> {
+        self.color.iter_mut().chain(self.depth.iter_mut()).chain(self.stencil.iter_mut())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// BUILDER
+// -------------------------------------------------------------------------------------------------
+
+/// Describes a framebuffer and creates it with all of its attachments in one go.
+///
+/// Color textures use `SamplerSettings::linear()`, which keeps NPOT sizes complete on ES 2.0.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FramebufferBuilder {
+    width: u32,
+    height: u32,
+    color: Option<Attachment>,
+    depth: Option<Attachment>,
+    stencil: Option<Attachment>,
+}
+
+impl FramebufferBuilder {
+    pub fn new(width: u32, height: u32) -> FramebufferBuilder {
+        FramebufferBuilder {
+            width,
+            height,
+            color: None,
+            depth: None,
+            stencil: None,
+        }
+    }
+
+    pub fn color(mut self, attachment: Attachment) -> FramebufferBuilder {
+        self.color = Some(attachment);
+        self
+    }
+
+    pub fn depth(mut self, attachment: Attachment) -> FramebufferBuilder {
+        self.depth = Some(attachment);
+        self
+    }
+
+    pub fn stencil(mut self, attachment: Attachment) -> FramebufferBuilder {
+        self.stencil = Some(attachment);
+        self
+    }
+
+    /// Checks the description, then creates the framebuffer and its attachments and verifies that
+    /// the result is complete. Everything created is deleted again if it is not. On success the
+    /// framebuffer is left bound.
+    pub fn build(self, gl: &mut Wrapper) -> Result<Framebuffer, Error> {
+        self.validate()?;
+
+        let id = gl.gl_gen_framebuffers(1)?[0];
+        let mut framebuffer = Framebuffer {
+            id,
+            width: self.width,
+            height: self.height,
+            color: None,
+            depth: None,
+            stencil: None,
+        };
+
+        match self.attach_all(gl, &mut framebuffer) {
+            Ok(()) => Ok(framebuffer),
+            Err(error) => {
+                // The original error is more useful than one from the cleanup.
+                let _ = Framebuffer::unbind(gl);
+                let _ = framebuffer.delete(gl);
+                Err(error)
+            }
+        }
+    }
+
+    fn attach_all(&self, gl: &mut Wrapper, framebuffer: &mut Framebuffer) -> Result<(), Error> {
+        framebuffer.bind(gl)?;
+
+        if let Some(attachment) = self.color {
+            framebuffer.color = Some(self.attach(gl, attachment, FrameBufferAttachmentType::COLOR_ATTACHMENT0)?);
+        }
+        if let Some(attachment) = self.depth {
+            framebuffer.depth = Some(self.attach(gl, attachment, FrameBufferAttachmentType::DEPTH_ATTACHMENT)?);
+        }
+        if let Some(attachment) = self.stencil {
+            framebuffer.stencil = Some(self.attach(gl, attachment, FrameBufferAttachmentType::STENCIL_ATTACHMENT)?);
+        }
+
+        check_complete(gl, &framebuffer.textures())
+    }
+
+    fn attach(&self, gl: &mut Wrapper, attachment: Attachment, point: FrameBufferAttachmentType)
+              -> Result<AttachedImage, Error> {
+        let (width, height) = attachment.size.unwrap_or((self.width, self.height));
+
+        match attachment.storage {
+            Storage::Texture(format, type_) => {
+                let texture = TextureBuilder::texture_2d(width, height)
+                    .format(format, type_)
+                    .sampler(SamplerSettings::linear())
+                    .build(gl)?;
+
+                gl.gl_framebuffer_texture_2d(FrameBufferTarget::FrameBuffer, point, TextureTarget::TEXTURE_2D,
+                                             texture.id, 0)?;
+                Ok(AttachedImage::Texture(texture))
+            }
+            Storage::Renderbuffer(format) => {
+                let renderbuffer = Renderbuffer::new(gl, format, width, height)?;
+                gl.gl_framebuffer_renderbuffer(FrameBufferTarget::FrameBuffer, point,
+                                               RenderBufferTarget::RENDERBUFFER, renderbuffer.id)?;
+                Ok(AttachedImage::Renderbuffer(renderbuffer))
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.color.is_none() && self.depth.is_none() && self.stencil.is_none() {
+            return Err(Error::new(ErrorKind::InvalidArgument, "a framebuffer needs at least one attachment"));
+        }
+
+        let attachments = [("color", self.color), ("depth", self.depth), ("stencil", self.stencil)];
+        for &(name, attachment) in &attachments {
+            let attachment = match attachment {
+                Some(attachment) => attachment,
+                None => continue,
+            };
+
+            let (width, height) = attachment.size.unwrap_or((self.width, self.height));
+            if width == 0 || height == 0 {
+                return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                    "the {} attachment has a zero size", name)));
+            }
+
+            let renderable = match (name, attachment.storage) {
+                ("color", Storage::Texture(format, type_)) => {
+                    FormatInfo::for_transfer(format as GLenum, type_ as GLenum)
+                        .is_some_and(|info| info.color_renderable.is_some())
+                }
+                ("color", Storage::Renderbuffer(format)) => {
+                    [PixelFormat::RGBA4, PixelFormat::RGB565, PixelFormat::RGB5_A1].contains(&format)
+                }
+                ("depth", Storage::Renderbuffer(format)) => format == PixelFormat::DEPTH_COMPONENT16,
+                ("stencil", Storage::Renderbuffer(format)) => format == PixelFormat::STENCIL_INDEX8,
+                _ => false,
+            };
+
+            if !renderable {
+                return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                    "{:?} can not be used as the {} attachment", attachment.storage, name)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// DIAGNOSTICS
+// -------------------------------------------------------------------------------------------------
+
+/// What the driver reports at one attachment point.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AttachedObject {
+    None,
+    Texture { name: u32, level: i32, face: GLenum },
+    Renderbuffer { name: u32 },
+}
+
+/// One attachment point of the bound framebuffer.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AttachmentState {
+    pub point: FrameBufferAttachmentType,
+    pub object: AttachedObject,
+    /// Size and format of the attached image. Renderbuffers are queried from the driver; ES 2.0
+    /// can not query textures, so these are only known for textures passed to `diagnose`.
+    pub size: Option<(u32, u32)>,
+    pub format: Option<&'static FormatInfo>,
+}
+
+/// A reason for a framebuffer to be incomplete.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Problem {
+    /// Nothing is attached at all.
+    MissingAttachment,
+    /// A renderbuffer without storage, or a texture level with a zero size or outside the texture's
+    /// mip chain.
+    EmptyImage(FrameBufferAttachmentType),
+    /// The format, given as its internal format, can not be rendered to at this attachment point.
+    NotRenderable(FrameBufferAttachmentType, GLenum),
+    /// Two attachments differ in size, which ES 2.0 does not allow.
+    SizeMismatch {
+        first: (FrameBufferAttachmentType, u32, u32),
+        second: (FrameBufferAttachmentType, u32, u32),
+    },
+    /// Depth and stencil are separate images. Many drivers only support both as one packed
+    /// `DEPTH24_STENCIL8_OES` image attached to both points.
+    SeparateDepthStencil,
+    /// The attachments have different sample counts.
+    SampleCountMismatch,
+    /// None of the attachments explains the status.
+    Unexplained(FrameBufferStatus),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Problem::MissingAttachment => write!(f, "nothing is attached"),
+            Problem::EmptyImage(point) => write!(f, "the image at {:?} has a zero size", point),
+            Problem::NotRenderable(point, format) => {
+                write!(f, "{} can not be rendered to at {:?}", enum_name(format).unwrap_or("the format"), point)
+            }
+            Problem::SizeMismatch { first, second } => {
+                write!(f, "{:?} is {}x{} but {:?} is {}x{}", second.0, second.1, second.2, first.0, first.1, first.2)
+            }
+            Problem::SeparateDepthStencil => {
+                write!(f, "separate depth and stencil images are not supported, use one packed depth/stencil image")
+            }
+            Problem::SampleCountMismatch => write!(f, "the attachments have different sample counts"),
+            Problem::Unexplained(status) => write!(f, "no attachment explains {:?}", status),
+        }
+    }
+}
+
+/// The completeness status of a framebuffer, what is attached to it and why it is incomplete.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnosis {
+    pub status: FrameBufferStatus,
+    pub attachments: Vec<AttachmentState>,
+    /// Empty for complete framebuffers.
+    pub problems: Vec<Problem>,
+}
+
+impl Diagnosis {
+    pub fn is_complete(&self) -> bool {
+        self.status == FrameBufferStatus::FRAMEBUFFER_COMPLETE
+    }
+}
+
+impl fmt::Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.status)?;
+        for (index, problem) in self.problems.iter().enumerate() {
+            write!(f, "{}{}", if index == 0 { ": " } else { "; " }, problem)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the bound framebuffer and explains why it is incomplete.
+///
+/// Each attachment point is walked with `gl_get_framebuffer_attachment_parameteriv`, and attached
+/// renderbuffers are bound to query their size and format, so the renderbuffer binding changes.
+/// Textures in `textures` contribute their size and format; other attached textures are only
+/// identified by name.
+pub fn diagnose(gl: &mut Wrapper, textures: &[&Texture]) -> Result<Diagnosis, Error> {
+    let status = gl.gl_check_framebuffer_status(FrameBufferTarget::FrameBuffer)?;
+
+    let mut attachments = Vec::new();
+    for &point in &ATTACHMENT_POINTS {
+        attachments.push(attachment_state(gl, point, textures)?);
+    }
+
+    let problems = if status == FrameBufferStatus::FRAMEBUFFER_COMPLETE {
+        Vec::new()
+    } else {
+        find_problems(status, &attachments)
+    };
+
+    Ok(Diagnosis { status, attachments, problems })
+}
+
+const ATTACHMENT_POINTS: [FrameBufferAttachmentType; 3] = [
+    FrameBufferAttachmentType::COLOR_ATTACHMENT0,
+    FrameBufferAttachmentType::DEPTH_ATTACHMENT,
+    FrameBufferAttachmentType::STENCIL_ATTACHMENT,
+];
+
+fn attachment_state(gl: &mut Wrapper, point: FrameBufferAttachmentType, textures: &[&Texture])
+                    -> Result<AttachmentState, Error> {
+    let query = |gl: &mut Wrapper, name| {
+        gl.gl_get_framebuffer_attachment_parameteriv(FrameBufferTarget::FrameBuffer, point, name)
+    };
+
+    let object_type = query(gl, FrameBufferAttachmentParamType::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE)? as GLenum;
+    let mut state = AttachmentState { point, object: AttachedObject::None, size: None, format: None };
+    if object_type == GL_NONE {
+        return Ok(state);
+    }
+
+    let name = query(gl, FrameBufferAttachmentParamType::FRAMEBUFFER_ATTACHMENT_OBJECT_NAME)? as u32;
+
+    if object_type == GL_RENDERBUFFER {
+        state.object = AttachedObject::Renderbuffer { name };
+
+        gl.gl_bind_renderbuffer(RenderBufferTarget::RENDERBUFFER, name)?;
+        let width = gl.gl_get_renderbuffer_parameteriv(RenderBufferTarget::RENDERBUFFER,
+                                                       RenderBufferParamType::RENDERBUFFER_WIDTH)?;
+        let height = gl.gl_get_renderbuffer_parameteriv(RenderBufferTarget::RENDERBUFFER,
+                                                        RenderBufferParamType::RENDERBUFFER_HEIGHT)?;
+        let internal_format = gl.gl_get_renderbuffer_parameteriv(RenderBufferTarget::RENDERBUFFER,
+                                                                 RenderBufferParamType::RENDERBUFFER_INTERNAL_FORMAT)?;
+
+        state.size = Some((width.max(0) as u32, height.max(0) as u32));
+        // A renderbuffer without storage reports the initial `GL_RGBA4`, which says nothing.
+        if width > 0 && height > 0 {
+            state.format = FormatInfo::for_internal_format(internal_format as GLenum);
+        }
+    } else {
+        let level = query(gl, FrameBufferAttachmentParamType::FRAMEBUFFER_ATTACHMENT_TEXTURE_LEVEL)?;
+        let face = query(gl, FrameBufferAttachmentParamType::FRAMEBUFFER_ATTACHMENT_TEXTURE_CUBE_MAP_FACE)? as GLenum;
+        state.object = AttachedObject::Texture { name, level, face };
+
+        if let Some(texture) = textures.iter().find(|texture| texture.id == name) {
+            // A level the texture can not have, negative or past its 1x1 level, counts as empty.
+            let shift = |value: u32| if level < 0 { None } else { value.checked_shr(level as u32) };
+            let size = shift(texture.width).zip(shift(texture.height));
+            state.size = Some(match size {
+                Some((width, height)) if width > 0 || height > 0 => (width.max(1), height.max(1)),
+                _ => (0, 0),
+            });
+            state.format = FormatInfo::lookup(texture.format as GLenum, texture.format as GLenum,
+                                              texture.type_ as GLenum);
+        }
+    }
+
+    Ok(state)
+}
+
+fn find_problems(status: FrameBufferStatus, attachments: &[AttachmentState]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let attached: Vec<&AttachmentState> = attachments.iter()
+        .filter(|state| state.object != AttachedObject::None)
+        .collect();
+
+    if attached.is_empty() {
+        problems.push(Problem::MissingAttachment);
+    }
+
+    let mut first_size = None;
+    for state in &attached {
+        if let Some((width, height)) = state.size {
+            if width == 0 || height == 0 {
+                problems.push(Problem::EmptyImage(state.point));
+            } else if let Some(first) = first_size {
+                let (_, first_width, first_height) = first;
+                if (width, height) != (first_width, first_height) {
+                    problems.push(Problem::SizeMismatch { first, second: (state.point, width, height) });
+                }
+            } else {
+                first_size = Some((state.point, width, height));
+            }
+        }
+
+        if let Some(info) = state.format {
+            let renderable = match state.point {
+                FrameBufferAttachmentType::COLOR_ATTACHMENT0 => info.color_renderable.is_some(),
+                FrameBufferAttachmentType::DEPTH_ATTACHMENT => info.depth_bits > 0,
+                FrameBufferAttachmentType::STENCIL_ATTACHMENT => info.stencil_bits > 0,
+            };
+            if !renderable {
+                problems.push(Problem::NotRenderable(state.point, info.internal_format));
+            }
+        }
+    }
+
+    let object = |point| attachments.iter().find(|state| state.point == point).map(|state| state.object);
+    let depth = object(FrameBufferAttachmentType::DEPTH_ATTACHMENT);
+    let stencil = object(FrameBufferAttachmentType::STENCIL_ATTACHMENT);
+    if status == FrameBufferStatus::FRAMEBUFFER_UNSUPPORTED && depth != Some(AttachedObject::None)
+        && stencil != Some(AttachedObject::None) && depth != stencil {
+        problems.push(Problem::SeparateDepthStencil);
+    }
+
+    if status == FrameBufferStatus::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE {
+        problems.push(Problem::SampleCountMismatch);
+    }
+
+    if problems.is_empty() {
+        problems.push(Problem::Unexplained(status));
+    }
+    problems
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+// Checks the bound framebuffer and turns an incomplete status into an error with a diagnosis.
+fn check_complete(gl: &mut Wrapper, textures: &[&Texture]) -> Result<(), Error> {
+    if gl.gl_check_framebuffer_status(FrameBufferTarget::FrameBuffer)? == FrameBufferStatus::FRAMEBUFFER_COMPLETE {
+        return Ok(());
+    }
+
+    let diagnosis = diagnose(gl, textures)?;
+    let kind = match diagnosis.status {
+        FrameBufferStatus::FRAMEBUFFER_UNSUPPORTED => ErrorKind::Unsupported,
+        _ => ErrorKind::InvalidArgument,
+    };
+    Err(Error::new(kind, format!("the framebuffer is incomplete, {}", diagnosis)))
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enums::StateType;
+    use es20::software::SoftwareBackend;
+
+    fn software() -> Wrapper {
+        Wrapper::with_backend(Box::new(SoftwareBackend::new(4, 4)))
+    }
+
+    #[test]
+    fn builds_a_complete_framebuffer_that_can_be_rendered_to() {
+        let mut gl = software();
+
+        let mut framebuffer = FramebufferBuilder::new(4, 2)
+            .color(Attachment::texture(PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE))
+            .depth(Attachment::renderbuffer(PixelFormat::DEPTH_COMPONENT16))
+            .build(&mut gl)
+            .unwrap();
+
+        assert_eq!(gl.gl_get_integerv(StateType::FRAMEBUFFER_BINDING).unwrap(), framebuffer.id as i32);
+        assert_eq!(framebuffer.status(&mut gl).unwrap(), FrameBufferStatus::FRAMEBUFFER_COMPLETE);
+        assert_eq!(framebuffer.color_texture().map(|texture| (texture.width, texture.height)), Some((4, 2)));
+
+        gl.gl_clear_color(1.0, 0.0, 0.0, 1.0).unwrap();
+        gl.gl_clear(GL_COLOR_BUFFER_BIT).unwrap();
+        let mut pixels = vec![0u8; 4 * 2 * 4];
+        gl.gl_read_pixels(0, 0, 4, 2, PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE, &mut pixels).unwrap();
+        assert!(pixels.chunks(4).all(|pixel| pixel == [255, 0, 0, 255]));
+
+        framebuffer.resize(&mut gl, 8, 8).unwrap();
+        assert_eq!(framebuffer.color.as_ref().map(|image| (image.width(), image.height())), Some((8, 8)));
+        assert_eq!(framebuffer.depth.as_ref().map(|image| (image.width(), image.height())), Some((8, 8)));
+
+        let (id, texture) = (framebuffer.id, framebuffer.color_texture().unwrap().id);
+        Framebuffer::unbind(&mut gl).unwrap();
+        framebuffer.delete(&mut gl).unwrap();
+        assert!(!gl.gl_is_framebuffer(id).unwrap());
+        assert!(!gl.gl_is_texture(texture).unwrap());
+    }
+
+    #[test]
+    fn rejects_attachments_that_can_not_be_rendered_to() {
+        let mut gl = software();
+
+        let error = FramebufferBuilder::new(4, 4).build(&mut gl).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidArgument);
+
+        let error = FramebufferBuilder::new(4, 4)
+            .depth(Attachment::texture(PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE))
+            .build(&mut gl)
+            .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidArgument);
+
+        let error = FramebufferBuilder::new(4, 4)
+            .color(Attachment::renderbuffer(PixelFormat::RGBA))
+            .build(&mut gl)
+            .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidArgument);
+
+        let error = FramebufferBuilder::new(4, 4)
+            .color(Attachment::renderbuffer(PixelFormat::RGBA4).size(0, 4))
+            .build(&mut gl)
+            .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidArgument);
+
+        // Nothing reached the driver.
+        assert!(!gl.gl_is_framebuffer(1).unwrap());
+    }
+
+    #[test]
+    fn deletes_everything_when_the_result_is_incomplete() {
+        let mut gl = software();
+
+        let error = FramebufferBuilder::new(4, 4)
+            .color(Attachment::texture(PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE))
+            .depth(Attachment::renderbuffer(PixelFormat::DEPTH_COMPONENT16).size(2, 2))
+            .build(&mut gl)
+            .unwrap_err();
+
+        assert_eq!(error.kind, ErrorKind::InvalidArgument);
+        assert!(error.message.contains("FRAMEBUFFER_INCOMPLETE_DIMENSIONS"), "{}", error.message);
+        assert_eq!(gl.gl_get_integerv(StateType::FRAMEBUFFER_BINDING).unwrap(), 0);
+        assert!(!gl.gl_is_framebuffer(1).unwrap());
+        assert!(!gl.gl_is_texture(1).unwrap());
+        assert!(!gl.gl_is_renderbuffer(1).unwrap());
+    }
+
+    #[test]
+    fn diagnoses_mismatched_sizes() {
+        let mut gl = software();
+        let texture = TextureBuilder::texture_2d(4, 4).build(&mut gl).unwrap();
+        let renderbuffer = Renderbuffer::new(&mut gl, PixelFormat::DEPTH_COMPONENT16, 2, 2).unwrap();
+
+        let framebuffer = gl.gl_gen_framebuffers(1).unwrap()[0];
+        gl.gl_bind_framebuffer(FrameBufferTarget::FrameBuffer, framebuffer).unwrap();
+        gl.gl_framebuffer_texture_2d(FrameBufferTarget::FrameBuffer, FrameBufferAttachmentType::COLOR_ATTACHMENT0,
+                                     TextureTarget::TEXTURE_2D, texture.id, 0).unwrap();
+        gl.gl_framebuffer_renderbuffer(FrameBufferTarget::FrameBuffer, FrameBufferAttachmentType::DEPTH_ATTACHMENT,
+                                       RenderBufferTarget::RENDERBUFFER, renderbuffer.id).unwrap();
+
+        let diagnosis = diagnose(&mut gl, &[&texture]).unwrap();
+        assert_eq!(diagnosis.status, FrameBufferStatus::FRAMEBUFFER_INCOMPLETE_DIMENSIONS);
+        assert_eq!(diagnosis.attachments[0].object, AttachedObject::Texture { name: texture.id, level: 0, face: 0 });
+        assert_eq!(diagnosis.attachments[1].object, AttachedObject::Renderbuffer { name: renderbuffer.id });
+        assert_eq!(diagnosis.attachments[1].size, Some((2, 2)));
+        assert_eq!(diagnosis.attachments[2].object, AttachedObject::None);
+        assert_eq!(diagnosis.problems, vec![Problem::SizeMismatch {
+            first: (FrameBufferAttachmentType::COLOR_ATTACHMENT0, 4, 4),
+            second: (FrameBufferAttachmentType::DEPTH_ATTACHMENT, 2, 2),
+        }]);
+        assert_eq!(diagnosis.to_string(), "FRAMEBUFFER_INCOMPLETE_DIMENSIONS: DEPTH_ATTACHMENT is 2x2 but \
+                                           COLOR_ATTACHMENT0 is 4x4");
+
+        // Textures that were not passed in have no size to compare.
+        let unknown = diagnose(&mut gl, &[]).unwrap();
+        assert_eq!(unknown.attachments[0].size, None);
+        assert_eq!(unknown.problems, vec![Problem::Unexplained(FrameBufferStatus::FRAMEBUFFER_INCOMPLETE_DIMENSIONS)]);
+    }
+
+    #[test]
+    fn diagnoses_missing_and_empty_images() {
+        let mut gl = software();
+
+        let framebuffer = gl.gl_gen_framebuffers(1).unwrap()[0];
+        gl.gl_bind_framebuffer(FrameBufferTarget::FrameBuffer, framebuffer).unwrap();
+        let diagnosis = diagnose(&mut gl, &[]).unwrap();
+        assert_eq!(diagnosis.problems, vec![Problem::MissingAttachment]);
+        assert!(!diagnosis.is_complete());
+
+        // A renderbuffer without storage.
+        let renderbuffer = gl.gl_gen_renderbuffers(1).unwrap()[0];
+        gl.gl_bind_renderbuffer(RenderBufferTarget::RENDERBUFFER, renderbuffer).unwrap();
+        gl.gl_framebuffer_renderbuffer(FrameBufferTarget::FrameBuffer, FrameBufferAttachmentType::DEPTH_ATTACHMENT,
+                                       RenderBufferTarget::RENDERBUFFER, renderbuffer).unwrap();
+        let diagnosis = diagnose(&mut gl, &[]).unwrap();
+        assert_eq!(diagnosis.status, FrameBufferStatus::FRAMEBUFFER_INCOMPLETE_ATTACHMENT);
+        assert_eq!(diagnosis.problems, vec![Problem::EmptyImage(FrameBufferAttachmentType::DEPTH_ATTACHMENT)]);
+    }
+
+    #[test]
+    fn complete_framebuffers_have_no_problems() {
+        let mut gl = software();
+        let framebuffer = FramebufferBuilder::new(2, 2)
+            .color(Attachment::renderbuffer(PixelFormat::RGB565))
+            .stencil(Attachment::renderbuffer(PixelFormat::STENCIL_INDEX8))
+            .build(&mut gl)
+            .unwrap();
+
+        let diagnosis = framebuffer.diagnose(&mut gl).unwrap();
+        assert!(diagnosis.is_complete());
+        assert!(diagnosis.problems.is_empty());
+        assert_eq!(diagnosis.attachments[2].format.map(|info| info.internal_format), Some(GL_STENCIL_INDEX8));
+        assert_eq!(diagnosis.to_string(), "FRAMEBUFFER_COMPLETE");
+    }
+}
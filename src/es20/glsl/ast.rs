@@ -0,0 +1,212 @@
+use super::Location;
+
+// The syntax tree the parser builds, before names and types are resolved.
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum Precision {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum StorageQualifier {
+    None,
+    Const,
+    Attribute,
+    Uniform,
+    Varying,
+    // The GLSL ES 3.00 shader inputs and outputs.
+    In,
+    Out,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum Interpolation {
+    Smooth,
+    Flat,
+}
+
+// One entry of a `layout(...)` qualifier, such as `location = 2` or `std140`.
+#[derive(Clone, Debug)]
+pub(super) struct LayoutQualifier {
+    pub name: String,
+    pub value: Option<i64>,
+    pub location: Location,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Qualifiers {
+    pub layout: Vec<LayoutQualifier>,
+    pub invariant: bool,
+    pub interpolation: Option<Interpolation>,
+    pub centroid: bool,
+    pub storage: StorageQualifier,
+    // Where the first qualifier is.
+    pub location: Location,
+}
+
+impl Qualifiers {
+    // Whether there are qualifiers other than the storage qualifier.
+    pub fn has_auxiliary(&self) -> bool {
+        !self.layout.is_empty() || self.invariant || self.interpolation.is_some() || self.centroid
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum ParameterQualifier {
+    In,
+    Out,
+    InOut,
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum TypeName {
+    // A basic type keyword, such as `vec3` or `sampler2D`.
+    Keyword(String),
+    Struct(StructDefinition),
+    // A reference to a structure declared earlier.
+    Named(String),
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct TypeSpecifier {
+    pub precision: Option<Precision>,
+    pub name: TypeName,
+    // The size of array types such as `float[3]`, which may be left out in constructors.
+    pub array: Option<ArraySize>,
+    pub location: Location,
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum ArraySize {
+    Sized(Box<Expression>),
+    // `[]`, sized by an initializer or the arguments of a constructor.
+    Unsized,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct StructDefinition {
+    // `None` for anonymous structures.
+    pub name: Option<String>,
+    pub fields: Vec<FieldDeclaration>,
+    pub location: Location,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct FieldDeclaration {
+    // Only uniform block members may have layout qualifiers.
+    pub layout: Vec<LayoutQualifier>,
+    pub type_: TypeSpecifier,
+    pub names: Vec<(String, Option<Expression>, Location)>,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Declaration {
+    pub qualifiers: Qualifiers,
+    pub type_: TypeSpecifier,
+    pub variables: Vec<VariableDeclaration>,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct VariableDeclaration {
+    pub name: String,
+    pub array_size: Option<ArraySize>,
+    pub initializer: Option<Expression>,
+    pub location: Location,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Parameter {
+    pub qualifier: ParameterQualifier,
+    pub constant: bool,
+    pub type_: TypeSpecifier,
+    pub name: Option<String>,
+    pub array_size: Option<Expression>,
+    pub location: Location,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Function {
+    pub return_type: TypeSpecifier,
+    pub name: String,
+    pub parameters: Vec<Parameter>,
+    // `None` for prototypes.
+    pub body: Option<Vec<Statement>>,
+    pub location: Location,
+}
+
+// A GLSL ES 3.00 uniform block.
+#[derive(Clone, Debug)]
+pub(super) struct Block {
+    pub qualifiers: Qualifiers,
+    pub name: String,
+    pub fields: Vec<FieldDeclaration>,
+    // The instance name and array size, without which the fields are global names.
+    pub instance: Option<(String, Option<Expression>, Location)>,
+    pub location: Location,
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum ExternalDeclaration {
+    Declaration(Declaration),
+    Function(Function),
+    Precision(Precision, TypeSpecifier),
+    // `invariant name, ...;` for varyings declared earlier.
+    Invariant(Vec<(String, Location)>),
+    Block(Block),
+    // `layout(...) uniform;`, which sets defaults for the blocks that follow.
+    DefaultLayout(Qualifiers),
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum Statement {
+    Block(Vec<Statement>),
+    Declaration(Declaration),
+    Precision(Precision, TypeSpecifier),
+    Expression(Expression),
+    If(Expression, Box<Statement>, Option<Box<Statement>>),
+    For(Option<Box<Statement>>, Option<Expression>, Option<Expression>, Box<Statement>),
+    While(Expression, Box<Statement>),
+    DoWhile(Box<Statement>, Expression),
+    Switch(Expression, Vec<Statement>),
+    // A `case` label, or `default` without a value.
+    Case(Option<Expression>, Location),
+    Return(Option<Expression>, Location),
+    Break(Location),
+    Continue(Location),
+    Discard(Location),
+    Empty,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Expression {
+    pub kind: ExpressionKind,
+    pub location: Location,
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum ExpressionKind {
+    Identifier(String),
+    IntLiteral(i64),
+    UintLiteral(u32),
+    FloatLiteral(f32),
+    BoolLiteral(bool),
+    // A function call, or a constructor of a structure the parser did not know to be one.
+    Call(String, Vec<Expression>),
+    // A constructor, by type specifier.
+    Construct(TypeSpecifier, Vec<Expression>),
+    // Structure field selection or a vector swizzle.
+    Field(Box<Expression>, String),
+    // `.length()` of an array.
+    Length(Box<Expression>),
+    Index(Box<Expression>, Box<Expression>),
+    Unary(&'static str, Box<Expression>),
+    // `++` and `--` after the operand.
+    Postfix(&'static str, Box<Expression>),
+    Binary(&'static str, Box<Expression>, Box<Expression>),
+    // `=` or a compound assignment operator.
+    Assign(&'static str, Box<Expression>, Box<Expression>),
+    Conditional(Box<Expression>, Box<Expression>, Box<Expression>),
+    Sequence(Box<Expression>, Box<Expression>),
+}
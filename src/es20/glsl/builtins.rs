@@ -0,0 +1,406 @@
+use enums::ShaderType;
+
+use super::ir::{Builtin, LodArgument, Texture};
+use super::types::*;
+use super::GlslVersion;
+
+// Names of the built-in functions of GLSL ES 1.00, which shaders can neither redefine nor overload.
+const FUNCTIONS: [&str; 55] = [
+    "radians", "degrees", "sin", "cos", "tan", "asin", "acos", "atan", "pow", "exp", "log", "exp2", "log2",
+    "sqrt", "inversesqrt", "abs", "sign", "floor", "ceil", "fract", "mod", "min", "max", "clamp", "mix", "step",
+    "smoothstep", "length", "distance", "dot", "cross", "normalize", "faceforward", "reflect", "refract",
+    "matrixCompMult", "lessThan", "lessThanEqual", "greaterThan", "greaterThanEqual", "equal", "notEqual", "any",
+    "all", "not", "texture2D", "texture2DProj", "texture2DLod", "texture2DProjLod", "textureCube",
+    "textureCubeLod", "dFdx", "dFdy", "fwidth", "texture2DRect",
+];
+
+// Names of the built-in functions of GLSL ES 3.00, which drops the 1.00 texture functions.
+const FUNCTIONS_300: [&str; 89] = [
+    "radians", "degrees", "sin", "cos", "tan", "asin", "acos", "atan", "sinh", "cosh", "tanh", "asinh", "acosh",
+    "atanh", "pow", "exp", "log", "exp2", "log2", "sqrt", "inversesqrt", "abs", "sign", "floor", "trunc", "round",
+    "roundEven", "ceil", "fract", "mod", "modf", "min", "max", "clamp", "mix", "step", "smoothstep", "isnan",
+    "isinf", "floatBitsToInt", "floatBitsToUint", "intBitsToFloat", "uintBitsToFloat", "packSnorm2x16",
+    "unpackSnorm2x16", "packUnorm2x16", "unpackUnorm2x16", "packHalf2x16", "unpackHalf2x16", "length", "distance",
+    "dot", "cross", "normalize", "faceforward", "reflect", "refract", "matrixCompMult", "outerProduct", "transpose",
+    "determinant", "inverse", "lessThan", "lessThanEqual", "greaterThan", "greaterThanEqual", "equal", "notEqual",
+    "any", "all", "not", "textureSize", "texture", "textureProj", "textureLod", "textureOffset", "texelFetch",
+    "texelFetchOffset", "textureProjOffset", "textureLodOffset", "textureProjLod", "textureProjLodOffset",
+    "textureGrad", "textureGradOffset", "textureProjGrad", "textureProjGradOffset", "dFdx", "dFdy", "fwidth",
+];
+
+// Argument patterns of component-wise functions.
+const G: bool = true;
+const F: bool = false;
+
+pub(super) fn is_builtin_function(name: &str, version: GlslVersion) -> bool {
+    match version {
+        GlslVersion::Es100 => FUNCTIONS.contains(&name),
+        GlslVersion::Es300 => FUNCTIONS_300.contains(&name),
+    }
+}
+
+// The result type and implementation of the overload of built-in function `name` that takes
+// `arguments`, or `None` when there is no such overload in this stage and version.
+//
+// `modf` has an output parameter, which the caller stores to; the implementation here yields the
+// fractional part.
+pub(super) fn builtin_function(name: &str, arguments: &[Type], stage: ShaderType, version: GlslVersion)
+    -> Option<(Type, Builtin)> {
+    let vertex = stage == ShaderType::VERTEX_SHADER;
+    let es300 = version == GlslVersion::Es300;
+    let map = |function: fn(f32) -> f32| component_wise(arguments, &[&[G]]).map(|type_| (type_, Builtin::Map(function)));
+    let signed: &[Scalar] = if es300 { &[Scalar::Float, Scalar::Int] } else { &[Scalar::Float] };
+    let integers: &[Scalar] = if es300 { &[Scalar::Float, Scalar::Int, Scalar::Uint] } else { &[Scalar::Float] };
+
+    match name {
+        "radians" => map(|x| x.to_radians()),
+        "degrees" => map(|x| x.to_degrees()),
+        "sin" => map(f32::sin),
+        "cos" => map(f32::cos),
+        "tan" => map(f32::tan),
+        "asin" => map(f32::asin),
+        "acos" => map(f32::acos),
+        "atan" if arguments.len() == 2 => map2(arguments, &[&[G, G]], f32::atan2),
+        "atan" => map(f32::atan),
+        "sinh" => map(f32::sinh),
+        "cosh" => map(f32::cosh),
+        "tanh" => map(f32::tanh),
+        "asinh" => map(f32::asinh),
+        "acosh" => map(f32::acosh),
+        "atanh" => map(f32::atanh),
+        "pow" => map2(arguments, &[&[G, G]], f32::powf),
+        "exp" => map(f32::exp),
+        "log" => map(f32::ln),
+        "exp2" => map(f32::exp2),
+        "log2" => map(f32::log2),
+        "sqrt" => map(f32::sqrt),
+        "inversesqrt" => map(|x| 1.0 / x.sqrt()),
+        "abs" => generic(arguments, &[&[G]], signed).map(|type_| (type_, Builtin::Map(f32::abs))),
+        "sign" => generic(arguments, &[&[G]], signed)
+            .map(|type_| (type_, Builtin::Map(|x| if x > 0.0 { 1.0 } else if x < 0.0 { -1.0 } else { 0.0 }))),
+        "floor" => map(f32::floor),
+        "trunc" => map(f32::trunc),
+        "round" => map(f32::round),
+        "roundEven" => map(f32::round_ties_even),
+        "ceil" => map(f32::ceil),
+        "fract" => map(|x| x - x.floor()),
+        "mod" => map2(arguments, &[&[G, G], &[G, F]], |x, y| x - y * (x / y).floor()),
+        "modf" => map2(arguments, &[&[G, G]], |x, _| x - x.trunc()),
+        "min" => generic(arguments, &[&[G, G], &[G, F]], integers).map(|type_| (type_, Builtin::Map2(f32::min))),
+        "max" => generic(arguments, &[&[G, G], &[G, F]], integers).map(|type_| (type_, Builtin::Map2(f32::max))),
+        "clamp" => generic(arguments, &[&[G, G, G], &[G, F, F]], integers)
+            .map(|type_| (type_, Builtin::Map3(|x, low, high| x.max(low).min(high)))),
+        "mix" if es300 && matches!(arguments.get(2), Some(&Type::Vector(Scalar::Bool, _))) => match arguments {
+            [Type::Vector(Scalar::Float, size), y, Type::Vector(Scalar::Bool, selectors)]
+                if *y == arguments[0] && selectors == size => {
+                Some((arguments[0].clone(), Builtin::Map3(|x, y, a| if a != 0.0 { y } else { x })))
+            }
+            _ => None,
+        },
+        "mix" => map3(arguments, &[&[G, G, G], &[G, G, F]], |x, y, a| x * (1.0 - a) + y * a),
+        "step" => map2(arguments, &[&[G, G], &[F, G]], |edge, x| if x < edge { 0.0 } else { 1.0 }),
+        "smoothstep" => map3(arguments, &[&[G, G, G], &[F, F, G]], |low, high, x| {
+            let t = ((x - low) / (high - low)).clamp(0.0, 1.0);
+            t * t * (3.0 - 2.0 * t)
+        }),
+        "isnan" => convert(arguments, Scalar::Float, Scalar::Bool, |x| x.is_nan() as i32 as f32),
+        "isinf" => convert(arguments, Scalar::Float, Scalar::Bool, |x| x.is_infinite() as i32 as f32),
+        "floatBitsToInt" => convert(arguments, Scalar::Float, Scalar::Int, |x| x.to_bits() as i32 as f32),
+        "floatBitsToUint" => convert(arguments, Scalar::Float, Scalar::Uint, |x| x.to_bits() as f32),
+        "intBitsToFloat" => convert(arguments, Scalar::Int, Scalar::Float, |x| f32::from_bits(x as i32 as u32)),
+        "uintBitsToFloat" => convert(arguments, Scalar::Uint, Scalar::Float, |x| f32::from_bits(x as u32)),
+        "packSnorm2x16" => pack(arguments, |[x, y]| snorm(x) | snorm(y) << 16),
+        "unpackSnorm2x16" => unpack(arguments, |bits| [from_snorm(bits), from_snorm(bits >> 16)]),
+        "packUnorm2x16" => pack(arguments, |[x, y]| unorm(x) | unorm(y) << 16),
+        "unpackUnorm2x16" => unpack(arguments, |bits| [(bits & 0xffff) as f32 / 65535.0, (bits >> 16) as f32 / 65535.0]),
+        "packHalf2x16" => pack(arguments, |[x, y]| to_half(x) | to_half(y) << 16),
+        "unpackHalf2x16" => unpack(arguments, |bits| [from_half(bits & 0xffff), from_half(bits >> 16)]),
+        "length" => component_wise(arguments, &[&[G]]).map(|_| (FLOAT, Builtin::Length)),
+        "distance" => component_wise(arguments, &[&[G, G]]).map(|_| (FLOAT, Builtin::Distance)),
+        "dot" => component_wise(arguments, &[&[G, G]]).map(|_| (FLOAT, Builtin::Dot)),
+        "cross" => component_wise(arguments, &[&[G, G]])
+            .filter(|type_| *type_ == Type::Vector(Scalar::Float, 3))
+            .map(|type_| (type_, Builtin::Cross)),
+        "normalize" => component_wise(arguments, &[&[G]]).map(|type_| (type_, Builtin::Normalize)),
+        "faceforward" => component_wise(arguments, &[&[G, G, G]]).map(|type_| (type_, Builtin::FaceForward)),
+        "reflect" => component_wise(arguments, &[&[G, G]]).map(|type_| (type_, Builtin::Reflect)),
+        "refract" => component_wise(arguments, &[&[G, G, F]]).map(|type_| (type_, Builtin::Refract)),
+        "matrixCompMult" => match arguments {
+            [Type::Matrix(..), other] if *other == arguments[0] => {
+                Some((arguments[0].clone(), Builtin::Map2(|a, b| a * b)))
+            }
+            _ => None,
+        },
+        "outerProduct" => match *arguments {
+            [Type::Vector(Scalar::Float, rows), Type::Vector(Scalar::Float, columns)] if rows > 1 && columns > 1 => {
+                Some((Type::Matrix(columns, rows), Builtin::OuterProduct))
+            }
+            _ => None,
+        },
+        "transpose" => match *arguments {
+            [Type::Matrix(columns, rows)] => Some((Type::Matrix(rows, columns), Builtin::Transpose(columns, rows))),
+            _ => None,
+        },
+        "determinant" => match *arguments {
+            [Type::Matrix(columns, rows)] if columns == rows => Some((FLOAT, Builtin::Determinant(columns))),
+            _ => None,
+        },
+        "inverse" => match *arguments {
+            [Type::Matrix(columns, rows)] if columns == rows => Some((arguments[0].clone(), Builtin::Inverse(columns))),
+            _ => None,
+        },
+        "lessThan" => compare(arguments, false, |a, b| (a < b) as i32 as f32),
+        "lessThanEqual" => compare(arguments, false, |a, b| (a <= b) as i32 as f32),
+        "greaterThan" => compare(arguments, false, |a, b| (a > b) as i32 as f32),
+        "greaterThanEqual" => compare(arguments, false, |a, b| (a >= b) as i32 as f32),
+        "equal" => compare(arguments, true, |a, b| (a == b) as i32 as f32),
+        "notEqual" => compare(arguments, true, |a, b| (a != b) as i32 as f32),
+        "any" | "all" | "not" => match arguments {
+            [Type::Vector(Scalar::Bool, size)] if *size > 1 => Some(match name {
+                "any" => (BOOL, Builtin::Any),
+                "all" => (BOOL, Builtin::All),
+                _ => (arguments[0].clone(), Builtin::Map(|x| (x == 0.0) as i32 as f32)),
+            }),
+            _ => None,
+        },
+        // GLSL ES 1.00 only has these with the OES_standard_derivatives extension.
+        "dFdx" | "dFdy" | "fwidth" if es300 && !vertex => {
+            component_wise(arguments, &[&[G]]).map(|type_| (type_, Builtin::Derivative))
+        }
+        "texture2D" | "texture2DProj" | "texture2DLod" | "texture2DProjLod" | "textureCube" | "textureCubeLod"
+            if !es300 => {
+            let cube = name.starts_with("textureCube");
+            let projective = name.contains("Proj");
+            let explicit = name.ends_with("Lod");
+
+            let lod = match arguments.len() {
+                2 if !explicit => LodArgument::None,
+                3 if explicit && vertex => LodArgument::Explicit,
+                3 if !explicit && !vertex => LodArgument::Bias,
+                _ => return None,
+            };
+            if arguments.len() == 3 && arguments[2] != FLOAT {
+                return None;
+            }
+
+            let coordinates_match = match (cube, projective, &arguments[1]) {
+                (true, _, &Type::Vector(Scalar::Float, 3)) => true,
+                (false, false, &Type::Vector(Scalar::Float, 2)) => true,
+                (false, true, &Type::Vector(Scalar::Float, size)) => size == 3 || size == 4,
+                _ => false,
+            };
+            let sampler = Sampler { shape: if cube { Shape::Cube } else { Shape::Dim2 }, scalar: Scalar::Float, shadow: false };
+
+            if arguments[0] == Type::Sampler(sampler) && coordinates_match {
+                let texture = Texture { sampler, projective, lod, offset: false };
+                Some((Type::Vector(Scalar::Float, 4), Builtin::Texture(texture)))
+            } else {
+                None
+            }
+        }
+        _ if es300 && (name.starts_with("texture") || name.starts_with("texelFetch")) => {
+            texture_function(name, arguments, vertex)
+        }
+        _ => None,
+    }
+}
+
+// The GLSL ES 3.00 texture functions, whose names combine the variations they take arguments for.
+fn texture_function(name: &str, arguments: &[Type], vertex: bool) -> Option<(Type, Builtin)> {
+    let sampler = match arguments.first() {
+        Some(&Type::Sampler(sampler)) => sampler,
+        _ => return None,
+    };
+    let floats = |size| Type::Vector(Scalar::Float, size);
+    let ints = |size| Type::Vector(Scalar::Int, size);
+
+    if name == "textureSize" {
+        let size = if matches!(sampler.shape, Shape::Dim3 | Shape::Array2) { 3 } else { 2 };
+        return if arguments[1..] == [INT] { Some((ints(size), Builtin::TextureSize)) } else { None };
+    }
+
+    let (fetch, mut rest) = match name.strip_prefix("texelFetch") {
+        Some(rest) => (true, rest),
+        None => (false, name.strip_prefix("texture")?),
+    };
+    let projective = take(&mut rest, "Proj");
+    let explicit = take(&mut rest, "Lod");
+    let gradient = take(&mut rest, "Grad");
+    let offset = take(&mut rest, "Offset");
+    if !rest.is_empty() || (fetch && (projective || explicit || gradient)) {
+        return None;
+    }
+
+    let array_shadow = sampler.shape == Shape::Array2 && sampler.shadow;
+    let allowed = !(fetch && (sampler.shadow || sampler.shape == Shape::Cube))
+        && !(projective && matches!(sampler.shape, Shape::Cube | Shape::Array2))
+        && !(explicit && sampler.shadow && sampler.shape != Shape::Dim2)
+        && !(offset && (sampler.shape == Shape::Cube || (array_shadow && !gradient)));
+    if !allowed {
+        return None;
+    }
+
+    // Coordinates include the depth reference of shadow samplers, and the divisor of projective
+    // lookups. Offsets and derivatives are in texels of a layer.
+    let coordinates = sampler.dimensions() + sampler.shadow as usize;
+    let spatial = if sampler.shape == Shape::Dim3 || sampler.shape == Shape::Cube { 3 } else { 2 };
+    let coordinates_match = match arguments.get(1) {
+        Some(coordinate) if fetch => *coordinate == ints(sampler.dimensions()),
+        Some(&Type::Vector(Scalar::Float, size)) if projective => {
+            size == coordinates + 1 || (size == 4 && sampler.shape == Shape::Dim2 && !sampler.shadow)
+        }
+        Some(coordinate) => *coordinate == floats(coordinates),
+        None => false,
+    };
+
+    let mut expected = Vec::new();
+    if fetch {
+        expected.push(INT);
+    }
+    if explicit {
+        expected.push(FLOAT);
+    }
+    if gradient {
+        expected.extend(vec![floats(spatial), floats(spatial)]);
+    }
+    if offset {
+        expected.push(ints(spatial));
+    }
+
+    let lod = if fetch {
+        LodArgument::Fetch
+    } else if explicit {
+        LodArgument::Explicit
+    } else if gradient {
+        LodArgument::Gradient
+    } else if arguments.len() == expected.len() + 3 && !vertex {
+        expected.push(FLOAT);
+        LodArgument::Bias
+    } else {
+        LodArgument::None
+    };
+
+    if !coordinates_match || arguments.len() != expected.len() + 2 || arguments[2..] != expected[..] {
+        return None;
+    }
+
+    let result = if sampler.shadow { FLOAT } else { Type::Vector(sampler.scalar, 4) };
+    Some((result, Builtin::Texture(Texture { sampler, projective, lod, offset })))
+}
+
+// Removes `part` from the start of `rest` when it is there.
+fn take(rest: &mut &str, part: &str) -> bool {
+    match rest.strip_prefix(part) {
+        Some(remaining) => {
+            *rest = remaining;
+            true
+        }
+        None => false,
+    }
+}
+
+// The type of the generic arguments when `arguments` match one of `patterns`. Generic arguments
+// are one scalar or vector type with components of one of `scalars`; the others are scalars of
+// the same kind.
+fn generic(arguments: &[Type], patterns: &[&[bool]], scalars: &[Scalar]) -> Option<Type> {
+    patterns.iter().filter(|pattern| pattern.len() == arguments.len()).find_map(|pattern| {
+        let generic = arguments.iter().zip(pattern.iter()).find(|&(_, &generic)| generic)?.0;
+        let scalar = match *generic {
+            Type::Vector(scalar, _) if scalars.contains(&scalar) => scalar,
+            _ => return None,
+        };
+
+        let matches = arguments.iter().zip(pattern.iter()).all(|(argument, &is_generic)| {
+            if is_generic { argument == generic } else { *argument == Type::Vector(scalar, 1) }
+        });
+        if matches { Some(generic.clone()) } else { None }
+    })
+}
+
+// `generic` on `float` and its vectors.
+fn component_wise(arguments: &[Type], patterns: &[&[bool]]) -> Option<Type> {
+    generic(arguments, patterns, &[Scalar::Float])
+}
+
+fn map2(arguments: &[Type], patterns: &[&[bool]], function: fn(f32, f32) -> f32) -> Option<(Type, Builtin)> {
+    component_wise(arguments, patterns).map(|type_| (type_, Builtin::Map2(function)))
+}
+
+fn map3(arguments: &[Type], patterns: &[&[bool]], function: fn(f32, f32, f32) -> f32) -> Option<(Type, Builtin)> {
+    component_wise(arguments, patterns).map(|type_| (type_, Builtin::Map3(function)))
+}
+
+// A component-wise function from one scalar kind to another, keeping the vector size.
+fn convert(arguments: &[Type], from: Scalar, to: Scalar, function: fn(f32) -> f32) -> Option<(Type, Builtin)> {
+    match *arguments {
+        [Type::Vector(scalar, size)] if scalar == from => Some((Type::Vector(to, size), Builtin::Map(function))),
+        _ => None,
+    }
+}
+
+// The vector relational functions, on two vectors of the same type.
+fn compare(arguments: &[Type], booleans: bool, function: fn(f32, f32) -> f32) -> Option<(Type, Builtin)> {
+    match arguments {
+        [Type::Vector(scalar, size), other] if *size > 1 && *other == arguments[0]
+            && (booleans || *scalar != Scalar::Bool) => {
+            Some((Type::Vector(Scalar::Bool, *size), Builtin::Map2(function)))
+        }
+        _ => None,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// PACKING
+// -------------------------------------------------------------------------------------------------
+
+fn pack(arguments: &[Type], function: fn([f32; 2]) -> u32) -> Option<(Type, Builtin)> {
+    if *arguments == [Type::Vector(Scalar::Float, 2)] { Some((UINT, Builtin::Pack(function))) } else { None }
+}
+
+fn unpack(arguments: &[Type], function: fn(u32) -> [f32; 2]) -> Option<(Type, Builtin)> {
+    if *arguments == [UINT] { Some((Type::Vector(Scalar::Float, 2), Builtin::Unpack(function))) } else { None }
+}
+
+fn snorm(value: f32) -> u32 {
+    (value.clamp(-1.0, 1.0) * 32767.0).round() as i16 as u16 as u32
+}
+
+fn from_snorm(bits: u32) -> f32 {
+    (bits as u16 as i16 as f32 / 32767.0).clamp(-1.0, 1.0)
+}
+
+fn unorm(value: f32) -> u32 {
+    (value.clamp(0.0, 1.0) * 65535.0).round() as u32
+}
+
+// The bits of the half float nearest to `value`. Values too small for a normal half become zero.
+fn to_half(value: f32) -> u32 {
+    let bits = value.to_bits();
+    let sign = (bits >> 16) & 0x8000;
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127 + 15;
+    let mantissa = bits & 0x7f_ffff;
+
+    if value.is_nan() {
+        sign | 0x7e00
+    } else if exponent >= 31 {
+        sign | 0x7c00
+    } else if exponent <= 0 {
+        sign
+    } else {
+        // Rounding may carry into the exponent, which is the right result.
+        (sign | (exponent as u32) << 10) + ((mantissa + 0x1000) >> 13)
+    }
+}
+
+fn from_half(bits: u32) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = (bits & 0x3ff) as f32;
+
+    sign * match exponent {
+        0 => mantissa * 2f32.powi(-24),
+        31 if mantissa == 0.0 => f32::INFINITY,
+        31 => f32::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f32.powi(exponent as i32 - 15),
+    }
+}
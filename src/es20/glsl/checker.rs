@@ -0,0 +1,2001 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use enums::ShaderType;
+
+use super::ast::*;
+use super::builtins::{builtin_function, is_builtin_function};
+use super::interpreter::Machine;
+use super::ir::*;
+use super::types::*;
+use super::{Diagnostic, Environment, GlslVersion, Limits, Location, Lod};
+
+type Check<T> = Result<T, ()>;
+
+// Layout qualifiers of uniform blocks and their defaults, and of block members.
+const BLOCK_LAYOUTS: [&str; 5] = ["shared", "packed", "std140", "row_major", "column_major"];
+const MEMBER_LAYOUTS: [&str; 2] = ["row_major", "column_major"];
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum Storage {
+    // Also the inputs of GLSL ES 3.00 vertex shaders.
+    Attribute,
+    Uniform,
+    // Also the outputs of GLSL ES 3.00 vertex shaders and the inputs of fragment shaders.
+    Varying,
+    // The outputs of GLSL ES 3.00 fragment shaders.
+    Output,
+    // A uniform block instance, or a member of a block without one.
+    Block,
+    BuiltIn,
+}
+
+// A variable other stages or the API see, with what the checker found out about it.
+#[derive(Clone, Debug)]
+pub(super) struct GlobalVariable {
+    pub name: String,
+    pub type_: Type,
+    pub address: usize,
+    pub storage: Storage,
+    // Whether the shader refers to it anywhere, even in code that never runs.
+    pub used: bool,
+    pub invariant: bool,
+    // Where it is declared, for diagnostics about the whole shader.
+    pub location: Location,
+    // Set by `layout(location = ...)`.
+    pub layout_location: Option<usize>,
+}
+
+// The checked shader.
+pub(super) struct Checked {
+    pub functions: Vec<FunctionCode>,
+    pub main: usize,
+    // The memory of an invocation before it starts, with global initializers applied.
+    pub memory: Vec<f32>,
+    pub globals: Vec<GlobalVariable>,
+}
+
+#[derive(Clone)]
+enum Symbol {
+    Variable(Variable),
+    Struct(Rc<StructType>),
+    // The overloads of a function, as indices into the function table.
+    Function(Vec<usize>),
+}
+
+#[derive(Clone)]
+struct Variable {
+    type_: Type,
+    address: usize,
+    writable: bool,
+    // The value of constants, which take no memory.
+    constant: Option<Vec<f32>>,
+    // The index in the global variable table, to record static use.
+    global: Option<usize>,
+}
+
+#[derive(Default)]
+struct Scope {
+    symbols: HashMap<String, Symbol>,
+    // Default precisions set by precision statements, by type keyword.
+    precisions: HashMap<&'static str, Precision>,
+}
+
+struct Signature {
+    parameters: Vec<(Type, ParameterQualifier)>,
+    return_type: Type,
+    location: Location,
+}
+
+// A checked expression.
+struct Value {
+    expr: Expr,
+    type_: Type,
+    // Whether this is a constant expression, in which case `expr` is already folded.
+    constant: bool,
+    // Whether `expr` is a `Load` from something assignments may write.
+    writable: bool,
+}
+
+struct Checker<'a> {
+    stage: ShaderType,
+    version: GlslVersion,
+    limits: Limits,
+    diagnostics: &'a mut Vec<Diagnostic>,
+    scopes: Vec<Scope>,
+    memory: Vec<f32>,
+    globals: Vec<GlobalVariable>,
+    signatures: Vec<Signature>,
+    functions: Vec<FunctionCode>,
+    // The functions each function calls.
+    calls: Vec<Vec<usize>>,
+    // The function whose body is being checked.
+    current: Option<usize>,
+    return_type: Type,
+    loops: usize,
+    switches: usize,
+    structures: usize,
+}
+
+// Folding never samples textures or reads attributes and uniforms.
+struct NoEnvironment;
+
+impl Environment for NoEnvironment {
+    fn uniform(&self, _index: usize, _values: &mut [f32]) {}
+
+    fn texture_2d(&self, _unit: usize, _coord: [f32; 2], _lod: Lod) -> [f32; 4] {
+        [0.0; 4]
+    }
+
+    fn texture_cube(&self, _unit: usize, _coord: [f32; 3], _lod: Lod) -> [f32; 4] {
+        [0.0; 4]
+    }
+
+    fn depth_range(&self) -> [f32; 2] {
+        [0.0, 1.0]
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// CHECKING
+// -------------------------------------------------------------------------------------------------
+
+// Resolves names and types of a parsed shader and lowers it for the interpreter, with the rules of
+// `version`. Errors are added to `diagnostics`; the result is only meaningful when there are none.
+pub(super) fn check(declarations: &[ExternalDeclaration], stage: ShaderType, version: GlslVersion, limits: &Limits,
+                    diagnostics: &mut Vec<Diagnostic>) -> Checked {
+    let mut checker = Checker {
+        stage,
+        version,
+        limits: *limits,
+        diagnostics,
+        scopes: vec![Scope::default()],
+        memory: Vec::new(),
+        globals: Vec::new(),
+        signatures: Vec::new(),
+        functions: Vec::new(),
+        calls: Vec::new(),
+        current: None,
+        return_type: Type::Void,
+        loops: 0,
+        switches: 0,
+        structures: 0,
+    };
+
+    checker.declare_builtins();
+    for declaration in declarations {
+        checker.external_declaration(declaration);
+    }
+    let main = checker.finish();
+
+    Checked { functions: checker.functions, main, memory: checker.memory, globals: checker.globals }
+}
+
+impl<'a> Checker<'a> {
+    fn error<T>(&mut self, location: Location, message: String) -> Check<T> {
+        self.diagnostics.push(Diagnostic::error(location, message));
+        Err(())
+    }
+
+    fn vertex(&self) -> bool {
+        self.stage == ShaderType::VERTEX_SHADER
+    }
+
+    fn es300(&self) -> bool {
+        self.version == GlslVersion::Es300
+    }
+
+    fn allocate(&mut self, size: usize) -> usize {
+        let address = self.memory.len();
+        self.memory.resize(address + size, 0.0);
+        address
+    }
+
+    fn lookup(&self, name: &str) -> Option<Symbol> {
+        self.scopes.iter().rev().find_map(|scope| scope.symbols.get(name).cloned())
+    }
+
+    fn declare(&mut self, name: &str, symbol: Symbol, location: Location) -> Check<()> {
+        if self.scopes.last().unwrap().symbols.contains_key(name) {
+            return self.error(location, format!("'{}' : redefinition", name));
+        }
+        self.scopes.last_mut().unwrap().symbols.insert(name.to_string(), symbol);
+        Ok(())
+    }
+
+    fn check_name(&mut self, name: &str, location: Location) -> Check<()> {
+        if name.starts_with("gl_") {
+            return self.error(location, format!("'{}' : identifiers starting with 'gl_' are reserved", name));
+        }
+        Ok(())
+    }
+
+    fn declare_builtins(&mut self) {
+        let limits = self.limits;
+        let defaults = if self.vertex() {
+            vec![("float", Precision::High), ("int", Precision::High)]
+        } else {
+            vec![("int", Precision::Medium)]
+        };
+        let scope = &mut self.scopes[0];
+        scope.precisions.extend(defaults);
+        scope.precisions.insert("sampler2D", Precision::Low);
+        scope.precisions.insert("samplerCube", Precision::Low);
+
+        let mut constants = vec![
+            ("gl_MaxVertexAttribs", limits.max_vertex_attribs as i32),
+            ("gl_MaxVertexUniformVectors", limits.max_vertex_uniform_vectors as i32),
+            ("gl_MaxVertexTextureImageUnits", limits.max_vertex_texture_image_units as i32),
+            ("gl_MaxCombinedTextureImageUnits", limits.max_combined_texture_image_units as i32),
+            ("gl_MaxTextureImageUnits", limits.max_texture_image_units as i32),
+            ("gl_MaxFragmentUniformVectors", limits.max_fragment_uniform_vectors as i32),
+            ("gl_MaxDrawBuffers", limits.max_draw_buffers as i32),
+        ];
+        if self.es300() {
+            constants.extend(vec![
+                ("gl_MaxVertexOutputVectors", limits.max_vertex_output_vectors as i32),
+                ("gl_MaxFragmentInputVectors", limits.max_fragment_input_vectors as i32),
+                ("gl_MinProgramTexelOffset", limits.min_program_texel_offset),
+                ("gl_MaxProgramTexelOffset", limits.max_program_texel_offset),
+            ]);
+        } else {
+            constants.push(("gl_MaxVaryingVectors", limits.max_varying_vectors as i32));
+        }
+        for (name, value) in constants {
+            let variable = Variable { type_: INT, address: 0, writable: false, constant: Some(vec![value as f32]), global: None };
+            self.scopes[0].symbols.insert(name.to_string(), Symbol::Variable(variable));
+        }
+
+        self.structures += 1;
+        let depth_range = Rc::new(StructType {
+            name: "gl_DepthRangeParameters".to_string(),
+            fields: vec![("near".to_string(), FLOAT), ("far".to_string(), FLOAT), ("diff".to_string(), FLOAT)],
+            id: self.structures,
+        });
+        self.scopes[0].symbols.insert(depth_range.name.clone(), Symbol::Struct(depth_range.clone()));
+        self.builtin_variable("gl_DepthRange", Type::Struct(depth_range), false);
+
+        let vec4 = Type::Vector(Scalar::Float, 4);
+        if self.vertex() {
+            self.builtin_variable("gl_Position", vec4, true);
+            let point_size = self.builtin_variable("gl_PointSize", FLOAT, true);
+            self.memory[point_size] = 1.0;
+            if self.es300() {
+                self.builtin_variable("gl_VertexID", INT, false);
+                self.builtin_variable("gl_InstanceID", INT, false);
+            }
+        } else {
+            self.builtin_variable("gl_FragCoord", vec4.clone(), false);
+            self.builtin_variable("gl_FrontFacing", BOOL, false);
+            self.builtin_variable("gl_PointCoord", Type::Vector(Scalar::Float, 2), false);
+            if self.es300() {
+                self.builtin_variable("gl_FragDepth", FLOAT, true);
+            } else {
+                self.builtin_variable("gl_FragColor", vec4.clone(), true);
+                self.builtin_variable("gl_FragData", Type::Array(Box::new(vec4), limits.max_draw_buffers.max(1)), true);
+            }
+        }
+    }
+
+    fn builtin_variable(&mut self, name: &str, type_: Type, writable: bool) -> usize {
+        let address = self.allocate(type_.slots());
+        let global = Some(self.globals.len());
+        self.globals.push(GlobalVariable {
+            name: name.to_string(),
+            type_: type_.clone(),
+            address,
+            storage: Storage::BuiltIn,
+            used: false,
+            invariant: false,
+            location: Location::default(),
+            layout_location: None,
+        });
+        let variable = Variable { type_, address, writable, constant: None, global };
+        self.scopes[0].symbols.insert(name.to_string(), Symbol::Variable(variable));
+        address
+    }
+
+    // The checks that need the whole shader. Returns the index of `main`.
+    fn finish(&mut self) -> usize {
+        let end = Location::default();
+
+        let main = match self.scopes[0].symbols.get("main") {
+            Some(Symbol::Function(overloads)) => {
+                overloads.iter().cloned().find(|&index| self.functions[index].body.is_some())
+            }
+            _ => None,
+        };
+        if main.is_none() {
+            let _: Check<()> = self.error(end, "Missing main()".to_string());
+        }
+
+        let mut reported = vec![false; self.functions.len()];
+        for caller in 0..self.functions.len() {
+            for callee in self.calls[caller].clone() {
+                if self.functions[callee].body.is_none() && !reported[callee] {
+                    reported[callee] = true;
+                    let location = self.signatures[callee].location;
+                    let message = format!("'{}' : function is called but never defined", self.functions[callee].name);
+                    let _: Check<()> = self.error(location, message);
+                }
+            }
+        }
+
+        // Recursion, direct or through other functions, by depth-first search for a cycle.
+        let mut state = vec![0u8; self.functions.len()];
+        for function in 0..self.functions.len() {
+            if let Some(recursive) = self.find_cycle(function, &mut state) {
+                let location = self.signatures[recursive].location;
+                let message = format!("'{}' : recursive function call", self.functions[recursive].name);
+                let _: Check<()> = self.error(location, message);
+                break;
+            }
+        }
+
+        let used = |name: &str| self.globals.iter().any(|global| global.name == name && global.used);
+        if used("gl_FragColor") && used("gl_FragData") {
+            let _: Check<()> = self.error(end, "cannot use both gl_FragColor and gl_FragData".to_string());
+        }
+
+        self.check_limits();
+        self.check_locations();
+        main.unwrap_or(0)
+    }
+
+    // The limits on what one shader uses. Only variables the shader refers to count.
+    fn check_limits(&mut self) {
+        let limits = self.limits;
+        let (vertex, es300) = (self.vertex(), self.es300());
+
+        let uniforms = if vertex {
+            ("gl_MaxVertexUniformVectors", limits.max_vertex_uniform_vectors)
+        } else {
+            ("gl_MaxFragmentUniformVectors", limits.max_fragment_uniform_vectors)
+        };
+        self.check_packing(Storage::Uniform, "uniforms", uniforms);
+
+        let varyings = match (es300, vertex) {
+            (false, _) => ("varyings", "gl_MaxVaryingVectors", limits.max_varying_vectors),
+            (true, true) => ("outputs", "gl_MaxVertexOutputVectors", limits.max_vertex_output_vectors),
+            (true, false) => ("inputs", "gl_MaxFragmentInputVectors", limits.max_fragment_input_vectors),
+        };
+        self.check_packing(Storage::Varying, varyings.0, (varyings.1, varyings.2));
+
+        let units = if vertex {
+            ("gl_MaxVertexTextureImageUnits", limits.max_vertex_texture_image_units)
+        } else {
+            ("gl_MaxTextureImageUnits", limits.max_texture_image_units)
+        };
+        let samplers = self.used_globals(Storage::Uniform).map(|global| (global, sampler_count(&global.type_)));
+        if let Some(global) = first_beyond(samplers, units.1) {
+            let message = format!("'{}' : samplers exceed the {} units of {}", global.name, units.1, units.0);
+            let location = global.location;
+            let _: Check<()> = self.error(location, message);
+        }
+
+        if vertex {
+            let attributes = self.used_globals(Storage::Attribute).map(|global| (global, attribute_slots(&global.type_)));
+            if let Some(global) = first_beyond(attributes, limits.max_vertex_attribs) {
+                let message = format!("'{}' : attributes exceed the {} of gl_MaxVertexAttribs", global.name,
+                                      limits.max_vertex_attribs);
+                let location = global.location;
+                let _: Check<()> = self.error(location, message);
+            }
+        }
+    }
+
+    fn used_globals(&self, storage: Storage) -> impl Iterator<Item = &GlobalVariable> {
+        self.globals.iter().filter(move |global| global.storage == storage && global.used)
+    }
+
+    // Packs the used variables of `storage` into rows of four components, widest first, each in
+    // the first place it fits, as the packing rules of the GLSL ES specification do. Reports the
+    // first variable that does not fit into the rows `limit` allows.
+    fn check_packing(&mut self, storage: Storage, what: &str, limit: (&str, usize)) {
+        let (constant, rows) = limit;
+        let mut variables = Vec::new();
+        for (index, global) in self.globals.iter().enumerate() {
+            if global.storage == storage && global.used {
+                let mut blocks = Vec::new();
+                packing_blocks(&global.type_, 1, &mut blocks);
+                variables.extend(blocks.into_iter().map(|(height, width)| (height, width, index)));
+            }
+        }
+        variables.sort_by_key(|&(height, width, _)| (Reverse(width), Reverse(height)));
+
+        let mut grid = vec![[false; 4]; rows];
+        for (height, width, index) in variables {
+            let place = (0..(rows + 1).saturating_sub(height))
+                .flat_map(|row| (0..=4 - width).map(move |column| (row, column)))
+                .find(|&(row, column)| grid[row..row + height].iter().all(|cells| !cells[column..column + width].contains(&true)));
+
+            match place {
+                Some((row, column)) => {
+                    for cells in &mut grid[row..row + height] {
+                        for cell in &mut cells[column..column + width] {
+                            *cell = true;
+                        }
+                    }
+                }
+                None => {
+                    let global = &self.globals[index];
+                    let message = format!("'{}' : {} exceed the {} vectors of {}", global.name, what, rows, constant);
+                    let location = global.location;
+                    let _: Check<()> = self.error(location, message);
+                    return;
+                }
+            }
+        }
+    }
+
+    // The `layout(location = ...)` rules of GLSL ES 3.00 vertex inputs and fragment outputs, which
+    // apply to all declared ones, used or not.
+    fn check_locations(&mut self) {
+        if !self.es300() {
+            return;
+        }
+
+        let (storage, constant, limit) = if self.vertex() {
+            (Storage::Attribute, "gl_MaxVertexAttribs", self.limits.max_vertex_attribs)
+        } else {
+            (Storage::Output, "gl_MaxDrawBuffers", self.limits.max_draw_buffers)
+        };
+        let declared: Vec<GlobalVariable> = self.globals.iter().filter(|global| global.storage == storage).cloned().collect();
+
+        let mut taken = vec![false; limit];
+        for global in &declared {
+            let start = match global.layout_location {
+                Some(start) => start,
+                None if storage == Storage::Output && declared.len() > 1 => {
+                    let message = format!("'{}' : fragment shader outputs need a location when there are several",
+                                          global.name);
+                    let _: Check<()> = self.error(global.location, message);
+                    continue;
+                }
+                None if storage == Storage::Output => 0,
+                None => continue,
+            };
+
+            let count = match global.type_ {
+                Type::Array(_, length) => length,
+                ref type_ => attribute_slots(type_),
+            };
+            if start + count > limit {
+                let message = format!("'{}' : location {} is out of range for {} ({})", global.name, start + count - 1,
+                                      constant, limit);
+                let _: Check<()> = self.error(global.location, message);
+            } else if taken[start..start + count].contains(&true) {
+                let message = format!("'{}' : location {} overlaps another variable", global.name, start);
+                let _: Check<()> = self.error(global.location, message);
+            } else {
+                for slot in &mut taken[start..start + count] {
+                    *slot = true;
+                }
+            }
+        }
+    }
+
+    // State 1 marks functions on the current path, 2 those known to lead to no cycle.
+    fn find_cycle(&self, function: usize, state: &mut [u8]) -> Option<usize> {
+        match state[function] {
+            1 => return Some(function),
+            2 => return None,
+            _ => {}
+        }
+        state[function] = 1;
+        for &callee in &self.calls[function] {
+            if let Some(recursive) = self.find_cycle(callee, state) {
+                return Some(recursive);
+            }
+        }
+        state[function] = 2;
+        None
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // TYPES
+    // -------------------------------------------------------------------------------------------------
+
+    fn type_(&mut self, specifier: &TypeSpecifier) -> Check<Type> {
+        let base = self.base_type(specifier)?;
+        match specifier.array {
+            Some(ref size) => self.array_of(base, size, None, specifier.location),
+            None => Ok(base),
+        }
+    }
+
+    // The type of a specifier without its GLSL ES 3.00 array size.
+    fn base_type(&mut self, specifier: &TypeSpecifier) -> Check<Type> {
+        match specifier.name {
+            TypeName::Keyword(ref name) => match Type::keyword(name) {
+                Some(type_) => Ok(type_),
+                None => self.error(specifier.location, format!("'{}' : not a type", name)),
+            },
+            TypeName::Named(ref name) => match self.lookup(name) {
+                Some(Symbol::Struct(structure)) => Ok(Type::Struct(structure)),
+                _ => self.error(specifier.location, format!("'{}' : undeclared type", name)),
+            },
+            TypeName::Struct(ref definition) => self.struct_definition(definition),
+        }
+    }
+
+    // An array of `base`. Without a size, `implicit` gives it, from an initializer or the
+    // arguments of a constructor.
+    fn array_of(&mut self, base: Type, size: &ArraySize, implicit: Option<usize>, location: Location) -> Check<Type> {
+        if base.is_array() {
+            return self.error(location, "'[]' : arrays of arrays are not allowed".to_string());
+        }
+        let length = match *size {
+            ArraySize::Sized(ref size) => self.array_size(size)?,
+            ArraySize::Unsized => match implicit {
+                Some(length) => length,
+                None => return self.error(location, "'[]' : array size required".to_string()),
+            },
+        };
+        Ok(Type::Array(Box::new(base), length))
+    }
+
+    fn struct_definition(&mut self, definition: &StructDefinition) -> Check<Type> {
+        let name = match definition.name {
+            Some(ref name) => name.clone(),
+            None => return self.error(definition.location, "anonymous structures are not supported".to_string()),
+        };
+        self.check_name(&name, definition.location)?;
+
+        let fields = self.fields(&definition.fields, false)?;
+        self.structures += 1;
+        let fields = fields.into_iter().map(|(name, type_, _)| (name, type_)).collect();
+        let structure = Rc::new(StructType { name: name.clone(), fields, id: self.structures });
+        self.declare(&name, Symbol::Struct(structure.clone()), definition.location)?;
+        Ok(Type::Struct(structure))
+    }
+
+    // The fields of a structure, or the members of a uniform block.
+    fn fields(&mut self, declarations: &[FieldDeclaration], block: bool) -> Check<Vec<(String, Type, Location)>> {
+        let mut fields: Vec<(String, Type, Location)> = Vec::new();
+        for field in declarations {
+            if let TypeName::Struct(ref embedded) = field.type_.name {
+                return self.error(embedded.location, "embedded structure definitions are not supported".to_string());
+            }
+            for layout in &field.layout {
+                self.layout_qualifier(layout, if block { &MEMBER_LAYOUTS } else { &[] })?;
+            }
+            let base = self.type_(&field.type_)?;
+            self.check_precision(&field.type_, &base)?;
+
+            for &(ref field_name, ref size, location) in &field.names {
+                if base == Type::Void {
+                    return self.error(location, format!("'{}' : illegal use of type 'void'", field_name));
+                }
+                let type_ = match *size {
+                    Some(_) if base.is_array() => {
+                        return self.error(location, "'[]' : arrays of arrays are not allowed".to_string());
+                    }
+                    Some(ref size) => Type::Array(Box::new(base.clone()), self.array_size(size)?),
+                    None => base.clone(),
+                };
+                if block && type_.contains_sampler() {
+                    return self.error(location, format!("'{}' : samplers are not allowed in uniform blocks",
+                                                        field_name));
+                }
+                if fields.iter().any(|field| field.0 == *field_name) {
+                    return self.error(location, format!("'{}' : duplicate field name in structure", field_name));
+                }
+                fields.push((field_name.clone(), type_, location));
+            }
+        }
+        Ok(fields)
+    }
+
+    fn array_size(&mut self, size: &Expression) -> Check<usize> {
+        let value = self.expression(size)?;
+        match constant_int(&value) {
+            Some(size) if size > 0 => Ok(size as usize),
+            Some(_) => self.error(size.location, "'[]' : array size must be a positive integer".to_string()),
+            None => self.error(size.location, "'[]' : array size must be a constant integer expression".to_string()),
+        }
+    }
+
+    // Declarations of float, int and sampler types need a precision, explicit or by default.
+    fn check_precision(&mut self, specifier: &TypeSpecifier, type_: &Type) -> Check<()> {
+        if specifier.precision.is_some() {
+            if !type_.has_precision() {
+                return self.error(specifier.location,
+                                  format!("precision qualifiers are not allowed on type '{}'", type_));
+            }
+            return Ok(());
+        }
+
+        if let Some(key) = type_.precision_key() {
+            if !self.scopes.iter().any(|scope| scope.precisions.contains_key(key)) {
+                return self.error(specifier.location, format!("No precision specified for ({})", key));
+            }
+        }
+        Ok(())
+    }
+
+    // Default precisions apply to float, int and each sampler type.
+    fn precision_statement(&mut self, precision: Precision, specifier: &TypeSpecifier) -> Check<()> {
+        let type_ = match specifier.name {
+            TypeName::Keyword(ref name) if specifier.array.is_none() => Type::keyword(name),
+            _ => None,
+        };
+
+        match type_ {
+            Some(type_) if type_ == FLOAT || type_ == INT || type_.is_sampler() => {
+                let key = type_.precision_key().unwrap();
+                self.scopes.last_mut().unwrap().precisions.insert(key, precision);
+                Ok(())
+            }
+            _ => self.error(specifier.location,
+                            "'precision' : illegal type argument for default precision qualifier".to_string()),
+        }
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // DECLARATIONS
+    // -------------------------------------------------------------------------------------------------
+
+    fn external_declaration(&mut self, declaration: &ExternalDeclaration) {
+        match *declaration {
+            ExternalDeclaration::Declaration(ref declaration) => {
+                let _ = self.declaration(declaration, true);
+            }
+            ExternalDeclaration::Function(ref function) => {
+                let _ = self.function(function);
+            }
+            ExternalDeclaration::Precision(precision, ref specifier) => {
+                let _ = self.precision_statement(precision, specifier);
+            }
+            ExternalDeclaration::Invariant(ref names) => {
+                for &(ref name, location) in names {
+                    let _ = self.invariant(name, location);
+                }
+            }
+            ExternalDeclaration::Block(ref block) => {
+                let _ = self.block(block);
+            }
+            ExternalDeclaration::DefaultLayout(ref qualifiers) => {
+                let _ = self.default_layout(qualifiers);
+            }
+        }
+    }
+
+    // `invariant name;` for an output declared earlier.
+    fn invariant(&mut self, name: &str, location: Location) -> Check<()> {
+        let global = match self.lookup(name) {
+            Some(Symbol::Variable(Variable { global: Some(global), .. })) => global,
+            _ => return self.error(location, format!("'{}' : undeclared identifier", name)),
+        };
+
+        let variable = &self.globals[global];
+        let builtin = ["gl_Position", "gl_PointSize", "gl_FragCoord", "gl_PointCoord"].contains(&variable.name.as_str());
+        let (allowed, outputs) = match self.version {
+            GlslVersion::Es100 => (variable.storage == Storage::Varying || builtin, "varyings"),
+            GlslVersion::Es300 => {
+                let output = if self.vertex() { Storage::Varying } else { Storage::Output };
+                (variable.storage == output || (builtin && self.vertex()), "outputs")
+            }
+        };
+        if !allowed {
+            return self.error(location, format!("'{}' : only {} can be invariant", name, outputs));
+        }
+        self.globals[global].invariant = true;
+        Ok(())
+    }
+
+    // One entry of a layout qualifier, where only the names in `allowed` may appear.
+    fn layout_qualifier(&mut self, layout: &LayoutQualifier, allowed: &[&str]) -> Check<()> {
+        let name = layout.name.as_str();
+        if name != "location" && !BLOCK_LAYOUTS.contains(&name) {
+            return self.error(layout.location, format!("'{}' : unknown layout qualifier", name));
+        }
+        if !allowed.contains(&name) {
+            return self.error(layout.location, format!("'{}' : layout qualifier not allowed here", name));
+        }
+        match (name == "location", layout.value) {
+            (true, None) => self.error(layout.location, "'location' : needs a value".to_string()),
+            (false, Some(_)) => self.error(layout.location, format!("'{}' : does not take a value", name)),
+            _ => Ok(()),
+        }
+    }
+
+    // The qualifiers besides storage, which only some variables take.
+    fn auxiliary_qualifiers(&mut self, qualifiers: &Qualifiers, global: bool) -> Check<()> {
+        let (storage, location) = (qualifiers.storage, qualifiers.location);
+        let vertex = self.vertex();
+
+        let (outputs, invariant) = match self.version {
+            GlslVersion::Es100 => ("varyings", storage == StorageQualifier::Varying),
+            GlslVersion::Es300 => ("outputs", storage == StorageQualifier::Out),
+        };
+        if qualifiers.invariant && (!invariant || !global) {
+            return self.error(location, format!("'invariant' : only {} can be invariant", outputs));
+        }
+
+        let interpolated = (storage == StorageQualifier::Out && vertex) || (storage == StorageQualifier::In && !vertex);
+        let keyword = match qualifiers.interpolation {
+            Some(Interpolation::Smooth) => Some("smooth"),
+            Some(Interpolation::Flat) => Some("flat"),
+            None if qualifiers.centroid => Some("centroid"),
+            None => None,
+        };
+        if let Some(keyword) = keyword {
+            if !interpolated {
+                return self.error(location, format!("'{}' : only allowed on vertex shader outputs and fragment \
+                                                     shader inputs", keyword));
+            }
+        }
+
+        let located = (storage == StorageQualifier::In && vertex) || (storage == StorageQualifier::Out && !vertex);
+        for layout in &qualifiers.layout {
+            self.layout_qualifier(layout, if located { &["location"] } else { &[] })?;
+        }
+        Ok(())
+    }
+
+    fn declaration(&mut self, declaration: &Declaration, global: bool) -> Check<Vec<Stmt>> {
+        let location = declaration.type_.location;
+        let storage = declaration.qualifiers.storage;
+
+        if interface_storage(storage, ShaderType::VERTEX_SHADER).is_some() && !global {
+            return self.error(location, format!("'{}' : only allowed at global scope", storage_keyword(storage)));
+        }
+        if storage == StorageQualifier::Attribute && !self.vertex() {
+            return self.error(location, "'attribute' : supported in vertex shaders only".to_string());
+        }
+        self.auxiliary_qualifiers(&declaration.qualifiers, global)?;
+
+        let base = self.base_type(&declaration.type_)?;
+        // Still declared without a precision, to avoid follow-up errors about undeclared names.
+        let _ = self.check_precision(&declaration.type_, &base);
+
+        let mut statements = Vec::new();
+        for variable in &declaration.variables {
+            if let Ok(Some(statement)) = self.variable(declaration, &base, variable, global) {
+                statements.push(statement);
+            }
+        }
+        Ok(statements)
+    }
+
+    fn variable(&mut self, declaration: &Declaration, base: &Type, variable: &VariableDeclaration, global: bool)
+        -> Check<Option<Stmt>> {
+        let (name, location, storage) = (&variable.name, variable.location, declaration.qualifiers.storage);
+        self.check_name(name, location)?;
+
+        if *base == Type::Void {
+            return self.error(location, format!("'{}' : illegal use of type 'void'", name));
+        }
+
+        let initializer = match variable.initializer {
+            Some(ref initializer) => Some(self.expression(initializer)?),
+            None => None,
+        };
+        let implicit = match initializer {
+            Some(Value { type_: Type::Array(_, length), .. }) => Some(length),
+            _ => None,
+        };
+        let type_ = match (declaration.type_.array.as_ref(), variable.array_size.as_ref()) {
+            (Some(_), Some(_)) => return self.error(location, "'[]' : arrays of arrays are not allowed".to_string()),
+            (Some(size), None) | (None, Some(size)) => self.array_of(base.clone(), size, implicit, location)?,
+            (None, None) => base.clone(),
+        };
+
+        self.interface_type(name, &type_, &declaration.qualifiers, location)?;
+        if storage == StorageQualifier::Const && type_.is_array() && !self.es300() {
+            return self.error(location, format!("'{}' : arrays can not be const", name));
+        }
+        if type_.contains_sampler() && storage != StorageQualifier::Uniform {
+            return self.error(location, format!("'{}' : samplers must be uniform", name));
+        }
+
+        let interface = interface_storage(storage, self.stage);
+        if let Some(ref value) = initializer {
+            if interface.is_some() {
+                return self.error(location, format!("'{}' : cannot initialize this type of qualifier", name));
+            }
+            if value.type_ != type_ {
+                return self.error(location, format!("'=' : cannot convert from '{}' to '{}'", value.type_, type_));
+            }
+        }
+
+        if storage == StorageQualifier::Const {
+            let values = match initializer {
+                Some(Value { expr: Expr::Constant(values), .. }) => values,
+                Some(_) => return self.error(location, format!("'=' : assigning non-constant to 'const {}'", type_)),
+                None => {
+                    return self.error(location, format!("'{}' : variables with qualifier 'const' must be initialized",
+                                                        name));
+                }
+            };
+            let constant = Variable { type_, address: 0, writable: false, constant: Some(values), global: None };
+            self.declare(name, Symbol::Variable(constant), location)?;
+            return Ok(None);
+        }
+
+        let size = type_.slots();
+        let address = self.allocate(size);
+        let global_index = if interface.is_some() { Some(self.globals.len()) } else { None };
+        if let Some(storage) = interface {
+            let layout_location = declaration.qualifiers.layout.iter()
+                .find(|layout| layout.name == "location")
+                .and_then(|layout| layout.value)
+                .map(|value| value as usize);
+            self.globals.push(GlobalVariable {
+                name: name.clone(),
+                type_: type_.clone(),
+                address,
+                storage,
+                used: false,
+                invariant: declaration.qualifiers.invariant,
+                location,
+                layout_location,
+            });
+        }
+
+        let mut statement = None;
+        if let Some(value) = initializer {
+            if global {
+                match value.expr {
+                    Expr::Constant(ref values) => self.memory[address..address + size].copy_from_slice(values),
+                    _ => return self.error(location, format!("'{}' : global initializers must be constant expressions",
+                                                             name)),
+                }
+            } else {
+                let place = Place { address, size, steps: Vec::new() };
+                statement = Some(Stmt::Expr(Expr::Assign(place, None, Box::new(value.expr)), size));
+            }
+        }
+
+        let writable = storage == StorageQualifier::None || matches!(interface, Some(Storage::Varying) if self.vertex())
+            || interface == Some(Storage::Output);
+        let symbol = Variable { type_, address, writable, constant: None, global: global_index };
+        self.declare(name, Symbol::Variable(symbol), location)?;
+        Ok(statement)
+    }
+
+    // The types each kind of shader input and output may have.
+    fn interface_type(&mut self, name: &str, type_: &Type, qualifiers: &Qualifiers, location: Location) -> Check<()> {
+        let element = match *type_ {
+            Type::Array(ref element, _) => &**element,
+            ref type_ => type_,
+        };
+        let float_type = matches!(*element, Type::Vector(Scalar::Float, _) | Type::Matrix(..));
+        let vector_type = matches!(*element, Type::Vector(scalar, _) if scalar != Scalar::Bool);
+        let opaque = type_.contains(&|type_| type_.scalar() == Some(Scalar::Bool) || type_.is_sampler());
+
+        let (kind, valid) = match (qualifiers.storage, self.vertex()) {
+            (StorageQualifier::Attribute, _) => ("attributes", float_type && !type_.is_array()),
+            (StorageQualifier::Varying, _) => ("varyings", float_type),
+            (StorageQualifier::In, true) => {
+                ("vertex shader inputs", (vector_type || float_type) && !type_.is_array())
+            }
+            (StorageQualifier::Out, false) => ("fragment shader outputs", vector_type),
+            (StorageQualifier::Out, true) => ("vertex shader outputs", !opaque),
+            (StorageQualifier::In, false) => ("fragment shader inputs", !opaque),
+            _ => return Ok(()),
+        };
+        if !valid {
+            return self.error(location, format!("'{}' : {} can not have type '{}'", name, kind, type_));
+        }
+
+        // Integers are not interpolated.
+        let interpolated = matches!(kind, "vertex shader outputs" | "fragment shader inputs");
+        if interpolated && type_.contains(&Type::is_integer) && qualifiers.interpolation != Some(Interpolation::Flat) {
+            return self.error(location, format!("'{}' : integer {} must be qualified 'flat'", name, kind));
+        }
+        Ok(())
+    }
+
+    // A GLSL ES 3.00 uniform block. Its members are globals of their own unless the block names an
+    // instance, which is then a structure.
+    fn block(&mut self, block: &Block) -> Check<()> {
+        self.check_name(&block.name, block.location)?;
+        let qualifiers = &block.qualifiers;
+        if qualifiers.invariant || qualifiers.interpolation.is_some() || qualifiers.centroid {
+            return self.error(qualifiers.location, "only layout qualifiers are allowed on uniform blocks".to_string());
+        }
+        for layout in &qualifiers.layout {
+            self.layout_qualifier(layout, &BLOCK_LAYOUTS)?;
+        }
+
+        let fields = self.fields(&block.fields, true)?;
+        match block.instance {
+            Some((ref instance, ref size, location)) => {
+                self.check_name(instance, location)?;
+                self.structures += 1;
+                let fields = fields.into_iter().map(|(name, type_, _)| (name, type_)).collect();
+                let structure = Rc::new(StructType { name: block.name.clone(), fields, id: self.structures });
+                let type_ = match *size {
+                    Some(ref size) => Type::Array(Box::new(Type::Struct(structure)), self.array_size(size)?),
+                    None => Type::Struct(structure),
+                };
+                self.block_variable(instance, type_, location)
+            }
+            None => {
+                for (name, type_, location) in fields {
+                    self.block_variable(&name, type_, location)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn block_variable(&mut self, name: &str, type_: Type, location: Location) -> Check<()> {
+        let address = self.allocate(type_.slots());
+        let global = Some(self.globals.len());
+        self.globals.push(GlobalVariable {
+            name: name.to_string(),
+            type_: type_.clone(),
+            address,
+            storage: Storage::Block,
+            used: false,
+            invariant: false,
+            location,
+            layout_location: None,
+        });
+        let variable = Variable { type_, address, writable: false, constant: None, global };
+        self.declare(name, Symbol::Variable(variable), location)
+    }
+
+    // `layout(...) uniform;`, whose defaults only matter to the memory layout of blocks.
+    fn default_layout(&mut self, qualifiers: &Qualifiers) -> Check<()> {
+        if qualifiers.storage != StorageQualifier::Uniform || qualifiers.invariant || qualifiers.interpolation.is_some()
+            || qualifiers.centroid {
+            return self.error(qualifiers.location, "'layout' : only defaults for uniform blocks can be declared"
+                .to_string());
+        }
+        for layout in &qualifiers.layout {
+            self.layout_qualifier(layout, &BLOCK_LAYOUTS)?;
+        }
+        Ok(())
+    }
+
+    fn function(&mut self, function: &Function) -> Check<()> {
+        let (name, location) = (&function.name, function.location);
+        if let TypeName::Struct(_) = function.return_type.name {
+            return self.error(location, "structure definitions are not allowed in return types".to_string());
+        }
+        let return_type = self.type_(&function.return_type)?;
+        self.check_precision(&function.return_type, &return_type)?;
+
+        self.check_name(name, location)?;
+        if is_builtin_function(name, self.version) {
+            return self.error(location, format!("'{}' : cannot redefine or overload built-in functions", name));
+        }
+
+        let mut parameters = Vec::new();
+        for parameter in &function.parameters {
+            let base = self.type_(&parameter.type_)?;
+            if base == Type::Void {
+                return self.error(parameter.location, "'void' : illegal use of type 'void'".to_string());
+            }
+            let _ = self.check_precision(&parameter.type_, &base);
+
+            let type_ = match parameter.array_size {
+                Some(_) if base.is_array() => {
+                    return self.error(parameter.location, "'[]' : arrays of arrays are not allowed".to_string());
+                }
+                Some(ref size) => Type::Array(Box::new(base), self.array_size(size)?),
+                None => base,
+            };
+            if type_.contains_sampler() && parameter.qualifier != ParameterQualifier::In {
+                return self.error(parameter.location, "samplers can not be output parameters".to_string());
+            }
+            if parameter.constant && parameter.qualifier != ParameterQualifier::In {
+                return self.error(parameter.location, "'const' : only allowed with 'in' parameters".to_string());
+            }
+            parameters.push((type_, parameter.qualifier));
+        }
+
+        if name == "main" && (return_type != Type::Void || !parameters.is_empty()) {
+            return self.error(location, "'main' : must return void and take no parameters".to_string());
+        }
+
+        let overloads = match self.scopes[0].symbols.get(name) {
+            Some(Symbol::Function(overloads)) => overloads.clone(),
+            Some(_) => return self.error(location, format!("'{}' : redefinition", name)),
+            None => Vec::new(),
+        };
+        let existing = overloads.iter().cloned().find(|&index| {
+            let declared = &self.signatures[index].parameters;
+            declared.len() == parameters.len() && declared.iter().zip(&parameters).all(|(a, b)| a.0 == b.0)
+        });
+
+        let index = match existing {
+            Some(index) => {
+                if self.signatures[index].return_type != return_type {
+                    return self.error(location, format!("'{}' : overloaded functions must have the same return type",
+                                                         name));
+                }
+                if self.signatures[index].parameters.iter().zip(&parameters).any(|(a, b)| a.1 != b.1) {
+                    return self.error(location, format!("'{}' : parameter qualifiers do not match the declaration",
+                                                         name));
+                }
+                if function.body.is_some() && self.functions[index].body.is_some() {
+                    return self.error(location, format!("'{}' : function already has a body", name));
+                }
+                index
+            }
+            None => {
+                let index = self.functions.len();
+                let addresses = parameters.iter().map(|parameter| {
+                    let size = parameter.0.slots();
+                    (self.allocate(size), size)
+                }).collect();
+
+                self.functions.push(FunctionCode {
+                    name: name.clone(),
+                    parameters: addresses,
+                    return_size: return_type.slots(),
+                    body: None,
+                });
+                self.signatures.push(Signature { parameters: parameters.clone(), return_type: return_type.clone(), location });
+                self.calls.push(Vec::new());
+
+                let mut overloads = overloads;
+                overloads.push(index);
+                self.scopes[0].symbols.insert(name.clone(), Symbol::Function(overloads));
+                index
+            }
+        };
+
+        let body = match function.body {
+            Some(ref body) => body,
+            None => return Ok(()),
+        };
+
+        // The parameters and the outermost statements of the body share a scope.
+        self.scopes.push(Scope::default());
+        for (parameter, (&(address, _), (type_, _))) in function.parameters.iter()
+            .zip(self.functions[index].parameters.clone().iter().zip(&parameters)) {
+            if let Some(ref name) = parameter.name {
+                if self.check_name(name, parameter.location).is_err() {
+                    continue;
+                }
+                let variable = Variable {
+                    type_: type_.clone(),
+                    address,
+                    writable: !parameter.constant,
+                    constant: None,
+                    global: None,
+                };
+                let _ = self.declare(name, Symbol::Variable(variable), parameter.location);
+            }
+        }
+
+        self.current = Some(index);
+        self.return_type = return_type;
+        let statements = self.statements(body);
+        self.current = None;
+        self.scopes.pop();
+
+        self.functions[index].body = Some(statements);
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // STATEMENTS
+    // -------------------------------------------------------------------------------------------------
+
+    fn statements(&mut self, statements: &[Statement]) -> Vec<Stmt> {
+        statements.iter().flat_map(|statement| self.statement(statement)).collect()
+    }
+
+    // A statement in its own scope, as the branches of `if` and the bodies of loops are.
+    fn nested_statement(&mut self, statement: &Statement) -> Stmt {
+        self.scopes.push(Scope::default());
+        let statements = self.statement(statement);
+        self.scopes.pop();
+        Stmt::Block(statements)
+    }
+
+    fn condition(&mut self, condition: &Expression) -> Check<Expr> {
+        let value = self.expression(condition)?;
+        if value.type_ != BOOL {
+            return self.error(condition.location, "boolean expression expected".to_string());
+        }
+        Ok(value.expr)
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Vec<Stmt> {
+        match *statement {
+            Statement::Block(ref statements) => {
+                self.scopes.push(Scope::default());
+                let statements = self.statements(statements);
+                self.scopes.pop();
+                vec![Stmt::Block(statements)]
+            }
+            Statement::Declaration(ref declaration) => self.declaration(declaration, false).unwrap_or_default(),
+            Statement::Precision(precision, ref specifier) => {
+                let _ = self.precision_statement(precision, specifier);
+                Vec::new()
+            }
+            Statement::Expression(ref expression) => match self.expression(expression) {
+                Ok(value) => vec![Stmt::Expr(value.expr, value.type_.slots())],
+                Err(()) => Vec::new(),
+            },
+            Statement::If(ref condition, ref then, ref otherwise) => {
+                let condition = self.condition(condition);
+                let then = self.nested_statement(then);
+                let otherwise = otherwise.as_ref().map(|otherwise| Box::new(self.nested_statement(otherwise)));
+                match condition {
+                    Ok(condition) => vec![Stmt::If(condition, Box::new(then), otherwise)],
+                    Err(()) => Vec::new(),
+                }
+            }
+            Statement::For(ref init, ref condition, ref step, ref body) => {
+                self.scopes.push(Scope::default());
+                let init = init.as_ref().map_or(Vec::new(), |init| self.statement(init));
+                let condition = condition.as_ref().map(|condition| self.condition(condition));
+                let step = step.as_ref().map(|step| self.expression(step));
+                let body = self.loop_body(body);
+                self.scopes.pop();
+
+                match (condition, step) {
+                    (Some(Err(())), _) | (_, Some(Err(()))) => Vec::new(),
+                    (condition, step) => vec![Stmt::Loop(Loop {
+                        init,
+                        condition: condition.map(Result::unwrap),
+                        step: step.map(|step| step.map(|step| {
+                            let size = step.type_.slots();
+                            (step.expr, size)
+                        }).unwrap()),
+                        body: Box::new(body),
+                        test_first: true,
+                    })],
+                }
+            }
+            Statement::While(ref condition, ref body) => {
+                let condition = self.condition(condition);
+                let body = self.loop_body(body);
+                match condition {
+                    Ok(condition) => vec![Stmt::Loop(Loop {
+                        init: Vec::new(),
+                        condition: Some(condition),
+                        step: None,
+                        body: Box::new(body),
+                        test_first: true,
+                    })],
+                    Err(()) => Vec::new(),
+                }
+            }
+            Statement::DoWhile(ref body, ref condition) => {
+                let body = self.loop_body(body);
+                match self.condition(condition) {
+                    Ok(condition) => vec![Stmt::Loop(Loop {
+                        init: Vec::new(),
+                        condition: Some(condition),
+                        step: None,
+                        body: Box::new(body),
+                        test_first: false,
+                    })],
+                    Err(()) => Vec::new(),
+                }
+            }
+            Statement::Switch(ref selector, ref body) => self.switch(selector, body).unwrap_or_default(),
+            Statement::Case(ref label, location) => {
+                let keyword = if label.is_some() { "case" } else { "default" };
+                let _: Check<()> = self.error(location, format!("'{}' : only allowed in switch statements", keyword));
+                Vec::new()
+            }
+            Statement::Return(ref value, location) => self.return_statement(value.as_ref(), location).unwrap_or_default(),
+            Statement::Break(location) => {
+                if self.loops == 0 && self.switches == 0 {
+                    let _: Check<()> = self.error(location, "'break' : only allowed in loops and switch statements"
+                        .to_string());
+                    return Vec::new();
+                }
+                vec![Stmt::Break]
+            }
+            Statement::Continue(location) => {
+                if self.loops == 0 {
+                    let _: Check<()> = self.error(location, "'continue' : only allowed in loops".to_string());
+                    return Vec::new();
+                }
+                vec![Stmt::Continue]
+            }
+            Statement::Discard(location) => {
+                if self.vertex() {
+                    let _: Check<()> = self.error(location, "'discard' : supported in fragment shaders only".to_string());
+                    return Vec::new();
+                }
+                vec![Stmt::Discard]
+            }
+            Statement::Empty => Vec::new(),
+        }
+    }
+
+    // A GLSL ES 3.00 switch statement. The labels are part of its body, which is one scope.
+    fn switch(&mut self, selector: &Expression, body: &[Statement]) -> Check<Vec<Stmt>> {
+        let value = self.expression(selector);
+        if let Ok(ref value) = value {
+            if value.type_ != INT && value.type_ != UINT {
+                return self.error(selector.location, "'switch' : init-expression in a switch statement must be a \
+                                                       scalar integer".to_string());
+            }
+        }
+
+        self.scopes.push(Scope::default());
+        self.switches += 1;
+        let mut labels: Vec<(Option<f32>, usize)> = Vec::new();
+        let mut statements = Vec::new();
+        let mut result = Ok(());
+        for (index, statement) in body.iter().enumerate() {
+            match *statement {
+                Statement::Case(ref label, location) => {
+                    let type_ = value.as_ref().map(|value| value.type_.clone()).unwrap_or(INT);
+                    match self.case_label(label.as_ref(), &type_, &labels, location) {
+                        Ok(label) => labels.push((label, statements.len())),
+                        Err(()) => result = Err(()),
+                    }
+                    if index + 1 == body.len() {
+                        result = self.error(location, "'switch' : a statement must follow the last case label"
+                            .to_string());
+                    }
+                }
+                _ if labels.is_empty() && result.is_ok() => {
+                    result = self.error(selector.location, "'switch' : statement before the first case label"
+                        .to_string());
+                }
+                ref statement => statements.extend(self.statement(statement)),
+            }
+        }
+        self.switches -= 1;
+        self.scopes.pop();
+
+        result?;
+        Ok(vec![Stmt::Switch(value?.expr, labels, statements)])
+    }
+
+    // The value of a case label, or `None` for `default`.
+    fn case_label(&mut self, label: Option<&Expression>, type_: &Type, labels: &[(Option<f32>, usize)],
+                  location: Location) -> Check<Option<f32>> {
+        let label = match label {
+            Some(label) => self.expression(label)?,
+            None if labels.iter().any(|label| label.0.is_none()) => {
+                return self.error(location, "'default' : duplicate default label".to_string());
+            }
+            None => return Ok(None),
+        };
+
+        let value = match label.expr {
+            Expr::Constant(ref values) if label.type_ == *type_ => values[0],
+            _ => {
+                return self.error(location, format!("'case' : case label must be a constant expression of type '{}'",
+                                                    type_));
+            }
+        };
+        if labels.iter().any(|label| label.0 == Some(value)) {
+            return self.error(location, format!("'case' : duplicate case label '{}'", value));
+        }
+        Ok(Some(value))
+    }
+
+    fn loop_body(&mut self, body: &Statement) -> Stmt {
+        self.loops += 1;
+        let body = self.nested_statement(body);
+        self.loops -= 1;
+        body
+    }
+
+    fn return_statement(&mut self, value: Option<&Expression>, location: Location) -> Check<Vec<Stmt>> {
+        let value = match value {
+            Some(value) => Some(self.expression(value)?),
+            None => None,
+        };
+
+        match value {
+            Some(_) if self.return_type == Type::Void => {
+                self.error(location, "'return' : void function cannot return a value".to_string())
+            }
+            None if self.return_type != Type::Void => {
+                self.error(location, "'return' : non-void function must return a value".to_string())
+            }
+            Some(ref value) if value.type_ != self.return_type => {
+                let message = format!("'return' : function return is not matching type: '{}' and '{}'",
+                                      value.type_, self.return_type);
+                self.error(location, message)
+            }
+            value => Ok(vec![Stmt::Return(value.map(|value| value.expr))]),
+        }
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // EXPRESSIONS
+    // -------------------------------------------------------------------------------------------------
+
+    fn value(&self, expr: Expr, type_: Type, constant: bool) -> Value {
+        let expr = match expr {
+            Expr::Constant(_) => expr,
+            expr if constant => {
+                let mut stack = Vec::new();
+                Expr::Constant(Machine::new(&mut [], &mut stack, &[], &NoEnvironment).fold(&expr))
+            }
+            expr => expr,
+        };
+        Value { expr, type_, constant, writable: false }
+    }
+
+    // The place a value is stored at, for assignments and output parameters.
+    fn l_value(&mut self, value: Value, operator: &str, location: Location) -> Check<Place> {
+        match value.expr {
+            Expr::Load(place) if value.writable => Ok(place),
+            _ => self.error(location, format!("'{}' : l-value required", operator)),
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression) -> Check<Value> {
+        let location = expression.location;
+
+        match expression.kind {
+            ExpressionKind::Identifier(ref name) => self.identifier(name, location),
+            ExpressionKind::IntLiteral(value) => Ok(self.value(Expr::Constant(vec![value as u32 as i32 as f32]), INT, true)),
+            ExpressionKind::UintLiteral(value) => Ok(self.value(Expr::Constant(vec![value as f32]), UINT, true)),
+            ExpressionKind::FloatLiteral(value) => Ok(self.value(Expr::Constant(vec![value]), FLOAT, true)),
+            ExpressionKind::BoolLiteral(value) => {
+                Ok(self.value(Expr::Constant(vec![if value { 1.0 } else { 0.0 }]), BOOL, true))
+            }
+            ExpressionKind::Call(ref name, ref arguments) => self.call(name, arguments, location),
+            ExpressionKind::Construct(ref specifier, ref arguments) => self.construct(specifier, arguments, location),
+            ExpressionKind::Field(ref base, ref field) => self.field(base, field, location),
+            ExpressionKind::Length(ref base) => {
+                let base = self.expression(base)?;
+                match base.type_ {
+                    // Arrays have a fixed size, so the base is not evaluated.
+                    Type::Array(_, length) if self.es300() => Ok(self.value(Expr::Constant(vec![length as f32]), INT, true)),
+                    _ if self.es300() => self.error(location, "'length' : only arrays have a length method".to_string()),
+                    _ => self.error(location, "'length' : methods are not supported in GLSL ES 1.00".to_string()),
+                }
+            }
+            ExpressionKind::Index(ref base, ref index) => self.index(base, index, location),
+            ExpressionKind::Unary(operator, ref operand) => self.unary(operator, operand, false, location),
+            ExpressionKind::Postfix(operator, ref operand) => self.unary(operator, operand, true, location),
+            ExpressionKind::Binary(operator, ref left, ref right) => self.binary(operator, left, right, location),
+            ExpressionKind::Assign(operator, ref target, ref value) => self.assign(operator, target, value, location),
+            ExpressionKind::Conditional(ref condition, ref then, ref otherwise) => {
+                let condition = self.expression(condition)?;
+                let then = self.expression(then)?;
+                let otherwise = self.expression(otherwise)?;
+                if condition.type_ != BOOL {
+                    return self.error(location, "'?:' : boolean expression expected".to_string());
+                }
+                if then.type_ != otherwise.type_ || then.type_.is_array() {
+                    let message = format!("'?:' : wrong operand types - no operation '?:' exists that takes a second \
+                                           operand of type '{}' and a third operand of type '{}'",
+                                          then.type_, otherwise.type_);
+                    return self.error(location, message);
+                }
+
+                let constant = condition.constant && then.constant && otherwise.constant;
+                let expr = Expr::Conditional(Box::new(condition.expr), Box::new(then.expr), Box::new(otherwise.expr));
+                Ok(self.value(expr, then.type_, constant))
+            }
+            ExpressionKind::Sequence(ref first, ref second) => {
+                let first = self.expression(first)?;
+                let second = self.expression(second)?;
+                let size = first.type_.slots();
+                Ok(self.value(Expr::Sequence(Box::new(first.expr), size, Box::new(second.expr)), second.type_, false))
+            }
+        }
+    }
+
+    fn identifier(&mut self, name: &str, location: Location) -> Check<Value> {
+        match self.lookup(name) {
+            Some(Symbol::Variable(variable)) => {
+                if let Some(values) = variable.constant {
+                    return Ok(self.value(Expr::Constant(values), variable.type_, true));
+                }
+                if let Some(global) = variable.global {
+                    self.globals[global].used = true;
+                }
+
+                let place = Place { address: variable.address, size: variable.type_.slots(), steps: Vec::new() };
+                Ok(Value { expr: Expr::Load(place), type_: variable.type_, constant: false, writable: variable.writable })
+            }
+            Some(_) => self.error(location, format!("'{}' : variable expected", name)),
+            None => self.error(location, format!("'{}' : undeclared identifier", name)),
+        }
+    }
+
+    // A part of `base` at a constant offset.
+    fn select(&self, base: Value, offset: usize, size: usize, type_: Type) -> Value {
+        match base.expr {
+            Expr::Load(mut place) => {
+                place.steps.push(Step::Range(offset, size));
+                Value { expr: Expr::Load(place), type_, constant: false, writable: base.writable }
+            }
+            expr => {
+                let total = base.type_.slots();
+                self.value(Expr::Extract(Box::new(expr), total, offset, size), type_, base.constant)
+            }
+        }
+    }
+
+    fn field(&mut self, base: &Expression, field: &str, location: Location) -> Check<Value> {
+        let base = self.expression(base)?;
+
+        match base.type_.clone() {
+            Type::Struct(structure) => {
+                let mut offset = 0;
+                for (name, type_) in &structure.fields {
+                    if name == field {
+                        return Ok(self.select(base, offset, type_.slots(), type_.clone()));
+                    }
+                    offset += type_.slots();
+                }
+                self.error(location, format!("'{}' : no such field in structure '{}'", field, structure.name))
+            }
+            Type::Vector(scalar, size) if size > 1 => {
+                let components = ["xyzw", "rgba", "stpq"].iter()
+                    .find_map(|set| field.chars().map(|c| set.find(c)).collect::<Option<Vec<usize>>>());
+                let components = match components {
+                    Some(ref components) if !components.is_empty() && components.len() <= 4 => components.clone(),
+                    _ => return self.error(location, format!("'{}' : illegal vector field selection", field)),
+                };
+                if components.iter().any(|&component| component >= size) {
+                    return self.error(location, format!("'{}' : vector field selection out of range", field));
+                }
+
+                let type_ = Type::Vector(scalar, components.len());
+                let repeated = components.iter().enumerate().any(|(index, component)| components[..index].contains(component));
+                match base.expr {
+                    Expr::Load(mut place) => {
+                        place.steps.push(Step::Swizzle(components));
+                        Ok(Value { expr: Expr::Load(place), type_, constant: false, writable: base.writable && !repeated })
+                    }
+                    expr => Ok(self.value(Expr::Swizzle(Box::new(expr), size, components), type_, base.constant)),
+                }
+            }
+            type_ => self.error(location, format!("'{}' : field selection requires structure or vector on left hand \
+                                                   side, not '{}'", field, type_)),
+        }
+    }
+
+    fn index(&mut self, base: &Expression, index: &Expression, location: Location) -> Check<Value> {
+        let base = self.expression(base)?;
+        let index = self.expression(index)?;
+        if index.type_ != INT && !(self.es300() && index.type_ == UINT) {
+            return self.error(location, "'[]' : integer expression required".to_string());
+        }
+
+        let (element, count) = match base.type_ {
+            Type::Array(ref element, count) => ((**element).clone(), count),
+            Type::Vector(scalar, size) if size > 1 => (Type::Vector(scalar, 1), size),
+            Type::Matrix(columns, rows) => (Type::Vector(Scalar::Float, rows), columns),
+            _ => return self.error(location, "'[]' : left of '[' is not of type array, matrix, or vector".to_string()),
+        };
+        let size = element.slots();
+
+        if let Some(value) = constant_int(&index) {
+            if value < 0 || value >= count as i64 {
+                return self.error(location, format!("'[]' : index out of range '{}'", value));
+            }
+            return Ok(self.select(base, value as usize * size, size, element));
+        }
+
+        match base.expr {
+            Expr::Load(mut place) => {
+                place.steps.push(Step::Index(Box::new(index.expr), size, count));
+                Ok(Value { expr: Expr::Load(place), type_: element, constant: false, writable: base.writable })
+            }
+            expr => {
+                let total = base.type_.slots();
+                Ok(self.value(Expr::Index(Box::new(expr), total, Box::new(index.expr), size, count), element, false))
+            }
+        }
+    }
+
+    fn unary(&mut self, operator: &str, operand: &Expression, postfix: bool, location: Location) -> Check<Value> {
+        let operand = self.expression(operand)?;
+        let type_ = operand.type_.clone();
+        let wrong = format!("'{}' : wrong operand type - no operation '{}' exists that takes an operand of type '{}'",
+                            operator, operator, type_);
+
+        match operator {
+            "++" | "--" if type_.is_numeric() => {
+                let place = self.l_value(operand, operator, location)?;
+                let delta = if operator == "++" { 1.0 } else { -1.0 };
+                Ok(Value { expr: Expr::Increment(place, delta, postfix), type_, constant: false, writable: false })
+            }
+            "+" if type_.is_numeric() => Ok(Value { writable: false, ..operand }),
+            "-" if type_.is_numeric() => Ok(self.value(Expr::Negate(Box::new(operand.expr)), type_, operand.constant)),
+            "!" if type_ == BOOL => Ok(self.value(Expr::Not(Box::new(operand.expr)), type_, operand.constant)),
+            "~" if self.es300() && type_.is_integer() => {
+                let unsigned = type_.scalar() == Some(Scalar::Uint);
+                Ok(self.value(Expr::Complement(Box::new(operand.expr), unsigned), type_, operand.constant))
+            }
+            "~" if !self.es300() => self.error(location, "'~' : reserved operator".to_string()),
+            _ => self.error(location, wrong),
+        }
+    }
+
+    fn binary(&mut self, operator: &str, left: &Expression, right: &Expression, location: Location) -> Check<Value> {
+        let left = self.expression(left)?;
+        let right = self.expression(right)?;
+        let constant = left.constant && right.constant;
+        let wrong = format!("'{}' : wrong operand types - no operation '{}' exists that takes a left-hand operand of \
+                             type '{}' and a right operand of type '{}'", operator, operator, left.type_, right.type_);
+
+        let (expr, type_) = match operator {
+            "%" | "&" | "|" | "^" | "<<" | ">>" if !self.es300() => {
+                return self.error(location, format!("'{}' : reserved operator", operator));
+            }
+            "%" | "&" | "|" | "^" | "<<" | ">>" => match integer_operator(operator, &left.type_, &right.type_) {
+                Some((operator, type_)) => (Expr::Binary(operator, Box::new(left.expr), Box::new(right.expr)), type_),
+                None => return self.error(location, wrong),
+            },
+            "&&" | "||" | "^^" => {
+                if left.type_ != BOOL || right.type_ != BOOL {
+                    return self.error(location, wrong);
+                }
+                let (left, right) = (Box::new(left.expr), Box::new(right.expr));
+                let expr = match operator {
+                    "&&" => Expr::And(left, right),
+                    "||" => Expr::Or(left, right),
+                    _ => Expr::Binary(BinaryOp::Xor, left, right),
+                };
+                (expr, BOOL)
+            }
+            "==" | "!=" => {
+                let array = left.type_.contains_array() && !self.es300();
+                if left.type_ != right.type_ || array || left.type_.contains_sampler() {
+                    return self.error(location, wrong);
+                }
+                let operator = if operator == "==" { BinaryOp::Equal } else { BinaryOp::NotEqual };
+                (Expr::Binary(operator, Box::new(left.expr), Box::new(right.expr)), BOOL)
+            }
+            "<" | ">" | "<=" | ">=" => {
+                if left.type_ != right.type_ || !left.type_.is_scalar() || !left.type_.is_numeric() {
+                    return self.error(location, wrong);
+                }
+                let operator = match operator {
+                    "<" => BinaryOp::Less,
+                    ">" => BinaryOp::Greater,
+                    "<=" => BinaryOp::LessEqual,
+                    _ => BinaryOp::GreaterEqual,
+                };
+                (Expr::Binary(operator, Box::new(left.expr), Box::new(right.expr)), BOOL)
+            }
+            _ => match arithmetic(operator, &left.type_, &right.type_) {
+                Some((operator, type_)) => (Expr::Binary(operator, Box::new(left.expr), Box::new(right.expr)), type_),
+                None => return self.error(location, wrong),
+            },
+        };
+
+        Ok(self.value(expr, type_, constant))
+    }
+
+    fn assign(&mut self, operator: &'static str, target: &Expression, value: &Expression, location: Location)
+        -> Check<Value> {
+        let target = self.expression(target)?;
+        let value = self.expression(value)?;
+        let type_ = target.type_.clone();
+
+        let combine = match operator {
+            "=" => {
+                if value.type_ != type_ {
+                    return self.error(location, format!("'=' : cannot convert from '{}' to '{}'", value.type_, type_));
+                }
+                if (type_.contains_array() && !self.es300()) || type_.contains_sampler() {
+                    return self.error(location, format!("'=' : cannot assign to '{}'", type_));
+                }
+                None
+            }
+            _ => {
+                let combined = match operator {
+                    "+=" | "-=" | "*=" | "/=" => arithmetic(&operator[..1], &type_, &value.type_),
+                    "%=" | "&=" | "|=" | "^=" | "<<=" | ">>=" if self.es300() => {
+                        integer_operator(&operator[..operator.len() - 1], &type_, &value.type_)
+                    }
+                    _ => return self.error(location, format!("'{}' : reserved operator", operator)),
+                };
+                match combined {
+                    Some((combine, ref result)) if *result == type_ => Some(combine),
+                    _ => {
+                        let message = format!("'{}' : wrong operand types - no operation '{}' exists that takes a \
+                                               left-hand operand of type '{}' and a right operand of type '{}'",
+                                              operator, operator, type_, value.type_);
+                        return self.error(location, message);
+                    }
+                }
+            }
+        };
+
+        let place = self.l_value(target, operator, location)?;
+        Ok(Value { expr: Expr::Assign(place, combine, Box::new(value.expr)), type_, constant: false, writable: false })
+    }
+
+    fn call(&mut self, name: &str, arguments: &[Expression], location: Location) -> Check<Value> {
+        let mut values = Vec::new();
+        for argument in arguments {
+            values.push(self.expression(argument)?);
+        }
+
+        match self.lookup(name) {
+            Some(Symbol::Struct(structure)) => self.struct_constructor(structure, values, location),
+            Some(Symbol::Function(overloads)) => self.user_call(name, &overloads, values, location),
+            Some(Symbol::Variable(_)) => self.error(location, format!("'{}' : function name expected", name)),
+            None if is_builtin_function(name, self.version) => self.builtin_call(name, values, location),
+            None => self.error(location, format!("'{}' : no matching overloaded function found", name)),
+        }
+    }
+
+    fn builtin_call(&mut self, name: &str, values: Vec<Value>, location: Location) -> Check<Value> {
+        let types: Vec<Type> = values.iter().map(|value| value.type_.clone()).collect();
+        let (type_, builtin) = match builtin_function(name, &types, self.stage, self.version) {
+            Some(overload) => overload,
+            None => return self.error(location, format!("'{}' : no matching overloaded function found", name)),
+        };
+
+        if let Builtin::Texture(texture) = builtin {
+            if texture.offset {
+                let index = values.len() - 1 - (texture.lod == LodArgument::Bias) as usize;
+                self.texel_offset(name, &values[index], location)?;
+            }
+        }
+        if name == "modf" {
+            return self.modf(values, type_, location);
+        }
+
+        let constant = !matches!(builtin, Builtin::Texture(_) | Builtin::TextureSize)
+            && values.iter().all(|value| value.constant);
+        let arguments = values.into_iter().map(|value| {
+            let size = value.type_.slots();
+            (value.expr, size)
+        }).collect();
+        let size = type_.slots();
+        Ok(self.value(Expr::Builtin(builtin, arguments, size), type_, constant))
+    }
+
+    // Texel offsets are constant and within the limits of the implementation.
+    fn texel_offset(&mut self, name: &str, offset: &Value, location: Location) -> Check<()> {
+        let (minimum, maximum) = (self.limits.min_program_texel_offset, self.limits.max_program_texel_offset);
+        match offset.expr {
+            Expr::Constant(ref values) => {
+                if values.iter().any(|&value| (value as i32) < minimum || (value as i32) > maximum) {
+                    return self.error(location, format!("'{}' : texel offset out of range [{}, {}]", name, minimum,
+                                                        maximum));
+                }
+                Ok(())
+            }
+            _ => self.error(location, format!("'{}' : texel offset must be a constant expression", name)),
+        }
+    }
+
+    // `modf(x, i)` stores the whole part of `x` to `i` and yields the fractional part. `x` goes to a
+    // temporary first, so that it is evaluated once.
+    fn modf(&mut self, mut values: Vec<Value>, type_: Type, location: Location) -> Check<Value> {
+        let whole = values.pop().unwrap();
+        let whole = self.l_value(whole, "modf", location)?;
+        let x = values.pop().unwrap();
+        let size = type_.slots();
+
+        let temporary = Place { address: self.allocate(size), size, steps: Vec::new() };
+        let load = || (Expr::Load(temporary.clone()), size);
+        let store = Expr::Assign(temporary.clone(), None, Box::new(x.expr));
+        let truncate = Expr::Assign(whole, None, Box::new(Expr::Builtin(Builtin::Map(f32::trunc), vec![load()], size)));
+        let fraction = Expr::Builtin(Builtin::Map2(|x, _| x - x.trunc()), vec![load(), load()], size);
+
+        let expr = Expr::Sequence(Box::new(store), size, Box::new(Expr::Sequence(Box::new(truncate), size,
+                                                                                 Box::new(fraction))));
+        Ok(Value { expr, type_, constant: false, writable: false })
+    }
+
+    // A constructor of a type named by a keyword, of a structure or, in GLSL ES 3.00, of an array.
+    fn construct(&mut self, specifier: &TypeSpecifier, arguments: &[Expression], location: Location)
+        -> Check<Value> {
+        let mut values = Vec::new();
+        for argument in arguments {
+            values.push(self.expression(argument)?);
+        }
+
+        let base = self.base_type(specifier)?;
+        if let Some(ref size) = specifier.array {
+            let type_ = self.array_of(base, size, Some(values.len()), location)?;
+            return self.array_constructor(type_, values, location);
+        }
+        match base {
+            Type::Struct(structure) => self.struct_constructor(structure, values, location),
+            type_ => {
+                let name = type_.to_string();
+                self.constructor(&name, type_, values, location)
+            }
+        }
+    }
+
+    fn array_constructor(&mut self, type_: Type, values: Vec<Value>, location: Location) -> Check<Value> {
+        let (element, length) = match type_ {
+            Type::Array(ref element, length) => ((**element).clone(), length),
+            _ => unreachable!(),
+        };
+        if values.len() != length {
+            return self.error(location, format!("'{}' : array constructor needs {} arguments", type_, length));
+        }
+        if let Some(value) = values.iter().find(|value| value.type_ != element) {
+            return self.error(location, format!("'{}' : cannot convert from '{}' to '{}'", type_, value.type_, element));
+        }
+
+        let constant = values.iter().all(|value| value.constant);
+        let arguments = values.into_iter().map(|value| {
+            let size = value.type_.slots();
+            (value.expr, size)
+        }).collect();
+        Ok(self.value(Expr::Construct(Construct::Concatenate, arguments), type_, constant))
+    }
+
+    fn constructor(&mut self, name: &str, type_: Type, values: Vec<Value>, location: Location) -> Check<Value> {
+        if values.is_empty() {
+            return self.error(location, format!("'{}' : constructor does not have any arguments", name));
+        }
+        for value in &values {
+            if value.type_.scalar().is_none() {
+                return self.error(location, format!("'{}' : cannot construct from '{}'", name, value.type_));
+            }
+        }
+
+        let conversion = match type_.scalar() {
+            Some(Scalar::Float) => Conversion::Float,
+            Some(Scalar::Int) => Conversion::Int,
+            Some(Scalar::Uint) => Conversion::Uint,
+            Some(Scalar::Bool) => Conversion::Bool,
+            None => return self.error(location, format!("'{}' : cannot construct this type", name)),
+        };
+
+        let single_scalar = values.len() == 1 && values[0].type_.is_scalar();
+        let single_matrix = match values[0].type_ {
+            Type::Matrix(columns, rows) if values.len() == 1 => Some((columns, rows)),
+            _ => None,
+        };
+        let construct = match (&type_, single_matrix) {
+            (&Type::Vector(_, size), _) if single_scalar && size > 1 => Construct::Replicate(size, conversion),
+            (&Type::Matrix(columns, rows), _) if single_scalar => Construct::Diagonal(columns, rows),
+            (&Type::Matrix(columns, rows), Some((from_columns, from_rows))) if self.es300() => {
+                Construct::Resize(from_columns, from_rows, columns, rows)
+            }
+            _ => {
+                if let Type::Matrix(..) = type_ {
+                    if values.iter().any(|value| matches!(value.type_, Type::Matrix(..))) {
+                        let message = match self.version {
+                            GlslVersion::Es100 => "constructing matrices from matrices is reserved",
+                            GlslVersion::Es300 => "a matrix argument must be the only one",
+                        };
+                        return self.error(location, format!("'{}' : {}", name, message));
+                    }
+                }
+
+                let count = type_.slots();
+                let mut provided = 0;
+                for value in &values {
+                    if provided >= count {
+                        return self.error(location, format!("'{}' : too many arguments", name));
+                    }
+                    provided += value.type_.slots();
+                }
+                if provided < count {
+                    return self.error(location, format!("'{}' : not enough data provided for construction", name));
+                }
+                Construct::Components(count, conversion)
+            }
+        };
+
+        let constant = values.iter().all(|value| value.constant);
+        let arguments = values.into_iter().map(|value| {
+            let size = value.type_.slots();
+            (value.expr, size)
+        }).collect();
+        Ok(self.value(Expr::Construct(construct, arguments), type_, constant))
+    }
+
+    fn struct_constructor(&mut self, structure: Rc<StructType>, values: Vec<Value>, location: Location) -> Check<Value> {
+        if values.len() != structure.fields.len() {
+            return self.error(location, format!("'{}' : number of constructor parameters does not match the number \
+                                                 of structure fields", structure.name));
+        }
+        for (value, field) in values.iter().zip(&structure.fields) {
+            if value.type_ != field.1 {
+                return self.error(location, format!("'{}' : cannot convert from '{}' to '{}'", structure.name,
+                                                    value.type_, field.1));
+            }
+        }
+
+        let constant = values.iter().all(|value| value.constant);
+        let arguments = values.into_iter().map(|value| {
+            let size = value.type_.slots();
+            (value.expr, size)
+        }).collect();
+        Ok(self.value(Expr::Construct(Construct::Concatenate, arguments), Type::Struct(structure), constant))
+    }
+
+    fn user_call(&mut self, name: &str, overloads: &[usize], values: Vec<Value>, location: Location) -> Check<Value> {
+        let index = overloads.iter().cloned().find(|&index| {
+            let parameters = &self.signatures[index].parameters;
+            parameters.len() == values.len() && parameters.iter().zip(&values).all(|(parameter, value)| parameter.0 == value.type_)
+        });
+        let index = match index {
+            Some(index) => index,
+            None => return self.error(location, format!("'{}' : no matching overloaded function found", name)),
+        };
+
+        let qualifiers: Vec<ParameterQualifier> = self.signatures[index].parameters.iter().map(|parameter| parameter.1).collect();
+        let mut arguments = Vec::new();
+        for (value, qualifier) in values.into_iter().zip(qualifiers) {
+            arguments.push(match qualifier {
+                ParameterQualifier::In => Argument::In(value.expr),
+                ParameterQualifier::Out => Argument::Out(self.l_value(value, name, location)?),
+                ParameterQualifier::InOut => Argument::InOut(self.l_value(value, name, location)?),
+            });
+        }
+
+        if let Some(current) = self.current {
+            self.calls[current].push(index);
+        }
+
+        let type_ = self.signatures[index].return_type.clone();
+        Ok(Value { expr: Expr::Call(index, arguments), type_, constant: false, writable: false })
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+// The interface a variable of this storage qualifier belongs to in this stage.
+fn interface_storage(storage: StorageQualifier, stage: ShaderType) -> Option<Storage> {
+    let vertex = stage == ShaderType::VERTEX_SHADER;
+    match storage {
+        StorageQualifier::Attribute => Some(Storage::Attribute),
+        StorageQualifier::Uniform => Some(Storage::Uniform),
+        StorageQualifier::Varying => Some(Storage::Varying),
+        StorageQualifier::In if vertex => Some(Storage::Attribute),
+        StorageQualifier::Out if vertex => Some(Storage::Varying),
+        StorageQualifier::In => Some(Storage::Varying),
+        StorageQualifier::Out => Some(Storage::Output),
+        StorageQualifier::None | StorageQualifier::Const => None,
+    }
+}
+
+fn storage_keyword(storage: StorageQualifier) -> &'static str {
+    match storage {
+        StorageQualifier::Attribute => "attribute",
+        StorageQualifier::Uniform => "uniform",
+        StorageQualifier::Varying => "varying",
+        StorageQualifier::In => "in",
+        StorageQualifier::Out => "out",
+        StorageQualifier::Const => "const",
+        StorageQualifier::None => "",
+    }
+}
+
+fn constant_int(value: &Value) -> Option<i64> {
+    match value.expr {
+        Expr::Constant(ref values) if value.type_ == INT || value.type_ == UINT => Some(values[0] as i64),
+        _ => None,
+    }
+}
+
+// The blocks of rows and columns the parts of a variable of this type take when packed, `count`
+// times each.
+fn packing_blocks(type_: &Type, count: usize, blocks: &mut Vec<(usize, usize)>) {
+    match *type_ {
+        Type::Vector(_, size) => blocks.push((count, size)),
+        Type::Matrix(columns, rows) => blocks.push((count * columns, rows)),
+        Type::Array(ref element, length) => packing_blocks(element, count * length, blocks),
+        Type::Struct(ref structure) => {
+            for field in &structure.fields {
+                packing_blocks(&field.1, count, blocks);
+            }
+        }
+        Type::Sampler(_) | Type::Void => {}
+    }
+}
+
+fn sampler_count(type_: &Type) -> usize {
+    match *type_ {
+        Type::Sampler(_) => 1,
+        Type::Array(ref element, length) => length * sampler_count(element),
+        Type::Struct(ref structure) => structure.fields.iter().map(|field| sampler_count(&field.1)).sum(),
+        _ => 0,
+    }
+}
+
+// Attributes take a slot for each matrix column.
+fn attribute_slots(type_: &Type) -> usize {
+    match *type_ {
+        Type::Matrix(columns, _) => columns,
+        _ => 1,
+    }
+}
+
+// The first variable at which the running total of `counts` goes beyond `limit`.
+fn first_beyond<'a, I>(counts: I, limit: usize) -> Option<&'a GlobalVariable>
+    where I: Iterator<Item = (&'a GlobalVariable, usize)> {
+    let mut total = 0;
+    for (global, count) in counts {
+        total += count;
+        if total > limit {
+            return Some(global);
+        }
+    }
+    None
+}
+
+// The operator and result type of `+`, `-`, `*` and `/` on operands of these types.
+fn arithmetic(operator: &str, left: &Type, right: &Type) -> Option<(BinaryOp, Type)> {
+    let scalar = left.scalar()?;
+    if right.scalar()? != scalar || scalar == Scalar::Bool {
+        return None;
+    }
+
+    let componentwise = match operator {
+        "+" => BinaryOp::Add,
+        "-" => BinaryOp::Subtract,
+        "*" => BinaryOp::Multiply,
+        "/" if scalar == Scalar::Int || scalar == Scalar::Uint => BinaryOp::DivideInt,
+        "/" => BinaryOp::Divide,
+        _ => return None,
+    };
+
+    if operator == "*" {
+        match (left, right) {
+            (&Type::Matrix(columns, rows), &Type::Matrix(other_columns, other_rows)) => {
+                return if columns == other_rows {
+                    Some((BinaryOp::Product(rows, columns, other_columns), Type::Matrix(other_columns, rows)))
+                } else {
+                    None
+                };
+            }
+            (&Type::Matrix(columns, rows), &Type::Vector(_, size)) if size > 1 => {
+                return if size == columns { Some((BinaryOp::Product(rows, columns, 1), Type::Vector(scalar, rows))) } else { None };
+            }
+            (&Type::Vector(_, size), &Type::Matrix(columns, rows)) if size > 1 => {
+                return if size == rows { Some((BinaryOp::Product(1, rows, columns), Type::Vector(scalar, columns))) } else { None };
+            }
+            _ => {}
+        }
+    }
+
+    let type_ = if left == right {
+        left.clone()
+    } else if left.is_scalar() {
+        right.clone()
+    } else if right.is_scalar() {
+        left.clone()
+    } else {
+        return None;
+    };
+    Some((componentwise, type_))
+}
+
+// The operator and result type of the GLSL ES 3.00 integer operators `%`, `&`, `|`, `^`, `<<` and
+// `>>`. Shifts take any integer type on the right; the others need the same kind on both sides.
+fn integer_operator(operator: &str, left: &Type, right: &Type) -> Option<(BinaryOp, Type)> {
+    if !left.is_integer() || !right.is_integer() {
+        return None;
+    }
+
+    let operation = match operator {
+        "%" => IntegerOp::Remainder,
+        "&" => IntegerOp::And,
+        "|" => IntegerOp::Or,
+        "^" => IntegerOp::Xor,
+        "<<" => IntegerOp::ShiftLeft,
+        _ => IntegerOp::ShiftRight,
+    };
+
+    let shift = operation == IntegerOp::ShiftLeft || operation == IntegerOp::ShiftRight;
+    let type_ = match (left, right) {
+        (&Type::Vector(_, a), &Type::Vector(_, b)) if shift && (b == 1 || a == b) => left,
+        _ if shift || left.scalar() != right.scalar() => return None,
+        _ if left == right || right.is_scalar() => left,
+        _ if left.is_scalar() => right,
+        _ => return None,
+    };
+    Some((BinaryOp::Integer(operation, left.scalar() == Some(Scalar::Uint)), type_.clone()))
+}
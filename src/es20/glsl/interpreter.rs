@@ -0,0 +1,658 @@
+use super::ir::*;
+use super::types::{Scalar, Shape};
+use super::{Environment, Lod};
+
+// What ended the execution of a statement.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Flow {
+    Normal,
+    Break,
+    Continue,
+    Return,
+    Discard,
+}
+
+// Runs checked code over the memory of one shader invocation. Expressions push their values on
+// `stack`; operators replace their operands with their result.
+pub(super) struct Machine<'a> {
+    pub memory: &'a mut [f32],
+    stack: &'a mut Vec<f32>,
+    functions: &'a [FunctionCode],
+    environment: &'a dyn Environment,
+    // Set by `discard` anywhere in the call tree, which ends the invocation.
+    discarded: bool,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(memory: &'a mut [f32], stack: &'a mut Vec<f32>, functions: &'a [FunctionCode],
+               environment: &'a dyn Environment) -> Machine<'a> {
+        stack.clear();
+        Machine { memory, stack, functions, environment, discarded: false }
+    }
+
+    // Runs `main` and returns `false` when the invocation was discarded.
+    pub fn run(&mut self, main: usize) -> bool {
+        if let Some(ref body) = self.functions[main].body {
+            for statement in body {
+                if self.execute(statement) != Flow::Normal {
+                    break;
+                }
+            }
+        }
+        !self.discarded
+    }
+
+    // Evaluates an expression that reads no memory and calls no functions.
+    pub fn fold(&mut self, expression: &Expr) -> Vec<f32> {
+        self.evaluate(expression);
+        self.stack.split_off(0)
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // STATEMENTS
+    // -------------------------------------------------------------------------------------------------
+
+    fn execute(&mut self, statement: &Stmt) -> Flow {
+        if self.discarded {
+            return Flow::Discard;
+        }
+
+        match *statement {
+            Stmt::Expr(ref expression, size) => {
+                self.evaluate(expression);
+                let length = self.stack.len() - size;
+                self.stack.truncate(length);
+            }
+            Stmt::Block(ref statements) => {
+                for statement in statements {
+                    let flow = self.execute(statement);
+                    if flow != Flow::Normal {
+                        return flow;
+                    }
+                }
+            }
+            Stmt::If(ref condition, ref then, ref otherwise) => {
+                let flow = match self.condition(condition) {
+                    Some(true) => self.execute(then),
+                    Some(false) => otherwise.as_ref().map_or(Flow::Normal, |otherwise| self.execute(otherwise)),
+                    None => Flow::Discard,
+                };
+                return flow;
+            }
+            Stmt::Loop(ref loop_) => {
+                for statement in &loop_.init {
+                    self.execute(statement);
+                }
+
+                let mut first = true;
+                loop {
+                    if loop_.test_first || !first {
+                        if let Some(ref condition) = loop_.condition {
+                            match self.condition(condition) {
+                                Some(true) => {}
+                                Some(false) => break,
+                                None => return Flow::Discard,
+                            }
+                        }
+                    }
+                    first = false;
+
+                    match self.execute(&loop_.body) {
+                        Flow::Break => break,
+                        Flow::Return => return Flow::Return,
+                        Flow::Discard => return Flow::Discard,
+                        Flow::Normal | Flow::Continue => {}
+                    }
+
+                    if let Some((ref step, size)) = loop_.step {
+                        self.evaluate(step);
+                        let length = self.stack.len() - size;
+                        self.stack.truncate(length);
+                    }
+                }
+            }
+            Stmt::Switch(ref selector, ref labels, ref statements) => {
+                self.evaluate(selector);
+                let value = self.pop();
+                let target = labels.iter().find(|label| label.0 == Some(value))
+                    .or_else(|| labels.iter().find(|label| label.0.is_none()));
+
+                if let Some(&(_, first)) = target {
+                    for statement in &statements[first..] {
+                        match self.execute(statement) {
+                            Flow::Normal => {}
+                            Flow::Break => break,
+                            flow => return flow,
+                        }
+                    }
+                }
+            }
+            Stmt::Return(ref value) => {
+                if let Some(ref value) = *value {
+                    self.evaluate(value);
+                }
+                return Flow::Return;
+            }
+            Stmt::Break => return Flow::Break,
+            Stmt::Continue => return Flow::Continue,
+            Stmt::Discard => self.discarded = true,
+        }
+
+        if self.discarded { Flow::Discard } else { Flow::Normal }
+    }
+
+    // `None` when evaluating the condition discarded the invocation.
+    fn condition(&mut self, condition: &Expr) -> Option<bool> {
+        self.evaluate(condition);
+        let value = self.pop();
+        if self.discarded { None } else { Some(value != 0.0) }
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // EXPRESSIONS
+    // -------------------------------------------------------------------------------------------------
+
+    fn pop(&mut self) -> f32 {
+        self.stack.pop().unwrap_or(0.0)
+    }
+
+    // Replaces everything on the stack from `start` on with `values`.
+    fn replace(&mut self, start: usize, values: &[f32]) {
+        self.stack.truncate(start);
+        self.stack.extend_from_slice(values);
+    }
+
+    fn evaluate(&mut self, expression: &Expr) {
+        let start = self.stack.len();
+
+        match *expression {
+            Expr::Constant(ref values) => self.stack.extend_from_slice(values),
+            Expr::Load(ref place) => {
+                if place.steps.is_empty() {
+                    let values = &self.memory[place.address..place.address + place.size];
+                    self.stack.extend_from_slice(values);
+                } else {
+                    for address in self.resolve(place) {
+                        let value = self.memory[address];
+                        self.stack.push(value);
+                    }
+                }
+            }
+            Expr::Assign(ref place, operator, ref value) => {
+                let addresses = self.resolve(place);
+                if let Some(operator) = operator {
+                    for &address in &addresses {
+                        let current = self.memory[address];
+                        self.stack.push(current);
+                    }
+                    let middle = self.stack.len();
+                    self.evaluate(value);
+                    self.binary(operator, start, middle);
+                } else {
+                    self.evaluate(value);
+                }
+
+                for (&address, &value) in addresses.iter().zip(&self.stack[start..]) {
+                    self.memory[address] = value;
+                }
+            }
+            Expr::Increment(ref place, delta, postfix) => {
+                for address in self.resolve(place) {
+                    let old = self.memory[address];
+                    self.memory[address] = old + delta;
+                    self.stack.push(if postfix { old } else { old + delta });
+                }
+            }
+            Expr::Binary(operator, ref left, ref right) => {
+                self.evaluate(left);
+                let middle = self.stack.len();
+                self.evaluate(right);
+                self.binary(operator, start, middle);
+            }
+            Expr::And(ref left, ref right) => {
+                self.evaluate(left);
+                if self.pop() != 0.0 { self.evaluate(right) } else { self.stack.push(0.0) }
+            }
+            Expr::Or(ref left, ref right) => {
+                self.evaluate(left);
+                if self.pop() != 0.0 { self.stack.push(1.0) } else { self.evaluate(right) }
+            }
+            Expr::Not(ref operand) => {
+                self.evaluate(operand);
+                let value = self.pop();
+                self.stack.push(if value != 0.0 { 0.0 } else { 1.0 });
+            }
+            Expr::Negate(ref operand) => {
+                self.evaluate(operand);
+                for value in &mut self.stack[start..] {
+                    *value = -*value;
+                }
+            }
+            Expr::Complement(ref operand, unsigned) => {
+                self.evaluate(operand);
+                for value in &mut self.stack[start..] {
+                    *value = integer(!(*value as i64), unsigned);
+                }
+            }
+            Expr::Conditional(ref condition, ref then, ref otherwise) => {
+                self.evaluate(condition);
+                if self.pop() != 0.0 { self.evaluate(then) } else { self.evaluate(otherwise) }
+            }
+            Expr::Sequence(ref first, size, ref second) => {
+                self.evaluate(first);
+                let length = self.stack.len() - size;
+                self.stack.truncate(length);
+                self.evaluate(second);
+            }
+            Expr::Swizzle(ref operand, _, ref components) => {
+                self.evaluate(operand);
+                let mut values = [0.0; 4];
+                for (value, &component) in values.iter_mut().zip(components) {
+                    *value = self.stack[start + component];
+                }
+                self.replace(start, &values[..components.len()]);
+            }
+            Expr::Extract(ref operand, _, offset, length) => {
+                self.evaluate(operand);
+                self.stack.copy_within(start + offset..start + offset + length, start);
+                self.stack.truncate(start + length);
+            }
+            Expr::Index(ref operand, _, ref index, size, count) => {
+                self.evaluate(operand);
+                self.evaluate(index);
+                let index = element(self.pop(), count);
+                self.stack.copy_within(start + index * size..start + (index + 1) * size, start);
+                self.stack.truncate(start + size);
+            }
+            Expr::Construct(construct, ref arguments) => {
+                for (argument, _) in arguments {
+                    self.evaluate(argument);
+                }
+                self.construct(construct, start);
+            }
+            Expr::Call(function, ref arguments) => self.call(function, arguments),
+            Expr::Builtin(builtin, ref arguments, size) => {
+                for (argument, _) in arguments {
+                    self.evaluate(argument);
+                }
+                let mut result = [0.0; 16];
+                {
+                    let mut values = [&[][..]; 5];
+                    let mut offset = start;
+                    for (value, &(_, size)) in values.iter_mut().zip(arguments) {
+                        *value = &self.stack[offset..offset + size];
+                        offset += size;
+                    }
+                    builtin_function(builtin, &values[..arguments.len()], &mut result[..size], self.environment);
+                }
+                self.replace(start, &result[..size]);
+            }
+        }
+    }
+
+    // The memory addresses `place` refers to, in component order.
+    fn resolve(&mut self, place: &Place) -> Vec<usize> {
+        let mut addresses: Vec<usize> = (place.address..place.address + place.size).collect();
+
+        for step in &place.steps {
+            match *step {
+                Step::Range(offset, length) => {
+                    addresses.drain(..offset);
+                    addresses.truncate(length);
+                }
+                Step::Index(ref index, size, count) => {
+                    self.evaluate(index);
+                    let index = element(self.pop(), count);
+                    addresses.drain(..index * size);
+                    addresses.truncate(size);
+                }
+                Step::Swizzle(ref components) => {
+                    addresses = components.iter().map(|&component| addresses[component]).collect();
+                }
+            }
+        }
+
+        addresses
+    }
+
+    // Applies `operator` to the operands on the stack from `start` and `middle` on.
+    fn binary(&mut self, operator: BinaryOp, start: usize, middle: usize) {
+        let mut result = [0.0; 16];
+        let size = {
+            let (left, right) = self.stack[start..].split_at(middle - start);
+            binary_operator(operator, left, right, &mut result)
+        };
+        self.replace(start, &result[..size]);
+    }
+
+    fn construct(&mut self, construct: Construct, start: usize) {
+        let mut result = [0.0; 16];
+        let size = {
+            let values = &self.stack[start..];
+            match construct {
+                Construct::Components(count, conversion) => {
+                    for (result, &value) in result.iter_mut().zip(values).take(count) {
+                        *result = convert(value, conversion);
+                    }
+                    count
+                }
+                Construct::Replicate(count, conversion) => {
+                    for result in &mut result[..count] {
+                        *result = convert(values[0], conversion);
+                    }
+                    count
+                }
+                Construct::Diagonal(columns, rows) => {
+                    for diagonal in 0..columns.min(rows) {
+                        result[diagonal * rows + diagonal] = values[0];
+                    }
+                    columns * rows
+                }
+                Construct::Resize(from_columns, from_rows, columns, rows) => {
+                    for column in 0..columns {
+                        for row in 0..rows {
+                            result[column * rows + row] = if column < from_columns && row < from_rows {
+                                values[column * from_rows + row]
+                            } else {
+                                boolean(column == row)
+                            };
+                        }
+                    }
+                    columns * rows
+                }
+                Construct::Concatenate => return,
+            }
+        };
+        self.replace(start, &result[..size]);
+    }
+
+    fn call(&mut self, index: usize, arguments: &[Argument]) {
+        let functions = self.functions;
+        let function = &functions[index];
+
+        // Output places are resolved before the call, and written back after it.
+        let mut outputs = Vec::new();
+        for (parameter, argument) in arguments.iter().enumerate() {
+            match *argument {
+                Argument::In(ref value) => self.evaluate(value),
+                Argument::Out(ref place) => {
+                    let addresses = self.resolve(place);
+                    let length = self.stack.len() + addresses.len();
+                    self.stack.resize(length, 0.0);
+                    outputs.push((parameter, addresses));
+                }
+                Argument::InOut(ref place) => {
+                    let addresses = self.resolve(place);
+                    for &address in &addresses {
+                        let value = self.memory[address];
+                        self.stack.push(value);
+                    }
+                    outputs.push((parameter, addresses));
+                }
+            }
+        }
+
+        for &(address, size) in function.parameters.iter().rev() {
+            let start = self.stack.len() - size;
+            self.memory[address..address + size].copy_from_slice(&self.stack[start..]);
+            self.stack.truncate(start);
+        }
+
+        let base = self.stack.len();
+        if let Some(ref body) = function.body {
+            for statement in body {
+                if self.execute(statement) != Flow::Normal {
+                    break;
+                }
+            }
+        }
+        // Falling off the end of a function with a return type leaves its value undefined.
+        self.stack.resize(base + function.return_size, 0.0);
+
+        for (parameter, addresses) in outputs {
+            let address = function.parameters[parameter].0;
+            for (offset, &target) in addresses.iter().enumerate() {
+                self.memory[target] = self.memory[address + offset];
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// OPERATORS
+// -------------------------------------------------------------------------------------------------
+
+// An index clamped to the element count. Out-of-range indices are undefined behavior in GLSL.
+fn element(index: f32, count: usize) -> usize {
+    if index > 0.0 { (index as usize).min(count - 1) } else { 0 }
+}
+
+fn convert(value: f32, conversion: Conversion) -> f32 {
+    match conversion {
+        Conversion::Float => value,
+        Conversion::Int => integer(value as i64, false),
+        Conversion::Uint => integer(value as i64, true),
+        Conversion::Bool => (value != 0.0) as i32 as f32,
+    }
+}
+
+// Component `index` of an operand, with scalars applying to every component.
+fn component(values: &[f32], index: usize) -> f32 {
+    if values.len() == 1 { values[0] } else { values[index] }
+}
+
+fn boolean(value: bool) -> f32 {
+    if value { 1.0 } else { 0.0 }
+}
+
+// Wraps an integer result to 32 bits.
+fn integer(value: i64, unsigned: bool) -> f32 {
+    if unsigned { value as u32 as f32 } else { value as i32 as f32 }
+}
+
+fn integer_operator(operator: IntegerOp, unsigned: bool, a: f32, b: f32) -> f32 {
+    let (a, b) = (a as i64, b as i64);
+    let value = match operator {
+        // Undefined in GLSL.
+        IntegerOp::Remainder if b == 0 => 0,
+        IntegerOp::Remainder => a % b,
+        IntegerOp::And => a & b,
+        IntegerOp::Or => a | b,
+        IntegerOp::Xor => a ^ b,
+        IntegerOp::ShiftLeft => a << (b & 31),
+        IntegerOp::ShiftRight if unsigned => (a as u32 >> (b & 31)) as i64,
+        IntegerOp::ShiftRight => (a as i32 >> (b & 31)) as i64,
+    };
+    integer(value, unsigned)
+}
+
+// Writes the result to `result` and returns its size.
+fn binary_operator(operator: BinaryOp, left: &[f32], right: &[f32], result: &mut [f32; 16]) -> usize {
+    let size = left.len().max(right.len());
+
+    let function: fn(f32, f32) -> f32 = match operator {
+        BinaryOp::Add => |a, b| a + b,
+        BinaryOp::Subtract => |a, b| a - b,
+        BinaryOp::Multiply => |a, b| a * b,
+        BinaryOp::Divide => |a, b| a / b,
+        BinaryOp::DivideInt => |a, b| if b == 0.0 { 0.0 } else { (a / b).trunc() },
+        BinaryOp::Less => |a, b| boolean(a < b),
+        BinaryOp::Greater => |a, b| boolean(a > b),
+        BinaryOp::LessEqual => |a, b| boolean(a <= b),
+        BinaryOp::GreaterEqual => |a, b| boolean(a >= b),
+        BinaryOp::Xor => |a, b| boolean((a != 0.0) != (b != 0.0)),
+        BinaryOp::Equal | BinaryOp::NotEqual => {
+            let equal = left == right;
+            result[0] = boolean(equal == (operator == BinaryOp::Equal));
+            return 1;
+        }
+        BinaryOp::Product(rows, inner, columns) => {
+            for column in 0..columns {
+                for row in 0..rows {
+                    result[column * rows + row] = (0..inner).map(|k| left[k * rows + row] * right[column * inner + k]).sum();
+                }
+            }
+            return rows * columns;
+        }
+        BinaryOp::Integer(operator, unsigned) => {
+            for (index, result) in result[..size].iter_mut().enumerate() {
+                *result = integer_operator(operator, unsigned, component(left, index), component(right, index));
+            }
+            return size;
+        }
+    };
+
+    for (index, result) in result[..size].iter_mut().enumerate() {
+        *result = function(component(left, index), component(right, index));
+    }
+    size
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+// The matrix of size `size` without column `column` and row `row`.
+fn minor(matrix: &[f32], size: usize, column: usize, row: usize) -> [f32; 16] {
+    let mut minor = [0.0; 16];
+    let mut index = 0;
+    for c in (0..size).filter(|&c| c != column) {
+        for r in (0..size).filter(|&r| r != row) {
+            minor[index] = matrix[c * size + r];
+            index += 1;
+        }
+    }
+    minor
+}
+
+// By cofactor expansion along the first column, which is cheap enough up to 4x4.
+fn determinant(matrix: &[f32], size: usize) -> f32 {
+    if size == 1 {
+        return matrix[0];
+    }
+    (0..size).map(|row| {
+        let sign = if row % 2 == 0 { 1.0 } else { -1.0 };
+        sign * matrix[row] * determinant(&minor(matrix, size, 0, row), size - 1)
+    }).sum()
+}
+
+fn builtin_function(builtin: Builtin, arguments: &[&[f32]], result: &mut [f32], environment: &dyn Environment) {
+    match builtin {
+        Builtin::Map(function) => {
+            for (result, &value) in result.iter_mut().zip(arguments[0]) {
+                *result = function(value);
+            }
+        }
+        Builtin::Map2(function) => {
+            for (index, result) in result.iter_mut().enumerate() {
+                *result = function(component(arguments[0], index), component(arguments[1], index));
+            }
+        }
+        Builtin::Map3(function) => {
+            for (index, result) in result.iter_mut().enumerate() {
+                *result = function(component(arguments[0], index), component(arguments[1], index),
+                                   component(arguments[2], index));
+            }
+        }
+        Builtin::Length => result[0] = dot(arguments[0], arguments[0]).sqrt(),
+        Builtin::Distance => {
+            result[0] = arguments[0].iter().zip(arguments[1]).map(|(a, b)| (a - b) * (a - b)).sum::<f32>().sqrt();
+        }
+        Builtin::Dot => result[0] = dot(arguments[0], arguments[1]),
+        Builtin::Cross => {
+            let (a, b) = (arguments[0], arguments[1]);
+            result[0] = a[1] * b[2] - a[2] * b[1];
+            result[1] = a[2] * b[0] - a[0] * b[2];
+            result[2] = a[0] * b[1] - a[1] * b[0];
+        }
+        Builtin::Normalize => {
+            let length = dot(arguments[0], arguments[0]).sqrt();
+            for (result, &value) in result.iter_mut().zip(arguments[0]) {
+                *result = value / length;
+            }
+        }
+        Builtin::FaceForward => {
+            let sign = if dot(arguments[2], arguments[1]) < 0.0 { 1.0 } else { -1.0 };
+            for (result, &value) in result.iter_mut().zip(arguments[0]) {
+                *result = sign * value;
+            }
+        }
+        Builtin::Reflect => {
+            let (incident, normal) = (arguments[0], arguments[1]);
+            let scale = 2.0 * dot(normal, incident);
+            for (index, result) in result.iter_mut().enumerate() {
+                *result = incident[index] - scale * normal[index];
+            }
+        }
+        Builtin::Refract => {
+            let (incident, normal, eta) = (arguments[0], arguments[1], arguments[2][0]);
+            let cosine = dot(normal, incident);
+            let k = 1.0 - eta * eta * (1.0 - cosine * cosine);
+            for (index, result) in result.iter_mut().enumerate() {
+                *result = if k < 0.0 { 0.0 } else { eta * incident[index] - (eta * cosine + k.sqrt()) * normal[index] };
+            }
+        }
+        Builtin::Any => result[0] = boolean(arguments[0].iter().any(|&value| value != 0.0)),
+        Builtin::All => result[0] = boolean(arguments[0].iter().all(|&value| value != 0.0)),
+        Builtin::OuterProduct => {
+            let (column, row) = (arguments[0], arguments[1]);
+            for (index, result) in result.iter_mut().enumerate() {
+                *result = column[index % column.len()] * row[index / column.len()];
+            }
+        }
+        Builtin::Transpose(columns, rows) => {
+            for column in 0..columns {
+                for row in 0..rows {
+                    result[row * columns + column] = arguments[0][column * rows + row];
+                }
+            }
+        }
+        Builtin::Determinant(size) => result[0] = determinant(arguments[0], size),
+        Builtin::Inverse(size) => {
+            let matrix = arguments[0];
+            let scale = 1.0 / determinant(matrix, size);
+            for column in 0..size {
+                for row in 0..size {
+                    let sign = if (column + row) % 2 == 0 { 1.0 } else { -1.0 };
+                    let cofactor = sign * determinant(&minor(matrix, size, row, column), size - 1);
+                    result[column * size + row] = cofactor * scale;
+                }
+            }
+        }
+        Builtin::Pack(function) => result[0] = function([arguments[0][0], arguments[0][1]]) as f32,
+        Builtin::Unpack(function) => result.copy_from_slice(&function(arguments[0][0] as i64 as u32)),
+        // Invocations run one at a time, with no neighbors to take differences with. The result is
+        // right for the constant expressions that are folded.
+        Builtin::Derivative => {}
+        // Only GLSL ES 3.00 has these, and its shaders are validated but never run, so what an ES
+        // 2.0 environment can not look up stays zero.
+        Builtin::TextureSize => {}
+        Builtin::Texture(texture) => {
+            let sampler = texture.sampler;
+            let supported = !texture.offset && !sampler.shadow && sampler.scalar == Scalar::Float
+                && matches!(texture.lod, LodArgument::None | LodArgument::Bias | LodArgument::Explicit);
+            if !supported {
+                return;
+            }
+
+            let unit = if arguments[0][0] > 0.0 { arguments[0][0] as usize } else { 0 };
+            let coord = arguments[1];
+            let lod = match texture.lod {
+                LodArgument::Bias => Lod::Implicit(arguments[2][0]),
+                LodArgument::Explicit => Lod::Explicit(arguments[2][0]),
+                _ => Lod::Implicit(0.0),
+            };
+
+            let color = match sampler.shape {
+                Shape::Cube => environment.texture_cube(unit, [coord[0], coord[1], coord[2]], lod),
+                Shape::Dim2 if texture.projective => {
+                    let q = coord[coord.len() - 1];
+                    environment.texture_2d(unit, [coord[0] / q, coord[1] / q], lod)
+                }
+                Shape::Dim2 => environment.texture_2d(unit, [coord[0], coord[1]], lod),
+                Shape::Dim3 | Shape::Array2 => return,
+            };
+            result.copy_from_slice(&color);
+        }
+    }
+}
@@ -0,0 +1,219 @@
+// The checked program the interpreter runs. Names are resolved to memory addresses and types to
+// value sizes, so nothing here needs the symbol tables any more.
+//
+// Every variable, parameter and built-in gets its own range of the memory of a shader stage.
+// Recursion is not allowed, so the ranges of locals never need to be told apart between calls.
+
+use super::types::Sampler;
+
+// -------------------------------------------------------------------------------------------------
+// EXPRESSIONS
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Debug)]
+pub(super) enum Expr {
+    Constant(Vec<f32>),
+    Load(Place),
+    // Stores the value, combined with the current one by a compound assignment operator, and
+    // yields what was stored.
+    Assign(Place, Option<BinaryOp>, Box<Expr>),
+    // `++` or `--`: the place, the added value and whether the old value is yielded.
+    Increment(Place, f32, bool),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    // `&&` and `||`, which skip the right operand when the left decides the result.
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Negate(Box<Expr>),
+    // `~`, on unsigned integers when the flag is set.
+    Complement(Box<Expr>, bool),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    // Evaluates and drops the first value, which has the given size, then yields the second.
+    Sequence(Box<Expr>, usize, Box<Expr>),
+    // Components of a value of the given size, by index.
+    Swizzle(Box<Expr>, usize, Vec<usize>),
+    // A range of a value of the given size: offset and length.
+    Extract(Box<Expr>, usize, usize, usize),
+    // An element of an array, vector or matrix value: the value size, index, element size and
+    // element count.
+    Index(Box<Expr>, usize, Box<Expr>, usize, usize),
+    Construct(Construct, Vec<(Expr, usize)>),
+    // A user function and its arguments.
+    Call(usize, Vec<Argument>),
+    // A built-in function, the size of each argument and the size of the result.
+    Builtin(Builtin, Vec<(Expr, usize)>, usize),
+}
+
+// Where a value is stored. Addresses are resolved by applying the steps in order to the range
+// starting at `address`.
+#[derive(Clone, Debug)]
+pub(super) struct Place {
+    pub address: usize,
+    pub size: usize,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum Step {
+    // A structure field or constant index: offset and length.
+    Range(usize, usize),
+    // An index computed at run time: the index, element size and element count.
+    Index(Box<Expr>, usize, usize),
+    Swizzle(Vec<usize>),
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum Argument {
+    In(Expr),
+    Out(Place),
+    InOut(Place),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    // Division of integers, rounding toward zero.
+    DivideInt,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    // Whole values, yielding a single boolean.
+    Equal,
+    NotEqual,
+    Xor,
+    // The linear algebra product of a matrix with `rows` rows and `inner` columns and one with
+    // `inner` rows and `columns` columns. Vectors are matrices with one column on the right and
+    // one row on the left.
+    Product(usize, usize, usize),
+    // A GLSL ES 3.00 integer operator, on unsigned integers when the flag is set.
+    Integer(IntegerOp, bool),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum IntegerOp {
+    Remainder,
+    And,
+    Or,
+    Xor,
+    ShiftLeft,
+    ShiftRight,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum Construct {
+    // The first components of all arguments, converted: count and target kind.
+    Components(usize, Conversion),
+    // One scalar repeated: count and target kind.
+    Replicate(usize, Conversion),
+    // One scalar on the diagonal of a matrix: columns and rows.
+    Diagonal(usize, usize),
+    // A matrix from a matrix, filled up from the identity: columns and rows of both.
+    Resize(usize, usize, usize, usize),
+    // The arguments as they are, for structure constructors.
+    Concatenate,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum Conversion {
+    Float,
+    Int,
+    Uint,
+    Bool,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(super) enum Builtin {
+    // Component-wise functions. Scalar arguments apply to every component.
+    Map(fn(f32) -> f32),
+    Map2(fn(f32, f32) -> f32),
+    Map3(fn(f32, f32, f32) -> f32),
+    Length,
+    Distance,
+    Dot,
+    Cross,
+    Normalize,
+    FaceForward,
+    Reflect,
+    Refract,
+    Any,
+    All,
+    // The GLSL ES 3.00 matrix functions, by the columns and rows of the argument.
+    OuterProduct,
+    Transpose(usize, usize),
+    Determinant(usize),
+    Inverse(usize),
+    // Two floats to the bits of a `uint` and back.
+    Pack(fn([f32; 2]) -> u32),
+    Unpack(fn(u32) -> [f32; 2]),
+    // `dFdx`, `dFdy` and `fwidth`.
+    Derivative,
+    TextureSize,
+    Texture(Texture),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) struct Texture {
+    pub sampler: Sampler,
+    // Whether the last coordinate divides the others, as in `texture2DProj`.
+    pub projective: bool,
+    pub lod: LodArgument,
+    // Whether a constant texel offset follows the level of detail arguments.
+    pub offset: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum LodArgument {
+    None,
+    Bias,
+    Explicit,
+    // The two derivatives of `textureGrad`.
+    Gradient,
+    // The integer level of `texelFetch`, whose coordinates are integer texel positions.
+    Fetch,
+}
+
+// -------------------------------------------------------------------------------------------------
+// STATEMENTS
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Debug)]
+pub(super) enum Stmt {
+    // An expression whose value, of the given size, is dropped.
+    Expr(Expr, usize),
+    Block(Vec<Stmt>),
+    If(Expr, Box<Stmt>, Option<Box<Stmt>>),
+    // `for`, `while` and `do`-`while` loops.
+    Loop(Loop),
+    // The selector, the value of each case label with the statement it jumps to, `None` for the
+    // default label, and the statements.
+    Switch(Expr, Vec<(Option<f32>, usize)>, Vec<Stmt>),
+    Return(Option<Expr>),
+    Break,
+    Continue,
+    Discard,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Loop {
+    pub init: Vec<Stmt>,
+    pub condition: Option<Expr>,
+    // The step expression and the size of its value.
+    pub step: Option<(Expr, usize)>,
+    pub body: Box<Stmt>,
+    // `false` for `do`-`while`, which tests after the body.
+    pub test_first: bool,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct FunctionCode {
+    pub name: String,
+    // The address and size of each parameter.
+    pub parameters: Vec<(usize, usize)>,
+    pub return_size: usize,
+    // `None` until the definition is seen, for functions declared by a prototype.
+    pub body: Option<Vec<Stmt>>,
+}
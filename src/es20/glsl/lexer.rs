@@ -0,0 +1,150 @@
+use super::Location;
+
+// Punctuators, longest first so that the first match is the longest one.
+const PUNCTUATORS: [&str; 47] = [
+    "<<=", ">>=", "++", "--", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "^^", "*=", "/=", "+=", "-=",
+    "%=", "&=", "^=", "|=", "##", "(", ")", "[", "]", "{", "}", ".", ",", ";", ":", "?", "+", "-", "*", "/",
+    "%", "<", ">", "=", "!", "~", "&", "|", "^", "#",
+];
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum TokenKind {
+    Identifier,
+    Number,
+    Punctuator,
+    // A character outside the GLSL ES character set, only an error where it is not skipped.
+    Invalid,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    pub location: Location,
+    // The character offset in the source, to tell whether two tokens touch.
+    pub offset: usize,
+}
+
+impl Token {
+    pub fn is(&self, text: &str) -> bool {
+        self.kind != TokenKind::Invalid && self.text == text
+    }
+
+    // Whether `next` follows this token without whitespace in between.
+    pub fn touches(&self, next: &Token) -> bool {
+        self.offset + self.text.chars().count() == next.offset
+    }
+}
+
+// The tokens of one source line. Directives work line by line.
+#[derive(Debug)]
+pub(super) struct Line {
+    pub number: u32,
+    pub tokens: Vec<Token>,
+}
+
+// -------------------------------------------------------------------------------------------------
+// LEXING
+// -------------------------------------------------------------------------------------------------
+
+// Splits `source` into preprocessing tokens, line by line, dropping comments. A comment spanning
+// lines counts as whitespace; the tokens after it belong to the line they are on.
+pub(super) fn tokenize(source: &str) -> Vec<Line> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut lines = vec![Line { number: 1, tokens: Vec::new() }];
+    let mut index = 0;
+    // The offset of the first character of the physical line, for columns.
+    let mut line_start = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+        let number = lines.len() as u32;
+
+        if c == '\n' {
+            lines.push(Line { number: number + 1, tokens: Vec::new() });
+            index += 1;
+            line_start = index;
+            continue;
+        }
+
+        if c.is_whitespace() {
+            index += 1;
+            continue;
+        }
+
+        if c == '/' && chars.get(index + 1) == Some(&'/') {
+            while index < chars.len() && chars[index] != '\n' {
+                index += 1;
+            }
+            continue;
+        }
+
+        if c == '/' && chars.get(index + 1) == Some(&'*') {
+            index += 2;
+            while index < chars.len() && !(chars[index] == '*' && chars.get(index + 1) == Some(&'/')) {
+                if chars[index] == '\n' {
+                    let number = lines.len() as u32;
+                    lines.push(Line { number: number + 1, tokens: Vec::new() });
+                    line_start = index + 1;
+                }
+                index += 1;
+            }
+            index += 2;
+            continue;
+        }
+
+        let start = index;
+        let kind = if c.is_ascii_alphabetic() || c == '_' {
+            while index < chars.len() && (chars[index].is_ascii_alphanumeric() || chars[index] == '_') {
+                index += 1;
+            }
+            TokenKind::Identifier
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(index + 1).is_some_and(|next| next.is_ascii_digit())) {
+            // A preprocessing number: digits, letters, dots and the sign of an exponent.
+            while index < chars.len() {
+                let next = chars[index];
+                let exponent_sign = (next == '+' || next == '-') && matches!(chars[index - 1], 'e' | 'E')
+                    && !is_hex(&chars[start..index]);
+                if next.is_ascii_alphanumeric() || next == '_' || next == '.' || exponent_sign {
+                    index += 1;
+                } else {
+                    break;
+                }
+            }
+            TokenKind::Number
+        } else {
+            match PUNCTUATORS.iter().find(|punctuator| matches_at(&chars, index, punctuator)) {
+                Some(punctuator) => {
+                    index += punctuator.len();
+                    TokenKind::Punctuator
+                }
+                None => {
+                    index += 1;
+                    TokenKind::Invalid
+                }
+            }
+        };
+
+        let text: String = chars[start..index].iter().collect();
+        lines.last_mut().unwrap().tokens.push(Token {
+            kind,
+            text,
+            location: Location { source: 0, line: number, column: (start - line_start) as u32 + 1 },
+            offset: start,
+        });
+    }
+
+    lines
+}
+
+fn matches_at(chars: &[char], index: usize, text: &str) -> bool {
+    text.chars().enumerate().all(|(offset, c)| chars.get(index + offset) == Some(&c))
+}
+
+fn is_hex(chars: &[char]) -> bool {
+    chars.len() > 1 && chars[0] == '0' && (chars[1] == 'x' || chars[1] == 'X')
+}
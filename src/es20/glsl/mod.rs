@@ -0,0 +1,713 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use types::*;
+use consts::*;
+use enums::ShaderType;
+
+use self::checker::{Checked, Storage};
+use self::interpreter::Machine;
+use self::types::Type;
+
+mod ast;
+mod builtins;
+mod checker;
+mod interpreter;
+mod ir;
+mod lexer;
+mod parser;
+mod preprocessor;
+mod types;
+
+// -------------------------------------------------------------------------------------------------
+// DIAGNOSTICS
+// -------------------------------------------------------------------------------------------------
+
+/// A position in shader source: the source string number and line, as `#line` may change them,
+/// and the column.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub source: u32,
+    pub line: u32,
+    /// Counted in characters from 1; 0 for diagnostics about the whole shader.
+    pub column: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A compile error or warning, formatted like `ERROR: 0:12: 'x' : undeclared identifier`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub location: Location,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(location: Location, message: String) -> Diagnostic {
+        Diagnostic { severity: Severity::Error, location, message }
+    }
+
+    pub fn warning(location: Location, message: String) -> Diagnostic {
+        Diagnostic { severity: Severity::Warning, location, message }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "ERROR",
+            Severity::Warning => "WARNING",
+        };
+        write!(f, "{}: {}:{}: {}", severity, self.location.source, self.location.line, self.message)
+    }
+}
+
+/// The info log for `diagnostics`: one per line.
+pub fn info_log(diagnostics: &[Diagnostic]) -> String {
+    diagnostics.iter().map(|diagnostic| format!("{}\n", diagnostic)).collect()
+}
+
+// -------------------------------------------------------------------------------------------------
+// COMPILING
+// -------------------------------------------------------------------------------------------------
+
+/// A GLSL ES version. Versions are ordered, so `Es100 < Es300`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GlslVersion {
+    /// GLSL ES 1.00, for OpenGL ES 2.0: `#version 100` or no directive.
+    Es100,
+    /// GLSL ES 3.00, for OpenGL ES 3.0: `#version 300 es`.
+    Es300,
+}
+
+/// Implementation limits, which shaders see as the `gl_Max*` constants. Shaders whose used
+/// attributes, uniforms, varyings or samplers do not fit them fail to compile.
+///
+/// The defaults are the minimums OpenGL ES 2.0 requires, and `es300` those of OpenGL ES 3.0.
+/// The output and input vectors and the texel offsets only apply to GLSL ES 3.00.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Limits {
+    pub max_vertex_attribs: usize,
+    pub max_vertex_uniform_vectors: usize,
+    pub max_varying_vectors: usize,
+    pub max_vertex_output_vectors: usize,
+    pub max_fragment_input_vectors: usize,
+    pub max_vertex_texture_image_units: usize,
+    pub max_combined_texture_image_units: usize,
+    pub max_texture_image_units: usize,
+    pub max_fragment_uniform_vectors: usize,
+    pub max_draw_buffers: usize,
+    pub min_program_texel_offset: i32,
+    pub max_program_texel_offset: i32,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            max_vertex_attribs: 8,
+            max_vertex_uniform_vectors: 128,
+            max_varying_vectors: 8,
+            max_vertex_output_vectors: 16,
+            max_fragment_input_vectors: 15,
+            max_vertex_texture_image_units: 0,
+            max_combined_texture_image_units: 8,
+            max_texture_image_units: 8,
+            max_fragment_uniform_vectors: 16,
+            max_draw_buffers: 1,
+            min_program_texel_offset: -8,
+            max_program_texel_offset: 7,
+        }
+    }
+}
+
+impl Limits {
+    pub fn es300() -> Limits {
+        Limits {
+            max_vertex_attribs: 16,
+            max_vertex_uniform_vectors: 256,
+            max_varying_vectors: 15,
+            max_vertex_output_vectors: 16,
+            max_fragment_input_vectors: 15,
+            max_vertex_texture_image_units: 16,
+            max_combined_texture_image_units: 32,
+            max_texture_image_units: 16,
+            max_fragment_uniform_vectors: 224,
+            max_draw_buffers: 4,
+            min_program_texel_offset: -8,
+            max_program_texel_offset: 7,
+        }
+    }
+
+    /// The minimums the OpenGL ES version that runs `version` requires.
+    pub fn minimum(version: GlslVersion) -> Limits {
+        match version {
+            GlslVersion::Es100 => Limits::default(),
+            GlslVersion::Es300 => Limits::es300(),
+        }
+    }
+}
+
+/// A GLSL ES 1.00 shader that compiled without errors.
+pub struct Shader {
+    type_: ShaderType,
+    // Warnings only; shaders with errors do not compile.
+    diagnostics: Vec<Diagnostic>,
+    code: Rc<Checked>,
+}
+
+impl Shader {
+    pub fn type_(&self) -> ShaderType {
+        self.type_
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn info_log(&self) -> String {
+        info_log(&self.diagnostics)
+    }
+}
+
+/// Preprocesses, parses and type checks `source`, which must be GLSL ES 1.00. Fails with all
+/// errors and warnings found.
+pub fn compile(source: &str, type_: ShaderType, limits: &Limits) -> Result<Shader, Vec<Diagnostic>> {
+    let mut diagnostics = Vec::new();
+    let code = front_end(source, type_, Some(GlslVersion::Es100), limits, &mut diagnostics);
+
+    match code {
+        Some(code) if !failed(&diagnostics) => Ok(Shader { type_, diagnostics, code: Rc::new(code) }),
+        _ => Err(diagnostics),
+    }
+}
+
+/// Checks `source` the way an OpenGL ES driver compiling it as `version` would, without running
+/// anything: preprocessing, syntax, types, the rules of the version and the minimum limits of
+/// the OpenGL ES version that goes with it. Returns all errors and warnings found, with their
+/// line and column, so an empty result means the shader is valid.
+pub fn validate_shader(source: &str, type_: ShaderType, version: GlslVersion) -> Vec<Diagnostic> {
+    validate_shader_with_limits(source, type_, version, &Limits::minimum(version))
+}
+
+/// `validate_shader` against the limits of a particular device.
+pub fn validate_shader_with_limits(source: &str, type_: ShaderType, version: GlslVersion, limits: &Limits)
+    -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    front_end(source, type_, Some(version), limits, &mut diagnostics);
+    diagnostics
+}
+
+fn failed(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Error)
+}
+
+// Runs the front end as far as it gets without errors. The shader must declare `expected` when
+// it is given, and is checked with the rules of the version it declares.
+fn front_end(source: &str, type_: ShaderType, expected: Option<GlslVersion>, limits: &Limits,
+             diagnostics: &mut Vec<Diagnostic>) -> Option<Checked> {
+    let output = preprocessor::preprocess(source, diagnostics);
+    if failed(diagnostics) {
+        return None;
+    }
+
+    if let Some(expected) = expected.filter(|&expected| expected != output.version) {
+        let directive = match expected {
+            GlslVersion::Es100 => "#version 100",
+            GlslVersion::Es300 => "#version 300 es",
+        };
+        let message = format!("'#version' : expected {} for this shader", directive);
+        diagnostics.push(Diagnostic::error(output.version_location, message));
+        return None;
+    }
+
+    let declarations = match parser::parse(&output.tokens, output.version) {
+        Ok(declarations) => declarations,
+        Err(error) => {
+            diagnostics.push(error);
+            return None;
+        }
+    };
+
+    Some(checker::check(&declarations, type_, output.version, limits, diagnostics))
+}
+
+// -------------------------------------------------------------------------------------------------
+// LINKING
+// -------------------------------------------------------------------------------------------------
+
+/// An active attribute or uniform of a linked program. Uniform structures are reported by field,
+/// as `light.color` or `lights[1].color`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActiveVariable {
+    pub name: String,
+    pub type_: GLenum,
+    /// The array length, 1 for variables that are not arrays.
+    pub size: usize,
+}
+
+/// Texture level of detail selection for a lookup.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Lod {
+    /// Computed from screen-space derivatives, plus a bias. Vertex shaders use the base level.
+    Implicit(f32),
+    Explicit(f32),
+}
+
+/// What a running shader reads from outside its own memory.
+pub trait Environment {
+    /// Column `column` of the attribute at `index` in `Program::attributes`.
+    fn attribute(&self, _index: usize, _column: usize) -> [f32; 4] {
+        [0.0, 0.0, 0.0, 1.0]
+    }
+
+    /// Writes all components of all elements of the uniform at `index` in `Program::uniforms`.
+    /// Booleans are 0 or 1 and samplers are their texture unit.
+    fn uniform(&self, index: usize, values: &mut [f32]);
+
+    fn texture_2d(&self, unit: usize, coord: [f32; 2], lod: Lod) -> [f32; 4];
+
+    fn texture_cube(&self, unit: usize, coord: [f32; 3], lod: Lod) -> [f32; 4];
+
+    /// The near and far values set by `glDepthRangef`.
+    fn depth_range(&self) -> [f32; 2];
+}
+
+/// The built-in inputs of a fragment shader invocation and the interpolated varyings.
+pub struct FragmentInput<'a> {
+    pub frag_coord: [f32; 4],
+    pub front_facing: bool,
+    pub point_coord: [f32; 2],
+    pub varyings: &'a [f32],
+}
+
+/// A vertex and a fragment shader linked together.
+pub struct Program {
+    vertex: Stage,
+    fragment: Stage,
+    attributes: Vec<ActiveVariable>,
+    uniforms: Vec<ActiveVariable>,
+    varyings: usize,
+}
+
+// One shader of a linked program and where its interface lives in its memory.
+struct Stage {
+    code: Rc<Checked>,
+    // Active uniform index, address and size.
+    uniforms: Vec<(usize, usize, usize)>,
+    // The address and size of each varying, in packed order.
+    varyings: Vec<(usize, usize)>,
+    // The address, column count and rows of each active attribute.
+    attributes: Vec<(usize, usize, usize)>,
+    // Reused between invocations.
+    memory: RefCell<Vec<f32>>,
+    stack: RefCell<Vec<f32>>,
+}
+
+impl Stage {
+    fn new(code: &Rc<Checked>) -> Stage {
+        Stage {
+            code: code.clone(),
+            uniforms: Vec::new(),
+            varyings: Vec::new(),
+            attributes: Vec::new(),
+            memory: RefCell::new(code.memory.clone()),
+            stack: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn builtin(&self, name: &str) -> Option<usize> {
+        self.code.globals.iter().find(|global| global.name == name).map(|global| global.address)
+    }
+
+    // Runs `main` with the uniforms loaded and the inputs `setup` writes. Returns what `finish`
+    // reads from memory, or `None` when the invocation was discarded.
+    fn run<T, S, F>(&self, environment: &dyn Environment, setup: S, finish: F) -> Option<T>
+        where S: FnOnce(&mut [f32]), F: FnOnce(&[f32]) -> T {
+        let mut memory = self.memory.borrow_mut();
+        memory.copy_from_slice(&self.code.memory);
+
+        for &(index, address, size) in &self.uniforms {
+            environment.uniform(index, &mut memory[address..address + size]);
+        }
+        if let Some(address) = self.builtin("gl_DepthRange") {
+            let [near, far] = environment.depth_range();
+            memory[address..address + 3].copy_from_slice(&[near, far, far - near]);
+        }
+        setup(&mut memory);
+
+        let mut stack = self.stack.borrow_mut();
+        let kept = Machine::new(&mut memory, &mut stack, &self.code.functions, environment).run(self.code.main);
+        if kept { Some(finish(&memory)) } else { None }
+    }
+}
+
+/// Links two compiled shaders. Fails with the reason when their interfaces do not match.
+pub fn link(vertex: &Shader, fragment: &Shader) -> Result<Program, String> {
+    if vertex.type_ != ShaderType::VERTEX_SHADER || fragment.type_ != ShaderType::FRAGMENT_SHADER {
+        return Err("a program needs one vertex and one fragment shader".to_string());
+    }
+
+    let mut program = Program {
+        vertex: Stage::new(&vertex.code),
+        fragment: Stage::new(&fragment.code),
+        attributes: Vec::new(),
+        uniforms: Vec::new(),
+        varyings: 0,
+    };
+
+    for global in fragment.code.globals.iter().filter(|global| global.storage == Storage::Varying) {
+        let output = vertex.code.globals.iter()
+            .find(|output| output.storage == Storage::Varying && output.name == global.name);
+        let output = match output {
+            Some(output) => output,
+            None if global.used => {
+                return Err(format!("varying '{}' is used in the fragment shader, but not declared in the vertex shader",
+                                   global.name));
+            }
+            None => continue,
+        };
+
+        if output.type_ != global.type_ {
+            return Err(format!("varying '{}' has type '{}' in the vertex shader and '{}' in the fragment shader",
+                               global.name, output.type_, global.type_));
+        }
+        if output.invariant != global.invariant {
+            return Err(format!("varying '{}' is invariant in only one of the shaders", global.name));
+        }
+
+        if global.used {
+            let size = global.type_.slots();
+            program.vertex.varyings.push((output.address, size));
+            program.fragment.varyings.push((global.address, size));
+            program.varyings += size;
+        }
+    }
+
+    for global in vertex.code.globals.iter().filter(|global| global.storage == Storage::Attribute && global.used) {
+        let (columns, rows) = match global.type_ {
+            Type::Matrix(columns, rows) => (columns, rows),
+            ref type_ => (1, type_.slots()),
+        };
+        program.vertex.attributes.push((global.address, columns, rows));
+        program.attributes.push(ActiveVariable {
+            name: global.name.clone(),
+            type_: global.type_.gl_enum().unwrap_or(GL_FLOAT),
+            size: 1,
+        });
+    }
+
+    // Uniforms declared in both shaders must agree field by field, even where only one uses them.
+    let mut declared: Vec<(String, Type, usize)> = Vec::new();
+    let mut stage_uniforms = [Vec::new(), Vec::new()];
+    for (stage, code) in [(0, &vertex.code), (1, &fragment.code)].iter().cloned() {
+        for global in code.globals.iter().filter(|global| global.storage == Storage::Uniform) {
+            let mut leaves = Vec::new();
+            uniform_leaves(&global.name, &global.type_, global.address, &mut leaves);
+
+            for (name, type_, size, address) in leaves {
+                match declared.iter().find(|other| other.0 == name) {
+                    Some(other) if other.1.gl_enum() != type_.gl_enum() || other.2 != size => {
+                        return Err(format!("uniform '{}' has different types in the vertex and fragment shaders",
+                                           name));
+                    }
+                    Some(_) => {}
+                    None => declared.push((name.clone(), type_.clone(), size)),
+                }
+
+                if !global.used {
+                    continue;
+                }
+                let index = match program.uniforms.iter().position(|uniform| uniform.name == name) {
+                    Some(index) => index,
+                    None => {
+                        program.uniforms.push(ActiveVariable {
+                            name: name.clone(),
+                            type_: type_.gl_enum().unwrap_or(GL_FLOAT),
+                            size,
+                        });
+                        program.uniforms.len() - 1
+                    }
+                };
+                stage_uniforms[stage].push((index, address, type_.slots() * size));
+            }
+        }
+    }
+    let [vertex_uniforms, fragment_uniforms] = stage_uniforms;
+    program.vertex.uniforms = vertex_uniforms;
+    program.fragment.uniforms = fragment_uniforms;
+
+    Ok(program)
+}
+
+// The uniforms the API sees for a declared one: basic types and arrays of them.
+fn uniform_leaves(name: &str, type_: &Type, address: usize, leaves: &mut Vec<(String, Type, usize, usize)>) {
+    match *type_ {
+        Type::Array(ref element, length) => match **element {
+            Type::Struct(_) => {
+                for index in 0..length {
+                    uniform_leaves(&format!("{}[{}]", name, index), element, address + index * element.slots(), leaves);
+                }
+            }
+            _ => leaves.push((name.to_string(), (**element).clone(), length, address)),
+        },
+        Type::Struct(ref structure) => {
+            let mut offset = 0;
+            for (field, field_type) in &structure.fields {
+                uniform_leaves(&format!("{}.{}", name, field), field_type, address + offset, leaves);
+                offset += field_type.slots();
+            }
+        }
+        _ => leaves.push((name.to_string(), type_.clone(), 1, address)),
+    }
+}
+
+impl Program {
+    /// The active attributes, which are those the vertex shader uses, in declaration order.
+    pub fn attributes(&self) -> &[ActiveVariable] {
+        &self.attributes
+    }
+
+    /// The active uniforms of both shaders.
+    pub fn uniforms(&self) -> &[ActiveVariable] {
+        &self.uniforms
+    }
+
+    /// The number of floats the vertex shader passes to the fragment shader.
+    pub fn varyings(&self) -> usize {
+        self.varyings
+    }
+
+    /// Runs the vertex shader and returns `gl_Position` and `gl_PointSize`. Writes the varyings
+    /// to `varyings`, which holds `varyings()` floats.
+    pub fn run_vertex(&self, environment: &dyn Environment, varyings: &mut [f32]) -> ([f32; 4], f32) {
+        let stage = &self.vertex;
+        let position = stage.builtin("gl_Position").unwrap_or(0);
+        let point_size = stage.builtin("gl_PointSize").unwrap_or(0);
+
+        let setup = |memory: &mut [f32]| {
+            for (index, &(address, columns, rows)) in stage.attributes.iter().enumerate() {
+                for column in 0..columns {
+                    let value = environment.attribute(index, column);
+                    let start = address + column * rows;
+                    memory[start..start + rows].copy_from_slice(&value[..rows]);
+                }
+            }
+        };
+
+        let finish = |memory: &[f32]| {
+            let mut offset = 0;
+            for &(address, size) in &stage.varyings {
+                varyings[offset..offset + size].copy_from_slice(&memory[address..address + size]);
+                offset += size;
+            }
+            let mut clip = [0.0; 4];
+            clip.copy_from_slice(&memory[position..position + 4]);
+            (clip, memory[point_size])
+        };
+
+        stage.run(environment, setup, finish).unwrap_or(([0.0; 4], 1.0))
+    }
+
+    /// Runs the fragment shader and returns its color, or `None` when it discards the fragment.
+    pub fn run_fragment(&self, input: &FragmentInput, environment: &dyn Environment) -> Option<[f32; 4]> {
+        let stage = &self.fragment;
+        let uses_data = stage.code.globals.iter().any(|global| global.name == "gl_FragData" && global.used);
+        let color = stage.builtin(if uses_data { "gl_FragData" } else { "gl_FragColor" }).unwrap_or(0);
+
+        let setup = |memory: &mut [f32]| {
+            let inputs: [(&str, &[f32]); 3] = [
+                ("gl_FragCoord", &input.frag_coord),
+                ("gl_FrontFacing", &[if input.front_facing { 1.0 } else { 0.0 }]),
+                ("gl_PointCoord", &input.point_coord),
+            ];
+            for &(name, values) in &inputs {
+                if let Some(address) = stage.builtin(name) {
+                    memory[address..address + values.len()].copy_from_slice(values);
+                }
+            }
+
+            let mut offset = 0;
+            for &(address, size) in &stage.varyings {
+                for (target, value) in memory[address..address + size].iter_mut().zip(&input.varyings[offset..]) {
+                    *target = *value;
+                }
+                offset += size;
+            }
+        };
+
+        stage.run(environment, setup, |memory| {
+            let mut rgba = [0.0; 4];
+            rgba.copy_from_slice(&memory[color..color + 4]);
+            rgba
+        })
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERTEX: &str = "attribute vec2 position;\nuniform float scale;\nvarying vec2 coord;\n\
+                          void main() {\n  coord = position * 0.5 + 0.5;\n\
+                          gl_Position = vec4(position * scale, 0.0, 1.0);\n}\n";
+    const FRAGMENT: &str = "precision mediump float;\nuniform vec4 tint;\nvarying vec2 coord;\n\
+                            vec4 shade(vec2 c) { return vec4(c, 0.0, 1.0) * tint; }\n\
+                            void main() {\n  if (coord.x > 0.9) discard;\n  gl_FragColor = shade(coord);\n}\n";
+
+    // Attributes and uniforms by index, no textures.
+    struct Values {
+        attributes: Vec<[f32; 4]>,
+        uniforms: Vec<Vec<f32>>,
+    }
+
+    impl Environment for Values {
+        fn attribute(&self, index: usize, _column: usize) -> [f32; 4] {
+            self.attributes[index]
+        }
+
+        fn uniform(&self, index: usize, values: &mut [f32]) {
+            values.copy_from_slice(&self.uniforms[index]);
+        }
+
+        fn texture_2d(&self, _unit: usize, _coord: [f32; 2], _lod: Lod) -> [f32; 4] {
+            [0.0; 4]
+        }
+
+        fn texture_cube(&self, _unit: usize, _coord: [f32; 3], _lod: Lod) -> [f32; 4] {
+            [0.0; 4]
+        }
+
+        fn depth_range(&self) -> [f32; 2] {
+            [0.0, 1.0]
+        }
+    }
+
+    fn program() -> Program {
+        let limits = Limits::default();
+        let vertex = compile(VERTEX, ShaderType::VERTEX_SHADER, &limits).unwrap();
+        let fragment = compile(FRAGMENT, ShaderType::FRAGMENT_SHADER, &limits).unwrap();
+        link(&vertex, &fragment).unwrap()
+    }
+
+    // The values of the active uniforms in the order the program reports them.
+    fn uniforms(program: &Program, values: &[(&str, &[f32])]) -> Vec<Vec<f32>> {
+        program.uniforms().iter().map(|uniform| {
+            values.iter().find(|&&(name, _)| name == uniform.name).unwrap().1.to_vec()
+        }).collect()
+    }
+
+    #[test]
+    fn links_the_active_interface() {
+        let program = program();
+
+        let position = ActiveVariable { name: "position".to_string(), type_: GL_FLOAT_VEC2, size: 1 };
+        assert_eq!(program.attributes(), [position]);
+        let mut uniforms: Vec<&str> = program.uniforms().iter().map(|uniform| uniform.name.as_str()).collect();
+        uniforms.sort();
+        assert_eq!(uniforms, ["scale", "tint"]);
+        assert_eq!(program.varyings(), 2);
+    }
+
+    #[test]
+    fn runs_both_stages() {
+        let program = program();
+        let uniforms = uniforms(&program, &[("scale", &[2.0]), ("tint", &[1.0, 0.5, 1.0, 1.0])]);
+        let values = Values { attributes: vec![[0.5, -0.5, 0.0, 1.0]], uniforms };
+
+        let mut varyings = [0.0; 2];
+        let (position, _) = program.run_vertex(&values, &mut varyings);
+        assert_eq!(position, [1.0, -1.0, 0.0, 1.0]);
+        assert_eq!(varyings, [0.75, 0.25]);
+
+        let mut input = FragmentInput { frag_coord: [0.5, 0.5, 0.5, 1.0], front_facing: true, point_coord: [0.0; 2],
+                                        varyings: &varyings };
+        assert_eq!(program.run_fragment(&input, &values), Some([0.75, 0.125, 0.0, 1.0]));
+        input.varyings = &[0.95, 0.0];
+        assert_eq!(program.run_fragment(&input, &values), None);
+    }
+
+    #[test]
+    fn reports_compile_errors_with_their_line() {
+        let source = "precision mediump float;\nvoid main() {\n  gl_FragColor = undeclared;\n}\n";
+        let diagnostics = compile(source, ShaderType::FRAGMENT_SHADER, &Limits::default()).err().unwrap();
+
+        assert!(diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Error
+            && diagnostic.location.line == 3 && diagnostic.message.contains("undeclared")), "{:?}", diagnostics);
+        assert!(info_log(&diagnostics).starts_with("ERROR: 0:3: "));
+
+        let mismatch = "precision mediump float;\nvoid main() { gl_FragColor = vec3(1.0); }\n";
+        assert!(compile(mismatch, ShaderType::FRAGMENT_SHADER, &Limits::default()).is_err());
+        let version = "#version 300 es\nvoid main() {}\n";
+        assert!(compile(version, ShaderType::VERTEX_SHADER, &Limits::default()).is_err());
+    }
+
+    #[test]
+    fn link_rejects_mismatched_varyings() {
+        let limits = Limits::default();
+        let vertex = compile(VERTEX, ShaderType::VERTEX_SHADER, &limits).unwrap();
+        let fragment = "precision mediump float;\nvarying vec3 coord;\n\
+                        void main() { gl_FragColor = vec4(coord, 1.0); }\n";
+        let fragment = compile(fragment, ShaderType::FRAGMENT_SHADER, &limits).unwrap();
+
+        assert!(link(&vertex, &fragment).is_err());
+        assert!(link(&fragment, &vertex).is_err());
+    }
+
+    fn validation_errors(source: &str, type_: ShaderType, version: GlslVersion) -> Vec<(u32, u32, String)> {
+        validate_shader(source, type_, version).into_iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .map(|diagnostic| (diagnostic.location.line, diagnostic.location.column, diagnostic.message))
+            .collect()
+    }
+
+    #[test]
+    fn validates_valid_shaders_of_both_versions() {
+        assert!(validate_shader(FRAGMENT, ShaderType::FRAGMENT_SHADER, GlslVersion::Es100).is_empty());
+        let es300 = "#version 300 es\nin vec4 position;\nvoid main() { gl_Position = position; }\n";
+        assert!(validate_shader(es300, ShaderType::VERTEX_SHADER, GlslVersion::Es300).is_empty());
+    }
+
+    #[test]
+    fn validation_reports_line_and_column() {
+        let source = "void main() {\n    gl_Position = nope;\n}\n";
+        let errors = validation_errors(source, ShaderType::VERTEX_SHADER, GlslVersion::Es100);
+        assert_eq!(errors, [(2, 19, "'nope' : undeclared identifier".to_string())]);
+
+        let source = "void main() {\n  float x = 1.0;\n  gl_FragColor = vec4(x);\n}\n";
+        let errors = validation_errors(source, ShaderType::FRAGMENT_SHADER, GlslVersion::Es100);
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].0, errors[0].1), (2, 3));
+        assert!(errors[0].2.contains("precision"), "{}", errors[0].2);
+    }
+
+    #[test]
+    fn validation_applies_the_rules_of_the_version() {
+        let es300 = "#version 300 es\nin vec4 position;\nvoid main() { gl_Position = position; }\n";
+        assert_eq!(validation_errors(es300, ShaderType::VERTEX_SHADER, GlslVersion::Es100).len(), 1);
+        let es100 = "attribute vec4 position;\nvoid main() { gl_Position = position; }\n";
+        assert_eq!(validation_errors(es100, ShaderType::VERTEX_SHADER, GlslVersion::Es300).len(), 1);
+        let in_es100 = "in vec4 position;\nvoid main() { gl_Position = position; }\n";
+        assert_eq!(validation_errors(in_es100, ShaderType::VERTEX_SHADER, GlslVersion::Es100).len(), 1);
+    }
+
+    #[test]
+    fn validation_checks_the_limits() {
+        let source = "uniform vec4 values[200];\nvoid main() { gl_Position = values[199]; }\n";
+        let errors = validation_errors(source, ShaderType::VERTEX_SHADER, GlslVersion::Es100);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].2.contains("gl_MaxVertexUniformVectors"), "{}", errors[0].2);
+
+        let limits = Limits { max_vertex_uniform_vectors: 256, ..Limits::default() };
+        assert!(validate_shader_with_limits(source, ShaderType::VERTEX_SHADER, GlslVersion::Es100, &limits).is_empty());
+    }
+}
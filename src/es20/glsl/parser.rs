@@ -0,0 +1,873 @@
+use super::ast::*;
+use super::lexer::{Token, TokenKind};
+use super::preprocessor::parse_integer;
+use super::{Diagnostic, GlslVersion, Location};
+
+// Type keywords of GLSL ES 1.00.
+const TYPE_KEYWORDS: [&str; 20] = [
+    "void", "bool", "int", "float", "vec2", "vec3", "vec4", "bvec2", "bvec3", "bvec4", "ivec2", "ivec3", "ivec4",
+    "mat2", "mat3", "mat4", "sampler2D", "samplerCube", "struct", "precision",
+];
+
+const KEYWORDS: [&str; 20] = [
+    "attribute", "const", "uniform", "varying", "break", "continue", "do", "for", "while", "if", "else", "in",
+    "out", "inout", "true", "false", "lowp", "mediump", "highp", "invariant",
+];
+
+const MORE_KEYWORDS: [&str; 2] = ["discard", "return"];
+
+// Type keywords GLSL ES 3.00 adds.
+const TYPE_KEYWORDS_300: [&str; 26] = [
+    "uint", "uvec2", "uvec3", "uvec4", "mat2x2", "mat2x3", "mat2x4", "mat3x2", "mat3x3", "mat3x4", "mat4x2",
+    "mat4x3", "mat4x4", "sampler3D", "sampler2DShadow", "samplerCubeShadow", "sampler2DArray",
+    "sampler2DArrayShadow", "isampler2D", "isampler3D", "isamplerCube", "isampler2DArray", "usampler2D",
+    "usampler3D", "usamplerCube", "usampler2DArray",
+];
+
+const KEYWORDS_300: [&str; 7] = ["layout", "centroid", "flat", "smooth", "switch", "case", "default"];
+
+// Qualifiers that start declarations in statements.
+const QUALIFIERS: [&str; 14] = [
+    "const", "attribute", "uniform", "varying", "in", "out", "invariant", "layout", "smooth", "flat", "centroid",
+    "lowp", "mediump", "highp",
+];
+
+// Words reserved for future use, which are errors wherever they appear.
+const RESERVED: [&str; 47] = [
+    "asm", "class", "union", "enum", "typedef", "template", "this", "packed", "goto", "switch", "default",
+    "inline", "noinline", "volatile", "public", "static", "extern", "external", "interface", "flat", "long",
+    "short", "double", "half", "fixed", "unsigned", "superp", "input", "output", "hvec2", "hvec3", "hvec4",
+    "dvec2", "dvec3", "dvec4", "fvec2", "fvec3", "fvec4", "sampler1D", "sampler3D", "sampler1DShadow",
+    "sampler2DShadow", "sampler2DRect", "sampler3DRect", "sampler2DRectShadow", "sizeof", "cast",
+];
+
+// The reserved words of GLSL ES 3.00, which include the storage qualifiers it dropped.
+const RESERVED_300: [&str; 97] = [
+    "attribute", "varying", "coherent", "volatile", "restrict", "readonly", "writeonly", "resource", "atomic_uint",
+    "noperspective", "patch", "sample", "subroutine", "common", "partition", "active", "asm", "class", "union",
+    "enum", "typedef", "template", "this", "goto", "inline", "noinline", "public", "static", "extern", "external",
+    "interface", "long", "short", "double", "half", "fixed", "unsigned", "superp", "input", "output", "hvec2",
+    "hvec3", "hvec4", "dvec2", "dvec3", "dvec4", "fvec2", "fvec3", "fvec4", "sampler3DRect", "filter", "image1D",
+    "image2D", "image3D", "imageCube", "iimage1D", "iimage2D", "iimage3D", "iimageCube", "uimage1D", "uimage2D",
+    "uimage3D", "uimageCube", "image1DArray", "image2DArray", "iimage1DArray", "iimage2DArray", "uimage1DArray",
+    "uimage2DArray", "imageBuffer", "iimageBuffer", "uimageBuffer", "sampler1D", "sampler1DShadow",
+    "sampler1DArray", "sampler1DArrayShadow", "isampler1D", "isampler1DArray", "usampler1D", "usampler1DArray",
+    "sampler2DRect", "sampler2DRectShadow", "isampler2DRect", "usampler2DRect", "samplerBuffer", "isamplerBuffer",
+    "usamplerBuffer", "sampler2DMS", "isampler2DMS", "usampler2DMS", "sampler2DMSArray", "isampler2DMSArray",
+    "usampler2DMSArray", "sizeof", "cast", "namespace", "using",
+];
+
+const ASSIGNMENT_OPERATORS: [&str; 11] = ["=", "+=", "-=", "*=", "/=", "%=", "<<=", ">>=", "&=", "^=", "|="];
+
+// Binary operators by increasing precedence.
+const BINARY_OPERATORS: [&[&str]; 10] = [
+    &["||"],
+    &["^^"],
+    &["&&"],
+    &["|"],
+    &["^"],
+    &["&"],
+    &["==", "!="],
+    &["<", ">", "<=", ">="],
+    &["<<", ">>"],
+    &["+", "-"],
+];
+
+const MULTIPLICATIVE_OPERATORS: [&str; 3] = ["*", "/", "%"];
+
+const UNARY_OPERATORS: [&str; 6] = ["+", "-", "!", "~", "++", "--"];
+
+// -------------------------------------------------------------------------------------------------
+// PARSING
+// -------------------------------------------------------------------------------------------------
+
+type Parse<T> = Result<T, Diagnostic>;
+
+// Parses a preprocessed translation unit with the grammar of `version`. Stops at the first syntax
+// error.
+pub(super) fn parse(tokens: &[Token], version: GlslVersion) -> Result<Vec<ExternalDeclaration>, Diagnostic> {
+    let end = tokens.last().map_or(Location { source: 0, line: 1, column: 0 }, |token| token.location);
+    let mut parser = Parser { tokens, position: 0, end, struct_names: Vec::new(), version };
+
+    let mut declarations = Vec::new();
+    while parser.peek().is_some() {
+        declarations.push(parser.external_declaration()?);
+    }
+    Ok(declarations)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    // Where errors at the end of the input are reported.
+    end: Location,
+    // Structure names declared so far, to tell declarations from expressions.
+    struct_names: Vec<String>,
+    version: GlslVersion,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&'a Token> {
+        self.tokens.get(self.position + offset)
+    }
+
+    fn location(&self) -> Location {
+        self.peek().map_or(self.end, |token| token.location)
+    }
+
+    fn at(&self, text: &str) -> bool {
+        self.peek().is_some_and(|token| token.is(text) && token.kind != TokenKind::Number)
+    }
+
+    fn accept(&mut self, text: &str) -> bool {
+        if self.at(text) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Accepts a keyword that only some versions have.
+    fn accept_keyword(&mut self, word: &str) -> bool {
+        self.is_keyword(word) && self.accept(word)
+    }
+
+    fn error<T>(&self, message: String) -> Parse<T> {
+        Err(Diagnostic::error(self.location(), message))
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Parse<T> {
+        match self.peek() {
+            Some(token) if self.is_reserved(&token.text) => {
+                self.error(format!("'{}' : reserved keyword", token.text))
+            }
+            Some(token) => self.error(format!("'{}' : syntax error, expected {}", token.text, expected)),
+            None => self.error(format!("syntax error, unexpected end of input, expected {}", expected)),
+        }
+    }
+
+    fn expect(&mut self, text: &str) -> Parse<()> {
+        if self.accept(text) { Ok(()) } else { self.unexpected(&format!("'{}'", text)) }
+    }
+
+    fn identifier(&mut self) -> Parse<(String, Location)> {
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::Identifier && !self.is_keyword(&token.text)
+                && !self.is_reserved(&token.text) => {
+                self.position += 1;
+                Ok((token.text.clone(), token.location))
+            }
+            _ => self.unexpected("an identifier"),
+        }
+    }
+
+    fn is_type_keyword(&self, word: &str) -> bool {
+        TYPE_KEYWORDS.contains(&word) || (self.version == GlslVersion::Es300 && TYPE_KEYWORDS_300.contains(&word))
+    }
+
+    fn is_keyword(&self, word: &str) -> bool {
+        let keyword = self.is_type_keyword(word) || KEYWORDS.contains(&word) || MORE_KEYWORDS.contains(&word)
+            || (self.version == GlslVersion::Es300 && KEYWORDS_300.contains(&word));
+        keyword && !self.is_reserved(word)
+    }
+
+    fn is_reserved(&self, word: &str) -> bool {
+        match self.version {
+            GlslVersion::Es100 => RESERVED.contains(&word),
+            GlslVersion::Es300 => RESERVED_300.contains(&word),
+        }
+    }
+
+    fn is_identifier(&self, token: Option<&Token>) -> bool {
+        token.is_some_and(|token| token.kind == TokenKind::Identifier && !self.is_keyword(&token.text))
+    }
+
+    fn is_type_start(&self, offset: usize) -> bool {
+        match self.peek_at(offset) {
+            Some(token) if token.kind == TokenKind::Identifier => {
+                (self.is_type_keyword(&token.text) && token.text != "precision")
+                    || self.struct_names.contains(&token.text)
+            }
+            _ => false,
+        }
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // DECLARATIONS
+    // -------------------------------------------------------------------------------------------------
+
+    fn external_declaration(&mut self) -> Parse<ExternalDeclaration> {
+        if self.at("precision") {
+            let (precision, type_) = self.precision_statement()?;
+            return Ok(ExternalDeclaration::Precision(precision, type_));
+        }
+
+        if self.at("invariant") && self.is_identifier(self.peek_at(1)) && !self.is_type_start(1) {
+            self.position += 1;
+            let mut names = vec![self.identifier()?];
+            while self.accept(",") {
+                names.push(self.identifier()?);
+            }
+            self.expect(";")?;
+            return Ok(ExternalDeclaration::Invariant(names));
+        }
+
+        let qualifiers = self.qualifiers()?;
+        if !qualifiers.layout.is_empty() && self.accept(";") {
+            return Ok(ExternalDeclaration::DefaultLayout(qualifiers));
+        }
+        if qualifiers.storage == StorageQualifier::Uniform && self.version == GlslVersion::Es300
+            && self.is_identifier(self.peek()) && self.peek_at(1).is_some_and(|token| token.is("{")) {
+            return self.block(qualifiers).map(ExternalDeclaration::Block);
+        }
+
+        let type_ = self.type_specifier()?;
+
+        if !qualifiers.has_auxiliary() && qualifiers.storage == StorageQualifier::None
+            && self.is_identifier(self.peek()) && self.peek_at(1).is_some_and(|token| token.is("(")) {
+            return self.function(type_).map(ExternalDeclaration::Function);
+        }
+
+        self.declaration_rest(qualifiers, type_).map(ExternalDeclaration::Declaration)
+    }
+
+    fn precision_statement(&mut self) -> Parse<(Precision, TypeSpecifier)> {
+        self.expect("precision")?;
+        let precision = match self.precision_qualifier() {
+            Some(precision) => precision,
+            None => return self.unexpected("a precision qualifier"),
+        };
+        let type_ = self.type_specifier()?;
+        self.expect(";")?;
+        Ok((precision, type_))
+    }
+
+    fn precision_qualifier(&mut self) -> Option<Precision> {
+        let precision = match self.peek().map(|token| token.text.as_str()) {
+            Some("lowp") => Precision::Low,
+            Some("mediump") => Precision::Medium,
+            Some("highp") => Precision::High,
+            _ => return None,
+        };
+        self.position += 1;
+        Some(precision)
+    }
+
+    // The qualifiers before the type of a declaration, in the order the grammar requires.
+    fn qualifiers(&mut self) -> Parse<Qualifiers> {
+        let es300 = self.version == GlslVersion::Es300;
+        let location = self.location();
+        let layout = if self.is_keyword("layout") && self.at("layout") { self.layout()? } else { Vec::new() };
+        let invariant = self.accept("invariant");
+        let interpolation = if self.accept_keyword("smooth") {
+            Some(Interpolation::Smooth)
+        } else if self.accept_keyword("flat") {
+            Some(Interpolation::Flat)
+        } else {
+            None
+        };
+        let centroid = self.accept_keyword("centroid");
+
+        let storage = match self.peek().map(|token| token.text.as_str()) {
+            Some("const") => StorageQualifier::Const,
+            Some("attribute") if !es300 => StorageQualifier::Attribute,
+            Some("uniform") => StorageQualifier::Uniform,
+            Some("varying") if !es300 => StorageQualifier::Varying,
+            Some("in") if es300 => StorageQualifier::In,
+            Some("out") if es300 => StorageQualifier::Out,
+            _ => StorageQualifier::None,
+        };
+        if storage != StorageQualifier::None {
+            self.position += 1;
+        }
+
+        let qualifiers = Qualifiers { layout, invariant, interpolation, centroid, storage, location };
+        if qualifiers.has_auxiliary() && storage == StorageQualifier::None {
+            return self.unexpected(if es300 { "'in', 'out' or 'uniform'" } else { "'varying'" });
+        }
+        Ok(qualifiers)
+    }
+
+    // `layout(name, name = value, ...)`.
+    fn layout(&mut self) -> Parse<Vec<LayoutQualifier>> {
+        self.expect("layout")?;
+        self.expect("(")?;
+
+        let mut layout = Vec::new();
+        loop {
+            let (name, location) = match self.peek() {
+                Some(token) if token.kind == TokenKind::Identifier => (token.text.clone(), token.location),
+                _ => return self.unexpected("a layout qualifier"),
+            };
+            self.position += 1;
+
+            let value = if self.accept("=") {
+                let token = match self.peek() {
+                    Some(token) if token.kind == TokenKind::Number => token,
+                    _ => return self.unexpected("an integer constant"),
+                };
+                self.position += 1;
+                match number(&token.text, self.version).map_err(|message| Diagnostic::error(token.location, message))? {
+                    ExpressionKind::IntLiteral(value) => Some(value),
+                    ExpressionKind::UintLiteral(value) => Some(value as i64),
+                    _ => return Err(Diagnostic::error(token.location, format!("'{}' : integer constant expected",
+                                                                             token.text))),
+                }
+            } else {
+                None
+            };
+            layout.push(LayoutQualifier { name, value, location });
+
+            if !self.accept(",") {
+                break;
+            }
+        }
+        self.expect(")")?;
+        Ok(layout)
+    }
+
+    fn type_specifier(&mut self) -> Parse<TypeSpecifier> {
+        let precision = self.precision_qualifier();
+        let location = self.location();
+
+        let token = match self.peek() {
+            Some(token) if token.kind == TokenKind::Identifier => token,
+            _ => return self.unexpected("a type"),
+        };
+
+        let name = if token.text == "struct" {
+            TypeName::Struct(self.struct_definition()?)
+        } else if self.is_type_keyword(&token.text) && token.text != "precision" {
+            self.position += 1;
+            TypeName::Keyword(token.text.clone())
+        } else if !self.is_keyword(&token.text) && !self.is_reserved(&token.text) {
+            self.position += 1;
+            TypeName::Named(token.text.clone())
+        } else {
+            return self.unexpected("a type");
+        };
+
+        let array = if self.version == GlslVersion::Es300 && self.at("[") { Some(self.array_size()?) } else { None };
+        Ok(TypeSpecifier { precision, name, array, location })
+    }
+
+    // `[size]`, or `[]` where it may be left out.
+    fn array_size(&mut self) -> Parse<ArraySize> {
+        self.expect("[")?;
+        if self.version == GlslVersion::Es300 && self.accept("]") {
+            return Ok(ArraySize::Unsized);
+        }
+        let size = self.conditional()?;
+        self.expect("]")?;
+        Ok(ArraySize::Sized(Box::new(size)))
+    }
+
+    fn struct_definition(&mut self) -> Parse<StructDefinition> {
+        let location = self.location();
+        self.expect("struct")?;
+
+        let name = if self.is_identifier(self.peek()) { Some(self.identifier()?.0) } else { None };
+        self.expect("{")?;
+
+        let mut fields = Vec::new();
+        while !self.accept("}") {
+            fields.push(self.field_declaration()?);
+        }
+
+        if let Some(ref name) = name {
+            self.struct_names.push(name.clone());
+        }
+        Ok(StructDefinition { name, fields, location })
+    }
+
+    // The fields of a structure or uniform block that share a type.
+    fn field_declaration(&mut self) -> Parse<FieldDeclaration> {
+        let layout = if self.is_keyword("layout") && self.at("layout") { self.layout()? } else { Vec::new() };
+        let type_ = self.type_specifier()?;
+        let mut names = Vec::new();
+        loop {
+            let (field, field_location) = self.identifier()?;
+            let size = if self.accept("[") {
+                let size = self.expression()?;
+                self.expect("]")?;
+                Some(size)
+            } else {
+                None
+            };
+            names.push((field, size, field_location));
+            if !self.accept(",") {
+                break;
+            }
+        }
+        self.expect(";")?;
+        Ok(FieldDeclaration { layout, type_, names })
+    }
+
+    // A uniform block, after its qualifiers.
+    fn block(&mut self, qualifiers: Qualifiers) -> Parse<Block> {
+        let (name, location) = self.identifier()?;
+        self.expect("{")?;
+
+        let mut fields = Vec::new();
+        while !self.accept("}") {
+            fields.push(self.field_declaration()?);
+        }
+
+        let instance = if self.at(";") {
+            None
+        } else {
+            let (instance, instance_location) = self.identifier()?;
+            let size = if self.accept("[") {
+                let size = self.conditional()?;
+                self.expect("]")?;
+                Some(size)
+            } else {
+                None
+            };
+            Some((instance, size, instance_location))
+        };
+        self.expect(";")?;
+
+        Ok(Block { qualifiers, name, fields, instance, location })
+    }
+
+    // The declarators after the type of a declaration, up to the `;`.
+    fn declaration_rest(&mut self, qualifiers: Qualifiers, type_: TypeSpecifier) -> Parse<Declaration> {
+        let mut variables = Vec::new();
+
+        if !self.accept(";") {
+            loop {
+                let (name, location) = self.identifier()?;
+                let array_size = if self.at("[") { Some(self.array_size()?) } else { None };
+                let initializer = if self.accept("=") { Some(self.assignment()?) } else { None };
+                variables.push(VariableDeclaration { name, array_size, initializer, location });
+
+                if !self.accept(",") {
+                    break;
+                }
+            }
+            self.expect(";")?;
+        }
+
+        Ok(Declaration { qualifiers, type_, variables })
+    }
+
+    fn function(&mut self, return_type: TypeSpecifier) -> Parse<Function> {
+        let (name, location) = self.identifier()?;
+        self.expect("(")?;
+
+        let mut parameters = Vec::new();
+        let void_list = self.at("void") && self.peek_at(1).is_some_and(|token| token.is(")"));
+        if void_list {
+            self.position += 1;
+        }
+
+        if !self.accept(")") {
+            loop {
+                parameters.push(self.parameter()?);
+                if !self.accept(",") {
+                    break;
+                }
+            }
+            self.expect(")")?;
+        }
+
+        let body = if self.accept(";") {
+            None
+        } else if self.at("{") {
+            self.position += 1;
+            Some(self.statements_until_brace()?)
+        } else {
+            return self.unexpected("'{' or ';'");
+        };
+
+        Ok(Function { return_type, name, parameters, body, location })
+    }
+
+    fn parameter(&mut self) -> Parse<Parameter> {
+        let location = self.location();
+        let constant = self.accept("const");
+        let qualifier = if self.accept("inout") {
+            ParameterQualifier::InOut
+        } else if self.accept("out") {
+            ParameterQualifier::Out
+        } else {
+            self.accept("in");
+            ParameterQualifier::In
+        };
+
+        let type_ = self.type_specifier()?;
+        let name = if self.is_identifier(self.peek()) { Some(self.identifier()?.0) } else { None };
+        let array_size = if self.accept("[") {
+            let size = self.conditional()?;
+            self.expect("]")?;
+            Some(size)
+        } else {
+            None
+        };
+
+        Ok(Parameter { qualifier, constant, type_, name, array_size, location })
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // STATEMENTS
+    // -------------------------------------------------------------------------------------------------
+
+    fn statements_until_brace(&mut self) -> Parse<Vec<Statement>> {
+        let mut statements = Vec::new();
+        while !self.accept("}") {
+            if self.peek().is_none() {
+                return self.unexpected("'}'");
+            }
+            statements.push(self.statement()?);
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Parse<Statement> {
+        let location = self.location();
+        let keyword = self.peek().map_or("", |token| token.text.as_str());
+
+        match keyword {
+            "{" => {
+                self.position += 1;
+                Ok(Statement::Block(self.statements_until_brace()?))
+            }
+            ";" => {
+                self.position += 1;
+                Ok(Statement::Empty)
+            }
+            "if" => {
+                self.position += 1;
+                self.expect("(")?;
+                let condition = self.expression()?;
+                self.expect(")")?;
+                let then = self.statement()?;
+                let otherwise = if self.accept("else") { Some(Box::new(self.statement()?)) } else { None };
+                Ok(Statement::If(condition, Box::new(then), otherwise))
+            }
+            "for" => {
+                self.position += 1;
+                self.expect("(")?;
+                let init = if self.accept(";") { None } else { Some(Box::new(self.simple_statement()?)) };
+                let condition = if self.at(";") { None } else { Some(self.expression()?) };
+                self.expect(";")?;
+                let step = if self.at(")") { None } else { Some(self.expression()?) };
+                self.expect(")")?;
+                let body = self.statement()?;
+                Ok(Statement::For(init, condition, step, Box::new(body)))
+            }
+            "while" => {
+                self.position += 1;
+                self.expect("(")?;
+                let condition = self.expression()?;
+                self.expect(")")?;
+                Ok(Statement::While(condition, Box::new(self.statement()?)))
+            }
+            "do" => {
+                self.position += 1;
+                let body = self.statement()?;
+                self.expect("while")?;
+                self.expect("(")?;
+                let condition = self.expression()?;
+                self.expect(")")?;
+                self.expect(";")?;
+                Ok(Statement::DoWhile(Box::new(body), condition))
+            }
+            "return" => {
+                self.position += 1;
+                let value = if self.at(";") { None } else { Some(self.expression()?) };
+                self.expect(";")?;
+                Ok(Statement::Return(value, location))
+            }
+            "break" | "continue" | "discard" => {
+                self.position += 1;
+                self.expect(";")?;
+                Ok(match keyword {
+                    "break" => Statement::Break(location),
+                    "continue" => Statement::Continue(location),
+                    _ => Statement::Discard(location),
+                })
+            }
+            "precision" => {
+                let (precision, type_) = self.precision_statement()?;
+                Ok(Statement::Precision(precision, type_))
+            }
+            "switch" if self.is_keyword("switch") => {
+                self.position += 1;
+                self.expect("(")?;
+                let selector = self.expression()?;
+                self.expect(")")?;
+                self.expect("{")?;
+                Ok(Statement::Switch(selector, self.statements_until_brace()?))
+            }
+            "case" if self.is_keyword("case") => {
+                self.position += 1;
+                let value = self.conditional()?;
+                self.expect(":")?;
+                Ok(Statement::Case(Some(value), location))
+            }
+            "default" if self.is_keyword("default") => {
+                self.position += 1;
+                self.expect(":")?;
+                Ok(Statement::Case(None, location))
+            }
+            _ => self.simple_statement(),
+        }
+    }
+
+    // A declaration or expression statement, including its `;`.
+    fn simple_statement(&mut self) -> Parse<Statement> {
+        let is_declaration = match self.peek() {
+            Some(token) if token.text == "struct" || (QUALIFIERS.contains(&token.text.as_str())
+                                                      && self.is_keyword(&token.text)) => true,
+            Some(token) if token.kind == TokenKind::Identifier => {
+                let next = self.peek_at(1);
+                (self.is_type_start(0) && !next.is_some_and(|next| next.is("(")))
+                    || (self.is_identifier(Some(token)) && self.is_identifier(next))
+            }
+            _ => false,
+        };
+
+        if is_declaration {
+            let qualifiers = self.qualifiers()?;
+            let type_ = self.type_specifier()?;
+            return self.declaration_rest(qualifiers, type_).map(Statement::Declaration);
+        }
+
+        let expression = self.expression()?;
+        self.expect(";")?;
+        Ok(Statement::Expression(expression))
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // EXPRESSIONS
+    // -------------------------------------------------------------------------------------------------
+
+    fn expression(&mut self) -> Parse<Expression> {
+        let mut expression = self.assignment()?;
+        while self.at(",") {
+            let location = self.location();
+            self.position += 1;
+            let next = self.assignment()?;
+            expression = Expression { kind: ExpressionKind::Sequence(Box::new(expression), Box::new(next)), location };
+        }
+        Ok(expression)
+    }
+
+    fn assignment(&mut self) -> Parse<Expression> {
+        let target = self.conditional()?;
+
+        let operator = match self.peek() {
+            Some(token) if token.kind == TokenKind::Punctuator => {
+                ASSIGNMENT_OPERATORS.iter().find(|&&operator| operator == token.text)
+            }
+            _ => None,
+        };
+
+        match operator {
+            Some(&operator) => {
+                let location = self.location();
+                self.position += 1;
+                let value = self.assignment()?;
+                Ok(Expression { kind: ExpressionKind::Assign(operator, Box::new(target), Box::new(value)), location })
+            }
+            None => Ok(target),
+        }
+    }
+
+    fn conditional(&mut self) -> Parse<Expression> {
+        let condition = self.binary(0)?;
+        if !self.at("?") {
+            return Ok(condition);
+        }
+
+        let location = self.location();
+        self.position += 1;
+        let then = self.expression()?;
+        self.expect(":")?;
+        let otherwise = self.assignment()?;
+        Ok(Expression {
+            kind: ExpressionKind::Conditional(Box::new(condition), Box::new(then), Box::new(otherwise)),
+            location,
+        })
+    }
+
+    fn binary(&mut self, level: usize) -> Parse<Expression> {
+        let operators: &[&'static str] = if level == BINARY_OPERATORS.len() {
+            &MULTIPLICATIVE_OPERATORS
+        } else {
+            BINARY_OPERATORS[level]
+        };
+        let operand = |parser: &mut Parser| if level == BINARY_OPERATORS.len() {
+            parser.unary()
+        } else {
+            parser.binary(level + 1)
+        };
+
+        let mut left = operand(self)?;
+        loop {
+            let operator = match self.peek() {
+                Some(token) if token.kind == TokenKind::Punctuator => {
+                    operators.iter().find(|&&operator| operator == token.text).cloned()
+                }
+                _ => None,
+            };
+            let operator = match operator {
+                Some(operator) => operator,
+                None => return Ok(left),
+            };
+
+            let location = self.location();
+            self.position += 1;
+            let right = operand(self)?;
+            left = Expression { kind: ExpressionKind::Binary(operator, Box::new(left), Box::new(right)), location };
+        }
+    }
+
+    fn unary(&mut self) -> Parse<Expression> {
+        let operator = match self.peek() {
+            Some(token) if token.kind == TokenKind::Punctuator => {
+                UNARY_OPERATORS.iter().find(|&&operator| operator == token.text).cloned()
+            }
+            _ => None,
+        };
+
+        match operator {
+            Some(operator) => {
+                let location = self.location();
+                self.position += 1;
+                let operand = self.unary()?;
+                Ok(Expression { kind: ExpressionKind::Unary(operator, Box::new(operand)), location })
+            }
+            None => self.postfix(),
+        }
+    }
+
+    fn postfix(&mut self) -> Parse<Expression> {
+        let mut expression = self.primary()?;
+
+        loop {
+            let location = self.location();
+            if self.accept("[") {
+                let index = self.expression()?;
+                self.expect("]")?;
+                expression = Expression { kind: ExpressionKind::Index(Box::new(expression), Box::new(index)), location };
+            } else if self.accept(".") {
+                let field = match self.peek() {
+                    Some(token) if token.kind == TokenKind::Identifier => token.text.clone(),
+                    _ => return self.unexpected("a field name"),
+                };
+                self.position += 1;
+                let method = field == "length" && self.at("(") && self.peek_at(1).is_some_and(|token| token.is(")"));
+                let kind = if method {
+                    self.position += 2;
+                    ExpressionKind::Length(Box::new(expression))
+                } else {
+                    ExpressionKind::Field(Box::new(expression), field)
+                };
+                expression = Expression { kind, location };
+            } else if self.at("++") || self.at("--") {
+                let operator = if self.accept("++") { "++" } else { self.position += 1; "--" };
+                expression = Expression { kind: ExpressionKind::Postfix(operator, Box::new(expression)), location };
+            } else {
+                return Ok(expression);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Parse<Expression> {
+        let token = match self.peek() {
+            Some(token) => token,
+            None => return self.unexpected("an expression"),
+        };
+        let location = token.location;
+
+        let kind = match token.kind {
+            TokenKind::Number => {
+                self.position += 1;
+                number(&token.text, self.version).map_err(|message| Diagnostic::error(location, message))?
+            }
+            TokenKind::Identifier if token.text == "true" || token.text == "false" => {
+                self.position += 1;
+                ExpressionKind::BoolLiteral(token.text == "true")
+            }
+            TokenKind::Identifier if self.is_type_start(0)
+                && self.peek_at(1).is_some_and(|next| next.is("(") || next.is("[")) => {
+                let type_ = self.type_specifier()?;
+                ExpressionKind::Construct(type_, self.arguments()?)
+            }
+            TokenKind::Identifier if self.peek_at(1).is_some_and(|next| next.is("(")) && self.is_identifier(Some(token))
+                && !self.is_reserved(&token.text) => {
+                self.position += 1;
+                ExpressionKind::Call(token.text.clone(), self.arguments()?)
+            }
+            TokenKind::Identifier if self.is_identifier(Some(token)) && !self.is_reserved(&token.text) => {
+                self.position += 1;
+                ExpressionKind::Identifier(token.text.clone())
+            }
+            TokenKind::Punctuator if token.text == "(" => {
+                self.position += 1;
+                let expression = self.expression()?;
+                self.expect(")")?;
+                return Ok(expression);
+            }
+            _ => return self.unexpected("an expression"),
+        };
+
+        Ok(Expression { kind, location })
+    }
+
+    // The parenthesized arguments of a call or constructor.
+    fn arguments(&mut self) -> Parse<Vec<Expression>> {
+        self.expect("(")?;
+        let mut arguments = Vec::new();
+        let void_list = self.at("void") && self.peek_at(1).is_some_and(|next| next.is(")"));
+        if void_list {
+            self.position += 1;
+        }
+        if !self.accept(")") {
+            loop {
+                arguments.push(self.assignment()?);
+                if !self.accept(",") {
+                    break;
+                }
+            }
+            self.expect(")")?;
+        }
+        Ok(arguments)
+    }
+}
+
+// Converts a number token to a literal. GLSL ES 3.00 adds the `f` and `u` suffixes.
+fn number(text: &str, version: GlslVersion) -> Result<ExpressionKind, String> {
+    let es300 = version == GlslVersion::Es300;
+    let hex = text.starts_with("0x") || text.starts_with("0X");
+    let float = !hex && (text.contains('.') || text.contains('e') || text.contains('E'));
+
+    if float {
+        let digits = match text.strip_suffix(['f', 'F']) {
+            Some(digits) if es300 => digits,
+            Some(_) => return Err(format!("'{}' : floating-point suffixes are not allowed in GLSL ES 1.00", text)),
+            None => text,
+        };
+        return digits.parse::<f32>().map(ExpressionKind::FloatLiteral)
+            .map_err(|_| format!("'{}' : invalid floating-point constant", text));
+    }
+
+    let (digits, unsigned) = match text.strip_suffix(['u', 'U']) {
+        Some(digits) if es300 => (digits, true),
+        _ => (text, false),
+    };
+    match parse_integer(digits) {
+        Some(value) if value <= u32::MAX as i64 && unsigned => Ok(ExpressionKind::UintLiteral(value as u32)),
+        Some(value) if value <= u32::MAX as i64 => Ok(ExpressionKind::IntLiteral(value)),
+        Some(_) => Err(format!("'{}' : integer constant too large", text)),
+        None => Err(format!("'{}' : invalid integer constant", text)),
+    }
+}
@@ -0,0 +1,628 @@
+use std::collections::HashMap;
+
+use super::lexer::{tokenize, Token, TokenKind};
+use super::{Diagnostic, GlslVersion, Location};
+
+// Macros the implementation defines, which shaders can neither redefine nor undefine.
+const PREDEFINED: [&str; 5] = ["__LINE__", "__FILE__", "__VERSION__", "GL_ES", "GL_FRAGMENT_PRECISION_HIGH"];
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+// The result of preprocessing: the tokens the parser sees and what the directives declared.
+pub(super) struct Output {
+    pub tokens: Vec<Token>,
+    pub version: GlslVersion,
+    // Where the `#version` directive is, or the start of the shader without one.
+    pub version_location: Location,
+    // Extension names and the behavior requested for them, in directive order.
+    pub extensions: Vec<(String, String)>,
+}
+
+#[derive(Clone, Debug)]
+struct Macro {
+    // `None` for object-like macros.
+    parameters: Option<Vec<String>>,
+    body: Vec<Token>,
+}
+
+struct Conditional {
+    // Whether the current branch is processed.
+    active: bool,
+    // Whether any branch so far was taken.
+    taken: bool,
+    // Whether the whole group is inside an active region.
+    enclosing: bool,
+    seen_else: bool,
+    location: Location,
+}
+
+struct Preprocessor<'a> {
+    macros: HashMap<String, Macro>,
+    conditionals: Vec<Conditional>,
+    diagnostics: &'a mut Vec<Diagnostic>,
+    output: Output,
+    // Added to physical line numbers, as set by `#line`.
+    line_offset: i64,
+    source: u32,
+    // Whether anything but a `#version` directive may still come first.
+    seen_content: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+// PREPROCESSING
+// -------------------------------------------------------------------------------------------------
+
+// Runs the preprocessor over `source`, adding errors and warnings to `diagnostics`.
+pub(super) fn preprocess(source: &str, diagnostics: &mut Vec<Diagnostic>) -> Output {
+    let mut preprocessor = Preprocessor {
+        macros: HashMap::new(),
+        conditionals: Vec::new(),
+        diagnostics,
+        output: Output {
+            tokens: Vec::new(),
+            version: GlslVersion::Es100,
+            version_location: Location { source: 0, line: 1, column: 0 },
+            extensions: Vec::new(),
+        },
+        line_offset: 0,
+        source: 0,
+        seen_content: false,
+    };
+
+    for &(name, value) in &[("GL_ES", "1"), ("GL_FRAGMENT_PRECISION_HIGH", "1"), ("__VERSION__", "100")] {
+        preprocessor.macros.insert(name.to_string(), Macro {
+            parameters: None,
+            body: vec![Token {
+                kind: TokenKind::Number,
+                text: value.to_string(),
+                location: Location::default(),
+                offset: 0,
+            }],
+        });
+    }
+
+    // Text lines are expanded together, so that macro invocations can span lines.
+    let mut pending = Vec::new();
+    for mut line in tokenize(source) {
+        let location = Location {
+            source: preprocessor.source,
+            line: (line.number as i64 + preprocessor.line_offset) as u32,
+            column: 0,
+        };
+        for token in &mut line.tokens {
+            token.location = Location { column: token.location.column, ..location };
+        }
+
+        if line.tokens.first().is_some_and(|token| token.is("#")) {
+            preprocessor.flush(&mut pending);
+            preprocessor.directive(&line.tokens, line.number);
+        } else if preprocessor.active() && !line.tokens.is_empty() {
+            preprocessor.seen_content = true;
+            pending.extend(line.tokens);
+        }
+    }
+    preprocessor.flush(&mut pending);
+
+    if let Some(conditional) = preprocessor.conditionals.last() {
+        let location = conditional.location;
+        preprocessor.error(location, "unterminated conditional directive".to_string());
+    }
+
+    preprocessor.output
+}
+
+impl<'a> Preprocessor<'a> {
+    fn error(&mut self, location: Location, message: String) {
+        self.diagnostics.push(Diagnostic::error(location, message));
+    }
+
+    fn active(&self) -> bool {
+        self.conditionals.last().is_none_or(|conditional| conditional.active)
+    }
+
+    fn flush(&mut self, pending: &mut Vec<Token>) {
+        if pending.is_empty() {
+            return;
+        }
+
+        let tokens = std::mem::take(pending);
+        let mut expanded = Vec::new();
+        self.expand(&tokens, &mut Vec::new(), &mut expanded);
+
+        for token in expanded {
+            match token.kind {
+                TokenKind::Invalid => self.error(token.location, format!("invalid character '{}'", token.text)),
+                TokenKind::Punctuator if token.text == "#" || token.text == "##" => {
+                    self.error(token.location, format!("unexpected '{}'", token.text))
+                }
+                _ => self.output.tokens.push(token),
+            }
+        }
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // DIRECTIVES
+    // -------------------------------------------------------------------------------------------------
+
+    fn directive(&mut self, tokens: &[Token], physical_line: u32) {
+        let location = tokens[0].location;
+        let name = match tokens.get(1) {
+            Some(name) => name.text.clone(),
+            // The null directive.
+            None => return,
+        };
+        let arguments = &tokens[2..];
+
+        // Only conditionals are looked at in skipped regions.
+        match name.as_str() {
+            "if" | "ifdef" | "ifndef" => return self.begin_conditional(&name, arguments, location),
+            "elif" | "else" | "endif" => return self.continue_conditional(&name, arguments, location),
+            _ if !self.active() => return,
+            _ => {}
+        }
+
+        match name.as_str() {
+            "define" => self.define(arguments, location),
+            "undef" => match arguments {
+                [name] if PREDEFINED.contains(&name.text.as_str()) => {
+                    self.error(location, format!("can not undefine predefined macro '{}'", name.text))
+                }
+                [name] if name.kind == TokenKind::Identifier => {
+                    self.macros.remove(&name.text);
+                }
+                _ => self.error(location, "#undef takes a single macro name".to_string()),
+            },
+            "version" => self.version(arguments, location),
+            "extension" => self.extension(arguments, location),
+            "line" => self.line(arguments, location, physical_line),
+            "error" => {
+                let message: Vec<&str> = arguments.iter().map(|token| token.text.as_str()).collect();
+                self.error(location, format!("#error {}", message.join(" ")));
+            }
+            "pragma" => {}
+            _ => self.error(location, format!("invalid directive '#{}'", name)),
+        }
+
+        self.seen_content = true;
+    }
+
+    fn define(&mut self, arguments: &[Token], location: Location) {
+        let name = match arguments.first() {
+            Some(name) if name.kind == TokenKind::Identifier => name,
+            _ => return self.error(location, "#define needs a macro name".to_string()),
+        };
+
+        if PREDEFINED.contains(&name.text.as_str()) || name.text.starts_with("GL_") {
+            return self.error(location, format!("macro name '{}' is reserved", name.text));
+        }
+
+        let (parameters, body) = match arguments.get(1) {
+            Some(open) if open.is("(") && name.touches(open) => {
+                let close = match arguments.iter().position(|token| token.is(")")) {
+                    Some(close) => close,
+                    None => return self.error(location, "unterminated macro parameter list".to_string()),
+                };
+
+                let mut parameters = Vec::new();
+                for (index, token) in arguments[2..close].iter().enumerate() {
+                    let valid = if index % 2 == 0 {
+                        token.kind == TokenKind::Identifier && !parameters.contains(&token.text)
+                    } else {
+                        token.is(",")
+                    };
+                    if !valid {
+                        return self.error(location, format!("invalid macro parameter list for '{}'", name.text));
+                    }
+                    if index % 2 == 0 {
+                        parameters.push(token.text.clone());
+                    }
+                }
+                if close > 2 && arguments[close - 1].is(",") {
+                    return self.error(location, format!("invalid macro parameter list for '{}'", name.text));
+                }
+
+                (Some(parameters), &arguments[close + 1..])
+            }
+            _ => (None, &arguments[1..]),
+        };
+
+        let definition = Macro { parameters, body: body.to_vec() };
+
+        if let Some(existing) = self.macros.get(&name.text) {
+            let same_body = existing.body.iter().map(|token| &token.text).eq(definition.body.iter().map(|token| &token.text));
+            if existing.parameters != definition.parameters || !same_body {
+                return self.error(location, format!("macro '{}' redefined", name.text));
+            }
+        }
+        self.macros.insert(name.text.clone(), definition);
+    }
+
+    fn version(&mut self, arguments: &[Token], location: Location) {
+        if self.seen_content {
+            return self.error(location, "#version must occur before anything else".to_string());
+        }
+
+        let version = match arguments {
+            [number] if number.text == "100" => GlslVersion::Es100,
+            [number, profile] if number.text == "300" && profile.is("es") => GlslVersion::Es300,
+            [number] if number.text == "300" => {
+                return self.error(location, "#version 300 needs the 'es' profile".to_string())
+            }
+            [number, ..] if number.kind == TokenKind::Number => {
+                return self.error(location, format!("version {} is not supported", number.text))
+            }
+            _ => return self.error(location, "#version needs a version number".to_string()),
+        };
+
+        self.output.version = version;
+        self.output.version_location = location;
+        if version == GlslVersion::Es300 {
+            self.macros.get_mut("__VERSION__").unwrap().body[0].text = "300".to_string();
+        }
+    }
+
+    fn extension(&mut self, arguments: &[Token], location: Location) {
+        let (name, behavior) = match arguments {
+            [name, colon, behavior] if name.kind == TokenKind::Identifier && colon.is(":") => (&name.text, &behavior.text),
+            _ => return self.error(location, "#extension needs the form 'name : behavior'".to_string()),
+        };
+
+        match behavior.as_str() {
+            "require" | "enable" if name == "all" => {
+                return self.error(location, format!("extension 'all' can not be used with '{}'", behavior))
+            }
+            "require" => return self.error(location, format!("extension '{}' is not supported", name)),
+            "enable" | "warn" if name != "all" => self.diagnostics.push(Diagnostic::warning(
+                location, format!("extension '{}' is not supported", name))),
+            "enable" | "warn" | "disable" => {}
+            _ => return self.error(location, format!("invalid extension behavior '{}'", behavior)),
+        }
+
+        self.output.extensions.push((name.clone(), behavior.clone()));
+    }
+
+    fn line(&mut self, arguments: &[Token], location: Location, physical_line: u32) {
+        let mut expanded = Vec::new();
+        self.expand(arguments, &mut Vec::new(), &mut expanded);
+
+        let numbers: Vec<Option<i64>> = expanded.iter().map(|token| parse_integer(&token.text)).collect();
+        let (line, source) = match numbers.as_slice() {
+            [Some(line)] => (*line, self.source as i64),
+            [Some(line), Some(source)] => (*line, *source),
+            _ => return self.error(location, "#line needs a line number and an optional source number".to_string()),
+        };
+
+        // The line after the directive gets the given number.
+        self.line_offset = line - (physical_line as i64 + 1);
+        self.source = source as u32;
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // CONDITIONALS
+    // -------------------------------------------------------------------------------------------------
+
+    fn begin_conditional(&mut self, name: &str, arguments: &[Token], location: Location) {
+        let enclosing = self.active();
+        let condition = if !enclosing {
+            false
+        } else if name == "if" {
+            self.condition(arguments, location)
+        } else {
+            match arguments {
+                [macro_name] if macro_name.kind == TokenKind::Identifier => {
+                    self.is_defined(&macro_name.text) == (name == "ifdef")
+                }
+                _ => {
+                    self.error(location, format!("#{} takes a single macro name", name));
+                    false
+                }
+            }
+        };
+
+        self.conditionals.push(Conditional { active: condition, taken: condition, enclosing, seen_else: false, location });
+    }
+
+    fn continue_conditional(&mut self, name: &str, arguments: &[Token], location: Location) {
+        let (enclosing, taken, seen_else) = match self.conditionals.last() {
+            Some(conditional) => (conditional.enclosing, conditional.taken, conditional.seen_else),
+            None => return self.error(location, format!("#{} without #if", name)),
+        };
+
+        if seen_else && name != "endif" {
+            return self.error(location, format!("#{} after #else", name));
+        }
+
+        match name {
+            "endif" => {
+                self.conditionals.pop();
+            }
+            "else" => {
+                let conditional = self.conditionals.last_mut().unwrap();
+                conditional.active = enclosing && !taken;
+                conditional.taken = true;
+                conditional.seen_else = true;
+            }
+            _ => {
+                let condition = enclosing && !taken && self.condition(arguments, location);
+                let conditional = self.conditionals.last_mut().unwrap();
+                conditional.active = condition;
+                conditional.taken |= condition;
+            }
+        }
+    }
+
+    fn is_defined(&self, name: &str) -> bool {
+        self.macros.contains_key(name) || name == "__LINE__" || name == "__FILE__"
+    }
+
+    // Evaluates the expression of `#if` or `#elif`.
+    fn condition(&mut self, arguments: &[Token], location: Location) -> bool {
+        // `defined` is applied before macro expansion.
+        let mut replaced = Vec::new();
+        let mut index = 0;
+        while index < arguments.len() {
+            let token = &arguments[index];
+            if !token.is("defined") {
+                replaced.push(token.clone());
+                index += 1;
+                continue;
+            }
+
+            let (name, next) = match (arguments.get(index + 1), arguments.get(index + 2), arguments.get(index + 3)) {
+                (Some(open), Some(name), Some(close)) if open.is("(") && close.is(")") => (name, index + 4),
+                (Some(name), _, _) if name.kind == TokenKind::Identifier => (name, index + 2),
+                _ => {
+                    self.error(location, "'defined' needs a macro name".to_string());
+                    return false;
+                }
+            };
+
+            let value = if self.is_defined(&name.text) { "1" } else { "0" };
+            replaced.push(Token { kind: TokenKind::Number, text: value.to_string(), ..token.clone() });
+            index = next;
+        }
+
+        let mut expanded = Vec::new();
+        self.expand(&replaced, &mut Vec::new(), &mut expanded);
+
+        let mut parser = ExpressionParser { tokens: &expanded, position: 0 };
+        match parser.expression(0) {
+            Ok(value) if parser.position == expanded.len() => value != 0,
+            Ok(_) => {
+                self.error(location, format!("unexpected '{}' in preprocessor expression", expanded[parser.position].text));
+                false
+            }
+            Err(message) => {
+                self.error(location, message);
+                false
+            }
+        }
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // MACRO EXPANSION
+    // -------------------------------------------------------------------------------------------------
+
+    // Appends `tokens` with their macros expanded to `out`. `expanding` holds the macros being
+    // expanded, which are not expanded again inside their own replacement.
+    fn expand(&mut self, tokens: &[Token], expanding: &mut Vec<String>, out: &mut Vec<Token>) {
+        let mut index = 0;
+        while index < tokens.len() {
+            let token = &tokens[index];
+            index += 1;
+
+            if token.kind != TokenKind::Identifier {
+                out.push(token.clone());
+                continue;
+            }
+
+            let special = match token.text.as_str() {
+                "__LINE__" => Some(token.location.line),
+                "__FILE__" => Some(token.location.source),
+                _ => None,
+            };
+            if let Some(value) = special {
+                out.push(Token { kind: TokenKind::Number, text: value.to_string(), ..token.clone() });
+                continue;
+            }
+
+            let definition = match self.macros.get(&token.text) {
+                Some(definition) if !expanding.contains(&token.text) => definition.clone(),
+                _ => {
+                    out.push(token.clone());
+                    continue;
+                }
+            };
+
+            // Replacement tokens report the location of the invocation.
+            let relocate = |body: &[Token]| -> Vec<Token> {
+                body.iter().map(|body_token| Token { location: token.location, ..body_token.clone() }).collect()
+            };
+
+            let replacement = match definition.parameters {
+                None => relocate(&definition.body),
+                Some(ref parameters) => {
+                    if !tokens.get(index).is_some_and(|next| next.is("(")) {
+                        out.push(token.clone());
+                        continue;
+                    }
+
+                    let (arguments, next) = match collect_arguments(tokens, index) {
+                        Some(arguments) => arguments,
+                        None => {
+                            self.error(token.location, format!("unterminated invocation of macro '{}'", token.text));
+                            return;
+                        }
+                    };
+                    index = next;
+
+                    let count = if parameters.is_empty() && arguments.len() == 1 && arguments[0].is_empty() {
+                        0
+                    } else {
+                        arguments.len()
+                    };
+                    if count != parameters.len() {
+                        self.error(token.location, format!("macro '{}' takes {} arguments, but {} were given",
+                                                           token.text, parameters.len(), count));
+                        continue;
+                    }
+
+                    // Arguments are fully expanded before they are substituted.
+                    let expanded_arguments: Vec<Vec<Token>> = arguments.iter().map(|argument| {
+                        let mut expanded = Vec::new();
+                        self.expand(argument, expanding, &mut expanded);
+                        expanded
+                    }).collect();
+
+                    let mut replacement = Vec::new();
+                    for body_token in relocate(&definition.body) {
+                        match parameters.iter().position(|parameter| body_token.kind == TokenKind::Identifier
+                            && *parameter == body_token.text) {
+                            Some(parameter) => replacement.extend(expanded_arguments[parameter].iter().cloned()),
+                            None => replacement.push(body_token),
+                        }
+                    }
+                    replacement
+                }
+            };
+
+            expanding.push(token.text.clone());
+            self.expand(&replacement, expanding, out);
+            expanding.pop();
+        }
+    }
+}
+
+// Splits the arguments of a macro invocation whose `(` is at `open`. Returns them and the index
+// after the closing parenthesis, or `None` if it is missing.
+fn collect_arguments(tokens: &[Token], open: usize) -> Option<(Vec<Vec<Token>>, usize)> {
+    let mut arguments = vec![Vec::new()];
+    let mut depth = 0;
+
+    for (index, token) in tokens.iter().enumerate().skip(open + 1) {
+        if token.is(")") && depth == 0 {
+            return Some((arguments, index + 1));
+        }
+        if token.is(",") && depth == 0 {
+            arguments.push(Vec::new());
+            continue;
+        }
+        if token.is("(") {
+            depth += 1;
+        } else if token.is(")") {
+            depth -= 1;
+        }
+        arguments.last_mut().unwrap().push(token.clone());
+    }
+
+    None
+}
+
+// Decimal, octal and hexadecimal integer literals.
+pub(super) fn parse_integer(text: &str) -> Option<i64> {
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        i64::from_str_radix(hex, 16).ok()
+    } else if text.len() > 1 && text.starts_with('0') {
+        i64::from_str_radix(&text[1..], 8).ok()
+    } else {
+        text.parse().ok()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// EXPRESSIONS
+// -------------------------------------------------------------------------------------------------
+
+// Evaluates the integer expressions of `#if` by precedence climbing.
+struct ExpressionParser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl<'a> ExpressionParser<'a> {
+    fn expression(&mut self, min_precedence: u8) -> Result<i64, String> {
+        let mut left = self.unary()?;
+
+        while let Some(token) = self.tokens.get(self.position) {
+            let precedence = match binary_precedence(&token.text) {
+                Some(precedence) if precedence >= min_precedence && token.kind == TokenKind::Punctuator => precedence,
+                _ => break,
+            };
+            let operator = token.text.clone();
+            self.position += 1;
+
+            let right = self.expression(precedence + 1)?;
+            left = match operator.as_str() {
+                "||" => (left != 0 || right != 0) as i64,
+                "&&" => (left != 0 && right != 0) as i64,
+                "|" => left | right,
+                "^" => left ^ right,
+                "&" => left & right,
+                "==" => (left == right) as i64,
+                "!=" => (left != right) as i64,
+                "<" => (left < right) as i64,
+                ">" => (left > right) as i64,
+                "<=" => (left <= right) as i64,
+                ">=" => (left >= right) as i64,
+                "<<" => left.wrapping_shl(right as u32),
+                ">>" => left.wrapping_shr(right as u32),
+                "+" => left.wrapping_add(right),
+                "-" => left.wrapping_sub(right),
+                "*" => left.wrapping_mul(right),
+                _ if right == 0 => return Err("division by zero in preprocessor expression".to_string()),
+                "/" => left.wrapping_div(right),
+                _ => left.wrapping_rem(right),
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<i64, String> {
+        let token = match self.tokens.get(self.position) {
+            Some(token) => token,
+            None => return Err("unexpected end of preprocessor expression".to_string()),
+        };
+        self.position += 1;
+
+        match (token.kind, token.text.as_str()) {
+            (TokenKind::Punctuator, "+") => self.unary(),
+            (TokenKind::Punctuator, "-") => Ok(self.unary()?.wrapping_neg()),
+            (TokenKind::Punctuator, "~") => Ok(!self.unary()?),
+            (TokenKind::Punctuator, "!") => Ok((self.unary()? == 0) as i64),
+            (TokenKind::Punctuator, "(") => {
+                let value = self.expression(0)?;
+                match self.tokens.get(self.position) {
+                    Some(close) if close.is(")") => {
+                        self.position += 1;
+                        Ok(value)
+                    }
+                    _ => Err("missing ')' in preprocessor expression".to_string()),
+                }
+            }
+            (TokenKind::Number, text) => {
+                parse_integer(text).ok_or_else(|| format!("invalid integer '{}' in preprocessor expression", text))
+            }
+            (TokenKind::Identifier, text) => Err(format!("undefined identifier '{}' in preprocessor expression", text)),
+            (_, text) => Err(format!("unexpected '{}' in preprocessor expression", text)),
+        }
+    }
+}
+
+fn binary_precedence(operator: &str) -> Option<u8> {
+    match operator {
+        "||" => Some(1),
+        "&&" => Some(2),
+        "|" => Some(3),
+        "^" => Some(4),
+        "&" => Some(5),
+        "==" | "!=" => Some(6),
+        "<" | ">" | "<=" | ">=" => Some(7),
+        "<<" | ">>" => Some(8),
+        "+" | "-" => Some(9),
+        "*" | "/" | "%" => Some(10),
+        _ => None,
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt;
+use std::rc::Rc;
+
+use types::*;
+use consts::*;
+
+// -------------------------------------------------------------------------------------------------
+// TYPES
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum Scalar {
+    Float,
+    Int,
+    // GLSL ES 3.00 only.
+    Uint,
+    Bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum Shape {
+    Dim2,
+    Dim3,
+    Cube,
+    Array2,
+}
+
+// A sampler type: `sampler2D` and `samplerCube`, and in GLSL ES 3.00 also the 3D, array, shadow
+// and integer ones.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) struct Sampler {
+    pub shape: Shape,
+    // The component type of lookup results, `Float` for shadow samplers.
+    pub scalar: Scalar,
+    pub shadow: bool,
+}
+
+// A resolved GLSL type. Values of every type are stored as a flat run of floats: booleans as 0 or
+// 1, integers as whole numbers and samplers as their texture unit.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Type {
+    Void,
+    // A scalar when the size is 1.
+    Vector(Scalar, usize),
+    // Columns and rows, stored column by column.
+    Matrix(usize, usize),
+    Sampler(Sampler),
+    Struct(Rc<StructType>),
+    Array(Box<Type>, usize),
+}
+
+#[derive(Debug)]
+pub(super) struct StructType {
+    pub name: String,
+    pub fields: Vec<(String, Type)>,
+    // Tells apart structures of the same name declared in different scopes.
+    pub id: usize,
+}
+
+impl PartialEq for StructType {
+    fn eq(&self, other: &StructType) -> bool {
+        self.id == other.id
+    }
+}
+
+pub(super) const FLOAT: Type = Type::Vector(Scalar::Float, 1);
+pub(super) const INT: Type = Type::Vector(Scalar::Int, 1);
+pub(super) const UINT: Type = Type::Vector(Scalar::Uint, 1);
+pub(super) const BOOL: Type = Type::Vector(Scalar::Bool, 1);
+
+// The sampler type keywords, with what they name.
+const SAMPLERS: [(&str, Shape, Scalar, bool); 15] = [
+    ("sampler2D", Shape::Dim2, Scalar::Float, false),
+    ("sampler3D", Shape::Dim3, Scalar::Float, false),
+    ("samplerCube", Shape::Cube, Scalar::Float, false),
+    ("sampler2DArray", Shape::Array2, Scalar::Float, false),
+    ("sampler2DShadow", Shape::Dim2, Scalar::Float, true),
+    ("samplerCubeShadow", Shape::Cube, Scalar::Float, true),
+    ("sampler2DArrayShadow", Shape::Array2, Scalar::Float, true),
+    ("isampler2D", Shape::Dim2, Scalar::Int, false),
+    ("isampler3D", Shape::Dim3, Scalar::Int, false),
+    ("isamplerCube", Shape::Cube, Scalar::Int, false),
+    ("isampler2DArray", Shape::Array2, Scalar::Int, false),
+    ("usampler2D", Shape::Dim2, Scalar::Uint, false),
+    ("usampler3D", Shape::Dim3, Scalar::Uint, false),
+    ("usamplerCube", Shape::Cube, Scalar::Uint, false),
+    ("usampler2DArray", Shape::Array2, Scalar::Uint, false),
+];
+
+impl Sampler {
+    pub fn keyword(&self) -> &'static str {
+        SAMPLERS.iter().find(|sampler| (sampler.1, sampler.2, sampler.3) == (self.shape, self.scalar, self.shadow))
+            .map_or("sampler2D", |sampler| sampler.0)
+    }
+
+    // The number of texture coordinates, without the depth reference of shadow samplers.
+    pub fn dimensions(&self) -> usize {
+        match self.shape {
+            Shape::Dim2 => 2,
+            Shape::Dim3 | Shape::Cube | Shape::Array2 => 3,
+        }
+    }
+}
+
+impl Type {
+    // The type a type keyword names.
+    // The type a type keyword of any GLSL ES version names.
+    pub fn keyword(name: &str) -> Option<Type> {
+        let type_ = match name {
+            "void" => Type::Void,
+            "float" => FLOAT,
+            "int" => INT,
+            "uint" => UINT,
+            "bool" => BOOL,
+            "vec2" => Type::Vector(Scalar::Float, 2),
+            "vec3" => Type::Vector(Scalar::Float, 3),
+            "vec4" => Type::Vector(Scalar::Float, 4),
+            "ivec2" => Type::Vector(Scalar::Int, 2),
+            "ivec3" => Type::Vector(Scalar::Int, 3),
+            "ivec4" => Type::Vector(Scalar::Int, 4),
+            "uvec2" => Type::Vector(Scalar::Uint, 2),
+            "uvec3" => Type::Vector(Scalar::Uint, 3),
+            "uvec4" => Type::Vector(Scalar::Uint, 4),
+            "bvec2" => Type::Vector(Scalar::Bool, 2),
+            "bvec3" => Type::Vector(Scalar::Bool, 3),
+            "bvec4" => Type::Vector(Scalar::Bool, 4),
+            "mat2" => Type::Matrix(2, 2),
+            "mat3" => Type::Matrix(3, 3),
+            "mat4" => Type::Matrix(4, 4),
+            _ if name.starts_with("mat") && name.len() == 6 && name.as_bytes()[4] == b'x' => {
+                let dimension = |index: usize| match name.as_bytes()[index] {
+                    digit @ b'2'..=b'4' => Some((digit - b'0') as usize),
+                    _ => None,
+                };
+                Type::Matrix(dimension(3)?, dimension(5)?)
+            }
+            _ => {
+                let &(_, shape, scalar, shadow) = SAMPLERS.iter().find(|sampler| sampler.0 == name)?;
+                Type::Sampler(Sampler { shape, scalar, shadow })
+            }
+        };
+        Some(type_)
+    }
+
+    // The number of floats a value of this type takes.
+    pub fn slots(&self) -> usize {
+        match *self {
+            Type::Void => 0,
+            Type::Vector(_, size) => size,
+            Type::Matrix(columns, rows) => columns * rows,
+            Type::Sampler(_) => 1,
+            Type::Struct(ref structure) => structure.fields.iter().map(|field| field.1.slots()).sum(),
+            Type::Array(ref element, length) => element.slots() * length,
+        }
+    }
+
+    // The scalar type of the components of scalars, vectors and matrices.
+    pub fn scalar(&self) -> Option<Scalar> {
+        match *self {
+            Type::Vector(scalar, _) => Some(scalar),
+            Type::Matrix(..) => Some(Scalar::Float),
+            _ => None,
+        }
+    }
+
+    pub fn is_scalar(&self) -> bool {
+        matches!(*self, Type::Vector(_, 1))
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self.scalar(), Some(Scalar::Float) | Some(Scalar::Int) | Some(Scalar::Uint))
+    }
+
+    // Scalars and vectors of `int` or `uint`.
+    pub fn is_integer(&self) -> bool {
+        matches!(*self, Type::Vector(Scalar::Int, _) | Type::Vector(Scalar::Uint, _))
+    }
+
+    pub fn is_sampler(&self) -> bool {
+        matches!(*self, Type::Sampler(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(*self, Type::Array(..))
+    }
+
+    // Whether values of this type or any part of it are samplers.
+    pub fn contains_sampler(&self) -> bool {
+        self.contains(&|type_| type_.is_sampler())
+    }
+
+    // Whether this type or any part of it satisfies `predicate`.
+    pub fn contains(&self, predicate: &dyn Fn(&Type) -> bool) -> bool {
+        predicate(self) || match *self {
+            Type::Struct(ref structure) => structure.fields.iter().any(|field| field.1.contains(predicate)),
+            Type::Array(ref element, _) => element.contains(predicate),
+            _ => false,
+        }
+    }
+
+    pub fn contains_array(&self) -> bool {
+        match *self {
+            Type::Array(..) => true,
+            Type::Struct(ref structure) => structure.fields.iter().any(|field| field.1.contains_array()),
+            _ => false,
+        }
+    }
+
+    // Whether declarations of this type take a precision qualifier.
+    pub fn has_precision(&self) -> bool {
+        match *self {
+            Type::Vector(Scalar::Bool, _) | Type::Void | Type::Struct(_) => false,
+            Type::Array(ref element, _) => element.has_precision(),
+            _ => true,
+        }
+    }
+
+    // The type keyword precision defaults are looked up by: `float`, `int` or the sampler type.
+    // Unsigned integers share the default of `int`.
+    pub fn precision_key(&self) -> Option<&'static str> {
+        match *self {
+            Type::Vector(Scalar::Float, _) | Type::Matrix(..) => Some("float"),
+            Type::Vector(Scalar::Int, _) | Type::Vector(Scalar::Uint, _) => Some("int"),
+            Type::Sampler(sampler) => Some(sampler.keyword()),
+            Type::Array(ref element, _) => element.precision_key(),
+            _ => None,
+        }
+    }
+
+    // The enum `glGetActiveAttrib` and `glGetActiveUniform` report for this type.
+    pub fn gl_enum(&self) -> Option<GLenum> {
+        let type_ = match *self {
+            Type::Vector(Scalar::Float, 1) => GL_FLOAT,
+            Type::Vector(Scalar::Float, 2) => GL_FLOAT_VEC2,
+            Type::Vector(Scalar::Float, 3) => GL_FLOAT_VEC3,
+            Type::Vector(Scalar::Float, 4) => GL_FLOAT_VEC4,
+            Type::Vector(Scalar::Int, 1) => GL_INT,
+            Type::Vector(Scalar::Int, 2) => GL_INT_VEC2,
+            Type::Vector(Scalar::Int, 3) => GL_INT_VEC3,
+            Type::Vector(Scalar::Int, 4) => GL_INT_VEC4,
+            Type::Vector(Scalar::Uint, 1) => GL_UNSIGNED_INT,
+            Type::Vector(Scalar::Uint, 2) => GL_UNSIGNED_INT_VEC2,
+            Type::Vector(Scalar::Uint, 3) => GL_UNSIGNED_INT_VEC3,
+            Type::Vector(Scalar::Uint, 4) => GL_UNSIGNED_INT_VEC4,
+            Type::Vector(Scalar::Bool, 1) => GL_BOOL,
+            Type::Vector(Scalar::Bool, 2) => GL_BOOL_VEC2,
+            Type::Vector(Scalar::Bool, 3) => GL_BOOL_VEC3,
+            Type::Vector(Scalar::Bool, 4) => GL_BOOL_VEC4,
+            Type::Matrix(2, 2) => GL_FLOAT_MAT2,
+            Type::Matrix(3, 3) => GL_FLOAT_MAT3,
+            Type::Matrix(4, 4) => GL_FLOAT_MAT4,
+            Type::Matrix(2, 3) => GL_FLOAT_MAT2x3,
+            Type::Matrix(2, 4) => GL_FLOAT_MAT2x4,
+            Type::Matrix(3, 2) => GL_FLOAT_MAT3x2,
+            Type::Matrix(3, 4) => GL_FLOAT_MAT3x4,
+            Type::Matrix(4, 2) => GL_FLOAT_MAT4x2,
+            Type::Matrix(4, 3) => GL_FLOAT_MAT4x3,
+            Type::Sampler(sampler) => match sampler.keyword() {
+                "sampler2D" => GL_SAMPLER_2D,
+                "sampler3D" => GL_SAMPLER_3D,
+                "samplerCube" => GL_SAMPLER_CUBE,
+                "sampler2DArray" => GL_SAMPLER_2D_ARRAY,
+                "sampler2DShadow" => GL_SAMPLER_2D_SHADOW,
+                "samplerCubeShadow" => GL_SAMPLER_CUBE_SHADOW,
+                "sampler2DArrayShadow" => GL_SAMPLER_2D_ARRAY_SHADOW,
+                "isampler2D" => GL_INT_SAMPLER_2D,
+                "isampler3D" => GL_INT_SAMPLER_3D,
+                "isamplerCube" => GL_INT_SAMPLER_CUBE,
+                "isampler2DArray" => GL_INT_SAMPLER_2D_ARRAY,
+                "usampler2D" => GL_UNSIGNED_INT_SAMPLER_2D,
+                "usampler3D" => GL_UNSIGNED_INT_SAMPLER_3D,
+                "usamplerCube" => GL_UNSIGNED_INT_SAMPLER_CUBE,
+                _ => GL_UNSIGNED_INT_SAMPLER_2D_ARRAY,
+            },
+            _ => return None,
+        };
+        Some(type_)
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Type::Void => write!(f, "void"),
+            Type::Vector(scalar, 1) => write!(f, "{}", match scalar {
+                Scalar::Float => "float",
+                Scalar::Int => "int",
+                Scalar::Uint => "uint",
+                Scalar::Bool => "bool",
+            }),
+            Type::Vector(scalar, size) => write!(f, "{}vec{}", match scalar {
+                Scalar::Float => "",
+                Scalar::Int => "i",
+                Scalar::Uint => "u",
+                Scalar::Bool => "b",
+            }, size),
+            Type::Matrix(columns, rows) if columns == rows => write!(f, "mat{}", columns),
+            Type::Matrix(columns, rows) => write!(f, "mat{}x{}", columns, rows),
+            Type::Sampler(sampler) => write!(f, "{}", sampler.keyword()),
+            Type::Struct(ref structure) => write!(f, "structure '{}'", structure.name),
+            Type::Array(ref element, length) => write!(f, "{}[{}]", element, length),
+        }
+    }
+}
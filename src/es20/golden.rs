@@ -0,0 +1,459 @@
+use std::env;
+use std::f64::consts::PI;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::image::{self, Image, ReadFormat, Rect};
+use super::wrapper::{Error, ErrorKind, Wrapper};
+
+/// Set to a non-empty value other than `0` to write the rendered images as the new goldens
+/// instead of comparing against them.
+pub const BLESS_VARIABLE: &str = "OPENGL_ES_RS_BLESS";
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+/// How a rendered image is compared to its golden.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Metric {
+    /// Every channel of every pixel must match.
+    Exact,
+    /// No channel of a pixel may differ by more than the given amount.
+    Tolerance(u8),
+    /// The peak signal-to-noise ratio over all RGBA channels must be at least the given number of
+    /// decibels. Identical images have an infinite PSNR.
+    Psnr(f64),
+    /// The CIEDE2000 color difference of a pixel may not exceed the given value. A difference of
+    /// about 1 is the smallest one most people notice. Alpha is ignored.
+    DeltaE(f64),
+}
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// Differences between a rendered image and its golden.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Statistics {
+    pub max_channel_difference: u8,
+    /// Infinite for identical images.
+    pub psnr: f64,
+    pub max_delta_e: f64,
+    pub mean_delta_e: f64,
+    /// Pixels that fail the metric. For `Psnr`, which judges the whole image, pixels that differ
+    /// at all.
+    pub failing_pixels: usize,
+    /// The first failing pixel, counted from the top left.
+    pub first_failure: Option<(u32, u32)>,
+}
+
+/// The outcome of comparing a rendered image to its golden.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    pub name: String,
+    pub passed: bool,
+    /// The golden was written instead of compared, see `BLESS_VARIABLE`.
+    pub blessed: bool,
+    /// `None` when the golden was blessed or the sizes differ.
+    pub statistics: Option<Statistics>,
+    pub summary: String,
+}
+
+/// Compares rendered images against PNG goldens stored in a directory.
+///
+/// Goldens are `<name>.png` in the golden directory. On failure, the rendered image, the golden, a
+/// diff image and a text summary are written to the output directory as `<name>.actual.png`,
+/// `<name>.expected.png`, `<name>.diff.png` and `<name>.txt`. Everything goes through `Wrapper`,
+/// so the harness runs on any backend, including software ones on machines without a GPU.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoldenTest {
+    golden_dir: PathBuf,
+    output_dir: PathBuf,
+    metric: Metric,
+    allowed_failures: usize,
+    bless: bool,
+}
+
+impl GoldenTest {
+    /// Compares exactly and writes failures to `failures` inside `golden_dir`. Blessing follows
+    /// `BLESS_VARIABLE`.
+    pub fn new<P: AsRef<Path>>(golden_dir: P) -> GoldenTest {
+        let golden_dir = golden_dir.as_ref().to_path_buf();
+        let bless = env::var(BLESS_VARIABLE).map(|value| !value.is_empty() && value != "0").unwrap_or(false);
+
+        GoldenTest {
+            output_dir: golden_dir.join("failures"),
+            golden_dir,
+            metric: Metric::Exact,
+            allowed_failures: 0,
+            bless,
+        }
+    }
+
+    pub fn metric(mut self, metric: Metric) -> GoldenTest {
+        self.metric = metric;
+        self
+    }
+
+    /// The number of pixels that may fail a per-pixel metric before the comparison fails.
+    pub fn allowed_failures(mut self, count: usize) -> GoldenTest {
+        self.allowed_failures = count;
+        self
+    }
+
+    pub fn output_dir<P: AsRef<Path>>(mut self, output_dir: P) -> GoldenTest {
+        self.output_dir = output_dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// Overrides `BLESS_VARIABLE`.
+    pub fn bless(mut self, bless: bool) -> GoldenTest {
+        self.bless = bless;
+        self
+    }
+
+    /// Runs `render`, reads `rect` of the bound framebuffer back as RGBA8 and compares it to the
+    /// golden `name`. A mismatch is an `InvalidResult` error carrying the summary.
+    pub fn check<F>(&self, gl: &mut Wrapper, name: &str, rect: Rect, render: F) -> Result<Comparison, Error>
+        where F: FnOnce(&mut Wrapper) -> Result<(), Error> {
+        render(gl)?;
+        gl.gl_finish()?;
+
+        let actual = image::read_image(gl, rect, ReadFormat::Rgba8)?;
+        self.check_image(name, &actual)
+    }
+
+    /// Like `check`, for an image that has already been read back.
+    pub fn check_image(&self, name: &str, actual: &Image) -> Result<Comparison, Error> {
+        let comparison = self.compare(name, actual)?;
+        if comparison.passed {
+            Ok(comparison)
+        } else {
+            Err(Error::new(ErrorKind::InvalidResult, comparison.summary))
+        }
+    }
+
+    /// Compares `actual` to the golden `name`, writing the failure artifacts when it does not
+    /// match. Blesses instead when blessing is enabled. A missing golden is an `Io` error.
+    pub fn compare(&self, name: &str, actual: &Image) -> Result<Comparison, Error> {
+        let golden_path = self.golden_dir.join(format!("{}.png", name));
+
+        if self.bless {
+            fs::create_dir_all(&self.golden_dir)?;
+            actual.save_png(&golden_path)?;
+            return Ok(Comparison {
+                name: name.to_string(),
+                passed: true,
+                blessed: true,
+                statistics: None,
+                summary: format!("{}: blessed {}", name, golden_path.display()),
+            });
+        }
+
+        if !golden_path.exists() {
+            return Err(Error::new(ErrorKind::Io, format!(
+                "{}: no golden at {}, set {}=1 to create it", name, golden_path.display(), BLESS_VARIABLE)));
+        }
+        let expected = Image::load_png(&golden_path)?;
+
+        let comparison = if (expected.width, expected.height) != (actual.width, actual.height) {
+            Comparison {
+                name: name.to_string(),
+                passed: false,
+                blessed: false,
+                statistics: None,
+                summary: format!("{}: rendered {}x{} but the golden is {}x{}", name, actual.width, actual.height,
+                                 expected.width, expected.height),
+            }
+        } else {
+            let statistics = self.statistics(&expected, actual);
+            let passed = match self.metric {
+                Metric::Psnr(minimum) => statistics.psnr >= minimum,
+                _ => statistics.failing_pixels <= self.allowed_failures,
+            };
+
+            Comparison {
+                name: name.to_string(),
+                passed,
+                blessed: false,
+                statistics: Some(statistics),
+                summary: self.summary(name, passed, &statistics, actual),
+            }
+        };
+
+        if !comparison.passed {
+            self.write_failure(name, &comparison, &expected, actual)?;
+        }
+        Ok(comparison)
+    }
+
+    fn statistics(&self, expected: &Image, actual: &Image) -> Statistics {
+        let mut statistics = Statistics {
+            max_channel_difference: 0,
+            psnr: f64::INFINITY,
+            max_delta_e: 0.0,
+            mean_delta_e: 0.0,
+            failing_pixels: 0,
+            first_failure: None,
+        };
+
+        let mut squared_error = 0.0;
+        let mut total_delta_e = 0.0;
+
+        for (index, (a, b)) in expected.pixels.chunks(4).zip(actual.pixels.chunks(4)).enumerate() {
+            let difference = channel_difference(a, b);
+            squared_error += a.iter().zip(b).map(|(&a, &b)| (f64::from(a) - f64::from(b)).powi(2)).sum::<f64>();
+
+            let delta_e = if a[..3] == b[..3] { 0.0 } else { delta_e_2000(lab(a), lab(b)) };
+            total_delta_e += delta_e;
+
+            statistics.max_channel_difference = statistics.max_channel_difference.max(difference);
+            statistics.max_delta_e = statistics.max_delta_e.max(delta_e);
+
+            let failing = match self.metric {
+                Metric::Exact | Metric::Psnr(_) => difference > 0,
+                Metric::Tolerance(tolerance) => difference > tolerance,
+                Metric::DeltaE(maximum) => delta_e > maximum,
+            };
+            if failing {
+                statistics.failing_pixels += 1;
+                if statistics.first_failure.is_none() {
+                    let width = actual.width as usize;
+                    statistics.first_failure = Some(((index % width) as u32, (index / width) as u32));
+                }
+            }
+        }
+
+        let pixels = (actual.pixels.len() / 4).max(1) as f64;
+        statistics.mean_delta_e = total_delta_e / pixels;
+        if squared_error > 0.0 {
+            let mean_squared_error = squared_error / (pixels * 4.0);
+            statistics.psnr = 10.0 * (255.0 * 255.0 / mean_squared_error).log10();
+        }
+
+        statistics
+    }
+
+    fn summary(&self, name: &str, passed: bool, statistics: &Statistics, actual: &Image) -> String {
+        let mut summary = String::new();
+        let _ = writeln!(summary, "{}: {} with {:?}", name, if passed { "passed" } else { "FAILED" }, self.metric);
+        let _ = writeln!(summary, "  size: {}x{}", actual.width, actual.height);
+        match self.metric {
+            Metric::Psnr(minimum) => {
+                let _ = writeln!(summary, "  differing pixels: {} (PSNR of at least {} dB required)",
+                                 statistics.failing_pixels, minimum);
+            }
+            _ => {
+                let _ = writeln!(summary, "  failing pixels: {} (allowed {})", statistics.failing_pixels,
+                                 self.allowed_failures);
+            }
+        }
+        let _ = writeln!(summary, "  max channel difference: {}", statistics.max_channel_difference);
+        let _ = writeln!(summary, "  PSNR: {:.2} dB", statistics.psnr);
+        let _ = writeln!(summary, "  delta E: max {:.3}, mean {:.3}", statistics.max_delta_e,
+                         statistics.mean_delta_e);
+        if let Some((x, y)) = statistics.first_failure {
+            let _ = writeln!(summary, "  first {} pixel: ({}, {}) from the top left",
+                             if let Metric::Psnr(_) = self.metric { "differing" } else { "failing" }, x, y);
+        }
+        if !passed {
+            let _ = writeln!(summary, "  artifacts: {}", self.output_dir.join(format!("{}.*", name)).display());
+        }
+        summary
+    }
+
+    fn write_failure(&self, name: &str, comparison: &Comparison, expected: &Image, actual: &Image)
+                     -> Result<(), Error> {
+        fs::create_dir_all(&self.output_dir)?;
+        actual.save_png(self.output_dir.join(format!("{}.actual.png", name)))?;
+        expected.save_png(self.output_dir.join(format!("{}.expected.png", name)))?;
+        fs::write(self.output_dir.join(format!("{}.txt", name)), &comparison.summary)?;
+
+        if (expected.width, expected.height) == (actual.width, actual.height) {
+            self.diff_image(expected, actual).save_png(self.output_dir.join(format!("{}.diff.png", name)))?;
+        }
+        Ok(())
+    }
+
+    // Failing pixels are red, other differences are gray scaled up to stay visible, and
+    // identical pixels are a dimmed copy of the golden.
+    fn diff_image(&self, expected: &Image, actual: &Image) -> Image {
+        let mut diff = Image::new(actual.width, actual.height);
+
+        for (out, (a, b)) in diff.pixels.chunks_mut(4).zip(expected.pixels.chunks(4).zip(actual.pixels.chunks(4))) {
+            let difference = channel_difference(a, b);
+            let failing = match self.metric {
+                Metric::Exact | Metric::Psnr(_) => difference > 0,
+                Metric::Tolerance(tolerance) => difference > tolerance,
+                Metric::DeltaE(maximum) => difference > 0 && delta_e_2000(lab(a), lab(b)) > maximum,
+            };
+
+            let pixel = if failing {
+                [255, 0, 0, 255]
+            } else if difference > 0 {
+                let gray = 64u16.saturating_add(u16::from(difference) * 8).min(255) as u8;
+                [gray, gray, gray, 255]
+            } else {
+                let dim = |channel: u8| channel / 4;
+                [dim(a[0]), dim(a[1]), dim(a[2]), 255]
+            };
+            out.copy_from_slice(&pixel);
+        }
+
+        diff
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+fn channel_difference(a: &[u8], b: &[u8]) -> u8 {
+    a.iter().zip(b).map(|(&a, &b)| a.abs_diff(b)).max().unwrap_or(0)
+}
+
+// CIE L*a*b* of an sRGB pixel, D65 white point.
+fn lab(pixel: &[u8]) -> [f64; 3] {
+    let linear = |channel: u8| {
+        let value = f64::from(channel) / 255.0;
+        if value <= 0.04045 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+    };
+    let (r, g, b) = (linear(pixel[0]), linear(pixel[1]), linear(pixel[2]));
+
+    let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / 0.950_47;
+    let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175_0 * b;
+    let z = (0.019_333_9 * r + 0.119_192_0 * g + 0.950_304_1 * b) / 1.088_83;
+
+    let f = |t: f64| if t > 0.008_856 { t.cbrt() } else { 7.787 * t + 16.0 / 116.0 };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+// The CIEDE2000 color difference with unit weighting factors.
+fn delta_e_2000(first: [f64; 3], second: [f64; 3]) -> f64 {
+    let [l1, a1, b1] = first;
+    let [l2, a2, b2] = second;
+
+    let c_mean = ((a1.hypot(b1) + a2.hypot(b2)) / 2.0).powi(7);
+    let g = 0.5 * (1.0 - (c_mean / (c_mean + 25f64.powi(7))).sqrt());
+    let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let hue = |b: f64, a: f64| if a == 0.0 && b == 0.0 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+    let (h1, h2) = (hue(b1, a1), hue(b2, a2));
+
+    let delta_l = l2 - l1;
+    let delta_c = c2 - c1;
+    let delta_h = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else {
+        h2 - h1 + 360.0
+    };
+    let delta_h = 2.0 * (c1 * c2).sqrt() * (delta_h.to_radians() / 2.0).sin();
+
+    let l_mean = (l1 + l2) / 2.0;
+    let c_mean = (c1 + c2) / 2.0;
+    let h_mean = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_mean - 30.0).to_radians().cos() + 0.24 * (2.0 * h_mean).to_radians().cos()
+        + 0.32 * (3.0 * h_mean + 6.0).to_radians().cos() - 0.20 * (4.0 * h_mean - 63.0).to_radians().cos();
+    let s_l = 1.0 + 0.015 * (l_mean - 50.0).powi(2) / (20.0 + (l_mean - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_mean;
+    let s_h = 1.0 + 0.015 * c_mean * t;
+
+    let rotation = 30.0 * (-((h_mean - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (c_mean.powi(7) / (c_mean.powi(7) + 25f64.powi(7))).sqrt();
+    let r_t = -r_c * (2.0 * rotation * PI / 180.0).sin();
+
+    let (l, c, h) = (delta_l / s_l, delta_c / s_c, delta_h / s_h);
+    (l * l + c * c + h * h + r_t * c * h).sqrt()
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consts::GL_COLOR_BUFFER_BIT;
+    use es20::software::SoftwareBackend;
+
+    fn clear(gl: &mut Wrapper, red: f32) -> Result<(), Error> {
+        gl.gl_clear_color(red, 0.5, 0.0, 1.0)?;
+        gl.gl_clear(GL_COLOR_BUFFER_BIT)
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("opengl_es_rs_golden_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn blessed_render_passes_and_a_different_one_fails() {
+        let dir = temp_dir("software");
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(8, 8)));
+        let rect = Rect::new(0, 0, 8, 8);
+
+        let blessed = GoldenTest::new(&dir).bless(true).check(&mut gl, "clear", rect, |gl| clear(gl, 1.0)).unwrap();
+        assert!(blessed.passed && blessed.blessed);
+        assert!(dir.join("clear.png").exists());
+
+        let test = GoldenTest::new(&dir).bless(false);
+        let comparison = test.check(&mut gl, "clear", rect, |gl| clear(gl, 1.0)).unwrap();
+        assert!(comparison.passed && !comparison.blessed);
+        assert_eq!(comparison.statistics.unwrap().failing_pixels, 0);
+
+        let error = test.check(&mut gl, "clear", rect, |gl| clear(gl, 0.0)).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidResult);
+        for artifact in &["actual.png", "expected.png", "diff.png", "txt"] {
+            assert!(dir.join("failures").join(format!("clear.{}", artifact)).exists(), "missing {}", artifact);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn metrics_allow_small_differences() {
+        let dir = temp_dir("metrics");
+        let expected = Image::from_rgba(2, 1, vec![100, 100, 100, 255, 200, 0, 0, 255]).unwrap();
+        let actual = Image::from_rgba(2, 1, vec![102, 100, 100, 255, 200, 0, 0, 255]).unwrap();
+        GoldenTest::new(&dir).bless(true).compare("near", &expected).unwrap();
+
+        let compare = |test: GoldenTest| test.bless(false).compare("near", &actual).unwrap().passed;
+        assert!(!compare(GoldenTest::new(&dir)));
+        assert!(compare(GoldenTest::new(&dir).allowed_failures(1)));
+        assert!(compare(GoldenTest::new(&dir).metric(Metric::Tolerance(2))));
+        assert!(!compare(GoldenTest::new(&dir).metric(Metric::Tolerance(1))));
+        assert!(compare(GoldenTest::new(&dir).metric(Metric::Psnr(40.0))));
+        assert!(compare(GoldenTest::new(&dir).metric(Metric::DeltaE(2.0))));
+
+        let small = Image::new(1, 1);
+        let comparison = GoldenTest::new(&dir).bless(false).compare("near", &small).unwrap();
+        assert!(!comparison.passed && comparison.statistics.is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_golden_is_an_io_error() {
+        let dir = temp_dir("missing");
+        let error = GoldenTest::new(&dir).bless(false).compare("absent", &Image::new(1, 1)).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Io);
+    }
+}
@@ -0,0 +1,329 @@
+use std::fs;
+use std::path::Path;
+
+use types::*;
+use consts::*;
+use enums::{PixelDataType, PixelFormat, StateType};
+use format::FormatInfo;
+use super::wrapper::{Error, ErrorKind, Wrapper};
+
+pub mod png;
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+/// The format `read_image` asks the driver for. The result is converted to RGBA8 either way.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ReadFormat {
+    /// `RGBA`/`UNSIGNED_BYTE`, which every implementation supports.
+    Rgba8,
+    /// `IMPLEMENTATION_COLOR_READ_FORMAT`/`TYPE`, which can be faster, e.g. `RGB`/`UNSIGNED_SHORT_5_6_5`
+    /// on 16 bit surfaces. Falls back to `Rgba8` when the driver reports a pair this crate does
+    /// not know.
+    Preferred,
+    Explicit(PixelFormat, PixelDataType),
+}
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// A rectangle in window coordinates, with the origin in the lower left corner.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Rect {
+        Rect { x, y, width, height }
+    }
+}
+
+/// An RGBA8 image with tightly packed rows, top row first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// A transparent black image.
+    pub fn new(width: u32, height: u32) -> Image {
+        Image { width, height, pixels: vec![0; width as usize * height as usize * 4] }
+    }
+
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Result<Image, Error> {
+        let expected = width as usize * height as usize * 4;
+        if pixels.len() != expected {
+            return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                "a {}x{} RGBA8 image has {} bytes, got {}", width, height, expected, pixels.len())));
+        }
+
+        Ok(Image { width, height, pixels })
+    }
+
+    /// Decodes a PNG, see `png::decode` for the supported variants.
+    pub fn decode_png(data: &[u8]) -> Result<Image, Error> {
+        let (width, height, pixels) = png::decode(data)?;
+        Ok(Image { width, height, pixels })
+    }
+
+    pub fn load_png<P: AsRef<Path>>(path: P) -> Result<Image, Error> {
+        Image::decode_png(&fs::read(path)?)
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let offset = self.offset(x, y);
+        [self.pixels[offset], self.pixels[offset + 1], self.pixels[offset + 2], self.pixels[offset + 3]]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        let offset = self.offset(x, y);
+        self.pixels[offset..offset + 4].copy_from_slice(&rgba);
+    }
+
+    pub fn encode_png(&self) -> Vec<u8> {
+        png::encode(self.width, self.height, &self.pixels)
+    }
+
+    /// Encodes the image as a binary PPM (P6). PPM has no alpha channel, so alpha is dropped.
+    pub fn encode_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        ppm.reserve(self.pixels.len() / 4 * 3);
+        for pixel in self.pixels.chunks(4) {
+            ppm.extend_from_slice(&pixel[..3]);
+        }
+        ppm
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        fs::write(path, self.encode_png())?;
+        Ok(())
+    }
+
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        fs::write(path, self.encode_ppm())?;
+        Ok(())
+    }
+
+    fn offset(&self, x: u32, y: u32) -> usize {
+        assert!(x < self.width && y < self.height, "pixel ({}, {}) is outside the {}x{} image", x, y, self.width,
+                self.height);
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// READING
+// -------------------------------------------------------------------------------------------------
+
+/// Reads `rect` of the bound framebuffer into an RGBA8 image, top row first.
+///
+/// The buffer is sized for the format and the current `GL_PACK_ALIGNMENT`. Packed formats are
+/// expanded to 8 bits per channel, luminance is replicated into red, green and blue, and missing
+/// alpha reads as 255. An empty `rect` gives an empty image without reading.
+pub fn read_image(gl: &mut Wrapper, rect: Rect, format: ReadFormat) -> Result<Image, Error> {
+    let (format, type_) = match format {
+        ReadFormat::Rgba8 => (PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE),
+        ReadFormat::Preferred => preferred_format(gl)?,
+        ReadFormat::Explicit(format, type_) => (format, type_),
+    };
+
+    let info = FormatInfo::for_transfer(format as GLenum, type_ as GLenum).ok_or_else(|| {
+        Error::new(ErrorKind::InvalidArgument, format!("{:?}/{:?} is not a pixel transfer format", format, type_))
+    })?;
+    if !type_.is_8_bit_or_packed() {
+        return Err(Error::new(ErrorKind::Unsupported, format!("{:?} pixels can not be converted to RGBA8", type_)));
+    }
+    if rect.width == 0 || rect.height == 0 {
+        return Ok(Image::new(rect.width, rect.height));
+    }
+
+    let alignment = gl.gl_get_integerv(StateType::PACK_ALIGNMENT)?.max(1) as usize;
+    let (width, height) = (rect.width as usize, rect.height as usize);
+    let mut buffer = vec![0u8; info.image_size(width, height, alignment)];
+
+    gl.gl_read_pixels(rect.x, rect.y, rect.width as i32, rect.height as i32, format, type_, &mut buffer)?;
+
+    let bytes_per_pixel = info.bytes_per_pixel as usize;
+    let stride = (width * bytes_per_pixel).div_ceil(alignment) * alignment;
+    let mut image = Image::new(rect.width, rect.height);
+
+    // GL returns the bottom row first.
+    for (y, row) in image.pixels.chunks_mut(width * 4).enumerate() {
+        let source = &buffer[(height - 1 - y) * stride..];
+        for (x, pixel) in row.chunks_mut(4).enumerate() {
+            let offset = x * bytes_per_pixel;
+            pixel.copy_from_slice(&to_rgba8(format, type_, &source[offset..offset + bytes_per_pixel]));
+        }
+    }
+
+    Ok(image)
+}
+
+fn preferred_format(gl: &mut Wrapper) -> Result<(PixelFormat, PixelDataType), Error> {
+    let format = gl.gl_get_integerv(StateType::IMPLEMENTATION_COLOR_READ_FORMAT)? as GLenum;
+    let type_ = gl.gl_get_integerv(StateType::IMPLEMENTATION_COLOR_READ_TYPE)? as GLenum;
+
+    let format = match format {
+        GL_RGBA => PixelFormat::RGBA,
+        GL_RGB => PixelFormat::RGB,
+        GL_ALPHA => PixelFormat::ALPHA,
+        GL_LUMINANCE => PixelFormat::LUMINANCE,
+        GL_LUMINANCE_ALPHA => PixelFormat::LUMINANCE_ALPHA,
+        _ => return Ok((PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE)),
+    };
+    let type_ = match type_ {
+        GL_UNSIGNED_BYTE => PixelDataType::UNSIGNED_BYTE,
+        GL_UNSIGNED_SHORT_5_6_5 => PixelDataType::UNSIGNED_SHORT_5_6_5,
+        GL_UNSIGNED_SHORT_4_4_4_4 => PixelDataType::UNSIGNED_SHORT_4_4_4_4,
+        GL_UNSIGNED_SHORT_5_5_5_1 => PixelDataType::UNSIGNED_SHORT_5_5_5_1,
+        _ => return Ok((PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE)),
+    };
+
+    if FormatInfo::for_transfer(format as GLenum, type_ as GLenum).is_none() {
+        return Ok((PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE));
+    }
+    Ok((format, type_))
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+fn to_rgba8(format: PixelFormat, type_: PixelDataType, bytes: &[u8]) -> [u8; 4] {
+    let packed = || u16::from_ne_bytes([bytes[0], bytes[1]]);
+
+    match type_ {
+        PixelDataType::UNSIGNED_SHORT_5_6_5 => {
+            let value = packed();
+            [expand(value >> 11, 5), expand(value >> 5, 6), expand(value, 5), 255]
+        }
+        PixelDataType::UNSIGNED_SHORT_4_4_4_4 => {
+            let value = packed();
+            [expand(value >> 12, 4), expand(value >> 8, 4), expand(value >> 4, 4), expand(value, 4)]
+        }
+        PixelDataType::UNSIGNED_SHORT_5_5_5_1 => {
+            let value = packed();
+            [expand(value >> 11, 5), expand(value >> 6, 5), expand(value >> 1, 5), expand(value, 1)]
+        }
+        PixelDataType::UNSIGNED_BYTE => match format {
+            PixelFormat::ALPHA => [0, 0, 0, bytes[0]],
+            PixelFormat::LUMINANCE => [bytes[0], bytes[0], bytes[0], 255],
+            PixelFormat::LUMINANCE_ALPHA => [bytes[0], bytes[0], bytes[0], bytes[1]],
+            PixelFormat::RGB => [bytes[0], bytes[1], bytes[2], 255],
+            _ => [bytes[0], bytes[1], bytes[2], bytes[3]],
+        },
+        // read_image rejects the other types.
+        _ => unreachable!("{:?} pixels can not be converted to RGBA8", type_),
+    }
+}
+
+// Widens the low `bits` bits of `value` to 8 by bit replication, so 0 stays 0 and the maximum
+// becomes 255.
+fn expand(value: u16, bits: u32) -> u8 {
+    let value = u32::from(value) & ((1 << bits) - 1);
+    let mut result = 0;
+    let mut shift = 8i32 - bits as i32;
+    while shift > -(bits as i32) {
+        result |= if shift >= 0 { value << shift } else { value >> -shift };
+        shift -= bits as i32;
+    }
+    result as u8
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enums::{FeatureType, PackParamType};
+    use es20::software::SoftwareBackend;
+
+    // A 3x2 framebuffer with a green bottom row and a red top row.
+    fn two_rows() -> Wrapper {
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(3, 2)));
+        gl.gl_clear_color(1.0, 0.0, 0.0, 1.0).unwrap();
+        gl.gl_clear(GL_COLOR_BUFFER_BIT).unwrap();
+        gl.gl_enable(FeatureType::SCISSOR_TEST).unwrap();
+        gl.gl_scissor(0, 0, 3, 1).unwrap();
+        gl.gl_clear_color(0.0, 1.0, 0.0, 1.0).unwrap();
+        gl.gl_clear(GL_COLOR_BUFFER_BIT).unwrap();
+        gl
+    }
+
+    #[test]
+    fn reads_top_row_first_with_any_pack_alignment() {
+        let mut gl = two_rows();
+
+        for &alignment in &[1, 2, 4, 8] {
+            gl.gl_pixel_storei(PackParamType::PACK_ALIGNMENT, alignment).unwrap();
+            let image = read_image(&mut gl, Rect::new(0, 0, 3, 2), ReadFormat::Rgba8).unwrap();
+            assert_eq!((image.width, image.height), (3, 2));
+            for x in 0..3 {
+                assert_eq!(image.pixel(x, 0), [255, 0, 0, 255]);
+                assert_eq!(image.pixel(x, 1), [0, 255, 0, 255]);
+            }
+        }
+
+        let preferred = read_image(&mut gl, Rect::new(1, 1, 2, 1), ReadFormat::Preferred).unwrap();
+        assert_eq!(preferred.pixels, [255, 0, 0, 255, 255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn empty_rect_gives_an_empty_image() {
+        let mut gl = two_rows();
+
+        assert_eq!(read_image(&mut gl, Rect::new(0, 0, 0, 2), ReadFormat::Rgba8).unwrap(), Image::new(0, 2));
+        assert_eq!(read_image(&mut gl, Rect::new(0, 0, 3, 0), ReadFormat::Rgba8).unwrap(), Image::new(3, 0));
+    }
+
+    #[test]
+    fn rejects_formats_it_can_not_convert() {
+        let mut gl = two_rows();
+        let rect = Rect::new(0, 0, 1, 1);
+
+        let float = ReadFormat::Explicit(PixelFormat::RGBA, PixelDataType::FLOAT);
+        assert_eq!(read_image(&mut gl, rect, float).unwrap_err().kind, ErrorKind::Unsupported);
+        let mismatched = ReadFormat::Explicit(PixelFormat::ALPHA, PixelDataType::UNSIGNED_SHORT_5_6_5);
+        assert_eq!(read_image(&mut gl, rect, mismatched).unwrap_err().kind, ErrorKind::InvalidArgument);
+    }
+
+    #[test]
+    fn converts_packed_and_luminance_pixels() {
+        let packed = |value: u16| value.to_ne_bytes();
+
+        assert_eq!(to_rgba8(PixelFormat::RGB, PixelDataType::UNSIGNED_SHORT_5_6_5, &packed(0xF800)), [255, 0, 0, 255]);
+        assert_eq!(to_rgba8(PixelFormat::RGB, PixelDataType::UNSIGNED_SHORT_5_6_5, &packed(0x07E0)), [0, 255, 0, 255]);
+        assert_eq!(to_rgba8(PixelFormat::RGBA, PixelDataType::UNSIGNED_SHORT_4_4_4_4, &packed(0x1234)),
+                   [0x11, 0x22, 0x33, 0x44]);
+        assert_eq!(to_rgba8(PixelFormat::RGBA, PixelDataType::UNSIGNED_SHORT_5_5_5_1, &packed(0x0001)), [0, 0, 0, 255]);
+        assert_eq!(to_rgba8(PixelFormat::LUMINANCE, PixelDataType::UNSIGNED_BYTE, &[7]), [7, 7, 7, 255]);
+        assert_eq!(to_rgba8(PixelFormat::LUMINANCE_ALPHA, PixelDataType::UNSIGNED_BYTE, &[7, 9]), [7, 7, 7, 9]);
+        assert_eq!(to_rgba8(PixelFormat::ALPHA, PixelDataType::UNSIGNED_BYTE, &[9]), [0, 0, 0, 9]);
+    }
+
+    #[test]
+    fn encodes_ppm_without_alpha() {
+        let image = Image::from_rgba(2, 1, vec![1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        assert_eq!(image.encode_ppm(), b"P6\n2 1\n255\n\x01\x02\x03\x05\x06\x07".to_vec());
+        assert_eq!(Image::from_rgba(2, 1, vec![0; 4]).unwrap_err().kind, ErrorKind::InvalidArgument);
+    }
+
+    #[test]
+    fn encodes_a_png_signature_and_header() {
+        let png = Image::new(3, 2).encode_png();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], [0, 0, 0, 3, 0, 0, 0, 2]);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+    }
+}
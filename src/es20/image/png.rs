@@ -0,0 +1,648 @@
+use super::super::wrapper::{Error, ErrorKind};
+
+// -------------------------------------------------------------------------------------------------
+// TABLES
+// -------------------------------------------------------------------------------------------------
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+// Base match lengths of the deflate length symbols 257..285 and their extra bits.
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+
+// Base distances of the deflate distance symbols and their extra bits.
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097,
+    6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+// The order in which dynamic blocks store the code lengths of the code length alphabet.
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+const WINDOW: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+// How many earlier positions with the same hash are tried per match. Trades speed for size.
+const MAX_CHAIN: usize = 64;
+
+// -------------------------------------------------------------------------------------------------
+// ENCODING
+// -------------------------------------------------------------------------------------------------
+
+/// Encodes tightly packed, top-down RGBA8 rows as an 8 bit RGBA PNG.
+pub fn encode(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let stride = width as usize * 4;
+    let mut filtered = Vec::with_capacity((stride + 1) * height as usize);
+
+    let mut previous = vec![0u8; stride];
+    for row in rgba.chunks(stride.max(1)).take(height as usize) {
+        filter_row(row, &previous, &mut filtered);
+        previous.copy_from_slice(row);
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, RGBA, deflate, adaptive filtering, no interlacing.
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib(&filtered));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+// Appends the filter type and the filtered row, picking the filter with the smallest sum of
+// absolute differences, the usual heuristic for photographic and rendered content.
+fn filter_row(row: &[u8], previous: &[u8], out: &mut Vec<u8>) {
+    let mut best: Option<(u8, Vec<u8>, u64)> = None;
+
+    for filter in 0..5u8 {
+        let filtered: Vec<u8> = (0..row.len()).map(|i| {
+            let left = if i >= 4 { row[i - 4] } else { 0 };
+            let up = previous[i];
+            let up_left = if i >= 4 { previous[i - 4] } else { 0 };
+
+            row[i].wrapping_sub(match filter {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((u16::from(left) + u16::from(up)) / 2) as u8,
+                _ => paeth(left, up, up_left),
+            })
+        }).collect();
+
+        let cost = filtered.iter().map(|&value| u64::from((value as i8).unsigned_abs())).sum();
+        if best.as_ref().is_none_or(|&(_, _, best_cost)| cost < best_cost) {
+            best = Some((filter, filtered, cost));
+        }
+    }
+
+    if let Some((filter, filtered, _)) = best {
+        out.push(filter);
+        out.extend_from_slice(&filtered);
+    }
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = i16::from(left) + i16::from(up) - i16::from(up_left);
+    let distance_left = (estimate - i16::from(left)).abs();
+    let distance_up = (estimate - i16::from(up)).abs();
+    let distance_up_left = (estimate - i16::from(up_left)).abs();
+
+    if distance_left <= distance_up && distance_left <= distance_up_left {
+        left
+    } else if distance_up <= distance_up_left {
+        up
+    } else {
+        up_left
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+// A zlib stream holding one deflate block with the fixed Huffman codes.
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+    // 32K window, no dictionary, default compression level.
+    bits.bytes.extend_from_slice(&[0x78, 0x9C]);
+
+    bits.write(1, 1);
+    bits.write(1, 2);
+
+    let mut head = vec![usize::MAX; 1 << 15];
+    let mut chain = vec![usize::MAX; data.len()];
+    let mut position = 0;
+
+    while position < data.len() {
+        let (length, distance) = longest_match(data, position, &head, &chain);
+
+        let advance = if length >= MIN_MATCH {
+            bits.write_length(length);
+            bits.write_distance(distance);
+            length
+        } else {
+            bits.write_literal(u16::from(data[position]));
+            1
+        };
+
+        let end = (position + advance).min(data.len().saturating_sub(MIN_MATCH - 1));
+        for (index, previous) in chain.iter_mut().enumerate().take(end).skip(position) {
+            let hash = hash(data, index);
+            *previous = head[hash];
+            head[hash] = index;
+        }
+        position += advance;
+    }
+
+    bits.write_literal(256);
+    bits.flush();
+
+    let mut stream = bits.bytes;
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+fn longest_match(data: &[u8], position: usize, head: &[usize], chain: &[usize]) -> (usize, usize) {
+    if position + MIN_MATCH > data.len() {
+        return (0, 0);
+    }
+
+    let limit = (data.len() - position).min(MAX_MATCH);
+    let mut best = (0, 0);
+    let mut candidate = head[hash(data, position)];
+
+    for _ in 0..MAX_CHAIN {
+        if candidate == usize::MAX || position - candidate > WINDOW {
+            break;
+        }
+
+        let length = data[candidate..].iter().zip(&data[position..position + limit])
+            .take_while(|&(a, b)| a == b)
+            .count();
+        if length > best.0 {
+            best = (length, position - candidate);
+            if length == limit {
+                break;
+            }
+        }
+        candidate = chain[candidate];
+    }
+
+    best
+}
+
+fn hash(data: &[u8], position: usize) -> usize {
+    let value = u32::from(data[position]) << 16 | u32::from(data[position + 1]) << 8 | u32::from(data[position + 2]);
+    (value.wrapping_mul(2_654_435_761) >> 17) as usize
+}
+
+// -------------------------------------------------------------------------------------------------
+// DECODING
+// -------------------------------------------------------------------------------------------------
+
+/// Decodes a non-interlaced 8 bit PNG into tightly packed, top-down RGBA8 rows.
+///
+/// Grayscale, RGB, palette, grayscale with alpha and RGBA images are supported; other bit depths
+/// and interlaced images are `Unsupported`.
+pub fn decode(data: &[u8]) -> Result<(u32, u32, Vec<u8>), Error> {
+    if data.len() < 8 || data[..8] != SIGNATURE {
+        return Err(format_error("not a PNG file"));
+    }
+
+    let mut header = None;
+    let mut palette: &[u8] = &[];
+    let mut transparency: &[u8] = &[];
+    let mut compressed = Vec::new();
+
+    let mut position = 8;
+    while position + 12 <= data.len() {
+        let length = be_u32(&data[position..]) as usize;
+        let kind = &data[position + 4..position + 8];
+        let body = data.get(position + 8..position + 8 + length).ok_or_else(|| format_error("truncated chunk"))?;
+
+        match kind {
+            b"IHDR" if body.len() >= 13 => header = Some((be_u32(body), be_u32(&body[4..]), body[8], body[9], body[12])),
+            b"PLTE" => palette = body,
+            b"tRNS" => transparency = body,
+            b"IDAT" => compressed.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        position += length + 12;
+    }
+
+    let (width, height, depth, color_type, interlace) = header.ok_or_else(|| format_error("missing IHDR"))?;
+    let channels = match color_type {
+        0 | 3 => 1,
+        2 => 3,
+        4 => 2,
+        6 => 4,
+        _ => return Err(format_error(format!("invalid color type {}", color_type))),
+    };
+    if depth != 8 || interlace != 0 {
+        return Err(Error::new(ErrorKind::Unsupported, format!(
+            "only non-interlaced 8 bit PNGs are supported, got depth {} and interlace method {}", depth, interlace)));
+    }
+
+    let filtered = inflate_zlib(&compressed)?;
+    let stride = width as usize * channels;
+    // Checked, so that a header claiming a huge image fails here instead of overflowing.
+    let needed = (stride + 1).checked_mul(height as usize);
+    if needed.is_none_or(|needed| filtered.len() < needed) {
+        return Err(format_error("image data is too short"));
+    }
+
+    let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+    let mut previous = vec![0u8; stride];
+    let mut row = vec![0u8; stride];
+
+    for line in filtered.chunks(stride + 1).take(height as usize) {
+        unfilter_row(line[0], &line[1..], &previous, channels, &mut row)?;
+
+        for pixel in row.chunks(channels) {
+            rgba.extend_from_slice(&match color_type {
+                0 => [pixel[0], pixel[0], pixel[0], 255],
+                2 => [pixel[0], pixel[1], pixel[2], 255],
+                3 => {
+                    let index = pixel[0] as usize;
+                    let color = palette.get(index * 3..index * 3 + 3)
+                        .ok_or_else(|| format_error(format!("palette index {} out of range", index)))?;
+                    [color[0], color[1], color[2], transparency.get(index).cloned().unwrap_or(255)]
+                }
+                4 => [pixel[0], pixel[0], pixel[0], pixel[1]],
+                _ => [pixel[0], pixel[1], pixel[2], pixel[3]],
+            });
+        }
+
+        previous.copy_from_slice(&row);
+    }
+
+    Ok((width, height, rgba))
+}
+
+fn unfilter_row(filter: u8, line: &[u8], previous: &[u8], bytes_per_pixel: usize, row: &mut [u8])
+                -> Result<(), Error> {
+    for i in 0..row.len() {
+        let left = if i >= bytes_per_pixel { row[i - bytes_per_pixel] } else { 0 };
+        let up = previous[i];
+        let up_left = if i >= bytes_per_pixel { previous[i - bytes_per_pixel] } else { 0 };
+
+        row[i] = line[i].wrapping_add(match filter {
+            0 => 0,
+            1 => left,
+            2 => up,
+            3 => ((u16::from(left) + u16::from(up)) / 2) as u8,
+            4 => paeth(left, up, up_left),
+            _ => return Err(format_error(format!("invalid filter type {}", filter))),
+        });
+    }
+    Ok(())
+}
+
+fn inflate_zlib(data: &[u8]) -> Result<Vec<u8>, Error> {
+    if data.len() < 6 || data[0] & 0x0F != 8 || (u16::from(data[0]) << 8 | u16::from(data[1])) % 31 != 0 {
+        return Err(format_error("invalid zlib header"));
+    }
+    if data[1] & 0x20 != 0 {
+        return Err(format_error("preset zlib dictionaries are not supported"));
+    }
+
+    let output = inflate(&data[2..])?;
+    if adler32(&output) != be_u32(&data[data.len() - 4..]) {
+        return Err(format_error("zlib checksum mismatch"));
+    }
+    Ok(output)
+}
+
+fn inflate(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut bits = BitReader { data, position: 0 };
+    let mut output = Vec::new();
+
+    loop {
+        let last = bits.read(1)? == 1;
+
+        match bits.read(2)? {
+            0 => {
+                bits.position = bits.position.div_ceil(8) * 8;
+                let length = bits.read(16)? as usize;
+                let _complement = bits.read(16)?;
+                let start = bits.position / 8;
+                let stored = data.get(start..start + length).ok_or_else(|| format_error("truncated stored block"))?;
+                output.extend_from_slice(stored);
+                bits.position += length * 8;
+            }
+            1 => {
+                let mut lengths = [0u8; 288 + 32];
+                for (symbol, length) in lengths.iter_mut().enumerate() {
+                    *length = match symbol {
+                        0..=143 => 8,
+                        144..=255 => 9,
+                        256..=279 => 7,
+                        280..=287 => 8,
+                        _ => 5,
+                    };
+                }
+                let literals = Huffman::new(&lengths[..288]);
+                let distances = Huffman::new(&lengths[288..]);
+                inflate_block(&mut bits, &literals, &distances, &mut output)?;
+            }
+            2 => {
+                let (literals, distances) = read_dynamic_codes(&mut bits)?;
+                inflate_block(&mut bits, &literals, &distances, &mut output)?;
+            }
+            _ => return Err(format_error("invalid deflate block type")),
+        }
+
+        if last {
+            return Ok(output);
+        }
+    }
+}
+
+fn read_dynamic_codes(bits: &mut BitReader) -> Result<(Huffman, Huffman), Error> {
+    let literal_count = bits.read(5)? as usize + 257;
+    let distance_count = bits.read(5)? as usize + 1;
+    let code_length_count = bits.read(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[index] = bits.read(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&code_lengths);
+
+    let mut lengths = vec![0u8; literal_count + distance_count];
+    let mut index = 0;
+    while index < lengths.len() {
+        let (value, repeat) = match code_lengths.decode(bits)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 if index > 0 => (lengths[index - 1], 3 + bits.read(2)? as usize),
+            17 => (0, 3 + bits.read(3)? as usize),
+            18 => (0, 11 + bits.read(7)? as usize),
+            _ => return Err(format_error("invalid code length repeat")),
+        };
+
+        let end = index + repeat;
+        if end > lengths.len() {
+            return Err(format_error("code lengths overflow the alphabets"));
+        }
+        for length in &mut lengths[index..end] {
+            *length = value;
+        }
+        index = end;
+    }
+
+    Ok((Huffman::new(&lengths[..literal_count]), Huffman::new(&lengths[literal_count..])))
+}
+
+fn inflate_block(bits: &mut BitReader, literals: &Huffman, distances: &Huffman, output: &mut Vec<u8>)
+                 -> Result<(), Error> {
+    loop {
+        let symbol = literals.decode(bits)? as usize;
+
+        if symbol < 256 {
+            output.push(symbol as u8);
+            continue;
+        }
+        if symbol == 256 {
+            return Ok(());
+        }
+
+        let index = symbol - 257;
+        if index >= LENGTH_BASES.len() {
+            return Err(format_error("invalid length symbol"));
+        }
+        let length = usize::from(LENGTH_BASES[index]) + bits.read(u32::from(LENGTH_EXTRA[index]))? as usize;
+
+        let index = distances.decode(bits)? as usize;
+        if index >= DISTANCE_BASES.len() {
+            return Err(format_error("invalid distance symbol"));
+        }
+        let distance = usize::from(DISTANCE_BASES[index]) + bits.read(u32::from(DISTANCE_EXTRA[index]))? as usize;
+        if distance > output.len() {
+            return Err(format_error("distance reaches before the start of the stream"));
+        }
+
+        // Matches may overlap the bytes they produce, so copy one at a time.
+        let start = output.len() - distance;
+        for offset in 0..length {
+            let byte = output[start + offset];
+            output.push(byte);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+// Deflate packs bits from the least significant end; Huffman codes go in most significant bit
+// first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, count: u32) {
+        self.buffer |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    fn write_code(&mut self, code: u32, length: u32) {
+        self.write(code.reverse_bits() >> (32 - length), length);
+    }
+
+    fn write_literal(&mut self, symbol: u16) {
+        let symbol = u32::from(symbol);
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol, 8),
+            144..=255 => self.write_code(0x190 + symbol - 144, 9),
+            256..=279 => self.write_code(symbol - 256, 7),
+            _ => self.write_code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    fn write_length(&mut self, length: usize) {
+        let index = LENGTH_BASES.iter().rposition(|&base| usize::from(base) <= length).unwrap_or(0);
+        self.write_literal(257 + index as u16);
+        self.write((length - usize::from(LENGTH_BASES[index])) as u32, u32::from(LENGTH_EXTRA[index]));
+    }
+
+    fn write_distance(&mut self, distance: usize) {
+        let index = DISTANCE_BASES.iter().rposition(|&base| usize::from(base) <= distance).unwrap_or(0);
+        self.write_code(index as u32, 5);
+        self.write((distance - usize::from(DISTANCE_BASES[index])) as u32, u32::from(DISTANCE_EXTRA[index]));
+    }
+
+    fn flush(&mut self) {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer = 0;
+            self.count = 0;
+        }
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn read(&mut self, count: u32) -> Result<u32, Error> {
+        let mut value = 0;
+        for index in 0..count {
+            let byte = *self.data.get(self.position / 8).ok_or_else(|| format_error("truncated deflate stream"))?;
+            value |= u32::from((byte >> (self.position % 8)) & 1) << index;
+            self.position += 1;
+        }
+        Ok(value)
+    }
+}
+
+// A canonical Huffman code, decoded one bit at a time.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Huffman {
+        let mut counts = [0u16; 16];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; 16];
+        for length in 1..15 {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+
+        Huffman { counts, symbols }
+    }
+
+    fn decode(&self, bits: &mut BitReader) -> Result<u16, Error> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+
+        for &count in &self.counts[1..] {
+            code |= bits.read(1)? as i32;
+            let count = i32::from(count);
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err(format_error("invalid Huffman code"))
+    }
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn format_error<S: Into<String>>(message: S) -> Error {
+    Error::new(ErrorKind::Format, message)
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    b << 16 | a
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(width: u32, height: u32, rgba: &[u8]) {
+        let (decoded_width, decoded_height, decoded) = decode(&encode(width, height, rgba)).unwrap();
+        assert_eq!((decoded_width, decoded_height), (width, height));
+        assert_eq!(decoded, rgba);
+    }
+
+    #[test]
+    fn round_trips_varied_pixels() {
+        // Gradients and noise, so that every row filter gets picked somewhere.
+        let mut rgba = Vec::new();
+        for y in 0..13u32 {
+            for x in 0..17u32 {
+                let noise = (x * 7919 + y * 104_729) % 251;
+                rgba.extend(&[(x * 15) as u8, (y * 19) as u8, noise as u8, 255 - (x * y) as u8]);
+            }
+        }
+        round_trip(17, 13, &rgba);
+    }
+
+    #[test]
+    fn round_trips_repetitive_and_empty_images() {
+        let rgba: Vec<u8> = [10, 20, 30, 255].iter().cycle().take(300 * 40 * 4).cloned().collect();
+        let png = encode(300, 40, &rgba);
+        assert!(png.len() < rgba.len() / 20);
+        round_trip(300, 40, &rgba);
+
+        round_trip(0, 0, &[]);
+    }
+
+    #[test]
+    fn rejects_truncated_and_foreign_files() {
+        let png = encode(4, 4, &[128; 64]);
+        assert_eq!(decode(&png[..png.len() - 20]).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(decode(&png[..20]).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(decode(b"GIF89a").unwrap_err().kind, ErrorKind::Format);
+    }
+
+    #[test]
+    fn rejects_oversized_header() {
+        let mut header = Vec::new();
+        header.extend_from_slice(&u32::MAX.to_be_bytes());
+        header.extend_from_slice(&u32::MAX.to_be_bytes());
+        header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+        let mut png = SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &zlib(&[0; 64]));
+        write_chunk(&mut png, b"IEND", &[]);
+        assert_eq!(decode(&png).unwrap_err().kind, ErrorKind::Format);
+    }
+
+    #[test]
+    fn checksums_match_reference_values() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+}
@@ -0,0 +1,448 @@
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use types::*;
+use consts::*;
+use super::backend::Backend;
+use super::call::{Call, Value};
+use super::wrapper::{Error, Interceptor};
+
+// -------------------------------------------------------------------------------------------------
+// LOGGER
+// -------------------------------------------------------------------------------------------------
+
+/// Writes every call made through a `Wrapper` as one line of text, in the spirit of apitrace's
+/// `dump` output:
+///
+/// ```text
+/// 12 glBindTexture(GL_TEXTURE_2D, 7) // 3 us
+/// 13 glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, GL_CLAMP) // 1 us, GL_INVALID_ENUM
+/// 14 glCreateProgram() = 3 // 25 us
+/// // end of frame 0
+/// ```
+///
+/// The leading number is the index of the call since the logger was added, counting calls that
+/// were filtered out or rate limited. The time is measured around the backend call.
+///
+/// Unless disabled with `with_error_check(false)`, `glGetError` is issued after every logged call
+/// and a pending error is appended to the line. The logger keeps the first error it cleared this
+/// way until `Wrapper::gl_get_error` takes it, so the application still sees it.
+///
+/// If writing fails, logging stops and the error is returned from the next intercepted call.
+pub struct CallLogger<W: Write> {
+    writer: W,
+    filters: Vec<String>,
+    check_errors: bool,
+    rate_limit: Option<(usize, Duration)>,
+    window_start: Instant,
+    window_lines: usize,
+    suppressed: usize,
+    index: u64,
+    started: Option<Instant>,
+    // The first error read by `glGetError` and not yet handed back to the application.
+    error: Option<GLenum>,
+    failed: Option<Error>,
+    stopped: bool,
+}
+
+impl<W: Write> CallLogger<W> {
+    pub fn new(writer: W) -> CallLogger<W> {
+        CallLogger {
+            writer,
+            filters: Vec::new(),
+            check_errors: true,
+            rate_limit: None,
+            window_start: Instant::now(),
+            window_lines: 0,
+            suppressed: 0,
+            index: 0,
+            started: None,
+            error: None,
+            failed: None,
+            stopped: false,
+        }
+    }
+
+    /// Only logs calls whose name matches `pattern`, where `*` matches any run of characters and
+    /// `?` a single one, e.g. `"glUniform*"`. Several filters are combined with "or"; without any
+    /// filter every call is logged.
+    pub fn with_filter(mut self, pattern: &str) -> CallLogger<W> {
+        self.filters.push(pattern.to_string());
+        self
+    }
+
+    /// Whether to call `glGetError` after each logged call, on by default. Calls that are filtered
+    /// out or rate limited are never checked, so their errors stay pending, but the next logged
+    /// call then reports them as its own.
+    pub fn with_error_check(mut self, check_errors: bool) -> CallLogger<W> {
+        self.check_errors = check_errors;
+        self
+    }
+
+    /// Writes at most `lines` calls per `window`. Calls over the limit are counted and reported
+    /// in a single line once the next window starts or the frame ends.
+    pub fn with_rate_limit(mut self, lines: usize, window: Duration) -> CallLogger<W> {
+        self.rate_limit = Some((lines, window));
+        self
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.filters.is_empty() || self.filters.iter().any(|filter| glob_match(filter.as_bytes(), name.as_bytes()))
+    }
+
+    // Returns whether another line may be written in the current window.
+    fn admit(&mut self) -> bool {
+        let (lines, window) = match self.rate_limit {
+            Some(limit) => limit,
+            None => return true,
+        };
+
+        let now = Instant::now();
+        if now.duration_since(self.window_start) >= window {
+            self.window_start = now;
+            self.window_lines = 0;
+            self.report_suppressed();
+        }
+
+        if self.window_lines < lines {
+            self.window_lines += 1;
+            true
+        } else {
+            self.suppressed += 1;
+            false
+        }
+    }
+
+    fn report_suppressed(&mut self) {
+        if self.suppressed > 0 {
+            let line = format!("// {} calls suppressed by rate limit\n", self.suppressed);
+            self.suppressed = 0;
+            self.write(&line);
+        }
+    }
+
+    fn write(&mut self, line: &str) {
+        if self.stopped {
+            return;
+        }
+
+        if let Err(error) = self.writer.write_all(line.as_bytes()) {
+            self.failed = Some(Error::from(error));
+            self.stopped = true;
+        }
+    }
+}
+
+impl<W: Write> Interceptor for CallLogger<W> {
+    fn intercept(&mut self, _call: &Call, _backend: &mut dyn Backend) -> Result<(), Error> {
+        if let Some(error) = self.failed.take() {
+            return Err(error);
+        }
+
+        self.started = Some(Instant::now());
+        Ok(())
+    }
+
+    fn completed(&mut self, call: &Call, backend: &mut dyn Backend) {
+        let elapsed = self.started.take().map(|started| started.elapsed());
+        let index = self.index;
+        self.index += 1;
+
+        if self.stopped || !self.matches(call.name) || !self.admit() {
+            return;
+        }
+
+        let mut line = format!("{} {}", index, format_call(call));
+
+        if let Some(elapsed) = elapsed {
+            line.push_str(&format!(" // {} us", elapsed.as_micros()));
+        }
+
+        // Only logged calls are checked, so filtering also limits whose errors are taken over.
+        if self.check_errors && call.name != "glGetError" {
+            let error = unsafe { backend.glGetError() };
+            if error != GL_NO_ERROR {
+                // Like GL, keep the first error until it is read and drop the later ones.
+                self.error = self.error.or(Some(error));
+                line.push_str(if elapsed.is_some() { ", " } else { " // " });
+                line.push_str(&format_enum(error, None));
+            }
+        }
+
+        line.push('\n');
+        self.write(&line);
+    }
+
+    fn end_frame(&mut self, frame: u64) {
+        self.report_suppressed();
+        self.write(&format!("// end of frame {}\n", frame));
+
+        if !self.stopped {
+            if let Err(error) = self.writer.flush() {
+                self.failed = Some(Error::from(error));
+                self.stopped = true;
+            }
+        }
+    }
+
+    fn take_error(&mut self) -> Option<GLenum> {
+        self.error.take()
+    }
+}
+
+impl<W: Write> Drop for CallLogger<W> {
+    fn drop(&mut self) {
+        self.report_suppressed();
+        let _ = self.writer.flush();
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// FORMATTING
+// -------------------------------------------------------------------------------------------------
+
+/// Formats `call` as `glName(arg, ...)`, followed by ` = ret` if the call returned something.
+pub fn format_call(call: &Call) -> String {
+    let args: Vec<String> = call.args.iter()
+        .enumerate()
+        .map(|(index, arg)| format_value(arg, Some((call.name, index))))
+        .collect();
+
+    let mut text = format!("{}({})", call.name, args.join(", "));
+
+    if call.ret != Value::Void {
+        text.push_str(" = ");
+        text.push_str(&format_value(&call.ret, Some((call.name, usize::MAX))));
+    }
+
+    text
+}
+
+// `context` is the call name and argument index (`usize::MAX` for the return value), used to
+// name enums below 0x100 whose meaning depends on where they appear.
+fn format_value(value: &Value, context: Option<(&str, usize)>) -> String {
+    match *value {
+        Value::Void => "void".to_string(),
+        Value::Bool(value) => if value { "GL_TRUE" } else { "GL_FALSE" }.to_string(),
+        Value::Int(value) => value.to_string(),
+        Value::Uint(value) => value.to_string(),
+        Value::Enum(value) => format_enum(value, context),
+        Value::Bitfield(value) => format_bitfield(value),
+        Value::Float(value) => format!("{:?}", value),
+        Value::Name(_, name) => name.to_string(),
+        Value::Names(_, ref names) => format_list(names),
+        Value::Location(location) => location.to_string(),
+        Value::Str(ref string) => format!("{:?}", string),
+        Value::Bytes(ref bytes) => format!("blob({})", bytes.len()),
+        Value::Ints(ref values) => format_list(values),
+        Value::Floats(ref values) => format_list(values),
+        Value::Offset(offset) => format!("0x{:x}", offset),
+    }
+}
+
+fn format_enum(value: GLenum, context: Option<(&str, usize)>) -> String {
+    if let Some(name) = enum_name(value) {
+        return name.to_string();
+    }
+
+    let name = match context {
+        Some(("glDrawArrays", 0)) | Some(("glDrawElements", 0)) => match value {
+            GL_POINTS => Some("GL_POINTS"),
+            GL_LINES => Some("GL_LINES"),
+            GL_LINE_LOOP => Some("GL_LINE_LOOP"),
+            GL_LINE_STRIP => Some("GL_LINE_STRIP"),
+            GL_TRIANGLES => Some("GL_TRIANGLES"),
+            GL_TRIANGLE_STRIP => Some("GL_TRIANGLE_STRIP"),
+            GL_TRIANGLE_FAN => Some("GL_TRIANGLE_FAN"),
+            _ => None,
+        },
+        Some(("glGetError", _)) | None => match value {
+            GL_NO_ERROR => Some("GL_NO_ERROR"),
+            _ => None,
+        },
+        Some(_) => match value {
+            GL_ZERO => Some("GL_ZERO"),
+            GL_ONE => Some("GL_ONE"),
+            _ => None,
+        },
+    };
+
+    match name {
+        Some(name) => name.to_string(),
+        None => format!("0x{:x}", value),
+    }
+}
+
+fn format_bitfield(value: GLbitfield) -> String {
+    let bits = [
+        (GL_COLOR_BUFFER_BIT, "GL_COLOR_BUFFER_BIT"),
+        (GL_DEPTH_BUFFER_BIT, "GL_DEPTH_BUFFER_BIT"),
+        (GL_STENCIL_BUFFER_BIT, "GL_STENCIL_BUFFER_BIT"),
+    ];
+
+    let mut names = Vec::new();
+    let mut rest = value;
+
+    for &(bit, name) in bits.iter() {
+        if rest & bit != 0 {
+            names.push(name.to_string());
+            rest &= !bit;
+        }
+    }
+
+    if rest != 0 || names.is_empty() {
+        names.push(format!("0x{:x}", rest));
+    }
+
+    names.join(" | ")
+}
+
+fn format_list<T: ::std::fmt::Debug>(values: &[T]) -> String {
+    let items: Vec<String> = values.iter().map(|value| format!("{:?}", value)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+// Matches `*` and `?` wildcards, backtracking to the last `*` on a mismatch.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::io;
+    use std::rc::Rc;
+
+    use enums::ErrorType;
+    use super::super::call::ObjectKind;
+    use super::super::software::SoftwareBackend;
+    use super::super::wrapper::Wrapper;
+    use super::*;
+
+    // A writer the test can still read after handing it to a `CallLogger`.
+    #[derive(Clone, Default)]
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(bytes)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Shared {
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8(self.0.borrow().clone()).unwrap().lines().map(str::to_string).collect()
+        }
+    }
+
+    fn logged<F: FnOnce(&mut Wrapper)>(logger: CallLogger<Shared>, log: &Shared, calls: F) -> Vec<String> {
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(1, 1)));
+        gl.add_interceptor(Box::new(logger));
+        calls(&mut gl);
+        drop(gl.take_interceptors());
+        log.lines()
+    }
+
+    #[test]
+    fn glob_matches_wildcards() {
+        assert!(glob_match(b"glUniform*", b"glUniform4fv"));
+        assert!(glob_match(b"glUniform?f", b"glUniform1f"));
+        assert!(glob_match(b"*Texture*", b"glBindTexture"));
+        assert!(glob_match(b"*", b""));
+        assert!(!glob_match(b"glUniform?f", b"glUniform1fv"));
+        assert!(!glob_match(b"glDraw*s", b"glDrawElements2"));
+        assert!(!glob_match(b"", b"glClear"));
+    }
+
+    #[test]
+    fn formats_calls() {
+        let texture = Value::Name(ObjectKind::Texture, 7);
+        let mut call = Call::new("glBindTexture", vec![Value::Enum(GL_TEXTURE_2D), texture]);
+        assert_eq!(format_call(&call), "glBindTexture(GL_TEXTURE_2D, 7)");
+
+        call = Call::new("glDrawArrays", vec![Value::Enum(GL_TRIANGLES), Value::Int(0), Value::Int(3)]);
+        assert_eq!(format_call(&call), "glDrawArrays(GL_TRIANGLES, 0, 3)");
+
+        call = Call::new("glCreateProgram", Vec::new());
+        call.ret = Value::Name(ObjectKind::Program, 3);
+        assert_eq!(format_call(&call), "glCreateProgram() = 3");
+
+        call = Call::new("glShaderSource", vec![Value::Str("void main() {}".to_string()), Value::Bytes(vec![0; 4])]);
+        assert_eq!(format_call(&call), "glShaderSource(\"void main() {}\", blob(4))");
+    }
+
+    #[test]
+    fn formats_bitfields() {
+        assert_eq!(format_bitfield(GL_COLOR_BUFFER_BIT | GL_DEPTH_BUFFER_BIT),
+                   "GL_COLOR_BUFFER_BIT | GL_DEPTH_BUFFER_BIT");
+        assert_eq!(format_bitfield(GL_STENCIL_BUFFER_BIT | 0x1), "GL_STENCIL_BUFFER_BIT | 0x1");
+        assert_eq!(format_bitfield(0), "0x0");
+    }
+
+    #[test]
+    fn filters_and_rate_limits_lines() {
+        let log = Shared::default();
+        let logger = CallLogger::new(log.clone()).with_filter("glClear*").with_rate_limit(2, Duration::from_secs(3600));
+        let lines = logged(logger, &log, |gl| {
+            for _ in 0..3 {
+                gl.gl_clear(GL_COLOR_BUFFER_BIT).unwrap();
+                gl.gl_clear_color(0.0, 0.0, 0.0, 1.0).unwrap();
+                gl.gl_flush().unwrap();
+            }
+            gl.end_frame();
+        });
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("0 glClear(GL_COLOR_BUFFER_BIT) // "));
+        assert!(lines[1].starts_with("1 glClearColor(0.0, 0.0, 0.0, 1.0) // "));
+        assert_eq!(lines[2], "// 4 calls suppressed by rate limit");
+        assert_eq!(lines[3], "// end of frame 0");
+    }
+
+    #[test]
+    fn checked_errors_still_reach_the_application() {
+        let log = Shared::default();
+        let lines = logged(CallLogger::new(log.clone()), &log, |gl| {
+            gl.gl_clear(0x1).unwrap();
+            gl.gl_clear(0x2).unwrap();
+            assert_eq!(gl.gl_get_error(), ErrorType::INVALID_VALUE);
+            assert_eq!(gl.gl_get_error(), ErrorType::NO_ERROR);
+        });
+
+        assert!(lines[0].ends_with(", GL_INVALID_VALUE"));
+        assert!(lines[1].ends_with(", GL_INVALID_VALUE"));
+        assert!(lines[2].starts_with("2 glGetError() = GL_INVALID_VALUE // "));
+    }
+}
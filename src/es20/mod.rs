@@ -1,4 +1,18 @@
 use super::*;
 
+pub mod backend;
+pub mod call;
+pub mod client_array;
 pub mod ffi;
-pub mod wrapper;
\ No newline at end of file
+pub mod framebuffer;
+pub mod glsl;
+pub mod golden;
+pub mod image;
+pub mod logger;
+pub mod shader;
+pub mod software;
+pub mod texture;
+pub mod trace;
+pub mod validation;
+pub mod vertex_array;
+pub mod wrapper;
@@ -0,0 +1,454 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+use enums::{ShaderType, StateType};
+use super::super::wrapper::{Error, Wrapper};
+use super::preprocess::{self, DirectoryProvider, FileProvider, Options};
+use super::{compile_shader, link_program};
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+/// A uniform value, by the `gl_uniform*v` call that sets it. Arrays hold all elements one after
+/// another; samplers are `Int`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UniformValue {
+    Float(Vec<f32>),
+    Vec2(Vec<f32>),
+    Vec3(Vec<f32>),
+    Vec4(Vec<f32>),
+    Int(Vec<i32>),
+    IVec2(Vec<i32>),
+    IVec3(Vec<i32>),
+    IVec4(Vec<i32>),
+    Mat2(Vec<f32>),
+    Mat3(Vec<f32>),
+    Mat4(Vec<f32>),
+}
+
+impl UniformValue {
+    /// Sets the uniform at `location` of the current program.
+    pub fn apply(&self, gl: &mut Wrapper, location: i32) -> Result<(), Error> {
+        match *self {
+            UniformValue::Float(ref values) => gl.gl_uniform1fv(location, values),
+            UniformValue::Vec2(ref values) => gl.gl_uniform2fv(location, values),
+            UniformValue::Vec3(ref values) => gl.gl_uniform3fv(location, values),
+            UniformValue::Vec4(ref values) => gl.gl_uniform4fv(location, values),
+            UniformValue::Int(ref values) => gl.gl_uniform1iv(location, values),
+            UniformValue::IVec2(ref values) => gl.gl_uniform2iv(location, values),
+            UniformValue::IVec3(ref values) => gl.gl_uniform3iv(location, values),
+            UniformValue::IVec4(ref values) => gl.gl_uniform4iv(location, values),
+            UniformValue::Mat2(ref values) => gl.gl_uniform_matrix2fv(location, false, values),
+            UniformValue::Mat3(ref values) => gl.gl_uniform_matrix3fv(location, false, values),
+            UniformValue::Mat4(ref values) => gl.gl_uniform_matrix4fv(location, false, values),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// Identifies a program of a `HotReload`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ProgramHandle(usize);
+
+/// The outcome of rebuilding a program after its files changed.
+#[derive(Clone, Debug)]
+pub struct Reload {
+    pub handle: ProgramHandle,
+    /// The new program, or the error with the info log. After an error the previous program stays
+    /// in use.
+    pub result: Result<u32, Error>,
+}
+
+// A file as it was when last looked at: its modification time and size, `None` if it could not
+// be read. The size catches edits within the resolution of the modification time.
+type Stamp = Option<(SystemTime, u64)>;
+
+// A program and what it was built from.
+struct Watched {
+    // Names of the vertex and the fragment shader file.
+    stages: [String; 2],
+    attributes: Vec<(u32, String)>,
+    program: u32,
+    // Every file the last successful build read, includes too, and those of failed builds since.
+    files: Vec<String>,
+    // In the order they were first set, so that they are applied again in that order.
+    uniforms: Vec<(String, UniformValue)>,
+}
+
+/// Rebuilds programs while their shader files are being edited, for development.
+///
+/// Files are named relative to a root directory and go through `preprocess::preprocess`, so
+/// editing an included file rebuilds every program that includes it. Changes are found by
+/// polling modification times, which works on every platform; call `poll` once a frame and it
+/// looks at the files at most once per interval.
+///
+/// Uniforms set through `set_uniform` are remembered and set again on the rebuilt program, which
+/// GL starts with all uniforms zero.
+pub struct HotReload {
+    provider: DirectoryProvider,
+    options: Options,
+    programs: Vec<Watched>,
+    stamps: HashMap<String, Stamp>,
+    interval: Duration,
+    polled: Option<Instant>,
+}
+
+impl HotReload {
+    /// Watches files in `root`, which are assembled with `options`.
+    pub fn new<P: Into<PathBuf>>(root: P, options: Options) -> HotReload {
+        HotReload {
+            provider: DirectoryProvider::new(root),
+            options,
+            programs: Vec::new(),
+            stamps: HashMap::new(),
+            interval: Duration::from_millis(500),
+            polled: None,
+        }
+    }
+
+    /// How often `poll` looks at the files, every 500 ms by default.
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Builds a program from the files `vertex` and `fragment`, binding the `(index, name)`
+    /// attribute locations, and watches them. A program that does not build is not watched.
+    pub fn add(&mut self, gl: &mut Wrapper, vertex: &str, fragment: &str, attributes: &[(u32, String)])
+        -> Result<ProgramHandle, Error> {
+        let mut watched = Watched {
+            stages: [vertex.to_string(), fragment.to_string()],
+            attributes: attributes.to_vec(),
+            program: 0,
+            files: Vec::new(),
+            uniforms: Vec::new(),
+        };
+
+        let (program, files) = self.build(gl, &watched.stages, &watched.attributes);
+        let program = program?;
+        for file in &files {
+            let stamp = self.stamp(file);
+            self.stamps.insert(file.clone(), stamp);
+        }
+        watched.program = program;
+        watched.files = files;
+        self.programs.push(watched);
+        Ok(ProgramHandle(self.programs.len() - 1))
+    }
+
+    /// The program currently in use for `handle`. It changes when a rebuild succeeds.
+    pub fn program(&self, handle: ProgramHandle) -> u32 {
+        self.programs[handle.0].program
+    }
+
+    /// Sets the uniform `name` of the program of `handle` and remembers the value for rebuilds.
+    /// Uniforms the program does not have, e.g. ones optimized away, are remembered all the same.
+    pub fn set_uniform(&mut self, gl: &mut Wrapper, handle: ProgramHandle, name: &str, value: UniformValue)
+        -> Result<(), Error> {
+        let watched = &mut self.programs[handle.0];
+        match watched.uniforms.iter_mut().find(|uniform| uniform.0 == name) {
+            Some(uniform) => uniform.1 = value,
+            None => watched.uniforms.push((name.to_string(), value)),
+        }
+
+        let previous = gl.gl_get_integerv(StateType::CURRENT_PROGRAM)? as u32;
+        gl.gl_use_program(watched.program)?;
+        let uniform = watched.uniforms.iter().find(|uniform| uniform.0 == name).unwrap();
+        apply_uniforms(gl, watched.program, std::slice::from_ref(uniform))?;
+        gl.gl_use_program(previous)
+    }
+
+    /// Rebuilds the programs whose files changed since they were last looked at, if the interval
+    /// has passed, and returns what happened to each.
+    pub fn poll(&mut self, gl: &mut Wrapper) -> Result<Vec<Reload>, Error> {
+        let now = Instant::now();
+        if self.polled.is_some_and(|polled| now.duration_since(polled) < self.interval) {
+            return Ok(Vec::new());
+        }
+        self.polled = Some(now);
+        self.reload_changed(gl)
+    }
+
+    /// Like `poll`, ignoring the interval.
+    pub fn reload_changed(&mut self, gl: &mut Wrapper) -> Result<Vec<Reload>, Error> {
+        let mut changed = Vec::new();
+        let names: Vec<String> = self.stamps.keys().cloned().collect();
+        for name in names {
+            let stamp = self.stamp(&name);
+            if self.stamps.insert(name.clone(), stamp) != Some(stamp) {
+                changed.push(name);
+            }
+        }
+        if changed.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut reloads = Vec::new();
+        for index in 0..self.programs.len() {
+            if self.programs[index].files.iter().any(|file| changed.contains(file)) {
+                let result = self.rebuild(gl, index);
+                reloads.push(Reload { handle: ProgramHandle(index), result });
+            }
+        }
+        Ok(reloads)
+    }
+
+    // Replaces program `index` with a new build, keeping the old one if it fails.
+    fn rebuild(&mut self, gl: &mut Wrapper, index: usize) -> Result<u32, Error> {
+        let (stages, attributes) = (self.programs[index].stages.clone(), self.programs[index].attributes.clone());
+        let (program, files) = self.build(gl, &stages, &attributes);
+        // Files the new build reads for the first time are watched from now on, also when it
+        // fails, so that fixing an error in a newly included file rebuilds the program.
+        for file in &files {
+            if !self.stamps.contains_key(file) {
+                let stamp = self.stamp(file);
+                self.stamps.insert(file.clone(), stamp);
+            }
+        }
+
+        let watched = &mut self.programs[index];
+        let swapped = program.and_then(|program| {
+            match swap_in(gl, program, watched.program, &watched.uniforms) {
+                Ok(()) => Ok(program),
+                Err(error) => {
+                    // Like a failed build, which leaves nothing behind.
+                    let _ = gl.gl_delete_program(program);
+                    Err(error)
+                }
+            }
+        });
+        let program = match swapped {
+            Ok(program) => program,
+            Err(error) => {
+                watched.files.extend(files);
+                watched.files.sort();
+                watched.files.dedup();
+                return Err(error);
+            }
+        };
+
+        let replaced = std::mem::replace(&mut watched.program, program);
+        watched.files = files;
+        gl.gl_delete_program(replaced)?;
+        Ok(program)
+    }
+
+    // A program built from the current files, and the names of all files it read or tried to
+    // read, whether the build succeeded or not.
+    fn build(&mut self, gl: &mut Wrapper, stages: &[String; 2], attributes: &[(u32, String)])
+        -> (Result<u32, Error>, Vec<String>) {
+        let mut recorder = Recorder { provider: &mut self.provider, files: Vec::new() };
+        let result = build_program(gl, stages, attributes, &mut recorder, &self.options);
+
+        let mut files = recorder.files;
+        files.sort();
+        files.dedup();
+        (result, files)
+    }
+
+    fn stamp(&self, name: &str) -> Stamp {
+        let metadata = fs::metadata(self.provider.root.join(name)).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+}
+
+// Passes reads on to `provider` and notes the names read.
+struct Recorder<'a> {
+    provider: &'a mut DirectoryProvider,
+    files: Vec<String>,
+}
+
+impl<'a> FileProvider for Recorder<'a> {
+    fn resolve(&self, path: &str, includer: &str) -> String {
+        self.provider.resolve(path, includer)
+    }
+
+    fn read(&mut self, name: &str) -> Result<String, Error> {
+        self.files.push(name.to_string());
+        self.provider.read(name)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// FUNCTIONS
+// -------------------------------------------------------------------------------------------------
+
+fn build_program(gl: &mut Wrapper, stages: &[String; 2], attributes: &[(u32, String)],
+                 provider: &mut Recorder, options: &Options) -> Result<u32, Error> {
+    let mut shaders = Vec::new();
+    let types = [ShaderType::VERTEX_SHADER, ShaderType::FRAGMENT_SHADER];
+    for (type_, name) in types.iter().zip(stages) {
+        let shader = provider.read(name)
+            .and_then(|source| preprocess::preprocess(&source, name, provider, options))
+            .and_then(|preprocessed| {
+                compile_shader(gl, *type_, &preprocessed.source, Some(&preprocessed.line_map))
+            });
+        match shader {
+            Ok(shader) => shaders.push(shader),
+            Err(error) => {
+                for shader in shaders {
+                    gl.gl_delete_shader(shader)?;
+                }
+                return Err(error);
+            }
+        }
+    }
+
+    let program = link_program(gl, &shaders, attributes);
+    // Attached shaders live on until the program is deleted.
+    for shader in shaders {
+        gl.gl_delete_shader(shader)?;
+    }
+    program
+}
+
+// Sets `uniforms` on `program` and makes current what was before, or `program` in place of
+// `replaced`. On failure the program that was current before is restored.
+fn swap_in(gl: &mut Wrapper, program: u32, replaced: u32, uniforms: &[(String, UniformValue)]) -> Result<(), Error> {
+    let previous = gl.gl_get_integerv(StateType::CURRENT_PROGRAM)? as u32;
+    let applied = gl.gl_use_program(program).and_then(|_| apply_uniforms(gl, program, uniforms));
+    gl.gl_use_program(if applied.is_ok() && previous == replaced { program } else { previous })?;
+    applied
+}
+
+// Sets `uniforms` on `program`, which must be current, skipping the ones it does not have.
+fn apply_uniforms(gl: &mut Wrapper, program: u32, uniforms: &[(String, UniformValue)]) -> Result<(), Error> {
+    for (name, value) in uniforms {
+        let location = gl.gl_get_uniform_location(program, name)?;
+        if location >= 0 {
+            value.apply(gl, location)?;
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::env;
+    use std::path::Path;
+    use std::rc::Rc;
+
+    use super::*;
+    use es20::backend::Backend;
+    use es20::call::{Call, Value};
+    use es20::software::SoftwareBackend;
+    use es20::validation::Validator;
+    use es20::wrapper::{ErrorKind, Interceptor};
+
+    const VERTEX: &str = "attribute vec2 position;\nvoid main() { gl_Position = vec4(position, 0.0, 1.0); }\n";
+    const VEC4_TINT: &str = "precision mediump float;\nuniform vec4 tint;\nvoid main() { gl_FragColor = tint; }\n";
+    const FLOAT_TINT: &str = "precision mediump float;\nuniform float tint;\n\
+                              void main() { gl_FragColor = vec4(tint); }\n";
+
+    // The programs created and deleted through the wrapper.
+    #[derive(Clone, Default)]
+    struct Programs(Rc<RefCell<(Vec<u32>, Vec<u32>)>>);
+
+    impl Programs {
+        fn alive(&self) -> Vec<u32> {
+            let programs = self.0.borrow();
+            programs.0.iter().cloned().filter(|program| !programs.1.contains(program)).collect()
+        }
+    }
+
+    impl Interceptor for Programs {
+        fn intercept(&mut self, _call: &Call, _backend: &mut dyn Backend) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn completed(&mut self, call: &Call, _backend: &mut dyn Backend) {
+            match (call.name, &call.ret, call.args.first()) {
+                ("glCreateProgram", &Value::Name(_, program), _) => self.0.borrow_mut().0.push(program),
+                ("glDeleteProgram", _, Some(&Value::Name(_, program))) => self.0.borrow_mut().1.push(program),
+                _ => {}
+            }
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("opengl_es_rs_hot_reload_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // A validated context watching `shader.vert` and `shader.frag` in `dir`, with the fragment
+    // shader `fragment` and `tint` set to red.
+    fn watch(dir: &Path, fragment: &str, programs: &Programs) -> (Wrapper, HotReload, ProgramHandle) {
+        fs::write(dir.join("shader.vert"), VERTEX).unwrap();
+        fs::write(dir.join("shader.frag"), fragment).unwrap();
+
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(1, 1)));
+        gl.add_interceptor(Box::new(programs.clone()));
+        gl.add_interceptor(Box::new(Validator::new()));
+        let mut hot = HotReload::new(dir, Options::default());
+        let handle = hot.add(&mut gl, "shader.vert", "shader.frag", &[(0, "position".to_string())]).unwrap();
+        hot.set_uniform(&mut gl, handle, "tint", UniformValue::Vec4(vec![1.0, 0.0, 0.0, 1.0])).unwrap();
+        (gl, hot, handle)
+    }
+
+    #[test]
+    fn rebuilds_changed_programs_with_their_uniforms() {
+        let dir = temp_dir("rebuild");
+        let programs = Programs::default();
+        let (mut gl, mut hot, handle) = watch(&dir, VEC4_TINT, &programs);
+        let first = hot.program(handle);
+        gl.gl_use_program(first).unwrap();
+
+        fs::write(dir.join("shader.frag"), format!("// edited\n{}", VEC4_TINT)).unwrap();
+        let reloads = hot.reload_changed(&mut gl).unwrap();
+        assert_eq!(reloads.len(), 1);
+        let second = reloads[0].result.clone().unwrap();
+
+        assert_eq!(hot.program(handle), second);
+        assert_eq!(programs.alive(), vec![second]);
+        assert_eq!(gl.gl_get_integerv(StateType::CURRENT_PROGRAM).unwrap() as u32, second);
+        let tint = gl.gl_get_uniform_location(second, "tint").unwrap();
+        assert_eq!(gl.gl_get_uniformfv(second, tint).unwrap(), 1.0);
+        assert!(hot.reload_changed(&mut gl).unwrap().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn failed_builds_keep_the_previous_program() {
+        let dir = temp_dir("compile_error");
+        let programs = Programs::default();
+        let (mut gl, mut hot, handle) = watch(&dir, VEC4_TINT, &programs);
+        let first = hot.program(handle);
+
+        fs::write(dir.join("shader.frag"), "void main() { gl_FragColor = nope; }\n").unwrap();
+        let reloads = hot.reload_changed(&mut gl).unwrap();
+        assert_eq!(reloads[0].result.clone().unwrap_err().kind, ErrorKind::Compile);
+        assert_eq!(hot.program(handle), first);
+        assert_eq!(programs.alive(), vec![first]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_program_whose_uniforms_can_not_be_set_is_deleted() {
+        let dir = temp_dir("uniform_error");
+        let programs = Programs::default();
+        let (mut gl, mut hot, handle) = watch(&dir, VEC4_TINT, &programs);
+        let first = hot.program(handle);
+        gl.gl_use_program(first).unwrap();
+
+        // The remembered vec4 no longer fits, which the validator rejects.
+        fs::write(dir.join("shader.frag"), FLOAT_TINT).unwrap();
+        let reloads = hot.reload_changed(&mut gl).unwrap();
+        assert_eq!(reloads[0].result.clone().unwrap_err().kind, ErrorKind::Validation);
+        assert_eq!(hot.program(handle), first);
+        assert_eq!(programs.alive(), vec![first]);
+        assert_eq!(gl.gl_get_integerv(StateType::CURRENT_PROGRAM).unwrap() as u32, first);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,191 @@
+use enums::{ErrorType, ProgramParamType, ShaderBinaryFormat, ShaderParamType, ShaderType, StateType};
+use types::GLenum;
+use super::wrapper::{Error, ErrorKind, Wrapper};
+use self::preprocess::LineMap;
+
+pub mod hot_reload;
+pub mod preprocess;
+pub mod program_cache;
+pub mod variants;
+
+// -------------------------------------------------------------------------------------------------
+// FUNCTIONS
+// -------------------------------------------------------------------------------------------------
+
+/// Creates and compiles a shader. When it does not compile, the shader is deleted and the error
+/// holds the info log, with its line references mapped through `line_map` if there is one.
+pub fn compile_shader(gl: &mut Wrapper, type_: ShaderType, source: &str, line_map: Option<&LineMap>)
+    -> Result<u32, Error> {
+    let shader = gl.gl_create_shader(type_)?;
+    gl.gl_shader_source(shader, source)?;
+    gl.gl_compile_shader(shader)?;
+
+    if gl.gl_get_shaderiv(shader, ShaderParamType::COMPILE_STATUS)? == 0 {
+        let length = gl.gl_get_shaderiv(shader, ShaderParamType::INFO_LOG_LENGTH)?;
+        let log = gl.gl_get_shader_info_log(shader, length.max(1))?;
+        gl.gl_delete_shader(shader)?;
+
+        let log = match line_map {
+            Some(line_map) => line_map.map_info_log(&log),
+            None => log,
+        };
+        return Err(Error::new(ErrorKind::Compile, log));
+    }
+    Ok(shader)
+}
+
+/// Links a program from compiled shaders, binding the `(index, name)` attribute locations first.
+/// When it does not link, the program is deleted and the error holds the info log.
+pub fn link_program(gl: &mut Wrapper, shaders: &[u32], attributes: &[(u32, String)]) -> Result<u32, Error> {
+    let program = gl.gl_create_program()?;
+    for &shader in shaders {
+        gl.gl_attach_shader(program, shader)?;
+    }
+    for (index, name) in attributes {
+        gl.gl_bind_attrib_location(program, *index, name)?;
+    }
+    gl.gl_link_program(program)?;
+
+    if gl.gl_get_programiv(program, ProgramParamType::LINK_STATUS)? == 0 {
+        let length = gl.gl_get_programiv(program, ProgramParamType::INFO_LOG_LENGTH)?;
+        let log = gl.gl_get_program_info_log(program, length.max(1))?;
+        gl.gl_delete_program(program)?;
+        return Err(Error::new(ErrorKind::Compile, log));
+    }
+    Ok(program)
+}
+
+/// The shader binary formats the driver loads, from `SHADER_BINARY_FORMATS`. Ones this crate does
+/// not know are `ShaderBinaryFormat::Other`.
+pub fn shader_binary_formats(gl: &mut Wrapper) -> Result<Vec<ShaderBinaryFormat>, Error> {
+    let count = gl.gl_get_integerv(StateType::NUM_SHADER_BINARY_FORMATS)?;
+    let formats = gl.gl_get_integerv_array(StateType::SHADER_BINARY_FORMATS, count.max(0) as usize)?;
+
+    Ok(formats.into_iter().map(|format| ShaderBinaryFormat::from_raw(format as GLenum)).collect())
+}
+
+/// Creates a shader of each of `types` and loads them all from the one `binary`, in that order.
+/// The format is checked against `shader_binary_formats` first. A binary the driver rejects only
+/// shows in `gl_get_error`, so errors pending from earlier calls are cleared before loading
+/// rather than taken for a rejection. No shaders are left behind on failure.
+pub fn load_shader_binary(gl: &mut Wrapper, types: &[ShaderType], format: ShaderBinaryFormat, binary: &[u8])
+    -> Result<Vec<u32>, Error> {
+    if !shader_binary_formats(gl)?.contains(&format) {
+        return Err(Error::new(ErrorKind::Unsupported,
+                              format!("the driver does not load {:?} shader binaries", format)));
+    }
+
+    // GL keeps at most one flag per kind of error; the bound guards against a lost context that
+    // keeps reporting one.
+    for _ in 0..8 {
+        if gl.gl_get_error() == ErrorType::NO_ERROR {
+            break;
+        }
+    }
+
+    let mut shaders = Vec::with_capacity(types.len());
+    for &type_ in types {
+        shaders.push(gl.gl_create_shader(type_)?);
+    }
+    let result = gl.gl_shader_binary(&shaders, format, binary).and_then(|_| match gl.gl_get_error() {
+        ErrorType::NO_ERROR => Ok(()),
+        error => Err(Error::new(ErrorKind::InvalidResult, format!("glShaderBinary failed with {:?}", error))),
+    });
+
+    if let Err(error) = result {
+        for &shader in &shaders {
+            gl.gl_delete_shader(shader)?;
+        }
+        return Err(error);
+    }
+    Ok(shaders)
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use consts::{GL_COLOR_BUFFER_BIT, GL_MALI_SHADER_BINARY_ARM};
+    use es20::backend::Backend;
+    use es20::call::{Call, Value};
+    use es20::software::SoftwareBackend;
+    use es20::wrapper::Interceptor;
+
+    // Keeps every call made through the wrapper.
+    struct Calls(Rc<RefCell<Vec<Call>>>);
+
+    impl Interceptor for Calls {
+        fn intercept(&mut self, call: &Call, _backend: &mut dyn Backend) -> Result<(), Error> {
+            self.0.borrow_mut().push(call.clone());
+            Ok(())
+        }
+    }
+
+    fn recorded() -> (Wrapper, Rc<RefCell<Vec<Call>>>) {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(1, 1)));
+        gl.add_interceptor(Box::new(Calls(calls.clone())));
+        (gl, calls)
+    }
+
+    #[test]
+    fn binary_formats_map_to_and_from_gl() {
+        assert_eq!(ShaderBinaryFormat::from_raw(GL_MALI_SHADER_BINARY_ARM), ShaderBinaryFormat::MALI_SHADER_BINARY_ARM);
+        assert_eq!(ShaderBinaryFormat::MALI_SHADER_BINARY_ARM.as_raw(), GL_MALI_SHADER_BINARY_ARM);
+        assert_eq!(ShaderBinaryFormat::from_raw(0x1234), ShaderBinaryFormat::Other(0x1234));
+        assert_eq!(ShaderBinaryFormat::Other(0x1234).as_raw(), 0x1234);
+    }
+
+    #[test]
+    fn shader_binary_passes_the_blob_length() {
+        let (mut gl, calls) = recorded();
+        let shader = gl.gl_create_shader(ShaderType::VERTEX_SHADER).unwrap();
+        assert_eq!(gl.gl_shader_binary(&[], ShaderBinaryFormat::SGX_BINARY_IMG, &[0; 4]).unwrap_err().kind,
+                   ErrorKind::InvalidArgument);
+
+        gl.gl_shader_binary(&[shader], ShaderBinaryFormat::SGX_BINARY_IMG, &[0; 12]).unwrap();
+        let call = calls.borrow().last().cloned().unwrap();
+        assert_eq!(call.name, "glShaderBinary");
+        assert_eq!(call.args[4], Value::Int(12));
+        // The software backend loads no binary formats.
+        assert_eq!(gl.gl_get_error(), ErrorType::INVALID_ENUM);
+    }
+
+    #[test]
+    fn unsupported_binaries_are_not_loaded() {
+        let (mut gl, calls) = recorded();
+        assert!(shader_binary_formats(&mut gl).unwrap().is_empty());
+
+        gl.gl_clear(GL_COLOR_BUFFER_BIT | 0x1).unwrap();
+        let types = [ShaderType::VERTEX_SHADER, ShaderType::FRAGMENT_SHADER];
+        let error = load_shader_binary(&mut gl, &types, ShaderBinaryFormat::MALI_SHADER_BINARY_ARM, &[0; 16]);
+        assert_eq!(error.unwrap_err().kind, ErrorKind::Unsupported);
+        assert!(calls.borrow().iter().all(|call| call.name != "glCreateShader"));
+        // Nothing was loaded, so the pending error is left to the application.
+        assert_eq!(gl.gl_get_error(), ErrorType::INVALID_VALUE);
+    }
+
+    #[test]
+    fn compile_and_link_errors_leave_nothing_behind() {
+        let (mut gl, calls) = recorded();
+        let vertex = "attribute vec2 position;\nvoid main() { gl_Position = vec4(position, 0.0, 1.0); }\n";
+        let shader = compile_shader(&mut gl, ShaderType::VERTEX_SHADER, vertex, None).unwrap();
+        let error = compile_shader(&mut gl, ShaderType::FRAGMENT_SHADER, "void main() { nope; }\n", None).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Compile);
+        assert!(error.message.contains("'nope' : undeclared identifier"));
+
+        // A vertex shader alone has no main for the fragment stage.
+        let error = link_program(&mut gl, &[shader], &[(0, "position".to_string())]).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Compile);
+
+        let calls = calls.borrow();
+        let deleted = |name: &str| calls.iter().filter(|call| call.name == name).count();
+        assert_eq!((deleted("glDeleteShader"), deleted("glDeleteProgram")), (1, 1));
+    }
+}
@@ -0,0 +1,424 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::super::wrapper::{Error, ErrorKind};
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+/// What happens to comments in the assembled source.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Comments {
+    /// Comments are removed, and so are lines left empty by that.
+    Strip,
+    Preserve,
+}
+
+// -------------------------------------------------------------------------------------------------
+// TRAITS
+// -------------------------------------------------------------------------------------------------
+
+/// Where the files named by `#include "..."` come from.
+pub trait FileProvider {
+    /// The name of the file `path` refers to in a file named `includer`. The default resolves it
+    /// relative to the directory of `includer`, or to the root for paths starting with `/`, and
+    /// removes `.` and `..` components so that every file has one name.
+    fn resolve(&self, path: &str, includer: &str) -> String {
+        let joined = if path.starts_with('/') {
+            path.to_string()
+        } else {
+            match includer.rfind('/') {
+                Some(end) => format!("{}/{}", &includer[..end], path),
+                None => path.to_string(),
+            }
+        };
+
+        let mut components: Vec<&str> = Vec::new();
+        for component in joined.split('/') {
+            match component {
+                "" | "." => {}
+                ".." if components.last().is_some_and(|last| *last != "..") => {
+                    components.pop();
+                }
+                component => components.push(component),
+            }
+        }
+        components.join("/")
+    }
+
+    /// The text of the file with a name `resolve` returned.
+    fn read(&mut self, name: &str) -> Result<String, Error>;
+}
+
+/// Files in memory, by name.
+impl FileProvider for HashMap<String, String> {
+    fn read(&mut self, name: &str) -> Result<String, Error> {
+        match self.get(name) {
+            Some(text) => Ok(text.clone()),
+            None => Err(Error::new(ErrorKind::Io, format!("no file named '{}'", name))),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// Reads included files from a directory. Names are paths relative to it; absolute names and ones
+/// that lead out of it through `..` are refused.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DirectoryProvider {
+    pub root: PathBuf,
+}
+
+impl DirectoryProvider {
+    pub fn new<P: Into<PathBuf>>(root: P) -> DirectoryProvider {
+        DirectoryProvider { root: root.into() }
+    }
+}
+
+impl FileProvider for DirectoryProvider {
+    fn read(&mut self, name: &str) -> Result<String, Error> {
+        let inside = Path::new(name).components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if !inside {
+            return Err(Error::new(ErrorKind::InvalidArgument, format!("'{}' is outside the root directory", name)));
+        }
+        fs::read_to_string(self.root.join(name))
+            .map_err(|error| Error::new(ErrorKind::Io, format!("reading '{}': {}", name, error)))
+    }
+}
+
+/// How to assemble a shader.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Options {
+    /// Written as `#define NAME VALUE` right after `#version`, in this order. The value may be
+    /// empty.
+    pub defines: Vec<(String, String)>,
+    pub comments: Comments,
+    /// The `#version` directive to write when no file has one, e.g. `300 es`. `None` writes none,
+    /// which GLSL ES takes as version 100.
+    pub default_version: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            defines: Vec::new(),
+            comments: Comments::Preserve,
+            default_version: None,
+        }
+    }
+}
+
+/// Where each line of an assembled shader came from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LineMap {
+    files: Vec<String>,
+    // The file index and line of each output line, `None` for the lines the preprocessor added.
+    lines: Vec<Option<(usize, u32)>>,
+}
+
+impl LineMap {
+    /// The names of all files that went into the shader, the root file first.
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    /// The file and line that line `line` of the assembled source came from. Lines count from 1,
+    /// as drivers count them. `None` for the lines the preprocessor added, `#version` and the
+    /// injected `#define`s.
+    pub fn original(&self, line: u32) -> Option<(&str, u32)> {
+        let origin = (*self.lines.get((line as usize).checked_sub(1)?)?)?;
+        Some((&self.files[origin.0], origin.1))
+    }
+
+    /// `log`, an info log from `gl_get_shader_info_log`, with the line references drivers write,
+    /// `0:12` or `0(12)`, replaced by the original `file:line` or `file(line)`. References to
+    /// lines the preprocessor added are left alone.
+    pub fn map_info_log(&self, log: &str) -> String {
+        let mut mapped = String::with_capacity(log.len());
+        for line in log.split_inclusive('\n') {
+            mapped.push_str(&self.map_reference(line));
+        }
+        mapped
+    }
+
+    // The first line reference of a line of an info log, replaced.
+    fn map_reference(&self, line: &str) -> String {
+        let bytes = line.as_bytes();
+        for start in 0..bytes.len() {
+            // References start words and name source string 0, the only one there is.
+            if bytes[start] != b'0' || (start > 0 && !bytes[start - 1].is_ascii_whitespace()) {
+                continue;
+            }
+            let (open, close) = match bytes.get(start + 1) {
+                Some(b':') => (':', ':'),
+                Some(b'(') => ('(', ')'),
+                _ => continue,
+            };
+            let digits = bytes[start + 2..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+            let end = start + 2 + digits;
+            if digits == 0 || bytes.get(end) != Some(&(close as u8)) {
+                continue;
+            }
+
+            let number = line[start + 2..end].parse().unwrap_or(0);
+            return match self.original(number) {
+                Some((file, original)) => {
+                    format!("{}{}{}{}{}", &line[..start], file, open, original, &line[end..])
+                }
+                None => line.to_string(),
+            };
+        }
+        line.to_string()
+    }
+}
+
+/// A shader assembled from its files.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Preprocessed {
+    /// Ready for `gl_shader_source`.
+    pub source: String,
+    pub line_map: LineMap,
+}
+
+// The state of assembling one shader.
+struct Assembler<'a> {
+    provider: &'a mut dyn FileProvider,
+    comments: Comments,
+    map: LineMap,
+    lines: Vec<String>,
+    // The version and where it was declared.
+    version: Option<(String, String, u32)>,
+    // The files being included, innermost last, to catch recursion.
+    including: Vec<String>,
+    // Files with `#pragma once` that were included already.
+    once: HashSet<String>,
+}
+
+// -------------------------------------------------------------------------------------------------
+// FUNCTIONS
+// -------------------------------------------------------------------------------------------------
+
+/// Assembles the shader `source`, which is the file `name`: resolves `#include "file"` through
+/// `provider`, injects the defines of `options` and handles comments as it asks.
+///
+/// `#version` from any file moves to the first line, as GLSL ES requires, followed by the
+/// defines. Included files may only repeat the same version. `#pragma once` includes a file at
+/// most once. Conditional directives are left to the driver, so includes between `#ifdef` and
+/// `#endif` are resolved either way.
+pub fn preprocess(source: &str, name: &str, provider: &mut dyn FileProvider, options: &Options)
+    -> Result<Preprocessed, Error> {
+    for (define, value) in &options.defines {
+        let identifier = define.chars().next().is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+            && define.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !identifier {
+            return Err(Error::new(ErrorKind::InvalidArgument, format!("'{}' is not a valid macro name", define)));
+        }
+        // A line break would end the define and inject the rest as source.
+        if value.contains(['\n', '\r']) {
+            return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                "the value of '{}' spans several lines", define)));
+        }
+    }
+
+    let mut assembler = Assembler {
+        provider,
+        comments: options.comments,
+        map: LineMap::default(),
+        lines: Vec::new(),
+        version: None,
+        including: Vec::new(),
+        once: HashSet::new(),
+    };
+    assembler.file(name, source)?;
+
+    let mut header = Vec::new();
+    match assembler.version {
+        Some((ref version, _, _)) => header.push(format!("#version {}", version)),
+        None => header.extend(options.default_version.iter().map(|version| format!("#version {}", version))),
+    }
+    for (define, value) in &options.defines {
+        header.push(format!("#define {} {}", define, value).trim_end().to_string());
+    }
+
+    let mut map = assembler.map;
+    let mut lines = header;
+    map.lines.splice(0..0, vec![None; lines.len()]);
+    lines.extend(assembler.lines);
+
+    let mut source = lines.join("\n");
+    source.push('\n');
+    Ok(Preprocessed { source, line_map: map })
+}
+
+impl<'a> Assembler<'a> {
+    fn file(&mut self, name: &str, text: &str) -> Result<(), Error> {
+        let code = strip_comments(text);
+        let once = code.iter().any(|line| directive(&line.0) == Some(("pragma", "once")));
+        if once && !self.once.insert(name.to_string()) {
+            return Ok(());
+        }
+
+        let index = match self.map.files.iter().position(|file| file == name) {
+            Some(index) => index,
+            None => {
+                self.map.files.push(name.to_string());
+                self.map.files.len() - 1
+            }
+        };
+        self.including.push(name.to_string());
+
+        for (number, (original, &(ref code, in_comment))) in text.lines().zip(&code).enumerate() {
+            let line = number as u32 + 1;
+            let at = |message: String| Error::new(ErrorKind::Format, format!("{}:{}: {}", name, line, message));
+
+            let consumed = match directive(code) {
+                Some(("include", argument)) => {
+                    let path = match argument.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+                        Some(path) if !path.is_empty() && !path.contains('"') => path,
+                        _ => return Err(at("#include needs a file name in double quotes".to_string())),
+                    };
+                    let included = self.provider.resolve(path, name);
+                    if self.including.contains(&included) {
+                        return Err(at(format!("#include of '{}' is recursive", included)));
+                    }
+                    let text = self.provider.read(&included).map_err(|error| at(error.message))?;
+                    self.file(&included, &text)?;
+                    true
+                }
+                Some(("version", argument)) => {
+                    let argument = argument.split_whitespace().collect::<Vec<_>>().join(" ");
+                    match self.version {
+                        Some((ref version, ref file, declared)) if *version != argument => {
+                            return Err(at(format!("#version {} conflicts with #version {} at {}:{}", argument,
+                                                  version, file, declared)));
+                        }
+                        Some(_) => {}
+                        None => self.version = Some((argument, name.to_string(), line)),
+                    }
+                    true
+                }
+                Some(("pragma", "once")) => true,
+                _ => false,
+            };
+
+            let output = match (consumed, self.comments) {
+                // A block comment the directive line opened still has to be opened.
+                (true, Comments::Preserve) if in_comment => "/*".to_string(),
+                (true, _) => continue,
+                (false, Comments::Strip) if code.trim().is_empty() => continue,
+                (false, Comments::Strip) => code.trim_end().to_string(),
+                (false, Comments::Preserve) => original.to_string(),
+            };
+            self.lines.push(output);
+            self.map.lines.push(Some((index, line)));
+        }
+
+        self.including.pop();
+        Ok(())
+    }
+}
+
+// The lines of `text` with comments replaced by spaces, each with whether it ends inside a block
+// comment.
+fn strip_comments(text: &str) -> Vec<(String, bool)> {
+    let mut lines = Vec::new();
+    let mut in_comment = false;
+    for line in text.lines() {
+        let mut code = String::with_capacity(line.len());
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if in_comment {
+                if c == '*' && chars.peek() == Some(&'/') {
+                    chars.next();
+                    in_comment = false;
+                }
+            } else if c == '/' && chars.peek() == Some(&'*') {
+                chars.next();
+                in_comment = true;
+                code.push(' ');
+            } else if c == '/' && chars.peek() == Some(&'/') {
+                break;
+            } else {
+                code.push(c);
+            }
+        }
+        lines.push((code, in_comment));
+    }
+    lines
+}
+
+// The name and argument of a preprocessor directive line.
+fn directive(line: &str) -> Option<(&str, &str)> {
+    let rest = line.trim_start().strip_prefix('#')?.trim_start();
+    let end = rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(rest.len());
+    Some((&rest[..end], rest[end..].trim()))
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn files(files: &[(&str, &str)]) -> HashMap<String, String> {
+        files.iter().map(|&(name, text)| (name.to_string(), text.to_string())).collect()
+    }
+
+    #[test]
+    fn assembles_includes_version_and_defines() {
+        let mut provider = files(&[
+            ("lib/common.glsl", "#pragma once\nfloat half_(float x) { return x * 0.5; }\n"),
+            ("lib/light.glsl", "#include \"common.glsl\"\n"),
+        ]);
+        let source = "// main\n#version 300 es\n#include \"lib/common.glsl\"\n\
+                      #include \"lib/light.glsl\"\nvoid main() {}\n";
+        let options = Options {
+            defines: vec![("LIGHTS".to_string(), "4".to_string()), ("FAST".to_string(), String::new())],
+            comments: Comments::Strip,
+            default_version: None,
+        };
+
+        let preprocessed = preprocess(source, "main.frag", &mut provider, &options).unwrap();
+        assert_eq!(preprocessed.source, "#version 300 es\n#define LIGHTS 4\n#define FAST\n\
+                                         float half_(float x) { return x * 0.5; }\nvoid main() {}\n");
+        assert_eq!(preprocessed.line_map.original(4), Some(("lib/common.glsl", 2)));
+        assert_eq!(preprocessed.line_map.original(5), Some(("main.frag", 5)));
+        assert_eq!(preprocessed.line_map.original(1), None);
+    }
+
+    #[test]
+    fn rejects_recursive_includes_and_bad_defines() {
+        let mut provider = files(&[("a.glsl", "#include \"b.glsl\"\n"), ("b.glsl", "#include \"a.glsl\"\n")]);
+        assert!(preprocess("#include \"a.glsl\"\n", "main.frag", &mut provider, &Options::default()).is_err());
+
+        for &(define, value) in &[("1ST", "1"), ("A B", "1"), ("VALUE", "1\nvoid main() {}")] {
+            let options = Options { defines: vec![(define.to_string(), value.to_string())], ..Options::default() };
+            let error = preprocess("void main() {}\n", "main.frag", &mut files(&[]), &options).unwrap_err();
+            assert_eq!(error.kind, ErrorKind::InvalidArgument);
+        }
+    }
+
+    #[test]
+    fn directory_provider_stays_inside_the_root() {
+        let root = env::temp_dir().join(format!("opengl_es_rs_preprocess_{}", std::process::id()));
+        fs::create_dir_all(root.join("lib")).unwrap();
+        fs::write(root.join("lib/common.glsl"), "float one() { return 1.0; }\n").unwrap();
+        let mut provider = DirectoryProvider::new(&root);
+
+        assert_eq!(provider.read("lib/common.glsl").unwrap(), "float one() { return 1.0; }\n");
+        assert_eq!(provider.resolve("../lib/./common.glsl", "shaders/main.frag"), "lib/common.glsl");
+        for name in &["../secret.glsl", "/etc/passwd", "lib/../../secret.glsl"] {
+            assert_eq!(provider.read(name).unwrap_err().kind, ErrorKind::InvalidArgument, "{}", name);
+        }
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,389 @@
+use std::fs;
+use std::path::PathBuf;
+
+use enums::{ConstantType, ProgramParamType, ShaderType, StateType};
+use types::GLenum;
+use super::super::wrapper::{Error, ErrorKind, Wrapper};
+use super::{compile_shader, link_program};
+
+// Starts every cache file, followed by the format version, the binary format, the length of the
+// key, the key and the binary. The file name is only a hash of the key, so the key itself is
+// compared on load.
+const MAGIC: &[u8] = b"GLPB";
+const VERSION: u32 = 2;
+const HEADER_SIZE: usize = 16;
+const EXTENSION: &str = "glbin";
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+/// Which entry points retrieve and load program binaries.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BinarySupport {
+    /// `GL_OES_get_program_binary`.
+    Extension,
+    /// ES 3.0 core.
+    Core,
+    /// Neither, or the driver offers no binary formats. Programs are always built from source.
+    None,
+}
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// Programs stored on disk as driver binaries, so that later launches skip compiling and linking.
+///
+/// A program is keyed by its sources, attribute bindings and the vendor, renderer and version
+/// strings of the driver, so a driver update starts a fresh cache. A binary the driver
+/// rejects anyway is deleted and the program is built from source and stored again.
+///
+/// Writing the cache is best effort: a program that could not be stored is still returned.
+pub struct ProgramCache {
+    directory: PathBuf,
+    support: BinarySupport,
+    formats: Vec<GLenum>,
+    driver: String,
+}
+
+impl ProgramCache {
+    /// A cache in `directory`, which is created if needed, for the driver behind `gl`.
+    pub fn new<P: Into<PathBuf>>(gl: &mut Wrapper, directory: P) -> Result<ProgramCache, Error> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory).map_err(|error| {
+            Error::new(ErrorKind::Io, format!("creating '{}': {}", directory.display(), error))
+        })?;
+
+        let vendor = gl.gl_get_string(ConstantType::VENDOR)?;
+        let renderer = gl.gl_get_string(ConstantType::RENDERER)?;
+        let version = gl.gl_get_string(ConstantType::VERSION)?;
+        let extensions = gl.gl_get_string(ConstantType::EXTENSIONS)?;
+
+        let mut support = if extensions.split_whitespace().any(|name| name == "GL_OES_get_program_binary") {
+            BinarySupport::Extension
+        } else if gl.es_major_version()? >= 3 {
+            BinarySupport::Core
+        } else {
+            BinarySupport::None
+        };
+        // Advertised, but the library does not have the functions.
+        let (get, load) = match support {
+            BinarySupport::Extension => ("glGetProgramBinaryOES", "glProgramBinaryOES"),
+            _ => ("glGetProgramBinary", "glProgramBinary"),
+        };
+        if !gl.has_entry_point(get) || !gl.has_entry_point(load) {
+            support = BinarySupport::None;
+        }
+
+        let mut formats = Vec::new();
+        if support != BinarySupport::None {
+            let count = gl.gl_get_integerv(StateType::NUM_PROGRAM_BINARY_FORMATS)?;
+            formats = gl.gl_get_integerv_array(StateType::PROGRAM_BINARY_FORMATS, count.max(0) as usize)?
+                .into_iter()
+                .map(|format| format as GLenum)
+                .collect();
+            if formats.is_empty() {
+                support = BinarySupport::None;
+            }
+        }
+
+        Ok(ProgramCache {
+            directory,
+            support,
+            formats,
+            driver: format!("{}\n{}\n{}", vendor, renderer, version),
+        })
+    }
+
+    pub fn support(&self) -> BinarySupport {
+        self.support
+    }
+
+    /// The program of `vertex` and `fragment` with the `(index, name)` attribute bindings: loaded
+    /// from the cache if it holds a binary the driver accepts, otherwise compiled, linked and
+    /// stored.
+    pub fn program(&self, gl: &mut Wrapper, vertex: &str, fragment: &str, attributes: &[(u32, String)])
+        -> Result<u32, Error> {
+        let key = self.key(vertex, fragment, attributes);
+        if self.support != BinarySupport::None {
+            if let Some(program) = self.load(gl, &key)? {
+                return Ok(program);
+            }
+        }
+
+        let vertex = compile_shader(gl, ShaderType::VERTEX_SHADER, vertex, None)?;
+        let fragment = match compile_shader(gl, ShaderType::FRAGMENT_SHADER, fragment, None) {
+            Ok(fragment) => fragment,
+            Err(error) => {
+                gl.gl_delete_shader(vertex)?;
+                return Err(error);
+            }
+        };
+        let program = link_program(gl, &[vertex, fragment], attributes);
+        // Attached shaders live on until the program is deleted.
+        gl.gl_delete_shader(vertex)?;
+        gl.gl_delete_shader(fragment)?;
+        let program = program?;
+
+        // The program is built either way, and a failed store only costs the next launch a build.
+        if self.support != BinarySupport::None {
+            let _ = self.store(gl, &key, program);
+        }
+        Ok(program)
+    }
+
+    /// Deletes every cached binary.
+    pub fn clear(&self) -> Result<(), Error> {
+        let io = |error: std::io::Error| Error::new(ErrorKind::Io, format!("clearing the program cache: {}", error));
+        for entry in fs::read_dir(&self.directory).map_err(io)? {
+            let path = entry.map_err(io)?.path();
+            if path.extension().is_some_and(|extension| extension == EXTENSION) {
+                fs::remove_file(&path).map_err(io)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn key(&self, vertex: &str, fragment: &str, attributes: &[(u32, String)]) -> Vec<u8> {
+        let mut key = Vec::new();
+        for part in &[&self.driver[..], vertex, fragment] {
+            key.extend_from_slice(&(part.len() as u64).to_le_bytes());
+            key.extend_from_slice(part.as_bytes());
+        }
+        for (index, name) in attributes {
+            key.extend_from_slice(&index.to_le_bytes());
+            key.extend_from_slice(&(name.len() as u64).to_le_bytes());
+            key.extend_from_slice(name.as_bytes());
+        }
+        key
+    }
+
+    fn path(&self, key: &[u8]) -> PathBuf {
+        let mut hash = Fnv::default();
+        hash.write(key);
+        self.directory.join(format!("{:016x}.{}", hash.0, EXTENSION))
+    }
+
+    // The program from the cache file of `key`, `None` if there is no usable one.
+    fn load(&self, gl: &mut Wrapper, key: &[u8]) -> Result<Option<u32>, Error> {
+        let path = self.path(key);
+        let file = match fs::read(&path) {
+            Ok(file) => file,
+            Err(_) => return Ok(None),
+        };
+
+        let (format, binary) = match parse(&file) {
+            Some((format, stored, binary)) if stored == key && self.formats.contains(&format) => (format, binary),
+            // Another program whose key has the same hash, damaged, or written for a format the
+            // driver no longer offers.
+            _ => {
+                let _ = fs::remove_file(&path);
+                return Ok(None);
+            }
+        };
+
+        let program = gl.gl_create_program()?;
+        match self.support {
+            BinarySupport::Extension => gl.gl_program_binary_oes(program, format, binary)?,
+            _ => gl.gl_program_binary(program, format, binary)?,
+        }
+        if gl.gl_get_programiv(program, ProgramParamType::LINK_STATUS)? == 0 {
+            gl.gl_delete_program(program)?;
+            let _ = fs::remove_file(&path);
+            return Ok(None);
+        }
+        Ok(Some(program))
+    }
+
+    fn store(&self, gl: &mut Wrapper, key: &[u8], program: u32) -> Result<(), Error> {
+        let length = gl.gl_get_programiv(program, ProgramParamType::PROGRAM_BINARY_LENGTH)?;
+        if length <= 0 {
+            return Ok(());
+        }
+        let (format, binary) = match self.support {
+            BinarySupport::Extension => gl.gl_get_program_binary_oes(program, length)?,
+            _ => gl.gl_get_program_binary(program, length)?,
+        };
+        if binary.is_empty() {
+            return Ok(());
+        }
+
+        let mut file = Vec::with_capacity(HEADER_SIZE + key.len() + binary.len());
+        file.extend_from_slice(MAGIC);
+        file.extend_from_slice(&VERSION.to_le_bytes());
+        file.extend_from_slice(&format.to_le_bytes());
+        file.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        file.extend_from_slice(key);
+        file.extend_from_slice(&binary);
+
+        // Written aside and renamed, so that a crash never leaves a truncated binary behind.
+        let path = self.path(key);
+        let temporary = path.with_extension("tmp");
+        if fs::write(&temporary, &file).and_then(|_| fs::rename(&temporary, &path)).is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+        Ok(())
+    }
+}
+
+// FNV-1a, which unlike `DefaultHasher` is the same in every build, as keys on disk need.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Fnv {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// FUNCTIONS
+// -------------------------------------------------------------------------------------------------
+
+// The binary format, key and binary of a cache file.
+fn parse(file: &[u8]) -> Option<(GLenum, &[u8], &[u8])> {
+    let word = |at: usize| u32::from_le_bytes([file[at], file[at + 1], file[at + 2], file[at + 3]]);
+    if file.len() < HEADER_SIZE || !file.starts_with(MAGIC) || word(4) != VERSION {
+        return None;
+    }
+    let key_end = HEADER_SIZE.checked_add(word(12) as usize)?;
+    if file.len() <= key_end {
+        return None;
+    }
+    Some((word(8), &file[HEADER_SIZE..key_end], &file[key_end..]))
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::env;
+    use std::path::Path;
+    use std::rc::Rc;
+
+    use super::*;
+    use es20::backend::Backend;
+    use es20::call::Call;
+    use es20::software::SoftwareBackend;
+    use es20::wrapper::Interceptor;
+
+    const VERTEX: &str = "attribute vec2 position;\nvoid main() { gl_Position = vec4(position, 0.0, 1.0); }\n";
+    const RED: &str = "void main() { gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0); }\n";
+    const GREEN: &str = "void main() { gl_FragColor = vec4(0.0, 1.0, 0.0, 1.0); }\n";
+
+    // Counts the shaders compiled through the wrapper.
+    struct Compiles(Rc<Cell<usize>>);
+
+    impl Interceptor for Compiles {
+        fn intercept(&mut self, call: &Call, _backend: &mut dyn Backend) -> Result<(), Error> {
+            if call.name == "glCompileShader" {
+                self.0.set(self.0.get() + 1);
+            }
+            Ok(())
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("opengl_es_rs_program_cache_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn context(compiles: &Rc<Cell<usize>>) -> Wrapper {
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(1, 1)));
+        gl.add_interceptor(Box::new(Compiles(compiles.clone())));
+        gl
+    }
+
+    fn binaries(dir: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect()
+    }
+
+    fn linked(gl: &mut Wrapper, program: u32) -> bool {
+        gl.gl_get_programiv(program, ProgramParamType::LINK_STATUS).unwrap() == 1
+    }
+
+    #[test]
+    fn stored_programs_load_without_compiling() {
+        let dir = temp_dir("reload");
+        let compiles = Rc::new(Cell::new(0));
+        let attributes = [(0, "position".to_string())];
+
+        let mut gl = context(&compiles);
+        let cache = ProgramCache::new(&mut gl, &dir).unwrap();
+        assert_eq!(cache.support(), BinarySupport::Extension);
+        let program = cache.program(&mut gl, VERTEX, RED, &attributes).unwrap();
+        assert!(linked(&mut gl, program));
+        assert_eq!((compiles.get(), binaries(&dir).len()), (2, 1));
+
+        let mut gl = context(&compiles);
+        let cache = ProgramCache::new(&mut gl, &dir).unwrap();
+        let program = cache.program(&mut gl, VERTEX, RED, &attributes).unwrap();
+        assert!(linked(&mut gl, program));
+        assert_eq!(compiles.get(), 2);
+
+        cache.clear().unwrap();
+        assert!(binaries(&dir).is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_binary_stored_for_another_key_is_not_loaded() {
+        let dir = temp_dir("collision");
+        let compiles = Rc::new(Cell::new(0));
+        let mut gl = context(&compiles);
+        let cache = ProgramCache::new(&mut gl, &dir).unwrap();
+
+        cache.program(&mut gl, VERTEX, RED, &[]).unwrap();
+        // Stands in for a hash collision: the file of the green program holds the red one.
+        let red = cache.path(&cache.key(VERTEX, RED, &[]));
+        let green = cache.path(&cache.key(VERTEX, GREEN, &[]));
+        fs::copy(&red, &green).unwrap();
+
+        let program = cache.program(&mut gl, VERTEX, GREEN, &[]).unwrap();
+        assert!(linked(&mut gl, program));
+        assert_eq!(compiles.get(), 4);
+        let file = fs::read(&green).unwrap();
+        assert_eq!(parse(&file).unwrap().1, &cache.key(VERTEX, GREEN, &[])[..]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_program_that_can_not_be_stored_is_still_returned() {
+        let dir = temp_dir("unwritable");
+        let compiles = Rc::new(Cell::new(0));
+        let mut gl = context(&compiles);
+        let cache = ProgramCache::new(&mut gl, &dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let program = cache.program(&mut gl, VERTEX, RED, &[]).unwrap();
+        assert!(linked(&mut gl, program));
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn damaged_files_do_not_parse() {
+        let mut file = MAGIC.to_vec();
+        file.extend_from_slice(&VERSION.to_le_bytes());
+        file.extend_from_slice(&1u32.to_le_bytes());
+        file.extend_from_slice(&3u32.to_le_bytes());
+        file.extend_from_slice(b"keybinary");
+        assert_eq!(parse(&file), Some((1, &b"key"[..], &b"binary"[..])));
+
+        assert_eq!(parse(&file[..HEADER_SIZE + 3]), None);
+        file[12] = 0xff;
+        assert_eq!(parse(&file), None);
+        assert_eq!(parse(b"GLPB"), None);
+    }
+}
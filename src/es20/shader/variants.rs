@@ -0,0 +1,320 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use enums::ShaderType;
+use super::super::wrapper::{Error, Wrapper};
+use super::preprocess::{self, FileProvider, Options};
+use super::{compile_shader, link_program};
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// The feature defines of one permutation of a shader. Defines are kept sorted by name, so the
+/// order they are added in does not make a different variant.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Variant {
+    defines: BTreeMap<String, String>,
+}
+
+impl Variant {
+    pub fn new() -> Variant {
+        Variant::default()
+    }
+
+    /// Adds `#define name 1`.
+    pub fn flag(self, name: &str) -> Variant {
+        self.define(name, "1")
+    }
+
+    /// Adds `#define name value`, replacing an earlier define of `name`.
+    pub fn define(mut self, name: &str, value: &str) -> Variant {
+        self.defines.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn defines(&self) -> &BTreeMap<String, String> {
+        &self.defines
+    }
+}
+
+// The source of one stage.
+struct Stage {
+    type_: ShaderType,
+    name: String,
+    source: String,
+    // The identifiers of the source assembled without variant defines. Defines of other names,
+    // and not used by the value of a define that is kept, can not change the shader, so they are
+    // left out and variants differing only in them share the shader.
+    identifiers: Option<HashSet<String>>,
+}
+
+/// Programs built on demand from one vertex and one fragment shader source and the defines of a
+/// `Variant`.
+///
+/// Both sources go through `preprocess::preprocess` with the options and file provider of the
+/// cache, a variant's defines replacing those of the same name in the options. Identical
+/// preprocessed sources of a stage compile to one shader, and identical pairs of shaders link to
+/// one program. Compile and link errors are remembered per variant, with info log lines mapped
+/// back to the original files.
+pub struct ShaderVariantCache {
+    stages: [Stage; 2],
+    provider: Box<dyn FileProvider>,
+    options: Options,
+    attributes: Vec<(u32, String)>,
+    variants: HashMap<Variant, Result<u32, Error>>,
+    // By the type and the preprocessed source.
+    shaders: HashMap<(u32, String), u32>,
+    programs: HashMap<(u32, u32), u32>,
+    // The variants for `prewarm`, in the order they were declared.
+    declared: Vec<Variant>,
+}
+
+impl ShaderVariantCache {
+    /// A cache for the vertex shader `vertex` and fragment shader `fragment`, each with the file
+    /// name includes resolve against and info logs refer to.
+    pub fn new(vertex: (&str, &str), fragment: (&str, &str), provider: Box<dyn FileProvider>, options: Options)
+        -> ShaderVariantCache {
+        let stage = |type_, (name, source): (&str, &str)| Stage {
+            type_,
+            name: name.to_string(),
+            source: source.to_string(),
+            identifiers: None,
+        };
+
+        ShaderVariantCache {
+            stages: [stage(ShaderType::VERTEX_SHADER, vertex), stage(ShaderType::FRAGMENT_SHADER, fragment)],
+            provider,
+            options,
+            attributes: Vec::new(),
+            variants: HashMap::new(),
+            shaders: HashMap::new(),
+            programs: HashMap::new(),
+            declared: Vec::new(),
+        }
+    }
+
+    /// Binds attribute `name` to `index` in every program linked from now on.
+    pub fn bind_attrib_location(&mut self, index: u32, name: &str) {
+        self.attributes.push((index, name.to_string()));
+    }
+
+    /// The program of `variant`, compiled and linked on first use. A variant that failed returns
+    /// the same error again without another attempt.
+    pub fn program(&mut self, gl: &mut Wrapper, variant: &Variant) -> Result<u32, Error> {
+        if let Some(result) = self.variants.get(variant) {
+            return result.clone();
+        }
+
+        let result = self.build(gl, variant);
+        self.variants.insert(variant.clone(), result.clone());
+        result
+    }
+
+    /// Adds variants for `prewarm` to build.
+    pub fn declare(&mut self, variants: &[Variant]) {
+        for variant in variants {
+            if !self.declared.contains(variant) {
+                self.declared.push(variant.clone());
+            }
+        }
+    }
+
+    /// Builds up to `limit` of the declared variants that are not built yet, e.g. a few per
+    /// frame of a loading screen, and returns how many are left. Failures are kept for `program`
+    /// to report.
+    pub fn prewarm(&mut self, gl: &mut Wrapper, limit: usize) -> usize {
+        let unbuilt: Vec<Variant> = self.declared.iter()
+            .filter(|variant| !self.variants.contains_key(variant))
+            .cloned()
+            .collect();
+        for variant in unbuilt.iter().take(limit) {
+            let _ = self.program(gl, variant);
+        }
+        unbuilt.len().saturating_sub(limit)
+    }
+
+    /// Deletes all shaders and programs and forgets every variant, e.g. after the sources
+    /// changed. Declared variants stay declared, for `prewarm` to build again.
+    pub fn clear(&mut self, gl: &mut Wrapper) -> Result<(), Error> {
+        for (_, program) in self.programs.drain() {
+            gl.gl_delete_program(program)?;
+        }
+        for (_, shader) in self.shaders.drain() {
+            gl.gl_delete_shader(shader)?;
+        }
+        self.variants.clear();
+        for stage in &mut self.stages {
+            stage.identifiers = None;
+        }
+        Ok(())
+    }
+
+    /// Replaces the source of the stage of type `type_` and clears the cache.
+    pub fn set_source(&mut self, gl: &mut Wrapper, type_: ShaderType, source: &str) -> Result<(), Error> {
+        self.clear(gl)?;
+        for stage in &mut self.stages {
+            if stage.type_ == type_ {
+                stage.source = source.to_string();
+            }
+        }
+        Ok(())
+    }
+
+    /// The number of distinct shaders and programs the variants built so far share.
+    pub fn counts(&self) -> (usize, usize) {
+        (self.shaders.len(), self.programs.len())
+    }
+
+    fn build(&mut self, gl: &mut Wrapper, variant: &Variant) -> Result<u32, Error> {
+        let vertex = self.shader(gl, 0, variant)?;
+        let fragment = self.shader(gl, 1, variant)?;
+        if let Some(&program) = self.programs.get(&(vertex, fragment)) {
+            return Ok(program);
+        }
+
+        let program = link_program(gl, &[vertex, fragment], &self.attributes)?;
+        self.programs.insert((vertex, fragment), program);
+        Ok(program)
+    }
+
+    fn shader(&mut self, gl: &mut Wrapper, stage: usize, variant: &Variant) -> Result<u32, Error> {
+        let stage = &mut self.stages[stage];
+        if stage.identifiers.is_none() {
+            let base = preprocess::preprocess(&stage.source, &stage.name, self.provider.as_mut(), &self.options)?;
+            stage.identifiers = Some(identifiers(&base.source));
+        }
+
+        // A define can also be used only through the value of another one, so keep adding the
+        // identifiers of kept values until nothing changes.
+        let mut used = stage.identifiers.clone().unwrap();
+        let mut kept = BTreeMap::new();
+        loop {
+            let added: Vec<_> = variant.defines.iter()
+                .filter(|define| used.contains(define.0) && !kept.contains_key(define.0))
+                .collect();
+            if added.is_empty() {
+                break;
+            }
+            for (name, value) in added {
+                used.extend(identifiers(value));
+                kept.insert(name.clone(), value.clone());
+            }
+        }
+
+        let mut options = self.options.clone();
+        options.defines.retain(|define| !kept.contains_key(&define.0));
+        options.defines.extend(kept);
+        let preprocessed = preprocess::preprocess(&stage.source, &stage.name, self.provider.as_mut(), &options)?;
+
+        let key = (stage.type_ as u32, preprocessed.source);
+        if let Some(&shader) = self.shaders.get(&key) {
+            return Ok(shader);
+        }
+
+        let shader = compile_shader(gl, stage.type_, &key.1, Some(&preprocessed.line_map))?;
+        self.shaders.insert(key, shader);
+        Ok(shader)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// FUNCTIONS
+// -------------------------------------------------------------------------------------------------
+
+fn identifiers(source: &str) -> HashSet<String> {
+    source.split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|word| word.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_'))
+        .map(str::to_string)
+        .collect()
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use es20::backend::Backend;
+    use es20::call::Call;
+    use es20::software::SoftwareBackend;
+    use es20::wrapper::{ErrorKind, Interceptor};
+
+    const VERTEX: &str = "attribute vec2 position;\nvoid main() { gl_Position = vec4(position, 0.0, 1.0); }\n";
+    const FRAGMENT: &str = "precision mediump float;\n#ifndef TINT\n#define TINT 1.0\n#endif\n#ifdef BROKEN\n\
+                            void main() { gl_FragColor = nope; }\n#else\nvoid main() { gl_FragColor = vec4(TINT); }\n\
+                            #endif\n";
+
+    // Counts the shaders compiled through the wrapper.
+    struct Compiles(Rc<Cell<usize>>);
+
+    impl Interceptor for Compiles {
+        fn intercept(&mut self, call: &Call, _backend: &mut dyn Backend) -> Result<(), Error> {
+            if call.name == "glCompileShader" {
+                self.0.set(self.0.get() + 1);
+            }
+            Ok(())
+        }
+    }
+
+    fn cache() -> (Wrapper, ShaderVariantCache, Rc<Cell<usize>>) {
+        let compiles = Rc::new(Cell::new(0));
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(1, 1)));
+        gl.add_interceptor(Box::new(Compiles(compiles.clone())));
+
+        let provider = Box::new(HashMap::<String, String>::new());
+        let cache = ShaderVariantCache::new(("shader.vert", VERTEX), ("shader.frag", FRAGMENT), provider,
+                                            Options::default());
+        (gl, cache, compiles)
+    }
+
+    #[test]
+    fn variants_share_shaders_and_programs() {
+        let (mut gl, mut cache, compiles) = cache();
+        let base = cache.program(&mut gl, &Variant::new()).unwrap();
+        assert_eq!((cache.counts(), compiles.get()), ((2, 1), 2));
+
+        // Hits, also for a define neither source uses.
+        assert_eq!(cache.program(&mut gl, &Variant::new()).unwrap(), base);
+        assert_eq!(cache.program(&mut gl, &Variant::new().flag("UNUSED")).unwrap(), base);
+        assert_eq!((cache.counts(), compiles.get()), ((2, 1), 2));
+
+        // A miss that only changes the fragment shader.
+        let tinted = cache.program(&mut gl, &Variant::new().define("TINT", "0.5")).unwrap();
+        assert_ne!(tinted, base);
+        assert_eq!((cache.counts(), compiles.get()), ((3, 2), 3));
+    }
+
+    #[test]
+    fn variant_defines_replace_option_defines() {
+        let mut options = Options::default();
+        options.defines.push(("TINT".to_string(), "1.0".to_string()));
+        let provider = Box::new(HashMap::<String, String>::new());
+        let fragment = "precision mediump float;\nvoid main() { gl_FragColor = vec4(TINT); }\n";
+        let mut cache = ShaderVariantCache::new(("shader.vert", VERTEX), ("shader.frag", fragment), provider, options);
+
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(1, 1)));
+        assert!(cache.program(&mut gl, &Variant::new().define("TINT", "0.5")).is_ok());
+    }
+
+    #[test]
+    fn failed_variants_are_remembered() {
+        let (mut gl, mut cache, compiles) = cache();
+        let broken = Variant::new().flag("BROKEN");
+
+        let error = cache.program(&mut gl, &broken).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Compile);
+        assert!(error.message.contains("shader.frag"));
+        assert_eq!(compiles.get(), 2);
+
+        assert_eq!(cache.program(&mut gl, &broken).unwrap_err().message, error.message);
+        assert_eq!(compiles.get(), 2);
+
+        cache.declare(&[broken, Variant::new()]);
+        assert_eq!(cache.prewarm(&mut gl, 8), 0);
+        assert_eq!(compiles.get(), 3);
+    }
+}
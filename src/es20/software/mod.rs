@@ -0,0 +1,3262 @@
+// The safety contract is the same for every entry point and is documented once on `Backend`.
+#![allow(clippy::missing_safety_doc)]
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::ffi::{CStr, CString};
+use std::{mem, ptr, slice};
+
+use types::*;
+use consts::*;
+use enums::{PixelDataType, PixelFormat, ShaderType};
+use format::FormatInfo;
+
+use super::backend::Backend;
+use super::glsl;
+use super::texture::decode;
+use super::texture::mipmap::{self, MipmapOptions};
+
+use self::program::{LinkedProgram, ProgramRegistry, Stages, TextureUnits, Vertex};
+use self::raster::{clamp, State, StencilState, Targets, LINE_WIDTH_RANGE, POINT_SIZE_RANGE};
+use self::sampler::{mip_count, Surface, TextureObject};
+
+mod pixels;
+pub mod program;
+mod raster;
+mod sampler;
+
+const MAX_VERTEX_ATTRIBS: usize = 16;
+const MAX_TEXTURE_UNITS: usize = 8;
+const MAX_TEXTURE_SIZE: GLsizei = 4096;
+const MAX_UNIFORM_VECTORS: GLint = 256;
+const MAX_VARYING_VECTORS: GLint = 16;
+
+const LIMITS: glsl::Limits = glsl::Limits {
+    max_vertex_attribs: MAX_VERTEX_ATTRIBS,
+    max_vertex_uniform_vectors: MAX_UNIFORM_VECTORS as usize,
+    max_varying_vectors: MAX_VARYING_VECTORS as usize,
+    max_vertex_output_vectors: MAX_VARYING_VECTORS as usize,
+    max_fragment_input_vectors: MAX_VARYING_VECTORS as usize,
+    max_vertex_texture_image_units: MAX_TEXTURE_UNITS,
+    max_combined_texture_image_units: MAX_TEXTURE_UNITS,
+    max_texture_image_units: MAX_TEXTURE_UNITS,
+    max_fragment_uniform_vectors: MAX_UNIFORM_VECTORS as usize,
+    max_draw_buffers: 1,
+    min_program_texel_offset: -8,
+    max_program_texel_offset: 7,
+};
+
+// Internal names of the default 2D and cube map textures, which GL calls 0.
+const DEFAULT_TEXTURE_2D: GLuint = GLuint::MAX;
+const DEFAULT_TEXTURE_CUBE: GLuint = GLuint::MAX - 1;
+
+const VENDOR: &str = "opengl_es_rs";
+const RENDERER: &str = "software rasterizer";
+const VERSION: &str = "OpenGL ES 2.0 software";
+const SHADING_LANGUAGE_VERSION: &str = "OpenGL ES GLSL ES 1.00";
+const EXTENSIONS: &str = "GL_OES_rgb8_rgba8 GL_OES_element_index_uint GL_OES_compressed_ETC1_RGB8_texture \
+                          GL_KHR_texture_compression_astc_ldr GL_OES_get_program_binary \
+                          GL_OES_vertex_array_object";
+// The one program binary format, which holds the sources the program was linked from. Not a
+// registered enum value.
+const PROGRAM_BINARY_FORMAT: GLenum = 0x5357_0001;
+const PROGRAM_BINARY_MAGIC: &[u8] = b"SWPB";
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// A `Backend` that renders in software, for running rendering tests where there is no GPU.
+///
+/// It implements the ES 2.0 pipeline with its core limits: non-power-of-two textures are
+/// restricted, and the only extensions are 8-bit renderbuffers, 32-bit indices and the compressed
+/// formats `texture::decode` handles. The default framebuffer is RGBA8 with a 24-bit depth and an
+/// 8-bit stencil buffer.
+///
+/// Shaders are compiled by `es20::glsl` and run by its interpreter. A program that has a
+/// `ClosureProgram` registered for it in `programs()` takes its stages from that instead when it is
+/// linked, whatever shaders are attached.
+pub struct SoftwareBackend {
+    registry: ProgramRegistry,
+    state: State,
+    error: GLenum,
+    next_name: GLuint,
+    buffers: HashMap<GLuint, BufferObject>,
+    textures: HashMap<GLuint, TextureObject>,
+    renderbuffers: HashMap<GLuint, RenderbufferObject>,
+    framebuffers: HashMap<GLuint, FramebufferObject>,
+    shaders: HashMap<GLuint, ShaderObject>,
+    programs: HashMap<GLuint, ProgramObject>,
+    // Color, depth and stencil.
+    default_framebuffer: [Surface; 3],
+    array_buffer: GLuint,
+    element_array_buffer: GLuint,
+    framebuffer: GLuint,
+    renderbuffer: GLuint,
+    active_texture: usize,
+    // The 2D and cube map texture bound to each unit.
+    texture_units: [[GLuint; 2]; MAX_TEXTURE_UNITS],
+    current_program: GLuint,
+    attributes: [AttributeArray; MAX_VERTEX_ATTRIBS],
+    current_attributes: [[f32; 4]; MAX_VERTEX_ATTRIBS],
+    // Every vertex array object, and 0 once another one was bound. The state of the bound one
+    // is in `attributes` and `element_array_buffer` and is copied here when it is unbound.
+    vertex_array: GLuint,
+    vertex_arrays: HashMap<GLuint, VertexArrayObject>,
+    strings: HashMap<GLenum, CString>,
+}
+
+#[derive(Default)]
+struct BufferObject {
+    bound: bool,
+    data: Vec<u8>,
+    usage: GLenum,
+}
+
+#[derive(Default)]
+struct RenderbufferObject {
+    bound: bool,
+    surface: Surface,
+}
+
+#[derive(Default)]
+struct FramebufferObject {
+    bound: bool,
+    // Color, depth and stencil.
+    attachments: [Attachment; 3],
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+enum Attachment {
+    #[default]
+    None,
+    Texture { name: GLuint, target: GLenum, face: usize, level: usize },
+    Renderbuffer(GLuint),
+}
+
+// Where the image behind an attachment lives.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ImageRef {
+    Default(usize),
+    Renderbuffer(GLuint),
+    Texture(GLuint, usize, usize),
+}
+
+struct ShaderObject {
+    type_: GLenum,
+    source: String,
+    compiled: bool,
+    info_log: String,
+    // The result of the last successful compile, which linking uses, and its source.
+    code: Option<Rc<glsl::Shader>>,
+    compiled_source: String,
+    delete_pending: bool,
+}
+
+#[derive(Default)]
+struct ProgramObject {
+    shaders: Vec<GLuint>,
+    bindings: HashMap<String, GLuint>,
+    linked: bool,
+    validated: bool,
+    info_log: String,
+    delete_pending: bool,
+    // Survives a failed relink while the program is current, as GL requires.
+    executable: Option<LinkedProgram>,
+    // What `glGetProgramBinary` returns, for programs linked from GLSL.
+    binary: Option<Vec<u8>>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct VertexArrayObject {
+    attributes: [AttributeArray; MAX_VERTEX_ATTRIBS],
+    element_array_buffer: GLuint,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct AttributeArray {
+    enabled: bool,
+    size: GLint,
+    type_: GLenum,
+    normalized: bool,
+    stride: GLsizei,
+    // A client address, or an offset into `buffer`.
+    pointer: usize,
+    buffer: GLuint,
+}
+
+impl Default for AttributeArray {
+    fn default() -> AttributeArray {
+        AttributeArray {
+            enabled: false,
+            size: 4,
+            type_: GL_FLOAT,
+            normalized: false,
+            stride: 0,
+            pointer: 0,
+            buffer: 0,
+        }
+    }
+}
+
+impl AttributeArray {
+    fn component_size(&self) -> usize {
+        match self.type_ {
+            GL_BYTE | GL_UNSIGNED_BYTE => 1,
+            GL_SHORT | GL_UNSIGNED_SHORT => 2,
+            _ => 4,
+        }
+    }
+
+    fn effective_stride(&self) -> usize {
+        if self.stride == 0 { self.size as usize * self.component_size() } else { self.stride as usize }
+    }
+}
+
+// How a state value converts between the `glGet*v` flavours.
+enum Query {
+    Ints(Vec<GLint>),
+    Floats(Vec<GLfloat>),
+    // Values in [0, 1] that integer queries scale to the full range.
+    Normalized(Vec<GLfloat>),
+    Bools(Vec<bool>),
+}
+
+impl SoftwareBackend {
+    pub fn new(width: u32, height: u32) -> SoftwareBackend {
+        let (width, height) = (width as usize, height as usize);
+        let mut textures = HashMap::new();
+        let mut default_2d = TextureObject::default();
+        default_2d.bind(GL_TEXTURE_2D);
+        let mut default_cube = TextureObject::default();
+        default_cube.bind(GL_TEXTURE_CUBE_MAP);
+        textures.insert(DEFAULT_TEXTURE_2D, default_2d);
+        textures.insert(DEFAULT_TEXTURE_CUBE, default_cube);
+
+        SoftwareBackend {
+            registry: ProgramRegistry::default(),
+            state: State::new(width as GLint, height as GLint),
+            error: GL_NO_ERROR,
+            next_name: 1,
+            buffers: HashMap::new(),
+            textures,
+            renderbuffers: HashMap::new(),
+            framebuffers: HashMap::new(),
+            shaders: HashMap::new(),
+            programs: HashMap::new(),
+            default_framebuffer: [
+                Surface::color(width, height, GL_RGBA8, [8, 8, 8, 8]),
+                Surface::depth(width, height, GL_DEPTH_COMPONENT24, 24),
+                Surface::stencil(width, height, GL_STENCIL_INDEX8),
+            ],
+            array_buffer: 0,
+            element_array_buffer: 0,
+            framebuffer: 0,
+            renderbuffer: 0,
+            active_texture: 0,
+            texture_units: [[0; 2]; MAX_TEXTURE_UNITS],
+            current_program: 0,
+            attributes: [AttributeArray::default(); MAX_VERTEX_ATTRIBS],
+            current_attributes: [[0.0, 0.0, 0.0, 1.0]; MAX_VERTEX_ATTRIBS],
+            vertex_array: 0,
+            vertex_arrays: HashMap::new(),
+            strings: HashMap::new(),
+        }
+    }
+
+    /// A handle for registering the closures of programs, which stays usable after the backend
+    /// has been moved into a `Wrapper`.
+    pub fn programs(&self) -> ProgramRegistry {
+        self.registry.clone()
+    }
+
+    // Records `code` unless an earlier error is still pending, like GL's single error flag.
+    fn error(&mut self, code: GLenum) {
+        if self.error == GL_NO_ERROR {
+            self.error = code;
+        }
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // OBJECTS
+    // -------------------------------------------------------------------------------------------------
+
+    // Names come from one counter shared by all object kinds, skipping any that are taken.
+    fn new_name(&mut self) -> GLuint {
+        loop {
+            let name = self.next_name;
+            self.next_name += 1;
+            let taken = self.buffers.contains_key(&name) || self.textures.contains_key(&name)
+                || self.renderbuffers.contains_key(&name) || self.framebuffers.contains_key(&name)
+                || self.shaders.contains_key(&name) || self.programs.contains_key(&name)
+                || self.vertex_arrays.contains_key(&name);
+            if !taken {
+                return name;
+            }
+        }
+    }
+
+    fn texture_key(name: GLuint, target: GLenum) -> GLuint {
+        match (name, target) {
+            (0, GL_TEXTURE_CUBE_MAP) => DEFAULT_TEXTURE_CUBE,
+            (0, _) => DEFAULT_TEXTURE_2D,
+            (name, _) => name,
+        }
+    }
+
+    // The texture bound to `target` on the active unit, by its internal name.
+    fn bound_texture(&self, target: GLenum) -> GLuint {
+        let index = (target == GL_TEXTURE_CUBE_MAP) as usize;
+        SoftwareBackend::texture_key(self.texture_units[self.active_texture][index], target)
+    }
+
+    // The texture an image target belongs to, and the face it selects.
+    fn image_target(&mut self, target: GLenum) -> Option<(GLuint, usize)> {
+        match target {
+            GL_TEXTURE_2D => Some((self.bound_texture(GL_TEXTURE_2D), 0)),
+            GL_TEXTURE_CUBE_MAP_POSITIVE_X..=GL_TEXTURE_CUBE_MAP_NEGATIVE_Z => {
+                Some((self.bound_texture(GL_TEXTURE_CUBE_MAP), (target - GL_TEXTURE_CUBE_MAP_POSITIVE_X) as usize))
+            }
+            _ => {
+                self.error(GL_INVALID_ENUM);
+                None
+            }
+        }
+    }
+
+    // The shared checks of glTexImage2D and friends. Returns the texture and face on success.
+    fn check_image(&mut self, target: GLenum, level: GLint, width: GLsizei, height: GLsizei, border: GLint)
+        -> Option<(GLuint, usize)> {
+        let (texture, face) = self.image_target(target)?;
+        let max_level = mip_count(MAX_TEXTURE_SIZE as usize, 1) as GLint;
+
+        if level < 0 || level >= max_level || width < 0 || height < 0 || width > MAX_TEXTURE_SIZE
+            || height > MAX_TEXTURE_SIZE || border != 0 || (target != GL_TEXTURE_2D && width != height) {
+            self.error(GL_INVALID_VALUE);
+            return None;
+        }
+
+        Some((texture, face))
+    }
+
+    // The existing level a sub-image call writes to, after checking that the rectangle fits.
+    fn check_sub_image(&mut self, target: GLenum, level: GLint, x: GLint, y: GLint, width: GLsizei,
+                       height: GLsizei) -> Option<(GLuint, usize)> {
+        let (texture, face) = self.image_target(target)?;
+        let size = match self.textures[&texture].level(face, level.max(0) as usize) {
+            Some(image) if level >= 0 => (image.width as i64, image.height as i64),
+            _ => {
+                self.error(if level < 0 { GL_INVALID_VALUE } else { GL_INVALID_OPERATION });
+                return None;
+            }
+        };
+
+        if x < 0 || y < 0 || width < 0 || height < 0 || x as i64 + width as i64 > size.0
+            || y as i64 + height as i64 > size.1 {
+            self.error(GL_INVALID_VALUE);
+            return None;
+        }
+
+        Some((texture, face))
+    }
+
+    fn attachment_point(&mut self, target: GLenum, attachment: GLenum) -> Option<usize> {
+        if target != GL_FRAMEBUFFER {
+            self.error(GL_INVALID_ENUM);
+            return None;
+        }
+
+        let point = match attachment {
+            GL_COLOR_ATTACHMENT0 => 0,
+            GL_DEPTH_ATTACHMENT => 1,
+            GL_STENCIL_ATTACHMENT => 2,
+            _ => {
+                self.error(GL_INVALID_ENUM);
+                return None;
+            }
+        };
+
+        if self.framebuffer == 0 {
+            self.error(GL_INVALID_OPERATION);
+            return None;
+        }
+
+        Some(point)
+    }
+
+    fn detach_everywhere(&mut self, is_match: &dyn Fn(&Attachment) -> bool) {
+        if let Some(framebuffer) = self.framebuffers.get_mut(&self.framebuffer) {
+            for attachment in framebuffer.attachments.iter_mut() {
+                if is_match(attachment) {
+                    *attachment = Attachment::None;
+                }
+            }
+        }
+    }
+
+    fn delete_shader_if_unused(&mut self, shader: GLuint) {
+        let attached = self.programs.values().any(|program| program.shaders.contains(&shader));
+        if !attached && self.shaders.get(&shader).is_some_and(|shader| shader.delete_pending) {
+            self.shaders.remove(&shader);
+        }
+    }
+
+    fn delete_program_now(&mut self, program: GLuint) {
+        if let Some(object) = self.programs.remove(&program) {
+            for shader in object.shaders {
+                self.delete_shader_if_unused(shader);
+            }
+        }
+    }
+
+    // Looks up a program name, raising the error GL raises for shader names or unknown ones.
+    fn program_object(&mut self, program: GLuint) -> Option<&mut ProgramObject> {
+        if !self.programs.contains_key(&program) {
+            self.error(if self.shaders.contains_key(&program) { GL_INVALID_OPERATION } else { GL_INVALID_VALUE });
+            return None;
+        }
+        self.programs.get_mut(&program)
+    }
+
+    fn shader_object(&mut self, shader: GLuint) -> Option<&mut ShaderObject> {
+        if !self.shaders.contains_key(&shader) {
+            self.error(if self.programs.contains_key(&shader) { GL_INVALID_OPERATION } else { GL_INVALID_VALUE });
+            return None;
+        }
+        self.shaders.get_mut(&shader)
+    }
+
+    // Links the compiled GLSL of the vertex and fragment shader attached to `program`.
+    // The linked GLSL of `program` and its binary.
+    fn link_shaders(&self, program: GLuint) -> Result<(glsl::Program, Vec<u8>), String> {
+        let (mut vertex, mut fragment) = (None, None);
+        for shader in &self.programs[&program].shaders {
+            let object = &self.shaders[shader];
+            let code = match object.code {
+                Some(ref code) if object.compiled => (code.clone(), object.compiled_source.as_str()),
+                _ => return Err(format!("shader {} is not compiled", shader)),
+            };
+            if object.type_ == GL_VERTEX_SHADER { vertex = Some(code) } else { fragment = Some(code) }
+        }
+
+        match (vertex, fragment) {
+            (Some(vertex), Some(fragment)) => {
+                let binary = encode_binary(vertex.1, fragment.1, &self.programs[&program].bindings);
+                glsl::link(&vertex.0, &fragment.0).map(|linked| (linked, binary))
+            }
+            (None, _) => Err("no vertex shader is attached".to_string()),
+            (_, None) => Err("no fragment shader is attached".to_string()),
+        }
+    }
+
+    // Records the outcome of linking `program`, from source or a binary.
+    fn set_link_result(&mut self, program: GLuint, result: Result<(LinkedProgram, Option<Vec<u8>>), String>) {
+        let current = self.current_program == program;
+        let object = self.programs.get_mut(&program).unwrap();
+        match result {
+            Ok((linked, binary)) => {
+                object.linked = true;
+                object.info_log = String::new();
+                object.executable = Some(linked);
+                object.binary = binary;
+            }
+            Err(message) => {
+                object.linked = false;
+                object.info_log = message;
+                object.binary = None;
+                if !current {
+                    object.executable = None;
+                }
+            }
+        }
+    }
+
+    // The linked program a query about `program` refers to.
+    fn linked_program(&mut self, program: GLuint) -> Option<&LinkedProgram> {
+        let linked = self.program_object(program)?.linked;
+        if !linked {
+            self.error(GL_INVALID_OPERATION);
+            return None;
+        }
+        self.programs[&program].executable.as_ref()
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // FRAMEBUFFERS
+    // -------------------------------------------------------------------------------------------------
+
+    fn attachment_images(&self) -> [Option<ImageRef>; 3] {
+        if self.framebuffer == 0 {
+            return [Some(ImageRef::Default(0)), Some(ImageRef::Default(1)), Some(ImageRef::Default(2))];
+        }
+
+        let mut images = [None; 3];
+        if let Some(framebuffer) = self.framebuffers.get(&self.framebuffer) {
+            for (image, attachment) in images.iter_mut().zip(framebuffer.attachments.iter()) {
+                *image = match *attachment {
+                    Attachment::None => None,
+                    Attachment::Texture { name, face, level, .. } => Some(ImageRef::Texture(name, face, level)),
+                    Attachment::Renderbuffer(name) => Some(ImageRef::Renderbuffer(name)),
+                };
+            }
+        }
+        images
+    }
+
+    fn surface(&self, image: ImageRef) -> Option<&Surface> {
+        match image {
+            ImageRef::Default(index) => Some(&self.default_framebuffer[index]),
+            ImageRef::Renderbuffer(name) => self.renderbuffers.get(&name).map(|renderbuffer| &renderbuffer.surface),
+            ImageRef::Texture(name, face, level) => {
+                self.textures.get(&name).and_then(|texture| texture.faces.get(face)).and_then(|levels| levels.get(level))
+            }
+        }
+    }
+
+    fn surface_mut(&mut self, image: ImageRef) -> Option<&mut Surface> {
+        match image {
+            ImageRef::Default(index) => Some(&mut self.default_framebuffer[index]),
+            ImageRef::Renderbuffer(name) => {
+                self.renderbuffers.get_mut(&name).map(|renderbuffer| &mut renderbuffer.surface)
+            }
+            ImageRef::Texture(name, face, level) => self.textures.get_mut(&name)
+                .and_then(|texture| texture.faces.get_mut(face))
+                .and_then(|levels| levels.get_mut(level)),
+        }
+    }
+
+    // The color buffer reads come from.
+    fn read_surface(&self) -> Option<&Surface> {
+        self.attachment_images()[0].and_then(|image| self.surface(image))
+    }
+
+    fn framebuffer_status(&self) -> GLenum {
+        if self.framebuffer == 0 {
+            return GL_FRAMEBUFFER_COMPLETE;
+        }
+
+        let mut size = None;
+        for (point, image) in self.attachment_images().iter().enumerate() {
+            let image = match *image {
+                Some(image) => image,
+                None => continue,
+            };
+
+            let surface = match self.surface(image) {
+                Some(surface) if !surface.is_empty() => surface,
+                _ => return GL_FRAMEBUFFER_INCOMPLETE_ATTACHMENT,
+            };
+
+            let renderable = match point {
+                0 => match image {
+                    ImageRef::Texture(..) => surface.internal_format == GL_RGB || surface.internal_format == GL_RGBA,
+                    _ => !surface.color.is_empty(),
+                },
+                1 => surface.depth_bits > 0,
+                _ => surface.stencil_bits > 0,
+            };
+            if !renderable {
+                return GL_FRAMEBUFFER_INCOMPLETE_ATTACHMENT;
+            }
+
+            match size {
+                None => size = Some((surface.width, surface.height)),
+                Some(size) if size != (surface.width, surface.height) => return GL_FRAMEBUFFER_INCOMPLETE_DIMENSIONS,
+                Some(_) => {}
+            }
+        }
+
+        if size.is_none() { GL_FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT } else { GL_FRAMEBUFFER_COMPLETE }
+    }
+
+    // Raises `GL_INVALID_FRAMEBUFFER_OPERATION` when the bound framebuffer can not be used.
+    fn check_framebuffer(&mut self) -> bool {
+        if self.framebuffer_status() != GL_FRAMEBUFFER_COMPLETE {
+            self.error(GL_INVALID_FRAMEBUFFER_OPERATION);
+            return false;
+        }
+        true
+    }
+
+    // Moves the images of the bound framebuffer out of their owners for a draw or clear.
+    fn take_targets(&mut self) -> (Targets, [Option<ImageRef>; 3]) {
+        let images = self.attachment_images();
+        let mut targets = Targets::default();
+
+        for (point, image) in images.iter().enumerate() {
+            if let Some(surface) = image.and_then(|image| self.surface_mut(image)) {
+                let surface = mem::take(surface);
+                targets.width = surface.width;
+                targets.height = surface.height;
+                match point {
+                    0 => targets.color = Some(surface),
+                    1 => targets.depth = Some(surface),
+                    _ => targets.stencil = Some(surface),
+                }
+            }
+        }
+
+        (targets, images)
+    }
+
+    fn restore_targets(&mut self, targets: Targets, images: [Option<ImageRef>; 3]) {
+        let surfaces = vec![targets.color, targets.depth, targets.stencil];
+        for (surface, image) in surfaces.into_iter().zip(images.iter()) {
+            if let (Some(surface), Some(image)) = (surface, *image) {
+                if let Some(slot) = self.surface_mut(image) {
+                    *slot = surface;
+                }
+            }
+        }
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // DRAWING
+    // -------------------------------------------------------------------------------------------------
+
+    fn check_mode(&mut self, mode: GLenum) -> bool {
+        match mode {
+            GL_POINTS | GL_LINES | GL_LINE_STRIP | GL_LINE_LOOP | GL_TRIANGLES | GL_TRIANGLE_STRIP
+            | GL_TRIANGLE_FAN => true,
+            _ => {
+                self.error(GL_INVALID_ENUM);
+                false
+            }
+        }
+    }
+
+    unsafe fn draw(&mut self, mode: GLenum, indices: &[usize]) {
+        if !self.check_framebuffer() || indices.is_empty() {
+            return;
+        }
+
+        let samplers = match self.programs.get(&self.current_program).and_then(|program| program.executable.as_ref()) {
+            Some(program) => program.sampler_units(),
+            None => return,
+        };
+
+        // Samplers of different types must not share a unit.
+        for &(type_, unit) in &samplers {
+            if samplers.iter().any(|&(other, other_unit)| other_unit == unit && other != type_) {
+                self.error(GL_INVALID_OPERATION);
+                return;
+            }
+        }
+
+        let max_index = indices.iter().cloned().max().unwrap_or(0);
+        if !self.check_attribute_ranges(max_index) {
+            self.error(GL_INVALID_OPERATION);
+            return;
+        }
+
+        let (mut targets, images) = self.take_targets();
+
+        for unit in 0..MAX_TEXTURE_UNITS {
+            for (index, &target) in [GL_TEXTURE_2D, GL_TEXTURE_CUBE_MAP].iter().enumerate() {
+                let key = SoftwareBackend::texture_key(self.texture_units[unit][index], target);
+                if let Some(texture) = self.textures.get_mut(&key) {
+                    texture.update_completeness();
+                }
+            }
+        }
+
+        {
+            let program = self.programs[&self.current_program].executable.as_ref().unwrap();
+            let units = TextureUnits {
+                units: self.texture_units.iter().map(|bindings| [
+                    self.textures.get(&SoftwareBackend::texture_key(bindings[0], GL_TEXTURE_2D)),
+                    self.textures.get(&SoftwareBackend::texture_key(bindings[1], GL_TEXTURE_CUBE_MAP)),
+                ]).collect(),
+            };
+
+            let mut slots: HashMap<usize, usize> = HashMap::new();
+            let mut outputs = Vec::new();
+            let mut order = Vec::with_capacity(indices.len());
+            let mut values = self.current_attributes;
+
+            for &index in indices {
+                let slot = match slots.get(&index) {
+                    Some(&slot) => slot,
+                    None => {
+                        self.fetch_attributes(index, &mut values);
+                        let vertex = Vertex {
+                            attributes: &values,
+                            locations: &program.locations,
+                            uniforms: &program.uniforms,
+                            textures: &units,
+                            depth_range: self.state.depth_range,
+                        };
+                        outputs.push(program.run_vertex(&vertex));
+                        slots.insert(index, outputs.len() - 1);
+                        outputs.len() - 1
+                    }
+                };
+                order.push(slot);
+            }
+
+            raster::draw(&self.state, program, &units, &mut targets, mode, &outputs, &order);
+        }
+
+        self.restore_targets(targets, images);
+    }
+
+    // Whether every enabled array backed by a buffer holds `max_index + 1` vertices.
+    fn check_attribute_ranges(&self, max_index: usize) -> bool {
+        self.attributes.iter().filter(|array| array.enabled).all(|array| {
+            let needed = array.pointer + max_index * array.effective_stride() + array.size as usize * array.component_size();
+            match array.buffer {
+                0 => array.pointer != 0,
+                buffer => self.buffers.get(&buffer).is_some_and(|buffer| needed <= buffer.data.len()),
+            }
+        })
+    }
+
+    unsafe fn fetch_attributes(&self, index: usize, values: &mut [[f32; 4]; MAX_VERTEX_ATTRIBS]) {
+        for (location, array) in self.attributes.iter().enumerate() {
+            if !array.enabled {
+                values[location] = self.current_attributes[location];
+                continue;
+            }
+
+            let base = match array.buffer {
+                0 => array.pointer as *const u8,
+                buffer => self.buffers[&buffer].data.as_ptr().add(array.pointer),
+            };
+            let element = base.add(index * array.effective_stride());
+
+            let mut value = [0.0, 0.0, 0.0, 1.0];
+            for (component, slot) in value.iter_mut().enumerate().take(array.size as usize) {
+                let source = element.add(component * array.component_size());
+                let normalized = array.normalized;
+                *slot = match array.type_ {
+                    GL_BYTE => {
+                        let raw = ptr::read_unaligned(source as *const i8) as f32;
+                        if normalized { (2.0 * raw + 1.0) / 255.0 } else { raw }
+                    }
+                    GL_UNSIGNED_BYTE => {
+                        let raw = *source as f32;
+                        if normalized { raw / 255.0 } else { raw }
+                    }
+                    GL_SHORT => {
+                        let raw = ptr::read_unaligned(source as *const i16) as f32;
+                        if normalized { (2.0 * raw + 1.0) / 65535.0 } else { raw }
+                    }
+                    GL_UNSIGNED_SHORT => {
+                        let raw = ptr::read_unaligned(source as *const u16) as f32;
+                        if normalized { raw / 65535.0 } else { raw }
+                    }
+                    GL_FIXED => ptr::read_unaligned(source as *const i32) as f32 / 65536.0,
+                    _ => ptr::read_unaligned(source as *const f32),
+                };
+            }
+            values[location] = value;
+        }
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // UNIFORMS
+    // -------------------------------------------------------------------------------------------------
+
+    // The common part of the glUniform* family. `components` is the vector size, or the matrix
+    // dimension for `matrix`.
+    fn uniform(&mut self, location: GLint, count: GLsizei, components: usize, matrix: bool, floats: Option<&[f32]>,
+               ints: Option<&[i32]>) {
+        if count < 0 {
+            self.error(GL_INVALID_VALUE);
+            return;
+        }
+
+        let current = self.current_program;
+        let program = match self.programs.get(&current) {
+            Some(program) if program.linked => program,
+            _ => {
+                self.error(GL_INVALID_OPERATION);
+                return;
+            }
+        };
+
+        if location == -1 {
+            return;
+        }
+
+        let program = program.executable.as_ref().unwrap();
+        let (type_, remaining, array) = match program.uniform_at(location) {
+            Some(uniform) if location >= 0 => uniform,
+            _ => {
+                self.error(GL_INVALID_OPERATION);
+                return;
+            }
+        };
+
+        let float = floats.is_some();
+        let accepted = match type_ {
+            GL_FLOAT | GL_FLOAT_VEC2 | GL_FLOAT_VEC3 | GL_FLOAT_VEC4 => float && !matrix,
+            GL_INT | GL_INT_VEC2 | GL_INT_VEC3 | GL_INT_VEC4 | GL_SAMPLER_2D | GL_SAMPLER_CUBE => !float,
+            GL_BOOL | GL_BOOL_VEC2 | GL_BOOL_VEC3 | GL_BOOL_VEC4 => !matrix,
+            _ => matrix,
+        };
+        let expected = match type_ {
+            GL_FLOAT | GL_INT | GL_BOOL | GL_SAMPLER_2D | GL_SAMPLER_CUBE => 1,
+            GL_FLOAT_VEC2 | GL_INT_VEC2 | GL_BOOL_VEC2 | GL_FLOAT_MAT2 => 2,
+            GL_FLOAT_VEC3 | GL_INT_VEC3 | GL_BOOL_VEC3 | GL_FLOAT_MAT3 => 3,
+            _ => 4,
+        };
+
+        // Only arrays take more than one element.
+        if !accepted || expected != components || (count > 1 && !array) {
+            self.error(GL_INVALID_OPERATION);
+            return;
+        }
+
+        if (type_ == GL_SAMPLER_2D || type_ == GL_SAMPLER_CUBE)
+            && ints.is_some_and(|ints| ints.iter().any(|&unit| unit < 0 || unit as usize >= MAX_TEXTURE_UNITS)) {
+            self.error(GL_INVALID_VALUE);
+            return;
+        }
+
+        let elements = (count as usize).min(remaining);
+        let values = if matrix { components * components } else { components } * elements;
+        let program = self.programs.get_mut(&current).unwrap().executable.as_mut().unwrap();
+        program.set_uniform(location, floats.map(|floats| &floats[..values]), ints.map(|ints| &ints[..values]));
+    }
+
+    // -------------------------------------------------------------------------------------------------
+    // QUERIES
+    // -------------------------------------------------------------------------------------------------
+
+    fn query(&mut self, pname: GLenum) -> Option<Query> {
+        let state = &self.state;
+        let bits = |surface: Option<&Surface>| surface.map_or([0; 4], |surface| surface.bits);
+        let images = self.attachment_images();
+        let color = images[0].and_then(|image| self.surface(image));
+        let depth = images[1].and_then(|image| self.surface(image));
+        let stencil = images[2].and_then(|image| self.surface(image));
+        let stencil_face = |face: usize| -> &StencilState { &state.stencil[face] };
+
+        let query = match pname {
+            GL_ACTIVE_TEXTURE => Query::Ints(vec![(GL_TEXTURE0 as usize + self.active_texture) as GLint]),
+            GL_ALIASED_LINE_WIDTH_RANGE => Query::Floats(LINE_WIDTH_RANGE.to_vec()),
+            GL_ALIASED_POINT_SIZE_RANGE => Query::Floats(POINT_SIZE_RANGE.to_vec()),
+            GL_RED_BITS => Query::Ints(vec![bits(color)[0] as GLint]),
+            GL_GREEN_BITS => Query::Ints(vec![bits(color)[1] as GLint]),
+            GL_BLUE_BITS => Query::Ints(vec![bits(color)[2] as GLint]),
+            GL_ALPHA_BITS => Query::Ints(vec![bits(color)[3] as GLint]),
+            GL_DEPTH_BITS => Query::Ints(vec![depth.map_or(0, |depth| depth.depth_bits as GLint)]),
+            GL_STENCIL_BITS => Query::Ints(vec![stencil.map_or(0, |stencil| stencil.stencil_bits as GLint)]),
+            GL_ARRAY_BUFFER_BINDING => Query::Ints(vec![self.array_buffer as GLint]),
+            GL_ELEMENT_ARRAY_BUFFER_BINDING => Query::Ints(vec![self.element_array_buffer as GLint]),
+            GL_VERTEX_ARRAY_BINDING => Query::Ints(vec![self.vertex_array as GLint]),
+            GL_FRAMEBUFFER_BINDING => Query::Ints(vec![self.framebuffer as GLint]),
+            GL_RENDERBUFFER_BINDING => Query::Ints(vec![self.renderbuffer as GLint]),
+            GL_TEXTURE_BINDING_2D => Query::Ints(vec![self.texture_units[self.active_texture][0] as GLint]),
+            GL_TEXTURE_BINDING_CUBE_MAP => Query::Ints(vec![self.texture_units[self.active_texture][1] as GLint]),
+            GL_CURRENT_PROGRAM => Query::Ints(vec![self.current_program as GLint]),
+            GL_BLEND_COLOR => Query::Normalized(state.blend_color.to_vec()),
+            GL_BLEND_EQUATION_RGB => Query::Ints(vec![state.blend_equation[0] as GLint]),
+            GL_BLEND_EQUATION_ALPHA => Query::Ints(vec![state.blend_equation[1] as GLint]),
+            GL_BLEND_SRC_RGB => Query::Ints(vec![state.blend_func[0] as GLint]),
+            GL_BLEND_DST_RGB => Query::Ints(vec![state.blend_func[1] as GLint]),
+            GL_BLEND_SRC_ALPHA => Query::Ints(vec![state.blend_func[2] as GLint]),
+            GL_BLEND_DST_ALPHA => Query::Ints(vec![state.blend_func[3] as GLint]),
+            GL_COLOR_CLEAR_VALUE => Query::Normalized(state.clear_color.to_vec()),
+            GL_COLOR_WRITEMASK => Query::Bools(state.color_mask.to_vec()),
+            GL_COMPRESSED_TEXTURE_FORMATS => {
+                Query::Ints(compressed_formats().iter().map(|&format| format as GLint).collect())
+            }
+            GL_NUM_COMPRESSED_TEXTURE_FORMATS => Query::Ints(vec![compressed_formats().len() as GLint]),
+            GL_CULL_FACE_MODE => Query::Ints(vec![state.cull_face_mode as GLint]),
+            GL_DEPTH_CLEAR_VALUE => Query::Normalized(vec![state.clear_depth]),
+            GL_DEPTH_FUNC => Query::Ints(vec![state.depth_func as GLint]),
+            GL_DEPTH_RANGE => Query::Normalized(state.depth_range.to_vec()),
+            GL_DEPTH_WRITEMASK => Query::Bools(vec![state.depth_mask]),
+            GL_FRONT_FACE => Query::Ints(vec![state.front_face as GLint]),
+            GL_GENERATE_MIPMAP_HINT => Query::Ints(vec![state.generate_mipmap_hint as GLint]),
+            GL_IMPLEMENTATION_COLOR_READ_FORMAT | GL_IMPLEMENTATION_COLOR_READ_TYPE => {
+                let (format, type_) = color.map_or((GL_RGBA, GL_UNSIGNED_BYTE), pixels::read_format);
+                Query::Ints(vec![if pname == GL_IMPLEMENTATION_COLOR_READ_FORMAT { format } else { type_ } as GLint])
+            }
+            GL_LINE_WIDTH => Query::Floats(vec![state.line_width]),
+            GL_MAX_COMBINED_TEXTURE_IMAGE_UNITS | GL_MAX_TEXTURE_IMAGE_UNITS | GL_MAX_VERTEX_TEXTURE_IMAGE_UNITS => {
+                Query::Ints(vec![MAX_TEXTURE_UNITS as GLint])
+            }
+            GL_MAX_CUBE_MAP_TEXTURE_SIZE | GL_MAX_RENDERBUFFER_SIZE | GL_MAX_TEXTURE_SIZE => {
+                Query::Ints(vec![MAX_TEXTURE_SIZE])
+            }
+            GL_MAX_FRAGMENT_UNIFORM_VECTORS | GL_MAX_VERTEX_UNIFORM_VECTORS => Query::Ints(vec![MAX_UNIFORM_VECTORS]),
+            GL_MAX_VARYING_VECTORS => Query::Ints(vec![MAX_VARYING_VECTORS]),
+            GL_MAX_VERTEX_ATTRIBS => Query::Ints(vec![MAX_VERTEX_ATTRIBS as GLint]),
+            GL_MAX_VIEWPORT_DIMS => Query::Ints(vec![MAX_TEXTURE_SIZE, MAX_TEXTURE_SIZE]),
+            GL_NUM_SHADER_BINARY_FORMATS | GL_SAMPLE_BUFFERS | GL_SAMPLES => Query::Ints(vec![0]),
+            GL_SHADER_BINARY_FORMATS => Query::Ints(Vec::new()),
+            GL_NUM_PROGRAM_BINARY_FORMATS => Query::Ints(vec![1]),
+            GL_PROGRAM_BINARY_FORMATS => Query::Ints(vec![PROGRAM_BINARY_FORMAT as GLint]),
+            GL_PACK_ALIGNMENT => Query::Ints(vec![state.pack_alignment]),
+            GL_UNPACK_ALIGNMENT => Query::Ints(vec![state.unpack_alignment]),
+            GL_POLYGON_OFFSET_FACTOR => Query::Floats(vec![state.polygon_offset[0]]),
+            GL_POLYGON_OFFSET_UNITS => Query::Floats(vec![state.polygon_offset[1]]),
+            GL_SAMPLE_COVERAGE_VALUE => Query::Floats(vec![state.sample_coverage_value]),
+            GL_SAMPLE_COVERAGE_INVERT => Query::Bools(vec![state.sample_coverage_invert]),
+            GL_SCISSOR_BOX => Query::Ints(state.scissor.to_vec()),
+            GL_VIEWPORT => Query::Ints(state.viewport.to_vec()),
+            GL_SHADER_COMPILER => Query::Bools(vec![true]),
+            GL_SUBPIXEL_BITS => Query::Ints(vec![8]),
+            GL_STENCIL_CLEAR_VALUE => Query::Ints(vec![state.clear_stencil]),
+            GL_STENCIL_FUNC => Query::Ints(vec![stencil_face(0).func as GLint]),
+            GL_STENCIL_REF => Query::Ints(vec![stencil_face(0).ref_]),
+            GL_STENCIL_VALUE_MASK => Query::Ints(vec![stencil_face(0).value_mask as GLint]),
+            GL_STENCIL_WRITEMASK => Query::Ints(vec![stencil_face(0).write_mask as GLint]),
+            GL_STENCIL_FAIL => Query::Ints(vec![stencil_face(0).fail as GLint]),
+            GL_STENCIL_PASS_DEPTH_FAIL => Query::Ints(vec![stencil_face(0).depth_fail as GLint]),
+            GL_STENCIL_PASS_DEPTH_PASS => Query::Ints(vec![stencil_face(0).depth_pass as GLint]),
+            GL_STENCIL_BACK_FUNC => Query::Ints(vec![stencil_face(1).func as GLint]),
+            GL_STENCIL_BACK_REF => Query::Ints(vec![stencil_face(1).ref_]),
+            GL_STENCIL_BACK_VALUE_MASK => Query::Ints(vec![stencil_face(1).value_mask as GLint]),
+            GL_STENCIL_BACK_WRITEMASK => Query::Ints(vec![stencil_face(1).write_mask as GLint]),
+            GL_STENCIL_BACK_FAIL => Query::Ints(vec![stencil_face(1).fail as GLint]),
+            GL_STENCIL_BACK_PASS_DEPTH_FAIL => Query::Ints(vec![stencil_face(1).depth_fail as GLint]),
+            GL_STENCIL_BACK_PASS_DEPTH_PASS => Query::Ints(vec![stencil_face(1).depth_pass as GLint]),
+            _ => {
+                let mut state = self.state.clone();
+                match state.capability(pname) {
+                    Some(&mut enabled) => Query::Bools(vec![enabled]),
+                    None => {
+                        self.error(GL_INVALID_ENUM);
+                        return None;
+                    }
+                }
+            }
+        };
+
+        Some(query)
+    }
+
+    fn texture_parameter(&mut self, target: GLenum, pname: GLenum) -> Option<GLint> {
+        if target != GL_TEXTURE_2D && target != GL_TEXTURE_CUBE_MAP {
+            self.error(GL_INVALID_ENUM);
+            return None;
+        }
+
+        let texture = &self.textures[&self.bound_texture(target)];
+        match pname {
+            GL_TEXTURE_MIN_FILTER => Some(texture.min_filter as GLint),
+            GL_TEXTURE_MAG_FILTER => Some(texture.mag_filter as GLint),
+            GL_TEXTURE_WRAP_S => Some(texture.wrap_s as GLint),
+            GL_TEXTURE_WRAP_T => Some(texture.wrap_t as GLint),
+            _ => {
+                self.error(GL_INVALID_ENUM);
+                None
+            }
+        }
+    }
+
+    fn set_texture_parameter(&mut self, target: GLenum, pname: GLenum, param: GLenum) {
+        if target != GL_TEXTURE_2D && target != GL_TEXTURE_CUBE_MAP {
+            self.error(GL_INVALID_ENUM);
+            return;
+        }
+
+        let valid = match pname {
+            GL_TEXTURE_MIN_FILTER => matches!(param, GL_NEAREST | GL_LINEAR | GL_NEAREST_MIPMAP_NEAREST
+                | GL_LINEAR_MIPMAP_NEAREST | GL_NEAREST_MIPMAP_LINEAR | GL_LINEAR_MIPMAP_LINEAR),
+            GL_TEXTURE_MAG_FILTER => param == GL_NEAREST || param == GL_LINEAR,
+            GL_TEXTURE_WRAP_S | GL_TEXTURE_WRAP_T => matches!(param, GL_REPEAT | GL_CLAMP_TO_EDGE | GL_MIRRORED_REPEAT),
+            _ => false,
+        };
+        if !valid {
+            self.error(GL_INVALID_ENUM);
+            return;
+        }
+
+        let key = self.bound_texture(target);
+        let texture = self.textures.get_mut(&key).unwrap();
+        match pname {
+            GL_TEXTURE_MIN_FILTER => texture.min_filter = param,
+            GL_TEXTURE_MAG_FILTER => texture.mag_filter = param,
+            GL_TEXTURE_WRAP_S => texture.wrap_s = param,
+            _ => texture.wrap_t = param,
+        }
+    }
+
+    fn vertex_attribute(&mut self, index: GLuint, pname: GLenum) -> Option<Query> {
+        if index as usize >= MAX_VERTEX_ATTRIBS {
+            self.error(GL_INVALID_VALUE);
+            return None;
+        }
+
+        let array = self.attributes[index as usize];
+        let query = match pname {
+            GL_VERTEX_ATTRIB_ARRAY_ENABLED => Query::Bools(vec![array.enabled]),
+            GL_VERTEX_ATTRIB_ARRAY_SIZE => Query::Ints(vec![array.size]),
+            GL_VERTEX_ATTRIB_ARRAY_STRIDE => Query::Ints(vec![array.stride]),
+            GL_VERTEX_ATTRIB_ARRAY_TYPE => Query::Ints(vec![array.type_ as GLint]),
+            GL_VERTEX_ATTRIB_ARRAY_NORMALIZED => Query::Bools(vec![array.normalized]),
+            GL_VERTEX_ATTRIB_ARRAY_BUFFER_BINDING => Query::Ints(vec![array.buffer as GLint]),
+            GL_CURRENT_VERTEX_ATTRIB => Query::Floats(self.current_attributes[index as usize].to_vec()),
+            _ => {
+                self.error(GL_INVALID_ENUM);
+                return None;
+            }
+        };
+        Some(query)
+    }
+
+    // The stencil states `face` selects.
+    fn stencil_faces(&mut self, face: GLenum) -> Option<&'static [usize]> {
+        match face {
+            GL_FRONT => Some(&[0]),
+            GL_BACK => Some(&[1]),
+            GL_FRONT_AND_BACK => Some(&[0, 1]),
+            _ => {
+                self.error(GL_INVALID_ENUM);
+                None
+            }
+        }
+    }
+
+    // Stores a color read from the bound framebuffer into a level of the bound texture.
+    fn copy_to_texture(&mut self, texture: GLuint, face: usize, level: usize, offset: (usize, usize),
+                       source: (GLint, GLint), size: (usize, usize)) {
+        let pixels = {
+            let surface = self.read_surface().unwrap();
+            let mut pixels = Vec::with_capacity(size.0 * size.1);
+            for row in 0..size.1 {
+                for column in 0..size.0 {
+                    let (x, y) = (source.0 as i64 + column as i64, source.1 as i64 + row as i64);
+                    pixels.push(if x >= 0 && y >= 0 && (x as usize) < surface.width && (y as usize) < surface.height {
+                        surface.color[y as usize * surface.width + x as usize]
+                    } else {
+                        [0, 0, 0, 0]
+                    });
+                }
+            }
+            pixels
+        };
+
+        let image = self.textures.get_mut(&texture).unwrap().level_mut(face, level);
+        for (index, pixel) in pixels.into_iter().enumerate() {
+            let (x, y) = (offset.0 + index % size.0, offset.1 + index / size.0);
+            let value = match image.internal_format {
+                GL_ALPHA => [0, 0, 0, pixel[3]],
+                GL_LUMINANCE => [pixel[0], pixel[0], pixel[0], 255],
+                GL_LUMINANCE_ALPHA => [pixel[0], pixel[0], pixel[0], pixel[3]],
+                GL_RGB => [pixel[0], pixel[1], pixel[2], 255],
+                _ => pixel,
+            };
+            image.color[y * image.width + x] = image.requantize(value);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// BACKEND
+// -------------------------------------------------------------------------------------------------
+
+impl Backend for SoftwareBackend {
+    unsafe fn glActiveTexture(&mut self, texture: GLenum) {
+        match texture.checked_sub(GL_TEXTURE0) {
+            Some(unit) if (unit as usize) < MAX_TEXTURE_UNITS => self.active_texture = unit as usize,
+            _ => self.error(GL_INVALID_ENUM),
+        }
+    }
+
+    unsafe fn glAttachShader(&mut self, program: GLuint, shader: GLuint) {
+        if self.program_object(program).is_none() {
+            return;
+        }
+        let type_ = match self.shader_object(shader) {
+            Some(shader) => shader.type_,
+            None => return,
+        };
+
+        let shaders = &self.shaders;
+        let object = self.programs.get_mut(&program).unwrap();
+        if object.shaders.iter().any(|attached| shaders[attached].type_ == type_) {
+            self.error(GL_INVALID_OPERATION);
+            return;
+        }
+        object.shaders.push(shader);
+    }
+
+    unsafe fn glBindAttribLocation(&mut self, program: GLuint, index: GLuint, name: *const GLchar) {
+        if index as usize >= MAX_VERTEX_ATTRIBS {
+            self.error(GL_INVALID_VALUE);
+            return;
+        }
+
+        let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+        if name.starts_with("gl_") {
+            self.error(GL_INVALID_OPERATION);
+            return;
+        }
+
+        if let Some(object) = self.program_object(program) {
+            object.bindings.insert(name, index);
+        }
+    }
+
+    unsafe fn glBindBuffer(&mut self, target: GLenum, buffer: GLuint) {
+        match target {
+            GL_ARRAY_BUFFER => self.array_buffer = buffer,
+            GL_ELEMENT_ARRAY_BUFFER => self.element_array_buffer = buffer,
+            _ => return self.error(GL_INVALID_ENUM),
+        }
+
+        if buffer != 0 {
+            self.buffers.entry(buffer).or_insert_with(|| BufferObject { usage: GL_STATIC_DRAW, ..BufferObject::default() })
+                .bound = true;
+        }
+    }
+
+    unsafe fn glBindFramebuffer(&mut self, target: GLenum, framebuffer: GLuint) {
+        if target != GL_FRAMEBUFFER {
+            return self.error(GL_INVALID_ENUM);
+        }
+
+        self.framebuffer = framebuffer;
+        if framebuffer != 0 {
+            self.framebuffers.entry(framebuffer).or_default().bound = true;
+        }
+    }
+
+    unsafe fn glBindRenderbuffer(&mut self, target: GLenum, renderbuffer: GLuint) {
+        if target != GL_RENDERBUFFER {
+            return self.error(GL_INVALID_ENUM);
+        }
+
+        self.renderbuffer = renderbuffer;
+        if renderbuffer != 0 {
+            self.renderbuffers.entry(renderbuffer).or_default().bound = true;
+        }
+    }
+
+    unsafe fn glBindTexture(&mut self, target: GLenum, texture: GLuint) {
+        let index = match target {
+            GL_TEXTURE_2D => 0,
+            GL_TEXTURE_CUBE_MAP => 1,
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+
+        if texture != 0 {
+            let object = self.textures.entry(texture).or_default();
+            if object.target != 0 && object.target != target {
+                return self.error(GL_INVALID_OPERATION);
+            }
+            if object.target == 0 {
+                object.bind(target);
+            }
+        }
+
+        self.texture_units[self.active_texture][index] = texture;
+    }
+
+    unsafe fn glBlendColor(
+        &mut self,
+        red: GLclampf,
+        green: GLclampf,
+        blue: GLclampf,
+        alpha: GLclampf,
+    ) {
+        self.state.blend_color = [clamp(red), clamp(green), clamp(blue), clamp(alpha)];
+    }
+
+    unsafe fn glBlendEquation(&mut self, mode: GLenum) {
+        self.glBlendEquationSeparate(mode, mode)
+    }
+
+    unsafe fn glBlendEquationSeparate(&mut self, modeRGB: GLenum, modeAlpha: GLenum) {
+        if !is_blend_equation(modeRGB) || !is_blend_equation(modeAlpha) {
+            return self.error(GL_INVALID_ENUM);
+        }
+        self.state.blend_equation = [modeRGB, modeAlpha];
+    }
+
+    unsafe fn glBlendFunc(&mut self, sfactor: GLenum, dfactor: GLenum) {
+        self.glBlendFuncSeparate(sfactor, dfactor, sfactor, dfactor)
+    }
+
+    unsafe fn glBlendFuncSeparate(
+        &mut self,
+        srcRGB: GLenum,
+        dstRGB: GLenum,
+        srcAlpha: GLenum,
+        dstAlpha: GLenum,
+    ) {
+        let valid = [srcRGB, dstRGB, srcAlpha, dstAlpha].iter().enumerate()
+            .all(|(index, &factor)| is_blend_factor(factor) && (factor != GL_SRC_ALPHA_SATURATE || index % 2 == 0));
+        if !valid {
+            return self.error(GL_INVALID_ENUM);
+        }
+        self.state.blend_func = [srcRGB, dstRGB, srcAlpha, dstAlpha];
+    }
+
+    unsafe fn glBufferData(
+        &mut self,
+        target: GLenum,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+        usage: GLenum,
+    ) {
+        let buffer = match target {
+            GL_ARRAY_BUFFER => self.array_buffer,
+            GL_ELEMENT_ARRAY_BUFFER => self.element_array_buffer,
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+        if !matches!(usage, GL_STREAM_DRAW | GL_STATIC_DRAW | GL_DYNAMIC_DRAW) {
+            return self.error(GL_INVALID_ENUM);
+        }
+        if size < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        if buffer == 0 {
+            return self.error(GL_INVALID_OPERATION);
+        }
+
+        let object = self.buffers.get_mut(&buffer).unwrap();
+        object.data = if data.is_null() {
+            vec![0; size as usize]
+        } else {
+            slice::from_raw_parts(data as *const u8, size as usize).to_vec()
+        };
+        object.usage = usage;
+    }
+
+    unsafe fn glBufferSubData(
+        &mut self,
+        target: GLenum,
+        offset: GLintptr,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+    ) {
+        let buffer = match target {
+            GL_ARRAY_BUFFER => self.array_buffer,
+            GL_ELEMENT_ARRAY_BUFFER => self.element_array_buffer,
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+        if buffer == 0 {
+            return self.error(GL_INVALID_OPERATION);
+        }
+
+        let object = self.buffers.get_mut(&buffer).unwrap();
+        if offset < 0 || size < 0 || (offset + size) as usize > object.data.len() {
+            return self.error(GL_INVALID_VALUE);
+        }
+        if size > 0 {
+            let source = slice::from_raw_parts(data as *const u8, size as usize);
+            object.data[offset as usize..(offset + size) as usize].copy_from_slice(source);
+        }
+    }
+
+    unsafe fn glCheckFramebufferStatus(&mut self, target: GLenum) -> GLenum {
+        if target != GL_FRAMEBUFFER {
+            self.error(GL_INVALID_ENUM);
+            return 0;
+        }
+        self.framebuffer_status()
+    }
+
+    unsafe fn glClear(&mut self, mask: GLbitfield) {
+        if mask & !(GL_COLOR_BUFFER_BIT | GL_DEPTH_BUFFER_BIT | GL_STENCIL_BUFFER_BIT) != 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        if !self.check_framebuffer() {
+            return;
+        }
+
+        let (mut targets, images) = self.take_targets();
+        raster::clear(&self.state, &mut targets, mask);
+        self.restore_targets(targets, images);
+    }
+
+    unsafe fn glClearColor(
+        &mut self,
+        red: GLclampf,
+        green: GLclampf,
+        blue: GLclampf,
+        alpha: GLclampf,
+    ) {
+        self.state.clear_color = [clamp(red), clamp(green), clamp(blue), clamp(alpha)];
+    }
+
+    unsafe fn glClearDepthf(&mut self, depth: GLclampf) {
+        self.state.clear_depth = clamp(depth);
+    }
+
+    unsafe fn glClearStencil(&mut self, s: GLint) {
+        self.state.clear_stencil = s;
+    }
+
+    unsafe fn glColorMask(
+        &mut self,
+        red: GLboolean,
+        green: GLboolean,
+        blue: GLboolean,
+        alpha: GLboolean,
+    ) {
+        self.state.color_mask = [red != 0, green != 0, blue != 0, alpha != 0];
+    }
+
+    unsafe fn glCompileShader(&mut self, shader: GLuint) {
+        if let Some(object) = self.shader_object(shader) {
+            let type_ = if object.type_ == GL_VERTEX_SHADER { ShaderType::VERTEX_SHADER } else { ShaderType::FRAGMENT_SHADER };
+            match glsl::compile(&object.source, type_, &LIMITS) {
+                Ok(compiled) => {
+                    object.compiled = true;
+                    object.info_log = compiled.info_log();
+                    object.code = Some(Rc::new(compiled));
+                    object.compiled_source = object.source.clone();
+                }
+                Err(diagnostics) => {
+                    object.compiled = false;
+                    object.info_log = glsl::info_log(&diagnostics);
+                    object.code = None;
+                }
+            }
+        }
+    }
+
+    unsafe fn glCompressedTexImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        imageSize: GLsizei,
+        data: *const GLvoid,
+    ) {
+        let (texture, face) = match self.check_image(target, level, width, height, border) {
+            Some(image) => image,
+            None => return,
+        };
+        if !compressed_formats().contains(&internalformat) {
+            return self.error(GL_INVALID_ENUM);
+        }
+
+        let info = FormatInfo::for_internal_format(internalformat).unwrap();
+        if imageSize < 0 || imageSize as usize != info.image_size(width as usize, height as usize, 1) {
+            return self.error(GL_INVALID_VALUE);
+        }
+
+        let (width, height) = (width as usize, height as usize);
+        let mut image = Surface::color(width, height, internalformat, [8, 8, 8, 8]);
+        if width > 0 && height > 0 {
+            let data = slice::from_raw_parts(data as *const u8, imageSize as usize);
+            match decode::decode(internalformat, width as u32, height as u32, data) {
+                Ok(rgba) => image.color = rgba.chunks_exact(4).map(|texel| [texel[0], texel[1], texel[2], texel[3]]).collect(),
+                Err(_) => return self.error(GL_INVALID_VALUE),
+            }
+        }
+
+        *self.textures.get_mut(&texture).unwrap().level_mut(face, level as usize) = image;
+    }
+
+    unsafe fn glCompressedTexSubImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        imageSize: GLsizei,
+        data: *const GLvoid,
+    ) {
+        let (texture, face) = match self.check_sub_image(target, level, xoffset, yoffset, width, height) {
+            Some(image) => image,
+            None => return,
+        };
+
+        let image = self.textures[&texture].level(face, level as usize).unwrap();
+        let info = match FormatInfo::for_internal_format(format) {
+            Some(info) if compressed_formats().contains(&format) => info,
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+
+        // ETC1 has no sub-image updates, the others only update whole blocks.
+        let (block_width, block_height) = (info.block_width as GLint, info.block_height as GLint);
+        let aligned = xoffset % block_width == 0 && yoffset % block_height == 0
+            && (width % block_width == 0 || xoffset + width == image.width as GLint)
+            && (height % block_height == 0 || yoffset + height == image.height as GLint);
+        if format != image.internal_format || format == GL_ETC1_RGB8_OES || !aligned {
+            return self.error(GL_INVALID_OPERATION);
+        }
+        if imageSize < 0 || imageSize as usize != info.image_size(width as usize, height as usize, 1) {
+            return self.error(GL_INVALID_VALUE);
+        }
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let data = slice::from_raw_parts(data as *const u8, imageSize as usize);
+        let rgba = match decode::decode(format, width as u32, height as u32, data) {
+            Ok(rgba) => rgba,
+            Err(_) => return self.error(GL_INVALID_VALUE),
+        };
+
+        let image = self.textures.get_mut(&texture).unwrap().level_mut(face, level as usize);
+        for (index, texel) in rgba.chunks_exact(4).enumerate() {
+            let (x, y) = (xoffset as usize + index % width as usize, yoffset as usize + index / width as usize);
+            image.color[y * image.width + x] = [texel[0], texel[1], texel[2], texel[3]];
+        }
+    }
+
+    unsafe fn glCopyTexImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        internalformat: GLenum,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+    ) {
+        let (texture, face) = match self.check_image(target, level, width, height, border) {
+            Some(image) => image,
+            None => return,
+        };
+        let bits = match internalformat {
+            GL_ALPHA => [0, 0, 0, 8],
+            GL_LUMINANCE | GL_RGB => [8, 8, 8, 0],
+            GL_LUMINANCE_ALPHA | GL_RGBA => [8, 8, 8, 8],
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+        if !self.check_framebuffer() {
+            return;
+        }
+
+        let source_has_alpha = self.read_surface().is_some_and(|surface| surface.bits[3] > 0);
+        if bits[3] > 0 && !source_has_alpha {
+            return self.error(GL_INVALID_OPERATION);
+        }
+
+        let (width, height) = (width as usize, height as usize);
+        *self.textures.get_mut(&texture).unwrap().level_mut(face, level as usize) =
+            Surface::color(width, height, internalformat, bits);
+        self.copy_to_texture(texture, face, level as usize, (0, 0), (x, y), (width, height));
+    }
+
+    unsafe fn glCopyTexSubImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        let (texture, face) = match self.check_sub_image(target, level, xoffset, yoffset, width, height) {
+            Some(image) => image,
+            None => return,
+        };
+        if !self.check_framebuffer() {
+            return;
+        }
+
+        let image = self.textures[&texture].level(face, level as usize).unwrap();
+        let source_has_alpha = self.read_surface().is_some_and(|surface| surface.bits[3] > 0);
+        if image.color.is_empty() || FormatInfo::for_internal_format(image.internal_format).is_some_and(|info| info.is_compressed)
+            || (image.bits[3] > 0 && !source_has_alpha) {
+            return self.error(GL_INVALID_OPERATION);
+        }
+
+        self.copy_to_texture(texture, face, level as usize, (xoffset as usize, yoffset as usize), (x, y),
+                             (width as usize, height as usize));
+    }
+
+    unsafe fn glCreateProgram(&mut self) -> GLuint {
+        let name = self.new_name();
+        self.programs.insert(name, ProgramObject::default());
+        name
+    }
+
+    unsafe fn glCreateShader(&mut self, type_: GLenum) -> GLuint {
+        if type_ != GL_VERTEX_SHADER && type_ != GL_FRAGMENT_SHADER {
+            self.error(GL_INVALID_ENUM);
+            return 0;
+        }
+
+        let name = self.new_name();
+        self.shaders.insert(name, ShaderObject {
+            type_,
+            source: String::new(),
+            compiled: false,
+            info_log: String::new(),
+            code: None,
+            compiled_source: String::new(),
+            delete_pending: false,
+        });
+        name
+    }
+
+    unsafe fn glCullFace(&mut self, mode: GLenum) {
+        match mode {
+            GL_FRONT | GL_BACK | GL_FRONT_AND_BACK => self.state.cull_face_mode = mode,
+            _ => self.error(GL_INVALID_ENUM),
+        }
+    }
+
+    unsafe fn glDeleteBuffers(&mut self, n: GLsizei, buffers: *const GLuint) {
+        if n < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+
+        for &buffer in client_slice(buffers, n as usize) {
+            if buffer == 0 || self.buffers.remove(&buffer).is_none() {
+                continue;
+            }
+            if self.array_buffer == buffer {
+                self.array_buffer = 0;
+            }
+            if self.element_array_buffer == buffer {
+                self.element_array_buffer = 0;
+            }
+            for array in self.attributes.iter_mut().filter(|array| array.buffer == buffer) {
+                array.buffer = 0;
+            }
+        }
+    }
+
+    unsafe fn glDeleteFramebuffers(&mut self, n: GLsizei, framebuffers: *const GLuint) {
+        if n < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+
+        for &framebuffer in client_slice(framebuffers, n as usize) {
+            if framebuffer != 0 && self.framebuffers.remove(&framebuffer).is_some() && self.framebuffer == framebuffer {
+                self.framebuffer = 0;
+            }
+        }
+    }
+
+    unsafe fn glDeleteProgram(&mut self, program: GLuint) {
+        if program == 0 || self.program_object(program).is_none() {
+            return;
+        }
+
+        if self.current_program == program {
+            self.programs.get_mut(&program).unwrap().delete_pending = true;
+        } else {
+            self.delete_program_now(program);
+        }
+    }
+
+    unsafe fn glDeleteRenderbuffers(&mut self, n: GLsizei, renderbuffers: *const GLuint) {
+        if n < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+
+        for &renderbuffer in client_slice(renderbuffers, n as usize) {
+            if renderbuffer == 0 || self.renderbuffers.remove(&renderbuffer).is_none() {
+                continue;
+            }
+            if self.renderbuffer == renderbuffer {
+                self.renderbuffer = 0;
+            }
+            self.detach_everywhere(&|attachment| *attachment == Attachment::Renderbuffer(renderbuffer));
+        }
+    }
+
+    unsafe fn glDeleteShader(&mut self, shader: GLuint) {
+        if shader == 0 {
+            return;
+        }
+        if let Some(object) = self.shader_object(shader) {
+            object.delete_pending = true;
+            self.delete_shader_if_unused(shader);
+        }
+    }
+
+    unsafe fn glDeleteTextures(&mut self, n: GLsizei, textures: *const GLuint) {
+        if n < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+
+        for &texture in client_slice(textures, n as usize) {
+            if texture == 0 || self.textures.remove(&texture).is_none() {
+                continue;
+            }
+            for bindings in self.texture_units.iter_mut() {
+                for binding in bindings.iter_mut().filter(|binding| **binding == texture) {
+                    *binding = 0;
+                }
+            }
+            self.detach_everywhere(&|attachment| match *attachment {
+                Attachment::Texture { name, .. } => name == texture,
+                _ => false,
+            });
+        }
+    }
+
+    unsafe fn glDepthFunc(&mut self, func: GLenum) {
+        if !is_compare_func(func) {
+            return self.error(GL_INVALID_ENUM);
+        }
+        self.state.depth_func = func;
+    }
+
+    unsafe fn glDepthMask(&mut self, flag: GLboolean) {
+        self.state.depth_mask = flag != 0;
+    }
+
+    unsafe fn glDepthRangef(&mut self, zNear: GLclampf, zFar: GLclampf) {
+        self.state.depth_range = [clamp(zNear), clamp(zFar)];
+    }
+
+    unsafe fn glDetachShader(&mut self, program: GLuint, shader: GLuint) {
+        if self.program_object(program).is_none() || self.shader_object(shader).is_none() {
+            return;
+        }
+
+        let object = self.programs.get_mut(&program).unwrap();
+        match object.shaders.iter().position(|&attached| attached == shader) {
+            Some(index) => {
+                object.shaders.remove(index);
+                self.delete_shader_if_unused(shader);
+            }
+            None => self.error(GL_INVALID_OPERATION),
+        }
+    }
+
+    unsafe fn glDisable(&mut self, cap: GLenum) {
+        match self.state.capability(cap) {
+            Some(enabled) => *enabled = false,
+            None => self.error(GL_INVALID_ENUM),
+        }
+    }
+
+    unsafe fn glDisableVertexAttribArray(&mut self, index: GLuint) {
+        match self.attributes.get_mut(index as usize) {
+            Some(array) => array.enabled = false,
+            None => self.error(GL_INVALID_VALUE),
+        }
+    }
+
+    unsafe fn glDrawArrays(&mut self, mode: GLenum, first: GLint, count: GLsizei) {
+        if !self.check_mode(mode) {
+            return;
+        }
+        if first < 0 || count < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+
+        let indices: Vec<usize> = (first as usize..first as usize + count as usize).collect();
+        self.draw(mode, &indices);
+    }
+
+    unsafe fn glDrawElements(
+        &mut self,
+        mode: GLenum,
+        count: GLsizei,
+        type_: GLenum,
+        indices: *const GLvoid,
+    ) {
+        if !self.check_mode(mode) {
+            return;
+        }
+        let size = match type_ {
+            GL_UNSIGNED_BYTE => 1,
+            GL_UNSIGNED_SHORT => 2,
+            GL_UNSIGNED_INT => 4,
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+        if count < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+
+        let count = count as usize;
+        let bytes = if self.element_array_buffer == 0 {
+            client_slice(indices as *const u8, count * size).to_vec()
+        } else {
+            let data = &self.buffers[&self.element_array_buffer].data;
+            let offset = indices as usize;
+            match data.get(offset..offset + count * size) {
+                Some(bytes) => bytes.to_vec(),
+                None => return self.error(GL_INVALID_OPERATION),
+            }
+        };
+
+        let indices: Vec<usize> = bytes.chunks_exact(size).map(|index| match size {
+            1 => index[0] as usize,
+            2 => u16::from_ne_bytes([index[0], index[1]]) as usize,
+            _ => u32::from_ne_bytes([index[0], index[1], index[2], index[3]]) as usize,
+        }).collect();
+        self.draw(mode, &indices);
+    }
+
+    unsafe fn glEnable(&mut self, cap: GLenum) {
+        match self.state.capability(cap) {
+            Some(enabled) => *enabled = true,
+            None => self.error(GL_INVALID_ENUM),
+        }
+    }
+
+    unsafe fn glEnableVertexAttribArray(&mut self, index: GLuint) {
+        match self.attributes.get_mut(index as usize) {
+            Some(array) => array.enabled = true,
+            None => self.error(GL_INVALID_VALUE),
+        }
+    }
+
+    unsafe fn glFinish(&mut self) {}
+
+    unsafe fn glFlush(&mut self) {}
+
+    unsafe fn glFramebufferRenderbuffer(
+        &mut self,
+        target: GLenum,
+        attachment: GLenum,
+        renderbuffertarget: GLenum,
+        renderbuffer: GLuint,
+    ) {
+        let point = match self.attachment_point(target, attachment) {
+            Some(point) => point,
+            None => return,
+        };
+        if renderbuffertarget != GL_RENDERBUFFER {
+            return self.error(GL_INVALID_ENUM);
+        }
+        if renderbuffer != 0 && !self.renderbuffers.contains_key(&renderbuffer) {
+            return self.error(GL_INVALID_OPERATION);
+        }
+
+        let framebuffer = self.framebuffers.get_mut(&self.framebuffer).unwrap();
+        framebuffer.attachments[point] = if renderbuffer == 0 {
+            Attachment::None
+        } else {
+            Attachment::Renderbuffer(renderbuffer)
+        };
+    }
+
+    unsafe fn glFramebufferTexture2D(
+        &mut self,
+        target: GLenum,
+        attachment: GLenum,
+        textarget: GLenum,
+        texture: GLuint,
+        level: GLint,
+    ) {
+        let point = match self.attachment_point(target, attachment) {
+            Some(point) => point,
+            None => return,
+        };
+        let (kind, face) = match textarget {
+            GL_TEXTURE_2D => (GL_TEXTURE_2D, 0),
+            GL_TEXTURE_CUBE_MAP_POSITIVE_X..=GL_TEXTURE_CUBE_MAP_NEGATIVE_Z => {
+                (GL_TEXTURE_CUBE_MAP, (textarget - GL_TEXTURE_CUBE_MAP_POSITIVE_X) as usize)
+            }
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+
+        let value = if texture == 0 {
+            Attachment::None
+        } else {
+            match self.textures.get(&texture) {
+                Some(object) if object.target == kind => {}
+                _ => return self.error(GL_INVALID_OPERATION),
+            }
+            if level != 0 {
+                return self.error(GL_INVALID_VALUE);
+            }
+            Attachment::Texture { name: texture, target: textarget, face, level: 0 }
+        };
+
+        self.framebuffers.get_mut(&self.framebuffer).unwrap().attachments[point] = value;
+    }
+
+    unsafe fn glFrontFace(&mut self, mode: GLenum) {
+        match mode {
+            GL_CW | GL_CCW => self.state.front_face = mode,
+            _ => self.error(GL_INVALID_ENUM),
+        }
+    }
+
+    unsafe fn glGenBuffers(&mut self, n: GLsizei, buffers: *mut GLuint) {
+        if n < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        for index in 0..n as usize {
+            let name = self.new_name();
+            self.buffers.insert(name, BufferObject { usage: GL_STATIC_DRAW, ..BufferObject::default() });
+            *buffers.add(index) = name;
+        }
+    }
+
+    unsafe fn glGenerateMipmap(&mut self, target: GLenum) {
+        if target != GL_TEXTURE_2D && target != GL_TEXTURE_CUBE_MAP {
+            return self.error(GL_INVALID_ENUM);
+        }
+
+        let key = self.bound_texture(target);
+        let texture = self.textures.get_mut(&key).unwrap();
+        let base = match texture.level(0, 0) {
+            Some(base) => base.clone(),
+            None => return self.error(GL_INVALID_OPERATION),
+        };
+
+        let cube_complete = (0..texture.faces.len()).all(|face| texture.level(face, 0).is_some_and(|image| {
+            image.width == base.width && image.height == base.height && image.internal_format == base.internal_format
+        }));
+        let compressed = FormatInfo::for_internal_format(base.internal_format).is_some_and(|info| info.is_compressed);
+        if !base.width.is_power_of_two() || !base.height.is_power_of_two() || !cube_complete || compressed
+            || (target == GL_TEXTURE_CUBE_MAP && base.width != base.height) {
+            return self.error(GL_INVALID_OPERATION);
+        }
+
+        for face in 0..texture.faces.len() {
+            let image = texture.level(face, 0).unwrap();
+            let bytes: Vec<u8> = image.color.iter().flat_map(|texel| texel.iter().cloned()).collect();
+            let levels = match mipmap::generate_chain(PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE, image.width as u32,
+                                                      image.height as u32, 1, &bytes, &MipmapOptions::default()) {
+                Ok(levels) => levels,
+                Err(_) => return self.error(GL_OUT_OF_MEMORY),
+            };
+
+            for (index, level) in levels.iter().enumerate().skip(1) {
+                let mut surface = Surface::color(level.width as usize, level.height as usize, base.internal_format, base.bits);
+                for (texel, source) in surface.color.iter_mut().zip(level.faces[0].chunks_exact(4)) {
+                    *texel = base.requantize([source[0], source[1], source[2], source[3]]);
+                }
+                *texture.level_mut(face, index) = surface;
+            }
+        }
+    }
+
+    unsafe fn glGenFramebuffers(&mut self, n: GLsizei, framebuffers: *mut GLuint) {
+        if n < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        for index in 0..n as usize {
+            let name = self.new_name();
+            self.framebuffers.insert(name, FramebufferObject::default());
+            *framebuffers.add(index) = name;
+        }
+    }
+
+    unsafe fn glGenRenderbuffers(&mut self, n: GLsizei, renderbuffers: *mut GLuint) {
+        if n < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        for index in 0..n as usize {
+            let name = self.new_name();
+            self.renderbuffers.insert(name, RenderbufferObject::default());
+            *renderbuffers.add(index) = name;
+        }
+    }
+
+    unsafe fn glGenTextures(&mut self, n: GLsizei, textures: *mut GLuint) {
+        if n < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        for index in 0..n as usize {
+            let name = self.new_name();
+            self.textures.insert(name, TextureObject::default());
+            *textures.add(index) = name;
+        }
+    }
+
+    unsafe fn glGetActiveAttrib(
+        &mut self,
+        program: GLuint,
+        index: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        size: *mut GLint,
+        type_: *mut GLenum,
+        name: *mut GLchar,
+    ) {
+        let attribute = match self.programs.get(&program).and_then(|program| program.executable.as_ref()) {
+            Some(linked) => linked.attributes.get(index as usize).cloned(),
+            None => {
+                self.program_object(program);
+                return;
+            }
+        };
+
+        match attribute {
+            Some(attribute) => {
+                write_string(&attribute.name, bufsize, length, name);
+                *size = 1;
+                *type_ = attribute.type_;
+            }
+            None => self.error(GL_INVALID_VALUE),
+        }
+    }
+
+    unsafe fn glGetActiveUniform(
+        &mut self,
+        program: GLuint,
+        index: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        size: *mut GLint,
+        type_: *mut GLenum,
+        name: *mut GLchar,
+    ) {
+        let uniform = match self.programs.get(&program).and_then(|program| program.executable.as_ref()) {
+            Some(linked) => linked.active_uniform(index as usize),
+            None => {
+                self.program_object(program);
+                return;
+            }
+        };
+
+        match uniform {
+            Some((uniform_name, uniform_size, uniform_type)) => {
+                write_string(&uniform_name, bufsize, length, name);
+                *size = uniform_size;
+                *type_ = uniform_type;
+            }
+            None => self.error(GL_INVALID_VALUE),
+        }
+    }
+
+    unsafe fn glGetAttachedShaders(
+        &mut self,
+        program: GLuint,
+        maxcount: GLsizei,
+        count: *mut GLsizei,
+        shaders: *mut GLuint,
+    ) {
+        if maxcount < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+
+        let attached = match self.program_object(program) {
+            Some(object) => object.shaders.clone(),
+            None => return,
+        };
+        let written = attached.len().min(maxcount as usize);
+        for (index, &shader) in attached.iter().take(written).enumerate() {
+            *shaders.add(index) = shader;
+        }
+        if !count.is_null() {
+            *count = written as GLsizei;
+        }
+    }
+
+    unsafe fn glGetAttribLocation(&mut self, program: GLuint, name: *const GLchar) -> GLint {
+        let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+        self.linked_program(program).map_or(-1, |linked| linked.attribute_location(&name))
+    }
+
+    unsafe fn glGetBooleanv(&mut self, pname: GLenum, params: *mut GLboolean) {
+        let values: Vec<bool> = match self.query(pname) {
+            Some(Query::Ints(values)) => values.iter().map(|&value| value != 0).collect(),
+            Some(Query::Floats(values)) | Some(Query::Normalized(values)) => {
+                values.iter().map(|&value| value != 0.0).collect()
+            }
+            Some(Query::Bools(values)) => values,
+            None => return,
+        };
+        for (index, value) in values.into_iter().enumerate() {
+            *params.add(index) = value as GLboolean;
+        }
+    }
+
+    unsafe fn glGetBufferParameteriv(&mut self, target: GLenum, pname: GLenum, params: *mut GLint) {
+        let buffer = match target {
+            GL_ARRAY_BUFFER => self.array_buffer,
+            GL_ELEMENT_ARRAY_BUFFER => self.element_array_buffer,
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+        if buffer == 0 {
+            return self.error(GL_INVALID_OPERATION);
+        }
+
+        let object = &self.buffers[&buffer];
+        match pname {
+            GL_BUFFER_SIZE => *params = object.data.len() as GLint,
+            GL_BUFFER_USAGE => *params = object.usage as GLint,
+            _ => self.error(GL_INVALID_ENUM),
+        }
+    }
+
+    unsafe fn glGetError(&mut self) -> GLenum {
+        mem::replace(&mut self.error, GL_NO_ERROR)
+    }
+
+    unsafe fn glGetFloatv(&mut self, pname: GLenum, params: *mut GLfloat) {
+        let values: Vec<GLfloat> = match self.query(pname) {
+            Some(Query::Ints(values)) => values.iter().map(|&value| value as GLfloat).collect(),
+            Some(Query::Floats(values)) | Some(Query::Normalized(values)) => values,
+            Some(Query::Bools(values)) => values.iter().map(|&value| value as u8 as GLfloat).collect(),
+            None => return,
+        };
+        for (index, value) in values.into_iter().enumerate() {
+            *params.add(index) = value;
+        }
+    }
+
+    unsafe fn glGetFramebufferAttachmentParameteriv(
+        &mut self,
+        target: GLenum,
+        attachment: GLenum,
+        pname: GLenum,
+        params: *mut GLint,
+    ) {
+        let point = match self.attachment_point(target, attachment) {
+            Some(point) => point,
+            None => return,
+        };
+
+        let value = match (self.framebuffers[&self.framebuffer].attachments[point], pname) {
+            (Attachment::None, GL_FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE) => GL_NONE as GLint,
+            (Attachment::Texture { .. }, GL_FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE) => GL_TEXTURE as GLint,
+            (Attachment::Renderbuffer(_), GL_FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE) => GL_RENDERBUFFER as GLint,
+            (Attachment::Texture { name, .. }, GL_FRAMEBUFFER_ATTACHMENT_OBJECT_NAME) => name as GLint,
+            (Attachment::Renderbuffer(name), GL_FRAMEBUFFER_ATTACHMENT_OBJECT_NAME) => name as GLint,
+            (Attachment::Texture { level, .. }, GL_FRAMEBUFFER_ATTACHMENT_TEXTURE_LEVEL) => level as GLint,
+            (Attachment::Texture { target, .. }, GL_FRAMEBUFFER_ATTACHMENT_TEXTURE_CUBE_MAP_FACE) => {
+                if target == GL_TEXTURE_2D { 0 } else { target as GLint }
+            }
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+        *params = value;
+    }
+
+    unsafe fn glGetIntegerv(&mut self, pname: GLenum, params: *mut GLint) {
+        let values: Vec<GLint> = match self.query(pname) {
+            Some(Query::Ints(values)) => values,
+            Some(Query::Floats(values)) => values.iter().map(|&value| value.round() as GLint).collect(),
+            Some(Query::Normalized(values)) => {
+                values.iter().map(|&value| (value as f64 * GLint::MAX as f64).round() as GLint).collect()
+            }
+            Some(Query::Bools(values)) => values.iter().map(|&value| value as GLint).collect(),
+            None => return,
+        };
+        for (index, value) in values.into_iter().enumerate() {
+            *params.add(index) = value;
+        }
+    }
+
+    unsafe fn glGetProgramiv(&mut self, program: GLuint, pname: GLenum, params: *mut GLint) {
+        let object = match self.program_object(program) {
+            Some(object) => object,
+            None => return,
+        };
+
+        let executable = object.executable.as_ref().filter(|_| object.linked);
+        let value = match pname {
+            GL_DELETE_STATUS => object.delete_pending as GLint,
+            GL_LINK_STATUS => object.linked as GLint,
+            GL_VALIDATE_STATUS => object.validated as GLint,
+            GL_INFO_LOG_LENGTH => string_length(&object.info_log),
+            GL_ATTACHED_SHADERS => object.shaders.len() as GLint,
+            GL_ACTIVE_ATTRIBUTES => executable.map_or(0, |linked| linked.attributes.len() as GLint),
+            GL_ACTIVE_ATTRIBUTE_MAX_LENGTH => executable.map_or(0, |linked| {
+                linked.attributes.iter().map(|attribute| attribute.name.len() as GLint + 1).max().unwrap_or(0)
+            }),
+            GL_ACTIVE_UNIFORMS => executable.map_or(0, |linked| linked.uniform_count() as GLint),
+            GL_ACTIVE_UNIFORM_MAX_LENGTH => executable.map_or(0, |linked| {
+                (0..linked.uniform_count()).filter_map(|index| linked.active_uniform(index))
+                    .map(|(name, _, _)| name.len() as GLint + 1).max().unwrap_or(0)
+            }),
+            GL_PROGRAM_BINARY_LENGTH => match object.binary {
+                Some(ref binary) if object.linked => binary.len() as GLint,
+                _ => 0,
+            },
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+        *params = value;
+    }
+
+    unsafe fn glGetProgramInfoLog(
+        &mut self,
+        program: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        infolog: *mut GLchar,
+    ) {
+        if bufsize < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        if let Some(object) = self.program_object(program) {
+            write_string(&object.info_log, bufsize, length, infolog);
+        }
+    }
+
+    unsafe fn glGetRenderbufferParameteriv(
+        &mut self,
+        target: GLenum,
+        pname: GLenum,
+        params: *mut GLint,
+    ) {
+        if target != GL_RENDERBUFFER {
+            return self.error(GL_INVALID_ENUM);
+        }
+        if self.renderbuffer == 0 {
+            return self.error(GL_INVALID_OPERATION);
+        }
+
+        let surface = &self.renderbuffers[&self.renderbuffer].surface;
+        let value = match pname {
+            GL_RENDERBUFFER_WIDTH => surface.width as GLint,
+            GL_RENDERBUFFER_HEIGHT => surface.height as GLint,
+            GL_RENDERBUFFER_INTERNAL_FORMAT => {
+                if surface.internal_format == 0 { GL_RGBA4 as GLint } else { surface.internal_format as GLint }
+            }
+            GL_RENDERBUFFER_RED_SIZE => surface.bits[0] as GLint,
+            GL_RENDERBUFFER_GREEN_SIZE => surface.bits[1] as GLint,
+            GL_RENDERBUFFER_BLUE_SIZE => surface.bits[2] as GLint,
+            GL_RENDERBUFFER_ALPHA_SIZE => surface.bits[3] as GLint,
+            GL_RENDERBUFFER_DEPTH_SIZE => surface.depth_bits as GLint,
+            GL_RENDERBUFFER_STENCIL_SIZE => surface.stencil_bits as GLint,
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+        *params = value;
+    }
+
+    unsafe fn glGetShaderiv(&mut self, shader: GLuint, pname: GLenum, params: *mut GLint) {
+        let object = match self.shader_object(shader) {
+            Some(object) => object,
+            None => return,
+        };
+
+        let value = match pname {
+            GL_SHADER_TYPE => object.type_ as GLint,
+            GL_DELETE_STATUS => object.delete_pending as GLint,
+            GL_COMPILE_STATUS => object.compiled as GLint,
+            GL_INFO_LOG_LENGTH => string_length(&object.info_log),
+            GL_SHADER_SOURCE_LENGTH => string_length(&object.source),
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+        *params = value;
+    }
+
+    unsafe fn glGetShaderInfoLog(
+        &mut self,
+        shader: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        infolog: *mut GLchar,
+    ) {
+        if bufsize < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        if let Some(object) = self.shader_object(shader) {
+            write_string(&object.info_log, bufsize, length, infolog);
+        }
+    }
+
+    unsafe fn glGetShaderPrecisionFormat(
+        &mut self,
+        shadertype: GLenum,
+        precisiontype: GLenum,
+        range: *mut GLint,
+        precision: *mut GLint,
+    ) {
+        if shadertype != GL_VERTEX_SHADER && shadertype != GL_FRAGMENT_SHADER {
+            return self.error(GL_INVALID_ENUM);
+        }
+
+        // Everything runs in single precision floats.
+        let (low, high, bits) = match precisiontype {
+            GL_LOW_FLOAT | GL_MEDIUM_FLOAT | GL_HIGH_FLOAT => (127, 127, 23),
+            GL_LOW_INT | GL_MEDIUM_INT | GL_HIGH_INT => (24, 24, 0),
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+        *range = low;
+        *range.add(1) = high;
+        *precision = bits;
+    }
+
+    unsafe fn glGetShaderSource(
+        &mut self,
+        shader: GLuint,
+        bufsize: GLsizei,
+        length: *mut GLsizei,
+        source: *mut GLchar,
+    ) {
+        if bufsize < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        if let Some(object) = self.shader_object(shader) {
+            write_string(&object.source, bufsize, length, source);
+        }
+    }
+
+    unsafe fn glGetString(&mut self, name: GLenum) -> *const GLubyte {
+        let value = match name {
+            GL_VENDOR => VENDOR,
+            GL_RENDERER => RENDERER,
+            GL_VERSION => VERSION,
+            GL_SHADING_LANGUAGE_VERSION => SHADING_LANGUAGE_VERSION,
+            GL_EXTENSIONS => EXTENSIONS,
+            _ => {
+                self.error(GL_INVALID_ENUM);
+                return ptr::null();
+            }
+        };
+
+        // Kept alive for as long as the backend, like the driver's static strings.
+        self.strings.entry(name).or_insert_with(|| CString::new(value).unwrap()).as_ptr() as *const GLubyte
+    }
+
+    unsafe fn glGetTexParameterfv(&mut self, target: GLenum, pname: GLenum, params: *mut GLfloat) {
+        if let Some(value) = self.texture_parameter(target, pname) {
+            *params = value as GLfloat;
+        }
+    }
+
+    unsafe fn glGetTexParameteriv(&mut self, target: GLenum, pname: GLenum, params: *mut GLint) {
+        if let Some(value) = self.texture_parameter(target, pname) {
+            *params = value;
+        }
+    }
+
+    unsafe fn glGetUniformfv(&mut self, program: GLuint, location: GLint, params: *mut GLfloat) {
+        let values = match self.linked_program(program) {
+            Some(linked) => linked.get_uniform(location),
+            None => return,
+        };
+        match values {
+            Some((floats, _)) => ptr::copy_nonoverlapping(floats.as_ptr(), params, floats.len()),
+            None => self.error(GL_INVALID_OPERATION),
+        }
+    }
+
+    unsafe fn glGetUniformiv(&mut self, program: GLuint, location: GLint, params: *mut GLint) {
+        let values = match self.linked_program(program) {
+            Some(linked) => linked.get_uniform(location),
+            None => return,
+        };
+        match values {
+            Some((_, ints)) => ptr::copy_nonoverlapping(ints.as_ptr(), params, ints.len()),
+            None => self.error(GL_INVALID_OPERATION),
+        }
+    }
+
+    unsafe fn glGetUniformLocation(&mut self, program: GLuint, name: *const GLchar) -> GLint {
+        let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+        self.linked_program(program).map_or(-1, |linked| linked.uniform_location(&name))
+    }
+
+    unsafe fn glGetVertexAttribfv(&mut self, index: GLuint, pname: GLenum, params: *mut GLfloat) {
+        let values: Vec<GLfloat> = match self.vertex_attribute(index, pname) {
+            Some(Query::Ints(values)) => values.iter().map(|&value| value as GLfloat).collect(),
+            Some(Query::Bools(values)) => values.iter().map(|&value| value as u8 as GLfloat).collect(),
+            Some(Query::Floats(values)) | Some(Query::Normalized(values)) => values,
+            None => return,
+        };
+        ptr::copy_nonoverlapping(values.as_ptr(), params, values.len());
+    }
+
+    unsafe fn glGetVertexAttribiv(&mut self, index: GLuint, pname: GLenum, params: *mut GLint) {
+        let values: Vec<GLint> = match self.vertex_attribute(index, pname) {
+            Some(Query::Ints(values)) => values,
+            Some(Query::Bools(values)) => values.iter().map(|&value| value as GLint).collect(),
+            Some(Query::Floats(values)) | Some(Query::Normalized(values)) => {
+                values.iter().map(|&value| value as GLint).collect()
+            }
+            None => return,
+        };
+        ptr::copy_nonoverlapping(values.as_ptr(), params, values.len());
+    }
+
+    unsafe fn glGetVertexAttribPointerv(
+        &mut self,
+        index: GLuint,
+        pname: GLenum,
+        pointer: *mut *mut GLvoid,
+    ) {
+        if pname != GL_VERTEX_ATTRIB_ARRAY_POINTER {
+            return self.error(GL_INVALID_ENUM);
+        }
+        match self.attributes.get(index as usize) {
+            Some(array) => *pointer = array.pointer as *mut GLvoid,
+            None => self.error(GL_INVALID_VALUE),
+        }
+    }
+
+    unsafe fn glHint(&mut self, target: GLenum, mode: GLenum) {
+        if target != GL_GENERATE_MIPMAP_HINT || !matches!(mode, GL_FASTEST | GL_NICEST | GL_DONT_CARE) {
+            return self.error(GL_INVALID_ENUM);
+        }
+        self.state.generate_mipmap_hint = mode;
+    }
+
+    unsafe fn glIsBuffer(&mut self, buffer: GLuint) -> GLboolean {
+        self.buffers.get(&buffer).is_some_and(|buffer| buffer.bound) as GLboolean
+    }
+
+    unsafe fn glIsEnabled(&mut self, cap: GLenum) -> GLboolean {
+        match self.state.capability(cap) {
+            Some(&mut enabled) => enabled as GLboolean,
+            None => {
+                self.error(GL_INVALID_ENUM);
+                0
+            }
+        }
+    }
+
+    unsafe fn glIsFramebuffer(&mut self, framebuffer: GLuint) -> GLboolean {
+        self.framebuffers.get(&framebuffer).is_some_and(|framebuffer| framebuffer.bound) as GLboolean
+    }
+
+    unsafe fn glIsProgram(&mut self, program: GLuint) -> GLboolean {
+        self.programs.contains_key(&program) as GLboolean
+    }
+
+    unsafe fn glIsRenderbuffer(&mut self, renderbuffer: GLuint) -> GLboolean {
+        self.renderbuffers.get(&renderbuffer).is_some_and(|renderbuffer| renderbuffer.bound) as GLboolean
+    }
+
+    unsafe fn glIsShader(&mut self, shader: GLuint) -> GLboolean {
+        self.shaders.contains_key(&shader) as GLboolean
+    }
+
+    unsafe fn glIsTexture(&mut self, texture: GLuint) -> GLboolean {
+        (texture != 0 && self.textures.get(&texture).is_some_and(|texture| texture.target != 0)) as GLboolean
+    }
+
+    unsafe fn glLineWidth(&mut self, width: GLfloat) {
+        if width <= 0.0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        self.state.line_width = width;
+    }
+
+    unsafe fn glLinkProgram(&mut self, program: GLuint) {
+        if self.program_object(program).is_none() {
+            return;
+        }
+
+        let result = match self.registry.get(program) {
+            Some(closures) => Ok((Stages::Closures(closures), None)),
+            None => self.link_shaders(program).map(|(linked, binary)| (Stages::Glsl(Box::new(linked)), Some(binary))),
+        }.and_then(|(stages, binary)| {
+            LinkedProgram::link(stages, &self.programs[&program].bindings, MAX_VERTEX_ATTRIBS)
+                .map(|linked| (linked, binary))
+        });
+        self.set_link_result(program, result);
+    }
+
+    unsafe fn glPixelStorei(&mut self, pname: GLenum, param: GLint) {
+        if !matches!(param, 1 | 2 | 4 | 8) {
+            return self.error(GL_INVALID_VALUE);
+        }
+        match pname {
+            GL_PACK_ALIGNMENT => self.state.pack_alignment = param,
+            GL_UNPACK_ALIGNMENT => self.state.unpack_alignment = param,
+            _ => self.error(GL_INVALID_ENUM),
+        }
+    }
+
+    unsafe fn glPolygonOffset(&mut self, factor: GLfloat, units: GLfloat) {
+        self.state.polygon_offset = [factor, units];
+    }
+
+    unsafe fn glReadPixels(
+        &mut self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *mut GLvoid,
+    ) {
+        let info = match pixels::check_transfer(format, type_) {
+            Ok(info) => info,
+            Err(error) => return self.error(error),
+        };
+        if width < 0 || height < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        if !self.check_framebuffer() {
+            return;
+        }
+
+        let surface = match self.read_surface() {
+            Some(surface) => surface,
+            None => return self.error(GL_INVALID_OPERATION),
+        };
+        if (format, type_) != (GL_RGBA, GL_UNSIGNED_BYTE) && (format, type_) != pixels::read_format(surface) {
+            return self.error(GL_INVALID_OPERATION);
+        }
+
+        let alignment = self.state.pack_alignment as usize;
+        let size = info.image_size(width as usize, height as usize, alignment);
+        if size == 0 {
+            return;
+        }
+        let out = slice::from_raw_parts_mut(pixels as *mut u8, size);
+        pixels::pack(surface, x as i64, y as i64, width as usize, height as usize, info, alignment, out);
+    }
+
+    unsafe fn glReleaseShaderCompiler(&mut self) {}
+
+    unsafe fn glRenderbufferStorage(
+        &mut self,
+        target: GLenum,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        if target != GL_RENDERBUFFER {
+            return self.error(GL_INVALID_ENUM);
+        }
+        if width < 0 || height < 0 || width > MAX_TEXTURE_SIZE || height > MAX_TEXTURE_SIZE {
+            return self.error(GL_INVALID_VALUE);
+        }
+        if self.renderbuffer == 0 {
+            return self.error(GL_INVALID_OPERATION);
+        }
+
+        let (width, height) = (width as usize, height as usize);
+        // GL_RGB8 and GL_RGBA8 share their values with the OES_rgb8_rgba8 formats.
+        let surface = match internalformat {
+            GL_RGBA4 | GL_RGB5_A1 | GL_RGB565 | GL_RGB8 | GL_RGBA8 => {
+                let bits = FormatInfo::for_internal_format(internalformat).unwrap().bits;
+                Surface::color(width, height, internalformat, bits)
+            }
+            GL_DEPTH_COMPONENT16 => Surface::depth(width, height, internalformat, 16),
+            GL_STENCIL_INDEX8 => Surface::stencil(width, height, internalformat),
+            _ => return self.error(GL_INVALID_ENUM),
+        };
+
+        self.renderbuffers.get_mut(&self.renderbuffer).unwrap().surface = surface;
+    }
+
+    unsafe fn glSampleCoverage(&mut self, value: GLclampf, invert: GLboolean) {
+        self.state.sample_coverage_value = clamp(value);
+        self.state.sample_coverage_invert = invert != 0;
+    }
+
+    unsafe fn glScissor(&mut self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        if width < 0 || height < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        self.state.scissor = [x, y, width, height];
+    }
+
+    unsafe fn glShaderBinary(
+        &mut self,
+        n: GLsizei,
+        shaders: *const GLuint,
+        binaryformat: GLenum,
+        binary: *const GLvoid,
+        length: GLsizei,
+    ) {
+        // No binary formats are supported.
+        let _ = (n, shaders, binaryformat, binary, length);
+        self.error(GL_INVALID_ENUM);
+    }
+
+    unsafe fn glShaderSource(
+        &mut self,
+        shader: GLuint,
+        count: GLsizei,
+        string: *const *const GLchar,
+        length: *const GLint,
+    ) {
+        if count < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+
+        let mut source = String::new();
+        for index in 0..count as usize {
+            let text = *string.add(index);
+            let size = if length.is_null() { -1 } else { *length.add(index) };
+            let bytes = if size < 0 {
+                CStr::from_ptr(text).to_bytes()
+            } else {
+                slice::from_raw_parts(text as *const u8, size as usize)
+            };
+            source.push_str(&String::from_utf8_lossy(bytes));
+        }
+
+        if let Some(object) = self.shader_object(shader) {
+            object.source = source;
+        }
+    }
+
+    unsafe fn glStencilFunc(&mut self, func: GLenum, ref_: GLint, mask: GLuint) {
+        self.glStencilFuncSeparate(GL_FRONT_AND_BACK, func, ref_, mask)
+    }
+
+    unsafe fn glStencilFuncSeparate(
+        &mut self,
+        face: GLenum,
+        func: GLenum,
+        ref_: GLint,
+        mask: GLuint,
+    ) {
+        if !is_compare_func(func) {
+            return self.error(GL_INVALID_ENUM);
+        }
+        let faces = match self.stencil_faces(face) {
+            Some(faces) => faces,
+            None => return,
+        };
+        for &index in faces {
+            let state = &mut self.state.stencil[index];
+            state.func = func;
+            state.ref_ = ref_;
+            state.value_mask = mask;
+        }
+    }
+
+    unsafe fn glStencilMask(&mut self, mask: GLuint) {
+        self.glStencilMaskSeparate(GL_FRONT_AND_BACK, mask)
+    }
+
+    unsafe fn glStencilMaskSeparate(&mut self, face: GLenum, mask: GLuint) {
+        let faces = match self.stencil_faces(face) {
+            Some(faces) => faces,
+            None => return,
+        };
+        for &index in faces {
+            let state = &mut self.state.stencil[index];
+            state.write_mask = mask;
+        }
+    }
+
+    unsafe fn glStencilOp(&mut self, fail: GLenum, zfail: GLenum, zpass: GLenum) {
+        self.glStencilOpSeparate(GL_FRONT_AND_BACK, fail, zfail, zpass)
+    }
+
+    unsafe fn glStencilOpSeparate(
+        &mut self,
+        face: GLenum,
+        fail: GLenum,
+        zfail: GLenum,
+        zpass: GLenum,
+    ) {
+        if ![fail, zfail, zpass].iter().all(|&op| is_stencil_op(op)) {
+            return self.error(GL_INVALID_ENUM);
+        }
+        let faces = match self.stencil_faces(face) {
+            Some(faces) => faces,
+            None => return,
+        };
+        for &index in faces {
+            let state = &mut self.state.stencil[index];
+            state.fail = fail;
+            state.depth_fail = zfail;
+            state.depth_pass = zpass;
+        }
+    }
+
+    unsafe fn glTexImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        internalformat: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *const GLvoid,
+    ) {
+        let (texture, face) = match self.check_image(target, level, width, height, border) {
+            Some(image) => image,
+            None => return,
+        };
+        let info = match pixels::check_transfer(format, type_) {
+            Ok(info) => info,
+            Err(error) => return self.error(error),
+        };
+        if internalformat as GLenum != format {
+            return self.error(if pixels::check_transfer(internalformat as GLenum, type_).is_err() {
+                GL_INVALID_VALUE
+            } else {
+                GL_INVALID_OPERATION
+            });
+        }
+
+        let (width, height) = (width as usize, height as usize);
+        let mut image = Surface::color(width, height, format, pixels::storage_bits(info));
+        if !pixels.is_null() {
+            let alignment = self.state.unpack_alignment as usize;
+            let data = slice::from_raw_parts(pixels as *const u8, info.image_size(width, height, alignment));
+            image.color = pixels::unpack(info, width, height, alignment, data);
+        }
+
+        *self.textures.get_mut(&texture).unwrap().level_mut(face, level as usize) = image;
+    }
+
+    unsafe fn glTexParameterf(&mut self, target: GLenum, pname: GLenum, param: GLfloat) {
+        self.set_texture_parameter(target, pname, param as GLenum)
+    }
+
+    unsafe fn glTexParameterfv(&mut self, target: GLenum, pname: GLenum, params: *const GLfloat) {
+        self.set_texture_parameter(target, pname, *params as GLenum)
+    }
+
+    unsafe fn glTexParameteri(&mut self, target: GLenum, pname: GLenum, param: GLint) {
+        self.set_texture_parameter(target, pname, param as GLenum)
+    }
+
+    unsafe fn glTexParameteriv(&mut self, target: GLenum, pname: GLenum, params: *const GLint) {
+        self.set_texture_parameter(target, pname, *params as GLenum)
+    }
+
+    unsafe fn glTexSubImage2D(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        type_: GLenum,
+        pixels: *const GLvoid,
+    ) {
+        let (texture, face) = match self.check_sub_image(target, level, xoffset, yoffset, width, height) {
+            Some(image) => image,
+            None => return,
+        };
+        let info = match pixels::check_transfer(format, type_) {
+            Ok(info) => info,
+            Err(error) => return self.error(error),
+        };
+        if self.textures[&texture].level(face, level as usize).unwrap().internal_format != format {
+            return self.error(GL_INVALID_OPERATION);
+        }
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let (width, height) = (width as usize, height as usize);
+        let alignment = self.state.unpack_alignment as usize;
+        let data = slice::from_raw_parts(pixels as *const u8, info.image_size(width, height, alignment));
+        let texels = pixels::unpack(info, width, height, alignment, data);
+
+        let image = self.textures.get_mut(&texture).unwrap().level_mut(face, level as usize);
+        for (index, texel) in texels.into_iter().enumerate() {
+            let (x, y) = (xoffset as usize + index % width, yoffset as usize + index / width);
+            image.color[y * image.width + x] = image.requantize(texel);
+        }
+    }
+
+    unsafe fn glUniform1f(&mut self, location: GLint, x: GLfloat) {
+        self.uniform(location, 1, 1, false, Some(&[x]), None)
+    }
+
+    unsafe fn glUniform1fv(&mut self, location: GLint, count: GLsizei, v: *const GLfloat) {
+        self.uniform(location, count, 1, false, Some(client_slice(v, count.max(0) as usize)), None)
+    }
+
+    unsafe fn glUniform1i(&mut self, location: GLint, x: GLint) {
+        self.uniform(location, 1, 1, false, None, Some(&[x]))
+    }
+
+    unsafe fn glUniform1iv(&mut self, location: GLint, count: GLsizei, v: *const GLint) {
+        self.uniform(location, count, 1, false, None, Some(client_slice(v, count.max(0) as usize)))
+    }
+
+    unsafe fn glUniform2f(&mut self, location: GLint, x: GLfloat, y: GLfloat) {
+        self.uniform(location, 1, 2, false, Some(&[x, y]), None)
+    }
+
+    unsafe fn glUniform2fv(&mut self, location: GLint, count: GLsizei, v: *const GLfloat) {
+        self.uniform(location, count, 2, false, Some(client_slice(v, 2 * count.max(0) as usize)), None)
+    }
+
+    unsafe fn glUniform2i(&mut self, location: GLint, x: GLint, y: GLint) {
+        self.uniform(location, 1, 2, false, None, Some(&[x, y]))
+    }
+
+    unsafe fn glUniform2iv(&mut self, location: GLint, count: GLsizei, v: *const GLint) {
+        self.uniform(location, count, 2, false, None, Some(client_slice(v, 2 * count.max(0) as usize)))
+    }
+
+    unsafe fn glUniform3f(&mut self, location: GLint, x: GLfloat, y: GLfloat, z: GLfloat) {
+        self.uniform(location, 1, 3, false, Some(&[x, y, z]), None)
+    }
+
+    unsafe fn glUniform3fv(&mut self, location: GLint, count: GLsizei, v: *const GLfloat) {
+        self.uniform(location, count, 3, false, Some(client_slice(v, 3 * count.max(0) as usize)), None)
+    }
+
+    unsafe fn glUniform3i(&mut self, location: GLint, x: GLint, y: GLint, z: GLint) {
+        self.uniform(location, 1, 3, false, None, Some(&[x, y, z]))
+    }
+
+    unsafe fn glUniform3iv(&mut self, location: GLint, count: GLsizei, v: *const GLint) {
+        self.uniform(location, count, 3, false, None, Some(client_slice(v, 3 * count.max(0) as usize)))
+    }
+
+    unsafe fn glUniform4f(
+        &mut self,
+        location: GLint,
+        x: GLfloat,
+        y: GLfloat,
+        z: GLfloat,
+        w: GLfloat,
+    ) {
+        self.uniform(location, 1, 4, false, Some(&[x, y, z, w]), None)
+    }
+
+    unsafe fn glUniform4fv(&mut self, location: GLint, count: GLsizei, v: *const GLfloat) {
+        self.uniform(location, count, 4, false, Some(client_slice(v, 4 * count.max(0) as usize)), None)
+    }
+
+    unsafe fn glUniform4i(&mut self, location: GLint, x: GLint, y: GLint, z: GLint, w: GLint) {
+        self.uniform(location, 1, 4, false, None, Some(&[x, y, z, w]))
+    }
+
+    unsafe fn glUniform4iv(&mut self, location: GLint, count: GLsizei, v: *const GLint) {
+        self.uniform(location, count, 4, false, None, Some(client_slice(v, 4 * count.max(0) as usize)))
+    }
+
+    unsafe fn glUniformMatrix2fv(
+        &mut self,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    ) {
+        if transpose != 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        self.uniform(location, count, 2, true, Some(client_slice(value, 4 * count.max(0) as usize)), None)
+    }
+
+    unsafe fn glUniformMatrix3fv(
+        &mut self,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    ) {
+        if transpose != 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        self.uniform(location, count, 3, true, Some(client_slice(value, 9 * count.max(0) as usize)), None)
+    }
+
+    unsafe fn glUniformMatrix4fv(
+        &mut self,
+        location: GLint,
+        count: GLsizei,
+        transpose: GLboolean,
+        value: *const GLfloat,
+    ) {
+        if transpose != 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        self.uniform(location, count, 4, true, Some(client_slice(value, 16 * count.max(0) as usize)), None)
+    }
+
+    unsafe fn glUseProgram(&mut self, program: GLuint) {
+        if program != 0 {
+            let linked = match self.program_object(program) {
+                Some(object) => object.linked,
+                None => return,
+            };
+            if !linked {
+                return self.error(GL_INVALID_OPERATION);
+            }
+        }
+
+        let previous = mem::replace(&mut self.current_program, program);
+        if previous != program && self.programs.get(&previous).is_some_and(|object| object.delete_pending) {
+            self.delete_program_now(previous);
+        }
+    }
+
+    unsafe fn glValidateProgram(&mut self, program: GLuint) {
+        let object = match self.program_object(program) {
+            Some(object) => object,
+            None => return,
+        };
+
+        let conflict = object.executable.as_ref().filter(|_| object.linked).map(|linked| {
+            let samplers = linked.sampler_units();
+            samplers.iter().any(|&(type_, unit)| samplers.iter().any(|&(other, other_unit)| unit == other_unit && type_ != other))
+        });
+
+        object.validated = conflict == Some(false);
+        object.info_log = match conflict {
+            None => "the program is not linked".to_string(),
+            Some(true) => "samplers of different types use the same texture unit".to_string(),
+            Some(false) => String::new(),
+        };
+    }
+
+    unsafe fn glVertexAttrib1f(&mut self, indx: GLuint, x: GLfloat) {
+        self.glVertexAttrib4f(indx, x, 0.0, 0.0, 1.0)
+    }
+
+    unsafe fn glVertexAttrib1fv(&mut self, indx: GLuint, values: *const GLfloat) {
+        self.glVertexAttrib4f(indx, *values, 0.0, 0.0, 1.0)
+    }
+
+    unsafe fn glVertexAttrib2f(&mut self, indx: GLuint, x: GLfloat, y: GLfloat) {
+        self.glVertexAttrib4f(indx, x, y, 0.0, 1.0)
+    }
+
+    unsafe fn glVertexAttrib2fv(&mut self, indx: GLuint, values: *const GLfloat) {
+        self.glVertexAttrib4f(indx, *values, *values.add(1), 0.0, 1.0)
+    }
+
+    unsafe fn glVertexAttrib3f(&mut self, indx: GLuint, x: GLfloat, y: GLfloat, z: GLfloat) {
+        self.glVertexAttrib4f(indx, x, y, z, 1.0)
+    }
+
+    unsafe fn glVertexAttrib3fv(&mut self, indx: GLuint, values: *const GLfloat) {
+        self.glVertexAttrib4f(indx, *values, *values.add(1), *values.add(2), 1.0)
+    }
+
+    unsafe fn glVertexAttrib4f(
+        &mut self,
+        indx: GLuint,
+        x: GLfloat,
+        y: GLfloat,
+        z: GLfloat,
+        w: GLfloat,
+    ) {
+        match self.current_attributes.get_mut(indx as usize) {
+            Some(value) => *value = [x, y, z, w],
+            None => self.error(GL_INVALID_VALUE),
+        }
+    }
+
+    unsafe fn glVertexAttrib4fv(&mut self, indx: GLuint, values: *const GLfloat) {
+        self.glVertexAttrib4f(indx, *values, *values.add(1), *values.add(2), *values.add(3))
+    }
+
+    unsafe fn glVertexAttribPointer(
+        &mut self,
+        indx: GLuint,
+        size: GLint,
+        type_: GLenum,
+        normalized: GLboolean,
+        stride: GLsizei,
+        ptr: *const GLvoid,
+    ) {
+        if !matches!(type_, GL_BYTE | GL_UNSIGNED_BYTE | GL_SHORT | GL_UNSIGNED_SHORT | GL_FIXED | GL_FLOAT) {
+            return self.error(GL_INVALID_ENUM);
+        }
+        if indx as usize >= MAX_VERTEX_ATTRIBS || !(1..=4).contains(&size) || stride < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+
+        let array = &mut self.attributes[indx as usize];
+        array.size = size;
+        array.type_ = type_;
+        array.normalized = normalized != 0;
+        array.stride = stride;
+        array.pointer = ptr as usize;
+        array.buffer = self.array_buffer;
+    }
+
+    unsafe fn glViewport(&mut self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        if width < 0 || height < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        self.state.viewport = [x, y, width.min(MAX_TEXTURE_SIZE), height.min(MAX_TEXTURE_SIZE)];
+    }
+
+    unsafe fn glGetProgramBinary(
+        &mut self,
+        program: GLuint,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        binaryFormat: *mut GLenum,
+        binary: *mut GLvoid,
+    ) {
+        let object = match self.program_object(program) {
+            Some(object) => object,
+            None => return,
+        };
+        let data = match object.binary {
+            Some(ref data) if object.linked => data.clone(),
+            _ => return self.error(GL_INVALID_OPERATION),
+        };
+        if bufSize < data.len() as GLsizei {
+            return self.error(GL_INVALID_OPERATION);
+        }
+
+        ptr::copy_nonoverlapping(data.as_ptr(), binary as *mut u8, data.len());
+        *binaryFormat = PROGRAM_BINARY_FORMAT;
+        if !length.is_null() {
+            *length = data.len() as GLsizei;
+        }
+    }
+
+    unsafe fn glProgramBinary(
+        &mut self,
+        program: GLuint,
+        binaryFormat: GLenum,
+        binary: *const GLvoid,
+        length: GLsizei,
+    ) {
+        if self.program_object(program).is_none() {
+            return;
+        }
+        if binaryFormat != PROGRAM_BINARY_FORMAT {
+            return self.error(GL_INVALID_ENUM);
+        }
+        if length < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+
+        let data = slice::from_raw_parts(binary as *const u8, length as usize);
+        let result = decode_binary(data).ok_or_else(|| "the program binary is invalid".to_string())
+            .and_then(|(vertex, fragment, bindings)| {
+                let compile = |source: &str, type_| glsl::compile(source, type_, &LIMITS)
+                    .map_err(|diagnostics| glsl::info_log(&diagnostics));
+                let vertex = compile(&vertex, ShaderType::VERTEX_SHADER)?;
+                let fragment = compile(&fragment, ShaderType::FRAGMENT_SHADER)?;
+                let stages = Stages::Glsl(Box::new(glsl::link(&vertex, &fragment)?));
+                LinkedProgram::link(stages, &bindings, MAX_VERTEX_ATTRIBS)
+            })
+            .map(|linked| (linked, Some(data.to_vec())));
+        self.set_link_result(program, result);
+    }
+
+    unsafe fn glGetProgramBinaryOES(
+        &mut self,
+        program: GLuint,
+        bufSize: GLsizei,
+        length: *mut GLsizei,
+        binaryFormat: *mut GLenum,
+        binary: *mut GLvoid,
+    ) {
+        self.glGetProgramBinary(program, bufSize, length, binaryFormat, binary)
+    }
+
+    unsafe fn glProgramBinaryOES(
+        &mut self,
+        program: GLuint,
+        binaryFormat: GLenum,
+        binary: *const GLvoid,
+        length: GLsizei,
+    ) {
+        self.glProgramBinary(program, binaryFormat, binary, length)
+    }
+
+    unsafe fn glBindVertexArray(&mut self, array: GLuint) {
+        if array != 0 && !self.vertex_arrays.contains_key(&array) {
+            return self.error(GL_INVALID_OPERATION);
+        }
+        if array == self.vertex_array {
+            return;
+        }
+
+        self.vertex_arrays.insert(self.vertex_array, VertexArrayObject {
+            attributes: self.attributes,
+            element_array_buffer: self.element_array_buffer,
+        });
+        let object = self.vertex_arrays[&array];
+        self.attributes = object.attributes;
+        self.element_array_buffer = object.element_array_buffer;
+        self.vertex_array = array;
+    }
+
+    unsafe fn glDeleteVertexArrays(&mut self, n: GLsizei, arrays: *const GLuint) {
+        if n < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+
+        for &array in client_slice(arrays, n as usize) {
+            if array == 0 {
+                continue;
+            }
+            if array == self.vertex_array {
+                self.glBindVertexArray(0);
+            }
+            self.vertex_arrays.remove(&array);
+        }
+    }
+
+    unsafe fn glGenVertexArrays(&mut self, n: GLsizei, arrays: *mut GLuint) {
+        if n < 0 {
+            return self.error(GL_INVALID_VALUE);
+        }
+        for index in 0..n as usize {
+            let name = self.new_name();
+            self.vertex_arrays.insert(name, VertexArrayObject {
+                attributes: [AttributeArray::default(); MAX_VERTEX_ATTRIBS],
+                element_array_buffer: 0,
+            });
+            *arrays.add(index) = name;
+        }
+    }
+
+    unsafe fn glBindVertexArrayOES(&mut self, array: GLuint) {
+        self.glBindVertexArray(array)
+    }
+
+    unsafe fn glDeleteVertexArraysOES(&mut self, n: GLsizei, arrays: *const GLuint) {
+        self.glDeleteVertexArrays(n, arrays)
+    }
+
+    unsafe fn glGenVertexArraysOES(&mut self, n: GLsizei, arrays: *mut GLuint) {
+        self.glGenVertexArrays(n, arrays)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+// The compressed formats uploads accept, which are the ones `decode` turns into RGBA8.
+fn compressed_formats() -> Vec<GLenum> {
+    let mut formats = vec![
+        GL_ETC1_RGB8_OES,
+        GL_COMPRESSED_RGB8_ETC2,
+        GL_COMPRESSED_SRGB8_ETC2,
+        GL_COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+        GL_COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+        GL_COMPRESSED_RGBA8_ETC2_EAC,
+        GL_COMPRESSED_SRGB8_ALPHA8_ETC2_EAC,
+        GL_COMPRESSED_R11_EAC,
+        GL_COMPRESSED_RG11_EAC,
+    ];
+    formats.extend(GL_COMPRESSED_RGBA_ASTC_4x4..=GL_COMPRESSED_RGBA_ASTC_12x12);
+    formats.extend(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4x4..=GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12x12);
+    formats.retain(|&format| decode::can_decode(format) && FormatInfo::for_internal_format(format).is_some());
+    formats
+}
+
+unsafe fn client_slice<'a, T>(data: *const T, length: usize) -> &'a [T] {
+    if data.is_null() || length == 0 { &[] } else { slice::from_raw_parts(data, length) }
+}
+
+// The program binary of the sources and attribute bindings a program was linked from:
+// `PROGRAM_BINARY_MAGIC`, the vertex and the fragment source, the binding count and each binding
+// as index and name. Strings are a little-endian u32 length and UTF-8 bytes.
+fn encode_binary(vertex: &str, fragment: &str, bindings: &HashMap<String, GLuint>) -> Vec<u8> {
+    fn string(binary: &mut Vec<u8>, value: &str) {
+        binary.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        binary.extend_from_slice(value.as_bytes());
+    }
+
+    let mut binary = PROGRAM_BINARY_MAGIC.to_vec();
+    string(&mut binary, vertex);
+    string(&mut binary, fragment);
+    let mut bindings: Vec<_> = bindings.iter().collect();
+    bindings.sort();
+    binary.extend_from_slice(&(bindings.len() as u32).to_le_bytes());
+    for (name, index) in bindings {
+        binary.extend_from_slice(&index.to_le_bytes());
+        string(&mut binary, name);
+    }
+    binary
+}
+
+// The sources and bindings of a binary from `encode_binary`, `None` if it is not one.
+fn decode_binary(mut binary: &[u8]) -> Option<(String, String, HashMap<String, GLuint>)> {
+    fn u32_(binary: &mut &[u8]) -> Option<u32> {
+        let bytes = binary.get(..4)?;
+        *binary = &binary[4..];
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    fn string(binary: &mut &[u8]) -> Option<String> {
+        let length = u32_(binary)? as usize;
+        let bytes = binary.get(..length)?;
+        *binary = &binary[length..];
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+
+    binary = binary.strip_prefix(PROGRAM_BINARY_MAGIC)?;
+    let vertex = string(&mut binary)?;
+    let fragment = string(&mut binary)?;
+    let mut bindings = HashMap::new();
+    for _ in 0..u32_(&mut binary)? {
+        let index = u32_(&mut binary)?;
+        bindings.insert(string(&mut binary)?, index);
+    }
+    if !binary.is_empty() {
+        return None;
+    }
+    Some((vertex, fragment, bindings))
+}
+
+// Copies `value` into a client buffer of `size` bytes the way the info log queries do, always
+// NUL-terminated and truncated if needed.
+unsafe fn write_string(value: &str, size: GLsizei, length: *mut GLsizei, out: *mut GLchar) {
+    let written = if size > 0 && !out.is_null() {
+        let written = value.len().min(size as usize - 1);
+        ptr::copy_nonoverlapping(value.as_ptr() as *const GLchar, out, written);
+        *out.add(written) = 0;
+        written
+    } else {
+        0
+    };
+
+    if !length.is_null() {
+        *length = written as GLsizei;
+    }
+}
+
+// The length `*_LENGTH` queries report, which counts the terminator of non-empty strings.
+fn string_length(value: &str) -> GLint {
+    if value.is_empty() { 0 } else { value.len() as GLint + 1 }
+}
+
+fn is_compare_func(func: GLenum) -> bool {
+    matches!(func, GL_NEVER | GL_LESS | GL_EQUAL | GL_LEQUAL | GL_GREATER | GL_NOTEQUAL | GL_GEQUAL | GL_ALWAYS)
+}
+
+fn is_stencil_op(op: GLenum) -> bool {
+    matches!(op, GL_KEEP | GL_ZERO | GL_REPLACE | GL_INCR | GL_DECR | GL_INVERT | GL_INCR_WRAP | GL_DECR_WRAP)
+}
+
+fn is_blend_equation(mode: GLenum) -> bool {
+    matches!(mode, GL_FUNC_ADD | GL_FUNC_SUBTRACT | GL_FUNC_REVERSE_SUBTRACT)
+}
+
+fn is_blend_factor(factor: GLenum) -> bool {
+    matches!(factor, GL_ZERO | GL_ONE | GL_SRC_COLOR | GL_ONE_MINUS_SRC_COLOR | GL_DST_COLOR | GL_ONE_MINUS_DST_COLOR
+        | GL_SRC_ALPHA | GL_ONE_MINUS_SRC_ALPHA | GL_DST_ALPHA | GL_ONE_MINUS_DST_ALPHA | GL_CONSTANT_COLOR
+        | GL_ONE_MINUS_CONSTANT_COLOR | GL_CONSTANT_ALPHA | GL_ONE_MINUS_CONSTANT_ALPHA | GL_SRC_ALPHA_SATURATE)
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enums::{BeginMode, DataType, FeatureType, FuncType, ProgramParamType, ShaderParamType, ShaderType};
+    use es20::client_array::Draw;
+    use es20::wrapper::Wrapper;
+    use self::program::{ClosureProgram, VertexOutput};
+
+    const QUAD: [f32; 8] = [-1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0, 1.0];
+
+    // A wrapper over a software backend and a program that draws `position` at the depth and in
+    // the color of its uniforms.
+    fn solid(width: u32, height: u32) -> (Wrapper, u32) {
+        let backend = SoftwareBackend::new(width, height);
+        let programs = backend.programs();
+        let mut gl = Wrapper::with_backend(Box::new(backend));
+
+        let program = gl.gl_create_program().unwrap();
+        let closures = ClosureProgram::new(|vertex| {
+            let position = vertex.attribute(0);
+            VertexOutput::new([position[0], position[1], vertex.uniforms().float("depth"), 1.0], Vec::new())
+        }, |fragment| Some(fragment.uniforms().vec4("color")));
+        programs.register(program, closures.attribute("position", DataType::FLOAT_VEC2)
+            .uniform("depth", DataType::FLOAT).uniform("color", DataType::FLOAT_VEC4));
+        gl.gl_link_program(program).unwrap();
+        gl.gl_use_program(program).unwrap();
+        (gl, program)
+    }
+
+    fn draw(gl: &mut Wrapper, program: u32, positions: &[f32], depth: f32, color: [f32; 4]) {
+        let location = gl.gl_get_uniform_location(program, "depth").unwrap();
+        gl.gl_uniform1f(location, depth).unwrap();
+        let location = gl.gl_get_uniform_location(program, "color").unwrap();
+        gl.gl_uniform4f(location, color[0], color[1], color[2], color[3]).unwrap();
+        let location = gl.gl_get_attrib_location(program, "position").unwrap() as u32;
+        let mut draw = Draw::new(gl);
+        draw.client_attribute(location, 2, DataType::FLOAT, false, 0, positions).unwrap();
+        draw.draw_arrays(BeginMode::TRIANGLE_STRIP, 0, positions.len() as i32 / 2).unwrap();
+    }
+
+    fn pixel(gl: &mut Wrapper, x: i32, y: i32) -> [u8; 4] {
+        let mut pixel = [0u8; 4];
+        gl.gl_read_pixels(x, y, 1, 1, PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE, &mut pixel).unwrap();
+        pixel
+    }
+
+    #[test]
+    fn clears_the_color_buffer() {
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(2, 2)));
+        gl.gl_clear_color(0.0, 0.0, 1.0, 1.0).unwrap();
+        gl.gl_clear(GL_COLOR_BUFFER_BIT).unwrap();
+
+        let mut pixels = [0u8; 16];
+        gl.gl_read_pixels(0, 0, 2, 2, PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE, &mut pixels).unwrap();
+        assert_eq!(pixels, [0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn draws_only_inside_the_triangle() {
+        let (mut gl, program) = solid(4, 4);
+        gl.gl_clear(GL_COLOR_BUFFER_BIT).unwrap();
+
+        draw(&mut gl, program, &[-1.0, -1.0, 1.0, -1.0, -1.0, 1.0], 0.0, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(pixel(&mut gl, 0, 0), [255, 0, 0, 255]);
+        assert_eq!(pixel(&mut gl, 2, 0), [255, 0, 0, 255]);
+        assert_eq!(pixel(&mut gl, 3, 3), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn depth_test_keeps_the_nearest_fragment() {
+        let (mut gl, program) = solid(2, 2);
+        gl.gl_enable(FeatureType::DEPTH_TEST).unwrap();
+        gl.gl_depth_func(FuncType::LESS).unwrap();
+        gl.gl_clear(GL_COLOR_BUFFER_BIT | GL_DEPTH_BUFFER_BIT).unwrap();
+
+        draw(&mut gl, program, &QUAD, -0.5, [1.0, 0.0, 0.0, 1.0]);
+        draw(&mut gl, program, &QUAD, 0.5, [0.0, 1.0, 0.0, 1.0]);
+        assert_eq!(pixel(&mut gl, 1, 1), [255, 0, 0, 255]);
+
+        draw(&mut gl, program, &QUAD, -0.75, [0.0, 1.0, 0.0, 1.0]);
+        assert_eq!(pixel(&mut gl, 1, 1), [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn interpolates_varyings() {
+        let backend = SoftwareBackend::new(4, 1);
+        let programs = backend.programs();
+        let mut gl = Wrapper::with_backend(Box::new(backend));
+
+        let program = gl.gl_create_program().unwrap();
+        let closures = ClosureProgram::new(|vertex| {
+            let position = vertex.attribute(0);
+            VertexOutput::new([position[0], position[1], 0.0, 1.0], vec![position[0] * 0.5 + 0.5])
+        }, |fragment| Some([fragment.varying(0), 0.0, 0.0, 1.0]));
+        programs.register(program, closures.attribute("position", DataType::FLOAT_VEC2).varyings(1));
+        gl.gl_link_program(program).unwrap();
+        gl.gl_use_program(program).unwrap();
+
+        let location = gl.gl_get_attrib_location(program, "position").unwrap() as u32;
+        let mut draw = Draw::new(&mut gl);
+        draw.client_attribute(location, 2, DataType::FLOAT, false, 0, &QUAD).unwrap();
+        draw.draw_arrays(BeginMode::TRIANGLE_STRIP, 0, 4).unwrap();
+        drop(draw);
+
+        let reds: Vec<u8> = (0..4).map(|x| pixel(&mut gl, x, 0)[0]).collect();
+        assert_eq!(reds, [32, 96, 159, 223]);
+    }
+
+    #[test]
+    fn runs_glsl_shaders() {
+        let vertex = "attribute vec2 position;\nvoid main() { gl_Position = vec4(position, 0.0, 1.0); }\n";
+        let fragment = "precision mediump float;\nuniform vec4 color;\nvoid main() { gl_FragColor = color.bgra; }\n";
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(2, 2)));
+
+        let program = gl.gl_create_program().unwrap();
+        for &(type_, source) in &[(ShaderType::VERTEX_SHADER, vertex), (ShaderType::FRAGMENT_SHADER, fragment)] {
+            let shader = gl.gl_create_shader(type_).unwrap();
+            gl.gl_shader_source(shader, source).unwrap();
+            gl.gl_compile_shader(shader).unwrap();
+            assert_eq!(gl.gl_get_shaderiv(shader, ShaderParamType::COMPILE_STATUS).unwrap(), 1);
+            gl.gl_attach_shader(program, shader).unwrap();
+        }
+        gl.gl_bind_attrib_location(program, 0, "position").unwrap();
+        gl.gl_link_program(program).unwrap();
+        assert_eq!(gl.gl_get_programiv(program, ProgramParamType::LINK_STATUS).unwrap(), 1);
+        gl.gl_use_program(program).unwrap();
+
+        let location = gl.gl_get_uniform_location(program, "color").unwrap();
+        gl.gl_uniform4f(location, 1.0, 0.0, 0.0, 1.0).unwrap();
+        let mut draw = Draw::new(&mut gl);
+        draw.client_attribute(0, 2, DataType::FLOAT, false, 0, &QUAD).unwrap();
+        draw.draw_arrays(BeginMode::TRIANGLE_STRIP, 0, 4).unwrap();
+        drop(draw);
+        assert_eq!(pixel(&mut gl, 1, 1), [0, 0, 255, 255]);
+
+        let shader = gl.gl_create_shader(ShaderType::FRAGMENT_SHADER).unwrap();
+        gl.gl_shader_source(shader, "void main() { gl_FragColor = nope; }\n").unwrap();
+        gl.gl_compile_shader(shader).unwrap();
+        assert_eq!(gl.gl_get_shaderiv(shader, ShaderParamType::COMPILE_STATUS).unwrap(), 0);
+        assert!(gl.gl_get_shader_info_log(shader, 1024).unwrap().contains("'nope' : undeclared identifier"));
+    }
+}
@@ -0,0 +1,139 @@
+use types::*;
+use consts::*;
+use format::FormatInfo;
+
+use super::sampler::{expand_bits, Surface};
+
+// -------------------------------------------------------------------------------------------------
+// UNPACKING
+// -------------------------------------------------------------------------------------------------
+
+// Checks a `glTexImage2D`/`glTexSubImage2D` format and type pair against the ES 2.0 core table and
+// returns the GL error it raises, if any.
+pub(super) fn check_transfer(format: GLenum, type_: GLenum) -> Result<&'static FormatInfo, GLenum> {
+    match format {
+        GL_ALPHA | GL_LUMINANCE | GL_LUMINANCE_ALPHA | GL_RGB | GL_RGBA => {}
+        _ => return Err(GL_INVALID_ENUM),
+    }
+
+    match type_ {
+        GL_UNSIGNED_BYTE | GL_UNSIGNED_SHORT_5_6_5 | GL_UNSIGNED_SHORT_4_4_4_4 | GL_UNSIGNED_SHORT_5_5_5_1 => {}
+        _ => return Err(GL_INVALID_ENUM),
+    }
+
+    FormatInfo::lookup(format, format, type_).ok_or(GL_INVALID_OPERATION)
+}
+
+// The precision a texture of `format`/`type_` keeps. Luminance is replicated into RGB.
+pub(super) fn storage_bits(info: &FormatInfo) -> [u8; 4] {
+    match info.format {
+        GL_LUMINANCE | GL_LUMINANCE_ALPHA => [info.bits[0], info.bits[0], info.bits[0], info.bits[3]],
+        _ => info.bits,
+    }
+}
+
+// Decodes `width` x `height` pixels of client data, rows padded to `alignment`, into RGBA8.
+pub(super) fn unpack(info: &FormatInfo, width: usize, height: usize, alignment: usize, data: &[u8]) -> Vec<[u8; 4]> {
+    let pixel_size = info.bytes_per_pixel as usize;
+    let stride = row_stride(width * pixel_size, alignment);
+    let mut texels = Vec::with_capacity(width * height);
+
+    for row in 0..height {
+        for column in 0..width {
+            let offset = row * stride + column * pixel_size;
+            let pixel = &data[offset..offset + pixel_size];
+            texels.push(decode_pixel(info, pixel));
+        }
+    }
+
+    texels
+}
+
+fn decode_pixel(info: &FormatInfo, pixel: &[u8]) -> [u8; 4] {
+    let packed = || u16::from_ne_bytes([pixel[0], pixel[1]]) as u32;
+
+    match (info.format, info.type_) {
+        (GL_RGBA, GL_UNSIGNED_BYTE) => [pixel[0], pixel[1], pixel[2], pixel[3]],
+        (GL_RGB, GL_UNSIGNED_BYTE) => [pixel[0], pixel[1], pixel[2], 255],
+        (GL_LUMINANCE_ALPHA, _) => [pixel[0], pixel[0], pixel[0], pixel[1]],
+        (GL_LUMINANCE, _) => [pixel[0], pixel[0], pixel[0], 255],
+        (GL_ALPHA, _) => [0, 0, 0, pixel[0]],
+        (_, GL_UNSIGNED_SHORT_5_6_5) => {
+            let value = packed();
+            [expand_bits(value >> 11, 5), expand_bits((value >> 5) & 0x3f, 6), expand_bits(value & 0x1f, 5), 255]
+        }
+        (_, GL_UNSIGNED_SHORT_4_4_4_4) => {
+            let value = packed();
+            [expand_bits(value >> 12, 4), expand_bits((value >> 8) & 0xf, 4), expand_bits((value >> 4) & 0xf, 4),
+             expand_bits(value & 0xf, 4)]
+        }
+        _ => {
+            let value = packed();
+            [expand_bits(value >> 11, 5), expand_bits((value >> 6) & 0x1f, 5), expand_bits((value >> 1) & 0x1f, 5),
+             expand_bits(value & 1, 1)]
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// PACKING
+// -------------------------------------------------------------------------------------------------
+
+// The format and type `glReadPixels` accepts besides RGBA/UNSIGNED_BYTE for a color buffer: the
+// one matching its precision.
+pub(super) fn read_format(surface: &Surface) -> (GLenum, GLenum) {
+    match surface.bits {
+        [4, 4, 4, 4] => (GL_RGBA, GL_UNSIGNED_SHORT_4_4_4_4),
+        [5, 5, 5, 1] => (GL_RGBA, GL_UNSIGNED_SHORT_5_5_5_1),
+        [5, 6, 5, 0] => (GL_RGB, GL_UNSIGNED_SHORT_5_6_5),
+        _ => (GL_RGBA, GL_UNSIGNED_BYTE),
+    }
+}
+
+// Writes a rectangle of `surface`, bottom row first, into `out` as `info` describes. Pixels outside
+// the surface are left untouched.
+pub(super) fn pack(surface: &Surface, x: i64, y: i64, width: usize, height: usize, info: &FormatInfo,
+                   alignment: usize, out: &mut [u8]) {
+    let pixel_size = info.bytes_per_pixel as usize;
+    let stride = row_stride(width * pixel_size, alignment);
+
+    for row in 0..height {
+        let source_y = y + row as i64;
+        if source_y < 0 || source_y >= surface.height as i64 {
+            continue;
+        }
+
+        for column in 0..width {
+            let source_x = x + column as i64;
+            if source_x < 0 || source_x >= surface.width as i64 {
+                continue;
+            }
+
+            let texel = surface.color[source_y as usize * surface.width + source_x as usize];
+            let offset = row * stride + column * pixel_size;
+            encode_pixel(info, texel, &mut out[offset..offset + pixel_size]);
+        }
+    }
+}
+
+fn encode_pixel(info: &FormatInfo, texel: [u8; 4], pixel: &mut [u8]) {
+    // Stored values are bit-replicated, so the top bits are the original ones.
+    let field = |channel: usize, bits: u32, shift: u32| (texel[channel] as u16 >> (8 - bits)) << shift;
+
+    let packed = match info.type_ {
+        GL_UNSIGNED_SHORT_5_6_5 => field(0, 5, 11) | field(1, 6, 5) | field(2, 5, 0),
+        GL_UNSIGNED_SHORT_4_4_4_4 => field(0, 4, 12) | field(1, 4, 8) | field(2, 4, 4) | field(3, 4, 0),
+        GL_UNSIGNED_SHORT_5_5_5_1 => field(0, 5, 11) | field(1, 5, 6) | field(2, 5, 1) | field(3, 1, 0),
+        _ => {
+            pixel.copy_from_slice(&texel[..pixel.len()]);
+            return;
+        }
+    };
+
+    pixel.copy_from_slice(&packed.to_ne_bytes());
+}
+
+pub(super) fn row_stride(row: usize, alignment: usize) -> usize {
+    let alignment = alignment.max(1);
+    row.div_ceil(alignment) * alignment
+}
@@ -0,0 +1,771 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use types::*;
+use consts::*;
+use enums::DataType;
+
+use es20::glsl::{self, Environment, FragmentInput, Lod};
+use super::sampler::{cube_face, TextureObject};
+
+/// Runs once per vertex.
+pub type VertexShader = dyn Fn(&Vertex) -> VertexOutput;
+
+/// Runs once per fragment and returns its color, or `None` to discard it.
+pub type FragmentShader = dyn Fn(&Fragment) -> Option<[f32; 4]>;
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// A program whose two stages are Rust closures, standing in for compiled GLSL.
+///
+/// The declared attributes and uniforms are what `glGetActiveAttrib`, `glGetActiveUniform` and the
+/// location queries report once the program object it is registered for is linked.
+pub struct ClosureProgram {
+    attributes: Vec<(String, DataType)>,
+    uniforms: Vec<(String, DataType, usize)>,
+    varyings: usize,
+    vertex: Box<VertexShader>,
+    fragment: Box<FragmentShader>,
+}
+
+impl ClosureProgram {
+    pub fn new<V, F>(vertex: V, fragment: F) -> ClosureProgram
+        where V: Fn(&Vertex) -> VertexOutput + 'static, F: Fn(&Fragment) -> Option<[f32; 4]> + 'static {
+        ClosureProgram {
+            attributes: Vec::new(),
+            uniforms: Vec::new(),
+            varyings: 0,
+            vertex: Box::new(vertex),
+            fragment: Box::new(fragment),
+        }
+    }
+
+    /// Declares the next attribute. `Vertex::attribute` takes indices in declaration order.
+    pub fn attribute<S: Into<String>>(mut self, name: S, type_: DataType) -> ClosureProgram {
+        self.attributes.push((name.into(), type_));
+        self
+    }
+
+    pub fn uniform<S: Into<String>>(self, name: S, type_: DataType) -> ClosureProgram {
+        self.uniform_array(name, type_, 1)
+    }
+
+    pub fn uniform_array<S: Into<String>>(mut self, name: S, type_: DataType, size: usize) -> ClosureProgram {
+        self.uniforms.push((name.into(), type_, size));
+        self
+    }
+
+    /// The number of floats the vertex stage passes to the fragment stage.
+    pub fn varyings(mut self, count: usize) -> ClosureProgram {
+        self.varyings = count;
+        self
+    }
+}
+
+/// Closure programs waiting to be linked, keyed by program object name.
+///
+/// Clones share the same table, so a handle taken before the backend moves into a `Wrapper` can
+/// register programs later. A registration takes effect at the next `glLinkProgram`.
+#[derive(Clone, Default)]
+pub struct ProgramRegistry {
+    programs: Rc<RefCell<HashMap<GLuint, Rc<ClosureProgram>>>>,
+}
+
+impl ProgramRegistry {
+    pub fn register(&self, program: GLuint, closures: ClosureProgram) {
+        self.programs.borrow_mut().insert(program, Rc::new(closures));
+    }
+
+    pub fn unregister(&self, program: GLuint) {
+        self.programs.borrow_mut().remove(&program);
+    }
+
+    pub(super) fn get(&self, program: GLuint) -> Option<Rc<ClosureProgram>> {
+        self.programs.borrow().get(&program).cloned()
+    }
+}
+
+/// What the vertex stage produces for one vertex.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexOutput {
+    /// `gl_Position`, in clip coordinates.
+    pub position: [f32; 4],
+    /// `gl_PointSize`, only used when drawing points.
+    pub point_size: f32,
+    /// Missing values read as 0, extra ones are ignored.
+    pub varyings: Vec<f32>,
+}
+
+impl VertexOutput {
+    pub fn new(position: [f32; 4], varyings: Vec<f32>) -> VertexOutput {
+        VertexOutput { position, point_size: 1.0, varyings }
+    }
+}
+
+/// The inputs of one vertex shader invocation.
+pub struct Vertex<'a> {
+    pub(super) attributes: &'a [[f32; 4]],
+    pub(super) locations: &'a [usize],
+    pub(super) uniforms: &'a Uniforms,
+    pub(super) textures: &'a TextureUnits<'a>,
+    pub(super) depth_range: [f32; 2],
+}
+
+impl<'a> Vertex<'a> {
+    /// The value of the attribute declared at `index`, or of the first column of a matrix.
+    pub fn attribute(&self, index: usize) -> [f32; 4] {
+        self.column(index, 0)
+    }
+
+    pub fn column(&self, index: usize, column: usize) -> [f32; 4] {
+        self.attributes[self.locations[index] + column]
+    }
+
+    pub fn uniforms(&self) -> &Uniforms {
+        self.uniforms
+    }
+
+    pub fn texture2d_lod(&self, sampler: &str, coord: [f32; 2], lod: f32) -> [f32; 4] {
+        self.textures.sample_2d(self.uniforms, sampler, coord, lod)
+    }
+
+    pub fn texture_cube_lod(&self, sampler: &str, coord: [f32; 3], lod: f32) -> [f32; 4] {
+        self.textures.sample_cube(self.uniforms, sampler, coord, lod)
+    }
+}
+
+/// The inputs of one fragment shader invocation.
+pub struct Fragment<'a> {
+    /// `gl_FragCoord`: window position of the pixel center, window depth and 1/w.
+    pub frag_coord: [f32; 4],
+    pub front_facing: bool,
+    /// `gl_PointCoord`, only meaningful when drawing points.
+    pub point_coord: [f32; 2],
+    pub(super) varyings: &'a [f32],
+    pub(super) uniforms: &'a Uniforms,
+    pub(super) textures: &'a TextureUnits<'a>,
+    pub(super) footprint: &'a Footprint<'a>,
+    pub(super) depth_range: [f32; 2],
+}
+
+impl<'a> Fragment<'a> {
+    pub fn varyings(&self) -> &[f32] {
+        self.varyings
+    }
+
+    pub fn varying(&self, index: usize) -> f32 {
+        self.varyings[index]
+    }
+
+    pub fn varying2(&self, first: usize) -> [f32; 2] {
+        [self.varyings[first], self.varyings[first + 1]]
+    }
+
+    pub fn varying3(&self, first: usize) -> [f32; 3] {
+        [self.varyings[first], self.varyings[first + 1], self.varyings[first + 2]]
+    }
+
+    pub fn varying4(&self, first: usize) -> [f32; 4] {
+        [self.varyings[first], self.varyings[first + 1], self.varyings[first + 2], self.varyings[first + 3]]
+    }
+
+    pub fn uniforms(&self) -> &Uniforms {
+        self.uniforms
+    }
+
+    pub fn texture2d(&self, sampler: &str, coord: [f32; 2]) -> [f32; 4] {
+        self.texture2d_bias(sampler, coord, 0.0)
+    }
+
+    pub fn texture2d_bias(&self, sampler: &str, coord: [f32; 2], bias: f32) -> [f32; 4] {
+        let size = self.textures.size_2d(self.uniforms, sampler);
+        let lod = self.footprint.lod([coord[0], coord[1], 0.0], size, false) + bias;
+        self.textures.sample_2d(self.uniforms, sampler, coord, lod)
+    }
+
+    pub fn texture_cube(&self, sampler: &str, coord: [f32; 3]) -> [f32; 4] {
+        self.texture_cube_bias(sampler, coord, 0.0)
+    }
+
+    pub fn texture_cube_bias(&self, sampler: &str, coord: [f32; 3], bias: f32) -> [f32; 4] {
+        let size = self.textures.size_cube(self.uniforms, sampler);
+        let lod = self.footprint.lod(coord, size, true) + bias;
+        self.textures.sample_cube(self.uniforms, sampler, coord, lod)
+    }
+}
+
+impl<'a> Environment for Vertex<'a> {
+    fn attribute(&self, index: usize, column: usize) -> [f32; 4] {
+        self.column(index, column)
+    }
+
+    fn uniform(&self, index: usize, values: &mut [f32]) {
+        self.uniforms.write(index, values);
+    }
+
+    fn texture_2d(&self, unit: usize, coord: [f32; 2], lod: Lod) -> [f32; 4] {
+        self.textures.unit_2d(unit).map_or([0.0, 0.0, 0.0, 1.0], |texture| texture.sample_2d(coord, vertex_lod(lod)))
+    }
+
+    fn texture_cube(&self, unit: usize, coord: [f32; 3], lod: Lod) -> [f32; 4] {
+        self.textures.unit_cube(unit).map_or([0.0, 0.0, 0.0, 1.0], |texture| texture.sample_cube(coord, vertex_lod(lod)))
+    }
+
+    fn depth_range(&self) -> [f32; 2] {
+        self.depth_range
+    }
+}
+
+impl<'a> Environment for Fragment<'a> {
+    fn uniform(&self, index: usize, values: &mut [f32]) {
+        self.uniforms.write(index, values);
+    }
+
+    fn texture_2d(&self, unit: usize, coord: [f32; 2], lod: Lod) -> [f32; 4] {
+        let texture = self.textures.unit_2d(unit);
+        let lod = match lod {
+            Lod::Implicit(bias) => {
+                let size = texture.map_or([1.0, 1.0], |texture| texture.base_size());
+                self.footprint.lod([coord[0], coord[1], 0.0], size, false) + bias
+            }
+            Lod::Explicit(lod) => lod,
+        };
+        texture.map_or([0.0, 0.0, 0.0, 1.0], |texture| texture.sample_2d(coord, lod))
+    }
+
+    fn texture_cube(&self, unit: usize, coord: [f32; 3], lod: Lod) -> [f32; 4] {
+        let texture = self.textures.unit_cube(unit);
+        let lod = match lod {
+            Lod::Implicit(bias) => {
+                let size = texture.map_or([1.0, 1.0], |texture| texture.base_size());
+                self.footprint.lod(coord, size, true) + bias
+            }
+            Lod::Explicit(lod) => lod,
+        };
+        texture.map_or([0.0, 0.0, 0.0, 1.0], |texture| texture.sample_cube(coord, lod))
+    }
+
+    fn depth_range(&self) -> [f32; 2] {
+        self.depth_range
+    }
+}
+
+// Vertex shaders have no derivatives, so implicit lookups use the base level.
+fn vertex_lod(lod: Lod) -> f32 {
+    match lod {
+        Lod::Implicit(_) => 0.0,
+        Lod::Explicit(lod) => lod,
+    }
+}
+
+/// The uniform values of a linked program.
+///
+/// Lookups take the declared name and panic when the program declares no such uniform, or when
+/// it has a different type.
+#[derive(Clone, Debug)]
+pub struct Uniforms {
+    slots: Vec<UniformSlot>,
+    names: HashMap<String, usize>,
+    // One entry per location: the slot and the array element.
+    locations: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Debug)]
+struct UniformSlot {
+    name: String,
+    type_: GLenum,
+    size: usize,
+    floats: Vec<f32>,
+    ints: Vec<i32>,
+}
+
+impl Uniforms {
+    pub fn float(&self, name: &str) -> f32 {
+        self.floats(name)[0]
+    }
+
+    pub fn vec2(&self, name: &str) -> [f32; 2] {
+        let values = self.floats(name);
+        [values[0], values[1]]
+    }
+
+    pub fn vec3(&self, name: &str) -> [f32; 3] {
+        let values = self.floats(name);
+        [values[0], values[1], values[2]]
+    }
+
+    pub fn vec4(&self, name: &str) -> [f32; 4] {
+        let values = self.floats(name);
+        [values[0], values[1], values[2], values[3]]
+    }
+
+    /// Column-major, as uploaded by `glUniformMatrix4fv`.
+    pub fn mat4(&self, name: &str) -> [[f32; 4]; 4] {
+        let values = self.floats(name);
+        let mut matrix = [[0.0; 4]; 4];
+        for (column, chunk) in matrix.iter_mut().zip(values.chunks(4)) {
+            column.copy_from_slice(chunk);
+        }
+        matrix
+    }
+
+    pub fn mat3(&self, name: &str) -> [[f32; 3]; 3] {
+        let values = self.floats(name);
+        let mut matrix = [[0.0; 3]; 3];
+        for (column, chunk) in matrix.iter_mut().zip(values.chunks(3)) {
+            column.copy_from_slice(chunk);
+        }
+        matrix
+    }
+
+    pub fn mat2(&self, name: &str) -> [[f32; 2]; 2] {
+        let values = self.floats(name);
+        [[values[0], values[1]], [values[2], values[3]]]
+    }
+
+    /// All components of all elements of a float, vector or matrix uniform.
+    pub fn floats(&self, name: &str) -> &[f32] {
+        let slot = self.slot(name);
+        if slot.floats.is_empty() {
+            panic!("uniform {:?} is not floating point", name);
+        }
+        &slot.floats
+    }
+
+    pub fn int(&self, name: &str) -> i32 {
+        self.ints(name)[0]
+    }
+
+    pub fn boolean(&self, name: &str) -> bool {
+        self.int(name) != 0
+    }
+
+    /// All components of all elements of an int, bool or sampler uniform.
+    pub fn ints(&self, name: &str) -> &[i32] {
+        let slot = self.slot(name);
+        if slot.ints.is_empty() {
+            panic!("uniform {:?} is not an integer, boolean or sampler", name);
+        }
+        &slot.ints
+    }
+
+    fn slot(&self, name: &str) -> &UniformSlot {
+        match self.names.get(name) {
+            Some(&index) => &self.slots[index],
+            None => panic!("the program declares no uniform {:?}", name),
+        }
+    }
+
+    // All values of the slot at `index` as floats, the way compiled shaders store them.
+    fn write(&self, index: usize, values: &mut [f32]) {
+        let slot = &self.slots[index];
+        if slot.floats.is_empty() {
+            for (value, &int) in values.iter_mut().zip(&slot.ints) {
+                *value = int as f32;
+            }
+        } else {
+            let count = values.len().min(slot.floats.len());
+            values[..count].copy_from_slice(&slot.floats[..count]);
+        }
+    }
+
+    fn sampler(&self, name: &str) -> (GLenum, usize) {
+        let slot = self.slot(name);
+        (slot.type_, slot.ints.first().map_or(0, |&unit| unit.max(0) as usize))
+    }
+}
+
+// The textures bound to each unit when a draw started, 2D and cube map.
+pub(super) struct TextureUnits<'a> {
+    pub units: Vec<[Option<&'a TextureObject>; 2]>,
+}
+
+impl<'a> TextureUnits<'a> {
+    fn texture(&self, uniforms: &Uniforms, sampler: &str, type_: GLenum) -> Option<&'a TextureObject> {
+        let (declared, unit) = uniforms.sampler(sampler);
+        if declared != type_ {
+            panic!("uniform {:?} is not a {}", sampler,
+                   if type_ == GL_SAMPLER_2D { "sampler2D" } else { "samplerCube" });
+        }
+
+        if type_ == GL_SAMPLER_2D { self.unit_2d(unit) } else { self.unit_cube(unit) }
+    }
+
+    fn unit_2d(&self, unit: usize) -> Option<&'a TextureObject> {
+        self.units.get(unit).and_then(|bindings| bindings[0])
+    }
+
+    fn unit_cube(&self, unit: usize) -> Option<&'a TextureObject> {
+        self.units.get(unit).and_then(|bindings| bindings[1])
+    }
+
+    fn size_2d(&self, uniforms: &Uniforms, sampler: &str) -> [f32; 2] {
+        self.texture(uniforms, sampler, GL_SAMPLER_2D).map_or([1.0, 1.0], |texture| texture.base_size())
+    }
+
+    fn size_cube(&self, uniforms: &Uniforms, sampler: &str) -> [f32; 2] {
+        self.texture(uniforms, sampler, GL_SAMPLER_CUBE).map_or([1.0, 1.0], |texture| texture.base_size())
+    }
+
+    fn sample_2d(&self, uniforms: &Uniforms, sampler: &str, coord: [f32; 2], lod: f32) -> [f32; 4] {
+        match self.texture(uniforms, sampler, GL_SAMPLER_2D) {
+            Some(texture) => texture.sample_2d(coord, lod),
+            None => [0.0, 0.0, 0.0, 1.0],
+        }
+    }
+
+    fn sample_cube(&self, uniforms: &Uniforms, sampler: &str, coord: [f32; 3], lod: f32) -> [f32; 4] {
+        match self.texture(uniforms, sampler, GL_SAMPLER_CUBE) {
+            Some(texture) => texture.sample_cube(coord, lod),
+            None => [0.0, 0.0, 0.0, 1.0],
+        }
+    }
+}
+
+// Texture coordinates a fragment passed to its sampling calls, in call order.
+//
+// Fragments are shaded in 2x2 quads. When the program samples textures, every quad runs twice: the
+// first pass records the coordinates of each call, the second derives the level of detail of a call
+// from the coordinates its horizontal and vertical neighbours recorded for the same call. Like on
+// a GPU, this assumes the four fragments take the same path through the shader.
+pub(super) struct Footprint<'a> {
+    pub recorded: RefCell<Vec<[f32; 3]>>,
+    // The records of the whole quad and the position of this fragment in it, in the second pass.
+    pub quad: Option<(&'a [Vec<[f32; 3]>; 4], usize)>,
+    pub call: Cell<usize>,
+}
+
+impl<'a> Footprint<'a> {
+    pub fn new(quad: Option<(&'a [Vec<[f32; 3]>; 4], usize)>) -> Footprint<'a> {
+        Footprint {
+            recorded: RefCell::new(Vec::new()),
+            quad,
+            call: Cell::new(0),
+        }
+    }
+
+    // log2 of the larger screen-space footprint in texels. Cube map coordinates are projected
+    // onto the face this fragment samples.
+    fn lod(&self, coord: [f32; 3], size: [f32; 2], cube: bool) -> f32 {
+        let (records, index) = match self.quad {
+            Some(quad) => quad,
+            None => {
+                self.recorded.borrow_mut().push(coord);
+                return 0.0;
+            }
+        };
+
+        let call = self.call.get();
+        self.call.set(call + 1);
+
+        let fetch = |position: usize| records[position].get(call).cloned();
+        let (left, right, bottom, top) = match (fetch(index & !1), fetch(index | 1), fetch(index & !2),
+                                                fetch(index | 2)) {
+            (Some(left), Some(right), Some(bottom), Some(top)) => (left, right, bottom, top),
+            _ => return 0.0,
+        };
+
+        let face = cube_face(coord).0;
+        let project = |c: [f32; 3]| if cube { cube_face_coord(face, c) } else { [c[0], c[1]] };
+
+        let (left, right, bottom, top) = (project(left), project(right), project(bottom), project(top));
+        let dx = [(right[0] - left[0]) * size[0], (right[1] - left[1]) * size[1]];
+        let dy = [(top[0] - bottom[0]) * size[0], (top[1] - bottom[1]) * size[1]];
+        let rho = (dx[0] * dx[0] + dx[1] * dx[1]).max(dy[0] * dy[0] + dy[1] * dy[1]).sqrt();
+
+        if rho > 0.0 { rho.log2() } else { f32::NEG_INFINITY }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// LINKING
+// -------------------------------------------------------------------------------------------------
+
+// An active attribute of a linked program.
+#[derive(Clone, Debug)]
+pub(super) struct ActiveAttribute {
+    pub name: String,
+    pub type_: GLenum,
+    pub location: usize,
+}
+
+// The code a linked program runs: registered closures or compiled GLSL.
+pub(super) enum Stages {
+    Closures(Rc<ClosureProgram>),
+    Glsl(Box<glsl::Program>),
+}
+
+pub(super) struct LinkedProgram {
+    pub stages: Stages,
+    pub attributes: Vec<ActiveAttribute>,
+    // The location of each declared attribute, in declaration order.
+    pub locations: Vec<usize>,
+    pub uniforms: Uniforms,
+}
+
+impl LinkedProgram {
+    pub fn link(stages: Stages, bindings: &HashMap<String, GLuint>, max_attributes: usize)
+        -> Result<LinkedProgram, String> {
+        let declared_attributes: Vec<(String, GLenum)> = match stages {
+            Stages::Closures(ref closures) => closures.attributes.iter()
+                .map(|&(ref name, type_)| (name.clone(), type_ as GLenum)).collect(),
+            Stages::Glsl(ref program) => program.attributes().iter()
+                .map(|attribute| (attribute.name.clone(), attribute.type_)).collect(),
+        };
+        let declared_uniforms: Vec<(String, GLenum, usize)> = match stages {
+            Stages::Closures(ref closures) => closures.uniforms.iter()
+                .map(|&(ref name, type_, size)| (name.clone(), type_ as GLenum, size)).collect(),
+            Stages::Glsl(ref program) => program.uniforms().iter()
+                .map(|uniform| (uniform.name.clone(), uniform.type_, uniform.size)).collect(),
+        };
+
+        let mut used = vec![false; max_attributes];
+        let mut locations = vec![None; declared_attributes.len()];
+
+        // Explicit bindings first, then the lowest free range for everything else.
+        for pass in 0..2 {
+            for (index, &(ref name, type_)) in declared_attributes.iter().enumerate() {
+                let columns = match attribute_columns(type_) {
+                    Some(columns) => columns,
+                    None => return Err(format!("attribute {:?} has type {:?}, which attributes can not have",
+                                               name, type_)),
+                };
+
+                let location = match (pass, bindings.get(name)) {
+                    (0, Some(&bound)) => bound as usize,
+                    (1, None) => match (0..max_attributes).find(|&first| {
+                        first + columns <= max_attributes && used[first..first + columns].iter().all(|used| !used)
+                    }) {
+                        Some(first) => first,
+                        None => return Err(format!("no room for attribute {:?}", name)),
+                    },
+                    _ => continue,
+                };
+
+                if location + columns > max_attributes || used[location..location + columns].iter().any(|&used| used) {
+                    return Err(format!("attribute {:?} overlaps another attribute at location {}", name, location));
+                }
+
+                for slot in &mut used[location..location + columns] {
+                    *slot = true;
+                }
+                locations[index] = Some(location);
+            }
+        }
+
+        let locations: Vec<usize> = locations.into_iter().map(|location| location.unwrap_or(0)).collect();
+        let attributes = declared_attributes.iter().zip(&locations)
+            .map(|(&(ref name, type_), &location)| ActiveAttribute { name: name.clone(), type_, location })
+            .collect();
+
+        let mut uniforms = Uniforms { slots: Vec::new(), names: HashMap::new(), locations: Vec::new() };
+        for &(ref name, type_, size) in &declared_uniforms {
+            let (components, float) = match uniform_layout(type_) {
+                Some(layout) => layout,
+                None => return Err(format!("uniform {:?} has type {:?}, which uniforms can not have", name, type_)),
+            };
+
+            if size == 0 {
+                return Err(format!("uniform {:?} is an empty array", name));
+            }
+            if uniforms.names.insert(name.clone(), uniforms.slots.len()).is_some() {
+                return Err(format!("uniform {:?} is declared twice", name));
+            }
+
+            for element in 0..size {
+                uniforms.locations.push((uniforms.slots.len(), element));
+            }
+
+            let values = components * size;
+            uniforms.slots.push(UniformSlot {
+                name: name.clone(),
+                type_,
+                size,
+                floats: if float { vec![0.0; values] } else { Vec::new() },
+                ints: if float { Vec::new() } else { vec![0; values] },
+            });
+        }
+
+        Ok(LinkedProgram { stages, attributes, locations, uniforms })
+    }
+
+    pub fn varyings(&self) -> usize {
+        match self.stages {
+            Stages::Closures(ref closures) => closures.varyings,
+            Stages::Glsl(ref program) => program.varyings(),
+        }
+    }
+
+    pub fn has_samplers(&self) -> bool {
+        self.uniforms.slots.iter().any(|slot| slot.type_ == GL_SAMPLER_2D || slot.type_ == GL_SAMPLER_CUBE)
+    }
+
+    pub fn run_vertex(&self, vertex: &Vertex) -> VertexOutput {
+        match self.stages {
+            Stages::Closures(ref closures) => (closures.vertex)(vertex),
+            Stages::Glsl(ref program) => {
+                let mut varyings = vec![0.0; program.varyings()];
+                let (position, point_size) = program.run_vertex(vertex, &mut varyings);
+                VertexOutput { position, point_size, varyings }
+            }
+        }
+    }
+
+    pub fn run_fragment(&self, fragment: &Fragment) -> Option<[f32; 4]> {
+        match self.stages {
+            Stages::Closures(ref closures) => (closures.fragment)(fragment),
+            Stages::Glsl(ref program) => {
+                let input = FragmentInput {
+                    frag_coord: fragment.frag_coord,
+                    front_facing: fragment.front_facing,
+                    point_coord: fragment.point_coord,
+                    varyings: fragment.varyings,
+                };
+                program.run_fragment(&input, fragment)
+            }
+        }
+    }
+
+    pub fn attribute_location(&self, name: &str) -> GLint {
+        self.attributes.iter().find(|attribute| attribute.name == name).map_or(-1, |attribute| attribute.location as GLint)
+    }
+
+    // Accepts `name`, `name[0]` and `name[i]` for arrays. Only a trailing subscript selects an
+    // element, so structure fields such as `lights[1].color[2]` resolve too.
+    pub fn uniform_location(&self, name: &str) -> GLint {
+        let (base, element) = match name.rfind('[') {
+            Some(open) if name.ends_with(']') => match name[open + 1..name.len() - 1].parse::<usize>() {
+                Ok(element) => (&name[..open], element),
+                Err(_) => return -1,
+            },
+            _ => (name, 0),
+        };
+
+        let slot = match self.uniforms.names.get(base) {
+            Some(&slot) => slot,
+            None => return -1,
+        };
+
+        self.uniforms.locations.iter().position(|&location| location == (slot, element)).map_or(-1, |location| location as GLint)
+    }
+
+    // Name, size and type of an active uniform. Arrays are reported with a `[0]` suffix.
+    pub fn active_uniform(&self, index: usize) -> Option<(String, GLint, GLenum)> {
+        self.uniforms.slots.get(index).map(|slot| {
+            let name = if slot.size > 1 { format!("{}[0]", slot.name) } else { slot.name.clone() };
+            (name, slot.size as GLint, slot.type_)
+        })
+    }
+
+    pub fn uniform_count(&self) -> usize {
+        self.uniforms.slots.len()
+    }
+
+    pub fn sampler_units(&self) -> Vec<(GLenum, i32)> {
+        self.uniforms.slots.iter()
+            .filter(|slot| slot.type_ == GL_SAMPLER_2D || slot.type_ == GL_SAMPLER_CUBE)
+            .flat_map(|slot| slot.ints.iter().map(move |&unit| (slot.type_, unit)))
+            .collect()
+    }
+
+    // The type of the uniform at `location`, how many elements from there on can be set and
+    // whether it is an array.
+    pub fn uniform_at(&self, location: GLint) -> Option<(GLenum, usize, bool)> {
+        let &(slot, element) = self.uniforms.locations.get(location as usize)?;
+        let slot = &self.uniforms.slots[slot];
+        Some((slot.type_, slot.size - element, slot.size > 1))
+    }
+
+    // Stores `count` elements starting at `location`. `values` holds whole elements.
+    pub fn set_uniform(&mut self, location: GLint, floats: Option<&[f32]>, ints: Option<&[i32]>) {
+        let (slot, element) = self.uniforms.locations[location as usize];
+        let slot = &mut self.uniforms.slots[slot];
+        let components = uniform_layout(slot.type_).map_or(1, |layout| layout.0);
+        let start = element * components;
+
+        if !slot.floats.is_empty() {
+            let values: Vec<f32> = match (floats, ints) {
+                (Some(floats), _) => floats.to_vec(),
+                (None, Some(ints)) => ints.iter().map(|&value| value as f32).collect(),
+                (None, None) => return,
+            };
+            slot.floats[start..start + values.len()].copy_from_slice(&values);
+        } else {
+            let is_bool = slot.type_ == GL_BOOL || slot.type_ == GL_BOOL_VEC2 || slot.type_ == GL_BOOL_VEC3
+                || slot.type_ == GL_BOOL_VEC4;
+            let values: Vec<i32> = match (floats, ints) {
+                (Some(floats), _) => floats.iter().map(|&value| (value != 0.0) as i32).collect(),
+                (None, Some(ints)) if is_bool => ints.iter().map(|&value| (value != 0) as i32).collect(),
+                (None, Some(ints)) => ints.to_vec(),
+                (None, None) => return,
+            };
+            slot.ints[start..start + values.len()].copy_from_slice(&values);
+        }
+    }
+
+    // All components of the element at `location`.
+    pub fn get_uniform(&self, location: GLint) -> Option<(Vec<f32>, Vec<i32>)> {
+        let &(slot, element) = self.uniforms.locations.get(location as usize)?;
+        let slot = &self.uniforms.slots[slot];
+        let components = uniform_layout(slot.type_).map_or(1, |layout| layout.0);
+        let range = element * components..(element + 1) * components;
+
+        if slot.floats.is_empty() {
+            let ints = slot.ints[range].to_vec();
+            Some((ints.iter().map(|&value| value as f32).collect(), ints))
+        } else {
+            let floats = slot.floats[range].to_vec();
+            Some((floats.clone(), floats.iter().map(|&value| value as i32).collect()))
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+// Locations a vertex attribute of `type_` occupies.
+fn attribute_columns(type_: GLenum) -> Option<usize> {
+    match type_ {
+        GL_FLOAT | GL_FLOAT_VEC2 | GL_FLOAT_VEC3 | GL_FLOAT_VEC4 => Some(1),
+        GL_FLOAT_MAT2 => Some(2),
+        GL_FLOAT_MAT3 => Some(3),
+        GL_FLOAT_MAT4 => Some(4),
+        _ => None,
+    }
+}
+
+// Components per element, and whether they are stored as floats.
+fn uniform_layout(type_: GLenum) -> Option<(usize, bool)> {
+    match type_ {
+        GL_FLOAT => Some((1, true)),
+        GL_FLOAT_VEC2 => Some((2, true)),
+        GL_FLOAT_VEC3 => Some((3, true)),
+        GL_FLOAT_VEC4 => Some((4, true)),
+        GL_FLOAT_MAT2 => Some((4, true)),
+        GL_FLOAT_MAT3 => Some((9, true)),
+        GL_FLOAT_MAT4 => Some((16, true)),
+        GL_INT | GL_BOOL | GL_SAMPLER_2D | GL_SAMPLER_CUBE => Some((1, false)),
+        GL_INT_VEC2 | GL_BOOL_VEC2 => Some((2, false)),
+        GL_INT_VEC3 | GL_BOOL_VEC3 => Some((3, false)),
+        GL_INT_VEC4 | GL_BOOL_VEC4 => Some((4, false)),
+        _ => None,
+    }
+}
+
+// Face coordinates in [0, 1] of `coord` projected onto `face`, even when another face is major.
+fn cube_face_coord(face: usize, coord: [f32; 3]) -> [f32; 2] {
+    let (sc, tc, ma) = match face {
+        0 => (-coord[2], -coord[1], coord[0]),
+        1 => (coord[2], -coord[1], -coord[0]),
+        2 => (coord[0], coord[2], coord[1]),
+        3 => (coord[0], -coord[2], -coord[1]),
+        4 => (coord[0], -coord[1], coord[2]),
+        _ => (-coord[0], -coord[1], -coord[2]),
+    };
+    let ma = ma.abs().max(1e-20);
+    [(sc / ma + 1.0) * 0.5, (tc / ma + 1.0) * 0.5]
+}
@@ -0,0 +1,870 @@
+use types::*;
+use consts::*;
+
+use super::program::{Footprint, Fragment, LinkedProgram, TextureUnits, VertexOutput};
+use super::sampler::Surface;
+
+// Window coordinates are snapped to this many subpixel steps, so edges shared by two triangles
+// evaluate identically for both and the fill convention can be applied exactly.
+const SUBPIXEL_STEPS: f32 = 256.0;
+
+// The width range of lines, `GL_ALIASED_LINE_WIDTH_RANGE`.
+pub(super) const LINE_WIDTH_RANGE: [f32; 2] = [1.0, 16.0];
+// The size range of points, `GL_ALIASED_POINT_SIZE_RANGE`.
+pub(super) const POINT_SIZE_RANGE: [f32; 2] = [1.0, 64.0];
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+// The stencil state of one face.
+#[derive(Copy, Clone, Debug)]
+pub(super) struct StencilState {
+    pub func: GLenum,
+    pub ref_: GLint,
+    pub value_mask: GLuint,
+    pub write_mask: GLuint,
+    pub fail: GLenum,
+    pub depth_fail: GLenum,
+    pub depth_pass: GLenum,
+}
+
+impl Default for StencilState {
+    fn default() -> StencilState {
+        StencilState {
+            func: GL_ALWAYS,
+            ref_: 0,
+            value_mask: !0,
+            write_mask: !0,
+            fail: GL_KEEP,
+            depth_fail: GL_KEEP,
+            depth_pass: GL_KEEP,
+        }
+    }
+}
+
+// The fixed-function state, with the initial values of the ES 2.0 specification.
+#[derive(Clone, Debug)]
+pub(super) struct State {
+    pub viewport: [GLint; 4],
+    pub depth_range: [f32; 2],
+    pub scissor: [GLint; 4],
+    pub clear_color: [f32; 4],
+    pub clear_depth: f32,
+    pub clear_stencil: GLint,
+    pub color_mask: [bool; 4],
+    pub depth_mask: bool,
+    pub blend: bool,
+    pub cull_face: bool,
+    pub depth_test: bool,
+    pub dither: bool,
+    pub polygon_offset_fill: bool,
+    pub sample_alpha_to_coverage: bool,
+    pub sample_coverage: bool,
+    pub scissor_test: bool,
+    pub stencil_test: bool,
+    pub blend_color: [f32; 4],
+    // RGB and alpha.
+    pub blend_equation: [GLenum; 2],
+    // Source RGB, destination RGB, source alpha and destination alpha.
+    pub blend_func: [GLenum; 4],
+    pub depth_func: GLenum,
+    pub cull_face_mode: GLenum,
+    pub front_face: GLenum,
+    // Front and back.
+    pub stencil: [StencilState; 2],
+    pub line_width: f32,
+    // Factor and units.
+    pub polygon_offset: [f32; 2],
+    pub sample_coverage_value: f32,
+    pub sample_coverage_invert: bool,
+    pub pack_alignment: GLint,
+    pub unpack_alignment: GLint,
+    pub generate_mipmap_hint: GLenum,
+}
+
+impl State {
+    pub fn new(width: GLint, height: GLint) -> State {
+        State {
+            viewport: [0, 0, width, height],
+            depth_range: [0.0, 1.0],
+            scissor: [0, 0, width, height],
+            clear_color: [0.0; 4],
+            clear_depth: 1.0,
+            clear_stencil: 0,
+            color_mask: [true; 4],
+            depth_mask: true,
+            blend: false,
+            cull_face: false,
+            depth_test: false,
+            dither: true,
+            polygon_offset_fill: false,
+            sample_alpha_to_coverage: false,
+            sample_coverage: false,
+            scissor_test: false,
+            stencil_test: false,
+            blend_color: [0.0; 4],
+            blend_equation: [GL_FUNC_ADD; 2],
+            blend_func: [GL_ONE, GL_ZERO, GL_ONE, GL_ZERO],
+            depth_func: GL_LESS,
+            cull_face_mode: GL_BACK,
+            front_face: GL_CCW,
+            stencil: [StencilState::default(); 2],
+            line_width: 1.0,
+            polygon_offset: [0.0; 2],
+            sample_coverage_value: 1.0,
+            sample_coverage_invert: false,
+            pack_alignment: 4,
+            unpack_alignment: 4,
+            generate_mipmap_hint: GL_DONT_CARE,
+        }
+    }
+
+    pub fn capability(&mut self, cap: GLenum) -> Option<&mut bool> {
+        match cap {
+            GL_BLEND => Some(&mut self.blend),
+            GL_CULL_FACE => Some(&mut self.cull_face),
+            GL_DEPTH_TEST => Some(&mut self.depth_test),
+            GL_DITHER => Some(&mut self.dither),
+            GL_POLYGON_OFFSET_FILL => Some(&mut self.polygon_offset_fill),
+            GL_SAMPLE_ALPHA_TO_COVERAGE => Some(&mut self.sample_alpha_to_coverage),
+            GL_SAMPLE_COVERAGE => Some(&mut self.sample_coverage),
+            GL_SCISSOR_TEST => Some(&mut self.scissor_test),
+            GL_STENCIL_TEST => Some(&mut self.stencil_test),
+            _ => None,
+        }
+    }
+}
+
+// The images a draw or clear writes, taken out of their owners for its duration.
+#[derive(Default)]
+pub(super) struct Targets {
+    pub width: usize,
+    pub height: usize,
+    pub color: Option<Surface>,
+    pub depth: Option<Surface>,
+    pub stencil: Option<Surface>,
+}
+
+// A vertex after clipping, still in clip coordinates.
+#[derive(Clone)]
+struct ClipVertex {
+    position: [f32; 4],
+    point_size: f32,
+    varyings: Vec<f32>,
+}
+
+// A vertex after the perspective division and the viewport transform.
+#[derive(Clone)]
+struct WindowVertex {
+    x: f32,
+    y: f32,
+    z: f32,
+    inv_w: f32,
+    varyings: Vec<f32>,
+}
+
+// How to evaluate the inputs of the fragment shader anywhere in the window, including outside the
+// primitive for the helper fragments of a quad.
+enum Setup {
+    Triangle {
+        vertices: [WindowVertex; 3],
+        area: f64,
+        offset: f32,
+    },
+    Line {
+        vertices: [WindowVertex; 2],
+    },
+    Point {
+        vertex: WindowVertex,
+        size: f32,
+    },
+}
+
+// -------------------------------------------------------------------------------------------------
+// DRAWING
+// -------------------------------------------------------------------------------------------------
+
+// Assembles `indices` into primitives of `mode` and renders them. `vertices` holds the vertex
+// shader results the indices refer to.
+pub(super) fn draw(state: &State, program: &LinkedProgram, textures: &TextureUnits, targets: &mut Targets,
+                   mode: GLenum, vertices: &[VertexOutput], indices: &[usize]) {
+    let varyings = program.varyings();
+    let clip = |index: usize| {
+        let output = &vertices[index];
+        let mut values = output.varyings.clone();
+        values.resize(varyings, 0.0);
+        ClipVertex { position: output.position, point_size: output.point_size, varyings: values }
+    };
+
+    let mut raster = Rasterizer { state, program, textures, targets };
+
+    match mode {
+        GL_POINTS => {
+            for &index in indices {
+                raster.point(clip(index));
+            }
+        }
+        GL_LINES => {
+            for pair in indices.chunks_exact(2) {
+                raster.line(clip(pair[0]), clip(pair[1]));
+            }
+        }
+        GL_LINE_STRIP | GL_LINE_LOOP => {
+            for pair in indices.windows(2) {
+                raster.line(clip(pair[0]), clip(pair[1]));
+            }
+            if mode == GL_LINE_LOOP && indices.len() > 1 {
+                raster.line(clip(indices[indices.len() - 1]), clip(indices[0]));
+            }
+        }
+        GL_TRIANGLES => {
+            for triple in indices.chunks_exact(3) {
+                raster.triangle([clip(triple[0]), clip(triple[1]), clip(triple[2])]);
+            }
+        }
+        GL_TRIANGLE_STRIP => {
+            for (first, triple) in indices.windows(3).enumerate() {
+                // Every other triangle is flipped so that all of them keep the winding of the first.
+                if first % 2 == 0 {
+                    raster.triangle([clip(triple[0]), clip(triple[1]), clip(triple[2])]);
+                } else {
+                    raster.triangle([clip(triple[1]), clip(triple[0]), clip(triple[2])]);
+                }
+            }
+        }
+        GL_TRIANGLE_FAN => {
+            for pair in indices.iter().skip(1).collect::<Vec<_>>().windows(2) {
+                raster.triangle([clip(indices[0]), clip(*pair[0]), clip(*pair[1])]);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Clears the buffers in `mask`, honoring the scissor box and the write masks.
+pub(super) fn clear(state: &State, targets: &mut Targets, mask: GLbitfield) {
+    let (x0, y0, x1, y1) = bounds(state, targets);
+
+    if mask & GL_COLOR_BUFFER_BIT != 0 {
+        if let Some(ref mut color) = targets.color {
+            let value = color.quantize(state.clear_color);
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let texel = &mut color.color[y * targets.width + x];
+                    for channel in 0..4 {
+                        if state.color_mask[channel] {
+                            texel[channel] = value[channel];
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if mask & GL_DEPTH_BUFFER_BIT != 0 && state.depth_mask {
+        if let Some(ref mut depth) = targets.depth {
+            let value = depth.quantize_depth(state.clear_depth);
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    depth.depth[y * targets.width + x] = value;
+                }
+            }
+        }
+    }
+
+    if mask & GL_STENCIL_BUFFER_BIT != 0 {
+        if let Some(ref mut stencil) = targets.stencil {
+            let write_mask = state.stencil[0].write_mask as u8;
+            let value = state.clear_stencil as u8;
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let stored = &mut stencil.stencil[y * targets.width + x];
+                    *stored = (*stored & !write_mask) | (value & write_mask);
+                }
+            }
+        }
+    }
+}
+
+// The pixels a draw may touch: the framebuffer, cut down to the scissor box when it is enabled.
+fn bounds(state: &State, targets: &Targets) -> (usize, usize, usize, usize) {
+    let (mut x0, mut y0, mut x1, mut y1) = (0i64, 0i64, targets.width as i64, targets.height as i64);
+
+    if state.scissor_test {
+        let [x, y, width, height] = state.scissor;
+        x0 = x0.max(x as i64);
+        y0 = y0.max(y as i64);
+        x1 = x1.min(x as i64 + width as i64);
+        y1 = y1.min(y as i64 + height as i64);
+    }
+
+    (x0 as usize, y0 as usize, x1.max(x0) as usize, y1.max(y0) as usize)
+}
+
+struct Rasterizer<'a, 'b: 'a> {
+    state: &'a State,
+    program: &'a LinkedProgram,
+    textures: &'a TextureUnits<'b>,
+    targets: &'a mut Targets,
+}
+
+impl<'a, 'b> Rasterizer<'a, 'b> {
+    fn point(&mut self, vertex: ClipVertex) {
+        let [x, y, z, w] = vertex.position;
+        if w.is_nan() || w <= 0.0 || x.abs() > w || y.abs() > w || z.abs() > w {
+            return;
+        }
+
+        let size = vertex.point_size.clamp(POINT_SIZE_RANGE[0], POINT_SIZE_RANGE[1]);
+        let vertex = self.to_window(&vertex);
+        let half = size / 2.0;
+
+        let mut pixels = Vec::new();
+        for row in pixel_span(vertex.y - half, vertex.y + half) {
+            for column in pixel_span(vertex.x - half, vertex.x + half) {
+                pixels.push((column, row));
+            }
+        }
+
+        self.shade_pixels(&Setup::Point { vertex, size }, pixels);
+    }
+
+    fn line(&mut self, a: ClipVertex, b: ClipVertex) {
+        let (a, b) = match clip_line(a, b) {
+            Some(line) => line,
+            None => return,
+        };
+
+        let (a, b) = (self.to_window(&a), self.to_window(&b));
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let width = self.state.line_width.clamp(LINE_WIDTH_RANGE[0], LINE_WIDTH_RANGE[1]).round().max(1.0) as i64;
+        let x_major = dx.abs() >= dy.abs();
+
+        // Walks the pixel centers along the major axis, half-open at the end point so connected
+        // segments do not touch a pixel twice, and widens the line along the minor axis.
+        let (start, end, delta) = if x_major { (a.x, b.x, dx) } else { (a.y, b.y, dy) };
+        if delta == 0.0 {
+            return;
+        }
+
+        let mut pixels = Vec::new();
+        let (low, high) = if delta > 0.0 { (start, end) } else { (end, start) };
+        let first = (low - 0.5).ceil() as i64;
+        let last = (high - 0.5).ceil() as i64;
+        let range: Vec<i64> = if delta > 0.0 { (first..last).collect() } else { (first + 1..=last).collect() };
+
+        for major in range {
+            let center = major as f32 + 0.5;
+            let t = (center - start) / delta;
+            let minor = if x_major { a.y + t * dy } else { a.x + t * dx };
+            let minor = minor.floor() as i64 - (width - 1) / 2;
+
+            for offset in 0..width {
+                pixels.push(if x_major { (major, minor + offset) } else { (minor + offset, major) });
+            }
+        }
+
+        self.shade_pixels(&Setup::Line { vertices: [a, b] }, pixels);
+    }
+
+    fn triangle(&mut self, vertices: [ClipVertex; 3]) {
+        let polygon = clip_polygon(vertices.to_vec());
+        if polygon.len() < 3 {
+            return;
+        }
+
+        let polygon: Vec<WindowVertex> = polygon.iter().map(|vertex| self.to_window(vertex)).collect();
+
+        let mut area = 0.0f64;
+        for (index, vertex) in polygon.iter().enumerate() {
+            let next = &polygon[(index + 1) % polygon.len()];
+            area += vertex.x as f64 * next.y as f64 - next.x as f64 * vertex.y as f64;
+        }
+        if area == 0.0 {
+            return;
+        }
+
+        let counter_clockwise = area > 0.0;
+        let front = counter_clockwise == (self.state.front_face == GL_CCW);
+        if self.state.cull_face {
+            let culled = match self.state.cull_face_mode {
+                GL_FRONT => front,
+                GL_BACK => !front,
+                _ => true,
+            };
+            if culled {
+                return;
+            }
+        }
+
+        for index in 1..polygon.len() - 1 {
+            let mut corners = [polygon[0].clone(), polygon[index].clone(), polygon[index + 1].clone()];
+            if !counter_clockwise {
+                corners.swap(1, 2);
+            }
+
+            let area = edge(&corners[0], &corners[1], corners[2].x as f64, corners[2].y as f64);
+            if area <= 0.0 {
+                continue;
+            }
+
+            let offset = self.polygon_offset(&corners, area);
+            self.fill(Setup::Triangle { vertices: corners, area, offset }, front);
+        }
+    }
+
+    // The depth offset of `GL_POLYGON_OFFSET_FILL`, from the depth slope of the triangle.
+    fn polygon_offset(&self, corners: &[WindowVertex; 3], area: f64) -> f32 {
+        if !self.state.polygon_offset_fill {
+            return 0.0;
+        }
+
+        let [a, b, c] = corners;
+        let dzdx = ((b.z - a.z) as f64 * (c.y - a.y) as f64 - (c.z - a.z) as f64 * (b.y - a.y) as f64) / area;
+        let dzdy = ((c.z - a.z) as f64 * (b.x - a.x) as f64 - (b.z - a.z) as f64 * (c.x - a.x) as f64) / area;
+        let slope = dzdx.abs().max(dzdy.abs()) as f32;
+        let bits = self.targets.depth.as_ref().map_or(24, |depth| depth.depth_bits);
+        let resolution = 1.0 / (1u64 << bits) as f32;
+
+        slope * self.state.polygon_offset[0] + resolution * self.state.polygon_offset[1]
+    }
+
+    fn fill(&mut self, setup: Setup, front: bool) {
+        let vertices = match setup {
+            Setup::Triangle { ref vertices, .. } => vertices,
+            _ => return,
+        };
+
+        let (x0, y0, x1, y1) = bounds(self.state, self.targets);
+        let min_x = vertices.iter().map(|v| v.x).fold(f32::INFINITY, f32::min).floor().max(x0 as f32) as i64;
+        let max_x = vertices.iter().map(|v| v.x).fold(f32::NEG_INFINITY, f32::max).ceil().min(x1 as f32) as i64;
+        let min_y = vertices.iter().map(|v| v.y).fold(f32::INFINITY, f32::min).floor().max(y0 as f32) as i64;
+        let max_y = vertices.iter().map(|v| v.y).fold(f32::NEG_INFINITY, f32::max).ceil().min(y1 as f32) as i64;
+
+        let edges = [(1, 2), (2, 0), (0, 1)];
+        let inside = |x: i64, y: i64| {
+            let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+            edges.iter().all(|&(from, to)| {
+                let (a, b) = (&vertices[from], &vertices[to]);
+                let value = edge(a, b, px, py);
+                // Pixel centers exactly on an edge belong to the triangle to the right of a
+                // downward edge, or below a horizontal edge running left.
+                value > 0.0 || (value == 0.0 && (b.y < a.y || (b.y == a.y && b.x < a.x)))
+            })
+        };
+
+        let mut y = min_y - min_y.rem_euclid(2);
+        while y < max_y {
+            let mut x = min_x - min_x.rem_euclid(2);
+            while x < max_x {
+                let mut mask = 0;
+                for index in 0..4 {
+                    let (px, py) = (x + (index & 1) as i64, y + (index >> 1) as i64);
+                    if px >= x0 as i64 && px < x1 as i64 && py >= y0 as i64 && py < y1 as i64 && inside(px, py) {
+                        mask |= 1 << index;
+                    }
+                }
+
+                if mask != 0 {
+                    self.shade_quad(&setup, x, y, mask, front);
+                }
+                x += 2;
+            }
+            y += 2;
+        }
+    }
+
+    // Groups the pixels of a point or line into quads and shades them.
+    fn shade_pixels(&mut self, setup: &Setup, mut pixels: Vec<(i64, i64)>) {
+        let (x0, y0, x1, y1) = bounds(self.state, self.targets);
+        pixels.retain(|&(x, y)| x >= x0 as i64 && x < x1 as i64 && y >= y0 as i64 && y < y1 as i64);
+        pixels.sort_by_key(|&(x, y)| (y.div_euclid(2), x.div_euclid(2), y, x));
+        pixels.dedup();
+
+        let mut index = 0;
+        while index < pixels.len() {
+            let (qx, qy) = (pixels[index].0 & !1, pixels[index].1 & !1);
+            let mut mask = 0;
+            while index < pixels.len() && (pixels[index].0 & !1, pixels[index].1 & !1) == (qx, qy) {
+                mask |= 1 << ((pixels[index].0 - qx) + 2 * (pixels[index].1 - qy));
+                index += 1;
+            }
+            self.shade_quad(setup, qx, qy, mask, true);
+        }
+    }
+
+    // Runs the fragment shader for the covered pixels of the quad whose lower left pixel is at
+    // `x`, `y` and writes the results.
+    fn shade_quad(&mut self, setup: &Setup, x: i64, y: i64, mask: u32, front: bool) {
+        let count = self.program.varyings();
+        let mut inputs = Vec::with_capacity(4);
+        for index in 0..4 {
+            let (px, py) = ((x + (index & 1)) as f32 + 0.5, (y + (index >> 1)) as f32 + 0.5);
+            let mut varyings = vec![0.0; count];
+            let (z, inv_w, point_coord) = setup.interpolate(px, py, &mut varyings);
+            inputs.push(([px, py, z, inv_w], point_coord, varyings));
+        }
+
+        let run = |index: usize, footprint: &Footprint| {
+            let (frag_coord, point_coord, ref varyings) = inputs[index];
+            let fragment = Fragment {
+                frag_coord,
+                front_facing: front,
+                point_coord,
+                varyings,
+                uniforms: &self.program.uniforms,
+                textures: self.textures,
+                footprint,
+                depth_range: self.state.depth_range,
+            };
+            self.program.run_fragment(&fragment)
+        };
+
+        let mut colors = [None; 4];
+        if self.program.has_samplers() {
+            let mut records: [Vec<[f32; 3]>; 4] = Default::default();
+            for (index, record) in records.iter_mut().enumerate() {
+                let footprint = Footprint::new(None);
+                run(index, &footprint);
+                *record = footprint.recorded.into_inner();
+            }
+
+            for (index, color) in colors.iter_mut().enumerate() {
+                if mask & (1 << index) != 0 {
+                    *color = run(index, &Footprint::new(Some((&records, index))));
+                }
+            }
+        } else {
+            for (index, color) in colors.iter_mut().enumerate() {
+                if mask & (1 << index) != 0 {
+                    *color = run(index, &Footprint::new(None));
+                }
+            }
+        }
+
+        for (index, color) in colors.iter().enumerate() {
+            if let Some(color) = *color {
+                let (px, py) = (x + (index & 1) as i64, y + (index >> 1) as i64);
+                self.write_fragment(px as usize, py as usize, inputs[index].0[2], color, front);
+            }
+        }
+    }
+
+    // The per-fragment operations after the scissor test: stencil, depth, blending and masking.
+    fn write_fragment(&mut self, x: usize, y: usize, z: f32, color: [f32; 4], front: bool) {
+        let state = self.state;
+        let pixel = y * self.targets.width + x;
+        let stencil_state = &state.stencil[if front { 0 } else { 1 }];
+        let stencil_enabled = state.stencil_test && self.targets.stencil.is_some();
+
+        if stencil_enabled {
+            let stencil = self.targets.stencil.as_mut().map(|surface| &mut surface.stencil[pixel]).unwrap();
+            let reference = stencil_state.ref_.clamp(0, 255) as u32;
+            let mask = stencil_state.value_mask;
+            if !compare(stencil_state.func, reference & mask, *stencil as u32 & mask) {
+                *stencil = stencil_op(stencil_state.fail, *stencil, reference as u8, stencil_state.write_mask);
+                return;
+            }
+        }
+
+        if state.depth_test {
+            if let Some(ref mut depth) = self.targets.depth {
+                let z = depth.quantize_depth(z);
+                if !compare_depth(state.depth_func, z, depth.depth[pixel]) {
+                    if stencil_enabled {
+                        let stencil = &mut self.targets.stencil.as_mut().unwrap().stencil[pixel];
+                        *stencil = stencil_op(stencil_state.depth_fail, *stencil,
+                                              stencil_state.ref_.clamp(0, 255) as u8, stencil_state.write_mask);
+                    }
+                    return;
+                }
+                if state.depth_mask {
+                    depth.depth[pixel] = z;
+                }
+            }
+        }
+
+        if stencil_enabled {
+            let stencil = &mut self.targets.stencil.as_mut().unwrap().stencil[pixel];
+            *stencil = stencil_op(stencil_state.depth_pass, *stencil, stencil_state.ref_.clamp(0, 255) as u8,
+                                  stencil_state.write_mask);
+        }
+
+        let target = match self.targets.color {
+            Some(ref mut target) => target,
+            None => return,
+        };
+
+        let source = [clamp(color[0]), clamp(color[1]), clamp(color[2]), clamp(color[3])];
+        let stored = target.color[pixel];
+        let result = if state.blend {
+            let destination = [stored[0] as f32 / 255.0, stored[1] as f32 / 255.0, stored[2] as f32 / 255.0,
+                               stored[3] as f32 / 255.0];
+            blend(state, source, destination)
+        } else {
+            source
+        };
+
+        let quantized = target.quantize(result);
+        let texel = &mut target.color[pixel];
+        for channel in 0..4 {
+            if state.color_mask[channel] {
+                texel[channel] = quantized[channel];
+            }
+        }
+    }
+
+    fn to_window(&self, vertex: &ClipVertex) -> WindowVertex {
+        let [x, y, z, w] = vertex.position;
+        let inv_w = 1.0 / w;
+        let [vx, vy, width, height] = self.state.viewport;
+        let [near, far] = self.state.depth_range;
+
+        let snap = |value: f32| (value * SUBPIXEL_STEPS).round() / SUBPIXEL_STEPS;
+        WindowVertex {
+            x: snap((x * inv_w + 1.0) * 0.5 * width as f32 + vx as f32),
+            y: snap((y * inv_w + 1.0) * 0.5 * height as f32 + vy as f32),
+            z: (far - near) * 0.5 * (z * inv_w) + (near + far) * 0.5,
+            inv_w,
+            varyings: vertex.varyings.clone(),
+        }
+    }
+}
+
+impl Setup {
+    // Window depth, 1/w and point coordinate at window position `x`, `y`, with the perspective
+    // correct varyings written to `varyings`.
+    fn interpolate(&self, x: f32, y: f32, varyings: &mut [f32]) -> (f32, f32, [f32; 2]) {
+        match *self {
+            Setup::Triangle { ref vertices, area, offset } => {
+                let (px, py) = (x as f64, y as f64);
+                let weights = [
+                    edge(&vertices[1], &vertices[2], px, py) / area,
+                    edge(&vertices[2], &vertices[0], px, py) / area,
+                    edge(&vertices[0], &vertices[1], px, py) / area,
+                ];
+
+                let mut z = 0.0;
+                let mut inv_w = 0.0;
+                let mut perspective = [0.0f64; 3];
+                for (index, vertex) in vertices.iter().enumerate() {
+                    z += weights[index] * vertex.z as f64;
+                    inv_w += weights[index] * vertex.inv_w as f64;
+                    perspective[index] = weights[index] * vertex.inv_w as f64;
+                }
+
+                let sum: f64 = perspective.iter().sum();
+                for (component, value) in varyings.iter_mut().enumerate() {
+                    let mut total = 0.0;
+                    for (index, vertex) in vertices.iter().enumerate() {
+                        total += perspective[index] * vertex.varyings[component] as f64;
+                    }
+                    *value = (total / sum) as f32;
+                }
+
+                (clamp(z as f32 + offset), inv_w as f32, [0.0, 0.0])
+            }
+            Setup::Line { ref vertices } => {
+                let [ref a, ref b] = *vertices;
+                let (dx, dy) = (b.x - a.x, b.y - a.y);
+                let t = ((x - a.x) * dx + (y - a.y) * dy) / (dx * dx + dy * dy);
+                let corrected = t * b.inv_w / ((1.0 - t) * a.inv_w + t * b.inv_w);
+
+                for (component, value) in varyings.iter_mut().enumerate() {
+                    *value = a.varyings[component] + (b.varyings[component] - a.varyings[component]) * corrected;
+                }
+
+                let z = a.z + (b.z - a.z) * t.clamp(0.0, 1.0);
+                (clamp(z), a.inv_w + (b.inv_w - a.inv_w) * t, [0.0, 0.0])
+            }
+            Setup::Point { ref vertex, size } => {
+                varyings.copy_from_slice(&vertex.varyings);
+                let point_coord = [0.5 + (x - vertex.x) / size, 0.5 - (y - vertex.y) / size];
+                (clamp(vertex.z), vertex.inv_w, point_coord)
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// CLIPPING
+// -------------------------------------------------------------------------------------------------
+
+// Distances to the six planes of the view volume, positive inside. The last one keeps w away
+// from zero for the perspective division.
+fn plane_distances(position: [f32; 4]) -> [f32; 7] {
+    let [x, y, z, w] = position;
+    [w + x, w - x, w + y, w - y, w + z, w - z, w - 1e-6]
+}
+
+fn lerp_vertex(a: &ClipVertex, b: &ClipVertex, t: f32) -> ClipVertex {
+    let mut position = [0.0; 4];
+    for (component, value) in position.iter_mut().enumerate() {
+        *value = a.position[component] + (b.position[component] - a.position[component]) * t;
+    }
+
+    ClipVertex {
+        position,
+        point_size: a.point_size,
+        varyings: a.varyings.iter().zip(&b.varyings).map(|(&from, &to)| from + (to - from) * t).collect(),
+    }
+}
+
+// Sutherland-Hodgman against every plane in turn.
+fn clip_polygon(mut polygon: Vec<ClipVertex>) -> Vec<ClipVertex> {
+    if polygon.iter().all(|vertex| plane_distances(vertex.position).iter().all(|&distance| distance >= 0.0)) {
+        return polygon;
+    }
+
+    for plane in 0..7 {
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+        for index in 0..polygon.len() {
+            let current = &polygon[index];
+            let next = &polygon[(index + 1) % polygon.len()];
+            let (d0, d1) = (plane_distances(current.position)[plane], plane_distances(next.position)[plane]);
+
+            if d0 >= 0.0 {
+                clipped.push(current.clone());
+            }
+            if (d0 >= 0.0) != (d1 >= 0.0) {
+                clipped.push(lerp_vertex(current, next, d0 / (d0 - d1)));
+            }
+        }
+
+        polygon = clipped;
+        if polygon.len() < 3 {
+            return Vec::new();
+        }
+    }
+
+    polygon
+}
+
+fn clip_line(a: ClipVertex, b: ClipVertex) -> Option<(ClipVertex, ClipVertex)> {
+    let (da, db) = (plane_distances(a.position), plane_distances(b.position));
+    let (mut enter, mut exit) = (0.0f32, 1.0f32);
+
+    for plane in 0..7 {
+        let (d0, d1) = (da[plane], db[plane]);
+        if d0 < 0.0 && d1 < 0.0 {
+            return None;
+        }
+        if d0 < 0.0 {
+            enter = enter.max(d0 / (d0 - d1));
+        } else if d1 < 0.0 {
+            exit = exit.min(d0 / (d0 - d1));
+        }
+    }
+
+    if enter > exit {
+        return None;
+    }
+
+    let start = if enter > 0.0 { lerp_vertex(&a, &b, enter) } else { a.clone() };
+    let end = if exit < 1.0 { lerp_vertex(&a, &b, exit) } else { b };
+    Some((start, end))
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+// Twice the signed area of `a`, `b`, `p`: positive when `p` is left of the edge from `a` to `b`.
+fn edge(a: &WindowVertex, b: &WindowVertex, px: f64, py: f64) -> f64 {
+    (b.x as f64 - a.x as f64) * (py - a.y as f64) - (b.y as f64 - a.y as f64) * (px - a.x as f64)
+}
+
+// The pixels whose centers lie in [low, high).
+fn pixel_span(low: f32, high: f32) -> std::ops::Range<i64> {
+    (low - 0.5).ceil() as i64..(high - 0.5).ceil() as i64
+}
+
+pub(super) fn clamp(value: f32) -> f32 {
+    value.clamp(0.0, 1.0)
+}
+
+fn compare(func: GLenum, reference: u32, stored: u32) -> bool {
+    match func {
+        GL_NEVER => false,
+        GL_LESS => reference < stored,
+        GL_EQUAL => reference == stored,
+        GL_LEQUAL => reference <= stored,
+        GL_GREATER => reference > stored,
+        GL_NOTEQUAL => reference != stored,
+        GL_GEQUAL => reference >= stored,
+        _ => true,
+    }
+}
+
+fn compare_depth(func: GLenum, incoming: f32, stored: f32) -> bool {
+    match func {
+        GL_NEVER => false,
+        GL_LESS => incoming < stored,
+        GL_EQUAL => incoming == stored,
+        GL_LEQUAL => incoming <= stored,
+        GL_GREATER => incoming > stored,
+        GL_NOTEQUAL => incoming != stored,
+        GL_GEQUAL => incoming >= stored,
+        _ => true,
+    }
+}
+
+fn stencil_op(op: GLenum, stored: u8, reference: u8, write_mask: GLuint) -> u8 {
+    let value = match op {
+        GL_ZERO => 0,
+        GL_REPLACE => reference,
+        GL_INCR => stored.saturating_add(1),
+        GL_DECR => stored.saturating_sub(1),
+        GL_INVERT => !stored,
+        GL_INCR_WRAP => stored.wrapping_add(1),
+        GL_DECR_WRAP => stored.wrapping_sub(1),
+        _ => stored,
+    };
+
+    let mask = write_mask as u8;
+    (stored & !mask) | (value & mask)
+}
+
+fn blend(state: &State, source: [f32; 4], destination: [f32; 4]) -> [f32; 4] {
+    let constant = state.blend_color;
+    let factor = |func: GLenum, channel: usize| -> f32 {
+        match func {
+            GL_ZERO => 0.0,
+            GL_ONE => 1.0,
+            GL_SRC_COLOR => source[channel],
+            GL_ONE_MINUS_SRC_COLOR => 1.0 - source[channel],
+            GL_DST_COLOR => destination[channel],
+            GL_ONE_MINUS_DST_COLOR => 1.0 - destination[channel],
+            GL_SRC_ALPHA => source[3],
+            GL_ONE_MINUS_SRC_ALPHA => 1.0 - source[3],
+            GL_DST_ALPHA => destination[3],
+            GL_ONE_MINUS_DST_ALPHA => 1.0 - destination[3],
+            GL_CONSTANT_COLOR => constant[channel],
+            GL_ONE_MINUS_CONSTANT_COLOR => 1.0 - constant[channel],
+            GL_CONSTANT_ALPHA => constant[3],
+            GL_ONE_MINUS_CONSTANT_ALPHA => 1.0 - constant[3],
+            GL_SRC_ALPHA_SATURATE if channel < 3 => source[3].min(1.0 - destination[3]),
+            _ => 1.0,
+        }
+    };
+
+    let mut result = [0.0; 4];
+    for (channel, value) in result.iter_mut().enumerate() {
+        let alpha = channel == 3;
+        let (source_func, destination_func) = if alpha {
+            (state.blend_func[2], state.blend_func[3])
+        } else {
+            (state.blend_func[0], state.blend_func[1])
+        };
+
+        let s = source[channel] * factor(source_func, channel);
+        let d = destination[channel] * factor(destination_func, channel);
+        *value = clamp(match state.blend_equation[alpha as usize] {
+            GL_FUNC_SUBTRACT => s - d,
+            GL_FUNC_REVERSE_SUBTRACT => d - s,
+            _ => s + d,
+        });
+    }
+    result
+}
@@ -0,0 +1,347 @@
+use types::*;
+use consts::*;
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+// Storage for one image: a texture level, a renderbuffer or a buffer of the default framebuffer.
+//
+// Color is kept as RGBA8 whatever the format, quantized to the format's precision on every write
+// so reads see what a driver would. Channels the format lacks hold 0, or 255 for alpha. Only one
+// of `color`, `depth` and `stencil` is populated.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Surface {
+    pub width: usize,
+    pub height: usize,
+    pub internal_format: GLenum,
+    pub bits: [u8; 4],
+    pub depth_bits: u8,
+    pub stencil_bits: u8,
+    pub color: Vec<[u8; 4]>,
+    pub depth: Vec<f32>,
+    pub stencil: Vec<u8>,
+}
+
+impl Surface {
+    pub fn color(width: usize, height: usize, internal_format: GLenum, bits: [u8; 4]) -> Surface {
+        let empty = [0, 0, 0, if bits[3] == 0 { 255 } else { 0 }];
+        Surface {
+            width,
+            height,
+            internal_format,
+            bits,
+            color: vec![empty; width * height],
+            ..Surface::default()
+        }
+    }
+
+    pub fn depth(width: usize, height: usize, internal_format: GLenum, depth_bits: u8) -> Surface {
+        Surface {
+            width,
+            height,
+            internal_format,
+            depth_bits,
+            depth: vec![0.0; width * height],
+            ..Surface::default()
+        }
+    }
+
+    pub fn stencil(width: usize, height: usize, internal_format: GLenum) -> Surface {
+        Surface {
+            width,
+            height,
+            internal_format,
+            stencil_bits: 8,
+            stencil: vec![0; width * height],
+            ..Surface::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    // Rounds `color` to the precision of the format and expands it back to 8 bits per channel.
+    pub fn quantize(&self, color: [f32; 4]) -> [u8; 4] {
+        let mut texel = [0u8; 4];
+        for (channel, value) in texel.iter_mut().enumerate() {
+            *value = quantize_channel(color[channel], self.bits[channel], channel == 3);
+        }
+        texel
+    }
+
+    // Applies the format's precision to a texel given with 8 bits per channel.
+    pub fn requantize(&self, texel: [u8; 4]) -> [u8; 4] {
+        self.quantize([texel[0] as f32 / 255.0, texel[1] as f32 / 255.0, texel[2] as f32 / 255.0,
+                       texel[3] as f32 / 255.0])
+    }
+
+    pub fn quantize_depth(&self, depth: f32) -> f32 {
+        let scale = ((1u64 << self.depth_bits.min(24)) - 1) as f32;
+        (depth.clamp(0.0, 1.0) * scale).round() / scale
+    }
+}
+
+pub(super) struct TextureObject {
+    // 0 until the name is first bound.
+    pub target: GLenum,
+    // One face for 2D textures and six for cube maps, each a list of levels.
+    pub faces: Vec<Vec<Surface>>,
+    pub min_filter: GLenum,
+    pub mag_filter: GLenum,
+    pub wrap_s: GLenum,
+    pub wrap_t: GLenum,
+    // Decided when a draw starts, see `update_completeness`.
+    pub complete: bool,
+}
+
+impl Default for TextureObject {
+    fn default() -> TextureObject {
+        TextureObject {
+            target: 0,
+            faces: Vec::new(),
+            min_filter: GL_NEAREST_MIPMAP_LINEAR,
+            mag_filter: GL_LINEAR,
+            wrap_s: GL_REPEAT,
+            wrap_t: GL_REPEAT,
+            complete: false,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// SAMPLING
+// -------------------------------------------------------------------------------------------------
+
+impl TextureObject {
+    pub fn bind(&mut self, target: GLenum) {
+        self.target = target;
+        self.faces.resize_with(if target == GL_TEXTURE_CUBE_MAP { 6 } else { 1 }, Vec::new);
+    }
+
+    pub fn level(&self, face: usize, level: usize) -> Option<&Surface> {
+        self.faces.get(face).and_then(|levels| levels.get(level)).filter(|surface| !surface.is_empty())
+    }
+
+    pub fn level_mut(&mut self, face: usize, level: usize) -> &mut Surface {
+        let levels = &mut self.faces[face];
+        if levels.len() <= level {
+            levels.resize_with(level + 1, Surface::default);
+        }
+        &mut levels[level]
+    }
+
+    pub fn base_size(&self) -> [f32; 2] {
+        self.level(0, 0).map_or([1.0, 1.0], |base| [base.width as f32, base.height as f32])
+    }
+
+    pub fn uses_mipmaps(&self) -> bool {
+        self.min_filter != GL_NEAREST && self.min_filter != GL_LINEAR
+    }
+
+    // The ES 2.0 completeness rules, including the limits on non-power-of-two textures.
+    pub fn update_completeness(&mut self) {
+        self.complete = self.is_complete();
+    }
+
+    fn is_complete(&self) -> bool {
+        let base = match self.level(0, 0) {
+            Some(base) => base,
+            None => return false,
+        };
+
+        let npot = !base.width.is_power_of_two() || !base.height.is_power_of_two();
+        if npot && (self.uses_mipmaps() || self.wrap_s != GL_CLAMP_TO_EDGE || self.wrap_t != GL_CLAMP_TO_EDGE) {
+            return false;
+        }
+
+        for face in 0..self.faces.len() {
+            let first = match self.level(face, 0) {
+                Some(first) => first,
+                None => return false,
+            };
+
+            if first.width != base.width || first.height != base.height || first.internal_format != base.internal_format {
+                return false;
+            }
+            if self.target == GL_TEXTURE_CUBE_MAP && first.width != first.height {
+                return false;
+            }
+
+            if self.uses_mipmaps() {
+                let (mut width, mut height) = (base.width, base.height);
+                for level in 1..mip_count(base.width, base.height) {
+                    width = (width / 2).max(1);
+                    height = (height / 2).max(1);
+                    match self.level(face, level) {
+                        Some(image) if image.width == width && image.height == height
+                            && image.internal_format == base.internal_format => {}
+                        _ => return false,
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    pub fn sample_2d(&self, coord: [f32; 2], lod: f32) -> [f32; 4] {
+        self.sample_face(0, coord, lod, self.wrap_s, self.wrap_t)
+    }
+
+    // Cube maps always clamp to the edge of the selected face.
+    pub fn sample_cube(&self, coord: [f32; 3], lod: f32) -> [f32; 4] {
+        let (face, st) = cube_face(coord);
+        self.sample_face(face, st, lod, GL_CLAMP_TO_EDGE, GL_CLAMP_TO_EDGE)
+    }
+
+    fn sample_face(&self, face: usize, coord: [f32; 2], lod: f32, wrap_s: GLenum, wrap_t: GLenum) -> [f32; 4] {
+        if !self.complete || face >= self.faces.len() {
+            return [0.0, 0.0, 0.0, 1.0];
+        }
+
+        // Below this LOD the texture is magnified. The 0.5 keeps LINEAR magnification continuous
+        // with NEAREST_MIPMAP_* minification.
+        let c = if self.mag_filter == GL_LINEAR
+            && (self.min_filter == GL_NEAREST_MIPMAP_NEAREST || self.min_filter == GL_NEAREST_MIPMAP_LINEAR) {
+            0.5
+        } else {
+            0.0
+        };
+
+        let levels = &self.faces[face];
+        let base = &levels[0];
+        let max_level = (mip_count(base.width, base.height) - 1) as f32;
+
+        if lod <= c || lod.is_nan() {
+            return filter(base, coord, self.mag_filter == GL_LINEAR, wrap_s, wrap_t);
+        }
+
+        let linear = self.min_filter == GL_LINEAR || self.min_filter == GL_LINEAR_MIPMAP_NEAREST
+            || self.min_filter == GL_LINEAR_MIPMAP_LINEAR;
+
+        match self.min_filter {
+            GL_NEAREST | GL_LINEAR => filter(base, coord, linear, wrap_s, wrap_t),
+            GL_NEAREST_MIPMAP_NEAREST | GL_LINEAR_MIPMAP_NEAREST => {
+                let level = if lod <= 0.5 { 0.0 } else { (lod + 0.5).ceil() - 1.0 };
+                filter(&levels[level.min(max_level) as usize], coord, linear, wrap_s, wrap_t)
+            }
+            _ => {
+                let lod = lod.min(max_level);
+                let lower = lod.floor();
+                let upper = (lower + 1.0).min(max_level);
+                let a = filter(&levels[lower as usize], coord, linear, wrap_s, wrap_t);
+                let b = filter(&levels[upper as usize], coord, linear, wrap_s, wrap_t);
+                let weight = lod - lower;
+                [
+                    a[0] + (b[0] - a[0]) * weight,
+                    a[1] + (b[1] - a[1]) * weight,
+                    a[2] + (b[2] - a[2]) * weight,
+                    a[3] + (b[3] - a[3]) * weight,
+                ]
+            }
+        }
+    }
+}
+
+// Picks the face a cube map direction samples and the [0, 1] coordinates on it, per table 3.21 of
+// the ES 2.0 specification.
+pub(super) fn cube_face(coord: [f32; 3]) -> (usize, [f32; 2]) {
+    let [x, y, z] = coord;
+    let (face, sc, tc, ma) = if x.abs() >= y.abs() && x.abs() >= z.abs() {
+        if x >= 0.0 { (0, -z, -y, x) } else { (1, z, -y, x) }
+    } else if y.abs() >= z.abs() {
+        if y >= 0.0 { (2, x, z, y) } else { (3, x, -z, y) }
+    } else if z >= 0.0 {
+        (4, x, -y, z)
+    } else {
+        (5, -x, -y, z)
+    };
+
+    let ma = ma.abs().max(1e-20);
+    (face, [(sc / ma + 1.0) * 0.5, (tc / ma + 1.0) * 0.5])
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+pub(super) fn mip_count(width: usize, height: usize) -> usize {
+    let mut size = width.max(height).max(1);
+    let mut count = 1;
+    while size > 1 {
+        size /= 2;
+        count += 1;
+    }
+    count
+}
+
+pub(super) fn quantize_channel(value: f32, bits: u8, alpha: bool) -> u8 {
+    if bits == 0 {
+        return if alpha { 255 } else { 0 };
+    }
+
+    let max = ((1u32 << bits.min(8)) - 1) as f32;
+    expand_bits((value.clamp(0.0, 1.0) * max).round() as u32, bits.min(8))
+}
+
+// Widens a `bits`-wide value to 8 bits by bit replication, which maps both ends of the range
+// exactly.
+pub(super) fn expand_bits(stored: u32, bits: u8) -> u8 {
+    let bits = bits as i32;
+    let mut expanded = 0u32;
+    let mut shift = 8 - bits;
+    while shift > -bits {
+        expanded |= if shift >= 0 { stored << shift } else { stored >> -shift };
+        shift -= bits;
+    }
+    expanded.min(255) as u8
+}
+
+fn filter(image: &Surface, coord: [f32; 2], linear: bool, wrap_s: GLenum, wrap_t: GLenum) -> [f32; 4] {
+    let u = coord[0] * image.width as f32;
+    let v = coord[1] * image.height as f32;
+
+    if !linear {
+        let i = wrap(u.floor() as i64, image.width, wrap_s);
+        let j = wrap(v.floor() as i64, image.height, wrap_t);
+        return texel(image, i, j);
+    }
+
+    let (u, v) = (u - 0.5, v - 0.5);
+    let (i0, j0) = (u.floor(), v.floor());
+    let (alpha, beta) = (u - i0, v - j0);
+    let (i0, j0) = (i0 as i64, j0 as i64);
+    let (i1, j1) = (wrap(i0 + 1, image.width, wrap_s), wrap(j0 + 1, image.height, wrap_t));
+    let (i0, j0) = (wrap(i0, image.width, wrap_s), wrap(j0, image.height, wrap_t));
+
+    let samples = [texel(image, i0, j0), texel(image, i1, j0), texel(image, i0, j1), texel(image, i1, j1)];
+    let weights = [(1.0 - alpha) * (1.0 - beta), alpha * (1.0 - beta), (1.0 - alpha) * beta, alpha * beta];
+
+    let mut result = [0.0; 4];
+    for (sample, weight) in samples.iter().zip(weights.iter()) {
+        for channel in 0..4 {
+            result[channel] += sample[channel] * weight;
+        }
+    }
+    result
+}
+
+fn wrap(index: i64, size: usize, mode: GLenum) -> usize {
+    let size = size as i64;
+    let wrapped = match mode {
+        GL_REPEAT => index.rem_euclid(size),
+        GL_MIRRORED_REPEAT => {
+            let period = index.rem_euclid(2 * size);
+            if period < size { period } else { 2 * size - 1 - period }
+        }
+        _ => index.clamp(0, size - 1),
+    };
+    wrapped as usize
+}
+
+fn texel(image: &Surface, i: usize, j: usize) -> [f32; 4] {
+    let texel = image.color[j * image.width + i];
+    [texel[0] as f32 / 255.0, texel[1] as f32 / 255.0, texel[2] as f32 / 255.0, texel[3] as f32 / 255.0]
+}
@@ -0,0 +1,70 @@
+use types::*;
+use consts::*;
+use super::super::wrapper::{Error, ErrorKind};
+use super::{format_error, image_size, Level, Reader, TextureData, TextureKind};
+
+// -------------------------------------------------------------------------------------------------
+// LOADING
+// -------------------------------------------------------------------------------------------------
+
+const MAGIC: u32 = 0x5CA1_AB13;
+
+// Block footprints in the order of the GL_COMPRESSED_RGBA_ASTC_* enums.
+const BLOCK_SIZES: [(u8, u8); 14] = [
+    (4, 4), (5, 4), (5, 5), (6, 5), (6, 6), (8, 5), (8, 6),
+    (8, 8), (10, 5), (10, 6), (10, 8), (10, 10), (12, 10), (12, 12),
+];
+
+/// Parses a `.astc` file as written by astcenc: a 16 byte header followed by a single image.
+///
+/// The header does not record the color space, so the linear `GL_COMPRESSED_RGBA_ASTC_*` format
+/// is used unless `srgb` asks for the `GL_COMPRESSED_SRGB8_ALPHA8_ASTC_*` one. 3D block
+/// footprints and volume images are rejected with `ErrorKind::Unsupported`.
+pub fn load(data: &[u8], srgb: bool) -> Result<TextureData, Error> {
+    let mut reader = Reader::new(data);
+
+    if reader.u32()? != MAGIC {
+        return Err(format_error("not an ASTC file"));
+    }
+
+    let block = (reader.u8()?, reader.u8()?);
+    let block_depth = reader.u8()?;
+    let width = u24(&mut reader)?;
+    let height = u24(&mut reader)?;
+    let depth = u24(&mut reader)?;
+
+    if block_depth > 1 || depth > 1 {
+        return Err(Error::new(ErrorKind::Unsupported, "3D ASTC images are not supported"));
+    }
+
+    let index = BLOCK_SIZES.iter().position(|&size| size == block).ok_or_else(|| {
+        format_error(format!("invalid ASTC block size {}x{}", block.0, block.1))
+    })? as GLenum;
+
+    let internal_format = if srgb {
+        GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4x4 + index
+    } else {
+        GL_COMPRESSED_RGBA_ASTC_4x4 + index
+    };
+
+    let size = image_size(internal_format, 0, 0, width, height)?;
+
+    Ok(TextureData {
+        kind: TextureKind::Texture2D,
+        internal_format,
+        format: 0,
+        type_: 0,
+        unpack_alignment: 1,
+        levels: vec![Level {
+            width,
+            height,
+            faces: vec![reader.bytes(size)?.to_vec()],
+        }],
+        generate_mipmaps: false,
+    })
+}
+
+fn u24(reader: &mut Reader) -> Result<u32, Error> {
+    let bytes = reader.bytes(3)?;
+    Ok(u32::from(bytes[0]) | u32::from(bytes[1]) << 8 | u32::from(bytes[2]) << 16)
+}
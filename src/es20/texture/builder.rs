@@ -0,0 +1,471 @@
+use types::*;
+use enums::{PackParamType, PixelDataType, PixelFormat, StateType, TextureBindTarget, TextureMagFilter,
+            TextureMinFilter, TextureParameter, TextureTarget, TextureWrapMode};
+use format::{FormatInfo, GlVersion};
+use super::super::wrapper::{Error, ErrorKind, Wrapper};
+use super::{Level, TextureKind};
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// Wrap and filter state of a texture. `Default` gives the GL initial values.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SamplerSettings {
+    pub wrap_s: TextureWrapMode,
+    pub wrap_t: TextureWrapMode,
+    pub min_filter: TextureMinFilter,
+    pub mag_filter: TextureMagFilter,
+}
+
+impl Default for SamplerSettings {
+    fn default() -> SamplerSettings {
+        SamplerSettings {
+            wrap_s: TextureWrapMode::REPEAT,
+            wrap_t: TextureWrapMode::REPEAT,
+            min_filter: TextureMinFilter::NEAREST_MIPMAP_LINEAR,
+            mag_filter: TextureMagFilter::LINEAR,
+        }
+    }
+}
+
+impl SamplerSettings {
+    /// Unfiltered and clamped, e.g. for pixel art or lookup tables. Valid for NPOT textures.
+    pub fn nearest() -> SamplerSettings {
+        SamplerSettings {
+            wrap_s: TextureWrapMode::CLAMP_TO_EDGE,
+            wrap_t: TextureWrapMode::CLAMP_TO_EDGE,
+            min_filter: TextureMinFilter::NEAREST,
+            mag_filter: TextureMagFilter::NEAREST,
+        }
+    }
+
+    /// Bilinear without mipmaps and clamped, e.g. for UI and render targets. Valid for NPOT textures.
+    pub fn linear() -> SamplerSettings {
+        SamplerSettings {
+            min_filter: TextureMinFilter::LINEAR,
+            mag_filter: TextureMagFilter::LINEAR,
+            ..SamplerSettings::nearest()
+        }
+    }
+
+    /// Trilinear and repeating, for tiled surface textures with a full mip chain.
+    pub fn trilinear() -> SamplerSettings {
+        SamplerSettings {
+            wrap_s: TextureWrapMode::REPEAT,
+            wrap_t: TextureWrapMode::REPEAT,
+            min_filter: TextureMinFilter::LINEAR_MIPMAP_LINEAR,
+            mag_filter: TextureMagFilter::LINEAR,
+        }
+    }
+
+    pub fn wrap(self, wrap_s: TextureWrapMode, wrap_t: TextureWrapMode) -> SamplerSettings {
+        SamplerSettings { wrap_s, wrap_t, ..self }
+    }
+
+    pub fn filter(self, min_filter: TextureMinFilter, mag_filter: TextureMagFilter) -> SamplerSettings {
+        SamplerSettings { min_filter, mag_filter, ..self }
+    }
+
+    /// Sets all four parameters on the texture bound to `target`.
+    pub fn apply(&self, gl: &mut Wrapper, target: TextureBindTarget) -> Result<(), Error> {
+        gl.gl_tex_parameter(target, TextureParameter::WrapS(self.wrap_s))?;
+        gl.gl_tex_parameter(target, TextureParameter::WrapT(self.wrap_t))?;
+        gl.gl_tex_parameter(target, TextureParameter::MinFilter(self.min_filter))?;
+        gl.gl_tex_parameter(target, TextureParameter::MagFilter(self.mag_filter))
+    }
+
+    // ES 2.0 only samples NPOT textures with clamped, unmipmapped settings.
+    fn check_npot(&self, width: u32, height: u32, version: GlVersion) -> Result<(), Error> {
+        if version >= GlVersion::Es30 || (width.is_power_of_two() && height.is_power_of_two()) {
+            return Ok(());
+        }
+
+        if self.wrap_s != TextureWrapMode::CLAMP_TO_EDGE || self.wrap_t != TextureWrapMode::CLAMP_TO_EDGE {
+            return Err(npot_error(width, height, format!(
+                "needs CLAMP_TO_EDGE wrapping, not {:?}/{:?}", self.wrap_s, self.wrap_t)));
+        }
+
+        if self.min_filter.uses_mipmaps() {
+            return Err(npot_error(width, height, format!(
+                "can not use the mipmapped min filter {:?}", self.min_filter)));
+        }
+
+        Ok(())
+    }
+}
+
+/// A texture created by `TextureBuilder`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Texture {
+    pub id: u32,
+    pub kind: TextureKind,
+    pub width: u32,
+    pub height: u32,
+    pub format: PixelFormat,
+    pub type_: PixelDataType,
+    pub sampler: SamplerSettings,
+    version: GlVersion,
+}
+
+impl Texture {
+    pub fn bind(&self, gl: &mut Wrapper) -> Result<(), Error> {
+        gl.gl_bind_texture(self.kind.bind_target(), self.id)
+    }
+
+    /// Binds the texture and replaces its sampler settings, with the same NPOT checks as `build`.
+    pub fn set_sampler(&mut self, gl: &mut Wrapper, sampler: SamplerSettings) -> Result<(), Error> {
+        sampler.check_npot(self.width, self.height, self.version)?;
+
+        self.bind(gl)?;
+        sampler.apply(gl, self.kind.bind_target())?;
+        self.sampler = sampler;
+        Ok(())
+    }
+
+    /// Binds the texture and reallocates the base level of every image at a new size, dropping
+    /// the contents. Only textures with a non-mipmapped min filter can be resized.
+    pub fn resize(&mut self, gl: &mut Wrapper, width: u32, height: u32) -> Result<(), Error> {
+        if width == 0 || height == 0 {
+            return Err(Error::new(ErrorKind::InvalidArgument, "texture dimensions must be non-zero"));
+        }
+        if self.sampler.min_filter.uses_mipmaps() {
+            return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                "a texture with min filter {:?} can not be resized, its other levels would keep their size",
+                self.sampler.min_filter)));
+        }
+        self.sampler.check_npot(width, height, self.version)?;
+
+        self.bind(gl)?;
+        for &target in self.kind.image_targets() {
+            gl.gl_tex_image_2d(target, 0, self.format as GLint, width as i32, height as i32, 0, self.format,
+                               self.type_, &[] as &[u8])?;
+        }
+
+        self.width = width;
+        self.height = height;
+        Ok(())
+    }
+
+    pub fn delete(self, gl: &mut Wrapper) -> Result<(), Error> {
+        gl.gl_delete_textures(&[self.id])
+    }
+}
+
+/// Describes a 2D texture or cube map and creates it in one go.
+///
+/// Pixel data rows are taken as tightly packed; `build` uploads with `GL_UNPACK_ALIGNMENT` 1 and
+/// restores the previous value afterwards. Levels
+/// without data are allocated uninitialized. The sampler defaults to `SamplerSettings::linear()`
+/// rather than the GL initial state, whose mipmapped min filter leaves single-level textures
+/// incomplete.
+#[derive(Clone, Debug)]
+pub struct TextureBuilder<'a> {
+    kind: TextureKind,
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    type_: PixelDataType,
+    images: Vec<(TextureTarget, u32, &'a [u8])>,
+    sampler: SamplerSettings,
+    generate_mipmaps: bool,
+    version: GlVersion,
+}
+
+impl<'a> TextureBuilder<'a> {
+    pub fn texture_2d(width: u32, height: u32) -> TextureBuilder<'a> {
+        TextureBuilder::new(TextureKind::Texture2D, width, height)
+    }
+
+    /// A cube map with square faces of `size` x `size`.
+    pub fn texture_cube(size: u32) -> TextureBuilder<'a> {
+        TextureBuilder::new(TextureKind::CubeMap, size, size)
+    }
+
+    fn new(kind: TextureKind, width: u32, height: u32) -> TextureBuilder<'a> {
+        TextureBuilder {
+            kind,
+            width,
+            height,
+            format: PixelFormat::RGBA,
+            type_: PixelDataType::UNSIGNED_BYTE,
+            images: Vec::new(),
+            sampler: SamplerSettings::linear(),
+            generate_mipmaps: false,
+            version: GlVersion::Es20,
+        }
+    }
+
+    /// Client format and type of the pixel data, `RGBA`/`UNSIGNED_BYTE` by default. ES 2.0 uses
+    /// the format as internal format too.
+    pub fn format(mut self, format: PixelFormat, type_: PixelDataType) -> TextureBuilder<'a> {
+        self.format = format;
+        self.type_ = type_;
+        self
+    }
+
+    /// Pixel data of one level of a 2D texture.
+    pub fn level(self, level: u32, data: &'a [u8]) -> TextureBuilder<'a> {
+        self.image(TextureTarget::TEXTURE_2D, level, data)
+    }
+
+    /// Pixel data of one level of the image `target`, which is a cube face for cube maps.
+    pub fn image(mut self, target: TextureTarget, level: u32, data: &'a [u8]) -> TextureBuilder<'a> {
+        self.images.retain(|&(t, l, _)| t != target || l != level);
+        self.images.push((target, level, data));
+        self
+    }
+
+    /// Pixel data of a whole mip chain, such as the one built by `mipmap::generate_chain`. Faces of
+    /// each level are assigned to the kind's image targets in order.
+    pub fn levels(mut self, levels: &'a [Level]) -> TextureBuilder<'a> {
+        for (index, level) in levels.iter().enumerate() {
+            for (&target, face) in self.kind.image_targets().iter().zip(&level.faces) {
+                self = self.image(target, index as u32, face);
+            }
+        }
+        self
+    }
+
+    pub fn sampler(mut self, sampler: SamplerSettings) -> TextureBuilder<'a> {
+        self.sampler = sampler;
+        self
+    }
+
+    pub fn wrap(mut self, wrap_s: TextureWrapMode, wrap_t: TextureWrapMode) -> TextureBuilder<'a> {
+        self.sampler = self.sampler.wrap(wrap_s, wrap_t);
+        self
+    }
+
+    pub fn filter(mut self, min_filter: TextureMinFilter, mag_filter: TextureMagFilter) -> TextureBuilder<'a> {
+        self.sampler = self.sampler.filter(min_filter, mag_filter);
+        self
+    }
+
+    /// Calls `gl_generate_mipmap` after uploading the base level.
+    pub fn generate_mipmaps(mut self, generate: bool) -> TextureBuilder<'a> {
+        self.generate_mipmaps = generate;
+        self
+    }
+
+    /// The API version the NPOT restrictions are checked against, ES 2.0 by default.
+    pub fn version(mut self, version: GlVersion) -> TextureBuilder<'a> {
+        self.version = version;
+        self
+    }
+
+    /// Checks the description, then generates, binds, fills and configures the texture.
+    pub fn build(self, gl: &mut Wrapper) -> Result<Texture, Error> {
+        self.validate()?;
+        let bind_target = self.kind.bind_target();
+
+        let id = gl.gl_gen_textures(1)?[0];
+        gl.gl_bind_texture(bind_target, id)?;
+
+        let alignment = gl.gl_get_integerv(StateType::UNPACK_ALIGNMENT)?;
+        gl.gl_pixel_storei(PackParamType::UNPACK_ALIGNMENT, 1)?;
+        let result = self.upload(gl);
+        gl.gl_pixel_storei(PackParamType::UNPACK_ALIGNMENT, alignment)?;
+        result?;
+
+        if self.generate_mipmaps {
+            gl.gl_generate_mipmap(bind_target)?;
+        }
+
+        self.sampler.apply(gl, bind_target)?;
+
+        Ok(Texture {
+            id,
+            kind: self.kind,
+            width: self.width,
+            height: self.height,
+            format: self.format,
+            type_: self.type_,
+            sampler: self.sampler,
+            version: self.version,
+        })
+    }
+
+    fn upload(&self, gl: &mut Wrapper) -> Result<(), Error> {
+        for &target in self.kind.image_targets() {
+            for level in 0..self.level_count() {
+                let data = self.images.iter()
+                    .find(|&&(t, l, _)| t == target && l == level)
+                    .map_or(&[][..], |&(_, _, data)| data);
+
+                // Only the base level is allocated when nothing else was given.
+                if level > 0 && data.is_empty() {
+                    continue;
+                }
+
+                gl.gl_tex_image_2d(target, level as i32, self.format as GLint, (self.width >> level).max(1) as i32,
+                                   (self.height >> level).max(1) as i32, 0, self.format, self.type_, data)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let info = FormatInfo::for_transfer(self.format as GLenum, self.type_ as GLenum).ok_or_else(|| {
+            Error::new(ErrorKind::InvalidArgument, format!(
+                "{:?}/{:?} is not a valid format and type combination", self.format, self.type_))
+        })?;
+
+        if self.width == 0 || self.height == 0 {
+            return Err(Error::new(ErrorKind::InvalidArgument, "texture dimensions must be non-zero"));
+        }
+
+        self.sampler.check_npot(self.width, self.height, self.version)?;
+
+        let npot = !self.width.is_power_of_two() || !self.height.is_power_of_two();
+        if npot && self.version < GlVersion::Es30 {
+            if self.generate_mipmaps {
+                return Err(npot_error(self.width, self.height, "can not generate mipmaps"));
+            }
+            if self.images.iter().any(|&(_, level, _)| level > 0) {
+                return Err(npot_error(self.width, self.height, "can not have levels above 0"));
+            }
+        }
+
+        let full_chain = 32 - self.width.max(self.height).leading_zeros();
+        for &(target, level, data) in &self.images {
+            if !self.kind.image_targets().contains(&target) {
+                return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                    "{:?} is not an image of a {:?}", target, self.kind)));
+            }
+
+            if level >= full_chain {
+                return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                    "level {} does not exist in a {}x{} texture", level, self.width, self.height)));
+            }
+
+            let (width, height) = ((self.width >> level).max(1), (self.height >> level).max(1));
+            let needed = info.image_size(width as usize, height as usize, 1);
+            if data.len() != needed {
+                return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                    "level {} of {:?} is {}x{} and needs {} bytes, got {}", level, target, width, height, needed,
+                    data.len())));
+            }
+        }
+
+        // A mipmapped min filter samples an incomplete texture as black unless every level exists.
+        if self.sampler.min_filter.uses_mipmaps() && !self.generate_mipmaps {
+            let complete = self.kind.image_targets().iter().all(|&target| {
+                (0..full_chain).all(|level| self.images.iter().any(|&(t, l, _)| t == target && l == level))
+            });
+
+            if !complete {
+                return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                    "min filter {:?} needs a full mip chain of {} levels or generate_mipmaps",
+                    self.sampler.min_filter, full_chain)));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn level_count(&self) -> u32 {
+        self.images.iter().map(|&(_, level, _)| level + 1).max().unwrap_or(1)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+fn npot_error<S: Into<String>>(width: u32, height: u32, detail: S) -> Error {
+    Error::new(ErrorKind::Validation, format!(
+        "the {}x{} texture is not power-of-two and on ES 2.0 {}", width, height, detail.into()))
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enums::{FrameBufferAttachmentType, FrameBufferTarget, TextureParamType};
+    use es20::software::SoftwareBackend;
+
+    fn software() -> Wrapper {
+        Wrapper::with_backend(Box::new(SoftwareBackend::new(4, 4)))
+    }
+
+    // Reads the base level of a 2D texture back through a framebuffer.
+    fn read_back(gl: &mut Wrapper, texture: &Texture) -> Vec<u8> {
+        let framebuffer = gl.gl_gen_framebuffers(1).unwrap()[0];
+        gl.gl_bind_framebuffer(FrameBufferTarget::FrameBuffer, framebuffer).unwrap();
+        gl.gl_framebuffer_texture_2d(FrameBufferTarget::FrameBuffer, FrameBufferAttachmentType::COLOR_ATTACHMENT0,
+                                     TextureTarget::TEXTURE_2D, texture.id, 0).unwrap();
+
+        let mut pixels = vec![0u8; (texture.width * texture.height * 4) as usize];
+        gl.gl_read_pixels(0, 0, texture.width as i32, texture.height as i32, PixelFormat::RGBA,
+                          PixelDataType::UNSIGNED_BYTE, &mut pixels).unwrap();
+        gl.gl_bind_framebuffer(FrameBufferTarget::FrameBuffer, 0).unwrap();
+        pixels
+    }
+
+    #[test]
+    fn uploads_tightly_packed_rows_and_restores_the_alignment() {
+        let mut gl = software();
+        let pixels: Vec<u8> = (0..3 * 2 * 3).collect();
+
+        let texture = TextureBuilder::texture_2d(3, 2)
+            .format(PixelFormat::RGB, PixelDataType::UNSIGNED_BYTE)
+            .level(0, &pixels)
+            .version(GlVersion::Es30)
+            .build(&mut gl)
+            .unwrap();
+
+        assert_eq!(gl.gl_get_integerv(StateType::UNPACK_ALIGNMENT).unwrap(), 4);
+        assert_eq!(gl.gl_get_integerv(StateType::TEXTURE_BINDING_2D).unwrap(), texture.id as i32);
+        assert_eq!(gl.gl_get_tex_parameteriv(TextureTarget::TEXTURE_2D, TextureParamType::TEXTURE_MIN_FILTER)
+                       .unwrap(), TextureMinFilter::LINEAR as i32);
+
+        let rgba = read_back(&mut gl, &texture);
+        let rgb: Vec<u8> = rgba.chunks(4).flat_map(|texel| texel[..3].to_vec()).collect();
+        assert_eq!(rgb, pixels);
+    }
+
+    #[test]
+    fn rejects_descriptions_gl_would_not_accept() {
+        let mut gl = software();
+        let short = [0u8; 15];
+        let pixels = [0u8; 3 * 3 * 4];
+
+        let error = TextureBuilder::texture_2d(2, 2).level(0, &short).build(&mut gl).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidArgument);
+
+        let error = TextureBuilder::texture_2d(3, 3).level(0, &pixels).generate_mipmaps(true).build(&mut gl)
+            .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Validation);
+
+        let error = TextureBuilder::texture_2d(4, 4).sampler(SamplerSettings::trilinear()).build(&mut gl)
+            .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidArgument);
+
+        let error = TextureBuilder::texture_2d(2, 2).image(TextureTarget::TEXTURE_CUBE_MAP_POSITIVE_X, 0, &short[..0])
+            .build(&mut gl).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidArgument);
+
+        // Nothing was created for the rejected descriptions.
+        assert!(!gl.gl_is_texture(1).unwrap());
+    }
+
+    #[test]
+    fn resizes_only_single_level_textures() {
+        let mut gl = software();
+
+        let mut texture = TextureBuilder::texture_2d(4, 4).build(&mut gl).unwrap();
+        texture.resize(&mut gl, 8, 2).unwrap();
+        assert_eq!((texture.width, texture.height), (8, 2));
+        assert_eq!(read_back(&mut gl, &texture).len(), 8 * 2 * 4);
+
+        let mut mipmapped = TextureBuilder::texture_2d(4, 4).sampler(SamplerSettings::trilinear())
+            .generate_mipmaps(true).build(&mut gl).unwrap();
+        assert_eq!(mipmapped.resize(&mut gl, 8, 8).unwrap_err().kind, ErrorKind::InvalidArgument);
+        assert_eq!((mipmapped.width, mipmapped.height), (4, 4));
+    }
+}
@@ -0,0 +1,348 @@
+use types::*;
+use consts::*;
+use super::super::wrapper::{Error, ErrorKind};
+use super::{check_size, format_error, image_size, Level, Reader, TextureData, TextureKind};
+
+// -------------------------------------------------------------------------------------------------
+// LOADING
+// -------------------------------------------------------------------------------------------------
+
+const MAGIC: &[u8] = b"DDS ";
+const HEADER_SIZE: u32 = 124;
+
+const DDSD_MIPMAPCOUNT: u32 = 0x2_0000;
+
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_ALPHA: u32 = 0x2;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDPF_LUMINANCE: u32 = 0x2_0000;
+
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_CUBEMAP_ALLFACES: u32 = 0xFC00;
+const DDSCAPS2_VOLUME: u32 = 0x20_0000;
+
+const DX10_RESOURCE_TEXTURE2D: u32 = 3;
+const DX10_MISC_TEXTURECUBE: u32 = 0x4;
+
+/// Parses a DDS file, including files with the DX10 extended header.
+///
+/// S3TC (DXT1/3/5, BC1-3) payloads, the ETC1 FourCC some mobile tools write, and 8 bit RGB(A),
+/// 565, luminance and alpha layouts are supported. BGR(A) channel orders are swizzled to RGB(A)
+/// while loading. Array and volume textures are rejected with `ErrorKind::Unsupported`.
+pub fn load(data: &[u8]) -> Result<TextureData, Error> {
+    let mut reader = Reader::new(data);
+
+    if reader.bytes(MAGIC.len()).ok() != Some(MAGIC) {
+        return Err(format_error("not a DDS file"));
+    }
+
+    if reader.u32()? != HEADER_SIZE {
+        return Err(format_error("invalid DDS header size"));
+    }
+
+    let flags = reader.u32()?;
+    let height = reader.u32()?;
+    let width = reader.u32()?;
+    let _pitch_or_linear_size = reader.u32()?;
+    let _depth = reader.u32()?;
+    let mipmap_count = reader.u32()?;
+    let level_count = if flags & DDSD_MIPMAPCOUNT != 0 { mipmap_count.max(1) } else { 1 };
+    reader.skip(11 * 4)?;
+
+    let pixel_format = PixelFormat {
+        _size: reader.u32()?,
+        flags: reader.u32()?,
+        four_cc: reader.u32()?.to_le_bytes(),
+        bit_count: reader.u32()?,
+        masks: [reader.u32()?, reader.u32()?, reader.u32()?, reader.u32()?],
+    };
+
+    let _caps = reader.u32()?;
+    let caps2 = reader.u32()?;
+    reader.skip(3 * 4)?;
+
+    if caps2 & DDSCAPS2_VOLUME != 0 {
+        return Err(Error::new(ErrorKind::Unsupported, "volume textures are not supported"));
+    }
+
+    let mut kind = if caps2 & DDSCAPS2_CUBEMAP != 0 {
+        if caps2 & DDSCAPS2_CUBEMAP_ALLFACES != DDSCAPS2_CUBEMAP_ALLFACES {
+            return Err(Error::new(ErrorKind::Unsupported, "cube maps with missing faces are not supported"));
+        }
+        TextureKind::CubeMap
+    } else {
+        TextureKind::Texture2D
+    };
+
+    let (layout, swizzle) = if pixel_format.flags & DDPF_FOURCC != 0 && &pixel_format.four_cc == b"DX10" {
+        let dxgi_format = reader.u32()?;
+        let dimension = reader.u32()?;
+        let misc_flags = reader.u32()?;
+        let array_size = reader.u32()?;
+        let _misc_flags2 = reader.u32()?;
+
+        if dimension != DX10_RESOURCE_TEXTURE2D || array_size > 1 {
+            return Err(Error::new(ErrorKind::Unsupported, "only single 2D textures and cube maps are supported"));
+        }
+
+        if misc_flags & DX10_MISC_TEXTURECUBE != 0 {
+            kind = TextureKind::CubeMap;
+        }
+
+        let layout = dxgi_format_layout(dxgi_format).ok_or_else(|| {
+            Error::new(ErrorKind::Unsupported, format!("DXGI format {} has no GL ES equivalent", dxgi_format))
+        })?;
+        (layout, None)
+    } else {
+        pixel_format.layout()?
+    };
+
+    let (internal_format, format, type_) = layout;
+    let faces = kind.image_targets().len();
+    check_size(width, height, level_count)?;
+
+    let mut levels = Vec::with_capacity(level_count as usize);
+    let mut sizes = Vec::with_capacity(level_count as usize);
+    for level in 0..level_count {
+        let (level_width, level_height) = ((width >> level).max(1), (height >> level).max(1));
+        sizes.push(match swizzle {
+            Some(ref swizzle) => swizzle.pixel_bytes * level_width as usize * level_height as usize,
+            None => image_size(internal_format, format, type_, level_width, level_height)?,
+        });
+        levels.push(Level {
+            width: level_width,
+            height: level_height,
+            faces: Vec::with_capacity(faces),
+        });
+    }
+
+    // Checked up front, so that a file claiming more than it holds fails before anything is copied.
+    if sizes.iter().sum::<usize>() * faces > reader.remaining() {
+        return Err(format_error(format!("DDS file is too short for its {} mip levels", level_count)));
+    }
+
+    // Unlike most containers, DDS stores every level of a face before moving to the next face.
+    for _ in 0..faces {
+        for (level, &size) in levels.iter_mut().zip(&sizes) {
+            let bytes = reader.bytes(size)?;
+            level.faces.push(match swizzle {
+                Some(ref swizzle) => swizzle.apply(bytes),
+                None => bytes.to_vec(),
+            });
+        }
+    }
+
+    Ok(TextureData {
+        kind,
+        internal_format,
+        format,
+        type_,
+        unpack_alignment: 1,
+        levels,
+        generate_mipmaps: false,
+    })
+}
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+// (internal format, format, type). Compressed formats have format and type 0.
+type Layout = (GLenum, GLenum, GLenum);
+
+struct PixelFormat {
+    _size: u32,
+    flags: u32,
+    four_cc: [u8; 4],
+    bit_count: u32,
+    /// Red, green, blue and alpha masks.
+    masks: [u32; 4],
+}
+
+impl PixelFormat {
+    // Maps the legacy pixel format to the layout to upload, along with a swizzle if the stored
+    // channel order has to change first.
+    fn layout(&self) -> Result<(Layout, Option<Swizzle>), Error> {
+        let compressed = |internal_format| Ok(((internal_format, 0, 0), None));
+
+        if self.flags & DDPF_FOURCC != 0 {
+            return match &self.four_cc {
+                b"DXT1" if self.flags & DDPF_ALPHAPIXELS != 0 => compressed(GL_COMPRESSED_RGBA_S3TC_DXT1_EXT),
+                b"DXT1" => compressed(GL_COMPRESSED_RGB_S3TC_DXT1_EXT),
+                b"DXT2" | b"DXT3" => compressed(GL_COMPRESSED_RGBA_S3TC_DXT3_EXT),
+                b"DXT4" | b"DXT5" => compressed(GL_COMPRESSED_RGBA_S3TC_DXT5_EXT),
+                b"ETC1" | b"ETC " => compressed(GL_ETC1_RGB8_OES),
+                other => Err(Error::new(ErrorKind::Unsupported, format!(
+                    "DDS FourCC {:?} is not supported", String::from_utf8_lossy(other)))),
+            };
+        }
+
+        let [red, green, blue, alpha] = self.masks;
+        let has_alpha = self.flags & DDPF_ALPHAPIXELS != 0;
+        let unsupported = || Error::new(ErrorKind::Unsupported, format!(
+            "DDS layout of {} bits with masks {:08x?} is not supported", self.bit_count, self.masks));
+
+        if self.flags & DDPF_RGB != 0 {
+            let layout = match (self.bit_count, red, green, blue) {
+                (16, 0xF800, 0x07E0, 0x001F) if !has_alpha => (GL_RGB, GL_RGB, GL_UNSIGNED_SHORT_5_6_5),
+                (24, _, _, _) | (32, _, _, _) => {
+                    let alpha = if has_alpha { alpha } else { 0 };
+                    let swizzle = Swizzle::new(self.bit_count as usize / 8, [red, green, blue, alpha])
+                        .ok_or_else(unsupported)?;
+                    return Ok(((swizzle.output_format, swizzle.output_format, GL_UNSIGNED_BYTE), Some(swizzle)));
+                }
+                _ => return Err(unsupported()),
+            };
+            return Ok((layout, None));
+        }
+
+        let layout = match (self.flags & (DDPF_LUMINANCE | DDPF_ALPHA), self.bit_count, has_alpha) {
+            (DDPF_LUMINANCE, 8, false) => (GL_LUMINANCE, GL_LUMINANCE, GL_UNSIGNED_BYTE),
+            (DDPF_LUMINANCE, 16, true) if red == 0xFF && alpha == 0xFF00 => {
+                (GL_LUMINANCE_ALPHA, GL_LUMINANCE_ALPHA, GL_UNSIGNED_BYTE)
+            }
+            (DDPF_ALPHA, 8, _) => (GL_ALPHA, GL_ALPHA, GL_UNSIGNED_BYTE),
+            _ => return Err(unsupported()),
+        };
+        Ok((layout, None))
+    }
+}
+
+/// Reorders 8 bit channels of 24 and 32 bit pixels into RGB or RGBA.
+struct Swizzle {
+    pixel_bytes: usize,
+    /// Source byte of red, green, blue and, for RGBA output, alpha.
+    sources: Vec<usize>,
+    output_format: GLenum,
+}
+
+impl Swizzle {
+    fn new(pixel_bytes: usize, masks: [u32; 4]) -> Option<Swizzle> {
+        let byte_of = |mask: u32| match mask {
+            0x0000_00FF => Some(0),
+            0x0000_FF00 => Some(1),
+            0x00FF_0000 => Some(2),
+            0xFF00_0000 => Some(3),
+            _ => None,
+        };
+
+        let mut sources = vec![byte_of(masks[0])?, byte_of(masks[1])?, byte_of(masks[2])?];
+        if masks[3] != 0 {
+            sources.push(byte_of(masks[3])?);
+        }
+
+        if sources.iter().any(|&source| source >= pixel_bytes) {
+            return None;
+        }
+
+        let output_format = if sources.len() == 4 { GL_RGBA } else { GL_RGB };
+        Some(Swizzle { pixel_bytes, sources, output_format })
+    }
+
+    fn apply(&self, image: &[u8]) -> Vec<u8> {
+        image.chunks(self.pixel_bytes)
+            .flat_map(|pixel| self.sources.iter().map(move |&source| pixel[source]))
+            .collect()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+fn dxgi_format_layout(dxgi_format: u32) -> Option<Layout> {
+    let compressed = |internal_format| Some((internal_format, 0, 0));
+
+    match dxgi_format {
+        2 => Some((GL_RGBA32F, GL_RGBA, GL_FLOAT)),
+        10 => Some((GL_RGBA16F, GL_RGBA, GL_HALF_FLOAT)),
+        16 => Some((GL_RG32F, GL_RG, GL_FLOAT)),
+        24 => Some((GL_RGB10_A2, GL_RGBA, GL_UNSIGNED_INT_2_10_10_10_REV)),
+        26 => Some((GL_R11F_G11F_B10F, GL_RGB, GL_UNSIGNED_INT_10F_11F_11F_REV)),
+        28 => Some((GL_RGBA8, GL_RGBA, GL_UNSIGNED_BYTE)),
+        29 => Some((GL_SRGB8_ALPHA8, GL_RGBA, GL_UNSIGNED_BYTE)),
+        34 => Some((GL_RG16F, GL_RG, GL_HALF_FLOAT)),
+        41 => Some((GL_R32F, GL_RED, GL_FLOAT)),
+        49 => Some((GL_RG8, GL_RG, GL_UNSIGNED_BYTE)),
+        54 => Some((GL_R16F, GL_RED, GL_HALF_FLOAT)),
+        61 => Some((GL_R8, GL_RED, GL_UNSIGNED_BYTE)),
+        67 => Some((GL_RGB9_E5, GL_RGB, GL_UNSIGNED_INT_5_9_9_9_REV)),
+        71 => compressed(GL_COMPRESSED_RGBA_S3TC_DXT1_EXT),
+        74 => compressed(GL_COMPRESSED_RGBA_S3TC_DXT3_EXT),
+        77 => compressed(GL_COMPRESSED_RGBA_S3TC_DXT5_EXT),
+        // B5G6R5 puts red in the high bits, exactly like GL_UNSIGNED_SHORT_5_6_5.
+        85 => Some((GL_RGB565, GL_RGB, GL_UNSIGNED_SHORT_5_6_5)),
+        _ => None,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A DDS file with the given pixel format (flags, FourCC, bit count, masks) and payload.
+    fn dds(width: u32, height: u32, mipmap_count: u32, format: (u32, &[u8; 4], u32, [u32; 4]), data: &[u8]) -> Vec<u8> {
+        let (flags, four_cc, bit_count, masks) = format;
+        let mut words = vec![HEADER_SIZE, DDSD_MIPMAPCOUNT, height, width, 0, 0, mipmap_count];
+        words.extend(&[0; 11]);
+        words.extend(&[32, flags, u32::from_le_bytes(*four_cc), bit_count]);
+        words.extend(&masks);
+        words.extend(&[0; 5]);
+
+        let mut file = MAGIC.to_vec();
+        file.extend(words.iter().flat_map(|word| word.to_le_bytes().to_vec()));
+        file.extend_from_slice(data);
+        file
+    }
+
+    const BGRA: (u32, &[u8; 4], u32, [u32; 4]) =
+        (DDPF_RGB | DDPF_ALPHAPIXELS, &[0; 4], 32, [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000]);
+    const DXT1: (u32, &[u8; 4], u32, [u32; 4]) = (DDPF_FOURCC, b"DXT1", 0, [0; 4]);
+
+    #[test]
+    fn loads_bgra_levels_as_rgba() {
+        let mut data = vec![];
+        for _ in 0..4 {
+            data.extend(&[3, 2, 1, 4]);
+        }
+        data.extend(&[7, 6, 5, 8]);
+        let texture = load(&dds(2, 2, 2, BGRA, &data)).unwrap();
+
+        assert_eq!((texture.internal_format, texture.format, texture.type_), (GL_RGBA, GL_RGBA, GL_UNSIGNED_BYTE));
+        assert_eq!(texture.levels.len(), 2);
+        assert_eq!(texture.levels[0].faces, vec![[1, 2, 3, 4].repeat(4)]);
+        assert_eq!(texture.levels[1], Level { width: 1, height: 1, faces: vec![vec![5, 6, 7, 8]] });
+    }
+
+    #[test]
+    fn loads_dxt1() {
+        let texture = load(&dds(4, 4, 1, DXT1, &[0xAB; 8])).unwrap();
+        assert!(texture.is_compressed());
+        assert_eq!(texture.internal_format, GL_COMPRESSED_RGB_S3TC_DXT1_EXT);
+        assert_eq!(texture.levels[0].faces, vec![vec![0xAB; 8]]);
+    }
+
+    #[test]
+    fn truncated_files_are_format_errors() {
+        let file = dds(4, 4, 3, DXT1, &[0; 24]);
+        assert!(load(&file).is_ok());
+        assert_eq!(load(&file[..file.len() - 1]).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(&file[..40]).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(b"DDS").unwrap_err().kind, ErrorKind::Format);
+    }
+
+    #[test]
+    fn impossible_headers_are_format_errors() {
+        assert_eq!(load(&dds(4, 4, 4, DXT1, &[0; 32])).unwrap_err().kind, ErrorKind::Format);
+        // Would otherwise reserve memory for four billion levels.
+        assert_eq!(load(&dds(4, 4, u32::MAX, DXT1, &[0; 24])).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(&dds(1 << 20, 4, 1, DXT1, &[])).unwrap_err().kind, ErrorKind::Format);
+        // A valid level count the file is too short for.
+        assert_eq!(load(&dds(1 << 16, 1 << 16, 17, BGRA, &[0; 64])).unwrap_err().kind, ErrorKind::Format);
+    }
+}
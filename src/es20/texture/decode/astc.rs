@@ -0,0 +1,611 @@
+// -------------------------------------------------------------------------------------------------
+// TABLES
+// -------------------------------------------------------------------------------------------------
+
+const ERROR_COLOR: [u8; 4] = [255, 0, 255, 255];
+
+// Every range the integer sequence encoding supports, by number of levels.
+const QUANT_LEVELS: [u32; 21] = [
+    2, 3, 4, 5, 6, 8, 10, 12, 16, 20, 24, 32, 40, 48, 64, 80, 96, 128, 160, 192, 256,
+];
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+struct BlockMode {
+    grid_width: usize,
+    grid_height: usize,
+    dual_plane: bool,
+    weight_levels: u32,
+}
+
+impl BlockMode {
+    // Decodes the 11 bit block mode field; `None` for reserved encodings.
+    fn decode(mode: u32) -> Option<BlockMode> {
+        let a = (mode >> 5) & 3;
+        let mut range = (mode >> 4) & 1;
+        let mut high_precision = (mode >> 9) & 1 != 0;
+        let mut dual_plane = (mode >> 10) & 1 != 0;
+
+        let (width, height) = if mode & 3 != 0 {
+            range |= (mode & 3) << 1;
+            let b = (mode >> 7) & 3;
+
+            match (mode >> 2) & 3 {
+                0 => (b + 4, a + 2),
+                1 => (b + 8, a + 2),
+                2 => (a + 2, b + 8),
+                _ if mode & 0x100 != 0 => ((b & 1) + 2, a + 2),
+                _ => (a + 2, (b & 1) + 6),
+            }
+        } else {
+            range |= ((mode >> 2) & 3) << 1;
+            if (mode >> 2) & 3 == 0 {
+                return None;
+            }
+
+            let b = (mode >> 9) & 3;
+            match (mode >> 7) & 3 {
+                0 => (12, a + 2),
+                1 => (a + 2, 12),
+                2 => {
+                    high_precision = false;
+                    dual_plane = false;
+                    (a + 6, b + 6)
+                }
+                _ => match a {
+                    0 => (6, 10),
+                    1 => (10, 6),
+                    _ => return None,
+                },
+            }
+        };
+
+        Some(BlockMode {
+            grid_width: width as usize,
+            grid_height: height as usize,
+            dual_plane,
+            weight_levels: QUANT_LEVELS[(range - 2 + if high_precision { 6 } else { 0 }) as usize],
+        })
+    }
+
+    fn weight_count(&self) -> usize {
+        self.grid_width * self.grid_height * if self.dual_plane { 2 } else { 1 }
+    }
+}
+
+/// One value of an integer sequence: the trit or quint part and the plain bits below it.
+#[derive(Copy, Clone)]
+struct Quantized {
+    high: u32,
+    low: u32,
+}
+
+// -------------------------------------------------------------------------------------------------
+// DECODING
+// -------------------------------------------------------------------------------------------------
+
+/// Decodes a 16 byte ASTC LDR block into `width` x `height` row-major texels.
+pub fn decode_block(block: &[u8], width: usize, height: usize, srgb: bool, texels: &mut [[u8; 4]]) {
+    if try_decode_block(block, width, height, srgb, texels).is_none() {
+        for texel in texels.iter_mut() {
+            *texel = ERROR_COLOR;
+        }
+    }
+}
+
+fn try_decode_block(block: &[u8], width: usize, height: usize, srgb: bool, texels: &mut [[u8; 4]]) -> Option<()> {
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&block[..16]);
+    let data = u128::from_le_bytes(bytes);
+
+    let mode = bits(data, 0, 11);
+    if mode & 0x1FF == 0x1FC {
+        return decode_void_extent(data, texels);
+    }
+
+    let block_mode = BlockMode::decode(mode)?;
+    let weight_count = block_mode.weight_count();
+    let weight_bits = sequence_bits(weight_count, block_mode.weight_levels);
+
+    if block_mode.grid_width > width || block_mode.grid_height > height || weight_count > 64
+        || !(24..=96).contains(&weight_bits) {
+        return None;
+    }
+
+    let partitions = bits(data, 11, 2) as usize + 1;
+    if partitions == 4 && block_mode.dual_plane {
+        return None;
+    }
+
+    // Color endpoint modes, and where the color data and the bits below the weights begin.
+    let mut endpoint_modes = [0u32; 4];
+    let (seed, color_start, extra_bits) = if partitions == 1 {
+        endpoint_modes[0] = bits(data, 13, 4);
+        (0, 17, 0)
+    } else {
+        let selector = bits(data, 23, 6);
+        if selector & 3 == 0 {
+            for endpoint_mode in &mut endpoint_modes[..partitions] {
+                *endpoint_mode = selector >> 2;
+            }
+            (bits(data, 13, 10), 29, 0)
+        } else {
+            let extra_bits = 3 * partitions as u32 - 4;
+            let extra = bits(data, 128 - weight_bits - extra_bits, extra_bits);
+            let value = selector >> 2 | extra << 4;
+            let class = (selector & 3) - 1;
+
+            for (index, endpoint_mode) in endpoint_modes[..partitions].iter_mut().enumerate() {
+                let class_offset = (value >> index) & 1;
+                let mode_bits = (value >> (partitions + 2 * index)) & 3;
+                *endpoint_mode = (class + class_offset) << 2 | mode_bits;
+            }
+            (bits(data, 13, 10), 29, extra_bits)
+        }
+    };
+
+    let below_weights = 128 - weight_bits - extra_bits - if block_mode.dual_plane { 2 } else { 0 };
+    let plane_channel = if block_mode.dual_plane { Some(bits(data, below_weights, 2) as usize) } else { None };
+
+    if below_weights < color_start {
+        return None;
+    }
+
+    let color_bits = below_weights - color_start;
+    let color_count: usize = endpoint_modes[..partitions].iter().map(|mode| (mode / 4 + 1) as usize * 2).sum();
+    if color_count > 18 || color_bits < (13 * color_count as u32).div_ceil(5) {
+        return None;
+    }
+
+    let color_levels = *QUANT_LEVELS.iter().rev().find(|&&levels| sequence_bits(color_count, levels) <= color_bits)?;
+    let colors: Vec<i32> = decode_sequence(slice(data, color_start, color_bits), color_count, color_levels)
+        .into_iter()
+        .map(|value| unquantize_color(value, color_levels))
+        .collect();
+
+    let mut endpoints = [([0i32; 4], [0i32; 4]); 4];
+    let mut values = &colors[..];
+    for (index, &endpoint_mode) in endpoint_modes[..partitions].iter().enumerate() {
+        let count = (endpoint_mode / 4 + 1) as usize * 2;
+        endpoints[index] = decode_endpoints(endpoint_mode, &values[..count])?;
+        values = &values[count..];
+    }
+
+    // Weights are stored bit-reversed from the top of the block.
+    let weights: Vec<u32> = decode_sequence(slice(data.reverse_bits(), 0, weight_bits), weight_count,
+                                            block_mode.weight_levels)
+        .into_iter()
+        .map(|value| unquantize_weight(value, block_mode.weight_levels))
+        .collect();
+
+    let planes = if block_mode.dual_plane { 2 } else { 1 };
+    let small_block = width * height < 31;
+
+    for y in 0..height {
+        for x in 0..width {
+            let partition = if partitions > 1 {
+                select_partition(seed, x as u32, y as u32, partitions as u32, small_block)
+            } else {
+                0
+            };
+
+            let (low, high) = endpoints[partition];
+            let plane_weights = [
+                infill(&weights, &block_mode, planes, 0, x, y, width, height),
+                infill(&weights, &block_mode, planes, 1, x, y, width, height),
+            ];
+
+            let texel = &mut texels[y * width + x];
+            for channel in 0..4 {
+                let plane = if plane_channel == Some(channel) { 1 } else { 0 };
+                texel[channel] = interpolate(low[channel], high[channel], plane_weights[plane], srgb);
+            }
+        }
+    }
+
+    Some(())
+}
+
+fn decode_void_extent(data: u128, texels: &mut [[u8; 4]]) -> Option<()> {
+    // HDR void extents can not be represented in RGBA8.
+    if bits(data, 9, 1) != 0 {
+        return None;
+    }
+
+    let color = [
+        (bits(data, 64, 16) >> 8) as u8,
+        (bits(data, 80, 16) >> 8) as u8,
+        (bits(data, 96, 16) >> 8) as u8,
+        (bits(data, 112, 16) >> 8) as u8,
+    ];
+
+    for texel in texels.iter_mut() {
+        *texel = color;
+    }
+
+    Some(())
+}
+
+// -------------------------------------------------------------------------------------------------
+// INTEGER SEQUENCE ENCODING
+// -------------------------------------------------------------------------------------------------
+
+// Splits a level count into (trits or quints, plain bits); the first is 1 for pure bit ranges.
+fn encoding(levels: u32) -> (u32, u32) {
+    if levels.is_multiple_of(3) {
+        (3, (levels / 3).trailing_zeros())
+    } else if levels.is_multiple_of(5) {
+        (5, (levels / 5).trailing_zeros())
+    } else {
+        (1, levels.trailing_zeros())
+    }
+}
+
+fn sequence_bits(count: usize, levels: u32) -> u32 {
+    let count = count as u32;
+    let (kind, plain) = encoding(levels);
+
+    plain * count + match kind {
+        3 => (8 * count).div_ceil(5),
+        5 => (7 * count).div_ceil(3),
+        _ => 0,
+    }
+}
+
+fn decode_sequence(data: u128, count: usize, levels: u32) -> Vec<Quantized> {
+    let (kind, plain) = encoding(levels);
+    let mut reader = BitReader { data, position: 0 };
+    let mut values = Vec::with_capacity(count);
+
+    while values.len() < count {
+        match kind {
+            3 => {
+                // m0 T[1:0] m1 T[3:2] m2 T[4] m3 T[6:5] m4 T[7]
+                let mut low = [0u32; 5];
+                let mut packed = 0;
+                for (index, &(shift, width)) in [(0, 2), (2, 2), (4, 1), (5, 2), (7, 1)].iter().enumerate() {
+                    low[index] = reader.read(plain);
+                    packed |= reader.read(width) << shift;
+                }
+
+                for (high, low) in decode_trits(packed).iter().zip(&low) {
+                    values.push(Quantized { high: *high, low: *low });
+                }
+            }
+            5 => {
+                // m0 Q[2:0] m1 Q[4:3] m2 Q[6:5]
+                let mut low = [0u32; 3];
+                let mut packed = 0;
+                for (index, &(shift, width)) in [(0, 3), (3, 2), (5, 2)].iter().enumerate() {
+                    low[index] = reader.read(plain);
+                    packed |= reader.read(width) << shift;
+                }
+
+                for (high, low) in decode_quints(packed).iter().zip(&low) {
+                    values.push(Quantized { high: *high, low: *low });
+                }
+            }
+            _ => values.push(Quantized { high: 0, low: reader.read(plain) }),
+        }
+    }
+
+    values.truncate(count);
+    values
+}
+
+fn decode_trits(packed: u32) -> [u32; 5] {
+    let bit = |value: u32, position: u32| (value >> position) & 1;
+
+    let (c, t4, t3) = if (packed >> 2) & 7 == 7 {
+        (((packed >> 5) & 7) << 2 | (packed & 3), 2, 2)
+    } else if (packed >> 5) & 3 == 3 {
+        (packed & 0x1F, 2, bit(packed, 7))
+    } else {
+        (packed & 0x1F, bit(packed, 7), (packed >> 5) & 3)
+    };
+
+    let (t2, t1, t0) = if c & 3 == 3 {
+        (2, bit(c, 4), bit(c, 3) << 1 | (bit(c, 2) & !bit(c, 3) & 1))
+    } else if (c >> 2) & 3 == 3 {
+        (2, 2, c & 3)
+    } else {
+        (bit(c, 4), (c >> 2) & 3, bit(c, 1) << 1 | (bit(c, 0) & !bit(c, 1) & 1))
+    };
+
+    [t0, t1, t2, t3, t4]
+}
+
+fn decode_quints(packed: u32) -> [u32; 3] {
+    let bit = |value: u32, position: u32| (value >> position) & 1;
+
+    if (packed >> 1) & 3 == 3 && (packed >> 5) & 3 == 0 {
+        let q0 = bit(packed, 0);
+        let q2 = q0 << 2 | (bit(packed, 4) & !q0 & 1) << 1 | (bit(packed, 3) & !q0 & 1);
+        return [4, 4, q2];
+    }
+
+    let (q2, c) = if (packed >> 1) & 3 == 3 {
+        (4, ((packed >> 3) & 3) << 3 | (!(packed >> 5) & 3) << 1 | bit(packed, 0))
+    } else {
+        ((packed >> 5) & 3, packed & 0x1F)
+    };
+
+    if c & 7 == 5 {
+        [(c >> 3) & 3, 4, q2]
+    } else {
+        [c & 7, (c >> 3) & 3, q2]
+    }
+}
+
+fn unquantize_color(value: Quantized, levels: u32) -> i32 {
+    let (kind, plain) = encoding(levels);
+    if kind == 1 {
+        return replicate(value.low, plain, 8) as i32;
+    }
+
+    let m = |position: u32| (value.low >> position) & 1;
+    let (scale, pattern) = match (kind, plain) {
+        (3, 1) => (204, 0),
+        (3, 2) => (93, m(1) * 0x116),
+        (3, 3) => (44, m(2) * 0x10A + m(1) * 0x85),
+        (3, 4) => (22, m(3) * 0x104 + m(2) * 0x82 + m(1) * 0x41),
+        (3, 5) => (11, m(4) * 0x102 + m(3) * 0x81 + m(2) * 0x40 + m(1) * 0x20),
+        (3, _) => (5, m(5) * 0x101 + m(4) * 0x80 + m(3) * 0x40 + m(2) * 0x20 + m(1) * 0x10),
+        (_, 1) => (113, 0),
+        (_, 2) => (54, m(1) * 0x10C),
+        (_, 3) => (26, m(2) * 0x105 + m(1) * 0x82),
+        (_, 4) => (13, m(3) * 0x102 + m(2) * 0x81 + m(1) * 0x40),
+        (_, _) => (6, m(4) * 0x101 + m(3) * 0x80 + m(2) * 0x40 + m(1) * 0x20),
+    };
+
+    let a = if m(0) != 0 { 0x1FF } else { 0 };
+    let t = (value.high * scale + pattern) ^ a;
+    ((a & 0x80) | (t >> 2)) as i32
+}
+
+fn unquantize_weight(value: Quantized, levels: u32) -> u32 {
+    let (kind, plain) = encoding(levels);
+    let m = |position: u32| (value.low >> position) & 1;
+
+    let unquantized = match (kind, plain) {
+        (1, _) => replicate(value.low, plain, 6),
+        (3, 0) => [0, 32, 63][value.high as usize],
+        (5, 0) => [0, 16, 32, 47, 63][value.high as usize],
+        _ => {
+            let (scale, pattern) = match (kind, plain) {
+                (3, 1) => (50, 0),
+                (3, 2) => (23, m(1) * 0x45),
+                (3, _) => (11, m(2) * 0x44 + m(1) * 0x21),
+                (_, 1) => (28, 0),
+                (_, _) => (13, m(1) * 0x42),
+            };
+
+            let a = if m(0) != 0 { 0x7F } else { 0 };
+            let t = (value.high * scale + pattern) ^ a;
+            (a & 0x20) | (t >> 2)
+        }
+    };
+
+    if unquantized > 32 { unquantized + 1 } else { unquantized }
+}
+
+// -------------------------------------------------------------------------------------------------
+// ENDPOINTS AND WEIGHTS
+// -------------------------------------------------------------------------------------------------
+
+// Decodes the LDR color endpoint modes; `None` for the HDR ones.
+fn decode_endpoints(mode: u32, v: &[i32]) -> Option<([i32; 4], [i32; 4])> {
+    let endpoints = match mode {
+        0 => ([v[0], v[0], v[0], 255], [v[1], v[1], v[1], 255]),
+        1 => {
+            let low = (v[0] >> 2) | (v[1] & 0xC0);
+            let high = (low + (v[1] & 0x3F)).min(255);
+            ([low, low, low, 255], [high, high, high, 255])
+        }
+        4 => ([v[0], v[0], v[0], v[2]], [v[1], v[1], v[1], v[3]]),
+        5 => {
+            let (l0, l1) = bit_transfer_signed(v[1], v[0]);
+            let (a0, a1) = bit_transfer_signed(v[3], v[2]);
+            let high = l0 + l1;
+            ([l0, l0, l0, a0], clamp_color([high, high, high, a0 + a1]))
+        }
+        6 => ([(v[0] * v[3]) >> 8, (v[1] * v[3]) >> 8, (v[2] * v[3]) >> 8, 255], [v[0], v[1], v[2], 255]),
+        8 | 12 => {
+            let (a0, a1) = if mode == 12 { (v[6], v[7]) } else { (255, 255) };
+            if v[1] + v[3] + v[5] >= v[0] + v[2] + v[4] {
+                ([v[0], v[2], v[4], a0], [v[1], v[3], v[5], a1])
+            } else {
+                (blue_contract([v[1], v[3], v[5], a1]), blue_contract([v[0], v[2], v[4], a0]))
+            }
+        }
+        9 | 13 => {
+            let (r0, r1) = bit_transfer_signed(v[1], v[0]);
+            let (g0, g1) = bit_transfer_signed(v[3], v[2]);
+            let (b0, b1) = bit_transfer_signed(v[5], v[4]);
+            let (a0, a1) = if mode == 13 { bit_transfer_signed(v[7], v[6]) } else { (255, 0) };
+
+            let base = [r0, g0, b0, a0];
+            let sum = [r0 + r1, g0 + g1, b0 + b1, a0 + a1];
+            if r1 + g1 + b1 >= 0 {
+                (base, clamp_color(sum))
+            } else {
+                (clamp_color(blue_contract(sum)), blue_contract(base))
+            }
+        }
+        10 => ([(v[0] * v[3]) >> 8, (v[1] * v[3]) >> 8, (v[2] * v[3]) >> 8, v[4]], [v[0], v[1], v[2], v[5]]),
+        _ => return None,
+    };
+
+    Some(endpoints)
+}
+
+// Moves the top bit of `offset` into `base` and returns (base, signed 6 bit offset).
+fn bit_transfer_signed(offset: i32, base: i32) -> (i32, i32) {
+    let base = (base >> 1) | (offset & 0x80);
+    let offset = (offset >> 1) & 0x3F;
+    (base, if offset & 0x20 != 0 { offset - 0x40 } else { offset })
+}
+
+fn blue_contract(color: [i32; 4]) -> [i32; 4] {
+    [(color[0] + color[2]) >> 1, (color[1] + color[2]) >> 1, color[2], color[3]]
+}
+
+fn clamp_color(color: [i32; 4]) -> [i32; 4] {
+    [color[0].clamp(0, 255), color[1].clamp(0, 255), color[2].clamp(0, 255), color[3].clamp(0, 255)]
+}
+
+// Bilinearly resamples the weight grid of `plane` at texel (x, y).
+fn infill(weights: &[u32], mode: &BlockMode, planes: usize, plane: usize, x: usize, y: usize, width: usize,
+          height: usize) -> u32 {
+    if plane >= planes {
+        return 0;
+    }
+
+    let scale = |size: usize| (1024 + size / 2) / (size - 1);
+    let cs = scale(width) * x;
+    let ct = scale(height) * y;
+    let gs = (cs * (mode.grid_width - 1) + 32) >> 6;
+    let gt = (ct * (mode.grid_height - 1) + 32) >> 6;
+
+    let (js, fs) = (gs >> 4, (gs & 0xF) as u32);
+    let (jt, ft) = (gt >> 4, (gt & 0xF) as u32);
+
+    let weight = |column: usize, row: usize| {
+        weights.get((row * mode.grid_width + column) * planes + plane).cloned().unwrap_or(0)
+    };
+
+    let w11 = (fs * ft + 8) >> 4;
+    let w10 = ft - w11;
+    let w01 = fs - w11;
+    let w00 = 16 + w11 - fs - ft;
+
+    let sum = weight(js, jt) * w00 + weight(js + 1, jt) * w01 + weight(js, jt + 1) * w10
+        + weight(js + 1, jt + 1) * w11;
+    (sum + 8) >> 4
+}
+
+fn interpolate(low: i32, high: i32, weight: u32, srgb: bool) -> u8 {
+    let expand = |value: i32| if srgb { (value << 8) | 0x80 } else { (value << 8) | value };
+    let weight = weight as i32;
+    let value = (expand(low) * (64 - weight) + expand(high) * weight + 32) >> 6;
+    (value >> 8) as u8
+}
+
+fn select_partition(seed: u32, x: u32, y: u32, partitions: u32, small_block: bool) -> usize {
+    let (x, y) = if small_block { (x << 1, y << 1) } else { (x, y) };
+    let seed = seed + (partitions - 1) * 1024;
+    let random = hash52(seed);
+
+    let mut seeds = [
+        random & 0xF,
+        (random >> 4) & 0xF,
+        (random >> 8) & 0xF,
+        (random >> 12) & 0xF,
+        (random >> 16) & 0xF,
+        (random >> 20) & 0xF,
+        (random >> 24) & 0xF,
+        (random >> 28) & 0xF,
+        (random >> 18) & 0xF,
+        (random >> 22) & 0xF,
+        (random >> 26) & 0xF,
+        random.rotate_right(30) & 0xF,
+    ];
+
+    let (shift1, shift2) = if seed & 1 != 0 {
+        (if seed & 2 != 0 { 4 } else { 5 }, if partitions == 3 { 6 } else { 5 })
+    } else {
+        (if partitions == 3 { 6 } else { 5 }, if seed & 2 != 0 { 4 } else { 5 })
+    };
+    let shift3 = if seed & 0x10 != 0 { shift1 } else { shift2 };
+
+    for (index, value) in seeds.iter_mut().enumerate() {
+        let shift = match index {
+            0..=7 if index % 2 == 0 => shift1,
+            0..=7 => shift2,
+            _ => shift3,
+        };
+        *value = (*value * *value) >> shift;
+    }
+
+    // Only x and y vary in 2D, z is always 0.
+    let a = (seeds[0].wrapping_mul(x).wrapping_add(seeds[1].wrapping_mul(y)).wrapping_add(random >> 14)) & 0x3F;
+    let b = (seeds[2].wrapping_mul(x).wrapping_add(seeds[3].wrapping_mul(y)).wrapping_add(random >> 10)) & 0x3F;
+    let mut c = (seeds[4].wrapping_mul(x).wrapping_add(seeds[5].wrapping_mul(y)).wrapping_add(random >> 6)) & 0x3F;
+    let mut d = (seeds[6].wrapping_mul(x).wrapping_add(seeds[7].wrapping_mul(y)).wrapping_add(random >> 2)) & 0x3F;
+
+    if partitions < 4 {
+        d = 0;
+    }
+    if partitions < 3 {
+        c = 0;
+    }
+
+    if a >= b && a >= c && a >= d {
+        0
+    } else if b >= c && b >= d {
+        1
+    } else if c >= d {
+        2
+    } else {
+        3
+    }
+}
+
+fn hash52(value: u32) -> u32 {
+    let mut p = value;
+    p ^= p >> 15;
+    p = p.wrapping_sub(p << 17);
+    p = p.wrapping_add(p << 7);
+    p = p.wrapping_add(p << 4);
+    p ^= p >> 5;
+    p = p.wrapping_add(p << 16);
+    p ^= p >> 7;
+    p ^= p >> 3;
+    p ^= p << 6;
+    p ^= p >> 17;
+    p
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+struct BitReader {
+    data: u128,
+    position: u32,
+}
+
+impl BitReader {
+    // Reads past the end of the sequence yield zeros, as the spec requires.
+    fn read(&mut self, count: u32) -> u32 {
+        let value = if self.position >= 128 { 0 } else { bits(self.data, self.position, count) };
+        self.position += count;
+        value
+    }
+}
+
+fn bits(data: u128, position: u32, count: u32) -> u32 {
+    ((data >> position) & ((1u128 << count) - 1)) as u32
+}
+
+// The `count` bits starting at `position`, moved to the bottom.
+fn slice(data: u128, position: u32, count: u32) -> u128 {
+    let data = data >> position;
+    if count >= 128 { data } else { data & ((1u128 << count) - 1) }
+}
+
+fn replicate(value: u32, from: u32, to: u32) -> u32 {
+    if from == 0 {
+        return 0;
+    }
+
+    let mut result = 0;
+    let mut filled = 0;
+    while filled < to {
+        result = (result << from) | value;
+        filled += from;
+    }
+    result >> (filled - to)
+}
@@ -0,0 +1,267 @@
+// -------------------------------------------------------------------------------------------------
+// TABLES
+// -------------------------------------------------------------------------------------------------
+
+// Intensity modifiers of the individual and differential modes, indexed by table codeword and
+// pixel index.
+const MODIFIERS: [[i32; 4]; 8] = [
+    [2, 8, -2, -8],
+    [5, 17, -5, -17],
+    [9, 29, -9, -29],
+    [13, 42, -13, -42],
+    [18, 60, -18, -60],
+    [24, 80, -24, -80],
+    [33, 106, -33, -106],
+    [47, 183, -47, -183],
+];
+
+// Paint color distances of the T and H modes.
+const DISTANCES: [i32; 8] = [3, 6, 11, 16, 23, 32, 41, 64];
+
+const EAC_MODIFIERS: [[i32; 8]; 16] = [
+    [-3, -6, -9, -15, 2, 5, 8, 14],
+    [-3, -7, -10, -13, 2, 6, 9, 12],
+    [-2, -5, -8, -13, 1, 4, 7, 12],
+    [-2, -4, -6, -13, 1, 3, 5, 12],
+    [-3, -6, -8, -12, 2, 5, 7, 11],
+    [-3, -7, -9, -11, 2, 6, 8, 10],
+    [-4, -7, -8, -11, 3, 6, 7, 10],
+    [-3, -5, -8, -11, 2, 4, 7, 10],
+    [-2, -6, -8, -10, 1, 5, 7, 9],
+    [-2, -5, -8, -10, 1, 4, 7, 9],
+    [-2, -4, -8, -10, 1, 3, 7, 9],
+    [-2, -5, -7, -10, 1, 4, 6, 9],
+    [-3, -4, -7, -10, 2, 3, 6, 9],
+    [-1, -2, -3, -10, 0, 1, 2, 9],
+    [-4, -6, -8, -9, 3, 5, 7, 8],
+    [-3, -5, -7, -9, 2, 4, 6, 8],
+];
+
+const TRANSPARENT: [u8; 4] = [0, 0, 0, 0];
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RgbMode {
+    /// ETC1: overflowing differential colors wrap instead of selecting the ETC2 modes.
+    Etc1,
+    Etc2,
+    /// ETC2 with punchthrough alpha: the differential bit marks opaque blocks instead.
+    Punchthrough,
+}
+
+// -------------------------------------------------------------------------------------------------
+// DECODING
+// -------------------------------------------------------------------------------------------------
+
+/// Decodes an 8 byte ETC1/ETC2 color block into 16 row-major texels.
+pub fn decode_rgb(block: &[u8], mode: RgbMode, texels: &mut [[u8; 4]]) {
+    let bits = read_u64(block);
+    let flag = bit(bits, 33) != 0;
+    let flip = bit(bits, 32) != 0;
+
+    let punchthrough = mode == RgbMode::Punchthrough;
+    let opaque = !punchthrough || flag;
+
+    if !punchthrough && !flag {
+        let first = [extend4(field(bits, 60, 4)), extend4(field(bits, 52, 4)), extend4(field(bits, 44, 4))];
+        let second = [extend4(field(bits, 56, 4)), extend4(field(bits, 48, 4)), extend4(field(bits, 40, 4))];
+        return decode_subblocks(bits, [first, second], flip, opaque, texels);
+    }
+
+    let base = [field(bits, 59, 5), field(bits, 51, 5), field(bits, 43, 5)];
+    let delta = [signed3(field(bits, 56, 3)), signed3(field(bits, 48, 3)), signed3(field(bits, 40, 3))];
+    let sum = [base[0] + delta[0], base[1] + delta[1], base[2] + delta[2]];
+    let overflows = |value: i32| !(0..32).contains(&value);
+
+    if mode != RgbMode::Etc1 {
+        if overflows(sum[0]) {
+            return decode_t(bits, opaque, texels);
+        }
+        if overflows(sum[1]) {
+            return decode_h(bits, opaque, texels);
+        }
+        if overflows(sum[2]) {
+            return decode_planar(bits, texels);
+        }
+    }
+
+    let first = [extend5(base[0]), extend5(base[1]), extend5(base[2])];
+    let second = [extend5(sum[0] & 31), extend5(sum[1] & 31), extend5(sum[2] & 31)];
+    decode_subblocks(bits, [first, second], flip, opaque, texels)
+}
+
+/// Decodes an 8 byte EAC block into channel `channel` of 16 row-major texels.
+pub fn decode_alpha(block: &[u8], channel: usize, texels: &mut [[u8; 4]]) {
+    let bits = read_u64(block);
+    let base = field(bits, 56, 8);
+    let multiplier = field(bits, 52, 4);
+    let modifiers = &EAC_MODIFIERS[field(bits, 48, 4) as usize];
+
+    for_each_pixel(texels, |texel, index| {
+        let modifier = modifiers[field(bits, 45 - 3 * index as u32, 3) as usize];
+        texel[channel] = clamp(base + modifier * multiplier);
+    });
+}
+
+/// Decodes an 8 byte unsigned R11 EAC block into channel `channel`, rounded to 8 bits.
+pub fn decode_r11(block: &[u8], channel: usize, texels: &mut [[u8; 4]]) {
+    let bits = read_u64(block);
+    let base = field(bits, 56, 8) * 8 + 4;
+    let multiplier = field(bits, 52, 4);
+    let modifiers = &EAC_MODIFIERS[field(bits, 48, 4) as usize];
+
+    for_each_pixel(texels, |texel, index| {
+        let modifier = modifiers[field(bits, 45 - 3 * index as u32, 3) as usize];
+        // A zero multiplier means 1/8, i.e. the modifier is applied at 11 bit precision.
+        let scaled = if multiplier == 0 { modifier } else { modifier * multiplier * 8 };
+        let value = (base + scaled).clamp(0, 2047);
+        texel[channel] = ((value * 255 + 1023) / 2047) as u8;
+    });
+}
+
+// -------------------------------------------------------------------------------------------------
+// MODES
+// -------------------------------------------------------------------------------------------------
+
+fn decode_subblocks(bits: u64, bases: [[i32; 3]; 2], flip: bool, opaque: bool, texels: &mut [[u8; 4]]) {
+    let tables = [field(bits, 37, 3) as usize, field(bits, 34, 3) as usize];
+
+    for_each_pixel(texels, |texel, index| {
+        let (x, y) = (index / 4, index % 4);
+        let subblock = if flip { (y >= 2) as usize } else { (x >= 2) as usize };
+        let selector = pixel_index(bits, index);
+
+        // Non-opaque punchthrough blocks trade the +a modifier for 0 and -a for transparency.
+        *texel = match (opaque, selector) {
+            (false, 2) => TRANSPARENT,
+            (false, 0) => offset(bases[subblock], 0),
+            _ => offset(bases[subblock], MODIFIERS[tables[subblock]][selector]),
+        };
+    });
+}
+
+fn decode_t(bits: u64, opaque: bool, texels: &mut [[u8; 4]]) {
+    let first = [
+        extend4(field(bits, 59, 2) << 2 | field(bits, 56, 2)),
+        extend4(field(bits, 52, 4)),
+        extend4(field(bits, 48, 4)),
+    ];
+    let second = [extend4(field(bits, 44, 4)), extend4(field(bits, 40, 4)), extend4(field(bits, 36, 4))];
+    let distance = DISTANCES[(field(bits, 34, 2) << 1 | field(bits, 32, 1)) as usize];
+
+    let paint = [offset(first, 0), offset(second, distance), offset(second, 0), offset(second, -distance)];
+    decode_paint(bits, paint, opaque, texels);
+}
+
+fn decode_h(bits: u64, opaque: bool, texels: &mut [[u8; 4]]) {
+    let first = [
+        field(bits, 59, 4),
+        field(bits, 56, 3) << 1 | field(bits, 52, 1),
+        field(bits, 51, 1) << 3 | field(bits, 47, 3),
+    ];
+    let second = [field(bits, 43, 4), field(bits, 39, 4), field(bits, 35, 4)];
+
+    // The last bit of the distance index is implied by the order of the two base colors.
+    let key = |color: [i32; 3]| color[0] << 8 | color[1] << 4 | color[2];
+    let index = field(bits, 34, 1) << 2 | field(bits, 32, 1) << 1 | (key(first) >= key(second)) as i32;
+    let distance = DISTANCES[index as usize];
+
+    let first = [extend4(first[0]), extend4(first[1]), extend4(first[2])];
+    let second = [extend4(second[0]), extend4(second[1]), extend4(second[2])];
+
+    let paint = [offset(first, distance), offset(first, -distance), offset(second, distance),
+                 offset(second, -distance)];
+    decode_paint(bits, paint, opaque, texels);
+}
+
+fn decode_paint(bits: u64, paint: [[u8; 4]; 4], opaque: bool, texels: &mut [[u8; 4]]) {
+    for_each_pixel(texels, |texel, index| {
+        let selector = pixel_index(bits, index);
+        *texel = if !opaque && selector == 2 { TRANSPARENT } else { paint[selector] };
+    });
+}
+
+fn decode_planar(bits: u64, texels: &mut [[u8; 4]]) {
+    let origin = [
+        extend6(field(bits, 57, 6)),
+        extend7(field(bits, 56, 1) << 6 | field(bits, 49, 6)),
+        extend6(field(bits, 48, 1) << 5 | field(bits, 43, 2) << 3 | field(bits, 39, 3)),
+    ];
+    let horizontal = [
+        extend6(field(bits, 34, 5) << 1 | field(bits, 32, 1)),
+        extend7(field(bits, 25, 7)),
+        extend6(field(bits, 19, 6)),
+    ];
+    let vertical = [extend6(field(bits, 13, 6)), extend7(field(bits, 6, 7)), extend6(field(bits, 0, 6))];
+
+    for_each_pixel(texels, |texel, index| {
+        let (x, y) = ((index / 4) as i32, (index % 4) as i32);
+        for channel in 0..3 {
+            let o = origin[channel];
+            let value = x * (horizontal[channel] - o) + y * (vertical[channel] - o) + 4 * o + 2;
+            texel[channel] = clamp(value >> 2);
+        }
+        texel[3] = 255;
+    });
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+// Pixels are numbered column by column inside the block, texels are stored row by row.
+fn for_each_pixel<F: FnMut(&mut [u8; 4], usize)>(texels: &mut [[u8; 4]], mut f: F) {
+    for index in 0..16 {
+        let (x, y) = (index / 4, index % 4);
+        f(&mut texels[y * 4 + x], index);
+    }
+}
+
+fn pixel_index(bits: u64, index: usize) -> usize {
+    (bit(bits, 16 + index as u32) << 1 | bit(bits, index as u32)) as usize
+}
+
+fn offset(color: [i32; 3], modifier: i32) -> [u8; 4] {
+    [clamp(color[0] + modifier), clamp(color[1] + modifier), clamp(color[2] + modifier), 255]
+}
+
+fn read_u64(block: &[u8]) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&block[..8]);
+    u64::from_be_bytes(bytes)
+}
+
+fn bit(bits: u64, position: u32) -> u64 {
+    (bits >> position) & 1
+}
+
+fn field(bits: u64, position: u32, count: u32) -> i32 {
+    ((bits >> position) & ((1 << count) - 1)) as i32
+}
+
+fn signed3(value: i32) -> i32 {
+    if value >= 4 { value - 8 } else { value }
+}
+
+fn extend4(value: i32) -> i32 {
+    value << 4 | value
+}
+
+fn extend5(value: i32) -> i32 {
+    value << 3 | value >> 2
+}
+
+fn extend6(value: i32) -> i32 {
+    value << 2 | value >> 4
+}
+
+fn extend7(value: i32) -> i32 {
+    value << 1 | value >> 6
+}
+
+fn clamp(value: i32) -> u8 {
+    value.clamp(0, 255) as u8
+}
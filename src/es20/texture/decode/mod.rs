@@ -0,0 +1,177 @@
+use types::*;
+use consts::*;
+use format::FormatInfo;
+use super::super::wrapper::{Error, ErrorKind};
+use super::format_name;
+
+mod astc;
+mod etc;
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum BlockFormat {
+    Etc1,
+    Etc2,
+    Etc2Punchthrough,
+    Etc2Eac,
+    R11Eac,
+    Rg11Eac,
+    Astc { srgb: bool },
+}
+
+impl BlockFormat {
+    fn for_internal_format(internal_format: GLenum) -> Option<BlockFormat> {
+        match internal_format {
+            GL_ETC1_RGB8_OES => Some(BlockFormat::Etc1),
+            GL_COMPRESSED_RGB8_ETC2 | GL_COMPRESSED_SRGB8_ETC2 => Some(BlockFormat::Etc2),
+            GL_COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2 | GL_COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2 => {
+                Some(BlockFormat::Etc2Punchthrough)
+            }
+            GL_COMPRESSED_RGBA8_ETC2_EAC | GL_COMPRESSED_SRGB8_ALPHA8_ETC2_EAC => Some(BlockFormat::Etc2Eac),
+            GL_COMPRESSED_R11_EAC => Some(BlockFormat::R11Eac),
+            GL_COMPRESSED_RG11_EAC => Some(BlockFormat::Rg11Eac),
+            GL_COMPRESSED_RGBA_ASTC_4x4..=GL_COMPRESSED_RGBA_ASTC_12x12 => Some(BlockFormat::Astc { srgb: false }),
+            GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4x4..=GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12x12 => {
+                Some(BlockFormat::Astc { srgb: true })
+            }
+            _ => None,
+        }
+    }
+
+    // Decodes one block into `texels`, which is row-major with the format's block dimensions.
+    fn decode(self, block: &[u8], width: usize, height: usize, texels: &mut [[u8; 4]]) {
+        match self {
+            BlockFormat::Etc1 => etc::decode_rgb(block, etc::RgbMode::Etc1, texels),
+            BlockFormat::Etc2 => etc::decode_rgb(block, etc::RgbMode::Etc2, texels),
+            BlockFormat::Etc2Punchthrough => etc::decode_rgb(block, etc::RgbMode::Punchthrough, texels),
+            BlockFormat::Etc2Eac => {
+                etc::decode_rgb(&block[8..], etc::RgbMode::Etc2, texels);
+                etc::decode_alpha(&block[..8], 3, texels);
+            }
+            BlockFormat::R11Eac => {
+                for texel in texels.iter_mut() {
+                    *texel = [0, 0, 0, 255];
+                }
+                etc::decode_r11(block, 0, texels);
+            }
+            BlockFormat::Rg11Eac => {
+                for texel in texels.iter_mut() {
+                    *texel = [0, 0, 0, 255];
+                }
+                etc::decode_r11(&block[..8], 0, texels);
+                etc::decode_r11(&block[8..], 1, texels);
+            }
+            BlockFormat::Astc { srgb } => astc::decode_block(block, width, height, srgb, texels),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// DECODING
+// -------------------------------------------------------------------------------------------------
+
+/// Whether `decode` can turn `internal_format` into RGBA8. Covers ETC1, the unsigned ETC2/EAC
+/// formats and ASTC LDR.
+pub fn can_decode(internal_format: GLenum) -> bool {
+    BlockFormat::for_internal_format(internal_format).is_some()
+}
+
+/// Decodes one compressed image of `width` x `height` texels into tightly packed RGBA8 rows.
+///
+/// Channels the format does not store read as 0, alpha as 255, matching how GL samples them.
+/// sRGB formats decode to sRGB-encoded bytes; the transfer function is not applied.
+/// ASTC blocks that are invalid or use HDR endpoints decode to the magenta error color, as the
+/// LDR profile requires.
+pub fn decode(internal_format: GLenum, width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let (block_format, info) = match (BlockFormat::for_internal_format(internal_format),
+                                      FormatInfo::for_internal_format(internal_format)) {
+        (Some(block_format), Some(info)) => (block_format, info),
+        _ => return Err(Error::new(ErrorKind::Unsupported, format!(
+            "no CPU decoder for {}", format_name(internal_format)))),
+    };
+
+    let (width, height) = (width as usize, height as usize);
+    let expected = info.image_size(width, height, 1);
+    if data.len() < expected {
+        return Err(Error::new(ErrorKind::InvalidArgument, format!(
+            "{}x{} {} image needs {} bytes, got {}", width, height, format_name(internal_format), expected,
+            data.len())));
+    }
+
+    let block_width = info.block_width as usize;
+    let block_height = info.block_height as usize;
+    let blocks_x = width.div_ceil(block_width);
+
+    let mut pixels = vec![0u8; width * height * 4];
+    let mut texels = vec![[0u8; 4]; block_width * block_height];
+
+    for (index, block) in data[..expected].chunks(info.block_bytes as usize).enumerate() {
+        block_format.decode(block, block_width, block_height, &mut texels);
+
+        let x0 = (index % blocks_x) * block_width;
+        let y0 = (index / blocks_x) * block_height;
+
+        for y in 0..block_height.min(height - y0) {
+            for x in 0..block_width.min(width - x0) {
+                let offset = ((y0 + y) * width + x0 + x) * 4;
+                pixels[offset..offset + 4].copy_from_slice(&texels[y * block_width + x]);
+            }
+        }
+    }
+
+    Ok(pixels)
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(pixels: &[u8], width: usize, x: usize, y: usize) -> &[u8] {
+        &pixels[(y * width + x) * 4..][..4]
+    }
+
+    #[test]
+    fn decodes_etc1_individual_block() {
+        // Red 15 in the left half, black in the right, table 0 and every pixel on modifier +2.
+        let block = [0xF0, 0, 0, 0, 0, 0, 0, 0];
+        let pixels = decode(GL_ETC1_RGB8_OES, 4, 4, &block).unwrap();
+
+        for y in 0..4 {
+            assert_eq!(pixel(&pixels, 4, 1, y), &[255, 2, 2, 255]);
+            assert_eq!(pixel(&pixels, 4, 2, y), &[2, 2, 2, 255]);
+        }
+    }
+
+    #[test]
+    fn crops_partial_blocks() {
+        let pixels = decode(GL_ETC1_RGB8_OES, 3, 2, &[0xF0, 0, 0, 0, 0, 0, 0, 0]).unwrap();
+        assert_eq!(pixels.len(), 3 * 2 * 4);
+        assert_eq!(pixel(&pixels, 3, 2, 1), &[2, 2, 2, 255]);
+    }
+
+    #[test]
+    fn decodes_astc_void_extent_and_error_color() {
+        let mut block = 0xFFFF_8000_0000_FFFF_FFFF_FFFF_FFFF_FDFCu128.to_le_bytes();
+        let pixels = decode(GL_COMPRESSED_RGBA_ASTC_4x4, 4, 4, &block).unwrap();
+        assert!(pixels.chunks(4).all(|texel| texel == [255, 0, 128, 255]));
+
+        // The HDR flag asks for values RGBA8 can not hold.
+        block[1] |= 0x02;
+        let pixels = decode(GL_COMPRESSED_RGBA_ASTC_4x4, 4, 4, &block).unwrap();
+        assert!(pixels.chunks(4).all(|texel| texel == [255, 0, 255, 255]));
+    }
+
+    #[test]
+    fn rejects_short_data_and_unknown_formats() {
+        assert_eq!(decode(GL_ETC1_RGB8_OES, 8, 4, &[0; 8]).unwrap_err().kind, ErrorKind::InvalidArgument);
+        assert_eq!(decode(GL_COMPRESSED_RGBA_S3TC_DXT1_EXT, 4, 4, &[0; 8]).unwrap_err().kind, ErrorKind::Unsupported);
+        assert!(!can_decode(GL_RGBA8));
+    }
+}
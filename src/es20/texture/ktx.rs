@@ -0,0 +1,347 @@
+use types::*;
+use consts::*;
+use super::super::wrapper::{Error, ErrorKind};
+use super::{check_size, format_error, Level, Reader, TextureData, TextureKind};
+
+// -------------------------------------------------------------------------------------------------
+// LOADING
+// -------------------------------------------------------------------------------------------------
+
+const KTX1_IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+const KTX2_IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// Parses a KTX 1.1 or KTX2 file.
+///
+/// 2D textures and cube maps are supported; array and 3D textures as well as KTX2
+/// supercompression are rejected with `ErrorKind::Unsupported`.
+pub fn load(data: &[u8]) -> Result<TextureData, Error> {
+    if data.starts_with(&KTX1_IDENTIFIER) {
+        load_ktx1(data)
+    } else if data.starts_with(&KTX2_IDENTIFIER) {
+        load_ktx2(data)
+    } else {
+        Err(format_error("not a KTX file"))
+    }
+}
+
+fn load_ktx1(data: &[u8]) -> Result<TextureData, Error> {
+    let mut reader = Reader::new(data);
+    reader.skip(KTX1_IDENTIFIER.len())?;
+
+    reader.big_endian = match reader.u32()? {
+        0x0403_0201 => false,
+        0x0102_0304 => true,
+        other => return Err(format_error(format!("invalid KTX endianness marker 0x{:08x}", other))),
+    };
+
+    let type_ = reader.u32()?;
+    let type_size = reader.u32()? as usize;
+    let format = reader.u32()?;
+    let internal_format = reader.u32()?;
+    let _base_internal_format = reader.u32()?;
+    let width = reader.u32()?;
+    let height = reader.u32()?.max(1);
+    let depth = reader.u32()?;
+    let array_elements = reader.u32()?;
+    let faces = reader.u32()?;
+    let level_count = reader.u32()?;
+    let key_value_bytes = reader.u32()?;
+
+    let kind = texture_kind(depth, array_elements, faces)?;
+    check_size(width, height, level_count)?;
+    reader.skip(key_value_bytes as usize)?;
+
+    let mut levels = Vec::new();
+    for level in 0..level_count.max(1) {
+        // For cube maps the size is that of one face, otherwise of the whole level.
+        let image_size = reader.u32()? as usize;
+        let mut images = Vec::with_capacity(faces as usize);
+
+        for _ in 0..faces {
+            let mut image = reader.bytes(image_size)?.to_vec();
+            if reader.big_endian && type_size > 1 {
+                for chunk in image.chunks_mut(type_size) {
+                    chunk.reverse();
+                }
+            }
+
+            images.push(image);
+            reader.align(4);
+        }
+
+        levels.push(Level {
+            width: (width >> level).max(1),
+            height: (height >> level).max(1),
+            faces: images,
+        });
+    }
+
+    Ok(TextureData {
+        kind,
+        internal_format,
+        format: if type_ == 0 { 0 } else { format },
+        type_,
+        unpack_alignment: 4,
+        levels,
+        generate_mipmaps: level_count == 0,
+    })
+}
+
+fn load_ktx2(data: &[u8]) -> Result<TextureData, Error> {
+    let mut reader = Reader::new(data);
+    reader.skip(KTX2_IDENTIFIER.len())?;
+
+    let vk_format = reader.u32()?;
+    let _type_size = reader.u32()?;
+    let width = reader.u32()?;
+    let height = reader.u32()?.max(1);
+    let depth = reader.u32()?;
+    let layers = reader.u32()?;
+    let faces = reader.u32()?;
+    let level_count = reader.u32()?;
+    let supercompression = reader.u32()?;
+
+    if supercompression != 0 {
+        return Err(Error::new(ErrorKind::Unsupported, format!(
+            "KTX2 supercompression scheme {} is not supported", supercompression)));
+    }
+
+    let kind = texture_kind(depth, layers, faces)?;
+    check_size(width, height, level_count)?;
+    let (internal_format, format, type_) = gl_format(vk_format).ok_or_else(|| {
+        Error::new(ErrorKind::Unsupported, format!("KTX2 vkFormat {} has no GL ES equivalent", vk_format))
+    })?;
+
+    // Data format descriptor, key/value data and supercompression global data are not needed.
+    reader.skip(4 * 4 + 8 * 2)?;
+
+    let mut levels = Vec::new();
+    for level in 0..level_count.max(1) {
+        let offset = reader.u64()? as usize;
+        let length = reader.u64()? as usize;
+        let _uncompressed_length = reader.u64()?;
+
+        let bytes = offset.checked_add(length)
+            .and_then(|end| data.get(offset..end))
+            .ok_or_else(|| format_error(format!("KTX2 level {} lies outside the file", level)))?;
+
+        if length == 0 || !length.is_multiple_of(faces as usize) {
+            return Err(format_error(format!("KTX2 level {} does not divide into {} faces", level, faces)));
+        }
+
+        levels.push(Level {
+            width: (width >> level).max(1),
+            height: (height >> level).max(1),
+            faces: bytes.chunks(length / faces as usize).map(|face| face.to_vec()).collect(),
+        });
+    }
+
+    Ok(TextureData {
+        kind,
+        internal_format,
+        format,
+        type_,
+        unpack_alignment: 1,
+        levels,
+        generate_mipmaps: level_count == 0,
+    })
+}
+
+fn texture_kind(depth: u32, layers: u32, faces: u32) -> Result<TextureKind, Error> {
+    if depth > 1 || layers > 1 {
+        return Err(Error::new(ErrorKind::Unsupported, "array and 3D textures are not supported"));
+    }
+
+    match faces {
+        1 => Ok(TextureKind::Texture2D),
+        6 => Ok(TextureKind::CubeMap),
+        _ => Err(format_error(format!("a texture has 1 or 6 faces, not {}", faces))),
+    }
+}
+
+// Maps a VkFormat to (internal format, format, type). Compressed formats have format and type 0.
+fn gl_format(vk_format: u32) -> Option<(GLenum, GLenum, GLenum)> {
+    let compressed = |internal_format| Some((internal_format, 0, 0));
+
+    match vk_format {
+        2 => Some((GL_RGBA4, GL_RGBA, GL_UNSIGNED_SHORT_4_4_4_4)),
+        4 => Some((GL_RGB565, GL_RGB, GL_UNSIGNED_SHORT_5_6_5)),
+        6 => Some((GL_RGB5_A1, GL_RGBA, GL_UNSIGNED_SHORT_5_5_5_1)),
+        9 => Some((GL_R8, GL_RED, GL_UNSIGNED_BYTE)),
+        16 => Some((GL_RG8, GL_RG, GL_UNSIGNED_BYTE)),
+        23 => Some((GL_RGB8, GL_RGB, GL_UNSIGNED_BYTE)),
+        29 => Some((GL_SRGB8, GL_RGB, GL_UNSIGNED_BYTE)),
+        37 => Some((GL_RGBA8, GL_RGBA, GL_UNSIGNED_BYTE)),
+        43 => Some((GL_SRGB8_ALPHA8, GL_RGBA, GL_UNSIGNED_BYTE)),
+        64 => Some((GL_RGB10_A2, GL_RGBA, GL_UNSIGNED_INT_2_10_10_10_REV)),
+        76 => Some((GL_R16F, GL_RED, GL_HALF_FLOAT)),
+        83 => Some((GL_RG16F, GL_RG, GL_HALF_FLOAT)),
+        97 => Some((GL_RGBA16F, GL_RGBA, GL_HALF_FLOAT)),
+        100 => Some((GL_R32F, GL_RED, GL_FLOAT)),
+        103 => Some((GL_RG32F, GL_RG, GL_FLOAT)),
+        109 => Some((GL_RGBA32F, GL_RGBA, GL_FLOAT)),
+        122 => Some((GL_R11F_G11F_B10F, GL_RGB, GL_UNSIGNED_INT_10F_11F_11F_REV)),
+        123 => Some((GL_RGB9_E5, GL_RGB, GL_UNSIGNED_INT_5_9_9_9_REV)),
+        131 => compressed(GL_COMPRESSED_RGB_S3TC_DXT1_EXT),
+        133 => compressed(GL_COMPRESSED_RGBA_S3TC_DXT1_EXT),
+        135 => compressed(GL_COMPRESSED_RGBA_S3TC_DXT3_EXT),
+        137 => compressed(GL_COMPRESSED_RGBA_S3TC_DXT5_EXT),
+        147 => compressed(GL_COMPRESSED_RGB8_ETC2),
+        148 => compressed(GL_COMPRESSED_SRGB8_ETC2),
+        149 => compressed(GL_COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2),
+        150 => compressed(GL_COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2),
+        151 => compressed(GL_COMPRESSED_RGBA8_ETC2_EAC),
+        152 => compressed(GL_COMPRESSED_SRGB8_ALPHA8_ETC2_EAC),
+        153 => compressed(GL_COMPRESSED_R11_EAC),
+        154 => compressed(GL_COMPRESSED_SIGNED_R11_EAC),
+        155 => compressed(GL_COMPRESSED_RG11_EAC),
+        156 => compressed(GL_COMPRESSED_SIGNED_RG11_EAC),
+        157..=184 => {
+            // VK_FORMAT_ASTC_4x4_UNORM_BLOCK .. VK_FORMAT_ASTC_12x12_SRGB_BLOCK, UNORM/SRGB pairs in
+            // the same block size order as the GL enums.
+            let index = (vk_format - 157) / 2;
+            if (vk_format - 157).is_multiple_of(2) {
+                compressed(GL_COMPRESSED_RGBA_ASTC_4x4 + index)
+            } else {
+                compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4x4 + index)
+            }
+        }
+        1_000_054_000 => compressed(GL_COMPRESSED_RGBA_PVRTC_2BPPV1_IMG),
+        1_000_054_001 => compressed(GL_COMPRESSED_RGBA_PVRTC_4BPPV1_IMG),
+        _ => None,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use es20::backend::Backend;
+    use es20::call::{Call, Value};
+    use es20::software::SoftwareBackend;
+    use es20::wrapper::{Interceptor, Wrapper};
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|value| value.to_le_bytes().to_vec()).collect()
+    }
+
+    // A little-endian RGBA8 2D texture with `levels` as the image data of each level.
+    fn ktx1(width: u32, height: u32, level_count: u32, levels: &[&[u8]]) -> Vec<u8> {
+        let mut file = KTX1_IDENTIFIER.to_vec();
+        file.extend(words(&[0x0403_0201, GL_UNSIGNED_BYTE, 1, GL_RGBA, GL_RGBA8, GL_RGBA, width, height, 0, 0, 1,
+                            level_count, 0]));
+        for level in levels {
+            file.extend(words(&[level.len() as u32]));
+            file.extend_from_slice(level);
+        }
+        file
+    }
+
+    // An RGBA8 2D texture, the levels stored one after another behind the level index.
+    fn ktx2(width: u32, height: u32, levels: &[&[u8]]) -> Vec<u8> {
+        let mut file = KTX2_IDENTIFIER.to_vec();
+        file.extend(words(&[37, 1, width, height, 0, 0, 1, levels.len() as u32, 0]));
+        file.extend(vec![0; 4 * 4 + 8 * 2]);
+
+        let mut offset = (file.len() + 24 * levels.len()) as u64;
+        for level in levels {
+            for value in &[offset, level.len() as u64, level.len() as u64] {
+                file.extend_from_slice(&value.to_le_bytes());
+            }
+            offset += level.len() as u64;
+        }
+        for level in levels {
+            file.extend_from_slice(level);
+        }
+        file
+    }
+
+    #[test]
+    fn loads_ktx1_mip_chain() {
+        let base: Vec<u8> = (0..16).collect();
+        let texture = load(&ktx1(2, 2, 2, &[&base, &[16, 17, 18, 19]])).unwrap();
+
+        assert_eq!(texture.kind, TextureKind::Texture2D);
+        assert_eq!((texture.internal_format, texture.format, texture.type_), (GL_RGBA8, GL_RGBA, GL_UNSIGNED_BYTE));
+        assert!(!texture.generate_mipmaps);
+        assert_eq!(texture.levels, vec![
+            Level { width: 2, height: 2, faces: vec![base] },
+            Level { width: 1, height: 1, faces: vec![vec![16, 17, 18, 19]] },
+        ]);
+    }
+
+    #[test]
+    fn ktx1_without_levels_asks_for_mipmaps() {
+        let texture = load(&ktx1(1, 1, 0, &[&[1, 2, 3, 4]])).unwrap();
+        assert!(texture.generate_mipmaps);
+        assert_eq!(texture.levels.len(), 1);
+    }
+
+    #[test]
+    fn loads_ktx2() {
+        let base = [7u8; 16];
+        let texture = load(&ktx2(2, 2, &[&base, &[8, 8, 8, 8]])).unwrap();
+
+        assert_eq!((texture.internal_format, texture.format, texture.type_), (GL_RGBA8, GL_RGBA, GL_UNSIGNED_BYTE));
+        assert_eq!(texture.levels[0], Level { width: 2, height: 2, faces: vec![base.to_vec()] });
+        assert_eq!(texture.levels[1], Level { width: 1, height: 1, faces: vec![vec![8, 8, 8, 8]] });
+    }
+
+    // Keeps the arguments of every glTexImage2D.
+    struct TexImages(Rc<RefCell<Vec<Vec<Value>>>>);
+
+    impl Interceptor for TexImages {
+        fn intercept(&mut self, call: &Call, _backend: &mut dyn Backend) -> Result<(), Error> {
+            if call.name == "glTexImage2D" {
+                self.0.borrow_mut().push(call.args.clone());
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn uploads_oes_half_float_data() {
+        // The RGBA8 file with the type, type size and internal format of GL_OES_texture_half_float.
+        let mut file = ktx1(1, 1, 1, &[&[0; 8]]);
+        file[16..24].copy_from_slice(&words(&[GL_HALF_FLOAT_OES, 2]));
+        file[28..32].copy_from_slice(&words(&[GL_RGBA]));
+        let texture = load(&file).unwrap();
+
+        let uploads = Rc::new(RefCell::new(Vec::new()));
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(1, 1)));
+        gl.add_interceptor(Box::new(TexImages(uploads.clone())));
+        texture.upload(&mut gl).unwrap();
+
+        let uploads = uploads.borrow();
+        assert_eq!(uploads.len(), 1);
+        assert_eq!(uploads[0][2..8], [Value::Enum(GL_RGBA), Value::Int(1), Value::Int(1), Value::Int(0),
+                                      Value::Enum(GL_RGBA), Value::Enum(GL_HALF_FLOAT_OES)]);
+    }
+
+    #[test]
+    fn truncated_files_are_format_errors() {
+        let file = ktx1(2, 2, 1, &[&[0; 16]]);
+        assert_eq!(load(&file[..file.len() - 1]).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(&file[..20]).unwrap_err().kind, ErrorKind::Format);
+
+        let file = ktx2(2, 2, &[&[0; 16]]);
+        assert_eq!(load(&file[..file.len() - 1]).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(b"not a texture").unwrap_err().kind, ErrorKind::Format);
+    }
+
+    #[test]
+    fn impossible_headers_are_format_errors() {
+        // More levels than halvings of the size, e.g. enough to overflow `width >> level`.
+        assert_eq!(load(&ktx1(2, 2, 3, &[])).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(&ktx1(4, 4, 40, &[])).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(&ktx1(u32::MAX, 1, 1, &[])).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(&ktx2(1 << 20, 1 << 20, &[&[0; 4]])).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(&ktx2(1, 1, &[&[]])).unwrap_err().kind, ErrorKind::Format);
+    }
+}
@@ -0,0 +1,510 @@
+use std::f32::consts::PI;
+
+use types::*;
+use enums::{PixelDataType, PixelFormat};
+use format::FormatInfo;
+use super::super::wrapper::{Error, ErrorKind};
+use super::{pixel_data_type, pixel_format, Level, TextureData};
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+/// The reconstruction filter used to shrink each level into the next.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MipFilter {
+    /// Averages the texels each destination texel covers. Fast, slightly blurry on odd sizes.
+    Box,
+    /// Tent filter over twice the footprint. Smoother, a little softer than `Box`.
+    Triangle,
+    /// Kaiser-windowed sinc over three texels on each side. Sharpest, may ring on hard edges.
+    Kaiser,
+}
+
+impl MipFilter {
+    fn support(self) -> f32 {
+        match self {
+            MipFilter::Box => 0.5,
+            MipFilter::Triangle => 1.0,
+            MipFilter::Kaiser => 3.0,
+        }
+    }
+
+    fn evaluate(self, x: f32) -> f32 {
+        let x = x.abs();
+        match self {
+            MipFilter::Box if x < 0.5 => 1.0,
+            // Texels exactly on the boundary are shared between both neighbours.
+            MipFilter::Box if x == 0.5 => 0.5,
+            MipFilter::Box => 0.0,
+            MipFilter::Triangle => (1.0 - x).max(0.0),
+            MipFilter::Kaiser if x >= 3.0 => 0.0,
+            MipFilter::Kaiser => {
+                const ALPHA: f32 = 4.0;
+                let window = bessel_i0(ALPHA * (1.0 - (x / 3.0) * (x / 3.0)).sqrt()) / bessel_i0(ALPHA);
+                sinc(x) * window
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MipmapOptions {
+    pub filter: MipFilter,
+    /// Color channels hold sRGB-encoded values and are averaged in linear space. Alpha is always
+    /// linear.
+    pub srgb: bool,
+    /// Rescales the alpha of each level so the fraction of texels above this cutoff matches the
+    /// base level, which keeps alpha-tested foliage and fences from thinning out with distance.
+    pub alpha_coverage: Option<f32>,
+    /// Sample across the opposite edge instead of clamping, for textures that use `REPEAT`.
+    pub wrap: bool,
+}
+
+impl Default for MipmapOptions {
+    fn default() -> MipmapOptions {
+        MipmapOptions {
+            filter: MipFilter::Box,
+            srgb: false,
+            alpha_coverage: None,
+            wrap: false,
+        }
+    }
+}
+
+// An image in floating point, one [r, g, b, a] per texel. Channels the format lacks stay unused.
+struct Image {
+    width: usize,
+    height: usize,
+    texels: Vec<[f32; 4]>,
+}
+
+// -------------------------------------------------------------------------------------------------
+// GENERATION
+// -------------------------------------------------------------------------------------------------
+
+/// Builds the complete mip chain of one image, down to 1x1.
+///
+/// `base` holds `width` x `height` texels of `format`/`type_` with rows padded to `alignment`; every
+/// generated level uses the same layout. The returned levels start with a copy of the base and
+/// have one face each.
+pub fn generate_chain(format: PixelFormat, type_: PixelDataType, width: u32, height: u32, alignment: usize,
+                      base: &[u8], options: &MipmapOptions) -> Result<Vec<Level>, Error> {
+    let info = FormatInfo::for_transfer(format as GLenum, type_ as GLenum).ok_or_else(|| {
+        Error::new(ErrorKind::Unsupported, format!("{:?}/{:?} is not a pixel transfer format", format, type_))
+    })?;
+    if width == 0 || height == 0 {
+        return Err(Error::new(ErrorKind::InvalidArgument, format!("a {}x{} image has no texels", width, height)));
+    }
+    if ![1, 2, 4, 8].contains(&alignment) {
+        return Err(Error::new(ErrorKind::InvalidArgument, format!("row alignment {} is not 1, 2, 4 or 8", alignment)));
+    }
+    if !type_.is_8_bit_or_packed() {
+        return Err(Error::new(ErrorKind::Unsupported, format!("{:?} texels can not be mipmapped on the CPU", type_)));
+    }
+
+    let (width, height) = (width as usize, height as usize);
+    let needed = info.image_size(width, height, alignment);
+    if base.len() < needed {
+        return Err(Error::new(ErrorKind::InvalidArgument, format!(
+            "a {}x{} {:?}/{:?} image needs {} bytes, got {}", width, height, format, type_, needed, base.len())));
+    }
+
+    let layout = Layout { format, type_, bytes_per_pixel: info.bytes_per_pixel as usize, alignment };
+    let mut image = layout.decode(width, height, base, options.srgb);
+    let coverage = options.alpha_coverage.filter(|_| layout.has_alpha()).map(|cutoff| (cutoff, coverage(&image, cutoff, 1.0)));
+
+    let mut levels = vec![Level {
+        width: width as u32,
+        height: height as u32,
+        faces: vec![base[..needed].to_vec()],
+    }];
+
+    while image.width > 1 || image.height > 1 {
+        image = downsample(&image, options.filter, options.wrap);
+
+        if let Some((cutoff, target)) = coverage {
+            preserve_coverage(&mut image, cutoff, target);
+        }
+
+        levels.push(Level {
+            width: image.width as u32,
+            height: image.height as u32,
+            faces: vec![layout.encode(&image, options.srgb)],
+        });
+    }
+
+    Ok(levels)
+}
+
+impl TextureData {
+    /// Replaces all levels with a CPU-generated chain built from the base level of each face,
+    /// instead of relying on `gl_generate_mipmap`.
+    pub fn generate_mipmaps_on_cpu(&mut self, options: &MipmapOptions) -> Result<(), Error> {
+        if self.is_compressed() {
+            return Err(Error::new(ErrorKind::Unsupported, "compressed textures can not be mipmapped on the CPU"));
+        }
+
+        let format = pixel_format(self.format)?;
+        let type_ = pixel_data_type(self.type_)?;
+        let base = match self.levels.first() {
+            Some(base) => base.clone(),
+            None => return Ok(()),
+        };
+
+        let mut levels: Vec<Level> = Vec::new();
+        for face in &base.faces {
+            let chain = generate_chain(format, type_, base.width, base.height, self.unpack_alignment.max(1) as usize,
+                                       face, options)?;
+
+            if levels.is_empty() {
+                levels = chain;
+            } else {
+                for (level, generated) in levels.iter_mut().zip(chain) {
+                    level.faces.extend(generated.faces);
+                }
+            }
+        }
+
+        self.levels = levels;
+        self.generate_mipmaps = false;
+        Ok(())
+    }
+}
+
+fn downsample(image: &Image, filter: MipFilter, wrap: bool) -> Image {
+    let width = (image.width / 2).max(1);
+    let height = (image.height / 2).max(1);
+
+    // Separable: rows first, then columns.
+    let columns = weights(image.width, width, filter, wrap);
+    let mut horizontal = vec![[0.0f32; 4]; width * image.height];
+    for y in 0..image.height {
+        for (x, taps) in columns.iter().enumerate() {
+            horizontal[y * width + x] = accumulate(taps.iter().map(|&(source, weight)| {
+                (image.texels[y * image.width + source], weight)
+            }));
+        }
+    }
+
+    let rows = weights(image.height, height, filter, wrap);
+    let mut texels = vec![[0.0f32; 4]; width * height];
+    for (y, taps) in rows.iter().enumerate() {
+        for x in 0..width {
+            texels[y * width + x] = accumulate(taps.iter().map(|&(source, weight)| {
+                (horizontal[source * width + x], weight)
+            }));
+        }
+    }
+
+    // Sharpening filters overshoot; keep values representable.
+    for texel in &mut texels {
+        for channel in texel.iter_mut() {
+            *channel = channel.clamp(0.0, 1.0);
+        }
+    }
+
+    Image { width, height, texels }
+}
+
+// For every destination texel, the source texels and normalized weights that contribute to it.
+fn weights(source: usize, destination: usize, filter: MipFilter, wrap: bool) -> Vec<Vec<(usize, f32)>> {
+    let scale = source as f32 / destination as f32;
+    let radius = filter.support() * scale;
+
+    (0..destination).map(|index| {
+        let center = (index as f32 + 0.5) * scale;
+        let first = (center - radius).floor() as isize;
+        let last = (center + radius).ceil() as isize;
+
+        let mut taps: Vec<(usize, f32)> = (first..=last)
+            .filter_map(|position| {
+                let weight = filter.evaluate((position as f32 + 0.5 - center) / scale);
+                if weight == 0.0 {
+                    return None;
+                }
+
+                let texel = if wrap {
+                    position.rem_euclid(source as isize)
+                } else {
+                    position.clamp(0, source as isize - 1)
+                };
+                Some((texel as usize, weight))
+            })
+            .collect();
+
+        let total: f32 = taps.iter().map(|&(_, weight)| weight).sum();
+        if total.abs() < f32::EPSILON {
+            taps = vec![((center as usize).min(source - 1), 1.0)];
+        } else {
+            for tap in &mut taps {
+                tap.1 /= total;
+            }
+        }
+        taps
+    }).collect()
+}
+
+fn accumulate<I: Iterator<Item = ([f32; 4], f32)>>(taps: I) -> [f32; 4] {
+    let mut sum = [0.0f32; 4];
+    for (texel, weight) in taps {
+        for (channel, value) in sum.iter_mut().zip(&texel) {
+            *channel += value * weight;
+        }
+    }
+    sum
+}
+
+fn coverage(image: &Image, cutoff: f32, scale: f32) -> f32 {
+    let covered = image.texels.iter().filter(|texel| (texel[3] * scale).min(1.0) > cutoff).count();
+    covered as f32 / image.texels.len() as f32
+}
+
+// Binary-searches the alpha scale whose coverage comes closest to the base level's.
+fn preserve_coverage(image: &mut Image, cutoff: f32, target: f32) {
+    let (mut low, mut high) = (0.0f32, 4.0f32);
+    let mut best = (1.0f32, (coverage(image, cutoff, 1.0) - target).abs());
+
+    for _ in 0..16 {
+        let scale = (low + high) / 2.0;
+        let current = coverage(image, cutoff, scale);
+
+        if (current - target).abs() < best.1 {
+            best = (scale, (current - target).abs());
+        }
+
+        if current < target {
+            low = scale;
+        } else {
+            high = scale;
+        }
+    }
+
+    for texel in &mut image.texels {
+        texel[3] = (texel[3] * best.0).min(1.0);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// PIXEL LAYOUT
+// -------------------------------------------------------------------------------------------------
+
+struct Layout {
+    format: PixelFormat,
+    type_: PixelDataType,
+    bytes_per_pixel: usize,
+    alignment: usize,
+}
+
+impl Layout {
+    // Which of r, g, b, a each stored component of an unsigned byte texel goes to.
+    fn channels(&self) -> &'static [usize] {
+        match self.format {
+            PixelFormat::ALPHA => &[3],
+            PixelFormat::LUMINANCE => &[0],
+            PixelFormat::LUMINANCE_ALPHA => &[0, 3],
+            PixelFormat::RED => &[0],
+            PixelFormat::RG => &[0, 1],
+            PixelFormat::RGB => &[0, 1, 2],
+            _ => &[0, 1, 2, 3],
+        }
+    }
+
+    fn has_alpha(&self) -> bool {
+        match self.type_ {
+            PixelDataType::UNSIGNED_BYTE => self.channels().contains(&3),
+            PixelDataType::UNSIGNED_SHORT_5_6_5 => false,
+            _ => true,
+        }
+    }
+
+    fn stride(&self, width: usize) -> usize {
+        (width * self.bytes_per_pixel).div_ceil(self.alignment) * self.alignment
+    }
+
+    fn decode(&self, width: usize, height: usize, data: &[u8], srgb: bool) -> Image {
+        let stride = self.stride(width);
+        let mut texels = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let offset = y * stride + x * self.bytes_per_pixel;
+                let mut texel = self.unpack(&data[offset..offset + self.bytes_per_pixel]);
+
+                if srgb {
+                    for channel in &mut texel[..3] {
+                        *channel = srgb_to_linear(*channel);
+                    }
+                }
+                texels.push(texel);
+            }
+        }
+
+        Image { width, height, texels }
+    }
+
+    fn encode(&self, image: &Image, srgb: bool) -> Vec<u8> {
+        let stride = self.stride(image.width);
+        let mut data = vec![0u8; stride * (image.height - 1) + image.width * self.bytes_per_pixel];
+
+        for y in 0..image.height {
+            for x in 0..image.width {
+                let mut texel = image.texels[y * image.width + x];
+                if srgb {
+                    for channel in &mut texel[..3] {
+                        *channel = linear_to_srgb(*channel);
+                    }
+                }
+
+                let offset = y * stride + x * self.bytes_per_pixel;
+                self.pack(texel, &mut data[offset..offset + self.bytes_per_pixel]);
+            }
+        }
+
+        data
+    }
+
+    fn unpack(&self, bytes: &[u8]) -> [f32; 4] {
+        let mut texel = [0.0, 0.0, 0.0, 1.0];
+
+        if self.type_ == PixelDataType::UNSIGNED_BYTE {
+            for (&channel, &byte) in self.channels().iter().zip(bytes) {
+                texel[channel] = f32::from(byte) / 255.0;
+            }
+            return texel;
+        }
+
+        let value = u16::from_ne_bytes([bytes[0], bytes[1]]);
+        for (channel, &(shift, bits)) in texel.iter_mut().zip(packed_fields(self.type_)) {
+            let max = (1u16 << bits) - 1;
+            *channel = f32::from((value >> shift) & max) / f32::from(max);
+        }
+        texel
+    }
+
+    fn pack(&self, texel: [f32; 4], bytes: &mut [u8]) {
+        if self.type_ == PixelDataType::UNSIGNED_BYTE {
+            for (&channel, byte) in self.channels().iter().zip(bytes.iter_mut()) {
+                *byte = quantize(texel[channel], 255) as u8;
+            }
+            return;
+        }
+
+        let mut value = 0u16;
+        for (&channel, &(shift, bits)) in texel.iter().zip(packed_fields(self.type_)) {
+            value |= quantize(channel, (1 << bits) - 1) << shift;
+        }
+        bytes.copy_from_slice(&value.to_ne_bytes());
+    }
+}
+
+// (shift, bits) of r, g, b and a in a packed 16 bit texel.
+fn packed_fields(type_: PixelDataType) -> &'static [(u16, u16)] {
+    match type_ {
+        PixelDataType::UNSIGNED_SHORT_5_6_5 => &[(11, 5), (5, 6), (0, 5)],
+        PixelDataType::UNSIGNED_SHORT_4_4_4_4 => &[(12, 4), (8, 4), (4, 4), (0, 4)],
+        PixelDataType::UNSIGNED_SHORT_5_5_5_1 => &[(11, 5), (6, 5), (1, 5), (0, 1)],
+        PixelDataType::UNSIGNED_BYTE => &[],
+        // generate_chain rejects the other types.
+        _ => unreachable!("{:?} texels are not 8 bit or packed", type_),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+fn quantize(value: f32, max: u16) -> u16 {
+    (value.clamp(0.0, 1.0) * f32::from(max) + 0.5) as u16
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 { value * 12.92 } else { 1.055 * value.powf(1.0 / 2.4) - 0.055 }
+}
+
+fn sinc(x: f32) -> f32 {
+    if x.abs() < 1e-6 { 1.0 } else { (PI * x).sin() / (PI * x) }
+}
+
+// Zeroth order modified Bessel function of the first kind, by its power series.
+fn bessel_i0(x: f32) -> f32 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half = x / 2.0;
+
+    for k in 1..32 {
+        term *= (half / k as f32) * (half / k as f32);
+        sum += term;
+        if term < sum * 1e-8 {
+            break;
+        }
+    }
+    sum
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgba_chain(width: u32, height: u32, base: &[u8], options: &MipmapOptions) -> Result<Vec<Level>, Error> {
+        generate_chain(PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE, width, height, 4, base, options)
+    }
+
+    #[test]
+    fn chain_halves_down_to_one_texel() {
+        let base = vec![255; 5 * 3 * 4];
+        let levels = rgba_chain(5, 3, &base, &MipmapOptions::default()).unwrap();
+
+        let sizes: Vec<_> = levels.iter().map(|level| (level.width, level.height)).collect();
+        assert_eq!(sizes, vec![(5, 3), (2, 1), (1, 1)]);
+        assert_eq!(levels[0].faces, vec![base]);
+        assert_eq!(levels[2].faces, vec![vec![255; 4]]);
+    }
+
+    #[test]
+    fn averages_in_linear_or_srgb_space() {
+        let checker = [0, 0, 0, 255, 255, 255, 255, 255, 255, 255, 255, 255, 0, 0, 0, 255];
+
+        let linear = rgba_chain(2, 2, &checker, &MipmapOptions::default()).unwrap();
+        assert_eq!(linear[1].faces[0], vec![128, 128, 128, 255]);
+
+        let srgb = rgba_chain(2, 2, &checker, &MipmapOptions { srgb: true, ..Default::default() }).unwrap();
+        assert_eq!(srgb[1].faces[0], vec![188, 188, 188, 255]);
+    }
+
+    #[test]
+    fn keeps_packed_colors() {
+        // Full red, no green and a third of blue.
+        let texel = ((31u16 << 11) | 10).to_ne_bytes();
+        let base: Vec<u8> = texel.iter().cycle().take(2 * 4 * 4).cloned().collect();
+        let levels = generate_chain(PixelFormat::RGB, PixelDataType::UNSIGNED_SHORT_5_6_5, 4, 4, 2, &base,
+                                    &MipmapOptions { filter: MipFilter::Kaiser, ..Default::default() }).unwrap();
+        assert_eq!(levels[2].faces[0], texel.to_vec());
+    }
+
+    #[test]
+    fn rejects_invalid_images() {
+        let options = MipmapOptions::default();
+        let kind = |result: Result<Vec<Level>, Error>| result.unwrap_err().kind;
+
+        assert_eq!(kind(rgba_chain(0, 4, &[], &options)), ErrorKind::InvalidArgument);
+        assert_eq!(kind(rgba_chain(4, 0, &[], &options)), ErrorKind::InvalidArgument);
+        assert_eq!(kind(rgba_chain(2, 2, &[0; 15], &options)), ErrorKind::InvalidArgument);
+        assert_eq!(kind(generate_chain(PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE, 2, 2, 3, &[0; 16], &options)),
+                   ErrorKind::InvalidArgument);
+        assert_eq!(kind(generate_chain(PixelFormat::RGBA, PixelDataType::FLOAT, 2, 2, 4, &[0; 64], &options)),
+                   ErrorKind::Unsupported);
+    }
+}
@@ -0,0 +1,310 @@
+use types::*;
+use consts::*;
+use enums::{PackParamType, PixelDataType, PixelFormat, StateType, TextureBindTarget, TextureTarget};
+use format::FormatInfo;
+use super::wrapper::{Error, ErrorKind, Wrapper};
+
+pub mod astc;
+pub mod builder;
+pub mod dds;
+pub mod decode;
+pub mod ktx;
+pub mod mipmap;
+pub mod pvr;
+
+// Larger than `MAX_TEXTURE_SIZE` of any device, and small enough that no image size overflows.
+const MAX_DIMENSION: u32 = 1 << 16;
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TextureKind {
+    Texture2D,
+    CubeMap,
+}
+
+impl TextureKind {
+    pub fn bind_target(self) -> TextureBindTarget {
+        match self {
+            TextureKind::Texture2D => TextureBindTarget::TEXTURE_2D,
+            TextureKind::CubeMap => TextureBindTarget::TEXTURE_CUBE_MAP,
+        }
+    }
+
+    /// The image targets of the texture, in the order faces are stored in `Level::faces`.
+    pub fn image_targets(self) -> &'static [TextureTarget] {
+        match self {
+            TextureKind::Texture2D => &[TextureTarget::TEXTURE_2D],
+            TextureKind::CubeMap => &CUBE_FACES,
+        }
+    }
+}
+
+const CUBE_FACES: [TextureTarget; 6] = [
+    TextureTarget::TEXTURE_CUBE_MAP_POSITIVE_X,
+    TextureTarget::TEXTURE_CUBE_MAP_NEGATIVE_X,
+    TextureTarget::TEXTURE_CUBE_MAP_POSITIVE_Y,
+    TextureTarget::TEXTURE_CUBE_MAP_NEGATIVE_Y,
+    TextureTarget::TEXTURE_CUBE_MAP_POSITIVE_Z,
+    TextureTarget::TEXTURE_CUBE_MAP_NEGATIVE_Z,
+];
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// One mip level. `faces` holds one image for 2D textures and six for cube maps, in
+/// `TEXTURE_CUBE_MAP_POSITIVE_X`, `NEGATIVE_X`, ... `NEGATIVE_Z` order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    pub width: u32,
+    pub height: u32,
+    pub faces: Vec<Vec<u8>>,
+}
+
+/// A texture loaded from a container file, ready to be uploaded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextureData {
+    pub kind: TextureKind,
+    pub internal_format: GLenum,
+    /// Client format and type of uncompressed data. Both are 0 for compressed formats.
+    pub format: GLenum,
+    pub type_: GLenum,
+    /// Row alignment of uncompressed data, as expected by `GL_UNPACK_ALIGNMENT`.
+    pub unpack_alignment: i32,
+    pub levels: Vec<Level>,
+    /// The file only holds the base level and asks for the rest to be generated.
+    pub generate_mipmaps: bool,
+}
+
+impl TextureData {
+    pub fn is_compressed(&self) -> bool {
+        self.type_ == 0
+    }
+
+    pub fn width(&self) -> u32 {
+        self.levels.first().map_or(0, |level| level.width)
+    }
+
+    pub fn height(&self) -> u32 {
+        self.levels.first().map_or(0, |level| level.height)
+    }
+
+    /// Uploads every level and face into the texture bound to `kind.bind_target()`.
+    ///
+    /// Compressed formats are checked against `COMPRESSED_TEXTURE_FORMATS` first; ones the
+    /// device lacks are decoded to RGBA8 on the CPU if `decode::can_decode` allows it.
+    /// Uncompressed data is specified with its sized internal format on ES 3.0. ES 2.0 only takes
+    /// unsized internal formats, so there sized formats are specified with the unsized format of
+    /// the same client data, and ones without such a format are `ErrorKind::Unsupported`.
+    ///
+    /// `GL_UNPACK_ALIGNMENT` is restored afterwards.
+    pub fn upload(&self, gl: &mut Wrapper) -> Result<(), Error> {
+        let alignment = gl.gl_get_integerv(StateType::UNPACK_ALIGNMENT)?;
+        let result = self.upload_images(gl);
+        gl.gl_pixel_storei(PackParamType::UNPACK_ALIGNMENT, alignment)?;
+        result?;
+
+        if self.generate_mipmaps {
+            gl.gl_generate_mipmap(self.kind.bind_target())?;
+        }
+
+        Ok(())
+    }
+
+    fn upload_images(&self, gl: &mut Wrapper) -> Result<(), Error> {
+        if self.is_compressed() {
+            let supported = compressed_texture_formats(gl)?;
+            if supported.contains(&self.internal_format) {
+                for (index, level) in self.levels.iter().enumerate() {
+                    for (face, &target) in level.faces.iter().zip(self.kind.image_targets()) {
+                        gl.gl_compressed_tex_image_2d(target, index as i32, self.internal_format, level.width,
+                                                      level.height, 0, face.len() as u32, face)?;
+                    }
+                }
+            } else if decode::can_decode(self.internal_format) {
+                gl.gl_pixel_storei(PackParamType::UNPACK_ALIGNMENT, 4)?;
+
+                for (index, level) in self.levels.iter().enumerate() {
+                    for (face, &target) in level.faces.iter().zip(self.kind.image_targets()) {
+                        let pixels = decode::decode(self.internal_format, level.width, level.height, face)?;
+                        gl.gl_tex_image_2d(target, index as i32, GL_RGBA as GLint, level.width as i32,
+                                           level.height as i32, 0, PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE,
+                                           &pixels)?;
+                    }
+                }
+            } else {
+                return Err(Error::new(ErrorKind::Unsupported, format!(
+                    "compressed format {} is not in COMPRESSED_TEXTURE_FORMATS", format_name(self.internal_format))));
+            }
+        } else {
+            let info = FormatInfo::lookup(self.internal_format, self.format, self.type_).ok_or_else(|| {
+                Error::new(ErrorKind::Unsupported, format!("{} can not be specified with {} and {}",
+                    format_name(self.internal_format), format_name(self.format), format_name(self.type_)))
+            })?;
+            let format = pixel_format(info.format)?;
+            let type_ = pixel_data_type(info.type_)?;
+
+            let mut internal_format = info.internal_format;
+            if internal_format != info.format && gl.es_major_version()? < 3 {
+                internal_format = match FormatInfo::lookup(info.format, info.format, info.type_) {
+                    Some(unsized_info) => unsized_info.internal_format,
+                    None => return Err(Error::new(ErrorKind::Unsupported, format!(
+                        "{} needs OpenGL ES 3.0", format_name(self.internal_format)))),
+                };
+            }
+
+            gl.gl_pixel_storei(PackParamType::UNPACK_ALIGNMENT, self.unpack_alignment)?;
+
+            for (index, level) in self.levels.iter().enumerate() {
+                for (face, &target) in level.faces.iter().zip(self.kind.image_targets()) {
+                    gl.gl_tex_image_2d(target, index as i32, internal_format as GLint, level.width as i32,
+                                       level.height as i32, 0, format, type_, face)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The compressed formats the device accepts for `gl_compressed_tex_image_2d`.
+pub fn compressed_texture_formats(gl: &mut Wrapper) -> Result<Vec<GLenum>, Error> {
+    let count = gl.gl_get_integerv(StateType::NUM_COMPRESSED_TEXTURE_FORMATS)?;
+    let formats = gl.gl_get_integerv_array(StateType::COMPRESSED_TEXTURE_FORMATS, count.max(0) as usize)?;
+
+    Ok(formats.into_iter().map(|format| format as GLenum).collect())
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+fn pixel_format(format: GLenum) -> Result<PixelFormat, Error> {
+    match format {
+        GL_ALPHA => Ok(PixelFormat::ALPHA),
+        GL_RGB => Ok(PixelFormat::RGB),
+        GL_RGBA => Ok(PixelFormat::RGBA),
+        GL_LUMINANCE => Ok(PixelFormat::LUMINANCE),
+        GL_LUMINANCE_ALPHA => Ok(PixelFormat::LUMINANCE_ALPHA),
+        GL_RED => Ok(PixelFormat::RED),
+        GL_RG => Ok(PixelFormat::RG),
+        _ => Err(Error::new(ErrorKind::Unsupported, format!("pixel format {} can not be uploaded", format_name(format)))),
+    }
+}
+
+fn pixel_data_type(type_: GLenum) -> Result<PixelDataType, Error> {
+    match type_ {
+        GL_UNSIGNED_BYTE => Ok(PixelDataType::UNSIGNED_BYTE),
+        GL_UNSIGNED_SHORT_5_6_5 => Ok(PixelDataType::UNSIGNED_SHORT_5_6_5),
+        GL_UNSIGNED_SHORT_4_4_4_4 => Ok(PixelDataType::UNSIGNED_SHORT_4_4_4_4),
+        GL_UNSIGNED_SHORT_5_5_5_1 => Ok(PixelDataType::UNSIGNED_SHORT_5_5_5_1),
+        GL_HALF_FLOAT_OES => Ok(PixelDataType::HALF_FLOAT_OES),
+        GL_HALF_FLOAT => Ok(PixelDataType::HALF_FLOAT),
+        GL_FLOAT => Ok(PixelDataType::FLOAT),
+        GL_UNSIGNED_INT_2_10_10_10_REV => Ok(PixelDataType::UNSIGNED_INT_2_10_10_10_REV),
+        GL_UNSIGNED_INT_10F_11F_11F_REV => Ok(PixelDataType::UNSIGNED_INT_10F_11F_11F_REV),
+        GL_UNSIGNED_INT_5_9_9_9_REV => Ok(PixelDataType::UNSIGNED_INT_5_9_9_9_REV),
+        _ => Err(Error::new(ErrorKind::Unsupported, format!("pixel type {} can not be uploaded", format_name(type_)))),
+    }
+}
+
+// Size of one tightly packed face image, for containers that do not store it.
+fn image_size(internal_format: GLenum, format: GLenum, type_: GLenum, width: u32, height: u32) -> Result<usize, Error> {
+    let info = if type_ == 0 {
+        FormatInfo::for_internal_format(internal_format)
+    } else {
+        FormatInfo::for_transfer(format, type_)
+    };
+
+    match info {
+        Some(info) => Ok(info.image_size(width as usize, height as usize, 1)),
+        None => Err(Error::new(ErrorKind::Unsupported, format!("unknown format {}", format_name(internal_format)))),
+    }
+}
+
+// Fails unless a `width` x `height` texture can exist and have `level_count` mip levels, at most
+// one more than the number of times its larger dimension halves. Containers are untrusted, and
+// sizes beyond this would overflow image sizes or shifts.
+fn check_size(width: u32, height: u32, level_count: u32) -> Result<(), Error> {
+    if width > MAX_DIMENSION || height > MAX_DIMENSION {
+        return Err(format_error(format!("a {}x{} texture is larger than any GL ES texture", width, height)));
+    }
+
+    let max_levels = 32 - width.max(height).max(1).leading_zeros();
+    if level_count > max_levels {
+        return Err(format_error(format!(
+            "a {}x{} texture has at most {} mip levels, not {}", width, height, max_levels, level_count)));
+    }
+    Ok(())
+}
+
+fn format_name(value: GLenum) -> String {
+    match enum_name(value) {
+        Some(name) => name.to_string(),
+        None => format!("0x{:x}", value),
+    }
+}
+
+fn format_error<S: Into<String>>(message: S) -> Error {
+    Error::new(ErrorKind::Format, message)
+}
+
+/// A cursor over a container file.
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Reader<'a> {
+        Reader {
+            data,
+            position: 0,
+            big_endian: false,
+        }
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], Error> {
+        let end = self.position.checked_add(count)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| format_error(format!("file is truncated at byte {}", self.data.len())))?;
+
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    fn skip(&mut self, count: usize) -> Result<(), Error> {
+        self.bytes(count).map(|_| ())
+    }
+
+    // Trailing padding after the last image is optional, so this never fails.
+    fn align(&mut self, alignment: usize) {
+        let padding = (alignment - self.position % alignment) % alignment;
+        self.position = (self.position + padding).min(self.data.len());
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.bytes(4)?);
+        Ok(if self.big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.bytes(8)?);
+        Ok(if self.big_endian { u64::from_be_bytes(bytes) } else { u64::from_le_bytes(bytes) })
+    }
+}
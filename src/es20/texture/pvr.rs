@@ -0,0 +1,200 @@
+use types::*;
+use consts::*;
+use super::super::wrapper::{Error, ErrorKind};
+use super::{check_size, format_error, image_size, Level, Reader, TextureData, TextureKind};
+
+// -------------------------------------------------------------------------------------------------
+// LOADING
+// -------------------------------------------------------------------------------------------------
+
+const VERSION: u32 = 0x0352_5650;
+const VERSION_SWAPPED: u32 = 0x5056_5203;
+
+const COLOR_SPACE_SRGB: u32 = 1;
+
+/// Parses a PVR v3 file (the format written by PVRTexTool).
+///
+/// Compressed payloads in PVRTC, ETC1, ETC2/EAC, S3TC and ASTC as well as the common 8 bit and
+/// packed 16 bit uncompressed layouts are supported. Texture arrays and 3D textures are not.
+pub fn load(data: &[u8]) -> Result<TextureData, Error> {
+    let mut reader = Reader::new(data);
+
+    reader.big_endian = match reader.u32()? {
+        VERSION => false,
+        VERSION_SWAPPED => true,
+        _ => return Err(format_error("not a PVR v3 file")),
+    };
+
+    let _flags = reader.u32()?;
+    let pixel_format = reader.u64()?;
+    let color_space = reader.u32()?;
+    let _channel_type = reader.u32()?;
+    let height = reader.u32()?;
+    let width = reader.u32()?;
+    let depth = reader.u32()?;
+    let surfaces = reader.u32()?;
+    let faces = reader.u32()?;
+    let level_count = reader.u32()?;
+    let metadata_bytes = reader.u32()?;
+
+    if depth > 1 || surfaces > 1 {
+        return Err(Error::new(ErrorKind::Unsupported, "array and 3D textures are not supported"));
+    }
+
+    let kind = match faces {
+        1 => TextureKind::Texture2D,
+        6 => TextureKind::CubeMap,
+        _ => return Err(format_error(format!("a texture has 1 or 6 faces, not {}", faces))),
+    };
+    check_size(width, height, level_count)?;
+
+    let srgb = color_space == COLOR_SPACE_SRGB;
+    let (internal_format, format, type_) = if pixel_format >> 32 == 0 {
+        let internal_format = compressed_format(pixel_format as u32, srgb).ok_or_else(|| {
+            Error::new(ErrorKind::Unsupported, format!("PVR pixel format {} is not supported", pixel_format))
+        })?;
+        (internal_format, 0, 0)
+    } else {
+        uncompressed_format(pixel_format).ok_or_else(|| {
+            Error::new(ErrorKind::Unsupported, format!("PVR channel layout 0x{:016x} is not supported", pixel_format))
+        })?
+    };
+
+    reader.skip(metadata_bytes as usize)?;
+
+    // Levels are stored largest first, each with all of its faces.
+    let mut levels = Vec::new();
+    for level in 0..level_count.max(1) {
+        let level_width = (width >> level).max(1);
+        let level_height = (height >> level).max(1);
+        let size = image_size(internal_format, format, type_, level_width, level_height)?;
+
+        let mut images = Vec::with_capacity(faces as usize);
+        for _ in 0..faces {
+            images.push(reader.bytes(size)?.to_vec());
+        }
+
+        levels.push(Level {
+            width: level_width,
+            height: level_height,
+            faces: images,
+        });
+    }
+
+    Ok(TextureData {
+        kind,
+        internal_format,
+        format,
+        type_,
+        unpack_alignment: 1,
+        levels,
+        generate_mipmaps: false,
+    })
+}
+
+fn compressed_format(format: u32, srgb: bool) -> Option<GLenum> {
+    let (linear, srgb_format) = match format {
+        0 => (GL_COMPRESSED_RGB_PVRTC_2BPPV1_IMG, None),
+        1 => (GL_COMPRESSED_RGBA_PVRTC_2BPPV1_IMG, None),
+        2 => (GL_COMPRESSED_RGB_PVRTC_4BPPV1_IMG, None),
+        3 => (GL_COMPRESSED_RGBA_PVRTC_4BPPV1_IMG, None),
+        6 => (GL_ETC1_RGB8_OES, None),
+        7 => (GL_COMPRESSED_RGBA_S3TC_DXT1_EXT, None),
+        9 => (GL_COMPRESSED_RGBA_S3TC_DXT3_EXT, None),
+        11 => (GL_COMPRESSED_RGBA_S3TC_DXT5_EXT, None),
+        22 => (GL_COMPRESSED_RGB8_ETC2, Some(GL_COMPRESSED_SRGB8_ETC2)),
+        23 => (GL_COMPRESSED_RGBA8_ETC2_EAC, Some(GL_COMPRESSED_SRGB8_ALPHA8_ETC2_EAC)),
+        24 => (GL_COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2, Some(GL_COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2)),
+        25 => (GL_COMPRESSED_R11_EAC, None),
+        26 => (GL_COMPRESSED_RG11_EAC, None),
+        // ASTC_4x4 .. ASTC_12x12 follow the order of the GL enums.
+        27..=40 => (GL_COMPRESSED_RGBA_ASTC_4x4 + (format - 27), Some(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4x4 + (format - 27))),
+        _ => return None,
+    };
+
+    Some(if srgb { srgb_format.unwrap_or(linear) } else { linear })
+}
+
+// The low four bytes name the channels in order, the high four bytes give their bit counts.
+fn uncompressed_format(pixel_format: u64) -> Option<(GLenum, GLenum, GLenum)> {
+    let bytes = pixel_format.to_le_bytes();
+    let (channels, bits) = (&bytes[..4], &bytes[4..]);
+
+    match (channels, bits) {
+        (b"rgba", [8, 8, 8, 8]) => Some((GL_RGBA, GL_RGBA, GL_UNSIGNED_BYTE)),
+        (b"rgba", [4, 4, 4, 4]) => Some((GL_RGBA, GL_RGBA, GL_UNSIGNED_SHORT_4_4_4_4)),
+        (b"rgba", [5, 5, 5, 1]) => Some((GL_RGBA, GL_RGBA, GL_UNSIGNED_SHORT_5_5_5_1)),
+        (b"rgb\0", [8, 8, 8, 0]) => Some((GL_RGB, GL_RGB, GL_UNSIGNED_BYTE)),
+        (b"rgb\0", [5, 6, 5, 0]) => Some((GL_RGB, GL_RGB, GL_UNSIGNED_SHORT_5_6_5)),
+        (b"la\0\0", [8, 8, 0, 0]) => Some((GL_LUMINANCE_ALPHA, GL_LUMINANCE_ALPHA, GL_UNSIGNED_BYTE)),
+        (b"l\0\0\0", [8, 0, 0, 0]) => Some((GL_LUMINANCE, GL_LUMINANCE, GL_UNSIGNED_BYTE)),
+        (b"a\0\0\0", [8, 0, 0, 0]) => Some((GL_ALPHA, GL_ALPHA, GL_UNSIGNED_BYTE)),
+        _ => None,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Channels "rgba" of 8 bits each.
+    const RGBA8888: u64 = 0x0808_0808_6162_6772;
+    const ETC1: u64 = 6;
+
+    fn pvr(pixel_format: u64, width: u32, height: u32, faces: u32, level_count: u32, data: &[u8]) -> Vec<u8> {
+        let mut file = Vec::new();
+        file.extend_from_slice(&VERSION.to_le_bytes());
+        file.extend_from_slice(&0u32.to_le_bytes());
+        file.extend_from_slice(&pixel_format.to_le_bytes());
+        for word in &[0, 0, height, width, 1, 1, faces, level_count, 4] {
+            file.extend_from_slice(&word.to_le_bytes());
+        }
+        // Metadata the loader skips.
+        file.extend_from_slice(&[0xEE; 4]);
+        file.extend_from_slice(data);
+        file
+    }
+
+    #[test]
+    fn loads_uncompressed_levels() {
+        let data: Vec<u8> = (0..20).collect();
+        let texture = load(&pvr(RGBA8888, 2, 2, 1, 2, &data)).unwrap();
+
+        assert_eq!((texture.internal_format, texture.format, texture.type_), (GL_RGBA, GL_RGBA, GL_UNSIGNED_BYTE));
+        assert_eq!(texture.levels, vec![
+            Level { width: 2, height: 2, faces: vec![data[..16].to_vec()] },
+            Level { width: 1, height: 1, faces: vec![data[16..].to_vec()] },
+        ]);
+    }
+
+    #[test]
+    fn loads_compressed_cube_map() {
+        let data: Vec<u8> = (0..48).collect();
+        let texture = load(&pvr(ETC1, 4, 4, 6, 1, &data)).unwrap();
+
+        assert_eq!(texture.kind, TextureKind::CubeMap);
+        assert_eq!(texture.internal_format, GL_ETC1_RGB8_OES);
+        assert_eq!(texture.levels[0].faces.len(), 6);
+        assert_eq!(texture.levels[0].faces[5], data[40..].to_vec());
+    }
+
+    #[test]
+    fn truncated_files_are_format_errors() {
+        let file = pvr(RGBA8888, 2, 2, 1, 1, &[0; 16]);
+        assert_eq!(load(&file[..file.len() - 1]).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(&file[..30]).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(b"PVR").unwrap_err().kind, ErrorKind::Format);
+    }
+
+    #[test]
+    fn impossible_headers_are_format_errors() {
+        assert_eq!(load(&pvr(RGBA8888, 2, 2, 1, 3, &[0; 20])).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(&pvr(RGBA8888, 4, 4, 1, 40, &[])).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(&pvr(RGBA8888, u32::MAX, 1, 1, 1, &[])).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(load(&pvr(RGBA8888, 1, 1, 2, 1, &[0; 8])).unwrap_err().kind, ErrorKind::Format);
+    }
+}
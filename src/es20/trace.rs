@@ -0,0 +1,1196 @@
+use std;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::ptr;
+
+use consts::*;
+use format::FormatInfo;
+use types::*;
+use super::backend::Backend;
+use super::call::{Call, ObjectKind, Value, CALL_NAMES};
+use super::wrapper::{Error, ErrorKind, Interceptor};
+
+// -------------------------------------------------------------------------------------------------
+// FORMAT
+// -------------------------------------------------------------------------------------------------
+//
+// A trace is a header followed by records, all little-endian:
+//
+//   header:  b"GLESTRC\0", u32 version, u32 call name count, call names
+//   call:    u8 TAG_CALL, u16 call id, u8 argument count, arguments, return value
+//   frame:   u8 TAG_FRAME, marks the end of a frame
+//
+// Every value is a one byte tag followed by its payload. Strings, blobs and arrays are prefixed
+// with a u32 element count.
+//
+// A call id is an index into the names of the header, which are written as strings without a
+// tag. The names are the writer's CALL_NAMES, so a reader with a different table still maps ids
+// to the right entry points.
+
+const MAGIC: &[u8; 8] = b"GLESTRC\0";
+const VERSION: u32 = 2;
+
+const TAG_CALL: u8 = 1;
+const TAG_FRAME: u8 = 2;
+
+const VALUE_VOID: u8 = 0;
+const VALUE_BOOL: u8 = 1;
+const VALUE_INT: u8 = 2;
+const VALUE_UINT: u8 = 3;
+const VALUE_ENUM: u8 = 4;
+const VALUE_BITFIELD: u8 = 5;
+const VALUE_FLOAT: u8 = 6;
+const VALUE_NAME: u8 = 7;
+const VALUE_NAMES: u8 = 8;
+const VALUE_LOCATION: u8 = 9;
+const VALUE_STR: u8 = 10;
+const VALUE_BYTES: u8 = 11;
+const VALUE_INTS: u8 = 12;
+const VALUE_FLOATS: u8 = 13;
+const VALUE_OFFSET: u8 = 14;
+
+fn kind_to_u8(kind: ObjectKind) -> u8 {
+    match kind {
+        ObjectKind::Buffer => 0,
+        ObjectKind::Framebuffer => 1,
+        ObjectKind::Renderbuffer => 2,
+        ObjectKind::Texture => 3,
+        ObjectKind::Program => 4,
+        ObjectKind::Shader => 5,
+        ObjectKind::VertexArray => 6,
+    }
+}
+
+fn kind_from_u8(kind: u8) -> Result<ObjectKind, Error> {
+    match kind {
+        0 => Ok(ObjectKind::Buffer),
+        1 => Ok(ObjectKind::Framebuffer),
+        2 => Ok(ObjectKind::Renderbuffer),
+        3 => Ok(ObjectKind::Texture),
+        4 => Ok(ObjectKind::Program),
+        5 => Ok(ObjectKind::Shader),
+        6 => Ok(ObjectKind::VertexArray),
+        _ => Err(Error::new(ErrorKind::Format, format!("unknown object kind {}", kind))),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// ENCODING
+// -------------------------------------------------------------------------------------------------
+
+fn write_u8<W: Write>(writer: &mut W, value: u8) -> std::io::Result<()> {
+    writer.write_all(&[value])
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: u32) -> std::io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_len<W: Write>(writer: &mut W, len: usize) -> std::io::Result<()> {
+    write_u32(writer, len as u32)
+}
+
+fn write_value<W: Write>(writer: &mut W, value: &Value) -> std::io::Result<()> {
+    match *value {
+        Value::Void => write_u8(writer, VALUE_VOID),
+        Value::Bool(b) => {
+            write_u8(writer, VALUE_BOOL)?;
+            write_u8(writer, b as u8)
+        }
+        Value::Int(i) => {
+            write_u8(writer, VALUE_INT)?;
+            writer.write_all(&i.to_le_bytes())
+        }
+        Value::Uint(u) => {
+            write_u8(writer, VALUE_UINT)?;
+            write_u32(writer, u)
+        }
+        Value::Enum(e) => {
+            write_u8(writer, VALUE_ENUM)?;
+            write_u32(writer, e)
+        }
+        Value::Bitfield(b) => {
+            write_u8(writer, VALUE_BITFIELD)?;
+            write_u32(writer, b)
+        }
+        Value::Float(f) => {
+            write_u8(writer, VALUE_FLOAT)?;
+            writer.write_all(&f.to_le_bytes())
+        }
+        Value::Name(kind, name) => {
+            write_u8(writer, VALUE_NAME)?;
+            write_u8(writer, kind_to_u8(kind))?;
+            write_u32(writer, name)
+        }
+        Value::Names(kind, ref names) => {
+            write_u8(writer, VALUE_NAMES)?;
+            write_u8(writer, kind_to_u8(kind))?;
+            write_len(writer, names.len())?;
+            for name in names {
+                write_u32(writer, *name)?;
+            }
+            Ok(())
+        }
+        Value::Location(l) => {
+            write_u8(writer, VALUE_LOCATION)?;
+            writer.write_all(&l.to_le_bytes())
+        }
+        Value::Str(ref s) => {
+            write_u8(writer, VALUE_STR)?;
+            write_len(writer, s.len())?;
+            writer.write_all(s.as_bytes())
+        }
+        Value::Bytes(ref bytes) => {
+            write_u8(writer, VALUE_BYTES)?;
+            write_len(writer, bytes.len())?;
+            writer.write_all(bytes)
+        }
+        Value::Ints(ref ints) => {
+            write_u8(writer, VALUE_INTS)?;
+            write_len(writer, ints.len())?;
+            for i in ints {
+                writer.write_all(&i.to_le_bytes())?;
+            }
+            Ok(())
+        }
+        Value::Floats(ref floats) => {
+            write_u8(writer, VALUE_FLOATS)?;
+            write_len(writer, floats.len())?;
+            for f in floats {
+                writer.write_all(&f.to_le_bytes())?;
+            }
+            Ok(())
+        }
+        Value::Offset(offset) => {
+            write_u8(writer, VALUE_OFFSET)?;
+            writer.write_all(&(offset as u64).to_le_bytes())
+        }
+    }
+}
+
+fn write_call<W: Write>(writer: &mut W, call: &Call) -> Result<(), Error> {
+    let id = CALL_NAMES.iter().position(|name| *name == call.name).ok_or_else(|| {
+        Error::new(ErrorKind::InvalidArgument, format!("{} has no id in the trace format", call.name))
+    })?;
+
+    write_u8(writer, TAG_CALL)?;
+    writer.write_all(&(id as u16).to_le_bytes())?;
+    write_u8(writer, call.args.len() as u8)?;
+    for arg in &call.args {
+        write_value(writer, arg)?;
+    }
+    write_value(writer, &call.ret)?;
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+// DECODING
+// -------------------------------------------------------------------------------------------------
+
+fn read_array<R: Read, const N: usize>(reader: &mut R) -> Result<[u8; N], Error> {
+    let mut bytes = [0u8; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> Result<u8, Error> {
+    Ok(read_array::<R, 1>(reader)?[0])
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, Error> {
+    Ok(u32::from_le_bytes(read_array(reader)?))
+}
+
+fn read_i32<R: Read>(reader: &mut R) -> Result<i32, Error> {
+    Ok(i32::from_le_bytes(read_array(reader)?))
+}
+
+fn read_f32<R: Read>(reader: &mut R) -> Result<f32, Error> {
+    Ok(f32::from_le_bytes(read_array(reader)?))
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> Result<Vec<u8>, Error> {
+    let len = read_u32(reader)? as usize;
+    let mut bytes = Vec::new();
+
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(Error::new(ErrorKind::Format, "trace ends inside a value"));
+    }
+
+    Ok(bytes)
+}
+
+fn read_value<R: Read>(reader: &mut R) -> Result<Value, Error> {
+    let tag = read_u8(reader)?;
+
+    Ok(match tag {
+        VALUE_VOID => Value::Void,
+        VALUE_BOOL => Value::Bool(read_u8(reader)? != 0),
+        VALUE_INT => Value::Int(read_i32(reader)?),
+        VALUE_UINT => Value::Uint(read_u32(reader)?),
+        VALUE_ENUM => Value::Enum(read_u32(reader)?),
+        VALUE_BITFIELD => Value::Bitfield(read_u32(reader)?),
+        VALUE_FLOAT => Value::Float(read_f32(reader)?),
+        VALUE_NAME => {
+            let kind = kind_from_u8(read_u8(reader)?)?;
+            Value::Name(kind, read_u32(reader)?)
+        }
+        VALUE_NAMES => {
+            let kind = kind_from_u8(read_u8(reader)?)?;
+            let len = read_u32(reader)?;
+            let mut names = Vec::new();
+            for _ in 0..len {
+                names.push(read_u32(reader)?);
+            }
+            Value::Names(kind, names)
+        }
+        VALUE_LOCATION => Value::Location(read_i32(reader)?),
+        VALUE_STR => {
+            let bytes = read_bytes(reader)?;
+            match String::from_utf8(bytes) {
+                Ok(s) => Value::Str(s),
+                Err(_) => return Err(Error::new(ErrorKind::Format, "string is not valid UTF-8")),
+            }
+        }
+        VALUE_BYTES => Value::Bytes(read_bytes(reader)?),
+        VALUE_INTS => {
+            let len = read_u32(reader)?;
+            let mut ints = Vec::new();
+            for _ in 0..len {
+                ints.push(read_i32(reader)?);
+            }
+            Value::Ints(ints)
+        }
+        VALUE_FLOATS => {
+            let len = read_u32(reader)?;
+            let mut floats = Vec::new();
+            for _ in 0..len {
+                floats.push(read_f32(reader)?);
+            }
+            Value::Floats(floats)
+        }
+        VALUE_OFFSET => Value::Offset(u64::from_le_bytes(read_array(reader)?) as usize),
+        _ => return Err(Error::new(ErrorKind::Format, format!("unknown value tag {}", tag))),
+    })
+}
+
+// -------------------------------------------------------------------------------------------------
+// CAPTURE
+// -------------------------------------------------------------------------------------------------
+
+/// Records every call made through a `Wrapper` into a binary trace.
+///
+/// Calls are written once they completed, so object names returned by `glGen*`/`glCreate*` end
+/// up in the trace. If a call can not be written, because writing fails or the trace format has no
+/// id for it, capturing stops and the error is returned from the next intercepted call.
+pub struct Capture<W: Write> {
+    writer: W,
+    failed: Option<Error>,
+    stopped: bool,
+}
+
+impl<W: Write> Capture<W> {
+    pub fn new(mut writer: W) -> Result<Capture<W>, Error> {
+        writer.write_all(MAGIC)?;
+        write_u32(&mut writer, VERSION)?;
+        write_len(&mut writer, CALL_NAMES.len())?;
+        for name in CALL_NAMES {
+            write_len(&mut writer, name.len())?;
+            writer.write_all(name.as_bytes())?;
+        }
+
+        Ok(Capture {
+            writer,
+            failed: None,
+            stopped: false,
+        })
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Interceptor for Capture<W> {
+    fn intercept(&mut self, _call: &Call, _backend: &mut dyn Backend) -> Result<(), Error> {
+        match self.failed.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    fn completed(&mut self, call: &Call, _backend: &mut dyn Backend) {
+        if self.stopped {
+            return;
+        }
+
+        if let Err(error) = write_call(&mut self.writer, call) {
+            self.failed = Some(error);
+            self.stopped = true;
+        }
+    }
+
+    fn end_frame(&mut self, _frame: u64) {
+        if self.stopped {
+            return;
+        }
+
+        if let Err(error) = write_u8(&mut self.writer, TAG_FRAME).and_then(|_| self.writer.flush()) {
+            self.failed = Some(Error::from(error));
+            self.stopped = true;
+        }
+    }
+}
+
+impl<W: Write> Drop for Capture<W> {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// REPLAY
+// -------------------------------------------------------------------------------------------------
+
+enum Record {
+    Call(Call),
+    Frame,
+}
+
+/// Reads a binary trace and re-issues its calls against a `Backend`.
+///
+/// Object names and uniform locations differ between the captured and the replaying context, so
+/// the replayer keeps a map from captured to replayed names, filled in by `glGen*`,
+/// `glCreate*` and `glGetUniformLocation`. Pure queries (`glGet*`, `glIs*`, `glReadPixels`, ...)
+/// are skipped.
+pub struct Replayer<R: Read> {
+    reader: R,
+    // The call names of the header by id, `None` for calls this version does not know.
+    calls: Vec<Option<&'static str>>,
+    names: HashMap<(ObjectKind, u32), u32>,
+    locations: HashMap<(u32, i32), i32>,
+    program: u32,
+    frame: u64,
+    // Client-side vertex arrays must stay alive until the draw call that reads them.
+    client_arrays: HashMap<u32, Vec<u8>>,
+}
+
+impl<R: Read> Replayer<R> {
+    pub fn new(mut reader: R) -> Result<Replayer<R>, Error> {
+        let magic: [u8; 8] = read_array(&mut reader)?;
+        if &magic != MAGIC {
+            return Err(Error::new(ErrorKind::Format, "not a GLES trace"));
+        }
+
+        let version = read_u32(&mut reader)?;
+        if version != VERSION {
+            return Err(Error::new(ErrorKind::Format,
+                                  format!("unsupported trace version {}", version)));
+        }
+
+        let count = read_u32(&mut reader)?;
+        if count > u32::from(u16::MAX) + 1 {
+            return Err(Error::new(ErrorKind::Format, format!("{} call names do not fit a u16 id", count)));
+        }
+        let mut calls = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let name = read_bytes(&mut reader)?;
+            calls.push(CALL_NAMES.iter().find(|known| known.as_bytes() == &name[..]).cloned());
+        }
+
+        Ok(Replayer {
+            reader,
+            calls,
+            names: HashMap::new(),
+            locations: HashMap::new(),
+            program: 0,
+            frame: 0,
+            client_arrays: HashMap::new(),
+        })
+    }
+
+    /// Returns the next call of the trace, or `None` at its end. Frame markers are skipped.
+    pub fn read_call(&mut self) -> Result<Option<Call>, Error> {
+        loop {
+            match self.read_record()? {
+                Some(Record::Call(call)) => return Ok(Some(call)),
+                Some(Record::Frame) => continue,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    fn read_record(&mut self) -> Result<Option<Record>, Error> {
+        let mut tag = [0u8; 1];
+        if self.reader.read(&mut tag)? == 0 {
+            return Ok(None);
+        }
+
+        match tag[0] {
+            TAG_CALL => {}
+            TAG_FRAME => {
+                self.frame += 1;
+                return Ok(Some(Record::Frame));
+            }
+            _ => return Err(Error::new(ErrorKind::Format, format!("unknown record tag {}", tag[0]))),
+        }
+
+        let id = u16::from_le_bytes(read_array(&mut self.reader)?) as usize;
+        let name = match self.calls.get(id) {
+            Some(&Some(name)) => name,
+            Some(&None) => {
+                return Err(Error::new(ErrorKind::Unsupported, format!("call id {} is not a known call", id)));
+            }
+            None => return Err(Error::new(ErrorKind::Format, format!("unknown call id {}", id))),
+        };
+
+        let count = read_u8(&mut self.reader)?;
+        let mut args = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            args.push(read_value(&mut self.reader)?);
+        }
+
+        let mut call = Call::new(name, args);
+        call.ret = read_value(&mut self.reader)?;
+
+        Ok(Some(Record::Call(call)))
+    }
+
+    /// Replays the whole remaining trace and returns the number of calls read.
+    pub fn replay(&mut self, backend: &mut dyn Backend) -> Result<usize, Error> {
+        let mut count = 0;
+
+        while let Some(call) = self.read_call()? {
+            self.replay_call(&call, backend)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Replays calls up to and including the next frame marker. Returns `false` once the trace
+    /// is exhausted.
+    pub fn replay_frame(&mut self, backend: &mut dyn Backend) -> Result<bool, Error> {
+        loop {
+            match self.read_record()? {
+                Some(Record::Call(call)) => self.replay_call(&call, backend)?,
+                Some(Record::Frame) => return Ok(true),
+                None => return Ok(false),
+            }
+        }
+    }
+
+    /// The number of frame markers read so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Maps a captured object name to the name of the same object in the replaying context.
+    pub fn name(&self, kind: ObjectKind, captured: u32) -> u32 {
+        if captured == 0 {
+            return 0;
+        }
+
+        *self.names.get(&(kind, captured)).unwrap_or(&captured)
+    }
+
+    fn location(&self, captured: i32) -> i32 {
+        if captured < 0 {
+            return captured;
+        }
+
+        *self.locations.get(&(self.program, captured)).unwrap_or(&captured)
+    }
+
+    fn map_names(&mut self, kind: ObjectKind, captured: &[u32], replayed: &[u32]) {
+        for (c, r) in captured.iter().zip(replayed.iter()) {
+            self.names.insert((kind, *c), *r);
+        }
+    }
+
+    pub fn replay_call(&mut self, call: &Call, backend: &mut dyn Backend) -> Result<(), Error> {
+        let a = &call.args;
+
+        unsafe {
+            match call.name {
+                "glActiveTexture" => backend.glActiveTexture(enum_(a, 0)?),
+                "glAttachShader" => backend.glAttachShader(self.arg_name(a, 0)?, self.arg_name(a, 1)?),
+                "glBindAttribLocation" => {
+                    let name = c_string(a, 2)?;
+                    backend.glBindAttribLocation(self.arg_name(a, 0)?, uint(a, 1)?,
+                                                 name.as_ptr() as *const GLchar)
+                }
+                "glBindBuffer" => backend.glBindBuffer(enum_(a, 0)?, self.arg_name(a, 1)?),
+                "glBindFramebuffer" => backend.glBindFramebuffer(enum_(a, 0)?, self.arg_name(a, 1)?),
+                "glBindRenderbuffer" => backend.glBindRenderbuffer(enum_(a, 0)?, self.arg_name(a, 1)?),
+                "glBindTexture" => backend.glBindTexture(enum_(a, 0)?, self.arg_name(a, 1)?),
+                "glBindVertexArray" => backend.glBindVertexArray(self.arg_name(a, 0)?),
+                "glBindVertexArrayOES" => backend.glBindVertexArrayOES(self.arg_name(a, 0)?),
+                "glBlendColor" => backend.glBlendColor(float(a, 0)?, float(a, 1)?, float(a, 2)?, float(a, 3)?),
+                "glBlendEquation" => backend.glBlendEquation(enum_(a, 0)?),
+                "glBlendEquationSeparate" => backend.glBlendEquationSeparate(enum_(a, 0)?, enum_(a, 1)?),
+                "glBlendFunc" => backend.glBlendFunc(enum_(a, 0)?, enum_(a, 1)?),
+                "glBlendFuncSeparate" => backend.glBlendFuncSeparate(enum_(a, 0)?, enum_(a, 1)?,
+                                                                     enum_(a, 2)?, enum_(a, 3)?),
+                "glBufferData" => {
+                    let data = bytes(a, 2)?;
+                    backend.glBufferData(enum_(a, 0)?, data.len() as GLsizeiptr,
+                                         data_ptr(data), enum_(a, 3)?)
+                }
+                "glBufferSubData" => {
+                    let data = bytes(a, 3)?;
+                    backend.glBufferSubData(enum_(a, 0)?, int(a, 1)? as GLintptr,
+                                            data.len() as GLsizeiptr, data_ptr(data))
+                }
+                "glClear" => backend.glClear(bitfield(a, 0)?),
+                "glClearColor" => backend.glClearColor(float(a, 0)?, float(a, 1)?, float(a, 2)?, float(a, 3)?),
+                "glClearDepthf" => backend.glClearDepthf(float(a, 0)?),
+                "glClearStencil" => backend.glClearStencil(int(a, 0)?),
+                "glColorMask" => backend.glColorMask(boolean(a, 0)?, boolean(a, 1)?,
+                                                     boolean(a, 2)?, boolean(a, 3)?),
+                "glCompileShader" => backend.glCompileShader(self.arg_name(a, 0)?),
+                "glCompressedTexImage2D" => {
+                    let data = bytes(a, 7)?;
+                    backend.glCompressedTexImage2D(enum_(a, 0)?, int(a, 1)?, enum_(a, 2)?,
+                                                   int(a, 3)?, int(a, 4)?, int(a, 5)?,
+                                                   count(a, 6, data.len(), 1)?, data_ptr(data))
+                }
+                "glCompressedTexSubImage2D" => {
+                    let data = bytes(a, 8)?;
+                    backend.glCompressedTexSubImage2D(enum_(a, 0)?, int(a, 1)?, int(a, 2)?,
+                                                      int(a, 3)?, int(a, 4)?, int(a, 5)?,
+                                                      enum_(a, 6)?, count(a, 7, data.len(), 1)?, data_ptr(data))
+                }
+                "glCopyTexImage2D" => backend.glCopyTexImage2D(enum_(a, 0)?, int(a, 1)?, enum_(a, 2)?,
+                                                               int(a, 3)?, int(a, 4)?, int(a, 5)?,
+                                                               int(a, 6)?, int(a, 7)?),
+                "glCopyTexSubImage2D" => backend.glCopyTexSubImage2D(enum_(a, 0)?, int(a, 1)?,
+                                                                     int(a, 2)?, int(a, 3)?,
+                                                                     int(a, 4)?, int(a, 5)?,
+                                                                     int(a, 6)?, int(a, 7)?),
+                "glCreateProgram" => {
+                    let program = backend.glCreateProgram();
+                    if let Value::Name(kind, captured) = call.ret {
+                        self.map_names(kind, &[captured], &[program]);
+                    }
+                }
+                "glCreateShader" => {
+                    let shader = backend.glCreateShader(enum_(a, 0)?);
+                    if let Value::Name(kind, captured) = call.ret {
+                        self.map_names(kind, &[captured], &[shader]);
+                    }
+                }
+                "glCullFace" => backend.glCullFace(enum_(a, 0)?),
+                "glDeleteBuffers" => {
+                    let names = self.arg_names(a, 1)?;
+                    backend.glDeleteBuffers(names.len() as GLsizei, names.as_ptr())
+                }
+                "glDeleteFramebuffers" => {
+                    let names = self.arg_names(a, 1)?;
+                    backend.glDeleteFramebuffers(names.len() as GLsizei, names.as_ptr())
+                }
+                "glDeleteProgram" => backend.glDeleteProgram(self.arg_name(a, 0)?),
+                "glDeleteRenderbuffers" => {
+                    let names = self.arg_names(a, 1)?;
+                    backend.glDeleteRenderbuffers(names.len() as GLsizei, names.as_ptr())
+                }
+                "glDeleteShader" => backend.glDeleteShader(self.arg_name(a, 0)?),
+                "glDeleteTextures" => {
+                    let names = self.arg_names(a, 1)?;
+                    backend.glDeleteTextures(names.len() as GLsizei, names.as_ptr())
+                }
+                "glDeleteVertexArrays" | "glDeleteVertexArraysOES" => {
+                    let names = self.arg_names(a, 1)?;
+                    if call.name == "glDeleteVertexArraysOES" {
+                        backend.glDeleteVertexArraysOES(names.len() as GLsizei, names.as_ptr())
+                    } else {
+                        backend.glDeleteVertexArrays(names.len() as GLsizei, names.as_ptr())
+                    }
+                }
+                "glDepthFunc" => backend.glDepthFunc(enum_(a, 0)?),
+                "glDepthMask" => backend.glDepthMask(boolean(a, 0)?),
+                "glDepthRangef" => backend.glDepthRangef(float(a, 0)?, float(a, 1)?),
+                "glDetachShader" => backend.glDetachShader(self.arg_name(a, 0)?, self.arg_name(a, 1)?),
+                "glDisable" => backend.glDisable(enum_(a, 0)?),
+                "glDisableVertexAttribArray" => backend.glDisableVertexAttribArray(uint(a, 0)?),
+                "glDrawArrays" => backend.glDrawArrays(enum_(a, 0)?, int(a, 1)?, int(a, 2)?),
+                "glDrawElements" => {
+                    if let Some(Value::Bytes(data)) = a.get(3) {
+                        let size = match enum_(a, 2)? {
+                            GL_UNSIGNED_SHORT => 2,
+                            GL_UNSIGNED_INT => 4,
+                            _ => 1,
+                        };
+                        count(a, 1, data.len(), size)?;
+                    }
+                    let indices = pointer(a, 3)?;
+                    backend.glDrawElements(enum_(a, 0)?, int(a, 1)?, enum_(a, 2)?, indices)
+                }
+                "glEnable" => backend.glEnable(enum_(a, 0)?),
+                "glEnableVertexAttribArray" => backend.glEnableVertexAttribArray(uint(a, 0)?),
+                "glFinish" => backend.glFinish(),
+                "glFlush" => backend.glFlush(),
+                "glFramebufferRenderbuffer" => backend.glFramebufferRenderbuffer(
+                    enum_(a, 0)?, enum_(a, 1)?, enum_(a, 2)?, self.arg_name(a, 3)?),
+                "glFramebufferTexture2D" => backend.glFramebufferTexture2D(
+                    enum_(a, 0)?, enum_(a, 1)?, enum_(a, 2)?, self.arg_name(a, 3)?, int(a, 4)?),
+                "glFrontFace" => backend.glFrontFace(enum_(a, 0)?),
+                "glGenBuffers" | "glGenFramebuffers" | "glGenRenderbuffers" | "glGenTextures" | "glGenVertexArrays"
+                | "glGenVertexArraysOES" => {
+                    let count = int(a, 0)?;
+                    let mut names = vec![0u32; count.max(0) as usize];
+
+                    match call.name {
+                        "glGenBuffers" => backend.glGenBuffers(count, names.as_mut_ptr()),
+                        "glGenFramebuffers" => backend.glGenFramebuffers(count, names.as_mut_ptr()),
+                        "glGenRenderbuffers" => backend.glGenRenderbuffers(count, names.as_mut_ptr()),
+                        "glGenVertexArrays" => backend.glGenVertexArrays(count, names.as_mut_ptr()),
+                        "glGenVertexArraysOES" => backend.glGenVertexArraysOES(count, names.as_mut_ptr()),
+                        _ => backend.glGenTextures(count, names.as_mut_ptr()),
+                    }
+
+                    if let Value::Names(kind, ref captured) = call.ret {
+                        self.map_names(kind, captured, &names);
+                    }
+                }
+                "glGenerateMipmap" => backend.glGenerateMipmap(enum_(a, 0)?),
+                "glGetUniformLocation" => {
+                    let program = self.arg_name(a, 0)?;
+                    let name = c_string(a, 1)?;
+                    let location = backend.glGetUniformLocation(program, name.as_ptr() as *const GLchar);
+
+                    if let (Value::Name(_, captured_program), Value::Location(captured)) = (&a[0], &call.ret) {
+                        self.locations.insert((*captured_program, *captured), location);
+                    }
+                }
+                "glHint" => backend.glHint(enum_(a, 0)?, enum_(a, 1)?),
+                "glLineWidth" => backend.glLineWidth(float(a, 0)?),
+                "glLinkProgram" => backend.glLinkProgram(self.arg_name(a, 0)?),
+                "glPixelStorei" => backend.glPixelStorei(enum_(a, 0)?, int(a, 1)?),
+                "glPolygonOffset" => backend.glPolygonOffset(float(a, 0)?, float(a, 1)?),
+                "glProgramBinary" | "glProgramBinaryOES" => {
+                    let program = self.arg_name(a, 0)?;
+                    let data = bytes(a, 2)?;
+                    let length = count(a, 3, data.len(), 1)?;
+                    if call.name == "glProgramBinaryOES" {
+                        backend.glProgramBinaryOES(program, enum_(a, 1)?, data_ptr(data), length)
+                    } else {
+                        backend.glProgramBinary(program, enum_(a, 1)?, data_ptr(data), length)
+                    }
+                }
+                "glReleaseShaderCompiler" => backend.glReleaseShaderCompiler(),
+                "glRenderbufferStorage" => backend.glRenderbufferStorage(enum_(a, 0)?, enum_(a, 1)?,
+                                                                         int(a, 2)?, int(a, 3)?),
+                "glSampleCoverage" => backend.glSampleCoverage(float(a, 0)?, boolean(a, 1)?),
+                "glScissor" => backend.glScissor(int(a, 0)?, int(a, 1)?, int(a, 2)?, int(a, 3)?),
+                "glShaderBinary" => {
+                    let shaders = self.arg_names(a, 1)?;
+                    let data = bytes(a, 3)?;
+                    backend.glShaderBinary(shaders.len() as GLsizei, shaders.as_ptr(), enum_(a, 2)?,
+                                           data_ptr(data), count(a, 4, data.len(), 1)?)
+                }
+                "glShaderSource" => {
+                    let source = string(a, 2)?;
+                    let length = source.len() as GLint;
+                    backend.glShaderSource(self.arg_name(a, 0)?, 1,
+                                           &(source.as_ptr() as *const GLchar), &length)
+                }
+                "glStencilFunc" => backend.glStencilFunc(enum_(a, 0)?, int(a, 1)?, uint(a, 2)?),
+                "glStencilFuncSeparate" => backend.glStencilFuncSeparate(enum_(a, 0)?, enum_(a, 1)?,
+                                                                         int(a, 2)?, uint(a, 3)?),
+                "glStencilMask" => backend.glStencilMask(uint(a, 0)?),
+                "glStencilMaskSeparate" => backend.glStencilMaskSeparate(enum_(a, 0)?, uint(a, 1)?),
+                "glStencilOp" => backend.glStencilOp(enum_(a, 0)?, enum_(a, 1)?, enum_(a, 2)?),
+                "glStencilOpSeparate" => backend.glStencilOpSeparate(enum_(a, 0)?, enum_(a, 1)?,
+                                                                     enum_(a, 2)?, enum_(a, 3)?),
+                "glTexImage2D" => {
+                    let data = bytes(a, 8)?;
+                    check_pixels(backend, data, int(a, 3)?, int(a, 4)?, enum_(a, 6)?, enum_(a, 7)?)?;
+                    backend.glTexImage2D(enum_(a, 0)?, int(a, 1)?, enum_(a, 2)? as GLint,
+                                         int(a, 3)?, int(a, 4)?, int(a, 5)?,
+                                         enum_(a, 6)?, enum_(a, 7)?, data_ptr(data))
+                }
+                "glTexParameterf" => backend.glTexParameterf(enum_(a, 0)?, enum_(a, 1)?, float(a, 2)?),
+                "glTexParameterfv" => {
+                    let params = floats(a, 2)?;
+                    check_len(2, params.len(), 1)?;
+                    backend.glTexParameterfv(enum_(a, 0)?, enum_(a, 1)?, params.as_ptr())
+                }
+                "glTexParameteri" => backend.glTexParameteri(enum_(a, 0)?, enum_(a, 1)?,
+                                                             enum_(a, 2)? as GLint),
+                "glTexParameteriv" => {
+                    let params = ints(a, 2)?;
+                    check_len(2, params.len(), 1)?;
+                    backend.glTexParameteriv(enum_(a, 0)?, enum_(a, 1)?, params.as_ptr())
+                }
+                "glTexSubImage2D" => {
+                    let data = bytes(a, 8)?;
+                    check_pixels(backend, data, int(a, 4)?, int(a, 5)?, enum_(a, 6)?, enum_(a, 7)?)?;
+                    backend.glTexSubImage2D(enum_(a, 0)?, int(a, 1)?, int(a, 2)?, int(a, 3)?,
+                                            int(a, 4)?, int(a, 5)?, enum_(a, 6)?, enum_(a, 7)?,
+                                            data_ptr(data))
+                }
+                "glUniform1f" => backend.glUniform1f(self.arg_location(a, 0)?, float(a, 1)?),
+                "glUniform1i" => backend.glUniform1i(self.arg_location(a, 0)?, int(a, 1)?),
+                "glUniform2f" => backend.glUniform2f(self.arg_location(a, 0)?, float(a, 1)?, float(a, 2)?),
+                "glUniform2i" => backend.glUniform2i(self.arg_location(a, 0)?, int(a, 1)?, int(a, 2)?),
+                "glUniform3f" => backend.glUniform3f(self.arg_location(a, 0)?, float(a, 1)?, float(a, 2)?,
+                                                     float(a, 3)?),
+                "glUniform3i" => backend.glUniform3i(self.arg_location(a, 0)?, int(a, 1)?, int(a, 2)?,
+                                                     int(a, 3)?),
+                "glUniform4f" => backend.glUniform4f(self.arg_location(a, 0)?, float(a, 1)?, float(a, 2)?,
+                                                     float(a, 3)?, float(a, 4)?),
+                "glUniform4i" => backend.glUniform4i(self.arg_location(a, 0)?, int(a, 1)?, int(a, 2)?,
+                                                     int(a, 3)?, int(a, 4)?),
+                "glUniform1fv" | "glUniform2fv" | "glUniform3fv" | "glUniform4fv" => {
+                    let location = self.arg_location(a, 0)?;
+                    let values = floats(a, 2)?;
+                    let len = values.len();
+                    match call.name {
+                        "glUniform1fv" => backend.glUniform1fv(location, count(a, 1, len, 1)?, values.as_ptr()),
+                        "glUniform2fv" => backend.glUniform2fv(location, count(a, 1, len, 2)?, values.as_ptr()),
+                        "glUniform3fv" => backend.glUniform3fv(location, count(a, 1, len, 3)?, values.as_ptr()),
+                        _ => backend.glUniform4fv(location, count(a, 1, len, 4)?, values.as_ptr()),
+                    }
+                }
+                "glUniform1iv" | "glUniform2iv" | "glUniform3iv" | "glUniform4iv" => {
+                    let location = self.arg_location(a, 0)?;
+                    let values = ints(a, 2)?;
+                    let len = values.len();
+                    match call.name {
+                        "glUniform1iv" => backend.glUniform1iv(location, count(a, 1, len, 1)?, values.as_ptr()),
+                        "glUniform2iv" => backend.glUniform2iv(location, count(a, 1, len, 2)?, values.as_ptr()),
+                        "glUniform3iv" => backend.glUniform3iv(location, count(a, 1, len, 3)?, values.as_ptr()),
+                        _ => backend.glUniform4iv(location, count(a, 1, len, 4)?, values.as_ptr()),
+                    }
+                }
+                "glUniformMatrix2fv" | "glUniformMatrix3fv" | "glUniformMatrix4fv" => {
+                    let location = self.arg_location(a, 0)?;
+                    let transpose = boolean(a, 2)?;
+                    let values = floats(a, 3)?;
+                    match call.name {
+                        "glUniformMatrix2fv" => backend.glUniformMatrix2fv(location, count(a, 1, values.len(), 4)?,
+                                                                           transpose, values.as_ptr()),
+                        "glUniformMatrix3fv" => backend.glUniformMatrix3fv(location, count(a, 1, values.len(), 9)?,
+                                                                           transpose, values.as_ptr()),
+                        _ => backend.glUniformMatrix4fv(location, count(a, 1, values.len(), 16)?,
+                                                        transpose, values.as_ptr()),
+                    }
+                }
+                "glUseProgram" => {
+                    let program = self.arg_name(a, 0)?;
+                    if let Some(&Value::Name(_, captured)) = a.first() {
+                        self.program = captured;
+                    }
+                    backend.glUseProgram(program)
+                }
+                "glValidateProgram" => backend.glValidateProgram(self.arg_name(a, 0)?),
+                "glVertexAttrib1f" => backend.glVertexAttrib1f(uint(a, 0)?, float(a, 1)?),
+                "glVertexAttrib1fv" => {
+                    let values = floats(a, 1)?;
+                    check_len(1, values.len(), 1)?;
+                    backend.glVertexAttrib1fv(uint(a, 0)?, values.as_ptr())
+                }
+                "glVertexAttrib2f" => backend.glVertexAttrib2f(uint(a, 0)?, float(a, 1)?, float(a, 2)?),
+                "glVertexAttrib2fv" => {
+                    let values = floats(a, 1)?;
+                    check_len(1, values.len(), 2)?;
+                    backend.glVertexAttrib2fv(uint(a, 0)?, values.as_ptr())
+                }
+                "glVertexAttrib3f" => backend.glVertexAttrib3f(uint(a, 0)?, float(a, 1)?, float(a, 2)?,
+                                                               float(a, 3)?),
+                "glVertexAttrib3fv" => {
+                    let values = floats(a, 1)?;
+                    check_len(1, values.len(), 3)?;
+                    backend.glVertexAttrib3fv(uint(a, 0)?, values.as_ptr())
+                }
+                "glVertexAttrib4f" => backend.glVertexAttrib4f(uint(a, 0)?, float(a, 1)?, float(a, 2)?,
+                                                               float(a, 3)?, float(a, 4)?),
+                "glVertexAttrib4fv" => {
+                    let values = floats(a, 1)?;
+                    check_len(1, values.len(), 4)?;
+                    backend.glVertexAttrib4fv(uint(a, 0)?, values.as_ptr())
+                }
+                "glVertexAttribPointer" => {
+                    let index = uint(a, 0)?;
+                    let data = match a.get(5) {
+                        Some(Value::Bytes(bytes)) => {
+                            self.client_arrays.insert(index, bytes.clone());
+                            data_ptr(&self.client_arrays[&index])
+                        }
+                        _ => pointer(a, 5)?,
+                    };
+                    backend.glVertexAttribPointer(index, int(a, 1)?, enum_(a, 2)?, boolean(a, 3)?,
+                                                  int(a, 4)?, data)
+                }
+                "glViewport" => backend.glViewport(int(a, 0)?, int(a, 1)?, int(a, 2)?, int(a, 3)?),
+                // Everything else only reads state back and has no effect worth replaying.
+                _ => {}
+            }
+        }
+
+        if call.name.starts_with("glDelete") {
+            if let Some(&Value::Names(kind, ref names)) = a.get(1) {
+                for name in names {
+                    self.names.remove(&(kind, *name));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn arg_name(&self, args: &[Value], index: usize) -> Result<GLuint, Error> {
+        match args.get(index) {
+            Some(&Value::Name(kind, name)) => Ok(self.name(kind, name)),
+            _ => Err(mismatch(index, "object name")),
+        }
+    }
+
+    fn arg_names(&self, args: &[Value], index: usize) -> Result<Vec<GLuint>, Error> {
+        match args.get(index) {
+            Some(&Value::Names(kind, ref names)) => {
+                Ok(names.iter().map(|name| self.name(kind, *name)).collect())
+            }
+            _ => Err(mismatch(index, "object names")),
+        }
+    }
+
+    fn arg_location(&self, args: &[Value], index: usize) -> Result<GLint, Error> {
+        match args.get(index) {
+            Some(&Value::Location(location)) => Ok(self.location(location)),
+            _ => Err(mismatch(index, "uniform location")),
+        }
+    }
+}
+
+fn mismatch(index: usize, expected: &str) -> Error {
+    Error::new(ErrorKind::Format, format!("argument {} is not a {}", index, expected))
+}
+
+fn int(args: &[Value], index: usize) -> Result<GLint, Error> {
+    match args.get(index) {
+        Some(&Value::Int(i)) => Ok(i),
+        _ => Err(mismatch(index, "int")),
+    }
+}
+
+fn uint(args: &[Value], index: usize) -> Result<GLuint, Error> {
+    match args.get(index) {
+        Some(&Value::Uint(u)) => Ok(u),
+        _ => Err(mismatch(index, "uint")),
+    }
+}
+
+fn enum_(args: &[Value], index: usize) -> Result<GLenum, Error> {
+    match args.get(index) {
+        Some(&Value::Enum(e)) => Ok(e),
+        _ => Err(mismatch(index, "enum")),
+    }
+}
+
+fn bitfield(args: &[Value], index: usize) -> Result<GLbitfield, Error> {
+    match args.get(index) {
+        Some(&Value::Bitfield(b)) => Ok(b),
+        _ => Err(mismatch(index, "bitfield")),
+    }
+}
+
+fn float(args: &[Value], index: usize) -> Result<GLfloat, Error> {
+    match args.get(index) {
+        Some(&Value::Float(f)) => Ok(f),
+        _ => Err(mismatch(index, "float")),
+    }
+}
+
+fn boolean(args: &[Value], index: usize) -> Result<GLboolean, Error> {
+    match args.get(index) {
+        Some(&Value::Bool(b)) => Ok(b as GLboolean),
+        _ => Err(mismatch(index, "bool")),
+    }
+}
+
+fn string(args: &[Value], index: usize) -> Result<&str, Error> {
+    match args.get(index) {
+        Some(Value::Str(s)) => Ok(s),
+        _ => Err(mismatch(index, "string")),
+    }
+}
+
+fn c_string(args: &[Value], index: usize) -> Result<std::ffi::CString, Error> {
+    std::ffi::CString::new(string(args, index)?)
+        .map_err(|_| Error::new(ErrorKind::Format, format!("argument {} contains a NUL byte", index)))
+}
+
+fn bytes(args: &[Value], index: usize) -> Result<&[u8], Error> {
+    match args.get(index) {
+        Some(Value::Bytes(bytes)) => Ok(bytes),
+        _ => Err(mismatch(index, "blob")),
+    }
+}
+
+fn ints(args: &[Value], index: usize) -> Result<&[GLint], Error> {
+    match args.get(index) {
+        Some(Value::Ints(ints)) => Ok(ints),
+        _ => Err(mismatch(index, "int array")),
+    }
+}
+
+fn floats(args: &[Value], index: usize) -> Result<&[GLfloat], Error> {
+    match args.get(index) {
+        Some(Value::Floats(floats)) => Ok(floats),
+        _ => Err(mismatch(index, "float array")),
+    }
+}
+
+/// A blob or buffer offset passed where the C API takes a pointer.
+fn pointer(args: &[Value], index: usize) -> Result<*const GLvoid, Error> {
+    match args.get(index) {
+        Some(Value::Bytes(bytes)) => Ok(data_ptr(bytes)),
+        Some(&Value::Offset(offset)) => Ok(offset as *const GLvoid),
+        _ => Err(mismatch(index, "pointer")),
+    }
+}
+
+// The backend reads as many values as the counts and sizes of a trace say, so a payload shorter
+// than that must be rejected before the call rather than read past its end.
+fn check_len(index: usize, len: usize, needed: usize) -> Result<(), Error> {
+    if len < needed {
+        return Err(Error::new(ErrorKind::Format,
+                              format!("argument {} holds {} values but the call reads {}", index, len, needed)));
+    }
+    Ok(())
+}
+
+// The count at `index`, checked against a payload of `len` values in which every counted element
+// takes `size` values. Negative counts are passed on for the backend to reject.
+fn count(args: &[Value], index: usize, len: usize, size: usize) -> Result<GLint, Error> {
+    let count = int(args, index)?;
+    let needed = (count.max(0) as usize).saturating_mul(size);
+    if needed > len {
+        return Err(Error::new(ErrorKind::Format,
+                              format!("argument {} needs {} values but only {} were captured", index, needed, len)));
+    }
+    Ok(count)
+}
+
+// A non-empty pixel upload must hold the whole image at the replaying context's unpack alignment.
+fn check_pixels(backend: &mut dyn Backend, data: &[u8], width: GLint, height: GLint,
+                format: GLenum, type_: GLenum) -> Result<(), Error> {
+    if data.is_empty() {
+        return Ok(());
+    }
+    let info = FormatInfo::for_transfer(format, type_).ok_or_else(|| {
+        Error::new(ErrorKind::Format, format!("no pixel layout for format {:#x} and type {:#x}", format, type_))
+    })?;
+
+    let mut alignment = 4;
+    unsafe { backend.glGetIntegerv(GL_UNPACK_ALIGNMENT, &mut alignment) };
+    let needed = info.image_size(width.max(0) as usize, height.max(0) as usize, alignment.max(1) as usize);
+    if data.len() < needed {
+        return Err(Error::new(ErrorKind::Format,
+                              format!("a {}x{} image needs {} bytes but only {} were captured",
+                                      width, height, needed, data.len())));
+    }
+    Ok(())
+}
+
+// An empty upload means "no data", e.g. allocating texture storage without initial pixels.
+fn data_ptr(data: &[u8]) -> *const GLvoid {
+    if data.is_empty() {
+        ptr::null()
+    } else {
+        data.as_ptr() as *const GLvoid
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::io::{self, Write};
+    use std::rc::Rc;
+
+    use consts::*;
+    use enums::{PixelDataType, PixelFormat, TextureBindTarget};
+    use super::super::software::SoftwareBackend;
+    use super::super::wrapper::Wrapper;
+    use super::*;
+
+    // A writer the test can still read after handing it to a `Capture`.
+    #[derive(Clone, Default)]
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(bytes)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn capture<F: FnOnce(&mut Wrapper)>(calls: F) -> Vec<u8> {
+        let trace = Shared::default();
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(4, 4)));
+        gl.add_interceptor(Box::new(Capture::new(trace.clone()).unwrap()));
+        calls(&mut gl);
+        drop(gl.take_interceptors());
+
+        let bytes = trace.0.borrow().clone();
+        bytes
+    }
+
+    #[test]
+    fn calls_read_back_as_written() {
+        let trace = capture(|gl| {
+            gl.gl_clear_color(0.25, 0.5, 0.75, 1.0).unwrap();
+            gl.gl_clear(GL_COLOR_BUFFER_BIT).unwrap();
+            gl.end_frame();
+        });
+
+        let mut replayer = Replayer::new(&trace[..]).unwrap();
+        let call = replayer.read_call().unwrap().unwrap();
+        assert_eq!(call.name, "glClearColor");
+        assert_eq!(call.args, vec![Value::Float(0.25), Value::Float(0.5), Value::Float(0.75), Value::Float(1.0)]);
+        let call = replayer.read_call().unwrap().unwrap();
+        assert_eq!(call.name, "glClear");
+        assert_eq!(call.args, vec![Value::Bitfield(GL_COLOR_BUFFER_BIT)]);
+        assert!(replayer.read_call().unwrap().is_none());
+        assert_eq!(replayer.frame(), 1);
+    }
+
+    #[test]
+    fn replay_maps_names_and_reproduces_the_frame() {
+        let trace = capture(|gl| {
+            let texture = gl.gl_gen_textures(1).unwrap()[0];
+            gl.gl_bind_texture(TextureBindTarget::TEXTURE_2D, texture).unwrap();
+            gl.gl_clear_color(0.0, 1.0, 0.0, 1.0).unwrap();
+            gl.gl_clear(GL_COLOR_BUFFER_BIT).unwrap();
+            gl.end_frame();
+        });
+
+        // A texture made before the replay shifts the names the replayed context hands out.
+        let mut backend = SoftwareBackend::new(4, 4);
+        let mut existing = 0;
+        unsafe { backend.glGenTextures(1, &mut existing) };
+
+        let mut replayer = Replayer::new(&trace[..]).unwrap();
+        assert!(replayer.replay_frame(&mut backend).unwrap());
+        assert!(!replayer.replay_frame(&mut backend).unwrap());
+        assert_ne!(replayer.name(ObjectKind::Texture, 1), existing);
+
+        let mut gl = Wrapper::with_backend(Box::new(backend));
+        let mut pixel = [0u8; 4];
+        gl.gl_read_pixels(1, 1, 1, 1, PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE, &mut pixel).unwrap();
+        assert_eq!(pixel, [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn truncated_trace_is_an_error() {
+        let trace = capture(|gl| gl.gl_clear(GL_COLOR_BUFFER_BIT).unwrap());
+
+        let mut replayer = Replayer::new(&trace[..trace.len() - 2]).unwrap();
+        assert!(replayer.read_call().is_err());
+        assert_eq!(Replayer::new(&b"not a trace"[..]).err().unwrap().kind, ErrorKind::Format);
+    }
+
+    // Replays a single call into a fresh context.
+    fn replay(name: &'static str, args: Vec<Value>) -> Result<(), Error> {
+        let trace = capture(|_| {});
+        let mut replayer = Replayer::new(&trace[..]).unwrap();
+        let mut backend = SoftwareBackend::new(4, 4);
+        replayer.replay_call(&Call::new(name, args), &mut backend)
+    }
+
+    #[test]
+    fn call_ids_follow_the_header_names() {
+        let mut trace = MAGIC.to_vec();
+        write_u32(&mut trace, VERSION).unwrap();
+        write_len(&mut trace, 2).unwrap();
+        for name in &["glClear", "glNotAFunction"] {
+            write_len(&mut trace, name.len()).unwrap();
+            trace.extend_from_slice(name.as_bytes());
+        }
+        for id in 0..2u16 {
+            write_u8(&mut trace, TAG_CALL).unwrap();
+            trace.extend_from_slice(&id.to_le_bytes());
+            write_u8(&mut trace, 1).unwrap();
+            write_value(&mut trace, &Value::Bitfield(GL_COLOR_BUFFER_BIT)).unwrap();
+            write_value(&mut trace, &Value::Void).unwrap();
+        }
+
+        let mut replayer = Replayer::new(&trace[..]).unwrap();
+        assert_eq!(replayer.read_call().unwrap().unwrap().name, "glClear");
+        assert_eq!(replayer.read_call().unwrap_err().kind, ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn short_payloads_are_rejected() {
+        let location = Value::Location(0);
+        let error = replay("glUniform4fv", vec![location.clone(), Value::Int(2), Value::Floats(vec![0.0; 4])]);
+        assert_eq!(error.unwrap_err().kind, ErrorKind::Format);
+        let error = replay("glUniform2iv", vec![location.clone(), Value::Int(3), Value::Ints(vec![0; 5])]);
+        assert_eq!(error.unwrap_err().kind, ErrorKind::Format);
+        let error = replay("glUniformMatrix4fv",
+                           vec![location.clone(), Value::Int(1), Value::Bool(false), Value::Floats(vec![0.0; 9])]);
+        assert_eq!(error.unwrap_err().kind, ErrorKind::Format);
+        assert!(replay("glUniform4fv", vec![location, Value::Int(-1), Value::Floats(Vec::new())]).is_ok());
+
+        let shaders = Value::Names(ObjectKind::Shader, vec![1]);
+        let error = replay("glShaderBinary",
+                           vec![Value::Int(1), shaders, Value::Enum(0), Value::Bytes(vec![0; 4]), Value::Int(100)]);
+        assert_eq!(error.unwrap_err().kind, ErrorKind::Format);
+        let error = replay("glProgramBinary", vec![Value::Name(ObjectKind::Program, 1), Value::Enum(0),
+                                                   Value::Bytes(Vec::new()), Value::Int(16)]);
+        assert_eq!(error.unwrap_err().kind, ErrorKind::Format);
+        let error = replay("glCompressedTexImage2D",
+                           vec![Value::Enum(GL_TEXTURE_2D), Value::Int(0), Value::Enum(GL_ETC1_RGB8_OES),
+                                Value::Int(8), Value::Int(8), Value::Int(0), Value::Int(32), Value::Bytes(vec![0; 8])]);
+        assert_eq!(error.unwrap_err().kind, ErrorKind::Format);
+        let error = replay("glDrawElements", vec![Value::Enum(GL_TRIANGLES), Value::Int(3),
+                                                  Value::Enum(GL_UNSIGNED_SHORT), Value::Bytes(vec![0; 4])]);
+        assert_eq!(error.unwrap_err().kind, ErrorKind::Format);
+        let error = replay("glVertexAttrib4fv", vec![Value::Uint(0), Value::Floats(vec![0.0; 3])]);
+        assert_eq!(error.unwrap_err().kind, ErrorKind::Format);
+    }
+
+    #[test]
+    fn short_pixel_uploads_are_rejected() {
+        let upload = |pixels: Vec<u8>| {
+            replay("glTexImage2D", vec![Value::Enum(GL_TEXTURE_2D), Value::Int(0), Value::Enum(GL_RGB),
+                                        Value::Int(3), Value::Int(2), Value::Int(0), Value::Enum(GL_RGB),
+                                        Value::Enum(GL_UNSIGNED_BYTE), Value::Bytes(pixels)])
+        };
+
+        // Rows of three RGB pixels are padded to 12 bytes at the default unpack alignment.
+        assert_eq!(upload(vec![0; 18]).unwrap_err().kind, ErrorKind::Format);
+        assert!(upload(vec![0; 21]).is_ok());
+        assert!(upload(Vec::new()).is_ok());
+
+        let error = replay("glTexSubImage2D", vec![Value::Enum(GL_TEXTURE_2D), Value::Int(0), Value::Int(0),
+                                                   Value::Int(0), Value::Int(2), Value::Int(2), Value::Enum(GL_RGBA),
+                                                   Value::Enum(GL_UNSIGNED_BYTE), Value::Bytes(vec![0; 15])]);
+        assert_eq!(error.unwrap_err().kind, ErrorKind::Format);
+    }
+
+    #[test]
+    fn missing_arguments_are_an_error() {
+        assert_eq!(replay("glUseProgram", Vec::new()).unwrap_err().kind, ErrorKind::Format);
+        assert_eq!(replay("glUniform1fv", vec![Value::Location(0)]).unwrap_err().kind, ErrorKind::Format);
+    }
+
+    #[test]
+    fn unknown_call_is_not_written() {
+        let mut bytes = Vec::new();
+        let error = write_call(&mut bytes, &Call::new("glNotAFunction", Vec::new())).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidArgument);
+        assert!(bytes.is_empty());
+    }
+}
@@ -0,0 +1,804 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::ffi::CString;
+use std::fmt;
+
+use types::*;
+use consts::*;
+use format::FormatInfo;
+use super::backend::Backend;
+use super::call::{Call, Value};
+use super::wrapper::{Error, ErrorKind, Interceptor};
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+/// The ES 2.0 usage rules checked by `Validator`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// `glDrawArrays`, `glDrawElements` and `glUniform*` need a program in use.
+    NoProgram,
+    /// Vertex attribute indices must be below `GL_MAX_VERTEX_ATTRIBS`.
+    AttribIndex,
+    /// The indices of `glDrawElements` must hold `count` elements of the index type.
+    IndicesTooShort,
+    /// Pixel data passed to `glTexImage2D`/`glTexSubImage2D` must cover the whole image,
+    /// including row padding from `GL_UNPACK_ALIGNMENT`. `glTexImage2D` may pass none at all.
+    PixelsTooShort,
+    /// Non-power-of-two textures can not be mipmapped and must clamp to edge.
+    NpotTexture,
+    /// `glUniform*` must match the type and array size of the uniform at the location.
+    UniformType,
+    /// The bound framebuffer must be complete when drawing.
+    IncompleteFramebuffer,
+    /// Objects are not deleted while they are still bound.
+    DeleteBound,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Rule::NoProgram => "no-program",
+            Rule::AttribIndex => "attrib-index",
+            Rule::IndicesTooShort => "indices-too-short",
+            Rule::PixelsTooShort => "pixels-too-short",
+            Rule::NpotTexture => "npot-texture",
+            Rule::UniformType => "uniform-type",
+            Rule::IncompleteFramebuffer => "incomplete-framebuffer",
+            Rule::DeleteBound => "delete-bound",
+        };
+
+        f.write_str(name)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+struct Texture {
+    width: i32,
+    height: i32,
+    min_filter: GLenum,
+    wrap_s: GLenum,
+    wrap_t: GLenum,
+}
+
+impl Default for Texture {
+    fn default() -> Self {
+        Texture {
+            width: 0,
+            height: 0,
+            min_filter: GL_NEAREST_MIPMAP_LINEAR,
+            wrap_s: GL_REPEAT,
+            wrap_t: GL_REPEAT,
+        }
+    }
+}
+
+struct Uniform {
+    name: String,
+    type_: GLenum,
+    // One location per array element.
+    locations: Vec<GLint>,
+}
+
+/// Checks ES 2.0 usage rules before a call reaches the backend, for drivers that crash instead
+/// of raising a GL error. A violation fails the call with `ErrorKind::Validation` and a message
+/// naming the `Rule`; the call is not issued.
+///
+/// Most state is queried from the backend when a call is checked. Texture bindings and texture
+/// parameters can not be queried without changing state, so they are tracked from the calls
+/// seen; textures set up before the validator was added are not checked for `NpotTexture`.
+#[derive(Default)]
+pub struct Validator {
+    disabled: Vec<Rule>,
+    max_vertex_attribs: Option<u32>,
+    active_unit: u32,
+    // (texture unit, target) -> texture
+    bindings: HashMap<(u32, GLenum), GLuint>,
+    textures: HashMap<GLuint, Texture>,
+    buffer_sizes: HashMap<GLuint, usize>,
+    uniforms: HashMap<GLuint, Vec<Uniform>>,
+}
+
+impl Validator {
+    pub fn new() -> Validator {
+        Validator::default()
+    }
+
+    /// Stops checking `rule`.
+    pub fn without(mut self, rule: Rule) -> Validator {
+        self.disabled.push(rule);
+        self
+    }
+
+    fn enabled(&self, rule: Rule) -> bool {
+        !self.disabled.contains(&rule)
+    }
+
+    fn check(&self, rule: Rule, call: &Call, failed: bool, detail: impl FnOnce() -> String) -> Result<(), Error> {
+        if failed && self.enabled(rule) {
+            Err(violation(rule, call, detail()))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_draw(&mut self, call: &Call, backend: &mut dyn Backend) -> Result<(), Error> {
+        let program = get_integer(backend, GL_CURRENT_PROGRAM) as GLuint;
+        self.check(Rule::NoProgram, call, program == 0, || "no program is in use".to_string())?;
+
+        let status = unsafe { backend.glCheckFramebufferStatus(GL_FRAMEBUFFER) };
+        self.check(Rule::IncompleteFramebuffer, call, status != GL_FRAMEBUFFER_COMPLETE, || {
+            format!("the bound framebuffer is {}", enum_name(status).unwrap_or("incomplete"))
+        })?;
+
+        if self.enabled(Rule::NpotTexture) {
+            self.check_sampled_textures(call, program, backend)?;
+        }
+
+        if call.name == "glDrawElements" {
+            self.check_indices(call, backend)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_sampled_textures(&mut self, call: &Call, program: GLuint, backend: &mut dyn Backend) -> Result<(), Error> {
+        let mut samplers = Vec::new();
+
+        if let Some(uniforms) = self.program_uniforms(program, backend) {
+            for uniform in uniforms.iter() {
+                let target = match uniform.type_ {
+                    GL_SAMPLER_2D => GL_TEXTURE_2D,
+                    GL_SAMPLER_CUBE => GL_TEXTURE_CUBE_MAP,
+                    _ => continue,
+                };
+
+                for &location in uniform.locations.iter() {
+                    samplers.push((uniform.name.clone(), location, target));
+                }
+            }
+        }
+
+        for (name, location, target) in samplers {
+            let mut unit: GLint = 0;
+            unsafe { backend.glGetUniformiv(program, location, &mut unit) }
+
+            let texture = match self.bindings.get(&(unit as u32, target)) {
+                Some(&texture) => texture,
+                None => continue,
+            };
+
+            let state = match self.textures.get(&texture) {
+                Some(state) if state.width > 0 && state.height > 0 => state,
+                _ => continue,
+            };
+
+            if is_power_of_two(state.width) && is_power_of_two(state.height) {
+                continue;
+            }
+
+            self.check(Rule::NpotTexture, call, is_mipmap_filter(state.min_filter), || {
+                format!("texture {} sampled by {} is {}x{} but uses the mipmap filter {}",
+                        texture, name, state.width, state.height, format_enum(state.min_filter))
+            })?;
+
+            self.check(Rule::NpotTexture, call, state.wrap_s != GL_CLAMP_TO_EDGE || state.wrap_t != GL_CLAMP_TO_EDGE, || {
+                format!("texture {} sampled by {} is {}x{} but does not use GL_CLAMP_TO_EDGE wrapping",
+                        texture, name, state.width, state.height)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn check_indices(&mut self, call: &Call, backend: &mut dyn Backend) -> Result<(), Error> {
+        let count = int_arg(call, 1).unwrap_or(0).max(0) as usize;
+        let type_ = enum_arg(call, 2).unwrap_or(0);
+        let size = match type_ {
+            GL_UNSIGNED_BYTE => 1,
+            GL_UNSIGNED_SHORT => 2,
+            GL_UNSIGNED_INT => 4,
+            _ => return Ok(()),
+        };
+        let needed = count * size;
+
+        match call.args.get(3) {
+            Some(Value::Bytes(bytes)) => {
+                self.check(Rule::IndicesTooShort, call, bytes.len() < needed, || {
+                    format!("{} indices of type {} need {} bytes but {} were passed",
+                            count, format_enum(type_), needed, bytes.len())
+                })
+            }
+            Some(&Value::Offset(offset)) => {
+                let buffer = get_integer(backend, GL_ELEMENT_ARRAY_BUFFER_BINDING) as GLuint;
+                self.check(Rule::IndicesTooShort, call, buffer == 0, || {
+                    "indices are given as a buffer offset but no element array buffer is bound".to_string()
+                })?;
+
+                match self.buffer_sizes.get(&buffer) {
+                    Some(&available) => self.check(Rule::IndicesTooShort, call, offset + needed > available, || {
+                        format!("{} indices of type {} at offset {} need {} bytes but buffer {} holds {}",
+                                count, format_enum(type_), offset, offset + needed, buffer, available)
+                    }),
+                    None => Ok(()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_attrib_index(&mut self, call: &Call, position: usize, backend: &mut dyn Backend) -> Result<(), Error> {
+        let index = match call.args.get(position) {
+            Some(&Value::Uint(index)) => index,
+            _ => return Ok(()),
+        };
+
+        let max = match self.max_vertex_attribs {
+            Some(max) => max,
+            None => {
+                let max = get_integer(backend, GL_MAX_VERTEX_ATTRIBS) as u32;
+                self.max_vertex_attribs = Some(max);
+                max
+            }
+        };
+
+        self.check(Rule::AttribIndex, call, index >= max, || {
+            format!("attribute index {} is not below GL_MAX_VERTEX_ATTRIBS ({})", index, max)
+        })
+    }
+
+    // The width and the format are at `width_at` and `format_at`, followed by the height and by
+    // the type and pixels.
+    fn check_pixels(&mut self, call: &Call, width_at: usize, format_at: usize, backend: &mut dyn Backend)
+        -> Result<(), Error> {
+        let width = int_arg(call, width_at).unwrap_or(0).max(0) as usize;
+        let height = int_arg(call, width_at + 1).unwrap_or(0).max(0) as usize;
+        let format = enum_arg(call, format_at).unwrap_or(0);
+        let type_ = enum_arg(call, format_at + 1).unwrap_or(0);
+
+        let length = match call.args.get(format_at + 2) {
+            // glTexImage2D without data only allocates the level.
+            Some(Value::Bytes(bytes)) if bytes.is_empty() && call.name == "glTexImage2D" => return Ok(()),
+            Some(Value::Bytes(bytes)) => bytes.len(),
+            _ => return Ok(()),
+        };
+
+        let info = match FormatInfo::for_transfer(format, type_) {
+            Some(info) => info,
+            None => return Ok(()),
+        };
+
+        let alignment = get_integer(backend, GL_UNPACK_ALIGNMENT).max(1) as usize;
+        let needed = info.image_size(width, height, alignment);
+
+        self.check(Rule::PixelsTooShort, call, length < needed, || {
+            format!("a {}x{} image of {}/{} needs {} bytes but {} were passed",
+                    width, height, format_enum(format), format_enum(type_), needed, length)
+        })
+    }
+
+    fn check_uniform(&mut self, call: &Call, backend: &mut dyn Backend) -> Result<(), Error> {
+        let location = match call.args.first() {
+            Some(&Value::Location(location)) => location,
+            _ => return Ok(()),
+        };
+
+        let program = get_integer(backend, GL_CURRENT_PROGRAM) as GLuint;
+        self.check(Rule::NoProgram, call, program == 0, || "no program is in use".to_string())?;
+
+        // Location -1 is silently ignored by GL, it is what optimized-out uniforms return.
+        if location == -1 || !self.enabled(Rule::UniformType) {
+            return Ok(());
+        }
+
+        let (setter, components) = match parse_uniform_call(call.name) {
+            Some(parsed) => parsed,
+            None => return Ok(()),
+        };
+
+        let uniforms = match self.program_uniforms(program, backend) {
+            Some(uniforms) => uniforms,
+            None => return Ok(()),
+        };
+
+        let uniform = match uniforms.iter().find(|uniform| uniform.locations.contains(&location)) {
+            Some(uniform) => uniform,
+            None => {
+                return Err(violation(Rule::UniformType, call, format!(
+                    "location {} is not an active uniform of program {}", location, program)));
+            }
+        };
+
+        if !accepts(uniform.type_, setter, components) {
+            return Err(violation(Rule::UniformType, call, format!(
+                "uniform {} is of type {}", uniform.name, format_enum(uniform.type_))));
+        }
+
+        if call.name.ends_with('v') {
+            let count = int_arg(call, 1).unwrap_or(1).max(0) as usize;
+            let size = uniform.locations.len();
+            if count > 1 && size == 1 {
+                return Err(violation(Rule::UniformType, call, format!(
+                    "{} values were passed but uniform {} is not an array", count, uniform.name)));
+            }
+
+            let element = uniform.locations.iter().position(|&element| element == location).unwrap_or(0);
+            if element + count > size {
+                return Err(violation(Rule::UniformType, call, format!(
+                    "{} values from element {} run past the end of uniform {}, which has {}",
+                    count, element, uniform.name, size)));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_delete(&mut self, call: &Call, backend: &mut dyn Backend) -> Result<(), Error> {
+        let names: &[u32] = match call.args.get(1) {
+            Some(Value::Names(_, names)) => names,
+            _ => match call.args.first() {
+                Some(Value::Name(_, name)) => ::std::slice::from_ref(name),
+                _ => return Ok(()),
+            },
+        };
+
+        let bound: Vec<(GLuint, String)> = match call.name {
+            "glDeleteTextures" => self.bindings.iter()
+                .map(|(&(unit, target), &texture)| (texture, format!("{} of texture unit {}", format_enum(target), unit)))
+                .collect(),
+            "glDeleteBuffers" => vec![
+                (get_integer(backend, GL_ARRAY_BUFFER_BINDING) as GLuint, "GL_ARRAY_BUFFER".to_string()),
+                (get_integer(backend, GL_ELEMENT_ARRAY_BUFFER_BINDING) as GLuint, "GL_ELEMENT_ARRAY_BUFFER".to_string()),
+            ],
+            "glDeleteFramebuffers" => vec![
+                (get_integer(backend, GL_FRAMEBUFFER_BINDING) as GLuint, "GL_FRAMEBUFFER".to_string()),
+            ],
+            "glDeleteRenderbuffers" => vec![
+                (get_integer(backend, GL_RENDERBUFFER_BINDING) as GLuint, "GL_RENDERBUFFER".to_string()),
+            ],
+            "glDeleteProgram" => vec![
+                (get_integer(backend, GL_CURRENT_PROGRAM) as GLuint, "the current program".to_string()),
+            ],
+            _ => return Ok(()),
+        };
+
+        for &name in names.iter().filter(|&&name| name != 0) {
+            if let Some((_, binding)) = bound.iter().find(|&&(bound, _)| bound == name) {
+                let kind = match call.name {
+                    "glDeleteTextures" => "texture",
+                    "glDeleteBuffers" => "buffer",
+                    "glDeleteFramebuffers" => "framebuffer",
+                    "glDeleteRenderbuffers" => "renderbuffer",
+                    _ => "program",
+                };
+
+                return Err(violation(Rule::DeleteBound, call, format!("{} {} is still bound as {}", kind, name, binding)));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Queries and caches the active uniforms of `program`. Returns `None` if it is not linked.
+    fn program_uniforms(&mut self, program: GLuint, backend: &mut dyn Backend) -> Option<&Vec<Uniform>> {
+        if let Entry::Vacant(entry) = self.uniforms.entry(program) {
+            entry.insert(query_uniforms(program, backend)?);
+        }
+
+        self.uniforms.get(&program)
+    }
+
+    fn bound_texture(&self, target: GLenum) -> Option<GLuint> {
+        let target = if (GL_TEXTURE_CUBE_MAP_POSITIVE_X..=GL_TEXTURE_CUBE_MAP_NEGATIVE_Z).contains(&target) {
+            GL_TEXTURE_CUBE_MAP
+        } else {
+            target
+        };
+
+        self.bindings.get(&(self.active_unit, target)).cloned()
+    }
+
+    fn track_image(&mut self, call: &Call, first: usize) {
+        if int_arg(call, 1) != Some(0) {
+            return;
+        }
+
+        let texture = match enum_arg(call, 0).and_then(|target| self.bound_texture(target)) {
+            Some(texture) => texture,
+            None => return,
+        };
+
+        let state = self.textures.entry(texture).or_default();
+        state.width = int_arg(call, first).unwrap_or(0);
+        state.height = int_arg(call, first + 1).unwrap_or(0);
+    }
+
+    fn track_parameter(&mut self, call: &Call) {
+        let texture = match enum_arg(call, 0).and_then(|target| self.bound_texture(target)) {
+            Some(texture) => texture,
+            None => return,
+        };
+
+        let value = match call.args.get(2) {
+            Some(&Value::Enum(value)) => value,
+            Some(&Value::Float(value)) => value as GLenum,
+            Some(Value::Ints(values)) if !values.is_empty() => values[0] as GLenum,
+            Some(Value::Floats(values)) if !values.is_empty() => values[0] as GLenum,
+            _ => return,
+        };
+
+        let state = self.textures.entry(texture).or_default();
+        match enum_arg(call, 1) {
+            Some(GL_TEXTURE_MIN_FILTER) => state.min_filter = value,
+            Some(GL_TEXTURE_WRAP_S) => state.wrap_s = value,
+            Some(GL_TEXTURE_WRAP_T) => state.wrap_t = value,
+            _ => {}
+        }
+    }
+}
+
+impl Interceptor for Validator {
+    fn intercept(&mut self, call: &Call, backend: &mut dyn Backend) -> Result<(), Error> {
+        match call.name {
+            "glDrawArrays" | "glDrawElements" => self.check_draw(call, backend),
+            "glBindAttribLocation" => self.check_attrib_index(call, 1, backend),
+            "glEnableVertexAttribArray" | "glDisableVertexAttribArray" | "glVertexAttribPointer" |
+            "glGetVertexAttribfv" | "glGetVertexAttribiv" | "glGetVertexAttribPointerv" => {
+                self.check_attrib_index(call, 0, backend)
+            }
+            name if name.starts_with("glVertexAttrib") => self.check_attrib_index(call, 0, backend),
+            // glTexImage2D has a border between the size and the format.
+            "glTexImage2D" => self.check_pixels(call, 3, 6, backend),
+            "glTexSubImage2D" => self.check_pixels(call, 4, 6, backend),
+            "glGenerateMipmap" => {
+                let texture = enum_arg(call, 0).and_then(|target| self.bound_texture(target));
+                match texture.and_then(|texture| self.textures.get(&texture).map(|state| (texture, state))) {
+                    // Without an image GL raises its own error, there is no size to judge.
+                    Some((texture, state)) if state.width > 0 && state.height > 0 => {
+                        let npot = !is_power_of_two(state.width) || !is_power_of_two(state.height);
+                        self.check(Rule::NpotTexture, call, npot, || {
+                            format!("texture {} is {}x{}, which can not have mipmaps", texture, state.width, state.height)
+                        })
+                    }
+                    _ => Ok(()),
+                }
+            }
+            name if name.starts_with("glUniform") => self.check_uniform(call, backend),
+            name if name.starts_with("glDelete") => self.check_delete(call, backend),
+            _ => Ok(()),
+        }
+    }
+
+    fn completed(&mut self, call: &Call, backend: &mut dyn Backend) {
+        match call.name {
+            "glActiveTexture" => {
+                if let Some(unit) = enum_arg(call, 0) {
+                    self.active_unit = unit.wrapping_sub(GL_TEXTURE0);
+                }
+            }
+            "glBindTexture" => {
+                if let (Some(target), Some(&Value::Name(_, texture))) = (enum_arg(call, 0), call.args.get(1)) {
+                    if texture == 0 {
+                        self.bindings.remove(&(self.active_unit, target));
+                    } else {
+                        self.bindings.insert((self.active_unit, target), texture);
+                        self.textures.entry(texture).or_default();
+                    }
+                }
+            }
+            "glTexImage2D" | "glCompressedTexImage2D" => self.track_image(call, 3),
+            "glCopyTexImage2D" => self.track_image(call, 5),
+            "glTexParameteri" | "glTexParameterf" | "glTexParameteriv" | "glTexParameterfv" => {
+                self.track_parameter(call)
+            }
+            "glBufferData" => {
+                let binding = match enum_arg(call, 0) {
+                    Some(GL_ARRAY_BUFFER) => GL_ARRAY_BUFFER_BINDING,
+                    Some(GL_ELEMENT_ARRAY_BUFFER) => GL_ELEMENT_ARRAY_BUFFER_BINDING,
+                    _ => return,
+                };
+
+                let buffer = get_integer(backend, binding) as GLuint;
+                if buffer != 0 {
+                    self.buffer_sizes.insert(buffer, int_arg(call, 1).unwrap_or(0).max(0) as usize);
+                }
+            }
+            "glDeleteTextures" => {
+                if let Some(Value::Names(_, textures)) = call.args.get(1) {
+                    for texture in textures.iter() {
+                        self.textures.remove(texture);
+                    }
+                    self.bindings.retain(|_, bound| !textures.contains(bound));
+                }
+            }
+            "glDeleteBuffers" => {
+                if let Some(Value::Names(_, buffers)) = call.args.get(1) {
+                    for buffer in buffers.iter() {
+                        self.buffer_sizes.remove(buffer);
+                    }
+                }
+            }
+            "glLinkProgram" | "glDeleteProgram" => {
+                if let Some(&Value::Name(_, program)) = call.args.first() {
+                    self.uniforms.remove(&program);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// HELPERS
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Copy, Clone, PartialEq)]
+enum Setter {
+    Float,
+    Int,
+    Matrix,
+}
+
+fn is_power_of_two(value: i32) -> bool {
+    value > 0 && (value as u32).is_power_of_two()
+}
+
+fn violation(rule: Rule, call: &Call, detail: String) -> Error {
+    Error::new(ErrorKind::Validation, format!("rule {} violated by {}: {}", rule, call.name, detail))
+}
+
+fn int_arg(call: &Call, index: usize) -> Option<i32> {
+    match call.args.get(index) {
+        Some(&Value::Int(value)) => Some(value),
+        _ => None,
+    }
+}
+
+fn enum_arg(call: &Call, index: usize) -> Option<GLenum> {
+    match call.args.get(index) {
+        Some(&Value::Enum(value)) => Some(value),
+        _ => None,
+    }
+}
+
+fn format_enum(value: GLenum) -> String {
+    match enum_name(value) {
+        Some(name) => name.to_string(),
+        None => format!("0x{:x}", value),
+    }
+}
+
+fn get_integer(backend: &mut dyn Backend, name: GLenum) -> GLint {
+    let mut value: GLint = 0;
+    unsafe { backend.glGetIntegerv(name, &mut value) }
+    value
+}
+
+fn is_mipmap_filter(filter: GLenum) -> bool {
+    matches!(filter, GL_NEAREST_MIPMAP_NEAREST | GL_LINEAR_MIPMAP_NEAREST |
+                     GL_NEAREST_MIPMAP_LINEAR | GL_LINEAR_MIPMAP_LINEAR)
+}
+
+// "glUniform3fv" -> (Float, 3), "glUniformMatrix4fv" -> (Matrix, 4)
+fn parse_uniform_call(name: &str) -> Option<(Setter, usize)> {
+    let rest = name.trim_start_matches("glUniform");
+
+    if let Some(rest) = rest.strip_prefix("Matrix") {
+        return rest.chars().next().and_then(|c| c.to_digit(10)).map(|n| (Setter::Matrix, n as usize));
+    }
+
+    let mut chars = rest.chars();
+    let components = chars.next()?.to_digit(10)? as usize;
+    match chars.next()? {
+        'f' => Some((Setter::Float, components)),
+        'i' => Some((Setter::Int, components)),
+        _ => None,
+    }
+}
+
+fn accepts(type_: GLenum, setter: Setter, components: usize) -> bool {
+    let (kinds, size): (&[Setter], usize) = match type_ {
+        GL_FLOAT => (&[Setter::Float], 1),
+        GL_FLOAT_VEC2 => (&[Setter::Float], 2),
+        GL_FLOAT_VEC3 => (&[Setter::Float], 3),
+        GL_FLOAT_VEC4 => (&[Setter::Float], 4),
+        GL_INT => (&[Setter::Int], 1),
+        GL_INT_VEC2 => (&[Setter::Int], 2),
+        GL_INT_VEC3 => (&[Setter::Int], 3),
+        GL_INT_VEC4 => (&[Setter::Int], 4),
+        GL_BOOL => (&[Setter::Float, Setter::Int], 1),
+        GL_BOOL_VEC2 => (&[Setter::Float, Setter::Int], 2),
+        GL_BOOL_VEC3 => (&[Setter::Float, Setter::Int], 3),
+        GL_BOOL_VEC4 => (&[Setter::Float, Setter::Int], 4),
+        GL_FLOAT_MAT2 => (&[Setter::Matrix], 2),
+        GL_FLOAT_MAT3 => (&[Setter::Matrix], 3),
+        GL_FLOAT_MAT4 => (&[Setter::Matrix], 4),
+        GL_SAMPLER_2D | GL_SAMPLER_CUBE => (&[Setter::Int], 1),
+        _ => return true,
+    };
+
+    kinds.contains(&setter) && size == components
+}
+
+fn query_uniforms(program: GLuint, backend: &mut dyn Backend) -> Option<Vec<Uniform>> {
+    let mut linked: GLint = 0;
+    let mut count: GLint = 0;
+    let mut max_length: GLint = 0;
+
+    unsafe {
+        backend.glGetProgramiv(program, GL_LINK_STATUS, &mut linked);
+        if linked == 0 {
+            return None;
+        }
+
+        backend.glGetProgramiv(program, GL_ACTIVE_UNIFORMS, &mut count);
+        backend.glGetProgramiv(program, GL_ACTIVE_UNIFORM_MAX_LENGTH, &mut max_length);
+    }
+
+    let mut uniforms = Vec::with_capacity(count.max(0) as usize);
+
+    for index in 0..count.max(0) as GLuint {
+        let mut buffer = vec![0u8; max_length.max(1) as usize];
+        let mut length: GLsizei = 0;
+        let mut size: GLint = 0;
+        let mut type_: GLenum = 0;
+
+        unsafe {
+            backend.glGetActiveUniform(program, index, buffer.len() as GLsizei, &mut length, &mut size,
+                                       &mut type_, buffer.as_mut_ptr() as *mut GLchar);
+        }
+
+        buffer.truncate(length.max(0) as usize);
+        let name = String::from_utf8_lossy(&buffer).into_owned();
+        let base = name.trim_end_matches("[0]").to_string();
+
+        let locations = (0..size.max(1))
+            .map(|element| {
+                let element_name = if size > 1 { format!("{}[{}]", base, element) } else { name.clone() };
+                let element_name = CString::new(element_name).unwrap_or_default();
+                unsafe { backend.glGetUniformLocation(program, element_name.as_ptr()) }
+            })
+            .collect();
+
+        uniforms.push(Uniform {
+            name: base,
+            type_,
+            locations,
+        });
+    }
+
+    Some(uniforms)
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enums::{BeginMode, FrameBufferTarget, PixelDataType, PixelFormat, ShaderType, TextureBindTarget, TextureTarget};
+    use es20::shader::{compile_shader, link_program};
+    use es20::software::SoftwareBackend;
+    use es20::wrapper::Wrapper;
+
+    const VERTEX: &str = "attribute vec2 position;\nvoid main() { gl_Position = vec4(position, 0.0, 1.0); }\n";
+    const FRAGMENT: &str = "precision mediump float;\nuniform vec4 color;\nuniform float weights[3];\n\
+                            void main() { gl_FragColor = color * (weights[0] + weights[2]); }\n";
+
+    fn validated() -> Wrapper {
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(4, 4)));
+        gl.add_interceptor(Box::new(Validator::new()));
+        gl
+    }
+
+    // A validated wrapper with a linked program in use.
+    fn with_program() -> (Wrapper, u32) {
+        let mut gl = validated();
+        let vertex = compile_shader(&mut gl, ShaderType::VERTEX_SHADER, VERTEX, None).unwrap();
+        let fragment = compile_shader(&mut gl, ShaderType::FRAGMENT_SHADER, FRAGMENT, None).unwrap();
+        let program = link_program(&mut gl, &[vertex, fragment], &[(0, "position".to_string())]).unwrap();
+        gl.gl_use_program(program).unwrap();
+        (gl, program)
+    }
+
+    fn broken(result: Result<(), Error>) -> String {
+        let error = result.unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Validation);
+        error.message.split(' ').nth(1).unwrap().to_string()
+    }
+
+    #[test]
+    fn draws_need_a_program() {
+        let mut gl = validated();
+        assert_eq!(broken(gl.gl_draw_arrays(BeginMode::TRIANGLES, 0, 3)), "no-program");
+        assert_eq!(broken(gl.gl_uniform1f(0, 1.0)), "no-program");
+    }
+
+    #[test]
+    fn attribute_indices_stay_below_the_maximum() {
+        let mut gl = validated();
+        assert_eq!(broken(gl.gl_enable_vertex_attrib_array(1000)), "attrib-index");
+        assert!(gl.gl_enable_vertex_attrib_array(0).is_ok());
+    }
+
+    #[test]
+    fn indices_must_cover_the_count() {
+        let (mut gl, _) = with_program();
+        assert_eq!(broken(gl.gl_draw_elements(BeginMode::TRIANGLES, 3, GL_UNSIGNED_SHORT, &[0u16, 1])),
+                   "indices-too-short");
+        assert!(gl.gl_draw_elements(BeginMode::TRIANGLES, 3, GL_UNSIGNED_SHORT, &[0u16, 1, 2]).is_ok());
+    }
+
+    #[test]
+    fn pixels_must_cover_the_image() {
+        // The wrapper checks its own uploads, so the call is handed to the validator directly.
+        let mut backend = SoftwareBackend::new(1, 1);
+        let upload = |pixels: Vec<u8>| Call::new("glTexImage2D", vec![
+            Value::Enum(GL_TEXTURE_2D), Value::Int(0), Value::Enum(GL_RGB), Value::Int(3), Value::Int(2),
+            Value::Int(0), Value::Enum(GL_RGB), Value::Enum(GL_UNSIGNED_BYTE), Value::Bytes(pixels),
+        ]);
+
+        let mut validator = Validator::new();
+        assert_eq!(broken(validator.intercept(&upload(vec![0; 18]), &mut backend)), "pixels-too-short");
+        assert!(validator.intercept(&upload(vec![0; 21]), &mut backend).is_ok());
+        assert!(validator.intercept(&upload(Vec::new()), &mut backend).is_ok());
+    }
+
+    #[test]
+    fn npot_textures_are_not_mipmapped() {
+        let mut gl = validated();
+        let texture = gl.gl_gen_textures(1).unwrap()[0];
+        gl.gl_bind_texture(TextureBindTarget::TEXTURE_2D, texture).unwrap();
+        // Nothing is known about a texture without an image.
+        assert!(gl.gl_generate_mipmap(TextureBindTarget::TEXTURE_2D).is_ok());
+
+        gl.gl_tex_image_2d(TextureTarget::TEXTURE_2D, 0, GL_RGBA as GLint, 3, 3, 0, PixelFormat::RGBA,
+                           PixelDataType::UNSIGNED_BYTE, &[] as &[u8]).unwrap();
+        assert_eq!(broken(gl.gl_generate_mipmap(TextureBindTarget::TEXTURE_2D)), "npot-texture");
+    }
+
+    #[test]
+    fn uniforms_match_type_and_array_size() {
+        let (mut gl, program) = with_program();
+        let color = gl.gl_get_uniform_location(program, "color").unwrap();
+        let weights = gl.gl_get_uniform_location(program, "weights[1]").unwrap();
+
+        assert_eq!(broken(gl.gl_uniform1f(color, 1.0)), "uniform-type");
+        assert!(gl.gl_uniform4f(color, 1.0, 1.0, 1.0, 1.0).is_ok());
+        assert_eq!(broken(gl.gl_uniform4fv(color, &[0.0; 8])), "uniform-type");
+        assert_eq!(broken(gl.gl_uniform1fv(weights, &[0.0; 3])), "uniform-type");
+        assert!(gl.gl_uniform1fv(weights, &[0.0; 2]).is_ok());
+    }
+
+    #[test]
+    fn draws_need_a_complete_framebuffer() {
+        let (mut gl, _) = with_program();
+        let framebuffer = gl.gl_gen_framebuffers(1).unwrap()[0];
+        gl.gl_bind_framebuffer(FrameBufferTarget::FrameBuffer, framebuffer).unwrap();
+        assert_eq!(broken(gl.gl_draw_arrays(BeginMode::TRIANGLES, 0, 3)), "incomplete-framebuffer");
+    }
+
+    #[test]
+    fn bound_objects_are_not_deleted() {
+        let (mut gl, program) = with_program();
+        let texture = gl.gl_gen_textures(1).unwrap()[0];
+        gl.gl_bind_texture(TextureBindTarget::TEXTURE_2D, texture).unwrap();
+
+        assert_eq!(broken(gl.gl_delete_textures(&[texture])), "delete-bound");
+        assert_eq!(broken(gl.gl_delete_program(program)), "delete-bound");
+        gl.gl_bind_texture(TextureBindTarget::TEXTURE_2D, 0).unwrap();
+        assert!(gl.gl_delete_textures(&[texture]).is_ok());
+    }
+
+    #[test]
+    fn disabled_rules_are_not_checked() {
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(1, 1)));
+        gl.add_interceptor(Box::new(Validator::new().without(Rule::NoProgram)));
+        assert!(gl.gl_draw_arrays(BeginMode::TRIANGLES, 0, 3).is_ok());
+    }
+}
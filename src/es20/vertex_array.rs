@@ -0,0 +1,410 @@
+use enums::{BufferTarget, ConstantType, DataType, StateType};
+use super::wrapper::{Error, Wrapper};
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+/// How `VertexArrays` puts a layout in place.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VertexArraySupport {
+    /// `GL_OES_vertex_array_object`.
+    Extension,
+    /// ES 3.0 core.
+    Core,
+    /// Neither: the attribute state is set on the context, changing only what differs from the
+    /// layout applied before.
+    Emulated,
+}
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// Where one attribute reads its data, as `gl_vertex_attrib_pointer_offset` takes it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Attribute {
+    pub buffer: u32,
+    pub size: i32,
+    pub type_: DataType,
+    pub normalized: bool,
+    pub stride: i32,
+    pub offset: u32,
+}
+
+// The enabled attributes, by index, and the element buffer.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Layout {
+    attributes: Vec<Option<Attribute>>,
+    element_buffer: u32,
+}
+
+impl Layout {
+    fn attribute(&self, index: usize) -> Option<Attribute> {
+        self.attributes.get(index).cloned().unwrap_or(None)
+    }
+}
+
+/// The attribute bindings and the element buffer of a draw.
+///
+/// Recording does not touch GL; `VertexArrays::bind` applies the layout. The buffers are not
+/// owned and must outlive the array.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VertexArray {
+    layout: Layout,
+    // The vertex array object holding the layout, 0 until first bound natively.
+    id: u32,
+    // What the vertex array object holds, which lags `layout` after changes.
+    applied: Layout,
+}
+
+impl VertexArray {
+    pub fn new() -> VertexArray {
+        VertexArray::default()
+    }
+
+    /// Enables attribute `index` and makes it read as `attribute` says.
+    pub fn set_attribute(&mut self, index: u32, attribute: Attribute) {
+        let index = index as usize;
+        if self.layout.attributes.len() <= index {
+            self.layout.attributes.resize(index + 1, None);
+        }
+        self.layout.attributes[index] = Some(attribute);
+    }
+
+    /// Disables attribute `index`.
+    pub fn disable_attribute(&mut self, index: u32) {
+        if let Some(attribute) = self.layout.attributes.get_mut(index as usize) {
+            *attribute = None;
+        }
+        while self.layout.attributes.last() == Some(&None) {
+            self.layout.attributes.pop();
+        }
+    }
+
+    /// The buffer indices are read from, 0 for none.
+    pub fn set_element_buffer(&mut self, buffer: u32) {
+        self.layout.element_buffer = buffer;
+    }
+
+    pub fn attribute(&self, index: u32) -> Option<Attribute> {
+        self.layout.attribute(index as usize)
+    }
+
+    pub fn element_buffer(&self) -> u32 {
+        self.layout.element_buffer
+    }
+}
+
+/// Applies `VertexArray`s to a context.
+///
+/// Vertex array objects are used where the driver has them, created when an array is first bound
+/// and updated with what changed since. Without them the attribute state of the context is
+/// diffed against the layout applied before, so drawing repeatedly with the same array costs no
+/// calls at all.
+///
+/// The applied layout is only known while attribute state and the element buffer binding change
+/// through here; call `invalidate` after changing them directly. The `ARRAY_BUFFER` binding is
+/// not tracked and is left at whatever buffer was needed last.
+pub struct VertexArrays {
+    support: VertexArraySupport,
+    max_attributes: usize,
+    // Emulated: the layout on the context, `None` when it is not known.
+    applied: Option<Layout>,
+    // Natively: the bound vertex array object, `!0` when it is not known.
+    bound: u32,
+}
+
+impl VertexArrays {
+    /// Uses vertex array objects if the driver behind `gl` has them.
+    pub fn new(gl: &mut Wrapper) -> Result<VertexArrays, Error> {
+        let extensions = gl.gl_get_string(ConstantType::EXTENSIONS)?;
+
+        let mut support = if extensions.split_whitespace().any(|name| name == "GL_OES_vertex_array_object") {
+            VertexArraySupport::Extension
+        } else if gl.es_major_version()? >= 3 {
+            VertexArraySupport::Core
+        } else {
+            VertexArraySupport::Emulated
+        };
+        // Advertised, but the library does not have the functions.
+        let names = match support {
+            VertexArraySupport::Extension => ["glBindVertexArrayOES", "glDeleteVertexArraysOES", "glGenVertexArraysOES"],
+            _ => ["glBindVertexArray", "glDeleteVertexArrays", "glGenVertexArrays"],
+        };
+        if support != VertexArraySupport::Emulated && !names.iter().all(|name| gl.has_entry_point(name)) {
+            support = VertexArraySupport::Emulated;
+        }
+        VertexArrays::with_support(gl, support)
+    }
+
+    /// Always emulates, e.g. to share the attribute state with code that does not use vertex
+    /// array objects.
+    pub fn emulated(gl: &mut Wrapper) -> Result<VertexArrays, Error> {
+        VertexArrays::with_support(gl, VertexArraySupport::Emulated)
+    }
+
+    fn with_support(gl: &mut Wrapper, support: VertexArraySupport) -> Result<VertexArrays, Error> {
+        let max_attributes = gl.gl_get_integerv(StateType::MAX_VERTEX_ATTRIBS)?.max(0) as usize;
+        Ok(VertexArrays {
+            support,
+            max_attributes,
+            applied: None,
+            bound: !0,
+        })
+    }
+
+    pub fn support(&self) -> VertexArraySupport {
+        self.support
+    }
+
+    /// Makes `array` the layout for the following draws.
+    pub fn bind(&mut self, gl: &mut Wrapper, array: &mut VertexArray) -> Result<(), Error> {
+        if self.support == VertexArraySupport::Emulated {
+            // Forgotten first, so that a failure part way leaves the state unknown.
+            let applied = self.applied.take();
+            match applied {
+                Some(ref applied) => apply(gl, applied, &array.layout)?,
+                None => reset(gl, &array.layout, self.max_attributes)?,
+            }
+            self.applied = Some(array.layout.clone());
+            return Ok(());
+        }
+
+        if array.id == 0 {
+            array.id = self.gen(gl)?;
+            // A new vertex array object has every attribute disabled and no element buffer.
+            array.applied = Layout::default();
+        }
+        if self.bound != array.id {
+            self.bind_object(gl, array.id)?;
+        }
+        if array.applied != array.layout {
+            apply(gl, &array.applied, &array.layout)?;
+            array.applied = array.layout.clone();
+        }
+        Ok(())
+    }
+
+    /// Returns to the default vertex array, which emulation never leaves.
+    pub fn unbind(&mut self, gl: &mut Wrapper) -> Result<(), Error> {
+        if self.support != VertexArraySupport::Emulated && self.bound != 0 {
+            self.bind_object(gl, 0)?;
+        }
+        Ok(())
+    }
+
+    /// Forgets what is applied, so that the next `bind` sets it again.
+    pub fn invalidate(&mut self) {
+        self.applied = None;
+        self.bound = !0;
+    }
+
+    /// Deletes the vertex array object of `array`, if it has one.
+    pub fn delete(&mut self, gl: &mut Wrapper, array: VertexArray) -> Result<(), Error> {
+        if array.id == 0 {
+            return Ok(());
+        }
+        match self.support {
+            VertexArraySupport::Extension => gl.gl_delete_vertex_arrays_oes(&[array.id])?,
+            _ => gl.gl_delete_vertex_arrays(&[array.id])?,
+        }
+        // Deleting the bound object binds 0.
+        if self.bound == array.id {
+            self.bound = 0;
+        }
+        Ok(())
+    }
+
+    fn gen(&mut self, gl: &mut Wrapper) -> Result<u32, Error> {
+        let ids = match self.support {
+            VertexArraySupport::Extension => gl.gl_gen_vertex_arrays_oes(1)?,
+            _ => gl.gl_gen_vertex_arrays(1)?,
+        };
+        Ok(ids[0])
+    }
+
+    fn bind_object(&mut self, gl: &mut Wrapper, id: u32) -> Result<(), Error> {
+        match self.support {
+            VertexArraySupport::Extension => gl.gl_bind_vertex_array_oes(id)?,
+            _ => gl.gl_bind_vertex_array(id)?,
+        }
+        self.bound = id;
+        Ok(())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// FUNCTIONS
+// -------------------------------------------------------------------------------------------------
+
+// Changes the bound attribute state from `from` to `to` with as few calls as it takes. Pointers
+// are grouped by buffer to save `ARRAY_BUFFER` binds.
+fn apply(gl: &mut Wrapper, from: &Layout, to: &Layout) -> Result<(), Error> {
+    let count = from.attributes.len().max(to.attributes.len());
+    let changed: Vec<(usize, Attribute)> = (0..count)
+        .filter_map(|index| match to.attribute(index) {
+            Some(attribute) if from.attribute(index) != Some(attribute) => Some((index, attribute)),
+            _ => None,
+        })
+        .collect();
+    set_pointers(gl, changed)?;
+
+    for index in 0..count {
+        match (from.attribute(index), to.attribute(index)) {
+            (None, Some(_)) => gl.gl_enable_vertex_attrib_array(index as u32)?,
+            (Some(_), None) => gl.gl_disable_vertex_attrib_array(index as u32)?,
+            _ => {}
+        }
+    }
+
+    if from.element_buffer != to.element_buffer {
+        gl.gl_bind_buffer(BufferTarget::ELEMENT_ARRAY_BUFFER, to.element_buffer)?;
+    }
+    Ok(())
+}
+
+// Sets `to` on a context in an unknown state, disabling every other of the `max_attributes`.
+fn reset(gl: &mut Wrapper, to: &Layout, max_attributes: usize) -> Result<(), Error> {
+    let enabled: Vec<(usize, Attribute)> = to.attributes.iter()
+        .enumerate()
+        .filter_map(|(index, attribute)| attribute.map(|attribute| (index, attribute)))
+        .collect();
+    set_pointers(gl, enabled)?;
+
+    for index in 0..max_attributes.max(to.attributes.len()) {
+        match to.attribute(index) {
+            Some(_) => gl.gl_enable_vertex_attrib_array(index as u32)?,
+            None => gl.gl_disable_vertex_attrib_array(index as u32)?,
+        }
+    }
+    gl.gl_bind_buffer(BufferTarget::ELEMENT_ARRAY_BUFFER, to.element_buffer)
+}
+
+fn set_pointers(gl: &mut Wrapper, mut attributes: Vec<(usize, Attribute)>) -> Result<(), Error> {
+    attributes.sort_by_key(|&(index, attribute)| (attribute.buffer, index));
+
+    let mut array_buffer = None;
+    for (index, attribute) in attributes {
+        if array_buffer != Some(attribute.buffer) {
+            gl.gl_bind_buffer(BufferTarget::ARRAY_BUFFER, attribute.buffer)?;
+            array_buffer = Some(attribute.buffer);
+        }
+        gl.gl_vertex_attrib_pointer_offset(index as u32, attribute.size, attribute.type_, attribute.normalized,
+                                           attribute.stride, attribute.offset)?;
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use enums::VertexAttributeParamType;
+    use es20::backend::Backend;
+    use es20::call::Call;
+    use es20::software::SoftwareBackend;
+    use es20::wrapper::Interceptor;
+
+    // Keeps the names of the calls made through the wrapper.
+    struct Names(Rc<RefCell<Vec<&'static str>>>);
+
+    impl Interceptor for Names {
+        fn intercept(&mut self, call: &Call, _backend: &mut dyn Backend) -> Result<(), Error> {
+            self.0.borrow_mut().push(call.name);
+            Ok(())
+        }
+    }
+
+    fn context() -> (Wrapper, Vec<u32>, Rc<RefCell<Vec<&'static str>>>) {
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(1, 1)));
+        let buffers = gl.gl_gen_buffers(2).unwrap();
+        let names = Rc::new(RefCell::new(Vec::new()));
+        gl.add_interceptor(Box::new(Names(names.clone())));
+        (gl, buffers, names)
+    }
+
+    fn attribute(buffer: u32, offset: u32) -> Attribute {
+        Attribute { buffer, size: 2, type_: DataType::FLOAT, normalized: false, stride: 8, offset }
+    }
+
+    fn enabled(gl: &mut Wrapper, index: u32) -> bool {
+        gl.gl_get_vertex_attribiv(index, VertexAttributeParamType::VERTEX_ATTRIB_ARRAY_ENABLED).unwrap() != 0
+    }
+
+    fn taken(names: &Rc<RefCell<Vec<&'static str>>>) -> Vec<&'static str> {
+        names.borrow_mut().drain(..).collect()
+    }
+
+    #[test]
+    fn emulation_only_changes_what_differs() {
+        let (mut gl, buffers, names) = context();
+        let mut arrays = VertexArrays::emulated(&mut gl).unwrap();
+        assert_eq!(arrays.support(), VertexArraySupport::Emulated);
+
+        let mut first = VertexArray::new();
+        first.set_attribute(0, attribute(buffers[0], 0));
+        first.set_attribute(1, attribute(buffers[1], 0));
+        first.set_element_buffer(buffers[1]);
+
+        arrays.bind(&mut gl, &mut first).unwrap();
+        assert!(enabled(&mut gl, 0) && enabled(&mut gl, 1) && !enabled(&mut gl, 2));
+        assert_eq!(gl.gl_get_vertex_attribiv(1, VertexAttributeParamType::VERTEX_ATTRIB_ARRAY_BUFFER_BINDING)
+                       .unwrap(), buffers[1] as i32);
+        assert_eq!(gl.gl_get_integerv(StateType::ELEMENT_ARRAY_BUFFER_BINDING).unwrap(), buffers[1] as i32);
+        taken(&names);
+
+        arrays.bind(&mut gl, &mut first).unwrap();
+        assert!(taken(&names).is_empty());
+
+        let mut second = VertexArray::new();
+        second.set_attribute(1, attribute(buffers[1], 0));
+        arrays.bind(&mut gl, &mut second).unwrap();
+        assert_eq!(taken(&names), vec!["glDisableVertexAttribArray", "glBindBuffer"]);
+        assert!(!enabled(&mut gl, 0) && enabled(&mut gl, 1));
+        assert_eq!(gl.gl_get_integerv(StateType::ELEMENT_ARRAY_BUFFER_BINDING).unwrap(), 0);
+        taken(&names);
+
+        // Changed behind its back: everything is set again.
+        arrays.invalidate();
+        arrays.bind(&mut gl, &mut second).unwrap();
+        assert!(taken(&names).contains(&"glVertexAttribPointer"));
+    }
+
+    #[test]
+    fn vertex_array_objects_are_created_once_and_updated() {
+        let (mut gl, buffers, names) = context();
+        let mut arrays = VertexArrays::new(&mut gl).unwrap();
+        assert_eq!(arrays.support(), VertexArraySupport::Extension);
+
+        let mut first = VertexArray::new();
+        first.set_attribute(0, attribute(buffers[0], 0));
+        let mut second = VertexArray::new();
+        second.set_attribute(2, attribute(buffers[1], 8));
+
+        arrays.bind(&mut gl, &mut first).unwrap();
+        arrays.bind(&mut gl, &mut second).unwrap();
+        assert!(!enabled(&mut gl, 0) && enabled(&mut gl, 2));
+        taken(&names);
+
+        // Switching back only binds the object, which still holds the layout.
+        arrays.bind(&mut gl, &mut first).unwrap();
+        assert_eq!(taken(&names), vec!["glBindVertexArrayOES"]);
+        assert!(enabled(&mut gl, 0) && !enabled(&mut gl, 2));
+        taken(&names);
+
+        first.disable_attribute(0);
+        arrays.bind(&mut gl, &mut first).unwrap();
+        assert_eq!(taken(&names), vec!["glDisableVertexAttribArray"]);
+
+        arrays.delete(&mut gl, first).unwrap();
+        arrays.unbind(&mut gl).unwrap();
+        assert_eq!(taken(&names), vec!["glDeleteVertexArraysOES"]);
+    }
+}
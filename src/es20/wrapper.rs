@@ -1,12 +1,17 @@
 use std;
 use std::ffi::CStr;
 use std::ffi::CString;
-use std::mem::size_of;
+use std::fmt;
+use std::mem::{size_of, size_of_val};
 use std::str::from_utf8;
 
 use libc::{c_char};
 
-use super::ffi;
+use super::backend::Backend;
+use super::backend::NativeBackend;
+use super::call::Call;
+use super::call::ObjectKind;
+use super::call::Value;
 use types::*;
 use consts::*;
 use enums::TextureUnit;
@@ -36,6 +41,7 @@ use enums::ShaderParamType;
 use enums::ShaderPrecisionType;
 use enums::ConstantType;
 use enums::TextureParamType;
+use enums::TextureParameter;
 use enums::VertexAttributeParamType;
 use enums::HintTargetType;
 use enums::HintBehaviorType;
@@ -44,6 +50,8 @@ use enums::PixelFormat;
 use enums::PixelDataType;
 use enums::ActionType;
 use enums::DataType;
+use enums::ShaderBinaryFormat;
+use format::FormatInfo;
 
 // -------------------------------------------------------------------------------------------------
 // STRUCTS
@@ -61,200 +69,507 @@ pub struct ShaderPrecisionFormat {
     pub range: [i32; 2],
 }
 
+/// The pointer and length arrays `gl_shader_source_with` passes to `glShaderSource`, kept by the
+/// caller so that setting sources again, e.g. when hot-reloading shaders, does not allocate.
+#[derive(Debug, Default)]
+pub struct ShaderSourceArrays {
+    pointers: Vec<*const GLchar>,
+    lengths: Vec<GLint>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ErrorKind {
+    /// The driver returned something the wrapper can not make sense of.
+    InvalidResult,
+    /// Reading or writing a file or stream failed.
+    Io,
+    /// A file or stream is not in the expected format.
+    Format,
+    /// The arguments of a call do not fit together, e.g. a buffer is too small for the image it
+    /// is supposed to hold. The call was not issued.
+    InvalidArgument,
+    /// The device lacks a format or extension the operation needs.
+    Unsupported,
+    /// A call broke a rule checked by `validation::Validator` and was not issued.
+    Validation,
+    /// A shader failed to compile or a program failed to link. The message is the info log.
+    Compile,
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Error {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new<S: Into<String>>(kind: ErrorKind, message: S) -> Error {
+        Error {
+            kind,
+            message: message.into(),
+        }
+    }
+}
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::new(ErrorKind::Io, error.to_string())
+    }
 }
 
 pub struct Wrapper {
+    backend: Box<dyn Backend>,
+    interceptors: Vec<Box<dyn Interceptor>>,
+    frame: u64,
 }
 
-trait Interceptor {
-    fn intercept(&mut self, fun_name: &str) -> Result<(), Error>;
+/// Observes, and optionally vetoes, every call made through a `Wrapper`.
+pub trait Interceptor {
+    /// Called before `call` reaches the backend. Returning an error skips the call and hands the
+    /// error to the caller.
+    fn intercept(&mut self, call: &Call, backend: &mut dyn Backend) -> Result<(), Error>;
+
+    /// Called after the backend executed `call`, with `call.ret` filled in.
+    fn completed(&mut self, _call: &Call, _backend: &mut dyn Backend) {}
+
+    /// Called by `Wrapper::end_frame` once the calls of `frame` have been issued.
+    fn end_frame(&mut self, _frame: u64) {}
+
+    /// Hands over an error the interceptor read with `glGetError` and so cleared in the backend.
+    /// `Wrapper::gl_get_error` reports such errors before asking the backend.
+    fn take_error(&mut self) -> Option<GLenum> {
+        None
+    }
+}
+
+impl Default for Wrapper {
+    fn default() -> Self {
+        Wrapper::new()
+    }
 }
 
 impl Wrapper {
+    /// Creates a wrapper that calls the platform GL library.
+    pub fn new() -> Wrapper {
+        Wrapper::with_backend(Box::new(NativeBackend))
+    }
+
+    pub fn with_backend(backend: Box<dyn Backend>) -> Wrapper {
+        Wrapper {
+            backend,
+            interceptors: Vec::new(),
+            frame: 0,
+        }
+    }
+
+    /// Interceptors run in the order they were added.
+    pub fn add_interceptor(&mut self, interceptor: Box<dyn Interceptor>) {
+        self.interceptors.push(interceptor);
+    }
+
+    pub fn take_interceptors(&mut self) -> Vec<Box<dyn Interceptor>> {
+        std::mem::take(&mut self.interceptors)
+    }
+
+    /// Marks the end of a frame, typically right before the platform's buffer swap.
+    ///
+    /// GL itself has no notion of frames, so interceptors rely on this to delimit them.
+    pub fn end_frame(&mut self) {
+        for interceptor in self.interceptors.iter_mut() {
+            interceptor.end_frame(self.frame);
+        }
+
+        self.frame += 1;
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Whether the function `name`, beyond ES 2.0 core, can be called. The ones that cannot fail
+    /// with `ErrorKind::Unsupported`.
+    pub fn has_entry_point(&self, name: &str) -> bool {
+        self.backend.has_entry_point(name)
+    }
+
+    /// The major version of the context, e.g. 3 for `OpenGL ES 3.2 ...`, 0 if `GL_VERSION` is not an
+    /// ES version string.
+    pub fn es_major_version(&mut self) -> Result<u32, Error> {
+        let version = self.gl_get_string(ConstantType::VERSION)?;
+        Ok(version.strip_prefix("OpenGL ES ")
+            .and_then(|rest| rest.split('.').next())
+            .and_then(|major| major.parse().ok())
+            .unwrap_or(0))
+    }
+
+    fn check_entry_point(&self, name: &'static str) -> Result<(), Error> {
+        if !self.has_entry_point(name) {
+            return Err(Error::new(ErrorKind::Unsupported, format!("{}: not available from the driver", name)));
+        }
+        Ok(())
+    }
+
+    // Reads state the wrapper needs itself, bypassing the interceptors.
+    fn get_integer(&mut self, name: GLenum) -> GLint {
+        let mut value: GLint = 0;
+        unsafe { self.backend.glGetIntegerv(name, &mut value) }
+        value
+    }
+
+    // The argument list is only built when someone is listening, so the common path does not
+    // copy client memory.
+    fn begin<F>(&mut self, name: &'static str, args: F) -> Result<Option<Call>, Error>
+        where F: FnOnce() -> Vec<Value> {
+        if self.interceptors.is_empty() {
+            return Ok(None);
+        }
+
+        let call = Call::new(name, args());
+
+        for interceptor in self.interceptors.iter_mut() {
+            interceptor.intercept(&call, self.backend.as_mut())?;
+        }
+
+        Ok(Some(call))
+    }
+
+    fn end<F>(&mut self, call: Option<Call>, ret: F) where F: FnOnce() -> Value {
+        if let Some(mut call) = call {
+            call.ret = ret();
+
+            for interceptor in self.interceptors.iter_mut() {
+                interceptor.completed(&call, self.backend.as_mut());
+            }
+        }
+    }
+
     pub fn gl_active_texture(&mut self, texture_unit: TextureUnit) -> Result<(), Error> {
+        let call = self.begin("glActiveTexture", || vec![Value::Enum(texture_unit as GLenum)])?;
+
         unsafe {
-            ffi::glActiveTexture(texture_unit as GLenum);
+            self.backend.glActiveTexture(texture_unit as GLenum);
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_attach_shader(&mut self, program: u32, shader: u32) -> Result<(), Error> {
+        let call = self.begin("glAttachShader", || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Name(ObjectKind::Shader, shader),
+        ])?;
+
         unsafe {
-            ffi::glAttachShader(program as GLuint, shader as GLuint);
+            self.backend.glAttachShader(program as GLuint, shader as GLuint);
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_bind_attrib_location(&mut self, program: u32, index: u32, name: &str) -> Result<(), Error> {
+        let call = self.begin("glBindAttribLocation", || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Uint(index),
+            Value::Str(name.to_string()),
+        ])?;
+
         unsafe {
             let c_str = CString::new(name).unwrap();
 
-            ffi::glBindAttribLocation(program as GLuint, index as GLuint,
-                                      c_str.as_ptr() as *const GLchar);
+            self.backend.glBindAttribLocation(program as GLuint, index as GLuint,
+                                              c_str.as_ptr() as *const GLchar);
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_bind_buffer(&mut self, target: BufferTarget, buffer: GLuint) -> Result<(), Error> {
+        let call = self.begin("glBindBuffer", || vec![
+            Value::Enum(target as GLenum),
+            Value::Name(ObjectKind::Buffer, buffer),
+        ])?;
+
         unsafe {
-            ffi::glBindBuffer(target as GLenum, buffer as GLuint);
+            self.backend.glBindBuffer(target as GLenum, buffer as GLuint);
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_bind_framebuffer(&mut self, target: FrameBufferTarget, framebuffer: GLuint) -> Result<(), Error> {
+        let call = self.begin("glBindFramebuffer", || vec![
+            Value::Enum(target as GLenum),
+            Value::Name(ObjectKind::Framebuffer, framebuffer),
+        ])?;
+
         unsafe {
-            ffi::glBindFramebuffer(target as GLenum, framebuffer as GLuint);
+            self.backend.glBindFramebuffer(target as GLenum, framebuffer as GLuint);
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_bind_renderbuffer(&mut self, target: RenderBufferTarget, renderbuffer: u32) -> Result<(), Error> {
+        let call = self.begin("glBindRenderbuffer", || vec![
+            Value::Enum(target as GLenum),
+            Value::Name(ObjectKind::Renderbuffer, renderbuffer),
+        ])?;
+
         unsafe {
-            ffi::glBindRenderbuffer(target as GLenum, renderbuffer as GLuint);
+            self.backend.glBindRenderbuffer(target as GLenum, renderbuffer as GLuint);
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_bind_texture(&mut self, target: TextureBindTarget, texture: u32) -> Result<(), Error> {
+        let call = self.begin("glBindTexture", || vec![
+            Value::Enum(target as GLenum),
+            Value::Name(ObjectKind::Texture, texture),
+        ])?;
+
         unsafe {
-            ffi::glBindTexture(target as GLenum, texture as GLuint)
+            self.backend.glBindTexture(target as GLenum, texture as GLuint)
         }
 
+        self.end(call, || Value::Void);
+        Ok(())
+    }
+
+    /// Binds vertex array object `array`, 0 for the default one. ES 3.0;
+    /// `gl_bind_vertex_array_oes` is the `GL_OES_vertex_array_object` version.
+    pub fn gl_bind_vertex_array(&mut self, array: u32) -> Result<(), Error> {
+        self.bind_vertex_array("glBindVertexArray", array)
+    }
+
+    pub fn gl_bind_vertex_array_oes(&mut self, array: u32) -> Result<(), Error> {
+        self.bind_vertex_array("glBindVertexArrayOES", array)
+    }
+
+    fn bind_vertex_array(&mut self, name: &'static str, array: u32) -> Result<(), Error> {
+        self.check_entry_point(name)?;
+        let call = self.begin(name, || vec![Value::Name(ObjectKind::VertexArray, array)])?;
+
+        unsafe {
+            if name == "glBindVertexArrayOES" {
+                self.backend.glBindVertexArrayOES(array as GLuint)
+            } else {
+                self.backend.glBindVertexArray(array as GLuint)
+            }
+        }
+
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_blend_color(&mut self, red: f32, green: f32, blue: f32,
                           alpha: f32) -> Result<(), Error> {
+        let call = self.begin("glBlendColor", || vec![
+            Value::Float(red), Value::Float(green), Value::Float(blue), Value::Float(alpha),
+        ])?;
+
         unsafe {
-            ffi::glBlendColor(red as GLclampf, green as GLclampf,
-                              blue as GLclampf, alpha as GLclampf)
+            self.backend.glBlendColor(red as GLclampf, green as GLclampf,
+                                      blue as GLclampf, alpha as GLclampf)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_blend_equation(&mut self, mode: BlendEquationMode) -> Result<(), Error> {
+        let call = self.begin("glBlendEquation", || vec![Value::Enum(mode as GLenum)])?;
+
         unsafe {
-            ffi::glBlendEquation(mode as GLenum)
+            self.backend.glBlendEquation(mode as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_blend_equation_separate(&mut self, mode_rgb: BlendEquationMode, mode_alpha: BlendEquationMode)
                                       -> Result<(), Error> {
+        let call = self.begin("glBlendEquationSeparate", || vec![
+            Value::Enum(mode_rgb as GLenum),
+            Value::Enum(mode_alpha as GLenum),
+        ])?;
+
         unsafe {
-            ffi::glBlendEquationSeparate(mode_rgb as GLenum, mode_alpha as GLenum)
+            self.backend.glBlendEquationSeparate(mode_rgb as GLenum, mode_alpha as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_blend_func(&mut self, src_factor: BlendFactor, dst_factor: BlendFactor) -> Result<(), Error> {
+        let call = self.begin("glBlendFunc", || vec![
+            Value::Enum(src_factor as GLenum),
+            Value::Enum(dst_factor as GLenum),
+        ])?;
+
         unsafe {
-            ffi::glBlendFunc(src_factor as GLenum, dst_factor as GLenum)
+            self.backend.glBlendFunc(src_factor as GLenum, dst_factor as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_blend_func_separate(&mut self, src_rgb: BlendFactor, dst_rgb: BlendFactor,
                                   src_alpha: BlendFactor, dst_alpha: BlendFactor) -> Result<(), Error> {
+        let call = self.begin("glBlendFuncSeparate", || vec![
+            Value::Enum(src_rgb as GLenum),
+            Value::Enum(dst_rgb as GLenum),
+            Value::Enum(src_alpha as GLenum),
+            Value::Enum(dst_alpha as GLenum),
+        ])?;
+
         unsafe {
-            ffi::glBlendFuncSeparate(src_rgb as GLenum, dst_rgb as GLenum,
-                                     src_alpha as GLenum, dst_alpha as GLenum)
+            self.backend.glBlendFuncSeparate(src_rgb as GLenum, dst_rgb as GLenum,
+                                             src_alpha as GLenum, dst_alpha as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_buffer_data<T>(&mut self, target: BufferTarget,
                              buffer: &[T], usage: BufferUsage) -> Result<(), Error> where T: std::fmt::Debug + Clone {
-        unsafe {
-            let t_size = size_of::<T>();
+        let call = self.begin("glBufferData", || vec![
+            Value::Enum(target as GLenum),
+            Value::Int(std::mem::size_of_val(buffer) as i32),
+            Value::bytes(buffer),
+            Value::Enum(usage as GLenum),
+        ])?;
 
-            ffi::glBufferData(
+        unsafe {
+            self.backend.glBufferData(
                 target as GLenum,
-                (buffer.len() * t_size) as GLsizeiptr,
+                std::mem::size_of_val(buffer) as GLsizeiptr,
                 buffer.as_ptr() as *const GLvoid,
                 usage as GLenum,
             )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_buffer_sub_data<T>(&mut self, target: BufferTarget, offset: u32, buffer: &[T])
                                  -> Result<(), Error> where T: std::fmt::Debug + Clone {
-        unsafe {
-            let t_size = size_of::<T>();
+        let t_size = size_of::<T>();
+
+        let call = self.begin("glBufferSubData", || vec![
+            Value::Enum(target as GLenum),
+            Value::Int((offset * (t_size as u32)) as i32),
+            Value::Int(std::mem::size_of_val(buffer) as i32),
+            Value::bytes(buffer),
+        ])?;
 
-            ffi::glBufferSubData(
+        unsafe {
+            self.backend.glBufferSubData(
                 target as GLenum,
                 (offset * (t_size as u32)) as GLintptr,
-                (buffer.len() * t_size) as GLsizeiptr,
+                std::mem::size_of_val(buffer) as GLsizeiptr,
                 buffer.as_ptr() as *const GLvoid,
             )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
+    /// Unknown statuses, and the 0 returned when the call itself fails, are `InvalidResult` errors.
     pub fn gl_check_framebuffer_status(&mut self, target: FrameBufferTarget) -> Result<FrameBufferStatus, Error> {
-        unsafe {
-            let status = ffi::glCheckFramebufferStatus(target as GLenum);
+        let call = self.begin("glCheckFramebufferStatus", || vec![Value::Enum(target as GLenum)])?;
+
+        let status = unsafe {
+            self.backend.glCheckFramebufferStatus(target as GLenum)
+        };
 
-            Ok(FrameBufferStatus::from(status))
+        self.end(call, || Value::Enum(status));
+
+        match FrameBufferStatus::from_raw(status) {
+            Some(status) => Ok(status),
+            None if status == 0 => Err(Error::new(ErrorKind::InvalidResult,
+                                                  "glCheckFramebufferStatus failed, see glGetError")),
+            None => Err(Error::new(ErrorKind::InvalidResult,
+                                   format!("unknown framebuffer status 0x{:04X}", status))),
         }
     }
 
     pub fn gl_clear(&mut self, mask: u32) -> Result<(), Error> {
+        let call = self.begin("glClear", || vec![Value::Bitfield(mask)])?;
+
         unsafe {
-            ffi::glClear(mask as GLbitfield)
+            self.backend.glClear(mask as GLbitfield)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_clear_color(&mut self, red: f32, green: f32,
                           blue: f32, alpha: f32) -> Result<(), Error> {
+        let call = self.begin("glClearColor", || vec![
+            Value::Float(red), Value::Float(green), Value::Float(blue), Value::Float(alpha),
+        ])?;
+
         unsafe {
-            ffi::glClearColor(red as GLclampf, green as GLclampf, blue as GLclampf,
-                              alpha as GLclampf)
+            self.backend.glClearColor(red as GLclampf, green as GLclampf, blue as GLclampf,
+                                      alpha as GLclampf)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_clear_depthf(&mut self, depth: f32) -> Result<(), Error> {
+        let call = self.begin("glClearDepthf", || vec![Value::Float(depth)])?;
+
         unsafe {
-            ffi::glClearDepthf(depth as GLclampf)
+            self.backend.glClearDepthf(depth as GLclampf)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_clear_stencil(&mut self, stencil: i32) -> Result<(), Error> {
+        let call = self.begin("glClearStencil", || vec![Value::Int(stencil)])?;
+
         unsafe {
-            ffi::glClearStencil(stencil as GLint)
+            self.backend.glClearStencil(stencil as GLint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
 
     pub fn gl_color_mask(&mut self, red: bool, green: bool, blue: bool, alpha: bool) -> Result<(), Error> {
+        let call = self.begin("glColorMask", || vec![
+            Value::Bool(red), Value::Bool(green), Value::Bool(blue), Value::Bool(alpha),
+        ])?;
+
         unsafe {
-            ffi::glColorMask(
+            self.backend.glColorMask(
                 red as GLboolean,
                 green as GLboolean,
                 blue as GLboolean,
@@ -262,14 +577,18 @@ impl Wrapper {
             )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_compile_shader(&mut self, shader: u32) -> Result<(), Error> {
+        let call = self.begin("glCompileShader", || vec![Value::Name(ObjectKind::Shader, shader)])?;
+
         unsafe {
-            ffi::glCompileShader(shader as GLuint)
+            self.backend.glCompileShader(shader as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
@@ -293,8 +612,21 @@ impl Wrapper {
         image_size: u32,
         buffer: &[T],
     ) -> Result<(), Error> where T: std::fmt::Debug + Clone {
+        check_compressed_size("glCompressedTexImage2D", internal_format, width, height, image_size, buffer)?;
+
+        let call = self.begin("glCompressedTexImage2D", || vec![
+            Value::Enum(target as GLenum),
+            Value::Int(level),
+            Value::Enum(internal_format),
+            Value::Int(width as i32),
+            Value::Int(height as i32),
+            Value::Int(border as i32),
+            Value::Int(image_size as i32),
+            Value::bytes(buffer),
+        ])?;
+
         unsafe {
-            ffi::glCompressedTexImage2D(
+            self.backend.glCompressedTexImage2D(
                 target as GLenum,
                 level as GLint,
                 internal_format,
@@ -306,6 +638,7 @@ impl Wrapper {
             )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
@@ -321,8 +654,22 @@ impl Wrapper {
         image_size: u32,
         buffer: &[T],
     ) -> Result<(), Error> where T: std::fmt::Debug + Clone {
-        unsafe {
-            ffi::glCompressedTexSubImage2D(
+        check_compressed_size("glCompressedTexSubImage2D", format, width, height, image_size, buffer)?;
+
+        let call = self.begin("glCompressedTexSubImage2D", || vec![
+            Value::Enum(target as GLenum),
+            Value::Int(level as i32),
+            Value::Int(x_offset as i32),
+            Value::Int(y_offset as i32),
+            Value::Int(width as i32),
+            Value::Int(height as i32),
+            Value::Enum(format),
+            Value::Int(image_size as i32),
+            Value::bytes(buffer),
+        ])?;
+
+        unsafe {
+            self.backend.glCompressedTexSubImage2D(
                 target as GLenum,
                 level as GLint,
                 x_offset as GLint,
@@ -335,6 +682,7 @@ impl Wrapper {
             )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
@@ -349,14 +697,26 @@ impl Wrapper {
         height: u32,
         border: u32,
     ) -> Result<(), Error> {
+        let call = self.begin("glCopyTexImage2D", || vec![
+            Value::Enum(target as GLenum),
+            Value::Int(level as i32),
+            Value::Enum(internal_format),
+            Value::Int(x as i32),
+            Value::Int(y as i32),
+            Value::Int(width as i32),
+            Value::Int(height as i32),
+            Value::Int(border as i32),
+        ])?;
+
         unsafe {
-            ffi::glCopyTexImage2D(target as GLenum,
-                                  level as GLint,
-                                  internal_format,
-                                  x as GLint, y as GLint,
-                                  width as GLsizei, height as GLsizei, border as GLint)
+            self.backend.glCopyTexImage2D(target as GLenum,
+                                          level as GLint,
+                                          internal_format,
+                                          x as GLint, y as GLint,
+                                          width as GLsizei, height as GLsizei, border as GLint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
@@ -371,186 +731,330 @@ impl Wrapper {
         width: u32,
         height: u32,
     ) -> Result<(), Error> {
+        let call = self.begin("glCopyTexSubImage2D", || vec![
+            Value::Enum(target as GLenum),
+            Value::Int(level as i32),
+            Value::Int(x_offset as i32),
+            Value::Int(y_offset as i32),
+            Value::Int(x as i32),
+            Value::Int(y as i32),
+            Value::Int(width as i32),
+            Value::Int(height as i32),
+        ])?;
+
         unsafe {
-            ffi::glCopyTexSubImage2D(target as GLenum,
-                                     level as GLint,
-                                     x_offset as GLint, y_offset as GLint,
-                                     x as GLint, y as GLint,
-                                     width as GLsizei, height as GLsizei)
+            self.backend.glCopyTexSubImage2D(target as GLenum,
+                                             level as GLint,
+                                             x_offset as GLint, y_offset as GLint,
+                                             x as GLint, y as GLint,
+                                             width as GLsizei, height as GLsizei)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_create_program(&mut self) -> Result<u32, Error> {
-        unsafe {
-            let program_id = ffi::glCreateProgram();
+        let call = self.begin("glCreateProgram", Vec::new)?;
 
-            Ok(program_id as u32)
-        }
+        let program_id = unsafe {
+            self.backend.glCreateProgram()
+        };
+
+        self.end(call, || Value::Name(ObjectKind::Program, program_id));
+        Ok(program_id)
     }
 
     pub fn gl_create_shader(&mut self, type_: ShaderType) -> Result<u32, Error> {
-        unsafe {
-            let shader_id = ffi::glCreateShader(type_ as GLenum);
+        let call = self.begin("glCreateShader", || vec![Value::Enum(type_ as GLenum)])?;
 
-            Ok(shader_id as u32)
-        }
+        let shader_id = unsafe {
+            self.backend.glCreateShader(type_ as GLenum)
+        };
+
+        self.end(call, || Value::Name(ObjectKind::Shader, shader_id));
+        Ok(shader_id)
     }
 
     pub fn gl_cull_face(&mut self, mode: FaceMode) -> Result<(), Error> {
+        let call = self.begin("glCullFace", || vec![Value::Enum(mode as GLenum)])?;
+
         unsafe {
-            ffi::glCullFace(mode as GLenum)
+            self.backend.glCullFace(mode as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_delete_buffers(&mut self, buffers: &[u32]) -> Result<(), Error> {
+        let call = self.begin("glDeleteBuffers", || vec![
+            Value::Int(buffers.len() as i32),
+            Value::Names(ObjectKind::Buffer, buffers.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glDeleteBuffers(buffers.len() as GLsizei, buffers.as_ptr())
+            self.backend.glDeleteBuffers(buffers.len() as GLsizei, buffers.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_delete_framebuffers(&mut self, framebuffers: &[u32]) -> Result<(), Error> {
+        let call = self.begin("glDeleteFramebuffers", || vec![
+            Value::Int(framebuffers.len() as i32),
+            Value::Names(ObjectKind::Framebuffer, framebuffers.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glDeleteFramebuffers(framebuffers.len() as GLsizei, framebuffers.as_ptr())
+            self.backend.glDeleteFramebuffers(framebuffers.len() as GLsizei, framebuffers.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_delete_program(&mut self, program: u32) -> Result<(), Error> {
+        let call = self.begin("glDeleteProgram", || vec![Value::Name(ObjectKind::Program, program)])?;
+
         unsafe {
-            ffi::glDeleteProgram(program as GLuint)
+            self.backend.glDeleteProgram(program as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_delete_renderbuffers(&mut self, renderbuffers: &[u32]) -> Result<(), Error> {
+        let call = self.begin("glDeleteRenderbuffers", || vec![
+            Value::Int(renderbuffers.len() as i32),
+            Value::Names(ObjectKind::Renderbuffer, renderbuffers.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glDeleteRenderbuffers(renderbuffers.len() as GLsizei,
-                                       renderbuffers.as_ptr())
+            self.backend.glDeleteRenderbuffers(renderbuffers.len() as GLsizei,
+                                               renderbuffers.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_delete_shader(&mut self, shader: u32) -> Result<(), Error> {
+        let call = self.begin("glDeleteShader", || vec![Value::Name(ObjectKind::Shader, shader)])?;
+
         unsafe {
-            ffi::glDeleteShader(shader as GLuint)
+            self.backend.glDeleteShader(shader as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_delete_textures(&mut self, textures: &[u32]) -> Result<(), Error> {
+        let call = self.begin("glDeleteTextures", || vec![
+            Value::Int(textures.len() as i32),
+            Value::Names(ObjectKind::Texture, textures.to_vec()),
+        ])?;
+
+        unsafe {
+            self.backend.glDeleteTextures(textures.len() as GLsizei, textures.as_ptr())
+        }
+
+        self.end(call, || Value::Void);
+        Ok(())
+    }
+
+    pub fn gl_delete_vertex_arrays(&mut self, arrays: &[u32]) -> Result<(), Error> {
+        self.delete_vertex_arrays("glDeleteVertexArrays", arrays)
+    }
+
+    pub fn gl_delete_vertex_arrays_oes(&mut self, arrays: &[u32]) -> Result<(), Error> {
+        self.delete_vertex_arrays("glDeleteVertexArraysOES", arrays)
+    }
+
+    fn delete_vertex_arrays(&mut self, name: &'static str, arrays: &[u32]) -> Result<(), Error> {
+        self.check_entry_point(name)?;
+        let call = self.begin(name, || vec![
+            Value::Int(arrays.len() as i32),
+            Value::Names(ObjectKind::VertexArray, arrays.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glDeleteTextures(textures.len() as GLsizei, textures.as_ptr())
+            if name == "glDeleteVertexArraysOES" {
+                self.backend.glDeleteVertexArraysOES(arrays.len() as GLsizei, arrays.as_ptr())
+            } else {
+                self.backend.glDeleteVertexArrays(arrays.len() as GLsizei, arrays.as_ptr())
+            }
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_depth_func(&mut self, func: FuncType) -> Result<(), Error> {
+        let call = self.begin("glDepthFunc", || vec![Value::Enum(func as GLenum)])?;
+
         unsafe {
-            ffi::glDepthFunc(func as GLenum)
+            self.backend.glDepthFunc(func as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_depth_mask(&mut self, flag: bool) -> Result<(), Error> {
+        let call = self.begin("glDepthMask", || vec![Value::Bool(flag)])?;
+
         unsafe {
-            ffi::glDepthMask(flag as GLboolean)
+            self.backend.glDepthMask(flag as GLboolean)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
 
     pub fn gl_depth_rangef(&mut self, z_near: f32, z_far: f32) -> Result<(), Error> {
+        let call = self.begin("glDepthRangef", || vec![Value::Float(z_near), Value::Float(z_far)])?;
+
         unsafe {
-            ffi::glDepthRangef(z_near as GLclampf, z_far as GLclampf)
+            self.backend.glDepthRangef(z_near as GLclampf, z_far as GLclampf)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_detach_shader(&mut self, program: u32, shader: u32) -> Result<(), Error> {
+        let call = self.begin("glDetachShader", || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Name(ObjectKind::Shader, shader),
+        ])?;
+
         unsafe {
-            ffi::glDetachShader(program as GLuint, shader as GLuint)
+            self.backend.glDetachShader(program as GLuint, shader as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_disable(&mut self, feature: FeatureType) -> Result<(), Error> {
+        let call = self.begin("glDisable", || vec![Value::Enum(feature as GLenum)])?;
+
         unsafe {
-            ffi::glDisable(feature as GLenum)
+            self.backend.glDisable(feature as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_disable_vertex_attrib_array(&mut self, index: u32) -> Result<(), Error> {
+        let call = self.begin("glDisableVertexAttribArray", || vec![Value::Uint(index)])?;
+
         unsafe {
-            ffi::glDisableVertexAttribArray(index as GLuint)
+            self.backend.glDisableVertexAttribArray(index as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_draw_arrays(&mut self, mode: BeginMode, first: i32, count: i32) -> Result<(), Error> {
+        let call = self.begin("glDrawArrays", || vec![
+            Value::Enum(mode as GLenum),
+            Value::Int(first),
+            Value::Int(count),
+        ])?;
+
         unsafe {
-            ffi::glDrawArrays(mode as GLenum, first as GLint, count as GLsizei)
+            self.backend.glDrawArrays(mode as GLenum, first as GLint, count as GLsizei)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     // TODO: type_ & T is reasonable ?
     pub fn gl_draw_elements<T>(&mut self, mode: BeginMode, count: i32, type_: GLenum, indices: &[T]) -> Result<(), Error> where T: std::fmt::Debug + Clone {
+        let call = self.begin("glDrawElements", || vec![
+            Value::Enum(mode as GLenum),
+            Value::Int(count),
+            Value::Enum(type_),
+            Value::bytes(indices),
+        ])?;
+
         unsafe {
-            ffi::glDrawElements(mode as GLenum, count as GLsizei,
-                                type_, indices.as_ptr() as *const GLvoid)
+            self.backend.glDrawElements(mode as GLenum, count as GLsizei,
+                                        type_, indices.as_ptr() as *const GLvoid)
         }
 
+        self.end(call, || Value::Void);
+        Ok(())
+    }
+
+    /// Draws with indices read from the bound `ELEMENT_ARRAY_BUFFER`, starting `offset` bytes in.
+    pub fn gl_draw_elements_offset(&mut self, mode: BeginMode, count: i32, type_: GLenum, offset: u32)
+        -> Result<(), Error> {
+        let call = self.begin("glDrawElements", || vec![
+            Value::Enum(mode as GLenum),
+            Value::Int(count),
+            Value::Enum(type_),
+            Value::Offset(offset as usize),
+        ])?;
+
+        unsafe {
+            self.backend.glDrawElements(mode as GLenum, count as GLsizei, type_, offset as *const GLvoid)
+        }
+
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_enable(&mut self, feature: FeatureType) -> Result<(), Error> {
+        let call = self.begin("glEnable", || vec![Value::Enum(feature as GLenum)])?;
+
         unsafe {
-            ffi::glEnable(feature as GLenum)
+            self.backend.glEnable(feature as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_enable_vertex_attrib_array(&mut self, index: u32) -> Result<(), Error> {
+        let call = self.begin("glEnableVertexAttribArray", || vec![Value::Uint(index)])?;
+
         unsafe {
-            ffi::glEnableVertexAttribArray(index as GLuint)
+            self.backend.glEnableVertexAttribArray(index as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_finish(&mut self) -> Result<(), Error> {
+        let call = self.begin("glFinish", Vec::new)?;
+
         unsafe {
-            ffi::glFinish()
+            self.backend.glFinish()
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_flush(&mut self) -> Result<(), Error> {
+        let call = self.begin("glFlush", Vec::new)?;
+
         unsafe {
-            ffi::glFlush()
+            self.backend.glFlush()
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
@@ -561,13 +1065,21 @@ impl Wrapper {
         renderbuffer_target: RenderBufferTarget,
         renderbuffer: u32,
     ) -> Result<(), Error> {
+        let call = self.begin("glFramebufferRenderbuffer", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(attachment as GLenum),
+            Value::Enum(renderbuffer_target as GLenum),
+            Value::Name(ObjectKind::Renderbuffer, renderbuffer),
+        ])?;
+
         unsafe {
-            ffi::glFramebufferRenderbuffer(target as GLenum,
-                                           attachment as GLenum,
-                                           renderbuffer_target as GLenum,
-                                           renderbuffer as GLuint)
+            self.backend.glFramebufferRenderbuffer(target as GLenum,
+                                                   attachment as GLenum,
+                                                   renderbuffer_target as GLenum,
+                                                   renderbuffer as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
@@ -579,87 +1091,152 @@ impl Wrapper {
         texture: u32,
         level: i32,
     ) -> Result<(), Error> {
+        let call = self.begin("glFramebufferTexture2D", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(attachment as GLenum),
+            Value::Enum(texture_target as GLenum),
+            Value::Name(ObjectKind::Texture, texture),
+            Value::Int(level),
+        ])?;
+
         unsafe {
-            ffi::glFramebufferTexture2D(target as GLenum,
-                                        attachment as GLenum,
-                                        texture_target as GLenum,
-                                        texture as GLuint,
-                                        level as GLint)
+            self.backend.glFramebufferTexture2D(target as GLenum,
+                                                attachment as GLenum,
+                                                texture_target as GLenum,
+                                                texture as GLuint,
+                                                level as GLint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_front_face(&mut self, mode: FrontFaceDirection) -> Result<(), Error> {
+        let call = self.begin("glFrontFace", || vec![Value::Enum(mode as GLenum)])?;
+
         unsafe {
-            ffi::glFrontFace(mode as GLenum)
+            self.backend.glFrontFace(mode as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_gen_buffers(&mut self, count: u32) -> Result<Vec<u32>, Error> {
-        unsafe {
+        let call = self.begin("glGenBuffers", || vec![Value::Int(count as i32)])?;
+
+        let vec = unsafe {
             let mut vec: Vec<u32> = Vec::with_capacity(count as usize);
 
-            ffi::glGenBuffers(count as GLsizei, vec.as_mut_ptr());
+            self.backend.glGenBuffers(count as GLsizei, vec.as_mut_ptr());
 
             vec.set_len(count as usize);
+            vec
+        };
 
-            Ok(vec)
-        }
+        self.end(call, || Value::Names(ObjectKind::Buffer, vec.clone()));
+        Ok(vec)
     }
 
     pub fn gl_generate_mipmap(&mut self, target: TextureBindTarget) -> Result<(), Error> {
+        let call = self.begin("glGenerateMipmap", || vec![Value::Enum(target as GLenum)])?;
+
         unsafe {
-            ffi::glGenerateMipmap(target as GLenum)
+            self.backend.glGenerateMipmap(target as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_gen_framebuffers(&mut self, count: u32) -> Result<Vec<u32>, Error> {
-        unsafe {
+        let call = self.begin("glGenFramebuffers", || vec![Value::Int(count as i32)])?;
+
+        let vec = unsafe {
             let mut vec: Vec<u32> = Vec::with_capacity(count as usize);
 
-            ffi::glGenFramebuffers(count as GLsizei, vec.as_mut_ptr());
+            self.backend.glGenFramebuffers(count as GLsizei, vec.as_mut_ptr());
 
             vec.set_len(count as usize);
-            Ok(vec)
-        }
+            vec
+        };
+
+        self.end(call, || Value::Names(ObjectKind::Framebuffer, vec.clone()));
+        Ok(vec)
     }
 
     pub fn gl_gen_renderbuffers(&mut self, count: u32) -> Result<Vec<u32>, Error> {
-        unsafe {
+        let call = self.begin("glGenRenderbuffers", || vec![Value::Int(count as i32)])?;
+
+        let vec = unsafe {
             let mut vec: Vec<u32> = Vec::with_capacity(count as usize);
 
-            ffi::glGenRenderbuffers(count as GLsizei, vec.as_mut_ptr());
+            self.backend.glGenRenderbuffers(count as GLsizei, vec.as_mut_ptr());
 
             vec.set_len(count as usize);
-            Ok(vec)
-        }
+            vec
+        };
+
+        self.end(call, || Value::Names(ObjectKind::Renderbuffer, vec.clone()));
+        Ok(vec)
     }
 
     pub fn gl_gen_textures(&mut self, count: u32) -> Result<Vec<u32>, Error> {
-        unsafe {
+        let call = self.begin("glGenTextures", || vec![Value::Int(count as i32)])?;
+
+        let vec = unsafe {
             let mut vec: Vec<u32> = Vec::with_capacity(count as usize);
 
-            ffi::glGenTextures(count as GLsizei, vec.as_mut_ptr());
+            self.backend.glGenTextures(count as GLsizei, vec.as_mut_ptr());
 
             vec.set_len(count as usize);
-            Ok(vec)
+            vec
+        };
+
+        self.end(call, || Value::Names(ObjectKind::Texture, vec.clone()));
+        Ok(vec)
+    }
+
+    pub fn gl_gen_vertex_arrays(&mut self, count: u32) -> Result<Vec<u32>, Error> {
+        self.gen_vertex_arrays("glGenVertexArrays", count)
+    }
+
+    pub fn gl_gen_vertex_arrays_oes(&mut self, count: u32) -> Result<Vec<u32>, Error> {
+        self.gen_vertex_arrays("glGenVertexArraysOES", count)
+    }
+
+    fn gen_vertex_arrays(&mut self, name: &'static str, count: u32) -> Result<Vec<u32>, Error> {
+        self.check_entry_point(name)?;
+        let call = self.begin(name, || vec![Value::Int(count as i32)])?;
+
+        let mut vec: Vec<u32> = vec![0; count as usize];
+
+        unsafe {
+            if name == "glGenVertexArraysOES" {
+                self.backend.glGenVertexArraysOES(count as GLsizei, vec.as_mut_ptr())
+            } else {
+                self.backend.glGenVertexArrays(count as GLsizei, vec.as_mut_ptr())
+            }
         }
+
+        self.end(call, || Value::Names(ObjectKind::VertexArray, vec.clone()));
+        Ok(vec)
     }
 
     pub fn gl_get_active_attrib(&mut self, program: u32, index: u32) -> Result<Active, Error> {
-        unsafe {
-            let mut length: GLsizei = 0;
-            let mut size: GLint = 0;
-            let mut attrib_type: GLenum = 0;
+        let call = self.begin("glGetActiveAttrib", || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Uint(index),
+        ])?;
 
-            let mut name = String::with_capacity(256);
+        let mut length: GLsizei = 0;
+        let mut size: GLint = 0;
+        let mut attrib_type: GLenum = 0;
 
-            ffi::glGetActiveAttrib(
+        let mut name = String::with_capacity(256);
+
+        unsafe {
+            self.backend.glGetActiveAttrib(
                 program as GLuint,
                 index as GLuint,
                 256,
@@ -671,32 +1248,42 @@ impl Wrapper {
 
             if length > 0 {
                 name.as_mut_vec().set_len(length as usize);
-                name.truncate(length as usize);
+            }
+        }
 
-                let type_ = DataType::from(attrib_type);
+        self.end(call, || Value::Str(name.clone()));
 
-                Ok(Active {
-                    name,
-                    size,
-                    type_,
-                    length,
-                })
-            } else {
-                // TODO: error desc
-                Err(Error{})
-            }
+        if length > 0 {
+            name.truncate(length as usize);
+
+            let type_ = DataType::from(attrib_type);
+
+            Ok(Active {
+                name,
+                size,
+                type_,
+                length,
+            })
+        } else {
+            Err(Error::new(ErrorKind::InvalidResult,
+                           format!("no active attribute {} in program {}", index, program)))
         }
     }
 
     pub fn gl_get_active_uniform(&mut self, program: u32, index: u32) -> Result<Active, Error> {
-        unsafe {
-            let mut length: GLsizei = 0;
-            let mut size: GLint = 0;
-            let mut uniform_data_type: GLenum = 0;
+        let call = self.begin("glGetActiveUniform", || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Uint(index),
+        ])?;
+
+        let mut length: GLsizei = 0;
+        let mut size: GLint = 0;
+        let mut uniform_data_type: GLenum = 0;
 
-            let mut name = String::with_capacity(256);
+        let mut name = String::with_capacity(256);
 
-            ffi::glGetActiveUniform(
+        unsafe {
+            self.backend.glGetActiveUniform(
                 program as GLuint,
                 index as GLuint,
                 256,
@@ -708,87 +1295,121 @@ impl Wrapper {
 
             if length > 0 {
                 name.as_mut_vec().set_len(length as usize);
-                name.truncate(length as usize);
+            }
+        }
 
-                let type_ = DataType::from(uniform_data_type);
+        self.end(call, || Value::Str(name.clone()));
 
-                Ok(Active {
-                    name,
-                    size,
-                    type_,
-                    length,
-                })
-            } else {
-                // TODO: error desc
-                Err(Error{})
-            }
+        if length > 0 {
+            name.truncate(length as usize);
+
+            let type_ = DataType::from(uniform_data_type);
+
+            Ok(Active {
+                name,
+                size,
+                type_,
+                length,
+            })
+        } else {
+            Err(Error::new(ErrorKind::InvalidResult,
+                           format!("no active uniform {} in program {}", index, program)))
         }
     }
 
     pub fn gl_get_attached_shaders(&mut self, program: u32, max_count: i32) -> Result<Vec<u32>, Error> {
-        unsafe {
+        let call = self.begin("glGetAttachedShaders", || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Int(max_count),
+        ])?;
+
+        let vec = unsafe {
             let mut count: GLsizei = 0;
             let mut vec: Vec<u32> = Vec::with_capacity(max_count as usize);
 
-            ffi::glGetAttachedShaders(program as GLuint,
-                                      max_count as GLsizei, &mut count,
-                                      vec.as_mut_ptr());
+            self.backend.glGetAttachedShaders(program as GLuint,
+                                              max_count as GLsizei, &mut count,
+                                              vec.as_mut_ptr());
 
             vec.set_len(count as usize);
-            vec.truncate(count as usize);
-            Ok(vec)
-        }
+            vec
+        };
+
+        self.end(call, || Value::Names(ObjectKind::Shader, vec.clone()));
+        Ok(vec)
     }
 
     pub fn gl_get_attrib_location(&mut self, program: u32, name: &str) -> Result<i32, Error> {
-        unsafe {
+        let call = self.begin("glGetAttribLocation", || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Str(name.to_string()),
+        ])?;
+
+        let loc = unsafe {
             let c_str = CString::new(name).unwrap();
 
-            let loc = ffi::glGetAttribLocation(program as GLuint, c_str.as_ptr() as *const GLchar);
+            self.backend.glGetAttribLocation(program as GLuint, c_str.as_ptr() as *const GLchar)
+        };
 
-            Ok(loc as i32)
-        }
+        self.end(call, || Value::Int(loc));
+        Ok(loc)
     }
 
     pub fn gl_get_booleanv(&mut self, name: StateType) -> Result<bool, Error> {
+        let call = self.begin("glGetBooleanv", || vec![Value::Enum(name as GLenum)])?;
+
         let mut value: GLboolean = 0;
 
         unsafe {
-            ffi::glGetBooleanv(name as GLenum, &mut value);
+            self.backend.glGetBooleanv(name as GLenum, &mut value);
         }
 
+        self.end(call, || Value::Bool(value == GL_TRUE));
         Ok(value == GL_TRUE)
     }
 
     pub fn gl_get_buffer_parameteriv(&mut self, target: BufferTarget, name: BufferParamName) -> Result<i32, Error> {
+        let call = self.begin("glGetBufferParameteriv", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(name as GLenum),
+        ])?;
+
         let mut value: GLint = 0;
 
         unsafe {
-            ffi::glGetBufferParameteriv(target as GLenum, name as GLenum,
-                                        &mut value);
+            self.backend.glGetBufferParameteriv(target as GLenum, name as GLenum,
+                                                &mut value);
         }
 
+        self.end(call, || Value::Int(value));
         Ok(value as i32)
     }
 
 
     pub fn gl_get_error(&mut self) -> ErrorType {
-        let mut error = GL_NO_ERROR;
+        // An interceptor can not veto this call, there is no error to report it through.
+        let call = self.begin("glGetError", Vec::new).unwrap_or(None);
 
-        unsafe {
-            error = ffi::glGetError();
-        }
+        let taken = self.interceptors.iter_mut().filter_map(|interceptor| interceptor.take_error()).next();
+        let error = match taken {
+            Some(error) => error,
+            None => unsafe { self.backend.glGetError() },
+        };
 
+        self.end(call, || Value::Enum(error));
         ErrorType::from(error)
     }
 
     pub fn gl_get_floatv(&mut self, name: StateType) -> Result<f32, Error> {
+        let call = self.begin("glGetFloatv", || vec![Value::Enum(name as GLenum)])?;
+
         let mut value: GLfloat = 0.0;
 
         unsafe {
-            ffi::glGetFloatv(name as GLenum, &mut value);
+            self.backend.glGetFloatv(name as GLenum, &mut value);
         }
 
+        self.end(call, || Value::Float(value));
         Ok(value as f32)
     }
 
@@ -798,53 +1419,82 @@ impl Wrapper {
         attachment: FrameBufferAttachmentType,
         name: FrameBufferAttachmentParamType,
     ) -> Result<i32, Error> {
+        let call = self.begin("glGetFramebufferAttachmentParameteriv", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(attachment as GLenum),
+            Value::Enum(name as GLenum),
+        ])?;
+
         let mut value: GLint = 0;
 
         unsafe {
-            ffi::glGetFramebufferAttachmentParameteriv(target as GLenum,
-                                                       attachment as GLenum,
-                                                       name as GLenum,
-                                                       &mut value);
+            self.backend.glGetFramebufferAttachmentParameteriv(target as GLenum,
+                                                               attachment as GLenum,
+                                                               name as GLenum,
+                                                               &mut value);
         }
 
+        self.end(call, || Value::Int(value));
         Ok(value as i32)
     }
 
+    /// For state with more than one value use `gl_get_integerv_array`.
     pub fn gl_get_integerv(&mut self, name: StateType) -> Result<i32, Error> {
+        let call = self.begin("glGetIntegerv", || vec![Value::Enum(name as GLenum)])?;
+
         let mut value: GLint = 0;
 
         unsafe {
-            ffi::glGetIntegerv(name as GLenum, &mut value);
+            self.backend.glGetIntegerv(name as GLenum, &mut value);
         }
 
+        self.end(call, || Value::Int(value));
         Ok(value as i32)
     }
 
+    /// Queries state with `count` values, e.g. `VIEWPORT` (4) or `COMPRESSED_TEXTURE_FORMATS`
+    /// (the value of `NUM_COMPRESSED_TEXTURE_FORMATS`).
+    pub fn gl_get_integerv_array(&mut self, name: StateType, count: usize) -> Result<Vec<i32>, Error> {
+        let call = self.begin("glGetIntegerv", || vec![Value::Enum(name as GLenum)])?;
+
+        let mut values: Vec<GLint> = vec![0; count.max(1)];
+
+        unsafe {
+            self.backend.glGetIntegerv(name as GLenum, values.as_mut_ptr());
+        }
+
+        values.truncate(count);
+        self.end(call, || Value::Ints(values.clone()));
+        Ok(values)
+    }
+
     pub fn gl_get_programiv(&mut self, program: u32, name: ProgramParamType) -> Result<i32, Error> {
+        let call = self.begin("glGetProgramiv", || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Enum(name as GLenum),
+        ])?;
+
         let mut value: GLint = 0;
 
         unsafe {
-            ffi::glGetProgramiv(program as GLuint, name as GLenum, &mut value);
+            self.backend.glGetProgramiv(program as GLuint, name as GLenum, &mut value);
         }
 
+        self.end(call, || Value::Int(value));
         Ok(value as i32)
     }
 
     pub fn gl_get_program_info_log(&mut self, program: u32, max_length: i32) -> Result<String, Error> {
-        unsafe {
+        let call = self.begin("glGetProgramInfoLog", || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Int(max_length),
+        ])?;
+
+        let log = unsafe {
             let mut length: GLsizei = 0;
             let mut log = String::with_capacity(max_length as usize);
 
-            #[cfg(target_os = "ios")]
-                ffi::glGetProgramInfoLog(
-                program as GLuint,
-                max_length as GLsizei,
-                &mut length,
-                log.as_mut_vec().as_mut_ptr() as *mut i8,
-            );
-
-            #[cfg(target_os = "android")]
-                ffi::glGetProgramInfoLog(
+            self.backend.glGetProgramInfoLog(
                 program as GLuint,
                 max_length as GLsizei,
                 &mut length,
@@ -855,50 +1505,98 @@ impl Wrapper {
                 log.as_mut_vec().set_len(length as usize);
                 log.truncate(length as usize);
 
-                Ok(log)
+                log
             } else {
-                Ok("".to_string())
+                "".to_string()
+            }
+        };
+
+        self.end(call, || Value::Str(log.clone()));
+        Ok(log)
+    }
+
+    /// The binary of a linked program and its format, for `gl_program_binary` to load later. At
+    /// most `buf_size` bytes are returned; `ProgramParamType::PROGRAM_BINARY_LENGTH` tells how many
+    /// there are. ES 3.0; `gl_get_program_binary_oes` is the `GL_OES_get_program_binary` version.
+    pub fn gl_get_program_binary(&mut self, program: u32, buf_size: i32) -> Result<(GLenum, Vec<u8>), Error> {
+        self.get_program_binary("glGetProgramBinary", program, buf_size)
+    }
+
+    pub fn gl_get_program_binary_oes(&mut self, program: u32, buf_size: i32) -> Result<(GLenum, Vec<u8>), Error> {
+        self.get_program_binary("glGetProgramBinaryOES", program, buf_size)
+    }
+
+    fn get_program_binary(&mut self, name: &'static str, program: u32, buf_size: i32)
+        -> Result<(GLenum, Vec<u8>), Error> {
+        self.check_entry_point(name)?;
+        let call = self.begin(name, || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Int(buf_size),
+        ])?;
+
+        let mut binary = vec![0u8; buf_size.max(0) as usize];
+        let mut length: GLsizei = 0;
+        let mut format: GLenum = 0;
+
+        unsafe {
+            let (program, buf_size, data) = (program as GLuint, buf_size as GLsizei, binary.as_mut_ptr() as *mut GLvoid);
+            if name == "glGetProgramBinaryOES" {
+                self.backend.glGetProgramBinaryOES(program, buf_size, &mut length, &mut format, data)
+            } else {
+                self.backend.glGetProgramBinary(program, buf_size, &mut length, &mut format, data)
             }
         }
+
+        binary.truncate(length.max(0) as usize);
+        self.end(call, || Value::Bytes(binary.clone()));
+        Ok((format, binary))
     }
 
     pub fn gl_get_renderbuffer_parameteriv(&mut self, target: RenderBufferTarget,
                                            name: RenderBufferParamType) -> Result<i32, Error> {
+        let call = self.begin("glGetRenderbufferParameteriv", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(name as GLenum),
+        ])?;
+
         let mut value: GLint = 0;
 
         unsafe {
-            ffi::glGetRenderbufferParameteriv(target as GLenum,
-                                              name as GLenum, &mut value);
+            self.backend.glGetRenderbufferParameteriv(target as GLenum,
+                                                      name as GLenum, &mut value);
         }
 
+        self.end(call, || Value::Int(value));
         Ok(value as i32)
     }
 
     pub fn gl_get_shaderiv(&mut self, shader: u32, name: ShaderParamType) -> Result<i32, Error> {
+        let call = self.begin("glGetShaderiv", || vec![
+            Value::Name(ObjectKind::Shader, shader),
+            Value::Enum(name as GLenum),
+        ])?;
+
         let mut value: GLint = 0;
 
         unsafe {
-            ffi::glGetShaderiv(shader as GLuint, name as GLenum, &mut value);
+            self.backend.glGetShaderiv(shader as GLuint, name as GLenum, &mut value);
         }
 
+        self.end(call, || Value::Int(value));
         Ok(value as i32)
     }
 
-    #[warn(unused_variables)]
     pub fn gl_get_shader_info_log(&mut self, shader: u32, max_length: i32) -> Result<String, Error> {
-        unsafe {
+        let call = self.begin("glGetShaderInfoLog", || vec![
+            Value::Name(ObjectKind::Shader, shader),
+            Value::Int(max_length),
+        ])?;
+
+        let log = unsafe {
             let mut length: GLsizei = 0;
             let mut log = String::with_capacity(max_length as usize);
 
-            #[cfg(target_os = "ios")]
-                ffi::glGetShaderInfoLog(
-                shader as GLuint,
-                max_length as GLsizei,
-                &mut length,
-                log.as_mut_vec().as_mut_ptr() as *mut i8,
-            );
-            #[cfg(target_os = "android")]
-                ffi::glGetShaderInfoLog(
+            self.backend.glGetShaderInfoLog(
                 shader as GLuint,
                 max_length as GLsizei,
                 &mut length,
@@ -909,11 +1607,14 @@ impl Wrapper {
                 log.as_mut_vec().set_len(length as usize);
                 log.truncate(length as usize);
 
-                Ok(log)
+                log
             } else {
-                Ok("".to_string())
+                "".to_string()
             }
-        }
+        };
+
+        self.end(call, || Value::Str(log.clone()));
+        Ok(log)
     }
 
     pub fn gl_get_shader_precision_format(
@@ -921,11 +1622,16 @@ impl Wrapper {
         shader_type: ShaderType,
         precision_type: ShaderPrecisionType,
     ) -> Result<ShaderPrecisionFormat, Error> {
+        let call = self.begin("glGetShaderPrecisionFormat", || vec![
+            Value::Enum(shader_type as GLenum),
+            Value::Enum(precision_type as GLenum),
+        ])?;
+
         let mut precision: GLint = 0;
         let mut range: [GLint; 2] = [0, 0];
 
         unsafe {
-            ffi::glGetShaderPrecisionFormat(
+            self.backend.glGetShaderPrecisionFormat(
                 shader_type as GLenum,
                 precision_type as GLenum,
                 range.as_mut_ptr(),
@@ -933,18 +1639,24 @@ impl Wrapper {
             );
         }
 
+        self.end(call, || Value::Ints(vec![range[0], range[1], precision]));
         Ok(ShaderPrecisionFormat {
-            precision: precision,
-            range: range,
+            precision,
+            range,
         })
     }
 
     pub fn gl_get_shader_source(&mut self, shader: u32, max_length: i32) -> Result<String, Error> {
-        unsafe {
+        let call = self.begin("glGetShaderSource", || vec![
+            Value::Name(ObjectKind::Shader, shader),
+            Value::Int(max_length),
+        ])?;
+
+        let source = unsafe {
             let mut length: GLsizei = 0;
             let mut source = String::with_capacity(max_length as usize);
 
-            ffi::glGetShaderSource(
+            self.backend.glGetShaderSource(
                 shader as GLuint,
                 max_length as GLsizei,
                 &mut length,
@@ -955,212 +1667,329 @@ impl Wrapper {
                 source.as_mut_vec().set_len(length as usize);
                 source.truncate(length as usize);
 
-                Ok(source)
+                source
             } else {
-                Ok("".to_string())
+                "".to_string()
             }
-        }
+        };
+
+        self.end(call, || Value::Str(source.clone()));
+        Ok(source)
     }
 
     pub fn gl_get_string(&mut self, name: ConstantType) -> Result<String, Error> {
-        unsafe {
-            let c_str = ffi::glGetString(name as GLenum);
+        let call = self.begin("glGetString", || vec![Value::Enum(name as GLenum)])?;
+
+        let res = unsafe {
+            let c_str = self.backend.glGetString(name as GLenum);
             //todo : can't guarantee the lifetime, because the memory is allocated by C
             if !c_str.is_null() {
                 match from_utf8(CStr::from_ptr(c_str as *const c_char).to_bytes()) {
                     Ok(s) => Ok(s.to_string()),
-                    // TODO: error desc
-                    Err(_) => Err(Error{}),
+                    Err(_) => Err(Error::new(ErrorKind::InvalidResult,
+                                             format!("{:?} is not valid UTF-8", name))),
                 }
             } else {
                 // TODO: Ok is not proper ?
                 Ok("".to_string())
             }
-        }
+        };
+
+        self.end(call, || match res {
+            Ok(ref s) => Value::Str(s.clone()),
+            Err(_) => Value::Void,
+        });
+        res
     }
 
     pub fn gl_get_tex_parameterfv(&mut self, target: TextureTarget, name: TextureParamType) -> Result<f32, Error> {
+        let call = self.begin("glGetTexParameterfv", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(name as GLenum),
+        ])?;
+
         let mut value: GLfloat = 0.0;
 
         unsafe {
-            ffi::glGetTexParameterfv(target as GLenum, name as GLenum, &mut value);
+            self.backend.glGetTexParameterfv(target as GLenum, name as GLenum, &mut value);
         }
 
+        self.end(call, || Value::Float(value));
         Ok(value as f32)
     }
 
     pub fn gl_get_tex_parameteriv(&mut self, target: TextureTarget, name: TextureParamType) -> Result<i32, Error> {
+        let call = self.begin("glGetTexParameteriv", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(name as GLenum),
+        ])?;
+
         let mut value: GLint = 0;
 
         unsafe {
-            ffi::glGetTexParameteriv(target as GLenum, name as GLenum, &mut value);
+            self.backend.glGetTexParameteriv(target as GLenum, name as GLenum, &mut value);
         }
 
+        self.end(call, || Value::Int(value));
         Ok(value as i32)
     }
 
     pub fn gl_get_uniformfv(&mut self, program: u32, location: i32) -> Result<f32, Error> {
+        let call = self.begin("glGetUniformfv", || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Location(location),
+        ])?;
+
         let mut value: GLfloat = 0.0;
         unsafe {
-            ffi::glGetUniformfv(program as GLuint, location as GLint, &mut value);
+            self.backend.glGetUniformfv(program as GLuint, location as GLint, &mut value);
         }
 
+        self.end(call, || Value::Float(value));
         Ok(value as f32)
     }
 
     pub fn gl_get_uniformiv(&mut self, program: u32, location: i32) -> Result<i32, Error> {
+        let call = self.begin("glGetUniformiv", || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Location(location),
+        ])?;
+
         let mut value: GLint = 0;
 
         unsafe {
-            ffi::glGetUniformiv(program as GLuint, location as GLint, &mut value);
+            self.backend.glGetUniformiv(program as GLuint, location as GLint, &mut value);
         }
 
+        self.end(call, || Value::Int(value));
         Ok(value as i32)
     }
 
     pub fn gl_get_uniform_location(&mut self, program: u32, name: &str) -> Result<i32, Error> {
-        let mut loc: GLint = 0;
+        let call = self.begin("glGetUniformLocation", || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Str(name.to_string()),
+        ])?;
 
-        unsafe {
+        let loc = unsafe {
             let name_c_str = CString::new(name).unwrap();
 
-            loc = ffi::glGetUniformLocation(program as GLuint, name_c_str.as_ptr() as *const GLchar);
-        }
+            self.backend.glGetUniformLocation(program as GLuint, name_c_str.as_ptr() as *const GLchar)
+        };
 
-        Ok(loc as i32)
+        self.end(call, || Value::Location(loc));
+        Ok(loc)
     }
 
     pub fn gl_get_vertex_attribfv(&mut self, index: u32, name: VertexAttributeParamType) -> Result<f32, Error> {
+        let call = self.begin("glGetVertexAttribfv", || vec![
+            Value::Uint(index),
+            Value::Enum(name as GLenum),
+        ])?;
+
         let mut value: GLfloat = 0.0;
 
         unsafe {
-            ffi::glGetVertexAttribfv(index as GLuint, name as GLenum, &mut value);
+            self.backend.glGetVertexAttribfv(index as GLuint, name as GLenum, &mut value);
         }
 
+        self.end(call, || Value::Float(value));
         Ok(value as f32)
     }
 
     pub fn gl_get_vertex_attribiv(&mut self, index: u32, name: VertexAttributeParamType) -> Result<i32, Error> {
+        let call = self.begin("glGetVertexAttribiv", || vec![
+            Value::Uint(index),
+            Value::Enum(name as GLenum),
+        ])?;
+
         let mut value: GLint = 0;
 
         unsafe {
-            ffi::glGetVertexAttribiv(index as GLuint, name as GLenum, &mut value);
+            self.backend.glGetVertexAttribiv(index as GLuint, name as GLenum, &mut value);
         }
 
+        self.end(call, || Value::Int(value));
         Ok(value as i32)
     }
 
     pub fn gl_hint(&mut self, target: HintTargetType, mode: HintBehaviorType) -> Result<(), Error> {
+        let call = self.begin("glHint", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(mode as GLenum),
+        ])?;
+
         unsafe {
-            ffi::glHint(target as GLenum, mode as GLenum)
+            self.backend.glHint(target as GLenum, mode as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_is_buffer(&mut self, buffer: u32) -> Result<bool, Error> {
-        let mut res = false;
+        let call = self.begin("glIsBuffer", || vec![Value::Name(ObjectKind::Buffer, buffer)])?;
 
-        unsafe {
-            res = ffi::glIsBuffer(buffer as GLuint) == GL_TRUE;
-        }
+        let res = unsafe {
+            self.backend.glIsBuffer(buffer as GLuint) == GL_TRUE
+        };
 
+        self.end(call, || Value::Bool(res));
         Ok(res)
     }
 
     pub fn gl_is_enabled(&mut self, feature: FeatureType) -> Result<bool, Error> {
-        let mut res = false;
+        let call = self.begin("glIsEnabled", || vec![Value::Enum(feature as GLenum)])?;
 
-        unsafe {
-            res = ffi::glIsEnabled(feature as GLenum) == GL_TRUE;
-        }
+        let res = unsafe {
+            self.backend.glIsEnabled(feature as GLenum) == GL_TRUE
+        };
 
+        self.end(call, || Value::Bool(res));
         Ok(res)
     }
 
     pub fn gl_is_framebuffer(&mut self, framebuffer: u32) -> Result<bool, Error> {
-        let mut res = false;
+        let call = self.begin("glIsFramebuffer", || vec![
+            Value::Name(ObjectKind::Framebuffer, framebuffer),
+        ])?;
 
-        unsafe {
-            res = ffi::glIsFramebuffer(framebuffer as GLuint) == GL_TRUE;
-        }
+        let res = unsafe {
+            self.backend.glIsFramebuffer(framebuffer as GLuint) == GL_TRUE
+        };
 
+        self.end(call, || Value::Bool(res));
         Ok(res)
     }
 
     pub fn gl_is_program(&mut self, program: u32) -> Result<bool, Error> {
-        let mut res = false;
+        let call = self.begin("glIsProgram", || vec![Value::Name(ObjectKind::Program, program)])?;
 
-        unsafe {
-            res = ffi::glIsProgram(program as GLuint) == GL_TRUE;
-        }
+        let res = unsafe {
+            self.backend.glIsProgram(program as GLuint) == GL_TRUE
+        };
 
+        self.end(call, || Value::Bool(res));
         Ok(res)
     }
 
     pub fn gl_is_renderbuffer(&mut self, renderbuffer: u32) -> Result<bool, Error> {
-        let mut res = false;
+        let call = self.begin("glIsRenderbuffer", || vec![
+            Value::Name(ObjectKind::Renderbuffer, renderbuffer),
+        ])?;
 
-        unsafe {
-            res = ffi::glIsRenderbuffer(renderbuffer as u32) == GL_TRUE;
-        }
+        let res = unsafe {
+            self.backend.glIsRenderbuffer(renderbuffer) == GL_TRUE
+        };
 
+        self.end(call, || Value::Bool(res));
         Ok(res)
     }
 
     pub fn gl_is_shader(&mut self, shader: u32) -> Result<bool, Error> {
-        let mut res = false;
+        let call = self.begin("glIsShader", || vec![Value::Name(ObjectKind::Shader, shader)])?;
 
-        unsafe {
-            res = ffi::glIsShader(shader as u32) == GL_TRUE;
-        }
+        let res = unsafe {
+            self.backend.glIsShader(shader) == GL_TRUE
+        };
 
+        self.end(call, || Value::Bool(res));
         Ok(res)
     }
 
     pub fn gl_is_texture(&mut self, texture: u32) -> Result<bool, Error> {
-        let mut res = false;
+        let call = self.begin("glIsTexture", || vec![Value::Name(ObjectKind::Texture, texture)])?;
 
-        unsafe {
-            res = ffi::glIsTexture(texture as u32) == GL_TRUE;
-        }
+        let res = unsafe {
+            self.backend.glIsTexture(texture) == GL_TRUE
+        };
 
+        self.end(call, || Value::Bool(res));
         Ok(res)
     }
 
     pub fn gl_line_width(&mut self, width: f32) -> Result<(), Error>  {
+        let call = self.begin("glLineWidth", || vec![Value::Float(width)])?;
+
         unsafe {
-            ffi::glLineWidth(width as GLfloat);
+            self.backend.glLineWidth(width as GLfloat);
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_link_program(&mut self, program: u32) -> Result<(), Error> {
+        let call = self.begin("glLinkProgram", || vec![Value::Name(ObjectKind::Program, program)])?;
+
         unsafe {
-            ffi::glLinkProgram(program as GLuint)
+            self.backend.glLinkProgram(program as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_pixel_storei(&mut self, name: PackParamType, param: i32) -> Result<(), Error> {
+        let call = self.begin("glPixelStorei", || vec![
+            Value::Enum(name as GLenum),
+            Value::Int(param),
+        ])?;
+
         unsafe {
-            ffi::glPixelStorei(name as GLenum, param as GLint)
+            self.backend.glPixelStorei(name as GLenum, param as GLint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_polygon_offset(&mut self, factor: f32, units: f32) -> Result<(), Error> {
+        let call = self.begin("glPolygonOffset", || vec![Value::Float(factor), Value::Float(units)])?;
+
+        unsafe {
+            self.backend.glPolygonOffset(factor as GLfloat, units as GLfloat)
+        }
+
+        self.end(call, || Value::Void);
+        Ok(())
+    }
+
+    /// Loads a binary from `gl_get_program_binary` into `program`, replacing its executable as
+    /// linking would. A binary the driver rejects, e.g. after a driver update, leaves
+    /// `LINK_STATUS` false and the program has to be built from source. ES 3.0;
+    /// `gl_program_binary_oes` is the `GL_OES_get_program_binary` version.
+    pub fn gl_program_binary(&mut self, program: u32, format: GLenum, binary: &[u8]) -> Result<(), Error> {
+        self.program_binary("glProgramBinary", program, format, binary)
+    }
+
+    pub fn gl_program_binary_oes(&mut self, program: u32, format: GLenum, binary: &[u8]) -> Result<(), Error> {
+        self.program_binary("glProgramBinaryOES", program, format, binary)
+    }
+
+    fn program_binary(&mut self, name: &'static str, program: u32, format: GLenum, binary: &[u8])
+        -> Result<(), Error> {
+        self.check_entry_point(name)?;
+        let call = self.begin(name, || vec![
+            Value::Name(ObjectKind::Program, program),
+            Value::Enum(format),
+            Value::bytes(binary),
+            Value::Int(binary.len() as i32),
+        ])?;
+
         unsafe {
-            ffi::glPolygonOffset(factor as GLfloat, units as GLfloat)
+            let data = binary.as_ptr() as *const GLvoid;
+            if name == "glProgramBinaryOES" {
+                self.backend.glProgramBinaryOES(program as GLuint, format, data, binary.len() as GLsizei)
+            } else {
+                self.backend.glProgramBinary(program as GLuint, format, data, binary.len() as GLsizei)
+            }
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
-    // TODO: buffer size calculate automatically?
     pub fn gl_read_pixels<T>(
         &mut self,
         x: i32,
@@ -1171,8 +2000,20 @@ impl Wrapper {
         type_: PixelDataType,
         buffer: &mut [T],
     ) -> Result<(), Error> where T: std::fmt::Debug + Clone {
+        let alignment = self.get_integer(GL_PACK_ALIGNMENT);
+        check_image_size("glReadPixels", width, height, format, type_, alignment, size_of_val(buffer))?;
+
+        let call = self.begin("glReadPixels", || vec![
+            Value::Int(x),
+            Value::Int(y),
+            Value::Int(width),
+            Value::Int(height),
+            Value::Enum(format as GLenum),
+            Value::Enum(type_ as GLenum),
+        ])?;
+
         unsafe {
-            ffi::glReadPixels(
+            self.backend.glReadPixels(
                 x as GLint,
                 y as GLint,
                 width as GLsizei,
@@ -1183,14 +2024,18 @@ impl Wrapper {
             )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_release_shader_compiler(&mut self) -> Result<(), Error> {
+        let call = self.begin("glReleaseShaderCompiler", Vec::new)?;
+
         unsafe {
-            ffi::glReleaseShaderCompiler()
+            self.backend.glReleaseShaderCompiler()
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
@@ -1201,11 +2046,19 @@ impl Wrapper {
         width: i32,
         height: i32,
     ) -> Result<(), Error>  {
+        let call = self.begin("glRenderbufferStorage", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(internal_format as GLenum),
+            Value::Int(width),
+            Value::Int(height),
+        ])?;
+
         unsafe {
-            ffi::glRenderbufferStorage(target as GLenum, internal_format as GLenum,
-                                       width as GLsizei, height as GLsizei)
+            self.backend.glRenderbufferStorage(target as GLenum, internal_format as GLenum,
+                                               width as GLsizei, height as GLsizei)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
@@ -1213,103 +2066,207 @@ impl Wrapper {
         &mut self,
         value: f32,
         invert: bool) -> Result<(), Error> {
+        let call = self.begin("glSampleCoverage", || vec![Value::Float(value), Value::Bool(invert)])?;
+
         unsafe {
-            ffi::glSampleCoverage(value as GLclampf, invert as GLboolean)
+            self.backend.glSampleCoverage(value as GLclampf, invert as GLboolean)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_scissor(&mut self, x: i32, y: i32, width: i32, height: i32) -> Result<(), Error> {
+        let call = self.begin("glScissor", || vec![
+            Value::Int(x), Value::Int(y), Value::Int(width), Value::Int(height),
+        ])?;
+
         unsafe {
-            ffi::glScissor(x as GLint, y as GLint,
-                           width as GLsizei, height as GLsizei)
+            self.backend.glScissor(x as GLint, y as GLint,
+                                   width as GLsizei, height as GLsizei)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
-    // TODO: data_format
-    // TODO: length's unit should be byte or T ?
-    pub fn gl_shader_binary<T>(&mut self, shaders: &[u32], data_format: GLenum,
-                               data: &[T], length: i32) -> Result<(), Error> where T: std::fmt::Debug + Clone {
+    /// Loads `binary` into `shaders`. A single binary may hold several shaders, e.g. the vertex
+    /// and fragment shader of a program; `shaders` then lists them, at most one of each type, as
+    /// the format expects. The driver reports a binary that does not match `format` with
+    /// `INVALID_VALUE`.
+    pub fn gl_shader_binary(&mut self, shaders: &[u32], format: ShaderBinaryFormat,
+                            binary: &[u8]) -> Result<(), Error> {
+        if shaders.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidArgument, "glShaderBinary: no shaders to load into"));
+        }
+
+        let call = self.begin("glShaderBinary", || vec![
+            Value::Int(shaders.len() as i32),
+            Value::Names(ObjectKind::Shader, shaders.to_vec()),
+            Value::Enum(format.as_raw()),
+            Value::bytes(binary),
+            Value::Int(binary.len() as i32),
+        ])?;
+
         unsafe {
-            ffi::glShaderBinary(
+            self.backend.glShaderBinary(
                 shaders.len() as GLsizei,
                 shaders.as_ptr(),
-                data_format,
-                data.as_ptr() as *const GLvoid,
-                length as GLsizei,
+                format.as_raw(),
+                binary.as_ptr() as *const GLvoid,
+                binary.len() as GLsizei,
             )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_shader_source(&mut self, shader: u32, source: &str) -> Result<(), Error> {
+        let call = self.begin("glShaderSource", || vec![
+            Value::Name(ObjectKind::Shader, shader),
+            Value::Int(1),
+            Value::Str(source.to_string()),
+        ])?;
+
         unsafe {
             let length: GLsizei = source.len() as GLsizei;
 
-            ffi::glShaderSource(shader as GLuint, 1,
-                                &(source.as_ptr() as *const GLchar), &length)
+            self.backend.glShaderSource(shader as GLuint, 1,
+                                        &(source.as_ptr() as *const GLchar), &length)
         }
 
+        self.end(call, || Value::Void);
+        Ok(())
+    }
+
+    /// Sets the source of `shader` from several strings, e.g. a prelude, defines and a body, in
+    /// one `glShaderSource` call. GL concatenates them; this does not.
+    pub fn gl_shader_source_array(&mut self, shader: u32, sources: &[&str]) -> Result<(), Error> {
+        self.gl_shader_source_with(shader, sources, &mut ShaderSourceArrays::default())
+    }
+
+    /// `gl_shader_source_array` with pointer and length arrays that are kept between calls.
+    pub fn gl_shader_source_with(&mut self, shader: u32, sources: &[&str], arrays: &mut ShaderSourceArrays)
+        -> Result<(), Error> {
+        // Recorded as one string, which replays the same as the parts.
+        let call = self.begin("glShaderSource", || vec![
+            Value::Name(ObjectKind::Shader, shader),
+            Value::Int(sources.len() as i32),
+            Value::Str(sources.concat()),
+        ])?;
+
+        arrays.pointers.clear();
+        arrays.pointers.extend(sources.iter().map(|source| source.as_ptr() as *const GLchar));
+        arrays.lengths.clear();
+        arrays.lengths.extend(sources.iter().map(|source| source.len() as GLint));
+
+        unsafe {
+            self.backend.glShaderSource(shader as GLuint, sources.len() as GLsizei,
+                                        arrays.pointers.as_ptr(), arrays.lengths.as_ptr())
+        }
+        // The strings are only borrowed for the call.
+        arrays.pointers.clear();
+
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_stencil_func(&mut self, func: FuncType, ref_: i32, mask: u32) -> Result<(), Error> {
+        let call = self.begin("glStencilFunc", || vec![
+            Value::Enum(func as GLenum),
+            Value::Int(ref_),
+            Value::Uint(mask),
+        ])?;
+
         unsafe {
-            ffi::glStencilFunc(func as GLenum, ref_ as GLint, mask as GLuint)
+            self.backend.glStencilFunc(func as GLenum, ref_ as GLint, mask as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_stencil_func_separate(&mut self, face: FaceMode, func: FuncType,
                                     ref_: i32, mask: u32) -> Result<(), Error> {
+        let call = self.begin("glStencilFuncSeparate", || vec![
+            Value::Enum(face as GLenum),
+            Value::Enum(func as GLenum),
+            Value::Int(ref_),
+            Value::Uint(mask),
+        ])?;
+
         unsafe {
-            ffi::glStencilFuncSeparate(face as GLenum, func as GLenum,
-                                       ref_ as GLint, mask as GLuint)
+            self.backend.glStencilFuncSeparate(face as GLenum, func as GLenum,
+                                               ref_ as GLint, mask as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_stencil_mask(&mut self, mask: u32) -> Result<(), Error> {
+        let call = self.begin("glStencilMask", || vec![Value::Uint(mask)])?;
+
         unsafe {
-            ffi::glStencilMask(mask as GLuint)
+            self.backend.glStencilMask(mask as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_stencil_mask_separate(&mut self, face: FaceMode, mask: u32) -> Result<(), Error> {
+        let call = self.begin("glStencilMaskSeparate", || vec![
+            Value::Enum(face as GLenum),
+            Value::Uint(mask),
+        ])?;
+
         unsafe {
-            ffi::glStencilMaskSeparate(face as GLenum, mask as GLuint)
+            self.backend.glStencilMaskSeparate(face as GLenum, mask as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_stencil_op(&mut self, s_fail: ActionType, dp_fail: ActionType, dp_pass: ActionType) -> Result<(), Error> {
+        let call = self.begin("glStencilOp", || vec![
+            Value::Enum(s_fail as GLenum),
+            Value::Enum(dp_fail as GLenum),
+            Value::Enum(dp_pass as GLenum),
+        ])?;
+
         unsafe {
-            ffi::glStencilOp(s_fail as GLenum, dp_fail as GLenum, dp_pass as GLenum)
+            self.backend.glStencilOp(s_fail as GLenum, dp_fail as GLenum, dp_pass as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_stencil_op_separate(&mut self, face: FaceMode, s_fail: ActionType,
                                   dp_fail: ActionType, dp_pass: ActionType) -> Result<(), Error> {
+        let call = self.begin("glStencilOpSeparate", || vec![
+            Value::Enum(face as GLenum),
+            Value::Enum(s_fail as GLenum),
+            Value::Enum(dp_fail as GLenum),
+            Value::Enum(dp_pass as GLenum),
+        ])?;
+
         unsafe {
-            ffi::glStencilOpSeparate(face as GLenum, s_fail as GLenum, dp_fail as GLenum,
-                                     dp_pass as GLenum)
+            self.backend.glStencilOpSeparate(face as GLenum, s_fail as GLenum, dp_fail as GLenum,
+                                             dp_pass as GLenum)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     // TODO: internal_format should be enum, but why GLint?
+    /// An empty `buffer` is passed to GL as a null pointer, which allocates the level without
+    /// specifying its contents. Any other buffer has to hold the whole image with rows padded to
+    /// `GL_UNPACK_ALIGNMENT`.
     pub fn gl_tex_image_2d<T>(
         &mut self,
         target: TextureTarget,
@@ -1322,8 +2279,25 @@ impl Wrapper {
         type_: PixelDataType,
         buffer: &[T],
     ) -> Result<(), Error> where T: std::fmt::Debug + Clone {
+        if !buffer.is_empty() {
+            let alignment = self.get_integer(GL_UNPACK_ALIGNMENT);
+            check_image_size("glTexImage2D", width, height, format, type_, alignment, size_of_val(buffer))?;
+        }
+
+        let call = self.begin("glTexImage2D", || vec![
+            Value::Enum(target as GLenum),
+            Value::Int(level),
+            Value::Enum(internal_format as GLenum),
+            Value::Int(width),
+            Value::Int(height),
+            Value::Int(border),
+            Value::Enum(format as GLenum),
+            Value::Enum(type_ as GLenum),
+            Value::bytes(buffer),
+        ])?;
+
         unsafe {
-            ffi::glTexImage2D(
+            self.backend.glTexImage2D(
                 target as GLenum,
                 level as GLint,
                 internal_format,
@@ -1332,49 +2306,84 @@ impl Wrapper {
                 border as GLint,
                 format as GLenum,
                 type_ as GLenum,
-                buffer.as_ptr() as *const GLvoid,
+                if buffer.is_empty() { std::ptr::null() } else { buffer.as_ptr() as *const GLvoid },
             )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_tex_parameterf(&mut self, target: TextureBindTarget, name: TextureParamType,
                              value: f32) -> Result<(), Error> {
+        let call = self.begin("glTexParameterf", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(name as GLenum),
+            Value::Float(value),
+        ])?;
+
         unsafe {
-            ffi::glTexParameterf(target as GLenum, name as GLenum, value as GLfloat)
+            self.backend.glTexParameterf(target as GLenum, name as GLenum, value as GLfloat)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_tex_parameterfv(&mut self, target: TextureBindTarget,
                               name: TextureParamType) -> Result<f32, Error> {
-
         let res: GLfloat = 0.0;
+
+        let call = self.begin("glTexParameterfv", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(name as GLenum),
+            Value::Floats(vec![res]),
+        ])?;
+
         unsafe {
-            ffi::glTexParameterfv(target as GLenum, name as GLenum, &res)
+            self.backend.glTexParameterfv(target as GLenum, name as GLenum, &res)
         }
 
+        self.end(call, || Value::Void);
         Ok(res as f32)
     }
 
     pub fn gl_tex_parameteri(&mut self, target: TextureBindTarget, name: TextureParamType,
                              value: GLint) -> Result<(), Error> {
+        // Every TextureParamType takes an enum value.
+        let call = self.begin("glTexParameteri", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(name as GLenum),
+            Value::Enum(value as GLenum),
+        ])?;
+
         unsafe {
-            ffi::glTexParameteri(target as GLenum, name as GLenum, value)
+            self.backend.glTexParameteri(target as GLenum, name as GLenum, value)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
+    /// Typed form of `gl_tex_parameteri`: the value always matches the parameter.
+    pub fn gl_tex_parameter(&mut self, target: TextureBindTarget, parameter: TextureParameter) -> Result<(), Error> {
+        self.gl_tex_parameteri(target, parameter.name(), parameter.value() as GLint)
+    }
+
     pub fn gl_tex_parameteriv(&mut self, target: TextureBindTarget, name: TextureParamType) -> Result<i32, Error> {
         let res: GLint = 0;
 
+        let call = self.begin("glTexParameteriv", || vec![
+            Value::Enum(target as GLenum),
+            Value::Enum(name as GLenum),
+            Value::Ints(vec![res]),
+        ])?;
+
         unsafe {
-            ffi::glTexParameteriv(target as GLenum, name as GLenum, &res)
+            self.backend.glTexParameteriv(target as GLenum, name as GLenum, &res)
         }
 
+        self.end(call, || Value::Void);
         Ok(res as i32)
     }
 
@@ -1390,8 +2399,23 @@ impl Wrapper {
         type_: PixelDataType,
         buffer: &[T],
     ) -> Result<(), Error> where T: std::fmt::Debug + Clone {
-        unsafe {
-            ffi::glTexSubImage2D(
+        let alignment = self.get_integer(GL_UNPACK_ALIGNMENT);
+        check_image_size("glTexSubImage2D", width, height, format, type_, alignment, size_of_val(buffer))?;
+
+        let call = self.begin("glTexSubImage2D", || vec![
+            Value::Enum(target as GLenum),
+            Value::Int(level),
+            Value::Int(x_offset),
+            Value::Int(y_offset),
+            Value::Int(width),
+            Value::Int(height),
+            Value::Enum(format as GLenum),
+            Value::Enum(type_ as GLenum),
+            Value::bytes(buffer),
+        ])?;
+
+        unsafe {
+            self.backend.glTexSubImage2D(
                 target as GLenum,
                 level as GLint,
                 x_offset as GLint,
@@ -1404,263 +2428,447 @@ impl Wrapper {
             )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform1f(&mut self, location: i32, x: f32) -> Result<(), Error> {
+        let call = self.begin("glUniform1f", || vec![Value::Location(location), Value::Float(x)])?;
+
         unsafe {
-            ffi::glUniform1f(location as GLint, x as GLfloat)
+            self.backend.glUniform1f(location as GLint, x as GLfloat)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform1fv(&mut self, location: i32, values: &[f32]) -> Result<(), Error> {
+        let call = self.begin("glUniform1fv", || vec![
+            Value::Location(location),
+            Value::Int(values.len() as i32),
+            Value::Floats(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glUniform1fv(location as GLint, values.len() as GLsizei, values.as_ptr())
+            self.backend.glUniform1fv(location as GLint, values.len() as GLsizei, values.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform1i(&mut self, location: i32, x: i32) -> Result<(), Error> {
+        let call = self.begin("glUniform1i", || vec![Value::Location(location), Value::Int(x)])?;
+
         unsafe {
-            ffi::glUniform1i(location as GLint, x as GLint)
+            self.backend.glUniform1i(location as GLint, x as GLint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform1iv(&mut self, location: i32, values: &[i32]) -> Result<(), Error> {
+        let call = self.begin("glUniform1iv", || vec![
+            Value::Location(location),
+            Value::Int(values.len() as i32),
+            Value::Ints(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glUniform1iv(location as GLint, values.len() as GLsizei, values.as_ptr())
+            self.backend.glUniform1iv(location as GLint, values.len() as GLsizei, values.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform2f(&mut self, location: i32, x: f32, y: f32) -> Result<(), Error> {
+        let call = self.begin("glUniform2f", || vec![
+            Value::Location(location), Value::Float(x), Value::Float(y),
+        ])?;
+
         unsafe {
-            ffi::glUniform2f(location as GLint, x as GLfloat, y as GLfloat)
+            self.backend.glUniform2f(location as GLint, x as GLfloat, y as GLfloat)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform2fv(&mut self, location: i32, values: &[f32]) -> Result<(), Error> {
+        let call = self.begin("glUniform2fv", || vec![
+            Value::Location(location),
+            Value::Int((values.len() / 2) as i32),
+            Value::Floats(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glUniform2fv(location as GLint, (values.len() / 2) as GLsizei,
-                              values.as_ptr())
+            self.backend.glUniform2fv(location as GLint, (values.len() / 2) as GLsizei,
+                                      values.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform2i(&mut self, location: i32, x: i32, y: i32) -> Result<(), Error> {
+        let call = self.begin("glUniform2i", || vec![
+            Value::Location(location), Value::Int(x), Value::Int(y),
+        ])?;
+
         unsafe {
-            ffi::glUniform2i(location as GLint, x as GLint, y as GLint)
+            self.backend.glUniform2i(location as GLint, x as GLint, y as GLint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform2iv(&mut self, location: i32, values: &[i32]) -> Result<(), Error> {
+        let call = self.begin("glUniform2iv", || vec![
+            Value::Location(location),
+            Value::Int((values.len() / 2) as i32),
+            Value::Ints(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glUniform2iv(location as GLint, (values.len() / 2) as GLsizei, values.as_ptr())
+            self.backend.glUniform2iv(location as GLint, (values.len() / 2) as GLsizei, values.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform3f(&mut self, location: i32, x: f32, y: f32, z: f32) -> Result<(), Error> {
+        let call = self.begin("glUniform3f", || vec![
+            Value::Location(location), Value::Float(x), Value::Float(y), Value::Float(z),
+        ])?;
+
         unsafe {
-            ffi::glUniform3f(location as GLint, x as GLfloat, y as GLfloat, z as GLfloat)
+            self.backend.glUniform3f(location as GLint, x as GLfloat, y as GLfloat, z as GLfloat)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform3fv(&mut self, location: i32, values: &[f32]) -> Result<(), Error> {
+        let call = self.begin("glUniform3fv", || vec![
+            Value::Location(location),
+            Value::Int((values.len() / 3) as i32),
+            Value::Floats(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glUniform3fv(location as GLint, (values.len() / 3) as GLsizei, values.as_ptr())
+            self.backend.glUniform3fv(location as GLint, (values.len() / 3) as GLsizei, values.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform3i(&mut self, location: i32, x: i32, y: i32, z: i32) -> Result<(), Error> {
+        let call = self.begin("glUniform3i", || vec![
+            Value::Location(location), Value::Int(x), Value::Int(y), Value::Int(z),
+        ])?;
+
         unsafe {
-            ffi::glUniform3i(location as GLint, x as GLint, y as GLint, z as GLint)
+            self.backend.glUniform3i(location as GLint, x as GLint, y as GLint, z as GLint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform3iv(&mut self, location: i32, values: &[i32]) -> Result<(), Error> {
+        let call = self.begin("glUniform3iv", || vec![
+            Value::Location(location),
+            Value::Int((values.len() / 3) as i32),
+            Value::Ints(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glUniform3iv(location as GLint, (values.len() / 3) as GLsizei, values.as_ptr())
+            self.backend.glUniform3iv(location as GLint, (values.len() / 3) as GLsizei, values.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
 
     pub fn gl_uniform4f(&mut self, location: i32, x: f32, y: f32, z: f32,
                         w: f32) -> Result<(), Error> {
+        let call = self.begin("glUniform4f", || vec![
+            Value::Location(location),
+            Value::Float(x), Value::Float(y), Value::Float(z), Value::Float(w),
+        ])?;
+
         unsafe {
-            ffi::glUniform4f(location as GLint, x as GLfloat,
-                             y as GLfloat, z as GLfloat, w as GLfloat)
+            self.backend.glUniform4f(location as GLint, x as GLfloat,
+                                     y as GLfloat, z as GLfloat, w as GLfloat)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform4fv(&mut self, location: i32, values: &[f32]) -> Result<(), Error> {
+        let call = self.begin("glUniform4fv", || vec![
+            Value::Location(location),
+            Value::Int((values.len() / 4) as i32),
+            Value::Floats(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glUniform4fv(location as GLint, (values.len() / 4) as GLsizei, values.as_ptr())
+            self.backend.glUniform4fv(location as GLint, (values.len() / 4) as GLsizei, values.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform4i(&mut self, location: i32, x: i32, y: i32, z: i32, w: i32) -> Result<(), Error> {
+        let call = self.begin("glUniform4i", || vec![
+            Value::Location(location),
+            Value::Int(x), Value::Int(y), Value::Int(z), Value::Int(w),
+        ])?;
+
         unsafe {
-            ffi::glUniform4i(location as GLint, x as GLint, y as GLint, z as GLint, w as GLint)
+            self.backend.glUniform4i(location as GLint, x as GLint, y as GLint, z as GLint, w as GLint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform4iv(&mut self, location: i32, values: &[i32]) -> Result<(), Error> {
+        let call = self.begin("glUniform4iv", || vec![
+            Value::Location(location),
+            Value::Int((values.len() / 4) as i32),
+            Value::Ints(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glUniform4iv(location as GLint, (values.len() / 4) as GLsizei, values.as_ptr())
+            self.backend.glUniform4iv(location as GLint, (values.len() / 4) as GLsizei, values.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform_matrix2fv(&mut self, location: i32, transpose: bool, values: &[f32]) -> Result<(), Error> {
+        let call = self.begin("glUniformMatrix2fv", || vec![
+            Value::Location(location),
+            Value::Int((values.len() / 4) as i32),
+            Value::Bool(transpose),
+            Value::Floats(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glUniformMatrix2fv(
-                location as i32,
-                (values.len() / 2*2) as GLsizei,
+            self.backend.glUniformMatrix2fv(
+                location,
+                (values.len() / 4) as GLsizei,
                 transpose as GLboolean,
                 values.as_ptr() as *const GLfloat,
             )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform_matrix3fv(&mut self, location: i32, transpose: bool, values: &[f32]) -> Result<(), Error> {
+        let call = self.begin("glUniformMatrix3fv", || vec![
+            Value::Location(location),
+            Value::Int((values.len() / 9) as i32),
+            Value::Bool(transpose),
+            Value::Floats(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glUniformMatrix3fv(
+            self.backend.glUniformMatrix3fv(
                 location as GLint,
-                (values.len() / 3*3) as GLsizei,
+                (values.len() / 9) as GLsizei,
                 transpose as GLboolean,
                 values.as_ptr() as *const GLfloat,
             )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_uniform_matrix4fv(&mut self, location: i32, transpose: bool,
                                 values: &[f32]) -> Result<(), Error> {
+        let call = self.begin("glUniformMatrix4fv", || vec![
+            Value::Location(location),
+            Value::Int((values.len() / 16) as i32),
+            Value::Bool(transpose),
+            Value::Floats(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glUniformMatrix4fv(
+            self.backend.glUniformMatrix4fv(
                 location as GLint,
-                (values.len() / 4*4) as GLsizei,
+                (values.len() / 16) as GLsizei,
                 transpose as GLboolean,
                 values.as_ptr() as *const GLfloat,
             )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_use_program(&mut self, program: u32) -> Result<(), Error> {
+        let call = self.begin("glUseProgram", || vec![Value::Name(ObjectKind::Program, program)])?;
+
         unsafe {
-            ffi::glUseProgram(program as GLuint)
+            self.backend.glUseProgram(program as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_validate_program(&mut self, program: u32) -> Result<(), Error> {
+        let call = self.begin("glValidateProgram", || vec![Value::Name(ObjectKind::Program, program)])?;
+
         unsafe {
-            ffi::glValidateProgram(program as GLuint)
+            self.backend.glValidateProgram(program as GLuint)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_vertex_attrib1f(&mut self, index: u32, x: f32) -> Result<(), Error> {
+        let call = self.begin("glVertexAttrib1f", || vec![Value::Uint(index), Value::Float(x)])?;
+
         unsafe {
-            ffi::glVertexAttrib1f(index as GLuint, x as GLfloat)
+            self.backend.glVertexAttrib1f(index as GLuint, x as GLfloat)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_vertex_attrib1fv(&mut self, index: u32, values: &[f32]) -> Result<(), Error> {
+        check_length("glVertexAttrib1fv", 1, values.len())?;
+
+        let call = self.begin("glVertexAttrib1fv", || vec![
+            Value::Uint(index),
+            Value::Floats(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glVertexAttrib1fv(index as GLuint, values.as_ptr())
+            self.backend.glVertexAttrib1fv(index as GLuint, values.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_vertex_attrib2f(&mut self, index: u32, x: f32, y: f32) -> Result<(), Error> {
+        let call = self.begin("glVertexAttrib2f", || vec![
+            Value::Uint(index), Value::Float(x), Value::Float(y),
+        ])?;
+
         unsafe {
-            ffi::glVertexAttrib2f(index as GLuint, x as GLfloat, y as GLfloat)
+            self.backend.glVertexAttrib2f(index as GLuint, x as GLfloat, y as GLfloat)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_vertex_attrib2fv(&mut self, index: u32, values: &[f32]) -> Result<(), Error> {
+        check_length("glVertexAttrib2fv", 2, values.len())?;
+
+        let call = self.begin("glVertexAttrib2fv", || vec![
+            Value::Uint(index),
+            Value::Floats(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glVertexAttrib2fv(index as GLuint, values.as_ptr())
+            self.backend.glVertexAttrib2fv(index as GLuint, values.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_vertex_attrib3f(&mut self, index: u32, x: f32, y: f32, z: f32) -> Result<(), Error> {
+        let call = self.begin("glVertexAttrib3f", || vec![
+            Value::Uint(index), Value::Float(x), Value::Float(y), Value::Float(z),
+        ])?;
+
         unsafe {
-            ffi::glVertexAttrib3f(index as GLuint, x as GLfloat, y as GLfloat, z as GLfloat)
+            self.backend.glVertexAttrib3f(index as GLuint, x as GLfloat, y as GLfloat, z as GLfloat)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_vertex_attrib3fv(&mut self, index: u32, values: &[f32]) -> Result<(), Error> {
+        check_length("glVertexAttrib3fv", 3, values.len())?;
+
+        let call = self.begin("glVertexAttrib3fv", || vec![
+            Value::Uint(index),
+            Value::Floats(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glVertexAttrib3fv(index as GLuint, values.as_ptr())
+            self.backend.glVertexAttrib3fv(index as GLuint, values.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_vertex_attrib4f(&mut self, index: u32, x: f32, y: f32, z: f32, w: f32) -> Result<(), Error> {
+        let call = self.begin("glVertexAttrib4f", || vec![
+            Value::Uint(index),
+            Value::Float(x), Value::Float(y), Value::Float(z), Value::Float(w),
+        ])?;
+
         unsafe {
-            ffi::glVertexAttrib4f(index as GLuint, x as GLfloat, y as GLfloat, z as GLfloat,
-                                  w as GLfloat)
+            self.backend.glVertexAttrib4f(index as GLuint, x as GLfloat, y as GLfloat, z as GLfloat,
+                                          w as GLfloat)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_vertex_attrib4fv(&mut self, index: u32, values: &[f32]) -> Result<(), Error> {
+        check_length("glVertexAttrib4fv", 4, values.len())?;
+
+        let call = self.begin("glVertexAttrib4fv", || vec![
+            Value::Uint(index),
+            Value::Floats(values.to_vec()),
+        ])?;
+
         unsafe {
-            ffi::glVertexAttrib4fv(index as GLuint, values.as_ptr())
+            self.backend.glVertexAttrib4fv(index as GLuint, values.as_ptr())
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
-    pub fn gl_vertex_attrib_pointer<T>(
+    /// Points attribute `index` at client memory. With a buffer bound to `ARRAY_BUFFER` use
+    /// `gl_vertex_attrib_pointer_offset` instead; `client_array::Draw` is the safe way to draw
+    /// from client memory.
+    ///
+    /// # Safety
+    ///
+    /// GL reads `buffer` at every later draw, so it must stay alive and unmoved until the attribute
+    /// is pointed elsewhere or disabled, and it must hold every vertex those draws read.
+    pub unsafe fn gl_vertex_attrib_pointer<T>(
         &mut self,
         index: u32,
         size: i32,
@@ -1669,28 +2877,32 @@ impl Wrapper {
         stride: i32,
         buffer: &[T],
     ) -> Result<(), Error> where T: std::fmt::Debug + Clone {
-        unsafe {
-            if buffer.len() == 0 {
-                ffi::glVertexAttribPointer(
-                    index as GLuint,
-                    size as GLint,
-                    type_ as GLenum,
-                    normalized as GLboolean,
-                    stride as GLsizei,
-                    &0 as *const i32 as *const GLvoid,
-                )
-            } else {
-                ffi::glVertexAttribPointer(
-                    index as GLuint,
-                    size as GLint,
-                    type_ as GLenum,
-                    normalized as GLboolean,
-                    stride as GLsizei,
-                    buffer.as_ptr() as *const GLvoid,
-                )
-            }
+        if buffer.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidArgument,
+                                  "glVertexAttribPointer: empty client array, use gl_vertex_attrib_pointer_offset \
+                                   for buffer offsets"));
+        }
+        let call = self.begin("glVertexAttribPointer", || vec![
+            Value::Uint(index),
+            Value::Int(size),
+            Value::Enum(type_ as GLenum),
+            Value::Bool(normalized),
+            Value::Int(stride),
+            Value::bytes(buffer),
+        ])?;
+
+        unsafe {
+            self.backend.glVertexAttribPointer(
+                index as GLuint,
+                size as GLint,
+                type_ as GLenum,
+                normalized as GLboolean,
+                stride as GLsizei,
+                buffer.as_ptr() as *const GLvoid,
+            )
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
@@ -1703,24 +2915,189 @@ impl Wrapper {
         stride: i32,
         offset: u32,
     ) -> Result<(), Error> {
+        let call = self.begin("glVertexAttribPointer", || vec![
+            Value::Uint(index),
+            Value::Int(size),
+            Value::Enum(type_ as GLenum),
+            Value::Bool(normalized),
+            Value::Int(stride),
+            Value::Offset(offset as usize),
+        ])?;
+
         unsafe {
-            ffi::glVertexAttribPointer(
+            self.backend.glVertexAttribPointer(
                 index as GLuint,
                 size as GLint,
                 type_ as GLenum,
                 normalized as GLboolean,
-                stride as i32,
+                stride,
                 offset as *const GLvoid)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
 
     pub fn gl_viewport(&mut self, x: i32, y: i32, width: i32, height: i32) -> Result<(), Error> {
+        let call = self.begin("glViewport", || vec![
+            Value::Int(x), Value::Int(y), Value::Int(width), Value::Int(height),
+        ])?;
+
         unsafe {
-            ffi::glViewport(x as GLint, y as GLint, width as GLsizei, height as GLsizei)
+            self.backend.glViewport(x as GLint, y as GLint, width as GLsizei, height as GLsizei)
         }
 
+        self.end(call, || Value::Void);
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+// -------------------------------------------------------------------------------------------------
+// SIZE CHECKS
+// -------------------------------------------------------------------------------------------------
+
+fn check_length(name: &str, needed: usize, length: usize) -> Result<(), Error> {
+    if length < needed {
+        return Err(Error::new(ErrorKind::InvalidArgument, format!(
+            "{}: needs {} values but {} were passed", name, needed, length)));
+    }
+
+    Ok(())
+}
+
+fn check_image_size(name: &str, width: i32, height: i32, format: PixelFormat, type_: PixelDataType,
+                    alignment: GLint, length: usize) -> Result<(), Error> {
+    let info = match FormatInfo::for_transfer(format as GLenum, type_ as GLenum) {
+        Some(info) => info,
+        None => {
+            return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                "{}: {:?} is not a pixel transfer format for {:?}", name, format, type_)));
+        }
+    };
+
+    let needed = info.image_size(width.max(0) as usize, height.max(0) as usize, alignment.max(1) as usize);
+
+    if length < needed {
+        return Err(Error::new(ErrorKind::InvalidArgument, format!(
+            "{}: a {}x{} {:?}/{:?} image with an alignment of {} needs {} bytes but the buffer holds {}",
+            name, width, height, format, type_, alignment, needed, length)));
+    }
+
+    Ok(())
+}
+
+// `image_size` has to match the format's block layout when the format is known, and the buffer
+// has to hold `image_size` bytes in any case.
+fn check_compressed_size<T>(name: &str, format: GLenum, width: u32, height: u32, image_size: u32,
+                            buffer: &[T]) -> Result<(), Error> {
+    if let Some(info) = FormatInfo::for_internal_format(format).filter(|info| info.is_compressed) {
+        let needed = info.image_size(width as usize, height as usize, 1);
+        if image_size as usize != needed {
+            return Err(Error::new(ErrorKind::InvalidArgument, format!(
+                "{}: a {}x{} image in format 0x{:x} takes {} bytes, not {}",
+                name, width, height, format, needed, image_size)));
+        }
+    }
+
+    if size_of_val(buffer) < image_size as usize {
+        return Err(Error::new(ErrorKind::InvalidArgument, format!(
+            "{}: image_size is {} bytes but the buffer holds {}", name, image_size, size_of_val(buffer))));
+    }
+
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use es20::software::SoftwareBackend;
+
+    #[test]
+    fn short_images_are_rejected() {
+        let rgba = |length| check_image_size("test", 2, 2, PixelFormat::RGBA, PixelDataType::UNSIGNED_BYTE, 4, length);
+
+        assert!(rgba(16).is_ok());
+        assert_eq!(rgba(15).unwrap_err().kind, ErrorKind::InvalidArgument);
+
+        // Packed types take two bytes per pixel whatever the format's component count.
+        let packed = check_image_size("test", 2, 2, PixelFormat::RGB, PixelDataType::UNSIGNED_SHORT_5_6_5, 1, 8);
+        assert!(packed.is_ok());
+    }
+
+    #[test]
+    fn rows_are_padded_to_the_unpack_alignment() {
+        let rgb = |alignment, length| {
+            check_image_size("test", 3, 2, PixelFormat::RGB, PixelDataType::UNSIGNED_BYTE, alignment, length)
+        };
+
+        // The last row is never padded: 12 + 9 bytes at an alignment of 4, 9 + 9 at 1.
+        assert!(rgb(4, 21).is_ok());
+        assert!(rgb(4, 20).is_err());
+        assert!(rgb(1, 18).is_ok());
+        assert!(rgb(1, 17).is_err());
+    }
+
+    #[test]
+    fn compressed_sizes_round_up_to_whole_blocks() {
+        let buffer = [0u8; 32];
+
+        // A 5x5 ETC1 image takes 2x2 blocks of 8 bytes.
+        assert!(check_compressed_size("test", GL_ETC1_RGB8_OES, 5, 5, 32, &buffer).is_ok());
+
+        let wrong = check_compressed_size("test", GL_ETC1_RGB8_OES, 5, 5, 25, &buffer).unwrap_err();
+        assert_eq!(wrong.kind, ErrorKind::InvalidArgument);
+
+        let short = check_compressed_size("test", GL_ETC1_RGB8_OES, 5, 5, 32, &buffer[..24]).unwrap_err();
+        assert_eq!(short.kind, ErrorKind::InvalidArgument);
+
+        // Unknown formats only need the buffer to hold image_size.
+        assert!(check_compressed_size("test", 0x1234, 5, 5, 32, &buffer).is_ok());
+        assert!(check_compressed_size("test", 0x1234, 5, 5, 33, &buffer).is_err());
+    }
+
+    #[test]
+    fn tex_image_checks_the_buffer_before_calling_gl() {
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(4, 4)));
+        let texture = gl.gl_gen_textures(1).unwrap()[0];
+        gl.gl_bind_texture(TextureBindTarget::TEXTURE_2D, texture).unwrap();
+
+        let short = gl.gl_tex_image_2d(TextureTarget::TEXTURE_2D, 0, GL_RGBA as GLint, 2, 2, 0, PixelFormat::RGBA,
+                                       PixelDataType::UNSIGNED_BYTE, &[0u8; 15]);
+        assert_eq!(short.unwrap_err().kind, ErrorKind::InvalidArgument);
+
+        // An empty slice allocates the level.
+        let empty: &[u8] = &[];
+        gl.gl_tex_image_2d(TextureTarget::TEXTURE_2D, 0, GL_RGBA as GLint, 2, 2, 0, PixelFormat::RGBA,
+                           PixelDataType::UNSIGNED_BYTE, empty).unwrap();
+        assert_eq!(gl.gl_get_error(), ErrorType::NO_ERROR);
+    }
+
+    #[test]
+    fn shader_sources_are_passed_in_parts() {
+        let mut gl = Wrapper::with_backend(Box::new(SoftwareBackend::new(4, 4)));
+        let shader = gl.gl_create_shader(ShaderType::FRAGMENT_SHADER).unwrap();
+        let parts = ["precision mediump float;\n", "#define RED 1.0\n",
+                     "void main() { gl_FragColor = vec4(RED, 0.0, 0.0, 1.0); }\n"];
+
+        let mut arrays = ShaderSourceArrays::default();
+        gl.gl_shader_source_with(shader, &parts, &mut arrays).unwrap();
+        gl.gl_compile_shader(shader).unwrap();
+
+        assert_eq!(gl.gl_get_shaderiv(shader, ShaderParamType::COMPILE_STATUS).unwrap(), GL_TRUE as i32);
+        assert_eq!(gl.gl_get_shader_source(shader, 1024).unwrap(), parts.concat());
+
+        // The borrowed pointers are dropped, the allocations are kept for the next call.
+        assert!(arrays.pointers.is_empty());
+        let capacity = arrays.pointers.capacity();
+        gl.gl_shader_source_with(shader, &parts[..2], &mut arrays).unwrap();
+        assert_eq!(arrays.pointers.capacity(), capacity);
+        assert_eq!(arrays.lengths, vec![parts[0].len() as GLint, parts[1].len() as GLint]);
+
+        gl.gl_shader_source_array(shader, &["void main() {", "}"]).unwrap();
+        assert_eq!(gl.gl_get_shader_source(shader, 1024).unwrap(), "void main() {}");
+    }
+}
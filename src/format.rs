@@ -0,0 +1,432 @@
+use types::*;
+use consts::*;
+use self::GlVersion::{Es20, Es30};
+
+// -------------------------------------------------------------------------------------------------
+// ENUMS
+// -------------------------------------------------------------------------------------------------
+
+/// An OpenGL ES core version. Versions are ordered, so `Es20 < Es30`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GlVersion {
+    Es20,
+    Es30,
+}
+
+/// Where a format comes from: a core version or an extension.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Availability {
+    Core(GlVersion),
+    Extension(&'static str),
+}
+
+// -------------------------------------------------------------------------------------------------
+// STRUCTS
+// -------------------------------------------------------------------------------------------------
+
+/// Metadata of one valid texture format combination.
+///
+/// Uncompressed entries are the `(internal_format, format, type_)` triples `glTexImage2D` accepts:
+/// the unsized ES 2.0 formats, where `internal_format == format`, and the sized ES 3.0 formats,
+/// several of which can be specified with more than one client type. Compressed entries have
+/// `format` set to the base format they decode to and `type_` set to 0.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FormatInfo {
+    pub internal_format: GLenum,
+    pub format: GLenum,
+    pub type_: GLenum,
+    pub components: u8,
+    /// Bits per channel in R, G, B, A order. Luminance is stored in R.
+    pub bits: [u8; 4],
+    pub depth_bits: u8,
+    pub stencil_bits: u8,
+    /// Size of one pixel of client data in `format`/`type_`, 0 for compressed formats.
+    pub bytes_per_pixel: u8,
+    pub is_integer: bool,
+    pub is_srgb: bool,
+    pub is_compressed: bool,
+    pub block_width: u8,
+    pub block_height: u8,
+    /// Size of one block of a compressed format, 0 for uncompressed formats.
+    pub block_bytes: u8,
+    /// The first version in which the format can be a color attachment, if any.
+    pub color_renderable: Option<GlVersion>,
+    /// The first version in which the format supports linear filtering, if any.
+    pub filterable: Option<GlVersion>,
+    pub availability: Availability,
+}
+
+impl FormatInfo {
+    const fn color(internal_format: GLenum, format: GLenum, type_: GLenum, bits: [u8; 4], bytes_per_pixel: u8,
+                   version: GlVersion, renderable: bool, filterable: bool) -> FormatInfo {
+        let components = (bits[0] > 0) as u8 + (bits[1] > 0) as u8 + (bits[2] > 0) as u8 + (bits[3] > 0) as u8;
+
+        FormatInfo {
+            internal_format,
+            format,
+            type_,
+            components,
+            bits,
+            depth_bits: 0,
+            stencil_bits: 0,
+            bytes_per_pixel,
+            is_integer: false,
+            is_srgb: false,
+            is_compressed: false,
+            block_width: 1,
+            block_height: 1,
+            block_bytes: 0,
+            color_renderable: if renderable { Some(version) } else { None },
+            filterable: if filterable { Some(version) } else { None },
+            availability: Availability::Core(version),
+        }
+    }
+
+    const fn depth(internal_format: GLenum, format: GLenum, type_: GLenum, depth_bits: u8, stencil_bits: u8,
+                   bytes_per_pixel: u8, version: GlVersion) -> FormatInfo {
+        FormatInfo {
+            components: (depth_bits > 0) as u8 + (stencil_bits > 0) as u8,
+            bits: [0; 4],
+            depth_bits,
+            stencil_bits,
+            color_renderable: None,
+            filterable: None,
+            ..FormatInfo::color(internal_format, format, type_, [0; 4], bytes_per_pixel, version, false, false)
+        }
+    }
+
+    const fn compressed(internal_format: GLenum, format: GLenum, components: u8, block_width: u8, block_height: u8,
+                        block_bytes: u8, availability: Availability) -> FormatInfo {
+        FormatInfo {
+            components,
+            bits: [0; 4],
+            is_compressed: true,
+            block_width,
+            block_height,
+            block_bytes,
+            filterable: Some(GlVersion::Es20),
+            availability,
+            ..FormatInfo::color(internal_format, format, 0, [0; 4], 0, GlVersion::Es20, false, false)
+        }
+    }
+
+    const fn integer(mut self) -> FormatInfo {
+        self.is_integer = true;
+        self.filterable = None;
+        self
+    }
+
+    const fn srgb(mut self) -> FormatInfo {
+        self.is_srgb = true;
+        self
+    }
+
+    const fn extension(mut self, name: &'static str) -> FormatInfo {
+        self.availability = Availability::Extension(name);
+        self
+    }
+
+    /// Every known format combination.
+    pub fn all() -> &'static [FormatInfo] {
+        FORMATS
+    }
+
+    /// The entry for an exact `glTexImage2D` combination.
+    pub fn lookup(internal_format: GLenum, format: GLenum, type_: GLenum) -> Option<&'static FormatInfo> {
+        FORMATS.iter().find(|info| {
+            info.internal_format == internal_format && info.format == format && info.type_ == type_
+        })
+    }
+
+    /// The canonical entry of an internal format, i.e. the one with the most compact client type.
+    pub fn for_internal_format(internal_format: GLenum) -> Option<&'static FormatInfo> {
+        FORMATS.iter().find(|info| info.internal_format == internal_format)
+    }
+
+    /// An entry describing client pixel data in `format`/`type_`, as passed to `glReadPixels` or
+    /// `glTexSubImage2D`. Only `bytes_per_pixel`, `components` and `bits` are meaningful, since the
+    /// same pair can belong to several internal formats.
+    pub fn for_transfer(format: GLenum, type_: GLenum) -> Option<&'static FormatInfo> {
+        FORMATS.iter().find(|info| !info.is_compressed && info.format == format && info.type_ == type_)
+    }
+
+    /// The `(format, type_)` pairs that can be used to specify `internal_format`.
+    pub fn transfer_pairs(internal_format: GLenum) -> impl Iterator<Item = (GLenum, GLenum)> {
+        FORMATS.iter()
+            .filter(move |info| info.internal_format == internal_format && !info.is_compressed)
+            .map(|info| (info.format, info.type_))
+    }
+
+    pub fn is_depth(&self) -> bool {
+        self.depth_bits > 0
+    }
+
+    pub fn is_stencil(&self) -> bool {
+        self.stencil_bits > 0
+    }
+
+    pub fn is_color_renderable(&self, version: GlVersion) -> bool {
+        self.color_renderable.is_some_and(|first| first <= version)
+    }
+
+    pub fn is_filterable(&self, version: GlVersion) -> bool {
+        self.filterable.is_some_and(|first| first <= version)
+    }
+
+    /// Number of bytes of one `width`×`height` image. Rows of uncompressed data are padded to
+    /// `alignment` bytes (`GL_PACK_ALIGNMENT`/`GL_UNPACK_ALIGNMENT`) except for the last one;
+    /// compressed data is a whole number of blocks.
+    pub fn image_size(&self, width: usize, height: usize, alignment: usize) -> usize {
+        if width == 0 || height == 0 {
+            return 0;
+        }
+
+        if self.is_compressed {
+            // PVRTC needs at least two blocks in each direction.
+            let (width, height) = match self.internal_format {
+                GL_COMPRESSED_RGB_PVRTC_4BPPV1_IMG | GL_COMPRESSED_RGBA_PVRTC_4BPPV1_IMG |
+                GL_COMPRESSED_RGB_PVRTC_2BPPV1_IMG | GL_COMPRESSED_RGBA_PVRTC_2BPPV1_IMG => {
+                    (width.max(2 * self.block_width as usize), height.max(2 * self.block_height as usize))
+                }
+                _ => (width, height),
+            };
+
+            return width.div_ceil(self.block_width as usize)
+                * height.div_ceil(self.block_height as usize)
+                * self.block_bytes as usize;
+        }
+
+        let row = width * self.bytes_per_pixel as usize;
+        let alignment = alignment.max(1);
+
+        row.div_ceil(alignment) * alignment * (height - 1) + row
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// TABLE
+// -------------------------------------------------------------------------------------------------
+
+const fn core(version: GlVersion) -> Availability {
+    Availability::Core(version)
+}
+
+const ETC1: &str = "GL_OES_compressed_ETC1_RGB8_texture";
+const S3TC: &str = "GL_EXT_texture_compression_s3tc";
+const PVRTC: &str = "GL_IMG_texture_compression_pvrtc";
+const ASTC: &str = "GL_KHR_texture_compression_astc_ldr";
+
+// Entries of one internal format are listed together, canonical client type first.
+static FORMATS: &[FormatInfo] = &[
+    // Unsized ES 2.0 formats
+    FormatInfo::color(GL_RGBA, GL_RGBA, GL_UNSIGNED_BYTE, [8, 8, 8, 8], 4, Es20, true, true),
+    FormatInfo::color(GL_RGBA, GL_RGBA, GL_UNSIGNED_SHORT_4_4_4_4, [4, 4, 4, 4], 2, Es20, true, true),
+    FormatInfo::color(GL_RGBA, GL_RGBA, GL_UNSIGNED_SHORT_5_5_5_1, [5, 5, 5, 1], 2, Es20, true, true),
+    FormatInfo::color(GL_RGBA, GL_RGBA, GL_HALF_FLOAT_OES, [16, 16, 16, 16], 8, Es20, false, false)
+        .extension("GL_OES_texture_half_float"),
+    FormatInfo::color(GL_RGBA, GL_RGBA, GL_FLOAT, [32, 32, 32, 32], 16, Es20, false, false)
+        .extension("GL_OES_texture_float"),
+    FormatInfo::color(GL_RGB, GL_RGB, GL_UNSIGNED_BYTE, [8, 8, 8, 0], 3, Es20, true, true),
+    FormatInfo::color(GL_RGB, GL_RGB, GL_UNSIGNED_SHORT_5_6_5, [5, 6, 5, 0], 2, Es20, true, true),
+    FormatInfo::color(GL_RGB, GL_RGB, GL_HALF_FLOAT_OES, [16, 16, 16, 0], 6, Es20, false, false)
+        .extension("GL_OES_texture_half_float"),
+    FormatInfo::color(GL_RGB, GL_RGB, GL_FLOAT, [32, 32, 32, 0], 12, Es20, false, false)
+        .extension("GL_OES_texture_float"),
+    FormatInfo::color(GL_LUMINANCE_ALPHA, GL_LUMINANCE_ALPHA, GL_UNSIGNED_BYTE, [8, 0, 0, 8], 2, Es20, false, true),
+    FormatInfo::color(GL_LUMINANCE, GL_LUMINANCE, GL_UNSIGNED_BYTE, [8, 0, 0, 0], 1, Es20, false, true),
+    FormatInfo::color(GL_ALPHA, GL_ALPHA, GL_UNSIGNED_BYTE, [0, 0, 0, 8], 1, Es20, false, true),
+    FormatInfo::depth(GL_DEPTH_COMPONENT, GL_DEPTH_COMPONENT, GL_UNSIGNED_SHORT, 16, 0, 2, Es20)
+        .extension("GL_OES_depth_texture"),
+    FormatInfo::depth(GL_DEPTH_COMPONENT, GL_DEPTH_COMPONENT, GL_UNSIGNED_INT, 32, 0, 4, Es20)
+        .extension("GL_OES_depth_texture"),
+
+    // Sized ES 2.0 renderbuffer formats, which ES 3.0 also accepts for textures
+    FormatInfo::color(GL_RGBA4, GL_RGBA, GL_UNSIGNED_SHORT_4_4_4_4, [4, 4, 4, 4], 2, Es20, true, true),
+    FormatInfo::color(GL_RGBA4, GL_RGBA, GL_UNSIGNED_BYTE, [4, 4, 4, 4], 4, Es30, true, true),
+    FormatInfo::color(GL_RGB5_A1, GL_RGBA, GL_UNSIGNED_SHORT_5_5_5_1, [5, 5, 5, 1], 2, Es20, true, true),
+    FormatInfo::color(GL_RGB5_A1, GL_RGBA, GL_UNSIGNED_BYTE, [5, 5, 5, 1], 4, Es30, true, true),
+    FormatInfo::color(GL_RGB5_A1, GL_RGBA, GL_UNSIGNED_INT_2_10_10_10_REV, [5, 5, 5, 1], 4, Es30, true, true),
+    FormatInfo::color(GL_RGB565, GL_RGB, GL_UNSIGNED_SHORT_5_6_5, [5, 6, 5, 0], 2, Es20, true, true),
+    FormatInfo::color(GL_RGB565, GL_RGB, GL_UNSIGNED_BYTE, [5, 6, 5, 0], 3, Es30, true, true),
+    FormatInfo::depth(GL_DEPTH_COMPONENT16, GL_DEPTH_COMPONENT, GL_UNSIGNED_SHORT, 16, 0, 2, Es20),
+    FormatInfo::depth(GL_DEPTH_COMPONENT16, GL_DEPTH_COMPONENT, GL_UNSIGNED_INT, 16, 0, 4, Es30),
+    FormatInfo::depth(GL_STENCIL_INDEX8, GL_STENCIL_INDEX, GL_UNSIGNED_BYTE, 0, 8, 1, Es20),
+
+    // Sized ES 3.0 formats
+    FormatInfo::color(GL_R8, GL_RED, GL_UNSIGNED_BYTE, [8, 0, 0, 0], 1, Es30, true, true),
+    FormatInfo::color(GL_R8_SNORM, GL_RED, GL_BYTE, [8, 0, 0, 0], 1, Es30, false, true),
+    FormatInfo::color(GL_R16F, GL_RED, GL_HALF_FLOAT, [16, 0, 0, 0], 2, Es30, false, true),
+    FormatInfo::color(GL_R16F, GL_RED, GL_FLOAT, [16, 0, 0, 0], 4, Es30, false, true),
+    FormatInfo::color(GL_R32F, GL_RED, GL_FLOAT, [32, 0, 0, 0], 4, Es30, false, false),
+    FormatInfo::color(GL_R8UI, GL_RED_INTEGER, GL_UNSIGNED_BYTE, [8, 0, 0, 0], 1, Es30, true, false).integer(),
+    FormatInfo::color(GL_R8I, GL_RED_INTEGER, GL_BYTE, [8, 0, 0, 0], 1, Es30, true, false).integer(),
+    FormatInfo::color(GL_R16UI, GL_RED_INTEGER, GL_UNSIGNED_SHORT, [16, 0, 0, 0], 2, Es30, true, false).integer(),
+    FormatInfo::color(GL_R16I, GL_RED_INTEGER, GL_SHORT, [16, 0, 0, 0], 2, Es30, true, false).integer(),
+    FormatInfo::color(GL_R32UI, GL_RED_INTEGER, GL_UNSIGNED_INT, [32, 0, 0, 0], 4, Es30, true, false).integer(),
+    FormatInfo::color(GL_R32I, GL_RED_INTEGER, GL_INT, [32, 0, 0, 0], 4, Es30, true, false).integer(),
+    FormatInfo::color(GL_RG8, GL_RG, GL_UNSIGNED_BYTE, [8, 8, 0, 0], 2, Es30, true, true),
+    FormatInfo::color(GL_RG8_SNORM, GL_RG, GL_BYTE, [8, 8, 0, 0], 2, Es30, false, true),
+    FormatInfo::color(GL_RG16F, GL_RG, GL_HALF_FLOAT, [16, 16, 0, 0], 4, Es30, false, true),
+    FormatInfo::color(GL_RG16F, GL_RG, GL_FLOAT, [16, 16, 0, 0], 8, Es30, false, true),
+    FormatInfo::color(GL_RG32F, GL_RG, GL_FLOAT, [32, 32, 0, 0], 8, Es30, false, false),
+    FormatInfo::color(GL_RG8UI, GL_RG_INTEGER, GL_UNSIGNED_BYTE, [8, 8, 0, 0], 2, Es30, true, false).integer(),
+    FormatInfo::color(GL_RG8I, GL_RG_INTEGER, GL_BYTE, [8, 8, 0, 0], 2, Es30, true, false).integer(),
+    FormatInfo::color(GL_RG16UI, GL_RG_INTEGER, GL_UNSIGNED_SHORT, [16, 16, 0, 0], 4, Es30, true, false).integer(),
+    FormatInfo::color(GL_RG16I, GL_RG_INTEGER, GL_SHORT, [16, 16, 0, 0], 4, Es30, true, false).integer(),
+    FormatInfo::color(GL_RG32UI, GL_RG_INTEGER, GL_UNSIGNED_INT, [32, 32, 0, 0], 8, Es30, true, false).integer(),
+    FormatInfo::color(GL_RG32I, GL_RG_INTEGER, GL_INT, [32, 32, 0, 0], 8, Es30, true, false).integer(),
+    FormatInfo::color(GL_RGB8, GL_RGB, GL_UNSIGNED_BYTE, [8, 8, 8, 0], 3, Es30, true, true),
+    FormatInfo::color(GL_SRGB8, GL_RGB, GL_UNSIGNED_BYTE, [8, 8, 8, 0], 3, Es30, false, true).srgb(),
+    FormatInfo::color(GL_RGB8_SNORM, GL_RGB, GL_BYTE, [8, 8, 8, 0], 3, Es30, false, true),
+    FormatInfo::color(GL_R11F_G11F_B10F, GL_RGB, GL_UNSIGNED_INT_10F_11F_11F_REV, [11, 11, 10, 0], 4, Es30, false, true),
+    FormatInfo::color(GL_R11F_G11F_B10F, GL_RGB, GL_HALF_FLOAT, [11, 11, 10, 0], 6, Es30, false, true),
+    FormatInfo::color(GL_R11F_G11F_B10F, GL_RGB, GL_FLOAT, [11, 11, 10, 0], 12, Es30, false, true),
+    FormatInfo::color(GL_RGB9_E5, GL_RGB, GL_UNSIGNED_INT_5_9_9_9_REV, [9, 9, 9, 0], 4, Es30, false, true),
+    FormatInfo::color(GL_RGB9_E5, GL_RGB, GL_HALF_FLOAT, [9, 9, 9, 0], 6, Es30, false, true),
+    FormatInfo::color(GL_RGB9_E5, GL_RGB, GL_FLOAT, [9, 9, 9, 0], 12, Es30, false, true),
+    FormatInfo::color(GL_RGB16F, GL_RGB, GL_HALF_FLOAT, [16, 16, 16, 0], 6, Es30, false, true),
+    FormatInfo::color(GL_RGB16F, GL_RGB, GL_FLOAT, [16, 16, 16, 0], 12, Es30, false, true),
+    FormatInfo::color(GL_RGB32F, GL_RGB, GL_FLOAT, [32, 32, 32, 0], 12, Es30, false, false),
+    FormatInfo::color(GL_RGB8UI, GL_RGB_INTEGER, GL_UNSIGNED_BYTE, [8, 8, 8, 0], 3, Es30, false, false).integer(),
+    FormatInfo::color(GL_RGB8I, GL_RGB_INTEGER, GL_BYTE, [8, 8, 8, 0], 3, Es30, false, false).integer(),
+    FormatInfo::color(GL_RGB16UI, GL_RGB_INTEGER, GL_UNSIGNED_SHORT, [16, 16, 16, 0], 6, Es30, false, false).integer(),
+    FormatInfo::color(GL_RGB16I, GL_RGB_INTEGER, GL_SHORT, [16, 16, 16, 0], 6, Es30, false, false).integer(),
+    FormatInfo::color(GL_RGB32UI, GL_RGB_INTEGER, GL_UNSIGNED_INT, [32, 32, 32, 0], 12, Es30, false, false).integer(),
+    FormatInfo::color(GL_RGB32I, GL_RGB_INTEGER, GL_INT, [32, 32, 32, 0], 12, Es30, false, false).integer(),
+    FormatInfo::color(GL_RGBA8, GL_RGBA, GL_UNSIGNED_BYTE, [8, 8, 8, 8], 4, Es30, true, true),
+    FormatInfo::color(GL_SRGB8_ALPHA8, GL_RGBA, GL_UNSIGNED_BYTE, [8, 8, 8, 8], 4, Es30, true, true).srgb(),
+    FormatInfo::color(GL_RGBA8_SNORM, GL_RGBA, GL_BYTE, [8, 8, 8, 8], 4, Es30, false, true),
+    FormatInfo::color(GL_RGB10_A2, GL_RGBA, GL_UNSIGNED_INT_2_10_10_10_REV, [10, 10, 10, 2], 4, Es30, true, true),
+    FormatInfo::color(GL_RGBA16F, GL_RGBA, GL_HALF_FLOAT, [16, 16, 16, 16], 8, Es30, false, true),
+    FormatInfo::color(GL_RGBA16F, GL_RGBA, GL_FLOAT, [16, 16, 16, 16], 16, Es30, false, true),
+    FormatInfo::color(GL_RGBA32F, GL_RGBA, GL_FLOAT, [32, 32, 32, 32], 16, Es30, false, false),
+    FormatInfo::color(GL_RGBA8UI, GL_RGBA_INTEGER, GL_UNSIGNED_BYTE, [8, 8, 8, 8], 4, Es30, true, false).integer(),
+    FormatInfo::color(GL_RGBA8I, GL_RGBA_INTEGER, GL_BYTE, [8, 8, 8, 8], 4, Es30, true, false).integer(),
+    FormatInfo::color(GL_RGB10_A2UI, GL_RGBA_INTEGER, GL_UNSIGNED_INT_2_10_10_10_REV, [10, 10, 10, 2], 4, Es30, true, false).integer(),
+    FormatInfo::color(GL_RGBA16UI, GL_RGBA_INTEGER, GL_UNSIGNED_SHORT, [16, 16, 16, 16], 8, Es30, true, false).integer(),
+    FormatInfo::color(GL_RGBA16I, GL_RGBA_INTEGER, GL_SHORT, [16, 16, 16, 16], 8, Es30, true, false).integer(),
+    FormatInfo::color(GL_RGBA32UI, GL_RGBA_INTEGER, GL_UNSIGNED_INT, [32, 32, 32, 32], 16, Es30, true, false).integer(),
+    FormatInfo::color(GL_RGBA32I, GL_RGBA_INTEGER, GL_INT, [32, 32, 32, 32], 16, Es30, true, false).integer(),
+    FormatInfo::depth(GL_DEPTH_COMPONENT24, GL_DEPTH_COMPONENT, GL_UNSIGNED_INT, 24, 0, 4, Es30),
+    FormatInfo::depth(GL_DEPTH_COMPONENT32F, GL_DEPTH_COMPONENT, GL_FLOAT, 32, 0, 4, Es30),
+    FormatInfo::depth(GL_DEPTH24_STENCIL8, GL_DEPTH_STENCIL, GL_UNSIGNED_INT_24_8, 24, 8, 4, Es30),
+    FormatInfo::depth(GL_DEPTH32F_STENCIL8, GL_DEPTH_STENCIL, GL_FLOAT_32_UNSIGNED_INT_24_8_REV, 32, 8, 8, Es30),
+
+    // Compressed formats
+    FormatInfo::compressed(GL_COMPRESSED_R11_EAC, GL_RED, 1, 4, 4, 8, core(Es30)),
+    FormatInfo::compressed(GL_COMPRESSED_SIGNED_R11_EAC, GL_RED, 1, 4, 4, 8, core(Es30)),
+    FormatInfo::compressed(GL_COMPRESSED_RG11_EAC, GL_RG, 2, 4, 4, 16, core(Es30)),
+    FormatInfo::compressed(GL_COMPRESSED_SIGNED_RG11_EAC, GL_RG, 2, 4, 4, 16, core(Es30)),
+    FormatInfo::compressed(GL_COMPRESSED_RGB8_ETC2, GL_RGB, 3, 4, 4, 8, core(Es30)),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ETC2, GL_RGB, 3, 4, 4, 8, core(Es30)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2, GL_RGBA, 4, 4, 4, 8, core(Es30)),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2, GL_RGBA, 4, 4, 4, 8, core(Es30)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA8_ETC2_EAC, GL_RGBA, 4, 4, 4, 16, core(Es30)),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ETC2_EAC, GL_RGBA, 4, 4, 4, 16, core(Es30)).srgb(),
+    FormatInfo::compressed(GL_ETC1_RGB8_OES, GL_RGB, 3, 4, 4, 8, Availability::Extension(ETC1)),
+    FormatInfo::compressed(GL_COMPRESSED_RGB_S3TC_DXT1_EXT, GL_RGB, 3, 4, 4, 8, Availability::Extension(S3TC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_S3TC_DXT1_EXT, GL_RGBA, 4, 4, 4, 8, Availability::Extension(S3TC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_S3TC_DXT3_EXT, GL_RGBA, 4, 4, 4, 16, Availability::Extension(S3TC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_S3TC_DXT5_EXT, GL_RGBA, 4, 4, 4, 16, Availability::Extension(S3TC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGB_PVRTC_4BPPV1_IMG, GL_RGB, 3, 4, 4, 8, Availability::Extension(PVRTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGB_PVRTC_2BPPV1_IMG, GL_RGB, 3, 8, 4, 8, Availability::Extension(PVRTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_PVRTC_4BPPV1_IMG, GL_RGBA, 4, 4, 4, 8, Availability::Extension(PVRTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_PVRTC_2BPPV1_IMG, GL_RGBA, 4, 8, 4, 8, Availability::Extension(PVRTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_4x4, GL_RGBA, 4, 4, 4, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_5x4, GL_RGBA, 4, 5, 4, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_5x5, GL_RGBA, 4, 5, 5, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_6x5, GL_RGBA, 4, 6, 5, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_6x6, GL_RGBA, 4, 6, 6, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_8x5, GL_RGBA, 4, 8, 5, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_8x6, GL_RGBA, 4, 8, 6, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_8x8, GL_RGBA, 4, 8, 8, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_10x5, GL_RGBA, 4, 10, 5, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_10x6, GL_RGBA, 4, 10, 6, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_10x8, GL_RGBA, 4, 10, 8, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_10x10, GL_RGBA, 4, 10, 10, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_12x10, GL_RGBA, 4, 12, 10, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_RGBA_ASTC_12x12, GL_RGBA, 4, 12, 12, 16, Availability::Extension(ASTC)),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4x4, GL_RGBA, 4, 4, 4, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5x4, GL_RGBA, 4, 5, 4, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5x5, GL_RGBA, 4, 5, 5, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6x5, GL_RGBA, 4, 6, 5, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6x6, GL_RGBA, 4, 6, 6, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8x5, GL_RGBA, 4, 8, 5, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8x6, GL_RGBA, 4, 8, 6, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8x8, GL_RGBA, 4, 8, 8, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10x5, GL_RGBA, 4, 10, 5, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10x6, GL_RGBA, 4, 10, 6, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10x8, GL_RGBA, 4, 10, 8, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10x10, GL_RGBA, 4, 10, 10, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12x10, GL_RGBA, 4, 12, 10, 16, Availability::Extension(ASTC)).srgb(),
+    FormatInfo::compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12x12, GL_RGBA, 4, 12, 12, 16, Availability::Extension(ASTC)).srgb(),
+];
+
+// -------------------------------------------------------------------------------------------------
+// TESTS
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compressed(internal_format: GLenum) -> &'static FormatInfo {
+        FormatInfo::for_internal_format(internal_format).unwrap()
+    }
+
+    #[test]
+    fn compressed_sizes_are_whole_blocks() {
+        let etc1 = compressed(GL_ETC1_RGB8_OES);
+        assert_eq!(etc1.image_size(4, 4, 4), 8);
+        assert_eq!(etc1.image_size(5, 5, 4), 32);
+        assert_eq!(etc1.image_size(1, 1, 4), 8);
+
+        // Blocks need not be square.
+        let astc = compressed(GL_COMPRESSED_RGBA_ASTC_10x5);
+        assert_eq!(astc.image_size(11, 11, 1), 2 * 3 * 16);
+
+        // The alignment only applies to uncompressed rows.
+        assert_eq!(etc1.image_size(5, 5, 1), etc1.image_size(5, 5, 8));
+    }
+
+    #[test]
+    fn pvrtc_images_take_at_least_two_blocks_each_way() {
+        let pvrtc4 = compressed(GL_COMPRESSED_RGBA_PVRTC_4BPPV1_IMG);
+        assert_eq!(pvrtc4.image_size(1, 1, 4), 2 * 2 * 8);
+        assert_eq!(pvrtc4.image_size(16, 16, 4), 4 * 4 * 8);
+
+        let pvrtc2 = compressed(GL_COMPRESSED_RGB_PVRTC_2BPPV1_IMG);
+        assert_eq!(pvrtc2.image_size(8, 8, 4), 2 * 2 * 8);
+        assert_eq!(pvrtc2.image_size(32, 8, 4), 4 * 2 * 8);
+    }
+
+    #[test]
+    fn uncompressed_rows_are_padded_except_the_last() {
+        let rgb = FormatInfo::for_transfer(GL_RGB, GL_UNSIGNED_BYTE).unwrap();
+        assert_eq!(rgb.image_size(3, 2, 4), 12 + 9);
+        assert_eq!(rgb.image_size(3, 2, 1), 9 + 9);
+        assert_eq!(rgb.image_size(3, 2, 0), 9 + 9);
+        assert_eq!(rgb.image_size(0, 2, 4), 0);
+    }
+
+    #[test]
+    fn transfers_are_looked_up_by_format_and_type() {
+        let rgba = FormatInfo::for_transfer(GL_RGBA, GL_UNSIGNED_BYTE).unwrap();
+        assert_eq!((rgba.bytes_per_pixel, rgba.components), (4, 4));
+
+        // Packed types take their size from the type, not the component count.
+        let packed = FormatInfo::for_transfer(GL_RGB, GL_UNSIGNED_SHORT_5_6_5).unwrap();
+        assert_eq!((packed.bytes_per_pixel, packed.components), (2, 3));
+
+        let half = FormatInfo::for_transfer(GL_RGBA, GL_HALF_FLOAT_OES).unwrap();
+        assert_eq!(half.bytes_per_pixel, 8);
+
+        let float = FormatInfo::for_transfer(GL_RED, GL_FLOAT).unwrap();
+        assert_eq!(float.bytes_per_pixel, 4);
+
+        assert!(FormatInfo::for_transfer(GL_RGBA, GL_UNSIGNED_SHORT_5_6_5).is_none());
+
+        // Compressed entries have no client type and never describe a transfer.
+        assert!(FormatInfo::for_transfer(GL_RGB, 0).is_none());
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(
 non_camel_case_types, non_snake_case, non_upper_case_globals, dead_code,
-missing_copy_implementations, unused_imports
+missing_copy_implementations, unused_imports, clippy::too_many_arguments
 )]
 
 // -------------------------------------------------------------------------------------------------
@@ -36,4 +36,5 @@ pub mod es20;
 
 pub mod consts;
 pub mod enums;
+pub mod format;
 pub mod types;
\ No newline at end of file